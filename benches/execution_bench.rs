@@ -1,6 +1,6 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{Criterion, criterion_group, criterion_main};
 use matrix_runner::core::config::TestCase;
-use matrix_runner::core::execution::run_test_case;
+use matrix_runner::core::execution::{ExecutionContext, run_test_case};
 use std::path::PathBuf;
 use tempfile::TempDir;
 use tokio::runtime::Runtime;
@@ -17,9 +17,9 @@ fn bench_run_test_case(c: &mut Criterion) {
         arch: vec![],
         timeout_secs: Some(10),
         retries: None,
+        ..Default::default()
     };
-    let project_root = PathBuf::from(".");
-    let crate_name = "bench_crate".to_string();
+    let ctx = ExecutionContext::new(PathBuf::from("."), "bench_crate");
 
     c.bench_function("run_test_case", |b| {
         b.to_async(&rt).iter(|| async {
@@ -27,7 +27,7 @@ fn bench_run_test_case(c: &mut Criterion) {
             tokio::spawn(async move {
                 while rx.recv().await.is_some() {}
             });
-            let _ = run_test_case(case.clone(), &project_root, &crate_name, tx).await;
+            let _ = run_test_case(case.clone(), &ctx, tx).await;
         });
     });
 }
@@ -0,0 +1,64 @@
+//! Build script: collects the translation keys used with `t!` in the sources, so the
+//! hidden `i18n-audit` command can compare them with the locale files.
+//!
+//! 构建脚本：收集源代码中通过 `t!` 使用的翻译键，
+//! 以便隐藏的 `i18n-audit` 命令将其与本地化文件进行比较。
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut keys = BTreeSet::new();
+    collect_keys(Path::new("src"), &mut keys);
+
+    let mut generated = String::from("&[\n");
+    for key in &keys {
+        generated.push_str(&format!("    {key:?},\n"));
+    }
+    generated.push(']');
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("i18n_keys.rs"), generated)
+        .expect("failed to write the translation key list");
+}
+
+/// Walks a directory and collects the literal keys of every `t!("...")` call.
+fn collect_keys(dir: &Path, keys: &mut BTreeSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_keys(&path, keys);
+        } else if path.extension().is_some_and(|ext| ext == "rs")
+            && let Ok(content) = fs::read_to_string(&path)
+        {
+            keys.extend(keys_in(&content));
+        }
+    }
+}
+
+fn keys_in(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for (index, _) in content.match_indices("t!(") {
+        // Skip macros merely ending in `t!`, such as `assert!` or `format!`.
+        let preceded_by_ident = content[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if preceded_by_ident {
+            continue;
+        }
+        let rest = content[index + 3..].trim_start();
+        if let Some(literal) = rest.strip_prefix('"')
+            && let Some(end) = literal.find('"')
+        {
+            keys.push(literal[..end].to_string());
+        }
+    }
+    keys
+}
@@ -10,7 +10,39 @@ pub mod commands;
 
 use crate::infra::t;
 use clap::{Arg, ArgMatches, Command};
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// The `--manifest-path` argument, an alternative to `--project-dir` for projects whose
+/// `Cargo.toml` is not at the root of the repository.
+fn manifest_path_arg() -> Arg {
+    Arg::new("manifest_path")
+        .long("manifest-path")
+        .help(t!("cli.run.manifest_path").to_string())
+        .value_name("PATH")
+        .conflicts_with("project_dir")
+        .value_parser(clap::value_parser!(PathBuf))
+}
+
+/// The `--lenient` flag, which ignores unknown keys of the matrix file instead of
+/// rejecting them.
+fn lenient_arg() -> Arg {
+    Arg::new("lenient")
+        .long("lenient")
+        .help(t!("cli.run.lenient").to_string())
+        .action(clap::ArgAction::SetTrue)
+}
+
+/// The project directory given by `--project-dir`, or the directory containing the
+/// `Cargo.toml` given by `--manifest-path`.
+fn project_dir_arg(matches: &ArgMatches) -> Option<PathBuf> {
+    if let Some(project_dir) = matches.get_one::<PathBuf>("project_dir") {
+        return Some(project_dir.clone());
+    }
+    let manifest_path = matches.get_one::<PathBuf>("manifest_path")?;
+    let dir = manifest_path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    Some(dir.map_or_else(|| PathBuf::from("."), Path::to_path_buf))
+}
 
 /// Builds the CLI structure using clap's builder pattern.
 ///
@@ -22,6 +54,8 @@ pub fn build_cli() -> Command {
         .version(env!("CARGO_PKG_VERSION"))
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .allow_external_subcommands(true)
+        .external_subcommand_value_parser(clap::value_parser!(OsString))
         .arg(
             Arg::new("lang")
                 .long("lang")
@@ -44,7 +78,6 @@ pub fn build_cli() -> Command {
                         .short('c')
                         .long("config")
                         .help(t!("cli.run.config").to_string())
-                        .default_value("TestMatrix.toml")
                         .value_parser(clap::value_parser!(PathBuf)),
                 )
                 .arg(
@@ -52,9 +85,10 @@ pub fn build_cli() -> Command {
                         .short('p')
                         .long("project-dir")
                         .help(t!("cli.run.project_dir").to_string())
-                        .default_value(".")
                         .value_parser(clap::value_parser!(PathBuf)),
                 )
+                .arg(manifest_path_arg())
+                .arg(lenient_arg())
                 .arg(
                     Arg::new("total_runners")
                         .long("total-runners")
@@ -73,11 +107,190 @@ pub fn build_cli() -> Command {
                         .help(t!("cli.run.html").to_string())
                         .value_parser(clap::value_parser!(PathBuf)),
                 )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help(t!("cli.run.json").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("junit")
+                        .long("junit")
+                        .help(t!("cli.run.junit").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("html_max_output_kb")
+                        .long("html-max-output-kb")
+                        .help(t!("cli.run.html_max_output_kb").to_string())
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("html_summary_only")
+                        .long("html-summary-only")
+                        .help(t!("cli.run.html_summary_only").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("html_failures_only")
+                        .long("html-failures-only")
+                        .help(t!("cli.run.html_failures_only").to_string())
+                        .conflicts_with("html_summary_only")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("html_lock")
+                        .long("html-lock")
+                        .help(t!("cli.run.html_lock").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
                 .arg(
                     Arg::new("fast_fail")
                         .long("fast-fail")
                         .help(t!("cli.run.fast_fail").to_string())
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("select")
+                        .long("select")
+                        .help(t!("cli.run.select").to_string())
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("explain")
+                        .long("explain")
+                        .help(t!("cli.run.explain").to_string())
+                        .value_name("CASE")
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("repro_bundle_dir")
+                        .long("repro-bundle-dir")
+                        .help(t!("cli.run.repro_bundle_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("from_plan")
+                        .long("from-plan")
+                        .help(t!("cli.run.from_plan").to_string())
+                        .conflicts_with("config")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("auto_install_toolchains")
+                        .long("auto-install-toolchains")
+                        .help(t!("cli.run.auto_install_toolchains").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("offline_only")
+                        .long("offline-only")
+                        .help(t!("cli.run.offline_only").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("record_checksums")
+                        .long("record-checksums")
+                        .help(t!("cli.run.record_checksums").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("verify_reproducible")
+                        .long("verify-reproducible")
+                        .help(t!("cli.run.verify_reproducible").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("resume")
+                        .long("resume")
+                        .value_name("STATE_FILE")
+                        .help(t!("cli.run.resume").to_string())
+                        .num_args(0..=1)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("smoke_first")
+                        .long("smoke-first")
+                        .help(t!("cli.run.smoke_first").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("smoke_keep_going")
+                        .long("smoke-keep-going")
+                        .help(t!("cli.run.smoke_keep_going").to_string())
+                        .requires("smoke_first")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("keep_build_logs")
+                        .long("keep-build-logs")
+                        .help(t!("cli.run.keep_build_logs").to_string())
+                        .value_name("all|failed|none")
+                        .default_value("none")
+                        .value_parser(|value: &str| value.parse::<crate::core::execution::KeepBuildLogs>()),
+                )
+                .arg(
+                    Arg::new("isolated_only")
+                        .long("isolated-only")
+                        .help(t!("cli.run.isolated_only").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("keep_going")
+                        .long("keep-going")
+                        .help(t!("cli.run.keep_going").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("prefer_nextest")
+                        .long("prefer-nextest")
+                        .help(t!("cli.run.prefer_nextest").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("prebuild_deps")
+                        .long("prebuild-deps")
+                        .help(t!("cli.run.prebuild_deps").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("package")
+                        .long("package")
+                        .value_name("NAME")
+                        .help(t!("cli.run.package").to_string()),
+                )
+                .arg(
+                    Arg::new("max_load")
+                        .long("max-load")
+                        .value_name("LOAD")
+                        .help(t!("cli.run.max_load").to_string())
+                        .value_parser(crate::core::scheduler::parse_ceiling),
+                )
+                .arg(
+                    Arg::new("max_total_memory_gb")
+                        .long("max-total-memory-gb")
+                        .value_name("GB")
+                        .help(t!("cli.run.max_total_memory_gb").to_string())
+                        .value_parser(crate::core::scheduler::parse_ceiling),
+                )
+                .arg(
+                    Arg::new("sort_by")
+                        .long("sort-by")
+                        .help(t!("cli.run.sort_by").to_string())
+                        .value_name("size|build-time")
+                        .value_parser(|value: &str| value.parse::<crate::core::costs::SummarySort>()),
+                )
+                .arg(
+                    Arg::new("validate_output")
+                        .long("validate-output")
+                        .help(t!("cli.run.validate_output").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("stable_output")
+                        .long("stable-output")
+                        .help(t!("cli.run.stable_output").to_string())
+                        .action(clap::ArgAction::SetTrue),
                 ),
         )
         .subcommand(
@@ -97,8 +310,417 @@ pub fn build_cli() -> Command {
                         .long("force")
                         .help(t!("cli.init.force").to_string())
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("workspace")
+                        .long("workspace")
+                        .help(t!("cli.init.workspace").to_string())
+                        .value_parser(clap::value_parser!(commands::init::WorkspaceLayout)),
+                )
+                .arg(
+                    Arg::new("template")
+                        .long("template")
+                        .help(t!("cli.init.template").to_string())
+                        .value_parser(clap::value_parser!(commands::init::Template)),
+                )
+                .arg(
+                    Arg::new("target")
+                        .long("target")
+                        .help(t!("cli.init.target").to_string())
+                        .value_name("TRIPLE")
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("runner")
+                        .long("runner")
+                        .help(t!("cli.init.runner").to_string())
+                        .value_name("TRIPLE=COMMAND")
+                        .action(clap::ArgAction::Append)
+                        .value_parser(commands::init::parse_runner),
                 ),
         )
+        .subcommand(
+            Command::new("plan")
+                .about(t!("cli.plan.about").to_string())
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("export")
+                        .about(t!("cli.plan.export.about").to_string())
+                        .arg(
+                            Arg::new("output")
+                                .help(t!("cli.plan.export.output").to_string())
+                                .required(true)
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("config")
+                                .short('c')
+                                .long("config")
+                                .help(t!("cli.run.config").to_string())
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("project_dir")
+                                .short('p')
+                                .long("project-dir")
+                                .help(t!("cli.run.project_dir").to_string())
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(manifest_path_arg())
+                        .arg(lenient_arg())
+                        .arg(
+                            Arg::new("total_runners")
+                                .long("total-runners")
+                                .help(t!("cli.run.total_runners").to_string())
+                                .value_parser(clap::value_parser!(usize)),
+                        )
+                        .arg(
+                            Arg::new("runner_index")
+                                .long("runner-index")
+                                .help(t!("cli.run.runner_index").to_string())
+                                .value_parser(clap::value_parser!(usize)),
+                        )
+                        .arg(
+                            Arg::new("select")
+                                .long("select")
+                                .help(t!("cli.run.select").to_string())
+                                .value_parser(clap::value_parser!(String)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("batch")
+                .about(t!("cli.batch.about").to_string())
+                .arg(
+                    Arg::new("manifest")
+                        .short('m')
+                        .long("manifest")
+                        .help(t!("cli.batch.manifest").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("parallel")
+                        .long("parallel")
+                        .help(t!("cli.batch.parallel").to_string())
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("run_args")
+                        .help(t!("cli.batch.run_args").to_string())
+                        .num_args(0..)
+                        .last(true)
+                        .allow_hyphen_values(true)
+                        .value_parser(clap::value_parser!(String)),
+                ),
+        )
+        .subcommand(
+            Command::new("schedule")
+                .about(t!("cli.schedule.about").to_string())
+                .arg(
+                    Arg::new("cron")
+                        .long("cron")
+                        .help(t!("cli.schedule.cron").to_string())
+                        .required(true)
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("config")
+                        .short('c')
+                        .long("config")
+                        .help(t!("cli.schedule.config").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.schedule.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("drift_window")
+                        .long("drift-window")
+                        .help(t!("cli.schedule.drift_window").to_string())
+                        .default_value("5")
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("drift_threshold")
+                        .long("drift-threshold")
+                        .help(t!("cli.schedule.drift_threshold").to_string())
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("max_runs")
+                        .long("max-runs")
+                        .help(t!("cli.schedule.max_runs").to_string())
+                        .value_parser(clap::value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("run_args")
+                        .help(t!("cli.schedule.run_args").to_string())
+                        .num_args(0..)
+                        .last(true)
+                        .allow_hyphen_values(true)
+                        .value_parser(clap::value_parser!(String)),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about(t!("cli.serve.about").to_string())
+                .arg(
+                    Arg::new("port")
+                        .long("port")
+                        .help(t!("cli.serve.port").to_string())
+                        .default_value("8080")
+                        .value_parser(clap::value_parser!(u16)),
+                )
+                .arg(
+                    Arg::new("run_args")
+                        .help(t!("cli.serve.run_args").to_string())
+                        .num_args(0..)
+                        .last(true)
+                        .allow_hyphen_values(true)
+                        .value_parser(clap::value_parser!(String)),
+                ),
+        )
+        .subcommand(
+            Command::new("compare-features")
+                .about(t!("cli.compare_features.about").to_string())
+                .arg(
+                    Arg::new("features_a")
+                        .long("features-a")
+                        .help(t!("cli.compare_features.features_a").to_string())
+                        .required(true)
+                        .allow_hyphen_values(true)
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("features_b")
+                        .long("features-b")
+                        .help(t!("cli.compare_features.features_b").to_string())
+                        .required(true)
+                        .allow_hyphen_values(true)
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("no_default_features")
+                        .long("no-default-features")
+                        .help(t!("cli.compare_features.no_default_features").to_string())
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("package")
+                        .long("package")
+                        .help(t!("cli.compare_features.package").to_string())
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.compare_features.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("status")
+                .about(t!("cli.status.about").to_string())
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.status.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("tail")
+                .about(t!("cli.tail.about").to_string())
+                .arg(
+                    Arg::new("case")
+                        .help(t!("cli.tail.case").to_string())
+                        .required(true)
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.tail.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("merge-results")
+                .about(t!("cli.merge_results.about").to_string())
+                .arg(
+                    Arg::new("inputs")
+                        .help(t!("cli.merge_results.inputs").to_string())
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help(t!("cli.merge_results.output").to_string())
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("schema")
+                .about(t!("cli.schema.about").to_string())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help(t!("cli.schema.output").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("history")
+                .about(t!("cli.history.about").to_string())
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("prune")
+                        .about(t!("cli.history.prune.about").to_string())
+                        .arg(
+                            Arg::new("project_dir")
+                                .short('p')
+                                .long("project-dir")
+                                .help(t!("cli.history.prune.project_dir").to_string())
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("dry_run")
+                                .long("dry-run")
+                                .help(t!("cli.history.prune.dry_run").to_string())
+                                .action(clap::ArgAction::SetTrue),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about(t!("cli.validate.about").to_string())
+                .arg(
+                    Arg::new("config")
+                        .short('c')
+                        .long("config")
+                        .help(t!("cli.run.config").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.run.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(lenient_arg()),
+        )
+        .subcommand(
+            Command::new("advise")
+                .about(t!("cli.advise.about").to_string())
+                .arg(
+                    Arg::new("config")
+                        .short('c')
+                        .long("config")
+                        .help(t!("cli.run.config").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("project_dir")
+                        .short('p')
+                        .long("project-dir")
+                        .help(t!("cli.run.project_dir").to_string())
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("package")
+                        .long("package")
+                        .value_name("NAME")
+                        .help(t!("cli.advise.package").to_string())
+                        .value_parser(clap::value_parser!(String)),
+                )
+                .arg(lenient_arg()),
+        )
+        .subcommand(
+            Command::new("i18n-audit")
+                .about(t!("cli.i18n_audit.about").to_string())
+                .hide(true)
+                .arg(
+                    Arg::new("locales")
+                        .long("locales")
+                        .help(t!("cli.i18n_audit.locales").to_string())
+                        .default_value(concat!(env!("CARGO_MANIFEST_DIR"), "/locales"))
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("stub")
+                        .long("stub")
+                        .help(t!("cli.i18n_audit.stub").to_string())
+                        .value_parser(clap::value_parser!(String)),
+                ),
+        )
+}
+
+/// Collects the options of the `run` command from its parsed arguments.
+fn run_options(sub_matches: &ArgMatches, lang: Option<String>) -> commands::run::RunOptions {
+    commands::run::RunOptions {
+        jobs: sub_matches.get_one::<usize>("jobs").copied(),
+        config: sub_matches.get_one::<PathBuf>("config").cloned(),
+        project_dir: project_dir_arg(sub_matches),
+        total_runners: sub_matches.get_one::<usize>("total_runners").copied(),
+        runner_index: sub_matches.get_one::<usize>("runner_index").copied(),
+        html: sub_matches.get_one::<PathBuf>("html").cloned(),
+        json: sub_matches.get_one::<PathBuf>("json").cloned(),
+        junit: sub_matches.get_one::<PathBuf>("junit").cloned(),
+        html_options: crate::reporting::html::HtmlOptions {
+            max_output_kb: sub_matches.get_one::<usize>("html_max_output_kb").copied(),
+            summary_only: sub_matches.get_flag("html_summary_only"),
+            failures_only: sub_matches.get_flag("html_failures_only"),
+            lock: sub_matches.get_flag("html_lock"),
+            columns: Vec::new(),
+        },
+        lang,
+        fast_fail: sub_matches.get_flag("fast_fail"),
+        select: sub_matches.get_one::<String>("select").cloned(),
+        explain: sub_matches.get_one::<String>("explain").cloned(),
+        repro_bundle_dir: sub_matches.get_one::<PathBuf>("repro_bundle_dir").cloned(),
+        from_plan: sub_matches.get_one::<PathBuf>("from_plan").cloned(),
+        auto_install_toolchains: sub_matches.get_flag("auto_install_toolchains"),
+        offline_only: sub_matches.get_flag("offline_only"),
+        record_checksums: sub_matches.get_flag("record_checksums"),
+        verify_reproducible: sub_matches.get_flag("verify_reproducible"),
+        resume: sub_matches.contains_id("resume"),
+        resume_file: sub_matches.get_one::<PathBuf>("resume").cloned(),
+        smoke_first: sub_matches.get_flag("smoke_first"),
+        smoke_keep_going: sub_matches.get_flag("smoke_keep_going"),
+        keep_build_logs: sub_matches
+            .get_one::<crate::core::execution::KeepBuildLogs>("keep_build_logs")
+            .copied()
+            .unwrap_or_default(),
+        isolated_only: sub_matches.get_flag("isolated_only"),
+        keep_going: sub_matches.get_flag("keep_going"),
+        prefer_nextest: sub_matches.get_flag("prefer_nextest"),
+        lenient: sub_matches.get_flag("lenient"),
+        sort_by: sub_matches
+            .get_one::<crate::core::costs::SummarySort>("sort_by")
+            .copied(),
+        stable_output: sub_matches.get_flag("stable_output"),
+        validate_output: sub_matches.get_flag("validate_output"),
+        prebuild_deps: sub_matches.get_flag("prebuild_deps"),
+        package: sub_matches.get_one::<String>("package").cloned(),
+        max_load: sub_matches.get_one::<f64>("max_load").copied(),
+        max_total_memory_gb: sub_matches.get_one::<f64>("max_total_memory_gb").copied(),
+        event_sink: None,
+    }
 }
 
 /// Process the parsed CLI command and dispatch to the appropriate handler.
@@ -110,41 +732,197 @@ pub async fn process_command(matches: ArgMatches) -> anyhow::Result<()> {
 
     match matches.subcommand() {
         Some(("run", sub_matches)) => {
-            let jobs = sub_matches.get_one::<usize>("jobs").copied();
-            let config = sub_matches
-                .get_one::<PathBuf>("config")
-                .expect("default value should be present")
-                .clone();
-            let project_dir = sub_matches
-                .get_one::<PathBuf>("project_dir")
+            commands::run::execute(run_options(sub_matches, lang)).await
+        }
+        Some(("init", sub_matches)) => {
+            let output = sub_matches
+                .get_one::<PathBuf>("output")
                 .expect("default value should be present")
                 .clone();
-            let total_runners = sub_matches.get_one::<usize>("total_runners").copied();
-            let runner_index = sub_matches.get_one::<usize>("runner_index").copied();
-            let html = sub_matches.get_one::<PathBuf>("html").cloned();
-            let fast_fail = sub_matches.get_flag("fast_fail");
-
-            commands::run::execute(
-                jobs,
-                config,
-                project_dir,
-                total_runners,
-                runner_index,
-                html,
+            let force = sub_matches.get_flag("force");
+            let layout = sub_matches
+                .get_one::<commands::init::WorkspaceLayout>("workspace")
+                .copied();
+            let template = sub_matches
+                .get_one::<commands::init::Template>("template")
+                .copied();
+            let targets: Vec<String> = sub_matches
+                .get_many::<String>("target")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+            let runners: Vec<(String, String)> = sub_matches
+                .get_many::<(String, String)>("runner")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default();
+            let embedded = commands::init::embedded_targets(&targets, &runners);
+
+            commands::init::execute(output, force, lang, layout, template, embedded).await
+        }
+        Some(("plan", sub_matches)) => match sub_matches.subcommand() {
+            Some(("export", export_matches)) => {
+                let options = commands::plan::ExportOptions {
+                    output: export_matches
+                        .get_one::<PathBuf>("output")
+                        .expect("output is required")
+                        .clone(),
+                    config: export_matches.get_one::<PathBuf>("config").cloned(),
+                    project_dir: project_dir_arg(export_matches),
+                    total_runners: export_matches.get_one::<usize>("total_runners").copied(),
+                    runner_index: export_matches.get_one::<usize>("runner_index").copied(),
+                    lang,
+                    select: export_matches.get_one::<String>("select").cloned(),
+                    lenient: export_matches.get_flag("lenient"),
+                };
+
+                commands::plan::export(options).await
+            }
+            _ => unreachable!("clap should have handled this because subcommand_required is set"),
+        },
+        Some(("batch", sub_matches)) => {
+            let options = commands::batch::BatchOptions {
+                manifest: sub_matches.get_one::<PathBuf>("manifest").cloned(),
+                parallel: sub_matches
+                    .get_one::<usize>("parallel")
+                    .copied()
+                    .expect("default value should be present"),
                 lang,
-                fast_fail,
+                run_args: sub_matches
+                    .get_many::<String>("run_args")
+                    .map(|args| args.cloned().collect())
+                    .unwrap_or_default(),
+            };
+
+            commands::batch::execute(options).await
+        }
+        Some(("schedule", sub_matches)) => {
+            let options = commands::schedule::ScheduleOptions {
+                cron: sub_matches
+                    .get_one::<String>("cron")
+                    .cloned()
+                    .expect("cron is required"),
+                config: sub_matches.get_one::<PathBuf>("config").cloned(),
+                project_dir: sub_matches.get_one::<PathBuf>("project_dir").cloned(),
+                drift_window: sub_matches
+                    .get_one::<usize>("drift_window")
+                    .copied()
+                    .expect("default value should be present"),
+                drift_threshold: sub_matches
+                    .get_one::<f64>("drift_threshold")
+                    .copied()
+                    .expect("default value should be present"),
+                max_runs: sub_matches.get_one::<usize>("max_runs").copied(),
+                lang,
+                run_args: sub_matches
+                    .get_many::<String>("run_args")
+                    .map(|args| args.cloned().collect())
+                    .unwrap_or_default(),
+            };
+
+            commands::schedule::execute(options).await
+        }
+        Some(("serve", sub_matches)) => {
+            let port = sub_matches
+                .get_one::<u16>("port")
+                .copied()
+                .expect("default value should be present");
+            // The arguments after `--` are those of `run`, parsed by its own definition.
+            let run_args = sub_matches
+                .get_many::<String>("run_args")
+                .map(|args| args.cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let run_matches = build_cli()
+                .try_get_matches_from(["matrix-runner".to_string(), "run".to_string()].into_iter().chain(run_args))
+                .unwrap_or_else(|e| e.exit());
+            let Some(("run", run_matches)) = run_matches.subcommand() else {
+                unreachable!("the arguments start with the `run` subcommand");
+            };
+            commands::serve::execute(port, run_options(run_matches, lang)).await
+        }
+        Some(("compare-features", sub_matches)) => {
+            let options = commands::compare_features::CompareFeaturesOptions {
+                features_a: sub_matches
+                    .get_one::<String>("features_a")
+                    .cloned()
+                    .expect("features-a is required"),
+                features_b: sub_matches
+                    .get_one::<String>("features_b")
+                    .cloned()
+                    .expect("features-b is required"),
+                no_default_features: sub_matches.get_flag("no_default_features"),
+                package: sub_matches.get_one::<String>("package").cloned(),
+                project_dir: sub_matches.get_one::<PathBuf>("project_dir").cloned(),
+                lang,
+            };
+
+            commands::compare_features::execute(options).await
+        }
+        Some(("status", sub_matches)) => {
+            commands::status::execute(sub_matches.get_one::<PathBuf>("project_dir").cloned())
+        }
+        Some(("tail", sub_matches)) => {
+            commands::tail::execute(
+                sub_matches
+                    .get_one::<String>("case")
+                    .cloned()
+                    .expect("case is required"),
+                sub_matches.get_one::<PathBuf>("project_dir").cloned(),
             )
             .await
         }
-        Some(("init", sub_matches)) => {
-            let output = sub_matches
+        Some(("merge-results", sub_matches)) => commands::merge_results::execute(
+            sub_matches
+                .get_many::<PathBuf>("inputs")
+                .expect("inputs are required")
+                .cloned()
+                .collect(),
+            sub_matches
                 .get_one::<PathBuf>("output")
+                .expect("output is required")
+                .clone(),
+        ),
+        Some(("schema", sub_matches)) => {
+            commands::schema::execute(sub_matches.get_one::<PathBuf>("output").cloned())
+        }
+        Some(("history", sub_matches)) => match sub_matches.subcommand() {
+            Some(("prune", prune_matches)) => commands::history::prune(
+                prune_matches.get_one::<PathBuf>("project_dir").cloned(),
+                prune_matches.get_flag("dry_run"),
+            ),
+            _ => unreachable!("clap should have handled this because subcommand_required is set"),
+        },
+        Some(("validate", sub_matches)) => commands::validate::execute(
+            sub_matches.get_one::<PathBuf>("config").cloned(),
+            sub_matches.get_one::<PathBuf>("project_dir").cloned(),
+            sub_matches.get_flag("lenient"),
+            lang,
+        ),
+        Some(("advise", sub_matches)) => {
+            let options = commands::advise::AdviseOptions {
+                config: sub_matches.get_one::<PathBuf>("config").cloned(),
+                project_dir: sub_matches.get_one::<PathBuf>("project_dir").cloned(),
+                package: sub_matches.get_one::<String>("package").cloned(),
+                lenient: sub_matches.get_flag("lenient"),
+                lang,
+            };
+            commands::advise::execute(options).await
+        }
+        Some(("i18n-audit", sub_matches)) => {
+            let locales = sub_matches
+                .get_one::<PathBuf>("locales")
                 .expect("default value should be present")
                 .clone();
-            let force = sub_matches.get_flag("force");
+            let stub = sub_matches.get_one::<String>("stub").cloned();
+
+            commands::i18n_audit::execute(locales, stub)
+        }
+        Some((plugin, sub_matches)) => {
+            let args = sub_matches
+                .get_many::<OsString>("")
+                .map(|args| args.cloned().collect())
+                .unwrap_or_default();
 
-            commands::init::execute(output, force, lang).await
+            commands::plugin::execute(plugin, args)
         }
-        _ => unreachable!("clap should have handled this because subcommand_required is set"),
+        None => unreachable!("clap should have handled this because subcommand_required is set"),
     }
 } 
\ No newline at end of file
@@ -5,4 +5,18 @@
 //! 此模块包含 CLI 命令的实现。
 
 pub mod run;
-pub mod init; 
\ No newline at end of file
+pub mod init;
+pub mod plan;
+pub mod batch;
+pub mod i18n_audit;
+pub mod status;
+pub mod schedule;
+pub mod tail;
+pub mod validate;
+pub mod plugin;
+pub mod merge_results;
+pub mod schema;
+pub mod history;
+pub mod serve;
+pub mod compare_features;
+pub mod advise;
@@ -0,0 +1,181 @@
+//! # Advise Command Module / 建议命令模块
+//!
+//! This module implements the `advise` command, which compares the test matrix with the
+//! features, `rust-version` and docs.rs metadata of the package and with the targets of
+//! the CI configuration, and suggests the cases the matrix is missing, each with a
+//! `[[cases]]` entry ready to be added. Nothing is built or run.
+//!
+//! 此模块实现了 `advise` 命令，它将测试矩阵与包的 features、`rust-version` 和 docs.rs 元数据
+//! 以及 CI 配置中的目标进行比较，并建议矩阵中缺失的用例，每条建议都附带一个可以直接添加的
+//! `[[cases]]` 条目。不会构建或运行任何内容。
+
+use anyhow::Result;
+use colored::*;
+use std::path::PathBuf;
+
+use super::run::{prepare_environment, resolve_config_location, setup_and_parse_config};
+use crate::{
+    core::{
+        advise::{Advice, ProjectFacts, advise},
+        config::{TestCase, edit::MatrixDocument},
+        context::RunContext,
+        error::MatrixError,
+        settings,
+    },
+    infra::{ci_config, t, workspace},
+};
+
+/// Options for the `advise` command, as collected from the command line.
+/// `advise` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct AdviseOptions {
+    /// Path to the test matrix configuration file; discovered from the current directory if `None` / 测试矩阵配置文件的路径；为 `None` 时从当前目录发现
+    pub config: Option<PathBuf>,
+    /// Path to the project directory; defaults to the discovered config's directory / 项目目录的路径；默认为发现的配置文件所在目录
+    pub project_dir: Option<PathBuf>,
+    /// The workspace package to advise on; defaults to the package at the project root / 要提供建议的工作区包；默认为项目根目录的包
+    pub package: Option<String>,
+    /// Ignore unknown keys of the matrix file, with a warning / 忽略矩阵文件中的未知键并发出警告
+    pub lenient: bool,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+}
+
+/// Executes the `advise` command.
+///
+/// # Arguments
+/// * `options` - The options collected from the command line
+///
+/// # Returns
+/// An error if the matrix or the package manifest cannot be read; suggestions are not errors
+pub async fn execute(options: AdviseOptions) -> Result<()> {
+    let (config, project_dir) = resolve_config_location(options.config, options.project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+
+    let (test_matrix, config_path) =
+        setup_and_parse_config(&config, options.lenient).map_err(MatrixError::Config)?;
+    if let Some(language) = settings.language(options.lang) {
+        rust_i18n::set_locale(&language);
+    } else if test_matrix.language != "en" {
+        rust_i18n::set_locale(&test_matrix.language);
+    }
+    let locale = rust_i18n::locale().to_string();
+
+    let (project_root, crate_name) = prepare_environment(&project_dir, false, &locale)
+        .await
+        .map_err(MatrixError::Environment)?;
+    let package = options.package.unwrap_or_else(|| crate_name.clone());
+    if package.is_empty() {
+        return Err(MatrixError::Config(anyhow::anyhow!(t!(
+            "advise.virtual_workspace",
+            locale = &locale
+        )))
+        .into());
+    }
+    let manifests = workspace::package_manifests(&project_root)
+        .await
+        .map_err(MatrixError::Environment)?;
+    let Some(manifest) = manifests.iter().find(|manifest| manifest.name == package) else {
+        let members: Vec<&str> = manifests
+            .iter()
+            .map(|manifest| manifest.name.as_str())
+            .collect();
+        return Err(MatrixError::Config(anyhow::anyhow!(t!(
+            "run.unknown_package",
+            locale = &locale,
+            package = &package,
+            members = members.join(", ")
+        )))
+        .into());
+    };
+
+    let facts = ProjectFacts {
+        features: manifest.features.clone(),
+        rust_version: manifest.rust_version.clone(),
+        docs_rs: manifest.docs_rs.clone(),
+        ci_targets: ci_config::ci_targets(&project_root),
+    };
+    let cases: Vec<TestCase> = test_matrix
+        .cases
+        .into_iter()
+        .filter(|case| case.package.as_deref().unwrap_or(&crate_name) == package)
+        .collect();
+    println!(
+        "{}",
+        t!(
+            "advise.checking",
+            locale = &locale,
+            count = cases.len(),
+            package = &package
+        )
+        .cyan()
+    );
+
+    let suggestions = advise(&cases, &facts, &RunContext::new(locale.clone()));
+    if suggestions.is_empty() {
+        println!(
+            "{}",
+            t!("advise.none", locale = &locale, package = &package).green()
+        );
+        return Ok(());
+    }
+    for suggestion in &suggestions {
+        println!("\n{}", advice_message(&suggestion.advice, &locale).yellow());
+        let mut snippet = MatrixDocument::parse("")?;
+        snippet.add_case(&suggestion.case)?;
+        for line in snippet.to_string().lines() {
+            println!("    {}", line.dimmed());
+        }
+    }
+    println!(
+        "\n{}",
+        t!(
+            "advise.summary",
+            locale = &locale,
+            count = suggestions.len(),
+            path = config_path.display()
+        )
+        .bold()
+    );
+    Ok(())
+}
+
+/// Describes a gap in the coverage of the matrix / 描述矩阵覆盖中的一个缺口
+fn advice_message(advice: &Advice, locale: &str) -> String {
+    match advice {
+        Advice::FeatureNeverEnabled { feature } => t!(
+            "advise.feature_never_enabled",
+            locale = locale,
+            feature = feature
+        ),
+        Advice::FeatureNeverWithoutDefaults { feature } => {
+            t!(
+                "advise.feature_never_without_defaults",
+                locale = locale,
+                feature = feature
+            )
+        }
+        Advice::NoDefaultFeaturesUntested => {
+            t!("advise.no_default_features_untested", locale = locale)
+        }
+        Advice::AllFeaturesUntested => t!("advise.all_features_untested", locale = locale),
+        Advice::MsrvUntested { version } => {
+            t!("advise.msrv_untested", locale = locale, version = version)
+        }
+        Advice::DocsRsUncovered => t!("advise.docs_rs_uncovered", locale = locale),
+        Advice::DocsRsTargetUncovered { target } => {
+            t!(
+                "advise.docs_rs_target_uncovered",
+                locale = locale,
+                target = target
+            )
+        }
+        Advice::CiTargetUncovered { target } => t!(
+            "advise.ci_target_uncovered",
+            locale = locale,
+            target = target
+        ),
+    }
+    .to_string()
+}
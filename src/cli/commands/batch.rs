@@ -0,0 +1,222 @@
+//! # Batch Command Module / 批处理命令模块
+//!
+//! This module implements the `batch` command, which runs the matrices of many projects
+//! listed in a manifest (or on stdin), one `run` process per project, and prints an
+//! aggregated summary across all of them.
+//!
+//! 此模块实现了 `batch` 命令，它运行清单（或标准输入）中列出的多个项目的矩阵，
+//! 每个项目一个 `run` 进程，并打印所有项目的汇总摘要。
+
+use anyhow::{Context, Result};
+use colored::*;
+use futures::{StreamExt, stream};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    core::{
+        batch::{BatchEntry, ProjectOutcome, ProjectResult, parse_manifest},
+        error::MatrixError,
+    },
+    infra::t,
+    reporting::console::print_batch_summary,
+};
+
+/// Options for the `batch` command, as collected from the command line.
+/// `batch` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// The manifest to read; stdin is read if `None` or `-` / 要读取的清单；为 `None` 或 `-` 时读取标准输入
+    pub manifest: Option<PathBuf>,
+    /// The number of projects run at the same time / 同时运行的项目数量
+    pub parallel: usize,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+    /// Extra arguments passed to every `run` / 传递给每个 `run` 的额外参数
+    pub run_args: Vec<String>,
+}
+
+/// Executes the `batch` command.
+///
+/// Projects run one after another with their output shown as it happens. With
+/// `parallel` above 1, several projects run at once and each one's output is printed
+/// when it finishes, so outputs do not interleave. Ctrl+C stops starting new projects.
+///
+/// # Arguments
+/// * `options` - The options collected from the command line
+///
+/// # Returns
+/// An error if the manifest is invalid or any project did not pass
+pub async fn execute(options: BatchOptions) -> Result<()> {
+    let BatchOptions {
+        manifest,
+        parallel,
+        lang,
+        run_args,
+    } = options;
+    let locale = rust_i18n::locale().to_string();
+
+    let entries = read_entries(manifest.as_deref()).map_err(MatrixError::Config)?;
+    if entries.is_empty() {
+        println!("{}", t!("batch.no_projects", locale = &locale).yellow());
+        return Ok(());
+    }
+    let exe = std::env::current_exe()
+        .context("Failed to locate the matrix-runner executable")
+        .map_err(MatrixError::Environment)?;
+
+    let stop_token = CancellationToken::new();
+    let signal_token = stop_token.clone();
+    tokio::spawn(async move {
+        if signal::ctrl_c().await.is_ok() {
+            signal_token.cancel();
+        }
+    });
+
+    let total = entries.len();
+    let parallel = parallel.max(1);
+    let results: Vec<ProjectResult> =
+        stream::iter(entries.into_iter().enumerate().map(|(index, entry)| {
+            let exe = exe.clone();
+            let lang = lang.clone();
+            let run_args = run_args.clone();
+            let locale = locale.clone();
+            let stop_token = stop_token.clone();
+            async move {
+                if stop_token.is_cancelled() {
+                    return ProjectResult {
+                        entry,
+                        outcome: ProjectOutcome::Skipped,
+                        duration: Default::default(),
+                    };
+                }
+                println!(
+                    "\n{}",
+                    t!(
+                        "batch.project_started",
+                        locale = &locale,
+                        index = index + 1,
+                        total = total,
+                        path = entry.config.display()
+                    )
+                    .bold()
+                );
+                let start = Instant::now();
+                let outcome = run_project(
+                    &exe,
+                    &entry,
+                    lang.as_deref(),
+                    &run_args,
+                    parallel > 1,
+                    &locale,
+                )
+                .await;
+                ProjectResult {
+                    entry,
+                    outcome,
+                    duration: start.elapsed(),
+                }
+            }
+        }))
+        .buffered(parallel)
+        .collect()
+        .await;
+
+    print_batch_summary(&results, &locale);
+
+    if stop_token.is_cancelled() {
+        return Err(MatrixError::Cancelled.into());
+    }
+    let failed = results
+        .iter()
+        .filter(|result| result.outcome != ProjectOutcome::Passed)
+        .count();
+    if failed > 0 {
+        return Err(MatrixError::UnexpectedFailures { count: failed }.into());
+    }
+    Ok(())
+}
+
+/// Reads the manifest from a file, or from stdin for `None` and `-`. Relative paths in a
+/// file are resolved against the file's directory, those on stdin against the current one.
+fn read_entries(manifest: Option<&Path>) -> Result<Vec<BatchEntry>> {
+    match manifest {
+        Some(path) if path != Path::new("-") => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read batch manifest: {}", path.display()))?;
+            let base_dir = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            parse_manifest(&content, base_dir)
+                .with_context(|| format!("Invalid batch manifest: {}", path.display()))
+        }
+        _ => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .context("Failed to read the batch manifest from stdin")?;
+            parse_manifest(&content, Path::new(".")).context("Invalid batch manifest on stdin")
+        }
+    }
+}
+
+/// Runs `matrix-runner run` for one project. When `capture` is set, the output is
+/// collected and printed in one piece once the run finishes.
+async fn run_project(
+    exe: &Path,
+    entry: &BatchEntry,
+    lang: Option<&str>,
+    run_args: &[String],
+    capture: bool,
+    locale: &str,
+) -> ProjectOutcome {
+    let mut cmd = tokio::process::Command::new(exe);
+    cmd.arg("run")
+        .arg("--config")
+        .arg(&entry.config)
+        .arg("--project-dir")
+        .arg(&entry.project_dir);
+    if let Some(lang) = lang {
+        cmd.args(["--lang", lang]);
+    }
+    cmd.args(run_args);
+
+    let status = if capture {
+        cmd.output().await.map(|output| {
+            println!(
+                "\n{}",
+                t!(
+                    "batch.project_output",
+                    locale = locale,
+                    path = entry.config.display()
+                )
+                .bold()
+            );
+            print!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            output.status
+        })
+    } else {
+        cmd.status().await
+    };
+    match status {
+        Ok(status) => ProjectOutcome::from_exit_code(status.code()),
+        Err(e) => {
+            eprintln!(
+                "{} {}",
+                t!(
+                    "batch.project_spawn_failed",
+                    locale = locale,
+                    path = entry.config.display()
+                )
+                .red(),
+                e
+            );
+            ProjectOutcome::Error
+        }
+    }
+}
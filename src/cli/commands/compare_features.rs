@@ -0,0 +1,128 @@
+//! # Compare Features Command Module / 比较 Feature 命令模块
+//!
+//! This module implements the `compare-features` command, which builds and runs the tests
+//! of a crate under two feature sets, one after the other, and reports the tests whose
+//! outcome depends on the set: those passing under one and failing under the other, and
+//! those only compiled in under one of them.
+//!
+//! 此模块实现了 `compare-features` 命令，它先后在两组 feature 下构建并运行一个 crate 的测试，
+//! 并报告结果取决于 feature 集合的测试：在一组下通过而在另一组下失败的测试，以及仅在其中一组下被编译进来的测试。
+
+use anyhow::Result;
+use colored::*;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+use super::{run::prepare_environment, status::discover_project_dir};
+use crate::{
+    core::{
+        config::TestCase,
+        context::RunContext,
+        error::MatrixError,
+        execution::{ExecutionContext, run_test_case},
+        feature_compare::FeatureComparison,
+        settings,
+    },
+    infra::{command, t},
+    reporting::console::print_feature_comparison,
+};
+
+/// Options for the `compare-features` command, as collected from the command line.
+/// `compare-features` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct CompareFeaturesOptions {
+    /// The first feature set, e.g. "foo" / 第一组 feature，例如 "foo"
+    pub features_a: String,
+    /// The second feature set, e.g. "foo,bar" / 第二组 feature，例如 "foo,bar"
+    pub features_b: String,
+    /// Build both sets without the default features / 两组都在不启用默认 feature 的情况下构建
+    pub no_default_features: bool,
+    /// The workspace package to test (passed as `-p`) / 要测试的工作区包（作为 `-p` 传递）
+    pub package: Option<String>,
+    /// The project to test; defaults to the directory of the nearest `TestMatrix.toml` / 要测试的项目；默认为最近的 `TestMatrix.toml` 所在目录
+    pub project_dir: Option<PathBuf>,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+}
+
+/// Executes the `compare-features` command.
+///
+/// # Arguments
+/// * `options` - The options collected from the command line
+///
+/// # Returns
+/// An error if the tests cannot be run with either feature set, or if some test passes
+/// with one set and fails with the other
+pub async fn execute(options: CompareFeaturesOptions) -> Result<()> {
+    let CompareFeaturesOptions {
+        features_a,
+        features_b,
+        no_default_features,
+        package,
+        project_dir,
+        lang,
+    } = options;
+    let project_dir = project_dir.unwrap_or_else(discover_project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+    if let Some(language) = settings.language(lang) {
+        rust_i18n::set_locale(&language);
+    }
+    let locale = rust_i18n::locale().to_string();
+
+    let (project_root, crate_name) = prepare_environment(&project_dir, false, &locale)
+        .await
+        .map_err(MatrixError::Environment)?;
+    let ctx = ExecutionContext {
+        state_dir: settings.state_dir(&project_root),
+        context: RunContext::new(locale.clone()),
+        ..ExecutionContext::new(project_root, crate_name)
+    };
+    // The build directories only need to live until both sets have run.
+    let (temp_dir_tx, _temp_dirs) = mpsc::unbounded_channel::<TempDir>();
+
+    let mut outcomes = Vec::new();
+    for (label, features) in [("a", &features_a), ("b", &features_b)] {
+        println!(
+            "\n{}",
+            t!(
+                "compare.running",
+                locale = &locale,
+                features = features,
+                label = label.to_uppercase()
+            )
+            .bold()
+        );
+        let case = TestCase {
+            name: format!("features-{label}"),
+            features: features.clone(),
+            no_default_features,
+            package: package.clone(),
+            ..Default::default()
+        };
+        let result = run_test_case(case, &ctx, temp_dir_tx.clone())
+            .await
+            .map_err(MatrixError::Environment)?;
+        let tests = command::parse_test_outcomes(&result.get_output());
+        if tests.is_empty() && result.is_failure() {
+            eprintln!(
+                "{}\n{}",
+                t!("compare.run_failed", locale = &locale, features = features).red(),
+                result.get_output().trim()
+            );
+            return Err(MatrixError::UnexpectedFailures { count: 1 }.into());
+        }
+        outcomes.push(tests);
+    }
+
+    let comparison = FeatureComparison::compare(&outcomes[0], &outcomes[1]);
+    print_feature_comparison(&comparison, &features_a, &features_b, &locale);
+    if comparison.has_differing_outcomes() {
+        return Err(MatrixError::UnexpectedFailures {
+            count: comparison.fail_only_in_a.len() + comparison.fail_only_in_b.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! # History Command Module / 历史命令模块
+//!
+//! This module implements the `history prune` command, which deletes the archived run
+//! results that the retention settings no longer keep. Runs prune the history
+//! themselves when they start; the command does it on demand, or shows with `--dry-run`
+//! what would go.
+//!
+//! 此模块实现了 `history prune` 命令，它删除保留设置不再保留的已归档运行结果。
+//! 运行在开始时会自行清理历史；此命令按需执行清理，或通过 `--dry-run` 显示将被删除的内容。
+
+use anyhow::Result;
+use chrono::Utc;
+use colored::*;
+use std::path::PathBuf;
+
+use super::status::discover_project_dir;
+use crate::{
+    core::{error::MatrixError, history, settings},
+    infra::t,
+};
+
+/// Executes the `history prune` command.
+///
+/// # Arguments
+/// * `project_dir` - The project whose history to prune; defaults to the directory of the nearest `TestMatrix.toml`, or the current directory
+/// * `dry_run` - Only list the results that would be deleted
+///
+/// # Returns
+/// An error if the settings cannot be read or a result cannot be deleted
+pub fn prune(project_dir: Option<PathBuf>, dry_run: bool) -> Result<()> {
+    let project_dir = project_dir.unwrap_or_else(discover_project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+    let project_root = project_dir.canonicalize().unwrap_or(project_dir);
+    let state_dir = settings.state_dir(&project_root);
+    let reports_dir = state_dir.join(history::REPORTS_DIR);
+
+    let pruned = history::prune_reports(
+        &state_dir,
+        settings.history_retention(),
+        Utc::now(),
+        dry_run,
+    )?;
+    if pruned.is_empty() {
+        println!("{}", t!("history.nothing", path = reports_dir.display()));
+    } else if dry_run {
+        println!(
+            "{}",
+            t!(
+                "history.would_prune",
+                count = pruned.len(),
+                path = reports_dir.display()
+            )
+            .yellow()
+        );
+        for run in &pruned {
+            println!("  - {}", run.path.display());
+        }
+    } else {
+        println!(
+            "{}",
+            t!(
+                "history.pruned",
+                count = pruned.len(),
+                path = reports_dir.display()
+            )
+            .green()
+        );
+    }
+    Ok(())
+}
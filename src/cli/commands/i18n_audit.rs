@@ -0,0 +1,69 @@
+//! # I18n Audit Command Module / 国际化审计命令模块
+//!
+//! This module implements the hidden, developer-facing `i18n-audit` command, which
+//! reports translation keys missing from a locale file and keys no code uses, or
+//! prints a stub file for a new locale.
+//!
+//! 此模块实现了隐藏的、面向开发者的 `i18n-audit` 命令，它报告本地化文件中缺失的翻译键
+//! 和代码未使用的键，或为新语言打印存根文件。
+
+use anyhow::{Result, bail};
+use colored::*;
+use std::path::PathBuf;
+
+use crate::infra::{
+    i18n_audit::{BASE_LOCALE, USED_KEYS, audit, load_locales, stub_locale},
+    t,
+};
+
+/// Executes the `i18n-audit` command.
+///
+/// # Arguments
+/// * `locales_dir` - The directory holding the `<locale>.toml` files
+/// * `stub` - If set, print a stub file for this locale instead of auditing
+///
+/// # Returns
+/// An error if a locale lacks keys, so the command can gate CI
+pub fn execute(locales_dir: PathBuf, stub: Option<String>) -> Result<()> {
+    let locales = load_locales(&locales_dir)?;
+
+    if let Some(locale) = stub {
+        let Some(base) = locales.get(BASE_LOCALE) else {
+            bail!("No {BASE_LOCALE}.toml in {}", locales_dir.display());
+        };
+        print!("{}", stub_locale(base, &locale));
+        return Ok(());
+    }
+
+    let report = audit(USED_KEYS, &locales);
+    for (locale, keys) in &report.missing {
+        if keys.is_empty() {
+            println!(
+                "{}",
+                t!("i18n_audit.locale_complete", name = locale).green()
+            );
+            continue;
+        }
+        println!(
+            "{}",
+            t!("i18n_audit.missing_keys", name = locale, count = keys.len()).red()
+        );
+        for key in keys {
+            println!("  - {key}");
+        }
+    }
+    if !report.unused.is_empty() {
+        println!(
+            "{}",
+            t!("i18n_audit.unused_keys", count = report.unused.len()).yellow()
+        );
+        for key in &report.unused {
+            println!("  - {key}");
+        }
+    }
+
+    if report.has_missing() {
+        bail!("{}", t!("i18n_audit.failed"));
+    }
+    Ok(())
+}
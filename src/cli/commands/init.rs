@@ -6,10 +6,19 @@
 //! 此模块实现了 Matrix Runner CLI 的 `init` 命令，
 //! 用于创建新的测试矩阵配置文件。
 
+use crate::core::config::{TestCase, edit::MatrixDocument};
+use crate::infra::{
+    t,
+    workspace::{WorkspaceMember, workspace_members},
+};
 use anyhow::{Context, Result};
 use colored::*;
-use std::{fs, path::PathBuf};
-use crate::infra::t;
+use std::{
+    fmt::Write as _,
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
 
 const DEFAULT_CONFIG: &str = r#"# Test Matrix Configuration / 测试矩阵配置
 # Documentation: https://github.com/ShaoG-R/matrix-runner
@@ -53,58 +62,384 @@ no_default_features = false
 command = "cargo run --example demo"
 "#;
 
+/// The target of the embedded template when none is given.
+/// 未给出目标时嵌入式模板使用的目标。
+pub const DEFAULT_EMBEDDED_TARGET: &str = "thumbv7em-none-eabihf";
+
+/// The matrix templates `init` can generate.
+/// `init` 可以生成的矩阵模板。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Template {
+    /// Feature combinations of a regular crate / 普通 crate 的 feature 组合
+    Default,
+    /// Build-only host cases and `build-std` cases per target, for `no_std` crates / 面向 `no_std` crate 的仅构建主机用例以及每个目标的 `build-std` 用例
+    Embedded,
+}
+
+/// A target of the embedded template, with the command its test binaries run under
+/// (e.g. `probe-rs run --chip <CHIP>` or QEMU), if they can run at all.
+/// 嵌入式模板的一个目标，以及运行其测试二进制文件的命令（例如 `probe-rs run --chip <CHIP>` 或 QEMU），如果可以运行的话。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedTarget {
+    /// The target triple / 目标三元组
+    pub triple: String,
+    /// The cargo runner of the target / 该目标的 cargo runner
+    pub runner: Option<String>,
+}
+
+/// Parses a `--runner` value of the form `<TRIPLE>=<COMMAND>`.
+/// 解析形如 `<TRIPLE>=<COMMAND>` 的 `--runner` 值。
+pub fn parse_runner(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((triple, command)) if !triple.trim().is_empty() && !command.trim().is_empty() => {
+            Ok((triple.trim().to_string(), command.trim().to_string()))
+        }
+        _ => Err(format!("expected `<TRIPLE>=<COMMAND>`, found `{value}`")),
+    }
+}
+
+/// Pairs the targets given on the command line with their runners. A runner for a
+/// target that was not listed adds that target.
+/// 将命令行中给出的目标与其 runner 配对。为未列出的目标指定 runner 时会添加该目标。
+pub fn embedded_targets(triples: &[String], runners: &[(String, String)]) -> Vec<EmbeddedTarget> {
+    let mut targets: Vec<EmbeddedTarget> = Vec::new();
+    for triple in triples.iter().chain(runners.iter().map(|(triple, _)| triple)) {
+        if !targets.iter().any(|target| &target.triple == triple) {
+            targets.push(EmbeddedTarget {
+                triple: triple.clone(),
+                runner: None,
+            });
+        }
+    }
+    for (triple, command) in runners {
+        if let Some(target) = targets.iter_mut().find(|target| &target.triple == triple) {
+            target.runner = Some(command.clone());
+        }
+    }
+    targets
+}
+
+/// How `init` lays out matrices for a workspace with several members.
+/// `init` 为具有多个成员的工作区布局矩阵的方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WorkspaceLayout {
+    /// One matrix at the workspace root, with a `package` field per case / 在工作区根目录生成一个矩阵，每个用例带有 `package` 字段
+    Single,
+    /// A `TestMatrix.toml` in every member's directory / 在每个成员目录中生成一个 `TestMatrix.toml`
+    PerMember,
+}
+
 /// Executes the init command with the provided arguments.
 ///
 /// # Arguments
 /// * `output` - Path for the new configuration file
 /// * `force` - Whether to overwrite an existing file
 /// * `lang` - Language for error messages
+/// * `layout` - How to lay out matrices in a workspace; asked interactively if `None`
+/// * `template` - The template to generate; implied by `embedded`, asked interactively
+///   outside a workspace if `None`
+/// * `embedded` - The targets of the embedded template; asked interactively if empty
 ///
 /// # Returns
 /// A Result indicating success or failure of the command execution
-pub async fn execute(output: PathBuf, force: bool, lang: Option<String>) -> Result<()> {
+pub async fn execute(
+    output: PathBuf,
+    force: bool,
+    lang: Option<String>,
+    layout: Option<WorkspaceLayout>,
+    template: Option<Template>,
+    embedded: Vec<EmbeddedTarget>,
+) -> Result<()> {
     if let Some(l) = lang {
         rust_i18n::set_locale(&l);
     }
 
+    let template = match template {
+        Some(template) => Some(template),
+        None if !embedded.is_empty() => Some(Template::Embedded),
+        None => None,
+    };
+    if template == Some(Template::Embedded) {
+        let targets = if embedded.is_empty() { prompt_embedded_targets()? } else { embedded };
+        if write_config(&output, &embedded_matrix(&targets), force)? {
+            println!("{}", t!("init.next_steps"));
+        }
+        return Ok(());
+    }
+
+    let project_dir = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let members = workspace_members(&project_dir)
+        .await
+        .filter(|members| members.len() > 1);
+
+    let Some(members) = members else {
+        if template.is_none() && prompt_template()? == Template::Embedded {
+            if write_config(&output, &embedded_matrix(&prompt_embedded_targets()?), force)? {
+                println!("{}", t!("init.next_steps"));
+            }
+            return Ok(());
+        }
+        if write_config(&output, DEFAULT_CONFIG, force)? {
+            println!("{}", t!("init.next_steps"));
+        }
+        return Ok(());
+    };
+
+    println!(
+        "{}",
+        t!("init.workspace_detected", count = members.len()).cyan()
+    );
+    let layout = match layout {
+        Some(layout) => layout,
+        None => prompt_layout()?,
+    };
+
+    let mut written = false;
+    match layout {
+        WorkspaceLayout::Single => {
+            written |= write_config(&output, &workspace_matrix(&members)?, force)?;
+        }
+        WorkspaceLayout::PerMember => {
+            let file_name = output
+                .file_name()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(crate::core::config::DEFAULT_CONFIG_FILE));
+            for member in &members {
+                written |= write_config(&member.dir.join(&file_name), DEFAULT_CONFIG, force)?;
+            }
+        }
+    }
+    if written {
+        println!("{}", t!("init.next_steps"));
+    }
+
+    Ok(())
+}
+
+/// Asks which workspace layout to generate. Without a terminal, a single matrix is generated.
+fn prompt_layout() -> Result<WorkspaceLayout> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(WorkspaceLayout::Single);
+    }
+    let choices = [
+        t!("init.layout_single").to_string(),
+        t!("init.layout_per_member").to_string(),
+    ];
+    let selection = dialoguer::Select::new()
+        .with_prompt(t!("init.layout_prompt").to_string())
+        .items(&choices)
+        .default(0)
+        .interact()?;
+    Ok(if selection == 0 {
+        WorkspaceLayout::Single
+    } else {
+        WorkspaceLayout::PerMember
+    })
+}
+
+/// Asks which template to generate. Without a terminal, the default template is generated.
+fn prompt_template() -> Result<Template> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(Template::Default);
+    }
+    let choices = [
+        t!("init.template_default").to_string(),
+        t!("init.template_embedded").to_string(),
+    ];
+    let selection = dialoguer::Select::new()
+        .with_prompt(t!("init.template_prompt").to_string())
+        .items(&choices)
+        .default(0)
+        .interact()?;
+    Ok(if selection == 0 {
+        Template::Default
+    } else {
+        Template::Embedded
+    })
+}
+
+/// Asks for the targets of the embedded template and the runner of each. Without a
+/// terminal, [`DEFAULT_EMBEDDED_TARGET`] is used without a runner.
+fn prompt_embedded_targets() -> Result<Vec<EmbeddedTarget>> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(embedded_targets(&[DEFAULT_EMBEDDED_TARGET.to_string()], &[]));
+    }
+    let triples: String = dialoguer::Input::new()
+        .with_prompt(t!("init.targets_prompt").to_string())
+        .default(DEFAULT_EMBEDDED_TARGET.to_string())
+        .interact_text()?;
+    let mut targets = Vec::new();
+    for triple in triples.split([',', ' ']).filter(|triple| !triple.is_empty()) {
+        let runner: String = dialoguer::Input::new()
+            .with_prompt(t!("init.runner_prompt", target = triple).to_string())
+            .allow_empty(true)
+            .interact_text()?;
+        targets.push(EmbeddedTarget {
+            triple: triple.to_string(),
+            runner: Some(runner.trim().to_string()).filter(|runner| !runner.is_empty()),
+        });
+    }
+    Ok(targets)
+}
+
+/// Builds the matrix of the embedded template: build-only host cases with and without
+/// default features, then for each target a nightly `-Zbuild-std` build, and, if the
+/// target has a runner, a case running its tests through it.
+///
+/// 构建嵌入式模板的矩阵：启用和禁用默认 features 的仅构建主机用例，然后为每个目标生成一个
+/// nightly `-Zbuild-std` 构建用例；如果目标有 runner，再生成一个通过它运行测试的用例。
+pub fn embedded_matrix(targets: &[EmbeddedTarget]) -> String {
+    let mut config = String::from(
+        r#"# Test Matrix Configuration / 测试矩阵配置
+# Documentation: https://github.com/ShaoG-R/matrix-runner
+
+# Language for error messages / 错误消息的语言
+language = "en"
+
+# Keep going after a failure, to see every broken target / 失败后继续运行，以便看到所有损坏的目标
+fast_fail = false
+
+# Host builds: the crate must also build with and without `std` / 主机构建：crate 在启用和不启用 `std` 时都必须能构建
+[[cases]]
+name = "host-default"
+features = ""
+no_default_features = false
+build_only = true
+
+[[cases]]
+name = "host-no-default-features"
+features = ""
+no_default_features = true
+build_only = true
+"#,
+    );
+    for target in targets {
+        let triple = &target.triple;
+        let _ = write!(
+            config,
+            r#"
+# Target `{triple}`: `core` and `alloc` are built from source / 目标 `{triple}`：`core` 和 `alloc` 从源码构建
+[[cases]]
+name = "{triple}-build"
+features = ""
+no_default_features = true
+build_only = true
+toolchain = "nightly"
+target = "{triple}"
+cargo_unstable_flags = ["-Zbuild-std=core,alloc"]
+components = ["rust-src"]
+"#
+        );
+        let Some(runner) = &target.runner else {
+            let _ = write!(
+                config,
+                r#"# To run the tests on the target, add a case with a custom `command`, e.g.
+# 要在目标上运行测试，请添加一个带有自定义 `command` 的用例，例如：
+# command = """cargo +nightly test --no-default-features --target {triple} -Zbuild-std=core,alloc --config 'target.{triple}.runner="probe-rs run --chip <CHIP>"'"""
+"#
+            );
+            continue;
+        };
+        // The runner is set through `--config`, quoted for the shell-like split of the command.
+        let runner_config = format!("target.{triple}.runner={}", toml::Value::String(runner.clone()));
+        let runner_config = shlex::try_quote(&runner_config).unwrap_or_default();
+        let command = format!(
+            "cargo +nightly test --no-default-features --target {triple} -Zbuild-std=core,alloc --config {runner_config}"
+        );
+        let _ = write!(
+            config,
+            r#"
+# Tests of `{triple}`, run with `{runner}` / `{triple}` 的测试，使用 `{runner}` 运行
+[[cases]]
+name = "{triple}-test"
+features = ""
+no_default_features = true
+toolchain = "nightly"
+components = ["rust-src"]
+command = {command}
+"#,
+            command = toml::Value::String(command)
+        );
+    }
+    config
+}
+
+/// Builds a single matrix for a workspace, with default and no-default-features cases per member.
+fn workspace_matrix(members: &[WorkspaceMember]) -> Result<String> {
+    let mut config = MatrixDocument::parse(
+        r#"# Test Matrix Configuration / 测试矩阵配置
+# Documentation: https://github.com/ShaoG-R/matrix-runner
+
+# Language for error messages / 错误消息的语言
+language = "en"
+
+# Abort on first failure? / 遇到首个失败时立即中止？
+fast_fail = true
+"#,
+    )?;
+    for member in members {
+        let name = &member.name;
+        for (suffix, no_default_features) in [("default", false), ("no-default-features", true)] {
+            config.add_case(&TestCase {
+                name: format!("{name}-{suffix}"),
+                features: String::new(),
+                no_default_features,
+                package: Some(name.clone()),
+                ..Default::default()
+            })?;
+        }
+        config.set_case_comment(
+            &format!("{name}-default"),
+            &format!("Cases for workspace member `{name}` / 工作区成员 `{name}` 的用例"),
+        )?;
+    }
+    Ok(config.to_string())
+}
+
+/// Writes a configuration file unless it already exists and `force` is not set.
+///
+/// # Returns
+/// `true` if the file was written
+fn write_config(path: &Path, content: &str, force: bool) -> Result<bool> {
     // Check if file already exists
-    if output.exists() && !force {
+    if path.exists() && !force {
         println!(
             "{}",
-            t!("init.file_exists", path = output.display()).red()
+            t!("init.file_exists", path = path.display()).red()
         );
         println!("{}", t!("init.use_force").yellow());
-        return Ok(());
+        return Ok(false);
     }
 
     // Create parent directories if needed
-    if let Some(parent) = output.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).with_context(|| {
-                format!(
-                    "{}",
-                    t!(
-                        "init.create_parent_dir_failed",
-                        path = parent.display()
-                    )
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "{}",
+                t!(
+                    "init.create_parent_dir_failed",
+                    path = parent.display()
                 )
-            })?;
-        }
+            )
+        })?;
     }
 
-    // Write the default configuration to the output file
-    fs::write(&output, DEFAULT_CONFIG).with_context(|| {
+    fs::write(path, content).with_context(|| {
         format!(
             "{}",
-            t!("init.write_failed", path = output.display())
+            t!("init.write_failed", path = path.display())
         )
     })?;
 
     println!(
         "{}",
-        t!("init.success", path = output.display()).green()
+        t!("init.success", path = path.display()).green()
     );
-    println!("{}", t!("init.next_steps"));
-
-    Ok(())
-} 
\ No newline at end of file
+    Ok(true)
+}
@@ -0,0 +1,63 @@
+//! # Merge Results Command Module / 合并结果命令模块
+//!
+//! This module implements the `merge-results` command, which merges the results JSON of
+//! several runs, e.g. the shards of a split CI run, into one with
+//! [`crate::core::results::merge`].
+//!
+//! 此模块实现了 `merge-results` 命令，它使用 [`crate::core::results::merge`] 将多次运行
+//! （例如拆分 CI 运行的各个分片）的结果 JSON 合并为一个。
+
+use anyhow::Result;
+use colored::*;
+use std::path::PathBuf;
+
+use crate::{
+    core::{context::RunContext, error::MatrixError, results},
+    infra::t,
+    reporting::json::generate_json_report,
+};
+
+/// Executes the `merge-results` command.
+///
+/// # Arguments
+/// * `inputs` - The results JSON files to merge, in any order
+/// * `output` - The path the merged results JSON is written to
+///
+/// # Returns
+/// An error if an input cannot be read or the output cannot be written, and
+/// `MatrixError::UnexpectedFailures` if a case failed unexpectedly in the merged results
+pub fn execute(inputs: Vec<PathBuf>, output: PathBuf) -> Result<()> {
+    let reports = inputs
+        .iter()
+        .map(|path| results::load_report(path))
+        .collect::<Result<Vec<_>>>()
+        .map_err(MatrixError::Config)?;
+    let context = RunContext::current();
+    let today = chrono::Local::now().date_naive();
+    let merged = results::merge(reports, &context, today);
+    generate_json_report(&merged.results, &merged.metadata, &output)?;
+
+    let failed = merged
+        .results
+        .iter()
+        .filter(|result| result.is_unexpected_failure(&context, today))
+        .count();
+    println!(
+        "{}",
+        t!(
+            "merge_results.written",
+            locale = &context.locale,
+            count = inputs.len(),
+            cases = merged.results.len(),
+            path = output.display()
+        )
+        .green()
+    );
+    for note in &merged.metadata.notes {
+        println!("  {}", note.dimmed());
+    }
+    if failed > 0 {
+        return Err(MatrixError::UnexpectedFailures { count: failed }.into());
+    }
+    Ok(())
+}
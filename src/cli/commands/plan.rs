@@ -0,0 +1,158 @@
+//! # Plan Command Module / 计划命令模块
+//!
+//! This module implements the `plan export` command, which resolves the cases a run
+//! would execute and writes them to a plan file for `run --from-plan`.
+//!
+//! 此模块实现了 `plan export` 命令，它解析一次运行将执行的用例，
+//! 并将其写入供 `run --from-plan` 使用的计划文件。
+
+use anyhow::Result;
+use colored::*;
+use std::path::PathBuf;
+
+use super::run::{
+    prepare_environment, resolve_config_location, select_cases, setup_and_parse_config,
+};
+use crate::{
+    core::{
+        context::RunContext, error::MatrixError, exec_plan::ExecPlan, planner, selector::Selector,
+        settings,
+    },
+    infra::t,
+};
+
+/// Options for the `plan export` command, as collected from the command line.
+/// `plan export` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Path the plan is written to / 计划写入的路径
+    pub output: PathBuf,
+    /// Path to the test matrix configuration file; discovered from the current directory if `None`
+    /// 测试矩阵配置文件的路径；为 `None` 时从当前目录开始查找
+    pub config: Option<PathBuf>,
+    /// Path to the project directory; defaults to the discovered config's directory
+    /// 项目目录的路径；默认为找到的配置文件所在的目录
+    pub project_dir: Option<PathBuf>,
+    /// Total number of distributed runners (for CI) / 分布式运行器的总数（用于 CI）
+    pub total_runners: Option<usize>,
+    /// Index of this runner (for CI) / 此运行器的索引（用于 CI）
+    pub runner_index: Option<usize>,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+    /// Optional `--select` expression restricting the cases / 限制用例的可选 `--select` 表达式
+    pub select: Option<String>,
+    /// Ignore unknown keys of the matrix file instead of rejecting them / 忽略矩阵文件中未知的键，而不是拒绝它们
+    pub lenient: bool,
+}
+
+/// Executes the `plan export` command.
+///
+/// The cases go through the same selection, architecture filtering and runner splitting
+/// as in `run`, and dependencies are fetched so the plan can be executed offline.
+///
+/// # Arguments
+/// * `options` - The options collected from the command line
+///
+/// # Returns
+/// A Result indicating success or failure of the command execution
+pub async fn export(options: ExportOptions) -> Result<()> {
+    let ExportOptions {
+        output,
+        config,
+        project_dir,
+        total_runners,
+        runner_index,
+        lang,
+        select,
+        lenient,
+    } = options;
+
+    let (config, project_dir) = resolve_config_location(config, project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+
+    let (mut test_matrix, _) =
+        setup_and_parse_config(&config, lenient).map_err(MatrixError::Config)?;
+    let selector = select
+        .as_deref()
+        .map(Selector::parse)
+        .transpose()
+        .map_err(|e| {
+            MatrixError::Config(anyhow::anyhow!("{}:\n{}", t!("run.select_invalid"), e))
+        })?;
+
+    if let Some(language) = settings.language(lang) {
+        rust_i18n::set_locale(&language);
+    } else if test_matrix.language != "en" {
+        rust_i18n::set_locale(&test_matrix.language);
+    }
+    let locale = rust_i18n::locale().to_string();
+
+    let (project_root, crate_name) = prepare_environment(&project_dir, true, &locale)
+        .await
+        .map_err(MatrixError::Environment)?;
+    test_matrix.namespace_by_package(&crate_name);
+
+    let mut notes = Vec::new();
+    if let Some(selector) = &selector {
+        notes.push(select_cases(
+            &mut test_matrix,
+            selector,
+            &settings.state_dir(&project_root),
+            &locale,
+        ));
+    }
+
+    let matrix_settings = test_matrix.clone();
+    let plan = planner::plan_execution(
+        test_matrix,
+        total_runners,
+        runner_index,
+        &RunContext::new(locale.clone()),
+        chrono::Local::now().date_naive(),
+    )
+    .map_err(MatrixError::Config)?;
+    if plan.filtered_arch_count > 0 {
+        notes.push(
+            t!(
+                "run.filtered_arch_cases",
+                locale = &locale,
+                filtered = plan.filtered_arch_count,
+                total = plan.cases_to_run.len() + plan.filtered_arch_count,
+            )
+            .to_string(),
+        );
+    }
+    if let (Some(total), Some(index)) = (total_runners, runner_index) {
+        notes.push(
+            t!(
+                "run.running_as_split_runner",
+                locale = &locale,
+                index = index,
+                total = total,
+                count = plan.cases_to_run.len()
+            )
+            .to_string(),
+        );
+    }
+
+    let exec_plan = ExecPlan::new(
+        &matrix_settings,
+        plan.cases_to_run,
+        project_root,
+        crate_name,
+        notes,
+    );
+    exec_plan.save(&output).map_err(MatrixError::Config)?;
+    println!(
+        "{}",
+        t!(
+            "plan.exported",
+            locale = &locale,
+            count = exec_plan.cases.len(),
+            path = output.display()
+        )
+        .green()
+    );
+    Ok(())
+}
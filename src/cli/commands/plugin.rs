@@ -0,0 +1,43 @@
+//! # Plugin Command Module / 插件命令模块
+//!
+//! This module runs an unknown subcommand as a plugin: `matrix-runner <name> [args]`
+//! executes `matrix-runner-<name>` from `PATH` with the remaining arguments.
+//!
+//! 此模块将未知的子命令作为插件运行：`matrix-runner <name> [args]` 会以剩余参数执行
+//! `PATH` 上的 `matrix-runner-<name>`。
+
+use anyhow::Result;
+use std::ffi::OsString;
+
+use crate::{
+    core::{error::MatrixError, plugins},
+    infra::t,
+};
+
+/// Executes a plugin subcommand. The process exits with the plugin's exit code if the
+/// plugin fails, so scripts see the same result as when calling the plugin directly.
+///
+/// # Arguments
+/// * `name` - The subcommand, i.e. the plugin name without the `matrix-runner-` prefix
+/// * `args` - The arguments following the subcommand
+///
+/// # Returns
+/// An error if no such plugin is found or it cannot be started
+pub fn execute(name: &str, args: Vec<OsString>) -> Result<()> {
+    let Some(path) = plugins::find_plugin(name) else {
+        return Err(MatrixError::Config(anyhow::anyhow!(
+            "{}",
+            t!(
+                "plugin.not_found",
+                name = name,
+                executable = format!("{}{}", plugins::PLUGIN_PREFIX, name)
+            )
+        ))
+        .into());
+    };
+    match plugins::run_plugin(&path, &args)? {
+        Some(0) => Ok(()),
+        Some(code) => std::process::exit(code),
+        None => std::process::exit(MatrixError::GENERIC_EXIT_CODE.into()),
+    }
+}
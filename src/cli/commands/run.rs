@@ -7,64 +7,290 @@
 //! 根据测试矩阵配置执行测试用例。
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use colored::*;
-use futures::{stream, StreamExt};
-use std::{env, fs, path::PathBuf, time::Duration};
+use futures::{StreamExt, stream};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tempfile::TempDir;
 use tokio::{signal, sync::mpsc};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    infra::t,
     core::{
-        config::{self, TestMatrix},
-        execution::run_test_case,
-        models::{self, FailureReason, Manifest},
-        planner,
+        anomaly, axis,
+        config::{self, MissingComponentPolicy, TestMatrix},
+        context::RunContext,
+        costs::SummarySort,
+        dependency_layer,
+        error::MatrixError,
+        exec_plan::ExecPlan,
+        execution::{ExecutionContext, KeepBuildLogs, run_test_case},
+        explain::{self, CaseFilters},
+        fingerprint, flakiness,
+        history::{self, Retention, RunHistory},
+        live_log,
+        models::{self, FailureReason, RunMetadata},
+        owners, planner,
+        plugins::{self, EventSink, EventStream, RunEvent},
+        resume::{self, RunJournal},
+        retry_hint,
+        scheduler::{ResourceCeiling, Scheduler, is_smoke_case},
+        selector::Selector,
+        settings,
+        status::StatusFile,
+    },
+    infra::{
+        command,
+        control::{self, RunControl},
+        system_load, t, toolchain, workspace,
     },
     reporting::{
-        console::{print_summary, print_unexpected_failure_details},
-        html::generate_html_report,
-    }
+        console::{
+            self, print_anomalies, print_axis_summary, print_failures_by_owner, print_flakiness,
+            print_summary_with_costs, print_unexpected_failure_details,
+        },
+        html::{HtmlOptions, generate_html_report},
+        json::{self, generate_json_report},
+        junit::generate_junit_report,
+        repro,
+    },
 };
 
-/// Executes the run command with the provided arguments.
+/// How long a run waits for another cargo process to release the project's target directory.
+/// 运行等待另一个 cargo 进程释放项目 target 目录的时长。
+const TARGET_LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often the target directory lock is checked while waiting / 等待期间检查 target 目录锁的频率
+const TARGET_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Options for the `run` command, as collected from the command line.
+/// `run` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Number of parallel jobs to run / 要运行的并行任务数量
+    pub jobs: Option<usize>,
+    /// Path to the test matrix configuration file; discovered from the current directory if `None`
+    /// 测试矩阵配置文件的路径；为 `None` 时从当前目录开始查找
+    pub config: Option<PathBuf>,
+    /// Path to the project directory; defaults to the discovered config's directory
+    /// 项目目录的路径；默认为找到的配置文件所在的目录
+    pub project_dir: Option<PathBuf>,
+    /// Total number of distributed runners (for CI) / 分布式运行器的总数（用于 CI）
+    pub total_runners: Option<usize>,
+    /// Index of this runner (for CI) / 此运行器的索引（用于 CI）
+    pub runner_index: Option<usize>,
+    /// Optional path for HTML report output / HTML 报告的可选输出路径
+    pub html: Option<PathBuf>,
+    /// Optional path for a copy of the JSON results / JSON 结果副本的可选输出路径
+    pub json: Option<PathBuf>,
+    /// Optional path for a JUnit XML report / JUnit XML 报告的可选输出路径
+    pub junit: Option<PathBuf>,
+    /// Size options for the HTML report / HTML 报告的大小选项
+    pub html_options: HtmlOptions,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+    /// Stop on the first unexpected failure / 在第一次意外失败时停止
+    pub fast_fail: bool,
+    /// Optional `--select` expression restricting the cases to run / 限制要运行用例的可选 `--select` 表达式
+    pub select: Option<String>,
+    /// Print why this case is or is not selected instead of running the matrix / 打印此用例被或未被选中的原因，而不运行矩阵
+    pub explain: Option<String>,
+    /// Optional directory to write reproduction bundles for failed cases to / 为失败用例写出复现包的可选目录
+    pub repro_bundle_dir: Option<PathBuf>,
+    /// Run the cases of a plan written by `plan export` instead of a matrix / 运行由 `plan export` 写出的计划中的用例，而不是矩阵
+    pub from_plan: Option<PathBuf>,
+    /// Install missing toolchains and targets used by the cases with rustup / 使用 rustup 安装用例使用的缺失工具链和目标
+    pub auto_install_toolchains: bool,
+    /// Skip cases with `requires_network = true` / 跳过 `requires_network = true` 的用例
+    pub offline_only: bool,
+    /// Record the SHA-256 of each built test binary / 记录每个所构建测试二进制文件的 SHA-256
+    pub record_checksums: bool,
+    /// Build each case twice and flag non-reproducible builds / 将每个用例构建两次并标记不可复现的构建
+    pub verify_reproducible: bool,
+    /// Keep the passed cases of an interrupted run and only run the others / 保留被中断运行中已通过的用例，只运行其他用例
+    pub resume: bool,
+    /// Where the run journal is kept; defaults to `run_journal.jsonl` in the state directory / 运行日志的保存位置；默认为状态目录中的 `run_journal.jsonl`
+    pub resume_file: Option<PathBuf>,
+    /// Run the cases tagged `smoke` before all others / 先于其他所有用例运行带有 `smoke` 标签的用例
+    pub smoke_first: bool,
+    /// Run the other cases even if a smoke case failed / 即使有冒烟用例失败也运行其他用例
+    pub smoke_keep_going: bool,
+    /// Which build logs to archive in the log directory / 要在日志目录中归档哪些构建日志
+    pub keep_build_logs: KeepBuildLogs,
+    /// Give custom commands their own target directory instead of the project's / 为自定义命令提供各自的 target 目录，而不是项目的目录
+    pub isolated_only: bool,
+    /// Keep a failure in a shared build directory from spreading to other cases / 防止共享构建目录中的失败波及其他用例
+    pub keep_going: bool,
+    /// Build and run the default flow with `cargo nextest` if it is installed / 如果已安装 `cargo nextest`，则用它构建和运行默认流程
+    pub prefer_nextest: bool,
+    /// Order the console summary by a cost of the cases, most expensive first / 按用例的某项成本对控制台摘要排序，成本最高的在前
+    pub sort_by: Option<SummarySort>,
+    /// Ignore unknown keys of the matrix file instead of rejecting them / 忽略矩阵文件中未知的键，而不是拒绝它们
+    pub lenient: bool,
+    /// Also print locale-independent result and status lines for scripts / 同时为脚本打印与语言环境无关的结果行和状态行
+    pub stable_output: bool,
+    /// Validate the results JSON against the published schema after writing it / 写出结果 JSON 后根据发布的模式验证它
+    pub validate_output: bool,
+    /// Build the dependencies once before the cases and seed every case build with them / 在用例之前一次性构建依赖，并以其作为每个用例构建的起点
+    pub prebuild_deps: bool,
+    /// Test this workspace member in every case, in place of the packages the matrix names / 在每个用例中测试此工作区成员，取代矩阵指定的包
+    pub package: Option<String>,
+    /// Start new cases only while the load average is at most this / 仅在平均负载不超过此值时启动新用例
+    pub max_load: Option<f64>,
+    /// Start new cases only while the system uses at most this much memory, in GiB / 仅在系统使用的内存不超过此值（GiB）时启动新用例
+    pub max_total_memory_gb: Option<f64>,
+    /// Also send the events of the run to this sink, e.g. the dashboard of `serve` / 同时将运行的事件发送给此接收者，例如 `serve` 的仪表盘
+    pub event_sink: Option<Arc<dyn EventSink>>,
+}
+
+/// Executes the run command with the provided options.
 ///
 /// # Arguments
-/// * `jobs` - Number of parallel jobs to run
-/// * `config` - Path to the test matrix configuration file
-/// * `project_dir` - Path to the project directory
-/// * `total_runners` - Total number of distributed runners (for CI)
-/// * `runner_index` - Index of this runner (for CI)
-/// * `html` - Optional path for HTML report output
-/// * `lang` - Optional language code for the test matrix (e.g., "en", "zh")
+/// * `options` - The options collected from the command line
 ///
 /// # Returns
 /// A Result indicating success or failure of the command execution
-pub async fn execute(
-    jobs: Option<usize>,
-    config: PathBuf,
-    project_dir: PathBuf,
-    total_runners: Option<usize>,
-    runner_index: Option<usize>,
-    html: Option<PathBuf>,
-    lang: Option<String>,
-    fast_fail_cli: bool,
-) -> Result<()> {
-    let (test_matrix, config_path) = setup_and_parse_config(&config)?;
+pub async fn execute(options: RunOptions) -> Result<()> {
+    let RunOptions {
+        jobs,
+        config,
+        project_dir,
+        total_runners,
+        runner_index,
+        html,
+        json,
+        junit,
+        mut html_options,
+        lang,
+        fast_fail: fast_fail_cli,
+        select,
+        explain,
+        repro_bundle_dir,
+        from_plan,
+        auto_install_toolchains,
+        offline_only,
+        record_checksums,
+        verify_reproducible,
+        resume,
+        resume_file,
+        smoke_first,
+        smoke_keep_going,
+        keep_build_logs,
+        isolated_only,
+        keep_going,
+        prefer_nextest,
+        sort_by,
+        lenient,
+        stable_output,
+        validate_output,
+        prebuild_deps,
+        package,
+        max_load,
+        max_total_memory_gb,
+        event_sink,
+    } = options;
+
+    let plan_file = from_plan
+        .as_deref()
+        .map(ExecPlan::load)
+        .transpose()
+        .map_err(MatrixError::Config)?;
+    let (config, project_dir) = match (&from_plan, &plan_file) {
+        // `--project-dir` relocates a plan to where the sources live on this machine.
+        (Some(path), Some(plan)) => (
+            path.clone(),
+            project_dir.unwrap_or_else(|| plan.project_root.clone()),
+        ),
+        _ => resolve_config_location(config, project_dir),
+    };
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+
+    let (mut test_matrix, config_path) = match &plan_file {
+        Some(plan) => (plan.to_matrix(), config.clone()),
+        None => setup_and_parse_config(&config, lenient).map_err(MatrixError::Config)?,
+    };
     let fast_fail_mode = fast_fail_cli || test_matrix.fast_fail;
+    let prebuild_deps = prebuild_deps || test_matrix.prebuild_dependencies;
 
-    // The locale has been pre-initialized in main.rs from the system or --lang argument.
-    // We only override it if the config file specifies a non-default language
-    // AND no --lang argument was provided.
-    if lang.is_none() && test_matrix.language != "en" {
-        rust_i18n::set_locale(&test_matrix.language);
-    }
-    
-    // Get the final, correct locale for use in this command.
-    let locale = rust_i18n::locale().to_string();
+    // The process locale has been pre-initialized in main.rs from the system or --lang
+    // argument. --lang and then the settings file take precedence; otherwise the run only
+    // departs from it if the config file specifies a non-default language. The locale is
+    // only carried by the run's context, so other runs in the process keep their own.
+    let locale = settings.language(lang).unwrap_or_else(|| {
+        if test_matrix.language != "en" {
+            test_matrix.language.clone()
+        } else {
+            rust_i18n::locale().to_string()
+        }
+    });
+    let context = RunContext::new(locale.clone());
+    let today = chrono::Local::now().date_naive();
 
-    let (project_root, crate_name) = prepare_environment(&project_dir, &locale).await?;
+    // Validate the selector before doing any expensive work.
+    let selector = select
+        .as_deref()
+        .map(Selector::parse)
+        .transpose()
+        .map_err(|e| {
+            MatrixError::Config(anyhow::anyhow!("{}:\n{}", t!("run.select_invalid", locale = &locale), e))
+        })?;
+
+    // A plan is made where dependencies can be fetched; its execution may be offline.
+    let (project_root, crate_name) = prepare_environment(&project_dir, plan_file.is_none(), &locale)
+        .await
+        .map_err(MatrixError::Environment)?;
+    if let Some(package) = &package {
+        check_workspace_member(&project_root, package, &locale)
+            .await
+            .map_err(MatrixError::Config)?;
+        test_matrix.override_package(package);
+    }
+    // Cases are named `pkg::case` from here on, in every report and in the history.
+    test_matrix.namespace_by_package(&crate_name);
+    let config_snapshot = test_matrix.clone();
+    let summary_columns = test_matrix.summary_columns.clone();
+    html_options.columns = summary_columns.clone();
+    let use_nextest = if prefer_nextest || test_matrix.prefer_nextest {
+        let installed = toolchain::nextest_installed(&project_root).await;
+        let message = if installed {
+            t!("run.using_nextest", locale = &locale).cyan()
+        } else {
+            t!("run.nextest_missing", locale = &locale).yellow()
+        };
+        println!("{}", message);
+        installed
+    } else {
+        false
+    };
+    let mut ctx = ExecutionContext {
+        state_dir: settings.state_dir(&project_root),
+        use_nextest,
+        record_checksums,
+        verify_reproducible,
+        keep_build_logs,
+        isolated_only,
+        keep_going,
+        force_c_locale: test_matrix.force_c_locale,
+        transient_build_retries: test_matrix.transient_build_retries,
+        collect_core_dumps: test_matrix.collect_core_dumps,
+        context: context.clone(),
+        today,
+        ..ExecutionContext::new(project_root.clone(), crate_name.clone())
+    };
+    let html = settings.html_report(html, &project_root);
+    let json = settings.json_report(json, &project_root);
+    let junit = settings.junit_report(junit, &project_root);
+    let mut metadata = RunMetadata {
+        environment: Some(crate::infra::environment::capture_environment(&project_root).await),
+        config: Some(config_snapshot),
+        ..Default::default()
+    };
 
     println!(
         "{}",
@@ -79,26 +305,74 @@ pub async fn execute(
         t!("common.loading_test_matrix", locale = &locale, path = config_path.display())
     );
 
-    let overall_stop_token = setup_signal_handler(&locale)?;
+    if cfg!(windows) {
+        warn_on_long_build_paths(&project_root, &locale);
+    }
+    prune_history(&ctx.state_dir, settings.history_retention(), &locale);
+
+    if let Some(plan) = &plan_file {
+        let note = t!(
+            "run.running_from_plan",
+            locale = &locale,
+            path = config_path.display(),
+            count = plan.cases.len()
+        );
+        println!("{}", note.cyan());
+        metadata.notes.push(note.to_string());
+        metadata.notes.extend(plan.notes.iter().cloned());
+    }
 
-    let plan = planner::plan_execution(test_matrix, total_runners, runner_index)?;
+    let stop = setup_signal_handler(&locale)?;
+
+    // `--explain` replays the selection on the whole matrix.
+    let explain_matrix = explain.is_some().then(|| test_matrix.clone());
+    if let Some(selector) = &selector {
+        let note = select_cases(&mut test_matrix, selector, &ctx.state_dir, &locale);
+        println!("{}", note.cyan());
+        metadata.notes.push(note);
+    }
+
+    // Cases left out on purpose are still reported, as skipped with the reason.
+    let mut skipped_results = Vec::new();
+    if offline_only && let Some((note, skipped)) = skip_network_cases(&mut test_matrix, &locale) {
+        println!("{}", note.yellow());
+        metadata.notes.push(note);
+        skipped_results = skipped;
+    }
+
+    let ceiling = ResourceCeiling {
+        max_load: max_load.or(test_matrix.max_load),
+        max_total_memory_gb: max_total_memory_gb.or(test_matrix.max_total_memory_gb),
+    };
+    if !ceiling.is_empty() {
+        announce_resource_ceiling(&ceiling, &locale);
+    }
+    let scheduler = Scheduler::new(settings.jobs(jobs), &test_matrix).with_resource_ceiling(ceiling, &context.locale);
+    // A shard keeps the matrix settings to write the retry matrix of its failed cases.
+    let retry_settings = total_runners.map(|_| TestMatrix {
+        cases: Vec::new(),
+        ..test_matrix.clone()
+    });
+    let anomaly_factor = test_matrix.anomaly_factor;
+    let on_missing_component = test_matrix.on_missing_component;
+    let case_owners = test_matrix.owners.clone();
+    let mut plan = planner::plan_execution(test_matrix, total_runners, runner_index, &context, today)
+        .map_err(MatrixError::Config)?;
 
     if plan.filtered_arch_count > 0 {
-        println!(
-            "{}",
-            t!(
-                "run.filtered_arch_cases",
-                locale = &locale,
-                filtered = plan.filtered_arch_count,
-                total = plan.cases_to_run.len() + plan.filtered_arch_count,
-            )
-            .cyan()
+        let note = t!(
+            "run.filtered_arch_cases",
+            locale = &locale,
+            filtered = plan.filtered_arch_count,
+            total = plan.cases_to_run.len() + plan.filtered_arch_count,
         );
+        println!("{}", note.cyan());
+        metadata.notes.push(note.to_string());
     }
 
     println!(
         "{}",
-        t!("common.current_os", locale = &locale, os = env::consts::OS).cyan()
+        t!("common.current_os", locale = &locale, os = &context.os).cyan()
     );
 
     if plan.flaky_cases_count > 0 {
@@ -109,26 +383,139 @@ pub async fn execute(
     }
 
     if let (Some(total), Some(index)) = (total_runners, runner_index) {
-        println!(
-            "{}",
-            t!(
-                "run.running_as_split_runner",
-                locale = &locale,
-                index = index,
-                total = total,
-                count = plan.cases_to_run.len()
-            )
-            .bold()
+        let note = t!(
+            "run.running_as_split_runner",
+            locale = &locale,
+            index = index,
+            total = total,
+            count = plan.cases_to_run.len()
         );
+        println!("{}", note.bold());
+        metadata.notes.push(note.to_string());
     } else {
         println!("{}", t!("run.running_as_single_runner", locale = &locale).bold());
     }
 
-    if plan.cases_to_run.is_empty() {
+    if let (Some(name), Some(matrix)) = (&explain, &explain_matrix) {
+        let history = RunHistory::load(&ctx.state_dir);
+        let filters = CaseFilters {
+            selector: selector.as_ref(),
+            history: &history,
+            offline_only,
+            runners: total_runners.zip(runner_index),
+        };
+        let explanation = explain::explain_case(matrix, name, &filters, &context, today, &crate_name)
+            .map_err(MatrixError::Config)?
+            .ok_or_else(|| {
+                MatrixError::Config(anyhow::anyhow!(
+                    "{}",
+                    t!("explain.unknown_case", locale = &locale, name = name)
+                ))
+            })?;
+        console::print_explanation(&explanation, &locale);
+        return Ok(());
+    }
+
+    let journal_path = resume_file.unwrap_or_else(|| resume::journal_path(&ctx.state_dir));
+    let resumed_results = if resume {
+        resume_previous_run(&mut plan.cases_to_run, &journal_path, &mut metadata, &locale)
+    } else {
+        Vec::new()
+    };
+
+    if plan.cases_to_run.is_empty() && resumed_results.is_empty() {
         println!("{}", t!("common.no_cases_to_run", locale = &locale).green());
+        // Reports are still written so CI steps that expect them find a valid, empty file.
+        let reports = Reports {
+            html: html.as_deref(),
+            json: json.as_deref(),
+            junit: junit.as_deref(),
+        };
+        write_reports(&skipped_results, &metadata, &reports, &html_options, &ctx.state_dir, &context, today);
+        if validate_output {
+            check_output(&ctx.state_dir, &locale)?;
+        }
+        if stable_output {
+            println!("{}", console::stable_status_line(&skipped_results, None));
+        }
         return Ok(());
     }
 
+    let missing_components = toolchain::ensure_toolchains(
+        &project_root,
+        &plan.cases_to_run,
+        auto_install_toolchains,
+        &locale,
+    )
+    .await
+    .map_err(MatrixError::Environment)?;
+    toolchain::check_unstable_flags_toolchain(&project_root, &plan.cases_to_run, &locale)
+        .await
+        .map_err(MatrixError::Environment)?;
+    if let Some(note) = handle_missing_components(
+        &mut plan.cases_to_run,
+        &missing_components,
+        on_missing_component,
+        &locale,
+    )
+    .map_err(MatrixError::Environment)?
+    {
+        println!("{}", note.yellow());
+        metadata.notes.push(note);
+    }
+
+    // Builds of the default flow have their own directories; custom commands share the project's.
+    if !isolated_only && plan.cases_to_run.iter().any(|case| case.command.is_some()) {
+        wait_for_target_dir(&project_root, &locale)
+            .await
+            .map_err(MatrixError::Environment)?;
+    }
+
+    if prebuild_deps {
+        ctx.dependency_layer = prebuild_dependencies(&ctx, &plan.cases_to_run, &locale).await;
+    }
+
+    ctx.partial.begin(metadata.clone(), ctx.state_dir.clone(), &context, today);
+    let journal = match RunJournal::start(&journal_path, &resumed_results) {
+        Ok(journal) => Some(journal),
+        Err(e) => {
+            eprintln!("{} {}", t!("run.journal_write_failed", locale = &locale).yellow(), e);
+            None
+        }
+    };
+    if let Err(e) = live_log::clear(&ctx.state_dir) {
+        eprintln!("{} {}", t!("run.live_log_failed", locale = &locale).yellow(), e);
+    }
+    let total_cases = plan.cases_to_run.len() + resumed_results.len();
+    let status = match StatusFile::start(&ctx.state_dir, total_cases, resumed_results.len()) {
+        Ok(status) => Some(status),
+        Err(e) => {
+            eprintln!("{} {}", t!("run.status_write_failed", locale = &locale).yellow(), e);
+            None
+        }
+    };
+    let events = if settings.plugins.is_empty() && event_sink.is_none() {
+        None
+    } else {
+        let (events, failures) = EventStream::start(&settings.plugins);
+        for (plugin, e) in failures {
+            eprintln!(
+                "{} {}",
+                t!("run.plugin_start_failed", locale = &locale, plugin = plugin).yellow(),
+                e
+            );
+        }
+        if let Some(sink) = event_sink {
+            events.add_sink(sink);
+        }
+        events.send(&RunEvent::RunStarted {
+            protocol: plugins::EVENT_PROTOCOL_VERSION,
+            project_root: project_root.clone(),
+            total: total_cases,
+        });
+        Some(events)
+    };
+
     let (temp_dir_tx, mut temp_dir_rx) = mpsc::unbounded_channel::<TempDir>();
     let collector_handle = tokio::spawn(async move {
         let mut dirs = Vec::new();
@@ -138,57 +525,516 @@ pub async fn execute(
         dirs
     });
 
-    let (final_results, has_unexpected_failures) = run_tests(
+    let run_control = RunControl::new(fast_fail_mode);
+    let control_stop_token = CancellationToken::new();
+    let control_path = control::control_path(&ctx.state_dir);
+    println!(
+        "{}",
+        t!("run.control_file_hint", locale = &locale, path = control_path.display()).dimmed()
+    );
+    let control_handle = tokio::spawn(control::watch_control_file(
+        control_path,
+        run_control.clone(),
+        control_stop_token.clone(),
+        locale.clone(),
+    ));
+
+    let mut scheduler = scheduler.with_order(&plan.cases_to_run);
+    if smoke_first {
+        let smoke_count = plan.cases_to_run.iter().filter(|case| is_smoke_case(case)).count();
+        if smoke_count == 0 {
+            println!("{}", t!("run.smoke_first_no_cases", locale = &locale).yellow());
+        } else {
+            println!("{}", t!("run.smoke_first", locale = &locale, count = smoke_count).cyan());
+            scheduler = scheduler.with_smoke_first(&plan.cases_to_run, !smoke_keep_going);
+        }
+    }
+    let (new_results, has_unexpected_failures) = run_tests(
         plan.cases_to_run,
-        jobs.unwrap_or(num_cpus::get() / 2 + 1),
-        &project_root,
-        &crate_name,
-        overall_stop_token,
+        scheduler.clone(),
+        &ctx,
+        stop.clone(),
         temp_dir_tx.clone(),
-        fast_fail_mode,
+        run_control,
+        CaseRecorder {
+            journal,
+            status: status.clone(),
+            events: events.clone(),
+            stable_output,
+            context: context.clone(),
+            today,
+        },
     )
     .await?;
+    if let Some(status) = status {
+        status.finish();
+    }
+    if stop.scheduling.is_cancelled() {
+        let note = if stop.running.is_cancelled() {
+            t!("run.stopped_aborted", locale = &locale)
+        } else {
+            t!("run.stopped_drained", locale = &locale)
+        };
+        metadata.notes.push(note.to_string());
+    }
+    if scheduler.resource_waits() > 0 {
+        let note = t!("run.resource_waits", locale = &locale, count = scheduler.resource_waits());
+        metadata.notes.push(note.to_string());
+    }
+    if scheduler.smoke_failed() && !smoke_keep_going {
+        let note = t!("run.smoke_failed_stopped", locale = &locale);
+        println!("{}", note.yellow());
+        metadata.notes.push(note.to_string());
+    }
+    // Cases kept from the interrupted run all passed, so they cannot add failures.
+    let mut final_results = resumed_results;
+    final_results.extend(new_results);
+    final_results.extend(skipped_results);
+    ctx.partial.finish();
+    control_stop_token.cancel();
+    let _ = control_handle.await;
 
     drop(temp_dir_tx);
     let _temp_dirs = collector_handle
         .await
         .context("Failed to collect temporary directories")?;
 
-    print_summary(&final_results, &locale);
+    metadata.case_costs = ctx.costs.snapshot();
+    print_summary_with_costs(
+        &final_results,
+        &metadata.case_costs,
+        sort_by,
+        &summary_columns,
+        &context,
+        today,
+    );
+    metadata.anomalies = anomaly::detect_anomalies(&final_results, anomaly_factor);
+    metadata.artifact_checksums = ctx.checksums.snapshot();
+    metadata.transient_build_retries = ctx.transient_retries.snapshot();
+    metadata.coverage = ctx.coverage.snapshot();
+    record_isolation(&ctx, &mut metadata, &locale);
+    print_anomalies(&metadata.anomalies, &locale);
+    metadata.axes = axis::summarize_by_axis(&final_results);
+    print_axis_summary(&metadata.axes, &locale);
+    metadata.failure_owners = owners::group_failures_by_owner(&final_results, &case_owners, &context, today);
+
+    let mut history = RunHistory::load(&ctx.state_dir);
+    history.record(&final_results);
+    metadata.failure_fingerprints = fingerprint::fingerprint_failures(&final_results, &history);
+    metadata.flaky_cases = flakiness::flaky_cases(&final_results, &history);
+    print_flakiness(&metadata.flaky_cases, &locale);
+    if let Err(e) = history.save(&ctx.state_dir) {
+        eprintln!("{} {}", t!("run.history_save_failed", locale = &locale).yellow(), e);
+    }
+
+    if let (Some(matrix_settings), Some(total), Some(index)) =
+        (&retry_settings, total_runners, runner_index)
+    {
+        match retry_hint::write_retry_config(
+            matrix_settings,
+            &final_results,
+            &ctx.state_dir,
+            index,
+            total,
+            &context,
+            today,
+        ) {
+            Ok(Some(hint)) => {
+                println!(
+                    "{}",
+                    t!(
+                        "run.retry_config_written",
+                        locale = &locale,
+                        count = hint.cases.len(),
+                        path = hint.config.display()
+                    )
+                    .yellow()
+                );
+                metadata.retry = Some(hint);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!(
+                "{} {}",
+                t!("run.retry_config_failed", locale = &locale).yellow(),
+                e
+            ),
+        }
+    }
+
+    let reports = Reports {
+        html: html.as_deref(),
+        json: json.as_deref(),
+        junit: junit.as_deref(),
+    };
+    write_reports(
+        &final_results,
+        &metadata,
+        &reports,
+        &html_options,
+        &ctx.state_dir,
+        &context,
+        today,
+    );
+    let output_check = if validate_output {
+        check_output(&ctx.state_dir, &locale)
+    } else {
+        Ok(())
+    };
+    if let Some(bundle_dir) = &repro_bundle_dir {
+        write_repro_bundles(&final_results, &metadata, &ctx, bundle_dir, &locale);
+    }
+    if let Some(events) = events {
+        events.send(&RunEvent::run_finished(&final_results, &context, today));
+        for plugin in events.finish() {
+            eprintln!("{}", t!("run.plugin_failed", locale = &locale, plugin = plugin).yellow());
+        }
+    }
+
+    let unexpected_failures: Vec<_> = final_results
+        .iter()
+        .filter(|r| r.is_unexpected_failure(&context, today))
+        .collect();
+    let log_dir = ctx.state_dir.join(console::LOGS_DIR);
+    print_unexpected_failure_details(&unexpected_failures, Some(&log_dir), &locale);
+    print_failures_by_owner(&metadata.failure_owners, &locale);
+
+    // A complete, successful run leaves nothing to resume.
+    if !stop.scheduling.is_cancelled() && !has_unexpected_failures {
+        resume::clear_journal(&journal_path);
+    }
+
+    // An interrupted run is incomplete, so cancellation takes precedence over failures.
+    let error = if stop.scheduling.is_cancelled() {
+        Some(MatrixError::Cancelled)
+    } else if has_unexpected_failures {
+        Some(MatrixError::UnexpectedFailures {
+            count: unexpected_failures.len(),
+        })
+    } else {
+        println!("\n{}", t!("common.all_tests_passed", locale = &locale).green().bold());
+        None
+    };
+    if stable_output {
+        println!("{}", console::stable_status_line(&final_results, error.as_ref()));
+    }
+    // Results that break the schema are a bug of the runner, reported before test failures.
+    output_check?;
+    error.map_or(Ok(()), |error| Err(error.into()))
+}
+
+/// The paths of the reports requested for a run / 一次运行所请求的报告路径
+struct Reports<'a> {
+    html: Option<&'a Path>,
+    json: Option<&'a Path>,
+    junit: Option<&'a Path>,
+}
+
+/// Writes every requested report, plus the JSON results in the state directory.
+/// A report that fails to write is reported but does not change the outcome of the run.
+fn write_reports(
+    results: &[models::TestResult],
+    metadata: &RunMetadata,
+    reports: &Reports,
+    html_options: &HtmlOptions,
+    state_dir: &Path,
+    context: &RunContext,
+    today: NaiveDate,
+) {
+    let locale = context.locale.as_str();
+    let results_path = state_dir.join(json::RESULTS_FILE);
+    if let Err(e) = generate_json_report(results, metadata, &results_path) {
+        eprintln!(
+            "{} {}",
+            t!("run.json_results_failed", locale = locale).yellow(),
+            e
+        );
+    }
+    if !results.is_empty() {
+        let archive_path = history::archived_report_path(state_dir, chrono::Utc::now());
+        if let Err(e) = generate_json_report(results, metadata, &archive_path) {
+            eprintln!(
+                "{} {}",
+                t!("run.history_archive_failed", locale = locale).yellow(),
+                e
+            );
+        }
+    }
+
+    if let Some(report_path) = reports.json {
+        println!(
+            "\n{}",
+            t!(
+                "run.json_report_generating",
+                locale = locale,
+                path = report_path.display()
+            )
+        );
+        if let Err(e) = generate_json_report(results, metadata, report_path) {
+            eprintln!(
+                "{} {}",
+                t!("run.json_report_failed", locale = locale).red(),
+                e
+            );
+        }
+    }
+
+    if let Some(report_path) = reports.junit {
+        println!(
+            "\n{}",
+            t!(
+                "run.junit_report_generating",
+                locale = locale,
+                path = report_path.display()
+            )
+        );
+        if let Err(e) = generate_junit_report(results, metadata, report_path, context, today) {
+            eprintln!(
+                "{} {}",
+                t!("run.junit_report_failed", locale = locale).red(),
+                e
+            );
+        }
+    }
 
-    if let Some(report_path) = &html {
+    if let Some(report_path) = reports.html {
         println!(
             "\n{}",
             t!(
                 "run.html_report_generating",
-                locale = &locale,
+                locale = locale,
                 path = report_path.display()
             )
         );
-        if let Err(e) = generate_html_report(&final_results, report_path, &locale) {
+        if let Err(e) = generate_html_report(results, metadata, report_path, html_options, context, today) {
             eprintln!(
                 "{} {}",
-                t!("run.html_report_failed", locale = &locale).red(),
+                t!("run.html_report_failed", locale = locale).red(),
                 e
             );
         }
     }
+}
 
-    if has_unexpected_failures {
-        let unexpected_failures: Vec<_> = final_results
-            .iter()
-            .filter(|r| r.is_unexpected_failure())
-            .collect();
-        print_unexpected_failure_details(&unexpected_failures, &locale);
-        anyhow::bail!("Matrix tests failed with unexpected errors.");
-    } else {
-        println!("\n{}", t!("common.all_tests_passed", locale = &locale).green().bold());
-        Ok(())
+/// Validates the results JSON just written against the published schema, for
+/// `--validate-output`, and prints every violation.
+///
+/// # Returns
+/// An error if the results cannot be read or do not match the schema
+fn check_output(state_dir: &Path, locale: &str) -> Result<()> {
+    let path = state_dir.join(json::RESULTS_FILE);
+    let violations = json::validate_results_file(&path)?;
+    if violations.is_empty() {
+        println!(
+            "{}",
+            t!("run.output_valid", locale = locale, path = path.display()).green()
+        );
+        return Ok(());
+    }
+    let message = t!(
+        "run.output_invalid",
+        locale = locale,
+        count = violations.len(),
+        path = path.display()
+    );
+    eprintln!("{}", message.red());
+    for violation in &violations {
+        eprintln!("  {}", violation);
+    }
+    anyhow::bail!("{}", message)
+}
+
+/// Deletes the archived results the retention policy no longer keeps. Like the reports,
+/// a failure is reported without affecting the run.
+fn prune_history(state_dir: &Path, retention: Retention, locale: &str) {
+    match history::prune_reports(state_dir, retention, chrono::Utc::now(), false) {
+        Ok(pruned) if !pruned.is_empty() => println!(
+            "{}",
+            t!("run.history_pruned", locale = locale, count = pruned.len()).dimmed()
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("{} {}", t!("run.history_prune_failed", locale = locale).yellow(), e),
     }
 }
 
-/// Sets up and parses the test matrix configuration file.
-fn setup_and_parse_config(config_path_arg: &PathBuf) -> Result<(TestMatrix, PathBuf)> {
+/// Writes a reproduction bundle for each failed case. Like the other reports, a bundle
+/// that fails to write is reported but does not change the outcome of the run.
+fn write_repro_bundles(
+    results: &[models::TestResult],
+    metadata: &RunMetadata,
+    ctx: &ExecutionContext,
+    bundle_dir: &Path,
+    locale: &str,
+) {
+    match repro::write_repro_bundles(results, metadata, &ctx.project_root, &ctx.crate_name, bundle_dir) {
+        Ok(bundles) => {
+            for bundle in bundles {
+                println!(
+                    "{}",
+                    t!("run.repro_bundle_written", locale = locale, path = bundle.display())
+                );
+            }
+        }
+        Err(e) => eprintln!(
+            "{} {}",
+            t!("run.repro_bundle_failed", locale = locale).yellow(),
+            e
+        ),
+    }
+}
+
+/// Keeps only the cases matching a `--select` expression.
+///
+/// # Returns
+/// A note describing how many cases were selected
+pub(super) fn select_cases(
+    test_matrix: &mut TestMatrix,
+    selector: &Selector,
+    state_dir: &Path,
+    locale: &str,
+) -> String {
+    let history = RunHistory::load(state_dir);
+    let total = test_matrix.cases.len();
+    test_matrix
+        .cases
+        .retain(|case| selector.matches(case, &history));
+    t!(
+        "run.selected_cases",
+        locale = locale,
+        selected = test_matrix.cases.len(),
+        total = total
+    )
+    .to_string()
+}
+
+/// Removes the cases that need network access, for `--offline-only` runs.
+///
+/// # Returns
+/// A note naming the skipped cases and their skipped results, or `None` if no case
+/// needs network access
+fn skip_network_cases(test_matrix: &mut TestMatrix, locale: &str) -> Option<(String, Vec<models::TestResult>)> {
+    let (skipped, cases): (Vec<_>, Vec<_>) = std::mem::take(&mut test_matrix.cases)
+        .into_iter()
+        .partition(|case| case.requires_network);
+    test_matrix.cases = cases;
+    if skipped.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = skipped.iter().map(|case| case.name.as_str()).collect();
+    let note = t!(
+        "run.network_cases_skipped",
+        locale = locale,
+        count = skipped.len(),
+        names = names.join(", ")
+    )
+    .to_string();
+    let reason = t!("run.network_case_skip_reason", locale = locale);
+    let results = skipped
+        .into_iter()
+        .map(|case| models::TestResult::skipped_case(case, reason.clone()))
+        .collect();
+    Some((note, results))
+}
+
+/// Applies `on_missing_component` to the cases lacking a rustup component after the
+/// toolchains were checked: either the run fails before any case starts, or these cases
+/// are dropped from `cases`.
+///
+/// # Returns
+/// A note about the skipped cases, or the error failing the run
+fn handle_missing_components(
+    cases: &mut Vec<config::TestCase>,
+    missing: &[toolchain::MissingComponents],
+    policy: MissingComponentPolicy,
+    locale: &str,
+) -> Result<Option<String>> {
+    let affected = toolchain::cases_missing_components(cases, missing);
+    if affected.is_empty() {
+        return Ok(None);
+    }
+    let listed = affected
+        .iter()
+        .map(|(name, components)| format!("{name} ({})", components.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ");
+    if policy == MissingComponentPolicy::Fail {
+        anyhow::bail!("{}", t!("run.components_missing", locale = locale, cases = listed));
+    }
+    cases.retain(|case| !affected.contains_key(&case.name));
+    Ok(Some(
+        t!(
+            "run.components_missing_skipped",
+            locale = locale,
+            count = affected.len(),
+            cases = listed
+        )
+        .to_string(),
+    ))
+}
+
+/// Reads the journal of an interrupted run for `--resume`, drops the cases that already
+/// passed from `cases` and adds a note about it to the metadata.
+///
+/// # Returns
+/// The results kept from the interrupted run
+fn resume_previous_run(
+    cases: &mut Vec<crate::core::config::TestCase>,
+    journal_path: &Path,
+    metadata: &mut RunMetadata,
+    locale: &str,
+) -> Vec<models::TestResult> {
+    let Some(journal) = resume::load_journal(journal_path) else {
+        println!(
+            "{}",
+            t!("run.nothing_to_resume", locale = locale, path = journal_path.display()).yellow()
+        );
+        return Vec::new();
+    };
+    let kept = resume::resumable_results(journal, cases);
+    cases.retain(|case| !kept.iter().any(|result| result.case_name() == case.name));
+    let note = t!(
+        "run.resumed",
+        locale = locale,
+        kept = kept.len(),
+        remaining = cases.len()
+    );
+    println!("{}", note.cyan());
+    metadata.notes.push(note.to_string());
+    kept
+}
+
+/// Resolves which matrix file to use and the project directory. Without `--config`,
+/// `TestMatrix.toml` is searched for upwards from the current directory, and its
+/// directory becomes the project directory unless `--project-dir` is given.
+pub(super) fn resolve_config_location(
+    config: Option<PathBuf>,
+    project_dir: Option<PathBuf>,
+) -> (PathBuf, PathBuf) {
+    if let Some(config) = config {
+        return (config, project_dir.unwrap_or_else(|| PathBuf::from(".")));
+    }
+
+    let discovered = env::current_dir()
+        .ok()
+        .and_then(|cwd| config::discover_config(&cwd).filter(|path| path.parent() != Some(cwd.as_path())));
+    match discovered {
+        Some(path) => {
+            println!(
+                "{}",
+                t!("run.config_discovered", path = path.display()).cyan()
+            );
+            let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            (path, project_dir.unwrap_or(root))
+        }
+        None => (
+            PathBuf::from(config::DEFAULT_CONFIG_FILE),
+            project_dir.unwrap_or_else(|| PathBuf::from(".")),
+        ),
+    }
+}
+
+/// Sets up and parses the test matrix configuration file. Unknown keys are rejected,
+/// or ignored with a warning if `lenient` is set.
+pub(super) fn setup_and_parse_config(config_path_arg: &PathBuf, lenient: bool) -> Result<(TestMatrix, PathBuf)> {
     // For config parsing, we must use the locale that has already been set in main.rs.
     let locale = rust_i18n::locale();
     let config_path = match fs::canonicalize(config_path_arg) {
@@ -202,14 +1048,37 @@ fn setup_and_parse_config(config_path_arg: &PathBuf) -> Result<(TestMatrix, Path
         }
     };
 
-    let config_matrix = config::load_test_matrix(&config_path)
+    let (config_matrix, unknown_keys) = config::load_test_matrix_with(&config_path, lenient)
         .with_context(|| t!("common.config_parse_failed", locale = &locale))?;
+    for unknown in unknown_keys {
+        let location = if unknown.location.is_empty() {
+            t!("common.config_top_level", locale = &locale).to_string()
+        } else {
+            unknown.location
+        };
+        let mut message = t!(
+            "common.config_unknown_key_ignored",
+            locale = &locale,
+            key = unknown.key,
+            location = location
+        )
+        .to_string();
+        if let Some(suggestion) = unknown.suggestion {
+            message.push(' ');
+            message.push_str(&t!("common.config_unknown_key_suggestion", locale = &locale, suggestion = suggestion));
+        }
+        println!("{}", message.yellow());
+    }
 
     Ok((config_matrix, config_path))
 }
 
-/// Prepares the environment for running tests.
-async fn prepare_environment(project_dir: &PathBuf, locale: &str) -> Result<(PathBuf, String)> {
+/// Prepares the environment for running tests. Dependencies are fetched only if `fetch` is set.
+pub(super) async fn prepare_environment(
+    project_dir: &PathBuf,
+    fetch: bool,
+    locale: &str,
+) -> Result<(PathBuf, String)> {
     let project_root = match fs::canonicalize(project_dir) {
         Ok(path) => path,
         Err(e) => {
@@ -221,91 +1090,373 @@ async fn prepare_environment(project_dir: &PathBuf, locale: &str) -> Result<(Pat
         }
     };
 
-    let fetch_status = tokio::process::Command::new("cargo")
-        .arg("fetch")
-        .current_dir(&project_root)
-        .status()
-        .await
-        .context("Failed to execute 'cargo fetch'")?;
+    if fetch {
+        let fetch_status = tokio::process::Command::new("cargo")
+            .arg("fetch")
+            .current_dir(&project_root)
+            .status()
+            .await
+            .context("Failed to execute 'cargo fetch'")?;
+
+        if !fetch_status.success() {
+            anyhow::bail!(t!("common.cargo_fetch_failed", locale = locale));
+        }
+    }
+
+    // A virtual workspace has no package of its own; cases then select one with `package`.
+    let crate_name = workspace::package_name(&project_root).await.with_context(|| {
+        t!(
+            "common.manifest_read_failed",
+            locale = locale,
+            path = project_root.join("Cargo.toml").display().to_string()
+        )
+    })?;
+
+    Ok((project_root, crate_name))
+}
+
+/// Checks that `package` is a member of the workspace, so a mistyped `--package` fails
+/// once instead of in every case. When `cargo metadata` cannot list the members, cargo
+/// reports the problem itself.
+async fn check_workspace_member(project_root: &Path, package: &str, locale: &str) -> Result<()> {
+    let Some(members) = workspace::workspace_members(project_root).await else {
+        return Ok(());
+    };
+    if !members.iter().any(|member| member.name == package) {
+        let names: Vec<&str> = members.iter().map(|member| member.name.as_str()).collect();
+        anyhow::bail!(t!(
+            "run.unknown_package",
+            locale = locale,
+            package = package,
+            members = names.join(", ")
+        ));
+    }
+    Ok(())
+}
 
-    if !fetch_status.success() {
-        anyhow::bail!(t!("common.cargo_fetch_failed", locale = locale));
+/// Warns when the directories builds are placed in are deep enough that cargo's
+/// nested output paths may exceed the Windows `MAX_PATH` limit.
+fn warn_on_long_build_paths(project_root: &Path, locale: &str) {
+    for dir in [env::temp_dir(), project_root.join("target")] {
+        if let Some(estimated) = crate::infra::fs::max_path_risk(&dir) {
+            println!(
+                "{}",
+                t!(
+                    "run.long_path_warning",
+                    locale = locale,
+                    path = dir.display(),
+                    length = estimated,
+                    limit = crate::infra::fs::WINDOWS_MAX_PATH
+                )
+                .yellow()
+            );
+        }
     }
+}
 
-    let manifest_path = project_root.join("Cargo.toml");
-    let manifest_content = match fs::read_to_string(&manifest_path) {
-        Ok(content) => content,
+/// Builds the dependency layer of the cases about to run. A failure is only a warning,
+/// the cases then build their dependencies on their own as without the layer.
+///
+/// # Returns
+/// The layer directory, or `None` if no case can use it or it failed to build
+async fn prebuild_dependencies(
+    ctx: &ExecutionContext,
+    cases: &[config::TestCase],
+    locale: &str,
+) -> Option<PathBuf> {
+    let builds = dependency_layer::layer_builds(cases, &ctx.crate_name);
+    if builds.is_empty() {
+        return None;
+    }
+    println!(
+        "{}",
+        t!(
+            "run.deps_layer_building",
+            locale = locale,
+            count = builds.len(),
+            path = ctx.state_dir.join(dependency_layer::DEPS_LAYER_DIR).display()
+        )
+        .blue()
+    );
+    match dependency_layer::build_layer(&ctx.project_root, &ctx.state_dir, &builds, locale).await {
+        Ok((layer_dir, duration)) => {
+            println!(
+                "{}",
+                t!(
+                    "run.deps_layer_ready",
+                    locale = locale,
+                    duration = format!("{:.2}", duration.as_secs_f64())
+                )
+                .green()
+            );
+            Some(layer_dir)
+        }
         Err(e) => {
-            return Err(anyhow::Error::new(e).context(t!(
-                "common.manifest_read_failed",
-                locale = locale,
-                path = manifest_path.display().to_string()
-            )));
+            eprintln!("{} {:#}", t!("run.deps_layer_failed", locale = locale).yellow(), e);
+            None
+        }
+    }
+}
+
+/// Notes in the metadata which shared build directories `--keep-going` left during the
+/// run. A discarded dependency layer is removed, so the next run builds it again.
+fn record_isolation(ctx: &ExecutionContext, metadata: &mut RunMetadata, locale: &str) {
+    let events = ctx.isolation.snapshot();
+    if let Some(name) = &events.layer_discarded_by {
+        metadata
+            .notes
+            .push(t!("run.keep_going_layer_discarded", locale = locale, name = name).to_string());
+        if let Some(layer) = &ctx.dependency_layer
+            && let Err(e) = fs::remove_dir_all(layer)
+        {
+            eprintln!("{} {}", t!("run.deps_layer_remove_failed", locale = locale).yellow(), e);
         }
+    }
+    if let Some(name) = &events.shared_target_failed_in {
+        metadata
+            .notes
+            .push(t!("run.keep_going_shared_target_left", locale = locale, name = name).to_string());
+    }
+}
+
+/// Waits until no other cargo process (e.g. a developer's `cargo build`) is building
+/// into the project's target directory, which custom commands share. Gives up after
+/// [`TARGET_LOCK_TIMEOUT`] instead of letting every case block on the lock.
+async fn wait_for_target_dir(project_root: &Path, locale: &str) -> Result<()> {
+    let target_dir = env::var_os("CARGO_TARGET_DIR")
+        .map(|dir| project_root.join(dir))
+        .unwrap_or_else(|| project_root.join("target"));
+    let Some(lock_path) = crate::infra::fs::held_cargo_lock(&target_dir) else {
+        return Ok(());
     };
-    let manifest: Manifest =
-        toml::from_str(&manifest_content).context(t!("common.manifest_parse_failed", locale = locale))?;
-    let crate_name = manifest.package.name;
+    let holder = crate::infra::fs::lock_holder(&lock_path).map_or_else(
+        || t!("run.target_lock_unknown_holder", locale = locale).to_string(),
+        |pid| t!("run.target_lock_holder", locale = locale, pid = pid).to_string(),
+    );
+    println!(
+        "{}",
+        t!(
+            "run.waiting_for_target_lock",
+            locale = locale,
+            path = lock_path.display(),
+            holder = &holder,
+            secs = TARGET_LOCK_TIMEOUT.as_secs()
+        )
+        .yellow()
+    );
+    let started = std::time::Instant::now();
+    while crate::infra::fs::held_cargo_lock(&target_dir).is_some() {
+        if started.elapsed() >= TARGET_LOCK_TIMEOUT {
+            anyhow::bail!(
+                "{}",
+                t!(
+                    "run.target_lock_timeout",
+                    locale = locale,
+                    path = lock_path.display(),
+                    holder = &holder,
+                    secs = TARGET_LOCK_TIMEOUT.as_secs()
+                )
+            );
+        }
+        tokio::time::sleep(TARGET_LOCK_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
 
-    Ok((project_root, crate_name))
+/// The tokens cancelled by Ctrl-C. The first press cancels `scheduling`: queued cases
+/// are skipped, but the running ones finish and the reports are complete. The second
+/// press also cancels `running`, aborting the cases still in progress.
+#[derive(Debug, Clone, Default)]
+struct StopTokens {
+    scheduling: CancellationToken,
+    running: CancellationToken,
 }
 
-/// Sets up a signal handler for graceful shutdown.
-fn setup_signal_handler(locale: &str) -> Result<CancellationToken> {
-    let token = CancellationToken::new();
-    let token_clone = token.clone();
+/// Sets up a signal handler for graceful shutdown: the first Ctrl-C stops scheduling,
+/// the second aborts the running cases.
+fn setup_signal_handler(locale: &str) -> Result<StopTokens> {
+    let tokens = StopTokens::default();
+    let handler_tokens = tokens.clone();
     let locale = locale.to_string();
 
     tokio::spawn(async move {
         signal::ctrl_c().await.expect("Failed to listen for Ctrl-C");
-        println!("\n{}", t!("common.shutdown_signal", locale = &locale).yellow());
-        token_clone.cancel();
+        println!("\n{}", t!("common.stop_scheduling_signal", locale = &locale).yellow());
+        handler_tokens.scheduling.cancel();
+
+        signal::ctrl_c().await.expect("Failed to listen for Ctrl-C");
+        println!("\n{}", t!("common.abort_signal", locale = &locale).yellow());
+        handler_tokens.running.cancel();
     });
 
-    Ok(token)
+    Ok(tokens)
 }
 
-/// Runs the test cases in parallel.
+/// Records the progress of each case as it happens: the journal read by `--resume`,
+/// the status file read by `matrix-runner status`, the events sent to plugins and the
+/// `--stable-output` result lines. Any of them may be missing if it could not be created
+/// or is not wanted.
+#[derive(Debug, Clone)]
+struct CaseRecorder {
+    journal: Option<RunJournal>,
+    status: Option<StatusFile>,
+    events: Option<EventStream>,
+    stable_output: bool,
+    context: RunContext,
+    today: NaiveDate,
+}
+
+impl CaseRecorder {
+    fn started(&self, name: &str) {
+        if let Some(status) = &self.status {
+            status.case_started(name);
+        }
+        if let Some(events) = &self.events {
+            events.send(&RunEvent::CaseStarted {
+                case: name.to_string(),
+            });
+        }
+    }
+
+    fn finished(&self, name: &str, result: &models::TestResult) {
+        if let Some(journal) = &self.journal
+            && let Err(e) = journal.record(result)
+        {
+            eprintln!("{} {}", t!("run.journal_write_failed", locale = &self.context.locale).yellow(), e);
+        }
+        if let Some(status) = &self.status {
+            status.case_finished(name, result);
+        }
+        if let Some(events) = &self.events {
+            events.send(&RunEvent::case_finished(name, result, &self.context, self.today));
+        }
+        if self.stable_output {
+            println!("{}", console::stable_result_line(name, result, &self.context, self.today));
+        }
+    }
+}
+
+/// Prints the resource ceiling of the run, and warns about the parts of it that cannot
+/// be measured on this platform and are therefore not enforced.
+fn announce_resource_ceiling(ceiling: &ResourceCeiling, locale: &str) {
+    let describe = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |value| value.to_string());
+    println!(
+        "{}",
+        t!(
+            "run.resource_ceiling",
+            locale = locale,
+            load = describe(ceiling.max_load),
+            memory = describe(ceiling.max_total_memory_gb)
+        )
+        .cyan()
+    );
+    let sample = system_load::sample();
+    let unmeasured = [
+        ("--max-load", ceiling.max_load.is_some() && sample.load.is_none()),
+        (
+            "--max-total-memory-gb",
+            ceiling.max_total_memory_gb.is_some() && sample.used_memory_gb.is_none(),
+        ),
+    ];
+    for (option, _) in unmeasured.into_iter().filter(|(_, unmeasured)| *unmeasured) {
+        println!(
+            "{}",
+            t!("run.resource_unmeasured", locale = locale, option = option).yellow()
+        );
+    }
+}
+
+/// Aborts the task of a running case and waits until it is dropped, so the processes of
+/// the case have been stopped or handed their grace period to shut down.
+async fn abort_case<T>(handle: tokio::task::JoinHandle<T>) {
+    handle.abort();
+    let _ = handle.await;
+}
+
+/// Runs the test cases in parallel. `control` carries the fast-fail mode and the
+/// commands received on the control channel while the run is in progress.
 async fn run_tests(
     cases_to_run: Vec<crate::core::config::TestCase>,
-    jobs: usize,
-    project_root: &PathBuf,
-    crate_name: &str,
-    overall_stop_token: CancellationToken,
+    scheduler: Scheduler,
+    ctx: &ExecutionContext,
+    stop: StopTokens,
     temp_dir_tx: mpsc::UnboundedSender<TempDir>,
-    fast_fail: bool,
+    control: RunControl,
+    recorder: CaseRecorder,
 ) -> Result<(
     Vec<models::TestResult>,
     bool,
 )> {
-    let fast_fail_token = CancellationToken::new();
-    let current_os = env::consts::OS;
+    let fast_fail_token = control.fast_fail_token();
+    // Every case is polled concurrently; the scheduler decides when each one may start.
+    let total_cases = cases_to_run.len().max(1);
 
     let stream = stream::iter(cases_to_run.into_iter().map(|case| {
         let fast_fail_token = fast_fail_token.clone();
-        let overall_stop_token = overall_stop_token.clone();
-        let project_root = project_root.clone();
-        let crate_name = crate_name.to_string();
-        let is_flaky = case.allow_failure.iter().any(|os| os == current_os);
+        let stop = stop.clone();
+        let ctx = ctx.clone();
+        let partial = ctx.partial.clone();
+        let is_flaky = case.allows_failure_on(&ctx.context.os, ctx.today);
         let temp_dir_tx = temp_dir_tx.clone();
+        let scheduler = scheduler.clone();
+        let control = control.clone();
+        let case_token = control.case_token(&case.name);
+        let recorder = recorder.clone();
+        let completion = scheduler.completion_guard(&case.name);
 
         async move {
+            // Released when this case is done, letting the cases ordered after it start.
+            let _completion = completion;
             let case_clone_for_error = case.clone();
+
+            // Cases still waiting for a slot are skipped as soon as scheduling stops or the
+            // case is cancelled. While scheduling is paused, no new case acquires a slot.
+            let permit = tokio::select! {
+                biased;
+                _ = stop.scheduling.cancelled() => None,
+                _ = fast_fail_token.cancelled() => None,
+                _ = case_token.cancelled() => None,
+                permit = async {
+                    control.wait_while_paused().await;
+                    scheduler.acquire(&case).await
+                } => Some(permit),
+            };
+            // The long tail is skipped once a failed smoke phase stops the run.
+            let Some(_permit) = permit.filter(|_| !scheduler.skips_after_smoke(&case)) else {
+                recorder.finished(&case_clone_for_error.name, &models::TestResult::skipped());
+                return (case_clone_for_error, models::TestResult::skipped());
+            };
+            recorder.started(&case_clone_for_error.name);
+            let live_log = match live_log::create(&ctx.state_dir, &case.name) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    eprintln!("{} {}", t!("run.live_log_failed", locale = &ctx.context.locale).yellow(), e);
+                    None
+                }
+            };
+
             let mut handle = tokio::spawn(async move {
-                run_test_case(case, &project_root, &crate_name, temp_dir_tx).await
+                command::with_live_log(live_log, run_test_case(case, &ctx, temp_dir_tx)).await
             });
 
             let result = tokio::select! {
                 biased;
 
-                _ = overall_stop_token.cancelled() => {
-                    handle.abort();
-                    Ok(models::TestResult::Skipped)
+                // Running cases are only aborted by the second Ctrl-C.
+                _ = stop.running.cancelled() => {
+                    abort_case(handle).await;
+                    Ok(models::TestResult::skipped())
                 }
 
                 _ = fast_fail_token.cancelled() => {
-                    handle.abort();
-                    Ok(models::TestResult::Skipped)
+                    abort_case(handle).await;
+                    Ok(models::TestResult::skipped())
+                }
+
+                _ = case_token.cancelled() => {
+                    abort_case(handle).await;
+                    println!("{}", t!("run.case_cancelled", locale = &recorder.context.locale, name = &case_clone_for_error.name).yellow());
+                    Ok(models::TestResult::skipped())
                 }
 
                 result = &mut handle => {
@@ -317,6 +1468,9 @@ async fn run_tests(
                                 output: e.to_string(),
                                 reason: FailureReason::TestFailed,
                                 duration: Duration::default(),
+                                attachments: Vec::new(),
+                                test_timings: Vec::new(),
+                                annotations: Default::default(),
                             },
                         }
                     })
@@ -330,29 +1484,38 @@ async fn run_tests(
                     output: e.to_string(),
                     reason: FailureReason::TestFailed,
                     duration: Duration::default(),
+                    attachments: Vec::new(),
+                    test_timings: Vec::new(),
+                    annotations: Default::default(),
                 }
             };
 
-            if fast_fail && !is_flaky && final_result.is_unexpected_failure() {
-                fast_fail_token.cancel();
+            partial.record(&final_result);
+            recorder.finished(&case_clone_for_error.name, &final_result);
+
+            if !is_flaky && final_result.is_unexpected_failure(&recorder.context, recorder.today) {
+                control.record_failure();
+                scheduler.record_failure(&case_clone_for_error.name);
             }
 
             (case_clone_for_error, final_result)
         }
     }))
-    .buffer_unordered(jobs)
+    .buffer_unordered(total_cases)
     .collect::<Vec<(
         crate::core::config::TestCase,
         models::TestResult,
     )>>()
     .await;
+    // Processes of timed out or cancelled cases may still be in their `kill_grace_secs`.
+    ctx.shutdowns.wait().await;
 
     // Process results and check for unexpected failures
     let mut has_unexpected_failures = false;
     let final_results: Vec<models::TestResult> = stream
         .into_iter()
         .map(|(_case, test_result)| {
-            if test_result.is_unexpected_failure() {
+            if test_result.is_unexpected_failure(&ctx.context, ctx.today) {
                 has_unexpected_failures = true;
             }
             test_result
@@ -0,0 +1,253 @@
+//! # Schedule Command Module / 调度命令模块
+//!
+//! This module implements the `schedule` command, a long-running mode for teams without a
+//! CI scheduler: it runs the matrix, one `run` process at a time, whenever a cron
+//! expression matches. Each run archives its results in the history as usual; after it,
+//! the pass rate is compared with the average of the runs before, and a clear drop is
+//! reported on the console and to the plugins listening for events.
+//!
+//! 此模块实现了 `schedule` 命令，这是为没有 CI 调度器的团队提供的长期运行模式：每当 cron
+//! 表达式匹配时，它就运行矩阵，每次一个 `run` 进程。每次运行照常将其结果归档到历史中；
+//! 运行结束后，将其通过率与之前运行的平均值进行比较，明显的下降会在控制台上报告，并发送给监听事件的插件。
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+
+use super::run::resolve_config_location;
+use crate::{
+    core::{
+        batch::ProjectOutcome,
+        error::MatrixError,
+        history,
+        plugins::{EventStream, RunEvent},
+        results::load_report,
+        schedule::{CronSchedule, detect_drift, pass_rate},
+        settings::{self, Settings},
+    },
+    infra::t,
+    reporting::console::batch_outcome_label,
+};
+
+/// The longest single wait, so a changed system clock is noticed within a minute.
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Options for the `schedule` command, as collected from the command line.
+/// `schedule` 命令的选项，从命令行收集。
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleOptions {
+    /// The cron expression of the runs / 运行的 cron 表达式
+    pub cron: String,
+    /// Path to the test matrix configuration file / 测试矩阵配置文件的路径
+    pub config: Option<PathBuf>,
+    /// Path to the project directory / 项目目录的路径
+    pub project_dir: Option<PathBuf>,
+    /// How many earlier runs the pass rate is compared with / 通过率与之比较的较早运行数量
+    pub drift_window: usize,
+    /// How many percentage points below the average count as a drop / 低于平均值多少个百分点视为下降
+    pub drift_threshold: f64,
+    /// Stop after this many runs; runs forever if `None` / 运行这么多次后停止；为 `None` 时永远运行
+    pub max_runs: Option<usize>,
+    /// Optional language code (e.g., "en", "zh-CN") / 可选的语言代码（例如 "en", "zh-CN"）
+    pub lang: Option<String>,
+    /// Extra arguments passed to every `run` / 传递给每个 `run` 的额外参数
+    pub run_args: Vec<String>,
+}
+
+/// Executes the `schedule` command.
+///
+/// # Arguments
+/// * `options` - The options collected from the command line
+///
+/// # Returns
+/// An error if the cron expression or the settings are invalid, or once Ctrl+C stops the
+/// schedule; failed runs do not stop it
+pub async fn execute(options: ScheduleOptions) -> Result<()> {
+    let ScheduleOptions {
+        cron,
+        config,
+        project_dir,
+        drift_window,
+        drift_threshold,
+        max_runs,
+        lang,
+        run_args,
+    } = options;
+    let locale = rust_i18n::locale().to_string();
+
+    let schedule: CronSchedule = cron
+        .parse()
+        .with_context(|| format!("Invalid cron expression: '{cron}'"))
+        .map_err(MatrixError::Config)?;
+    let (config, project_dir) = resolve_config_location(config, project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+    let project_root = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.clone());
+    let exe = std::env::current_exe()
+        .context("Failed to locate the matrix-runner executable")
+        .map_err(MatrixError::Environment)?;
+
+    let stop_token = CancellationToken::new();
+    let signal_token = stop_token.clone();
+    tokio::spawn(async move {
+        if signal::ctrl_c().await.is_ok() {
+            signal_token.cancel();
+        }
+    });
+
+    let mut runs = 0;
+    while max_runs.is_none_or(|max_runs| runs < max_runs) {
+        let Some(next) = schedule.next_after(&Local::now()) else {
+            println!(
+                "{}",
+                t!("schedule.never_matches", locale = &locale, cron = &cron).yellow()
+            );
+            return Ok(());
+        };
+        println!(
+            "{}",
+            t!(
+                "schedule.next_run",
+                locale = &locale,
+                time = next.format("%Y-%m-%d %H:%M")
+            )
+            .cyan()
+        );
+        // Wait in short steps, so the schedule follows the wall clock, not a monotonic timer.
+        while let Ok(remaining) = (next - Local::now()).to_std()
+            && !remaining.is_zero()
+        {
+            tokio::select! {
+                _ = stop_token.cancelled() => return Err(MatrixError::Cancelled.into()),
+                _ = tokio::time::sleep(remaining.min(MAX_WAIT)) => {}
+            }
+        }
+
+        runs += 1;
+        let started_at = chrono::Utc::now();
+        println!(
+            "\n{}",
+            t!("schedule.run_started", locale = &locale, run = runs).bold()
+        );
+        let outcome = run_matrix(&exe, &config, &project_dir, lang.as_deref(), &run_args).await;
+        println!(
+            "{}",
+            t!(
+                "schedule.run_finished",
+                locale = &locale,
+                run = runs,
+                outcome = batch_outcome_label(outcome, &locale)
+            )
+        );
+        if stop_token.is_cancelled() {
+            return Err(MatrixError::Cancelled.into());
+        }
+        check_drift(
+            &settings,
+            &project_root,
+            started_at,
+            drift_window,
+            drift_threshold,
+            &locale,
+        );
+    }
+    Ok(())
+}
+
+/// Runs `matrix-runner run` once with the scheduled matrix.
+async fn run_matrix(
+    exe: &Path,
+    config: &Path,
+    project_dir: &Path,
+    lang: Option<&str>,
+    run_args: &[String],
+) -> ProjectOutcome {
+    let mut cmd = tokio::process::Command::new(exe);
+    cmd.arg("run")
+        .arg("--config")
+        .arg(config)
+        .arg("--project-dir")
+        .arg(project_dir);
+    if let Some(lang) = lang {
+        cmd.args(["--lang", lang]);
+    }
+    cmd.args(run_args);
+    match cmd.status().await {
+        Ok(status) => ProjectOutcome::from_exit_code(status.code()),
+        Err(e) => {
+            eprintln!("{} {}", t!("schedule.spawn_failed").red(), e);
+            ProjectOutcome::Error
+        }
+    }
+}
+
+/// Compares the pass rate of the run that just finished with the archived runs before it,
+/// and reports a drop. A run that archived no results (e.g. an invalid matrix) is skipped.
+fn check_drift(
+    settings: &Settings,
+    project_root: &Path,
+    started_at: chrono::DateTime<chrono::Utc>,
+    window: usize,
+    threshold: f64,
+    locale: &str,
+) {
+    let state_dir = settings.state_dir(project_root);
+    let archived = history::archived_runs(&state_dir);
+    if archived
+        .first()
+        .is_none_or(|latest| latest.recorded_at < started_at)
+    {
+        return;
+    }
+    let rates: Vec<f64> = archived
+        .iter()
+        .take(window + 1)
+        .filter_map(|run| load_report(&run.path).ok())
+        .filter_map(|report| pass_rate(&report.results))
+        .collect();
+    let Some(drift) = detect_drift(&rates, window, threshold) else {
+        return;
+    };
+
+    println!(
+        "{}",
+        t!(
+            "schedule.drift",
+            locale = locale,
+            rate = format!("{:.1}", drift.pass_rate),
+            average = format!("{:.1}", drift.trailing_average),
+            runs = drift.runs
+        )
+        .red()
+        .bold()
+    );
+    if settings.plugins.is_empty() {
+        return;
+    }
+    let (events, failures) = EventStream::start(&settings.plugins);
+    for (plugin, e) in failures {
+        eprintln!(
+            "{} {}",
+            t!("run.plugin_start_failed", locale = locale, plugin = plugin).yellow(),
+            e
+        );
+    }
+    events.send(&RunEvent::PassRateDropped {
+        project_root: project_root.to_path_buf(),
+        pass_rate: drift.pass_rate,
+        trailing_average: drift.trailing_average,
+        runs: drift.runs,
+    });
+    for plugin in events.finish() {
+        eprintln!(
+            "{}",
+            t!("run.plugin_failed", locale = locale, plugin = plugin).yellow()
+        );
+    }
+}
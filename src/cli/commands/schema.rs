@@ -0,0 +1,36 @@
+//! # Schema Command Module / 模式命令模块
+//!
+//! This module implements the `schema` command, which prints the JSON Schema of the
+//! results document (see [`crate::reporting::json::results_schema`]), so downstream tools
+//! can validate against, or generate code from, the exact format this version writes.
+//!
+//! 此模块实现了 `schema` 命令，它打印结果文档的 JSON Schema（参见
+//! [`crate::reporting::json::results_schema`]），使下游工具可以根据此版本写出的确切格式进行验证或生成代码。
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{core::error::MatrixError, infra::t, reporting::json::results_schema};
+
+/// Executes the `schema` command.
+///
+/// # Arguments
+/// * `output` - Where to write the schema; printed to stdout if `None`
+///
+/// # Returns
+/// An error if the schema cannot be written
+pub fn execute(output: Option<PathBuf>) -> Result<()> {
+    let schema = serde_json::to_string_pretty(&results_schema())
+        .context("Failed to serialize the schema")?;
+    match output {
+        Some(path) => {
+            fs::write(&path, format!("{schema}\n"))
+                .with_context(|| format!("Failed to write {}", path.display()))
+                .map_err(MatrixError::Environment)?;
+            println!("{}", t!("schema.written", path = path.display()));
+        }
+        None => println!("{schema}"),
+    }
+    Ok(())
+}
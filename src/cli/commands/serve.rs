@@ -0,0 +1,73 @@
+//! # Serve Command Module / 服务命令模块
+//!
+//! This module implements the `serve` command, which runs the matrix like `run` while
+//! serving a live dashboard of the run on a local port: the status of every case is shown
+//! in the browser as it changes, which is easier to follow than the console of a long run.
+//! Once the run is over, the dashboard keeps showing its outcome until Ctrl+C.
+//!
+//! 此模块实现了 `serve` 命令，它像 `run` 一样运行矩阵，同时在本地端口上提供该运行的实时仪表盘：
+//! 每个用例的状态一旦变化就会显示在浏览器中，比长时间运行的控制台更容易跟踪。
+//! 运行结束后，仪表盘会继续显示其结果，直到按下 Ctrl+C。
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+
+use super::run::{self, RunOptions};
+use crate::{
+    core::{error::MatrixError, plugins::EventSink},
+    infra::t,
+    reporting::dashboard::Dashboard,
+};
+
+/// Executes the `serve` command.
+///
+/// # Arguments
+/// * `port` - The local port of the dashboard; `0` picks a free one
+/// * `options` - The options of the run, as for `run`
+///
+/// # Returns
+/// The outcome of the run, or an error if the port cannot be listened on
+pub async fn execute(port: u16, mut options: RunOptions) -> Result<()> {
+    let locale = rust_i18n::locale().to_string();
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .with_context(|| format!("Failed to listen on port {port}"))
+        .map_err(MatrixError::Environment)?;
+    let url = format!("http://{}", listener.local_addr()?);
+    println!(
+        "{}",
+        t!("serve.listening", locale = &locale, url = &url)
+            .green()
+            .bold()
+    );
+
+    let dashboard = Arc::new(Dashboard::new(locale.clone()));
+    let stop = CancellationToken::new();
+    let server = tokio::spawn(dashboard.clone().serve(listener, stop.clone()));
+    options.event_sink = Some(dashboard.clone());
+    let result = run::execute(options).await;
+    // A run stopped before its first event never closes the dashboard itself.
+    dashboard.close();
+
+    let cancelled = result
+        .as_ref()
+        .err()
+        .and_then(|e| e.downcast_ref::<MatrixError>())
+        .is_some_and(|e| matches!(e, MatrixError::Cancelled));
+    if !cancelled {
+        let locale = rust_i18n::locale().to_string();
+        println!(
+            "{}",
+            t!("serve.finished", locale = &locale, url = &url).cyan()
+        );
+        let _ = signal::ctrl_c().await;
+    }
+    stop.cancel();
+    let _ = server.await;
+    result
+}
@@ -0,0 +1,112 @@
+//! # Status Command Module / 状态命令模块
+//!
+//! This module implements the `status` command, which reads the status file written by
+//! a run in progress and prints its progress: the running cases, how many cases have
+//! finished and an estimate of the remaining time.
+//!
+//! 此模块实现了 `status` 命令，它读取正在进行的运行所写入的状态文件并打印其进度：
+//! 正在运行的用例、已完成的用例数量以及剩余时间的估计。
+
+use anyhow::Result;
+use chrono::Utc;
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{
+    core::{
+        config,
+        error::MatrixError,
+        settings,
+        status::{load_status, process_alive, status_path},
+    },
+    infra::t,
+};
+
+/// Executes the `status` command.
+///
+/// # Arguments
+/// * `project_dir` - The project whose run to inspect; defaults to the directory of the nearest `TestMatrix.toml`, or the current directory
+///
+/// # Returns
+/// An error only if the project settings cannot be read; "no run in progress" is not an error
+pub fn execute(project_dir: Option<PathBuf>) -> Result<()> {
+    let project_dir = project_dir.unwrap_or_else(discover_project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+    let project_root = project_dir.canonicalize().unwrap_or(project_dir);
+    let state_dir = settings.state_dir(&project_root);
+    let path = status_path(&state_dir);
+
+    let Some(status) = load_status(&state_dir) else {
+        println!("{}", t!("status.no_run", path = project_root.display()));
+        return Ok(());
+    };
+    if !process_alive(status.pid) {
+        println!(
+            "{}",
+            t!("status.stale", path = path.display(), pid = status.pid).yellow()
+        );
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let elapsed = (now - status.started_at).to_std().unwrap_or_default();
+    println!(
+        "{}",
+        t!(
+            "status.header",
+            pid = status.pid,
+            elapsed = format_elapsed(elapsed)
+        )
+        .bold()
+    );
+    println!(
+        "{}",
+        t!(
+            "status.progress",
+            completed = status.completed(),
+            total = status.total,
+            passed = status.passed,
+            failed = status.failed,
+            skipped = status.skipped
+        )
+    );
+    if status.resumed > 0 {
+        println!("{}", t!("status.resumed", count = status.resumed).dimmed());
+    }
+
+    println!("{}", t!("status.running", count = status.running.len()));
+    for (name, started_at) in &status.running {
+        let running_for = (now - *started_at).to_std().unwrap_or_default();
+        println!("  - {:<40} {}", name, format_elapsed(running_for).dimmed());
+    }
+
+    match status.eta(now) {
+        Some(eta) => println!("{}", t!("status.eta", eta = format_elapsed(eta)).cyan()),
+        None => println!("{}", t!("status.eta_unknown").dimmed()),
+    }
+    Ok(())
+}
+
+/// The directory of the nearest `TestMatrix.toml`, or the current directory if there is none.
+pub(super) fn discover_project_dir() -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| config::discover_config(&cwd))
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Formats a duration as hours, minutes and seconds, e.g. "1h 02m 03s" or "4m 05s".
+fn format_elapsed(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m {seconds:02}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
@@ -0,0 +1,90 @@
+//! # Tail Command Module / 跟踪命令模块
+//!
+//! This module implements the `tail` command, which follows the live log of one case of
+//! a run in progress and prints its output as it is produced, until the case finishes.
+//! It waits for a case that has not started yet, and prints the log of a finished run.
+//!
+//! 此模块实现了 `tail` 命令，它跟踪正在进行的运行中某个用例的实时日志，并在输出产生时将其打印出来，
+//! 直到该用例完成。对于尚未开始的用例，它会等待其开始；对于已结束的运行，则打印其日志。
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::status::discover_project_dir;
+use crate::{
+    core::{
+        error::MatrixError,
+        live_log::{LogFollower, live_log_path},
+        settings,
+        status::{load_status, process_alive},
+    },
+    infra::t,
+};
+
+/// How often the live log and the status file are checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Executes the `tail` command.
+///
+/// # Arguments
+/// * `case` - The name of the case to follow
+/// * `project_dir` - The project whose run to follow; defaults to the directory of the nearest `TestMatrix.toml`, or the current directory
+///
+/// # Returns
+/// An error if the project settings or the live log cannot be read; a case without a log is not an error
+pub async fn execute(case: String, project_dir: Option<PathBuf>) -> Result<()> {
+    let project_dir = project_dir.unwrap_or_else(discover_project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+    let project_root = project_dir.canonicalize().unwrap_or(project_dir);
+    let state_dir = settings.state_dir(&project_root);
+    let path = live_log_path(&state_dir, &case);
+    let mut follower = LogFollower::new(path.clone());
+    let mut waiting = false;
+
+    loop {
+        let run_in_progress = load_status(&state_dir).filter(|status| process_alive(status.pid));
+        // The case is over once its log exists but it is no longer running, or the run is.
+        let done = match &run_in_progress {
+            Some(status) => follower.exists() && !status.running.contains_key(&case),
+            None => true,
+        };
+        // Read after checking, so the lines written before the case finished are printed.
+        print_new(&mut follower, &path)?;
+        if done {
+            break;
+        }
+        if !follower.exists() && !waiting {
+            println!("{}", t!("tail.waiting", case = &case).dimmed());
+            waiting = true;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    if follower.exists() {
+        println!("{}", t!("tail.finished", case = &case).dimmed());
+    } else if waiting {
+        println!("{}", t!("tail.run_ended", case = &case).yellow());
+    } else {
+        println!(
+            "{}",
+            t!("tail.no_log", case = &case, path = project_root.display())
+        );
+    }
+    Ok(())
+}
+
+/// Prints what was appended to the live log since the last call.
+fn print_new(follower: &mut LogFollower, path: &std::path::Path) -> Result<()> {
+    let content = follower
+        .read_new()
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if !content.is_empty() {
+        print!("{content}");
+        let _ = std::io::stdout().flush();
+    }
+    Ok(())
+}
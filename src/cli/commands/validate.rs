@@ -0,0 +1,90 @@
+//! # Validate Command Module / 验证命令模块
+//!
+//! This module implements the `validate` command, which loads the test matrix with all
+//! the checks of a run, without building anything, and warns about `allow_failure`
+//! entries that have expired or expire soon.
+//!
+//! 此模块实现了 `validate` 命令，它以运行时的全部检查加载测试矩阵但不构建任何内容，
+//! 并对已到期或即将到期的 `allow_failure` 条目发出警告。
+
+use anyhow::Result;
+use colored::*;
+use std::path::PathBuf;
+
+use super::run::{resolve_config_location, setup_and_parse_config};
+use crate::{
+    core::{error::MatrixError, settings},
+    infra::t,
+};
+
+/// The number of days before its expiry that an `allow_failure` entry is warned about.
+/// `allow_failure` 条目在到期前多少天开始发出警告。
+pub const EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// Executes the `validate` command.
+///
+/// # Arguments
+/// * `config` - Path to the test matrix configuration file; discovered from the current directory if `None`
+/// * `project_dir` - Path to the project directory; defaults to the discovered config's directory
+/// * `lenient` - Ignore unknown keys of the matrix file, with a warning, instead of rejecting them
+/// * `lang` - Optional language code (e.g., "en", "zh-CN")
+///
+/// # Returns
+/// An error if the matrix cannot be loaded; expiring entries are only warnings
+pub fn execute(
+    config: Option<PathBuf>,
+    project_dir: Option<PathBuf>,
+    lenient: bool,
+    lang: Option<String>,
+) -> Result<()> {
+    let (config, project_dir) = resolve_config_location(config, project_dir);
+    let settings = settings::load_settings(&project_dir).map_err(MatrixError::Config)?;
+    settings.apply_color();
+
+    let (test_matrix, config_path) =
+        setup_and_parse_config(&config, lenient).map_err(MatrixError::Config)?;
+    if let Some(language) = settings.language(lang) {
+        rust_i18n::set_locale(&language);
+    } else if test_matrix.language != "en" {
+        rust_i18n::set_locale(&test_matrix.language);
+    }
+    println!(
+        "{}",
+        t!(
+            "validate.valid",
+            path = config_path.display(),
+            count = test_matrix.cases.len()
+        )
+        .green()
+    );
+
+    let today = chrono::Local::now().date_naive();
+    for expiring in test_matrix.expiring_allowances(today, EXPIRY_WARNING_DAYS) {
+        let until = expiring
+            .entry
+            .until
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        let message = if expiring.days_left < 0 {
+            t!(
+                "validate.allowance_expired",
+                case = expiring.case,
+                os = expiring.entry.os,
+                until = until
+            )
+        } else {
+            t!(
+                "validate.allowance_expiring",
+                case = expiring.case,
+                os = expiring.entry.os,
+                until = until,
+                days = expiring.days_left
+            )
+        };
+        println!("{}", message.yellow());
+        if let Some(issue) = &expiring.entry.issue {
+            println!("  {}", t!("validate.allowance_issue", issue = issue));
+        }
+    }
+    Ok(())
+}
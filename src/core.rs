@@ -7,11 +7,41 @@
 //! 包括数据模型、配置和测试执行逻辑。
 
 pub mod models;
+pub mod error;
 pub mod config;
+pub mod generator;
+pub mod exec_plan;
+pub mod context;
 pub mod execution;
+pub mod fixtures;
 pub mod planner;
+pub mod explain;
+pub mod scheduler;
+pub mod history;
+pub mod fingerprint;
+pub mod flakiness;
+pub mod resume;
+pub mod retry_hint;
+pub mod status;
+pub mod selector;
+pub mod settings;
+pub mod anomaly;
+pub mod axis;
+pub mod owners;
+pub mod batch;
+pub mod plugins;
+pub mod results;
+pub mod costs;
+pub mod columns;
+pub mod builder;
+pub mod dependency_layer;
+pub mod live_log;
+pub mod schedule;
+pub mod feature_compare;
+pub mod advise;
 
 // Re-exports
 pub use models::TestResult;
 pub use config::TestMatrix;
-pub use execution::run_test_case; 
\ No newline at end of file
+pub use execution::run_test_case;
+pub use error::MatrixError; 
\ No newline at end of file
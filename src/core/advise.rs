@@ -0,0 +1,289 @@
+//! # Matrix Advisor Module / 矩阵建议模块
+//!
+//! This module compares the cases of a matrix with what is known about the package they
+//! test — its features, its `rust-version`, how docs.rs builds it, the targets CI builds
+//! for — and suggests the cases that are missing, such as a feature never tested without
+//! the default features. It keeps the matrix meaningful as the crate grows new features
+//! and targets. Each suggestion comes with a case that would close the gap.
+//!
+//! 此模块将矩阵中的用例与其所测试的包的已知信息（其 features、`rust-version`、docs.rs 如何构建它、
+//! CI 为哪些目标构建）进行比较，并建议缺失的用例，例如某个 feature 从未在不启用默认 features
+//! 的情况下被测试。它使矩阵在 crate 增加新的 features 和目标时仍保持有意义。
+//! 每条建议都附带一个可以弥补该缺口的用例。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::config::{CaseKind, TestCase};
+use crate::core::context::RunContext;
+use crate::infra::workspace::DocsRsMetadata;
+
+/// The feature enabled with the default features / 随默认 features 启用的 feature
+const DEFAULT_FEATURE: &str = "default";
+
+/// What is known about the package the matrix tests.
+/// 关于矩阵所测试的包的已知信息。
+#[derive(Debug, Clone, Default)]
+pub struct ProjectFacts {
+    /// The `[features]` table: each feature and what it enables / `[features]` 表：每个 feature 及其启用的内容
+    pub features: BTreeMap<String, Vec<String>>,
+    /// The minimum supported Rust version (`rust-version`) / 最低支持的 Rust 版本（`rust-version`）
+    pub rust_version: Option<String>,
+    /// How docs.rs builds the documentation / docs.rs 如何构建文档
+    pub docs_rs: Option<DocsRsMetadata>,
+    /// The target triples the CI configuration builds for / CI 配置所构建的目标三元组
+    pub ci_targets: BTreeSet<String>,
+}
+
+/// A gap in the coverage of the matrix.
+/// 矩阵覆盖中的一个缺口。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Advice {
+    /// No case enables the feature / 没有用例启用该 feature
+    FeatureNeverEnabled { feature: String },
+    /// The feature is only tested along with the default features / 该 feature 只与默认 features 一起被测试
+    FeatureNeverWithoutDefaults { feature: String },
+    /// No case builds without the default features / 没有用例在不启用默认 features 的情况下构建
+    NoDefaultFeaturesUntested,
+    /// No case enables all features at once / 没有用例同时启用所有 features
+    AllFeaturesUntested,
+    /// No case uses the minimum supported Rust version / 没有用例使用最低支持的 Rust 版本
+    MsrvUntested { version: String },
+    /// No `doc` case builds the documentation with the features docs.rs uses / 没有 `doc` 用例使用 docs.rs 所用的 features 构建文档
+    DocsRsUncovered,
+    /// No case builds for a target docs.rs documents / 没有用例为 docs.rs 生成文档的目标构建
+    DocsRsTargetUncovered { target: String },
+    /// No case builds for a target the CI configuration builds for / 没有用例为 CI 配置所构建的目标构建
+    CiTargetUncovered { target: String },
+}
+
+/// A gap in the coverage of the matrix and a case closing it.
+/// 矩阵覆盖中的一个缺口，以及弥补它的用例。
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// The gap / 缺口
+    pub advice: Advice,
+    /// A case that would close it / 可以弥补它的用例
+    pub case: TestCase,
+}
+
+/// Suggests the cases missing from `cases` for the package described by `facts`.
+///
+/// Features whose name starts with `_` are taken as internal and are not asked for. A
+/// case without a `target` covers the targets the platform of `context` can run.
+///
+/// # Returns
+/// The suggestions, the feature gaps first, then the toolchain, docs.rs and CI ones
+///
+/// 为 `facts` 所描述的包建议 `cases` 中缺失的用例。名称以 `_` 开头的 feature 被视为内部 feature，不会被要求测试。
+/// 没有 `target` 的用例覆盖 `context` 平台可以运行的目标。返回建议，先是 feature 的缺口，然后是工具链、docs.rs 和 CI 的缺口。
+pub fn advise(cases: &[TestCase], facts: &ProjectFacts, context: &RunContext) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    let public: Vec<&str> = facts
+        .features
+        .keys()
+        .map(String::as_str)
+        .filter(|feature| *feature != DEFAULT_FEATURE && !feature.starts_with('_'))
+        .collect();
+    let defaults = enabled_features(&facts.features, &[DEFAULT_FEATURE]);
+    let enabled: Vec<BTreeSet<&str>> = cases
+        .iter()
+        .map(|case| case_features(&facts.features, case))
+        .collect();
+    let any_without_defaults = cases.iter().any(|case| case.no_default_features);
+
+    for feature in &public {
+        let enabling: Vec<&TestCase> = cases
+            .iter()
+            .zip(&enabled)
+            .filter(|(_, features)| features.contains(feature))
+            .map(|(case, _)| case)
+            .collect();
+        if enabling.is_empty() {
+            suggestions.push(Suggestion {
+                advice: Advice::FeatureNeverEnabled {
+                    feature: feature.to_string(),
+                },
+                case: suggested(feature, feature, false),
+            });
+        } else if any_without_defaults
+            && !defaults.contains(feature)
+            && !enabling.iter().any(|case| case.no_default_features)
+        {
+            suggestions.push(Suggestion {
+                advice: Advice::FeatureNeverWithoutDefaults {
+                    feature: feature.to_string(),
+                },
+                case: suggested(&format!("{feature}-no-default"), feature, true),
+            });
+        }
+    }
+    if !defaults.is_empty() && !any_without_defaults {
+        suggestions.push(Suggestion {
+            advice: Advice::NoDefaultFeaturesUntested,
+            case: suggested("no-default-features", "", true),
+        });
+    }
+    if public.len() > 1
+        && !enabled
+            .iter()
+            .any(|features| public.iter().all(|f| features.contains(f)))
+    {
+        suggestions.push(Suggestion {
+            advice: Advice::AllFeaturesUntested,
+            case: suggested("all-features", &public.join(","), false),
+        });
+    }
+
+    if let Some(version) = &facts.rust_version
+        && !cases.iter().any(|case| {
+            case.toolchain
+                .as_deref()
+                .is_some_and(|toolchain| same_version(toolchain, version))
+        })
+    {
+        suggestions.push(Suggestion {
+            advice: Advice::MsrvUntested {
+                version: version.clone(),
+            },
+            case: TestCase {
+                toolchain: Some(version.clone()),
+                ..suggested("msrv", "", false)
+            },
+        });
+    }
+
+    if let Some(docs_rs) = &facts.docs_rs {
+        let features = if docs_rs.all_features {
+            public.join(",")
+        } else {
+            docs_rs.features.join(",")
+        };
+        let docs_case = TestCase {
+            kind: CaseKind::Doc,
+            ..suggested("docs-rs", &features, docs_rs.no_default_features)
+        };
+        let wanted = case_features(&facts.features, &docs_case);
+        let covered = cases
+            .iter()
+            .zip(&enabled)
+            .any(|(case, features)| case.kind == CaseKind::Doc && *features == wanted);
+        if !covered {
+            suggestions.push(Suggestion {
+                advice: Advice::DocsRsUncovered,
+                case: docs_case.clone(),
+            });
+        }
+        let targets: BTreeSet<&String> = docs_rs
+            .targets
+            .iter()
+            .chain(&docs_rs.default_target)
+            .collect();
+        for target in targets {
+            if !covers_target(cases, target, context) {
+                suggestions.push(Suggestion {
+                    advice: Advice::DocsRsTargetUncovered {
+                        target: target.clone(),
+                    },
+                    case: TestCase {
+                        name: format!("docs-rs-{target}"),
+                        target: Some(target.clone()),
+                        ..docs_case.clone()
+                    },
+                });
+            }
+        }
+    }
+
+    for target in &facts.ci_targets {
+        if !covers_target(cases, target, context) {
+            suggestions.push(Suggestion {
+                advice: Advice::CiTargetUncovered {
+                    target: target.clone(),
+                },
+                case: TestCase {
+                    target: Some(target.clone()),
+                    ..suggested(target, "", false)
+                },
+            });
+        }
+    }
+    suggestions
+}
+
+fn suggested(name: &str, features: &str, no_default_features: bool) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        features: features.to_string(),
+        no_default_features,
+        ..Default::default()
+    }
+}
+
+/// The features a case ends up with: those it lists, the default ones unless it turns
+/// them off, and everything they enable in turn.
+fn case_features<'a>(
+    table: &'a BTreeMap<String, Vec<String>>,
+    case: &'a TestCase,
+) -> BTreeSet<&'a str> {
+    let mut roots: Vec<&str> = case
+        .features
+        .split(',')
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .collect();
+    if !case.no_default_features {
+        roots.push(DEFAULT_FEATURE);
+    }
+    enabled_features(table, &roots)
+}
+
+/// The features enabled by `roots`, following the `[features]` table. `dep:` entries
+/// name dependencies, not features; `dep/feature` entries enable the feature of the
+/// same name as the dependency, if there is one.
+fn enabled_features<'a>(
+    table: &'a BTreeMap<String, Vec<String>>,
+    roots: &[&'a str],
+) -> BTreeSet<&'a str> {
+    let mut enabled = BTreeSet::new();
+    let mut pending = roots.to_vec();
+    while let Some(feature) = pending.pop() {
+        if !enabled.insert(feature) {
+            continue;
+        }
+        for entry in table.get(feature).into_iter().flatten() {
+            if entry.starts_with("dep:") {
+                continue;
+            }
+            let name = entry
+                .split('/')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('?');
+            if let Some((key, _)) = table.get_key_value(name) {
+                pending.push(key);
+            }
+        }
+    }
+    enabled.remove(DEFAULT_FEATURE);
+    enabled
+}
+
+/// Whether a case builds for `target`: one setting it, or one without a target on a
+/// platform running it.
+fn covers_target(cases: &[TestCase], target: &str, context: &RunContext) -> bool {
+    cases.iter().any(|case| match &case.target {
+        Some(case_target) => case_target == target,
+        None => context.can_run(target),
+    })
+}
+
+/// Whether a toolchain names the Rust version, e.g. `1.70` for `rust-version = "1.70.0"`.
+fn same_version(toolchain: &str, version: &str) -> bool {
+    let normalize = |v: &str| {
+        v.strip_suffix(".0")
+            .filter(|v| v.matches('.').count() == 1)
+            .unwrap_or(v)
+            .to_string()
+    };
+    normalize(toolchain) == normalize(version)
+}
@@ -0,0 +1,64 @@
+//! # Duration Anomaly Module / 耗时异常模块
+//!
+//! This module compares each case's duration with its declared `expected_secs` and
+//! flags the ones that deviate by more than the matrix's `anomaly_factor`. A passing
+//! case that suddenly takes ten times longer is an early warning of a performance
+//! cliff introduced by a feature combination.
+//!
+//! 此模块将每个用例的耗时与其声明的 `expected_secs` 进行比较，
+//! 并标记偏差超过矩阵 `anomaly_factor` 的用例。一个突然耗时十倍的通过用例
+//! 是某个 feature 组合引入性能断崖的早期预警。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::models::TestResult;
+
+/// A case whose duration deviated from its expectation.
+/// 耗时偏离预期的用例。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DurationAnomaly {
+    /// The name of the case / 用例名称
+    pub case: String,
+    /// The declared expected duration in seconds / 声明的预期耗时（秒）
+    pub expected_secs: u64,
+    /// The actual duration in seconds / 实际耗时（秒）
+    pub actual_secs: f64,
+    /// `actual / expected`; above 1 means slower than expected / `实际 / 预期`；大于 1 表示比预期慢
+    pub ratio: f64,
+}
+
+/// Finds the cases whose duration deviates from `expected_secs` by more than `factor`
+/// in either direction. Skipped cases and cases without an expectation are ignored.
+///
+/// # Arguments
+/// * `results` - The results of the run
+/// * `factor` - The allowed deviation ratio (e.g., 3.0 for "3x slower or faster")
+///
+/// # Returns
+/// The anomalies, in the order of the results
+///
+/// 查找耗时在任一方向上偏离 `expected_secs` 超过 `factor` 的用例。
+/// 跳过的用例和没有预期的用例会被忽略。
+pub fn detect_anomalies(results: &[TestResult], factor: f64) -> Vec<DurationAnomaly> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let (TestResult::Passed { case, duration, .. }
+            | TestResult::Failed { case, duration, .. }
+            | TestResult::Built { case, duration, .. }) = result
+            else {
+                return None;
+            };
+            let expected_secs = case.expected_secs.filter(|&secs| secs > 0)?;
+            let actual_secs = duration.as_secs_f64();
+            let ratio = actual_secs / expected_secs as f64;
+            (ratio > factor || ratio * factor < 1.0).then(|| DurationAnomaly {
+                case: case.name.clone(),
+                expected_secs,
+                actual_secs,
+                ratio,
+            })
+        })
+        .collect()
+}
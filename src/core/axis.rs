@@ -0,0 +1,140 @@
+//! # Axis Summary Module / 维度汇总模块
+//!
+//! This module aggregates results per value of a matrix axis (each feature, toolchain,
+//! target and package), so that a pattern like "everything with `simd` fails" is
+//! visible at a glance instead of having to be read out of dozens of rows.
+//!
+//! 此模块按矩阵维度的每个取值（每个 feature、工具链、目标和包）汇总结果，
+//! 使诸如“所有带 `simd` 的用例都失败”的规律一目了然，而不必从几十行结果中读出。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::config::TestCase;
+use crate::core::models::TestResult;
+
+/// A dimension cases vary along.
+/// 用例变化所沿的维度。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    /// A single enabled feature / 单个启用的 feature
+    Feature,
+    /// The rustup toolchain / rustup 工具链
+    Toolchain,
+    /// The target triple / 目标三元组
+    Target,
+    /// The workspace package / 工作区包
+    Package,
+}
+
+impl Axis {
+    /// The values a case has on this axis. A case can have several features.
+    /// 用例在此维度上的取值。一个用例可以有多个 feature。
+    fn values(self, case: &TestCase) -> Vec<String> {
+        match self {
+            Axis::Feature => case
+                .features
+                .split(',')
+                .map(str::trim)
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Axis::Toolchain => vec![
+                case.toolchain
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string()),
+            ],
+            Axis::Target => vec![case.target.clone().unwrap_or_else(|| "host".to_string())],
+            Axis::Package => vec![
+                case.package
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string()),
+            ],
+        }
+    }
+}
+
+/// The results of the cases sharing one value of an axis.
+/// 共享某一维度取值的用例的结果。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AxisValueSummary {
+    /// The axis / 维度
+    pub axis: Axis,
+    /// The value on the axis (e.g. a feature name) / 维度上的取值（例如 feature 名称）
+    pub value: String,
+    /// Cases that passed or were built / 通过或已构建的用例
+    pub passed: usize,
+    /// Cases that failed, including allowed failures / 失败的用例，包括允许的失败
+    pub failed: usize,
+}
+
+impl AxisValueSummary {
+    /// The number of cases with this value / 具有此取值的用例数量
+    pub fn total(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    /// The share of passed cases, between 0 and 1 / 通过用例的比例，介于 0 和 1 之间
+    pub fn pass_rate(&self) -> f64 {
+        if self.total() == 0 {
+            return 1.0;
+        }
+        self.passed as f64 / self.total() as f64
+    }
+}
+
+/// Aggregates results per axis value.
+///
+/// An axis is only included if it tells cases apart, i.e. some value does not cover
+/// every case. Within an axis, the values with the lowest pass rate come first.
+/// Skipped results are ignored.
+///
+/// # Returns
+/// The summaries, grouped by axis
+///
+/// 按维度取值汇总结果。仅当某个维度能区分用例时（即某个取值未覆盖所有用例）才会包含该维度。
+/// 在每个维度内，通过率最低的取值排在最前。跳过的结果会被忽略。
+pub fn summarize_by_axis(results: &[TestResult]) -> Vec<AxisValueSummary> {
+    let outcomes: Vec<(&TestCase, bool)> = results
+        .iter()
+        .filter_map(|result| match result {
+            TestResult::Passed { case, .. } | TestResult::Built { case, .. } => Some((case, true)),
+            TestResult::Failed { case, .. } => Some((case, false)),
+            TestResult::Skipped { .. } => None,
+        })
+        .collect();
+
+    let mut summaries = Vec::new();
+    for axis in [Axis::Feature, Axis::Toolchain, Axis::Target, Axis::Package] {
+        let mut values: BTreeMap<String, AxisValueSummary> = BTreeMap::new();
+        for (case, passed) in &outcomes {
+            for value in axis.values(case) {
+                let summary = values.entry(value.clone()).or_insert(AxisValueSummary {
+                    axis,
+                    value,
+                    passed: 0,
+                    failed: 0,
+                });
+                if *passed {
+                    summary.passed += 1;
+                } else {
+                    summary.failed += 1;
+                }
+            }
+        }
+        if values
+            .values()
+            .all(|summary| summary.total() == outcomes.len())
+        {
+            continue;
+        }
+        let mut values: Vec<_> = values.into_values().collect();
+        values.sort_by(|a, b| a.pass_rate().total_cmp(&b.pass_rate()));
+        summaries.extend(values);
+    }
+    summaries
+}
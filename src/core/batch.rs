@@ -0,0 +1,121 @@
+//! # Batch Module / 批处理模块
+//!
+//! This module defines the manifest read by `matrix-runner batch`, which lists the
+//! matrices of many projects, and the outcome of each project's run, so an organization
+//! maintaining a fleet of small crates can test all of them with one command.
+//!
+//! 此模块定义由 `matrix-runner batch` 读取的清单（列出多个项目的矩阵）以及每个项目运行的结果，
+//! 使维护大量小型 crate 的组织可以用一条命令测试所有 crate。
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::error::MatrixError;
+
+/// A project to run in a batch.
+/// 批处理中要运行的一个项目。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchEntry {
+    /// The test matrix configuration file / 测试矩阵配置文件
+    pub config: PathBuf,
+    /// The project directory; defaults to the configuration file's directory / 项目目录；默认为配置文件所在的目录
+    pub project_dir: PathBuf,
+}
+
+/// Parses a batch manifest.
+///
+/// Each non-empty line holds a configuration path, optionally followed by a project
+/// directory, separated by whitespace (quote paths containing spaces). Lines starting
+/// with `#` are comments. Relative paths are resolved against `base_dir`.
+///
+/// # Arguments
+/// * `content` - The manifest content
+/// * `base_dir` - The directory relative paths are resolved against
+///
+/// # Returns
+/// The entries in manifest order, or an error naming the first malformed line
+///
+/// 解析批处理清单。每个非空行包含一个配置路径，后面可选地跟一个项目目录，以空白分隔
+/// （包含空格的路径需加引号）。以 `#` 开头的行是注释。相对路径基于 `base_dir` 解析。
+pub fn parse_manifest(content: &str, base_dir: &Path) -> Result<Vec<BatchEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields = shlex::split(line)
+            .ok_or_else(|| anyhow!("Line {}: unbalanced quotes in '{}'", index + 1, line))?;
+        let (config, project_dir) = match fields.as_slice() {
+            [config] => {
+                let config = base_dir.join(config);
+                let project_dir = config
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|| base_dir.to_path_buf());
+                (config, project_dir)
+            }
+            [config, project_dir] => (base_dir.join(config), base_dir.join(project_dir)),
+            _ => bail!(
+                "Line {}: expected '<config> [<project-dir>]', found '{}'",
+                index + 1,
+                line
+            ),
+        };
+        entries.push(BatchEntry {
+            config,
+            project_dir,
+        });
+    }
+    Ok(entries)
+}
+
+/// How the run of one project ended.
+/// 一个项目的运行如何结束。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectOutcome {
+    /// All cases passed / 所有用例均通过
+    Passed,
+    /// Some cases failed unexpectedly / 部分用例意外失败
+    Failed,
+    /// The configuration, environment or runner failed before the cases ran / 配置、环境或运行器在用例运行之前失败
+    Error,
+    /// The run was interrupted / 运行被中断
+    Cancelled,
+    /// The batch was interrupted before the project started / 批处理在项目开始之前被中断
+    Skipped,
+}
+
+impl ProjectOutcome {
+    /// Maps the exit code of a `run` process to an outcome (see [`MatrixError::exit_code`]).
+    /// A process killed by a signal has no exit code and counts as cancelled.
+    ///
+    /// 将 `run` 进程的退出码映射为结果（参见 [`MatrixError::exit_code`]）。
+    /// 被信号终止的进程没有退出码，视为已取消。
+    pub fn from_exit_code(code: Option<i32>) -> Self {
+        let failed = i32::from(MatrixError::UnexpectedFailures { count: 0 }.exit_code());
+        let cancelled = i32::from(MatrixError::Cancelled.exit_code());
+        match code {
+            Some(0) => ProjectOutcome::Passed,
+            Some(code) if code == failed => ProjectOutcome::Failed,
+            Some(code) if code == cancelled => ProjectOutcome::Cancelled,
+            Some(_) => ProjectOutcome::Error,
+            None => ProjectOutcome::Cancelled,
+        }
+    }
+}
+
+/// The result of one project of a batch.
+/// 批处理中一个项目的结果。
+#[derive(Debug, Clone)]
+pub struct ProjectResult {
+    /// The project that was run / 运行的项目
+    pub entry: BatchEntry,
+    /// How its run ended / 其运行如何结束
+    pub outcome: ProjectOutcome,
+    /// The wall-clock time of its run / 其运行的实际耗时
+    pub duration: Duration,
+}
@@ -0,0 +1,360 @@
+//! # Builder Module / 构建器模块
+//!
+//! This module lets programs using the library, e.g. an `xtask`, construct a test matrix
+//! in code instead of TOML: `TestCase::builder()` sets only the fields a case needs and
+//! leaves the others at the defaults of the matrix file, and `TestMatrix::from_cases`
+//! wraps the cases in a matrix with the default settings. Both run the checks applied when
+//! a matrix file is loaded, so a matrix built in code is rejected for the same mistakes.
+//!
+//! ```
+//! use matrix_runner::core::config::{TestCase, TestMatrix};
+//!
+//! let case = TestCase::builder()
+//!     .name("nightly-simd")
+//!     .features(["simd", "std"])
+//!     .toolchain("nightly")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(case.features, "simd,std");
+//!
+//! let matrix = TestMatrix::from_cases([case]).unwrap();
+//! assert_eq!(matrix.cases.len(), 1);
+//! ```
+//!
+//! 此模块让使用本库的程序（例如 `xtask`）可以在代码中而不是在 TOML 中构造测试矩阵：
+//! `TestCase::builder()` 只设置用例需要的字段，其余字段保持矩阵文件中的默认值；
+//! `TestMatrix::from_cases` 将这些用例包装成使用默认设置的矩阵。两者都会执行加载矩阵文件时的检查，
+//! 因此在代码中构建的矩阵会因同样的错误而被拒绝。
+
+use anyhow::{Result, bail};
+
+use crate::core::config::{
+    AllowFailure, CaseKind, Fixture, FuzzSmoke, Sandbox, TestCase, TestMatrix, Ulimits,
+};
+
+/// Builds a [`TestCase`] field by field; see [`TestCase::builder`].
+/// 逐个字段地构建 [`TestCase`]；参见 [`TestCase::builder`]。
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TestCaseBuilder {
+    case: TestCase,
+}
+
+impl TestCase {
+    /// Starts building a case. Every field not set keeps the default it has when omitted
+    /// from the matrix file; only the name is required.
+    /// 开始构建一个用例。未设置的字段保持在矩阵文件中省略时的默认值；只有名称是必需的。
+    pub fn builder() -> TestCaseBuilder {
+        TestCaseBuilder {
+            case: TestCase {
+                name: String::new(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl TestCaseBuilder {
+    /// Sets the unique name of the case / 设置用例的唯一名称
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.case.name = name.into();
+        self
+    }
+
+    /// Sets the stable id of the case, keeping its history across renames / 设置用例的稳定 ID，使其历史在重命名后得以保留
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.case.id = Some(id.into());
+        self
+    }
+
+    /// Sets the features to enable, joined with commas / 设置要启用的 features，以逗号连接
+    pub fn features<I, S>(mut self, features: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let features: Vec<String> = features
+            .into_iter()
+            .map(|f| f.as_ref().to_string())
+            .collect();
+        self.case.features = features.join(",");
+        self
+    }
+
+    /// Builds without the default features / 不使用默认 features 构建
+    pub fn no_default_features(mut self, no_default_features: bool) -> Self {
+        self.case.no_default_features = no_default_features;
+        self
+    }
+
+    /// Runs a custom command instead of the tests / 运行自定义命令而不是测试
+    pub fn command(mut self, command: impl Into<String>) -> Self {
+        self.case.command = Some(command.into());
+        self
+    }
+
+    /// Sets what the case runs without a custom command / 设置用例在没有自定义命令时运行的内容
+    pub fn kind(mut self, kind: CaseKind) -> Self {
+        self.case.kind = kind;
+        self
+    }
+
+    /// Sets how a `kind = "fuzz-smoke"` case runs its fuzz targets / 设置 `kind = "fuzz-smoke"` 用例如何运行其模糊测试目标
+    pub fn fuzz(mut self, fuzz: FuzzSmoke) -> Self {
+        self.case.fuzz = fuzz;
+        self
+    }
+
+    /// Sets the exit codes of the custom command that count as a pass / 设置自定义命令中视为通过的退出码
+    pub fn success_exit_codes(mut self, codes: impl IntoIterator<Item = i32>) -> Self {
+        self.case.success_exit_codes = codes.into_iter().collect();
+        self
+    }
+
+    /// Sets the timeout in seconds / 设置超时时间（秒）
+    pub fn timeout_secs(mut self, secs: u64) -> Self {
+        self.case.timeout_secs = Some(secs);
+        self
+    }
+
+    /// Sets how long the test process may print nothing, in seconds / 设置测试进程可以不输出任何内容的时长（秒）
+    pub fn output_idle_timeout_secs(mut self, secs: u64) -> Self {
+        self.case.output_idle_timeout_secs = Some(secs);
+        self
+    }
+
+    /// Sets how long the processes of the case may take to shut down, in seconds / 设置用例进程可用于关闭的时长（秒）
+    pub fn kill_grace_secs(mut self, secs: u64) -> Self {
+        self.case.kill_grace_secs = Some(secs);
+        self
+    }
+
+    /// Sets how many times a failed case is retried / 设置失败用例的重试次数
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.case.retries = Some(retries);
+        self
+    }
+
+    /// Allows the case to fail on an OS or architecture; an OS name converts into an
+    /// entry without expiry.
+    /// 允许用例在某个操作系统或架构上失败；操作系统名称会转换为没有到期日期的条目。
+    pub fn allow_failure(mut self, entry: impl Into<AllowFailure>) -> Self {
+        self.case.allow_failure.push(entry.into());
+        self
+    }
+
+    /// Makes a timeout of the case an allowed failure / 使用例的超时成为允许的失败
+    pub fn allow_timeout(mut self, allow_timeout: bool) -> Self {
+        self.case.allow_timeout = allow_timeout;
+        self
+    }
+
+    /// Restricts the case to some architectures / 将用例限制在某些架构上
+    pub fn arch<I, S>(mut self, arch: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.arch = arch.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the tags of the case / 设置用例的标签
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the tests left out of the run of the test binary / 设置从测试二进制文件的运行中排除的测试
+    pub fn skip_tests<I, S>(mut self, tests: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.skip_tests = tests.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Puts the case in a concurrency group / 将用例放入并发组
+    pub fn concurrency_group(mut self, group: impl Into<String>) -> Self {
+        self.case.concurrency_group = Some(group.into());
+        self
+    }
+
+    /// Sets how long the case is expected to take, in seconds / 设置用例预计耗时（秒）
+    pub fn expected_secs(mut self, secs: u64) -> Self {
+        self.case.expected_secs = Some(secs);
+        self
+    }
+
+    /// Sets the workspace package to test / 设置要测试的工作区包
+    pub fn package(mut self, package: impl Into<String>) -> Self {
+        self.case.package = Some(package.into());
+        self
+    }
+
+    /// Only compiles the tests / 仅编译测试
+    pub fn build_only(mut self, build_only: bool) -> Self {
+        self.case.build_only = build_only;
+        self
+    }
+
+    /// Runs the test binary in this many shards / 以这么多个分片运行测试二进制文件
+    pub fn shards(mut self, shards: u32) -> Self {
+        self.case.shards = Some(shards);
+        self
+    }
+
+    /// Sets the resource limits of the test process / 设置测试进程的资源限制
+    pub fn ulimits(mut self, ulimits: Ulimits) -> Self {
+        self.case.ulimits = ulimits;
+        self
+    }
+
+    /// Sets the files staged into the project directory while the case runs / 设置用例运行期间放置到项目目录中的文件
+    pub fn fixtures(mut self, fixtures: impl IntoIterator<Item = Fixture>) -> Self {
+        self.case.fixtures = fixtures.into_iter().collect();
+        self
+    }
+
+    /// Sets the rustup toolchain / 设置 rustup 工具链
+    pub fn toolchain(mut self, toolchain: impl Into<String>) -> Self {
+        self.case.toolchain = Some(toolchain.into());
+        self
+    }
+
+    /// Sets the target triple / 设置目标三元组
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.case.target = Some(target.into());
+        self
+    }
+
+    /// Sets the unstable `-Z` flags passed to cargo / 设置传递给 cargo 的不稳定 `-Z` 标志
+    pub fn cargo_unstable_flags<I, S>(mut self, flags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.cargo_unstable_flags = flags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the rustup components the case needs / 设置用例需要的 rustup 组件
+    pub fn components<I, S>(mut self, components: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.components = components.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Marks the case as needing network access / 将用例标记为需要网络访问
+    pub fn requires_network(mut self, requires_network: bool) -> Self {
+        self.case.requires_network = requires_network;
+        self
+    }
+
+    /// Reserves this many free TCP ports for the test process / 为测试进程预留这么多个空闲 TCP 端口
+    pub fn needs_ports(mut self, count: u16) -> Self {
+        self.case.needs_ports = Some(count);
+        self
+    }
+
+    /// Runs the test process in a sandbox (Linux only) / 在沙箱中运行测试进程（仅 Linux）
+    pub fn sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.case.sandbox = Some(sandbox);
+        self
+    }
+
+    /// Overrides the matrix `force_c_locale` / 覆盖矩阵的 `force_c_locale`
+    pub fn force_c_locale(mut self, force_c_locale: bool) -> Self {
+        self.case.force_c_locale = Some(force_c_locale);
+        self
+    }
+
+    /// Sets the locale the test process runs in / 设置测试进程运行时使用的语言环境
+    pub fn env_locale(mut self, locale: impl Into<String>) -> Self {
+        self.case.env_locale = Some(locale.into());
+        self
+    }
+
+    /// Sets an environment variable for the processes of the case / 为用例的进程设置一个环境变量
+    pub fn env(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.case.env.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the cases that should finish before this one / 设置应在此用例之前完成的用例
+    pub fn after<I, S>(mut self, cases: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.after = cases.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets why the case exists / 设置用例存在的原因
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.case.description = Some(description.into());
+        self
+    }
+
+    /// Sets the related links / 设置相关链接
+    pub fn links<I, S>(mut self, links: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.case.links = links.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Finishes the case, checking it like a case of a matrix file.
+    ///
+    /// # Returns
+    /// The case, or an error if it has no name or an invalid field
+    ///
+    /// 完成用例的构建，并像矩阵文件中的用例一样对其进行检查。
+    /// 返回该用例；如果它没有名称或有无效字段，则返回错误。
+    pub fn build(self) -> Result<TestCase> {
+        if self.case.name.trim().is_empty() {
+            bail!("A test case needs a name");
+        }
+        self.case.validate_unstable_flags()?;
+        self.case.validate_shards()?;
+        self.case.validate_sandbox()?;
+        self.case.validate_fixtures()?;
+        self.case.validate_fuzz()?;
+        self.case.validate_env()?;
+        Ok(self.case)
+    }
+}
+
+impl TestMatrix {
+    /// Creates a matrix of the given cases with the default settings, checking it like a
+    /// loaded matrix file (e.g. that every `after` names one of the cases).
+    /// 使用默认设置创建包含给定用例的矩阵，并像加载的矩阵文件一样对其进行检查
+    /// （例如每个 `after` 是否都指向其中的用例）。
+    pub fn from_cases(cases: impl IntoIterator<Item = TestCase>) -> Result<Self> {
+        let mut matrix = TestMatrix {
+            cases: cases.into_iter().collect(),
+            ..Default::default()
+        };
+        matrix.resolve_duplicates()?;
+        matrix.assign_case_ids()?;
+        matrix.validate_after()?;
+        matrix.validate_unstable_flags()?;
+        matrix.validate_shards()?;
+        matrix.validate_sandbox()?;
+        matrix.validate_fixtures()?;
+        matrix.validate_fuzz()?;
+        matrix.validate_env()?;
+        Ok(matrix)
+    }
+}
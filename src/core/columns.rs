@@ -0,0 +1,236 @@
+//! # Columns Module / 列模块
+//!
+//! This module defines the columns a matrix can choose for the console summary and the
+//! HTML results table (`summary_columns`), and the value each column shows for a result.
+//! Besides the built-in columns, `annotation:<key>` shows a value the cases write to
+//! their annotations file, so a team can track e.g. the memory use its tests report.
+//!
+//! 此模块定义矩阵可以为控制台摘要和 HTML 结果表选择的列（`summary_columns`），以及每列为一个结果显示的值。
+//! 除内置列外，`annotation:<key>` 显示用例写入其注解文件的值，使团队可以跟踪例如测试所报告的内存使用量。
+
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::core::context::RunContext;
+use crate::core::costs::{self, CaseCost};
+use crate::core::models::TestResult;
+use crate::infra::t;
+
+/// The prefix of a column showing a case annotation, e.g. `annotation:memory`.
+/// 显示用例注解的列的前缀，例如 `annotation:memory`。
+pub const ANNOTATION_PREFIX: &str = "annotation:";
+
+/// A column of the console summary and the HTML results table, written as its name in
+/// `summary_columns`.
+/// 控制台摘要和 HTML 结果表中的一列，在 `summary_columns` 中以其名称书写。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schemars(with = "String")]
+pub enum SummaryColumn {
+    /// The status of the result (`status`) / 结果的状态（`status`）
+    Status,
+    /// The name of the case (`name`) / 用例名称（`name`）
+    Name,
+    /// The package the case tests (`package`) / 用例测试的包（`package`）
+    Package,
+    /// The features of the case (`features`) / 用例的 features（`features`）
+    Features,
+    /// The target triple of the case (`target`) / 用例的目标三元组（`target`）
+    Target,
+    /// The toolchain of the case (`toolchain`) / 用例的工具链（`toolchain`）
+    Toolchain,
+    /// The duration of the case (`duration`) / 用例的耗时（`duration`）
+    Duration,
+    /// The retries the case needed (`retries`) / 用例所需的重试次数（`retries`）
+    Retries,
+    /// The build time of the case (`build_time`) / 用例的构建耗时（`build_time`）
+    BuildTime,
+    /// The duration of the case without its build (`run_time`) / 用例不含构建的耗时（`run_time`）
+    RunTime,
+    /// The final size of the case's target directory (`target_size`) / 用例 target 目录的最终大小（`target_size`）
+    TargetSize,
+    /// An annotation written by the case (`annotation:<key>`) / 用例写入的注解（`annotation:<key>`）
+    Annotation(String),
+}
+
+/// The names of the built-in columns, in the order they are listed to users.
+/// 内置列的名称，按向用户列出的顺序排列。
+const BUILT_IN: [(&str, SummaryColumn); 11] = [
+    ("status", SummaryColumn::Status),
+    ("name", SummaryColumn::Name),
+    ("package", SummaryColumn::Package),
+    ("features", SummaryColumn::Features),
+    ("target", SummaryColumn::Target),
+    ("toolchain", SummaryColumn::Toolchain),
+    ("duration", SummaryColumn::Duration),
+    ("retries", SummaryColumn::Retries),
+    ("build_time", SummaryColumn::BuildTime),
+    ("run_time", SummaryColumn::RunTime),
+    ("target_size", SummaryColumn::TargetSize),
+];
+
+impl SummaryColumn {
+    /// Whether the column holds numbers, which are aligned to the right.
+    /// 该列是否包含数字（数字右对齐）。
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            SummaryColumn::Duration
+                | SummaryColumn::Retries
+                | SummaryColumn::BuildTime
+                | SummaryColumn::RunTime
+                | SummaryColumn::TargetSize
+        )
+    }
+
+    /// The header of the column in the reports.
+    /// 该列在报告中的表头。
+    pub fn header(&self, locale: &str) -> String {
+        match self {
+            SummaryColumn::Status => {
+                t!("html_report.table.header.status", locale = locale).to_string()
+            }
+            SummaryColumn::Name => t!("html_report.table.header.name", locale = locale).to_string(),
+            SummaryColumn::Package => {
+                t!("html_report.table.header.package", locale = locale).to_string()
+            }
+            SummaryColumn::Features => {
+                t!("html_report.table.header.features", locale = locale).to_string()
+            }
+            SummaryColumn::Target => {
+                t!("html_report.table.header.target", locale = locale).to_string()
+            }
+            SummaryColumn::Toolchain => {
+                t!("html_report.table.header.toolchain", locale = locale).to_string()
+            }
+            SummaryColumn::Duration => {
+                t!("html_report.table.header.duration", locale = locale).to_string()
+            }
+            SummaryColumn::Retries => {
+                t!("html_report.table.header.retries", locale = locale).to_string()
+            }
+            SummaryColumn::BuildTime => {
+                t!("html_report.table.header.build_time", locale = locale).to_string()
+            }
+            SummaryColumn::RunTime => {
+                t!("html_report.table.header.run_time", locale = locale).to_string()
+            }
+            SummaryColumn::TargetSize => {
+                t!("html_report.table.header.target_size", locale = locale).to_string()
+            }
+            SummaryColumn::Annotation(key) => key.clone(),
+        }
+    }
+
+    /// The plain-text value of the column for a result; empty when the result has none.
+    ///
+    /// # Arguments
+    /// * `result` - The result of the row
+    /// * `cost` - The recorded cost of the case, if any
+    /// * `context` - The language of the status and the platform allowed failures are decided on
+    /// * `today` - The date allowed failures are decided on
+    ///
+    /// 该列对一个结果的纯文本值；结果没有该值时为空。
+    pub fn value(
+        &self,
+        result: &TestResult,
+        cost: Option<&CaseCost>,
+        context: &RunContext,
+        today: NaiveDate,
+    ) -> String {
+        let case = result.get_case();
+        let seconds = |duration: Option<std::time::Duration>| {
+            duration
+                .map(|d| format!("{:.2}s", d.as_secs_f64()))
+                .unwrap_or_default()
+        };
+        match self {
+            SummaryColumn::Status => result.get_status_str(context, today),
+            SummaryColumn::Name => result.case_name().to_string(),
+            SummaryColumn::Package => result.package().unwrap_or_default().to_string(),
+            SummaryColumn::Features => result.get_features().to_string(),
+            SummaryColumn::Target => case
+                .and_then(|case| case.target.clone())
+                .unwrap_or_default(),
+            SummaryColumn::Toolchain => case
+                .and_then(|case| case.toolchain.clone())
+                .unwrap_or_default(),
+            SummaryColumn::Duration => result
+                .get_duration()
+                .map(|d| format!("{:.2}s", d.as_secs_f64()))
+                .unwrap_or_else(|| "N/A".to_string()),
+            SummaryColumn::Retries => match result.get_retries() {
+                retries if retries > 1 => (retries - 1).to_string(),
+                _ => String::new(),
+            },
+            SummaryColumn::BuildTime => seconds(cost.and_then(|cost| cost.build_duration)),
+            SummaryColumn::RunTime => seconds(cost.and_then(|cost| cost.run_duration(result))),
+            SummaryColumn::TargetSize => cost
+                .map(|cost| costs::format_size(cost.target_bytes))
+                .unwrap_or_default(),
+            SummaryColumn::Annotation(key) => result
+                .get_annotations()
+                .get(key)
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl std::str::FromStr for SummaryColumn {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(key) = value.strip_prefix(ANNOTATION_PREFIX) {
+            if key.is_empty() {
+                return Err(format!(
+                    "`{ANNOTATION_PREFIX}` needs an annotation key, e.g. `{ANNOTATION_PREFIX}memory`"
+                ));
+            }
+            return Ok(SummaryColumn::Annotation(key.to_string()));
+        }
+        BUILT_IN
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, column)| column.clone())
+            .ok_or_else(|| {
+                let names: Vec<&str> = BUILT_IN.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown summary column `{value}`; expected one of {}, or `{ANNOTATION_PREFIX}{value}` for an annotation the cases write",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for SummaryColumn {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for SummaryColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SummaryColumn::Annotation(key) => write!(f, "{ANNOTATION_PREFIX}{key}"),
+            column => {
+                let name = BUILT_IN
+                    .iter()
+                    .find(|(_, built_in)| built_in == column)
+                    .map(|(name, _)| *name)
+                    .unwrap_or_default();
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+impl From<SummaryColumn> for String {
+    fn from(column: SummaryColumn) -> Self {
+        column.to_string()
+    }
+}
@@ -5,20 +5,38 @@
 //!
 //! 此模块定义了用于解析和处理测试矩阵配置文件的结构和函数。
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
-use anyhow::{Context, Result};
+use std::path::{Component, Path, PathBuf};
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+
+pub mod edit;
+
+use crate::core::columns::SummaryColumn;
+use crate::core::generator::{generate_cases, CaseGenerator};
+use crate::core::planner::{AxisFilter, MatrixAxes};
 
 /// Represents a single test case defined in the test matrix configuration.
 /// Each `TestCase` corresponds to a specific build and test configuration.
 /// 代表测试矩阵配置中定义的单个测试用例。
 /// 每个 `TestCase` 对应一个特定的构建和测试配置。
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TestCase {
     /// The unique name for the test case, used for identification in logs.
     /// 测试用例的唯一名称，用于在日志中进行识别。
     pub name: String,
+    /// A stable identity of the case across runs, keying its entries in the run history.
+    /// When not set, it is derived from the fields defining what the case builds and runs
+    /// (see [`TestCase::derived_id`]), so renaming a case keeps its history.
+    /// 用例在多次运行之间的稳定标识，作为其在运行历史中条目的键。未设置时，它由定义用例构建和运行内容的字段派生
+    /// （参见 [`TestCase::derived_id`]），因此重命名用例会保留其历史。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// A string of comma-separated features to enable for this test case.
     /// 为此测试用例启用的一系列以逗号分隔的 features。
     pub features: String,
@@ -31,12 +49,48 @@ pub struct TestCase {
     /// 则会构建一个默认的 `cargo test` 命令。
     #[serde(default)]
     pub command: Option<String>,
+    /// What the case runs when it has no custom `command`: the tests (`"test"`, the default),
+    /// the tests under `cargo tarpaulin` (`"tarpaulin"`), recording their line coverage,
+    /// `cargo doc` (`"doc"`), failing on broken intra-doc links, or a short run of every
+    /// `cargo fuzz` target (`"fuzz-smoke"`), failing when one does not build or crashes.
+    /// 用例在没有自定义 `command` 时运行的内容：测试（`"test"`，默认）、在 `cargo tarpaulin`
+    /// 下运行的测试（`"tarpaulin"`，并记录其行覆盖率）、`cargo doc`（`"doc"`，遇到失效的文档内链接时失败），
+    /// 或对每个 `cargo fuzz` 目标的短时运行（`"fuzz-smoke"`，某个目标无法构建或崩溃时失败）。
+    #[serde(default, skip_serializing_if = "CaseKind::is_test")]
+    pub kind: CaseKind,
+    /// How a `kind = "fuzz-smoke"` case runs its fuzz targets, e.g. `{ runs = 100000, max_secs = 60, targets = ["parse"] }`.
+    /// 一个 `kind = "fuzz-smoke"` 用例如何运行其模糊测试目标，例如 `{ runs = 100000, max_secs = 60, targets = ["parse"] }`。
+    #[serde(default, skip_serializing_if = "FuzzSmoke::is_empty")]
+    pub fuzz: FuzzSmoke,
+    /// The exit codes of the custom `command` that count as a pass, e.g. `[0, 2]` for tools
+    /// that exit with 2 on warnings. Defaults to `[0]`. Has no effect on cases without a `command`.
+    /// 自定义 `command` 中视为通过的退出码，例如对于在有警告时以 2 退出的工具使用 `[0, 2]`。
+    /// 默认为 `[0]`。对没有 `command` 的用例无效。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub success_exit_codes: Vec<i32>,
     /// An optional timeout in seconds for the test case. If the test runs longer
     /// than this, it will be marked as a timeout failure.
     /// 测试用例的可选超时时间（秒）。如果测试运行时间超过此值，
     /// 它将被标记为超时失败。
     #[serde(default)]
     pub timeout_secs: Option<u64>,
+    /// An optional limit in seconds on how long the test process may print nothing.
+    /// A process silent for longer is killed and fails as stalled, long before
+    /// `timeout_secs` runs out. The build is not covered, since compiling a large crate
+    /// can be silent for a long time.
+    /// 测试进程可以不输出任何内容的可选时间上限（秒）。静默更久的进程会被终止并以停滞失败，
+    /// 远早于 `timeout_secs` 耗尽。构建不受此限制，因为编译大型 crate 可能长时间没有输出。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_idle_timeout_secs: Option<u64>,
+    /// How long, in seconds, the processes of the case may take to shut down when it times
+    /// out, stalls or is cancelled. They are first sent SIGTERM and only killed once the
+    /// grace period is over, so e.g. servers under test can flush their logs. Unset kills
+    /// them right away. Unix only; on Windows the processes are always killed right away.
+    /// 用例超时、停滞或被取消时，其进程可用于关闭的时间（秒）。它们会先收到 SIGTERM，
+    /// 宽限期结束后才会被强制终止，使例如被测服务器可以刷新其日志。未设置时立即终止。
+    /// 仅限 Unix；在 Windows 上进程总是被立即终止。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kill_grace_secs: Option<u64>,
     /// The number of times to retry a failed test case before marking it as failed.
     /// This is useful for flaky tests. Retries are only attempted on `Test` or `Build` failures,
     /// not on `Timeout` failures.
@@ -47,38 +101,690 @@ pub struct TestCase {
     pub retries: Option<u8>,
     /// A list of operating systems (e.g., "windows", "linux") on which this
     /// test case is allowed to fail without causing the overall run to fail.
+    /// Entries may carry an expiry date, after which failures count again.
     /// 一个操作系统列表（例如 "windows", "linux"），在此列表中的系统上，
-    /// 该测试用例允许失败，而不会导致整个运行失败。
+    /// 该测试用例允许失败，而不会导致整个运行失败。条目可以带有到期日期，之后失败将重新计入。
+    #[serde(default)]
+    pub allow_failure: Vec<AllowFailure>,
+    /// If `true`, a timeout of this case is an allowed failure on every platform,
+    /// e.g. for a case known to be slow on some CI runners. Other failures still count.
+    /// 如果为 `true`，则此用例的超时在所有平台上都是允许的失败，
+    /// 例如用于已知在某些 CI 运行器上较慢的用例。其他失败仍然计入。
     #[serde(default)]
-    pub allow_failure: Vec<String>,
+    pub allow_timeout: bool,
     /// A list of CPU architectures (e.g., "x86_64", "aarch64") on which this
     /// test case should be run. If empty, the case runs on all architectures.
     /// 一个 CPU 架构列表（例如 "x86_64", "aarch64"），此测试用例应在这些架构上运行。
     /// 如果为空，则该用例在所有架构上运行。
     #[serde(default)]
     pub arch: Vec<String>,
+    /// Free-form labels used to group and select cases (e.g. with `run --select 'tag == "fast"'`).
+    /// 用于分组和选择用例的自由标签（例如使用 `run --select 'tag == "fast"'`）。
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The name of a concurrency group this case belongs to. Cases in the same group
+    /// share the group's `max_concurrent` limit, e.g. because they use the same database.
+    /// 此用例所属的并发组名称。同一组中的用例共享该组的 `max_concurrent` 限制，
+    /// 例如因为它们使用同一个数据库。
+    #[serde(default)]
+    pub concurrency_group: Option<String>,
+    /// How long this case is expected to take, in seconds. Runs deviating from it by
+    /// more than the matrix's `anomaly_factor` are flagged as anomalies, even if they pass.
+    /// 此用例预计耗时（秒）。与其偏差超过矩阵 `anomaly_factor` 的运行会被标记为异常，即使它们通过。
+    #[serde(default)]
+    pub expected_secs: Option<u64>,
+    /// The workspace package to test (passed as `-p`). Defaults to the package at the project root.
+    /// 要测试的工作区包（作为 `-p` 传递）。默认为项目根目录下的包。
+    #[serde(default)]
+    pub package: Option<String>,
+    /// If `true`, the case is only compiled (`cargo test --no-run`) and its tests are not run,
+    /// e.g. for targets the host cannot execute or `no_std` feature combinations.
+    /// Has no effect on cases with a custom `command`.
+    /// 如果为 `true`，则该用例仅被编译（`cargo test --no-run`），不运行其测试，
+    /// 例如用于主机无法执行的目标或 `no_std` 的 feature 组合。对带有自定义 `command` 的用例无效。
+    #[serde(default)]
+    pub build_only: bool,
+    /// Splits the run of the built test binary into this many processes running in
+    /// parallel, each with `MATRIX_SHARD=<index>/<count>` (1-based) set, for huge
+    /// integration test binaries. The test harness picks its share of the tests from that
+    /// variable, and the shards' outputs are merged into the case's result. Only the default
+    /// flow is sharded; cases with a custom `command`, run by nextest or by tarpaulin are not.
+    /// 将已构建测试二进制文件的运行拆分为这么多个并行运行的进程，每个进程都设置了
+    /// `MATRIX_SHARD=<序号>/<总数>`（从 1 开始），用于非常庞大的集成测试二进制文件。
+    /// 测试框架根据该变量选取自己负责的测试，各分片的输出会合并到用例的结果中。
+    /// 只有默认流程会被分片；带有自定义 `command`、由 nextest 或 tarpaulin 运行的用例不会。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shards: Option<u32>,
+    /// Tests left out of the run of the test binary, passed to it as `--skip <name>`, e.g.
+    /// `["module::flaky_test"]`, so a single known-bad test does not need the whole case
+    /// to allow failures. As with libtest's `--skip`, a test is left out when its name
+    /// contains one of them. Only the default flow is affected, not cases with a custom `command`.
+    /// 从测试二进制文件的运行中排除的测试，以 `--skip <名称>` 传递给它，例如 `["module::flaky_test"]`，
+    /// 使单个已知有问题的测试不必让整个用例允许失败。与 libtest 的 `--skip` 一样，名称包含其中之一的测试会被排除。
+    /// 只影响默认流程，不影响带有自定义 `command` 的用例。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_tests: Vec<String>,
+    /// Resource limits applied to the spawned test process (Unix only), e.g. `{ nofile = 65535, core = "unlimited" }`.
+    /// 应用于所启动测试进程的资源限制（仅 Unix），例如 `{ nofile = 65535, core = "unlimited" }`。
+    #[serde(default, skip_serializing_if = "Ulimits::is_empty")]
+    pub ulimits: Ulimits,
+    /// Files staged into the project directory before the case runs and removed after it,
+    /// e.g. `[{ from = "tests/data/big.bin", to = "data/big.bin" }]`, so custom commands and
+    /// tests find their inputs at a fixed path.
+    /// 在用例运行前放置到项目目录中、并在其运行后删除的文件，例如
+    /// `[{ from = "tests/data/big.bin", to = "data/big.bin" }]`，使自定义命令和测试能在固定路径找到其输入。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixtures: Vec<Fixture>,
+    /// The rustup toolchain to build and test with (e.g. "nightly", "1.75.0"), passed as `cargo +<toolchain>`.
+    /// Has no effect on cases with a custom `command`.
+    /// 用于构建和测试的 rustup 工具链（例如 "nightly"、"1.75.0"），作为 `cargo +<toolchain>` 传递。
+    /// 对带有自定义 `command` 的用例无效。
+    #[serde(default)]
+    pub toolchain: Option<String>,
+    /// The target triple to build for (passed as `--target`), e.g. "wasm32-unknown-unknown".
+    /// Cases for a target the host cannot run are only built, as with `build_only`.
+    /// 要构建的目标三元组（作为 `--target` 传递），例如 "wasm32-unknown-unknown"。
+    /// 主机无法运行的目标的用例仅被构建，与 `build_only` 相同。
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Unstable `-Z` flags passed to cargo, e.g. `["-Zbuild-std=core,alloc"]`. Only allowed
+    /// with a nightly `toolchain`. Has no effect on cases with a custom `command`.
+    /// 传递给 cargo 的不稳定 `-Z` 标志，例如 `["-Zbuild-std=core,alloc"]`。仅允许与 nightly
+    /// `toolchain` 一起使用。对带有自定义 `command` 的用例无效。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cargo_unstable_flags: Vec<String>,
+    /// The rustup components the case needs from its toolchain, e.g. `["miri", "rust-src"]`.
+    /// They are checked before the run starts, and a case lacking one is skipped or fails
+    /// the run according to the matrix `on_missing_component`. For a custom `command`,
+    /// they are checked for the case's `toolchain` if set, otherwise the active one.
+    /// 用例需要其工具链提供的 rustup 组件，例如 `["miri", "rust-src"]`。它们会在运行开始前被检查，
+    /// 缺少组件的用例会根据矩阵的 `on_missing_component` 被跳过或导致运行失败。对于自定义 `command`，
+    /// 如果设置了用例的 `toolchain` 则针对它检查，否则针对当前激活的工具链检查。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<String>,
+    /// If `true`, the case needs network access: it is skipped by `run --offline-only`,
+    /// and otherwise runs with `NETWORK_TESTS=1` set.
+    /// 如果为 `true`，则该用例需要网络访问：它会被 `run --offline-only` 跳过，
+    /// 否则在设置了 `NETWORK_TESTS=1` 的情况下运行。
+    #[serde(default)]
+    pub requires_network: bool,
+    /// How many free TCP ports the test process needs, e.g. for servers started by
+    /// integration tests. The runner reserves that many ports, unique among the running
+    /// cases, and passes them as `MATRIX_PORT_0`, `MATRIX_PORT_1`, ...
+    /// 测试进程需要多少个空闲 TCP 端口，例如用于集成测试启动的服务器。运行器会预留这么多个
+    /// 在运行中用例之间唯一的端口，并通过 `MATRIX_PORT_0`、`MATRIX_PORT_1`…… 传递它们。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needs_ports: Option<u16>,
+    /// Runs the test process in a sandbox on Linux, e.g. `{ network = false, readonly_project = true }`,
+    /// to check that the tests need no network access and leave the source tree alone.
+    /// 在 Linux 上于沙箱中运行测试进程，例如 `{ network = false, readonly_project = true }`，
+    /// 以检查测试不需要网络访问并且不会改动源码树。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<Sandbox>,
+    /// Overrides the matrix `force_c_locale` for this case / 为此用例覆盖矩阵的 `force_c_locale`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force_c_locale: Option<bool>,
+    /// The locale the test process runs in (e.g. `"zh-CN"`), for cases asserting on localized
+    /// output. Sets `LANG`, `LC_ALL` and `LANGUAGE` for the test process only, taking precedence
+    /// over `force_c_locale`; the runner's own language is not affected.
+    /// 测试进程运行时使用的语言环境（例如 `"zh-CN"`），用于断言本地化输出的用例。仅为测试进程设置
+    /// `LANG`、`LC_ALL` 和 `LANGUAGE`，优先于 `force_c_locale`；运行器自身的语言不受影响。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_locale: Option<String>,
+    /// Environment variables set for every process the case spawns — cargo, the test
+    /// binaries and a custom `command` — e.g. `{ RUST_LOG = "debug", MY_FLAG = "1" }`.
+    /// They are added to the matrix `[env]`, and take precedence over it.
+    /// 为用例启动的每个进程（cargo、测试二进制文件和自定义 `command`）设置的环境变量，例如
+    /// `{ RUST_LOG = "debug", MY_FLAG = "1" }`。它们会与矩阵的 `[env]` 合并，并优先于它。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Cases that should finish before this one starts, e.g. a case priming a shared build cache.
+    /// Unlike a dependency, this case still runs if they fail. Cases not in the run are ignored.
+    /// 应在此用例开始之前完成的用例，例如预热共享构建缓存的用例。
+    /// 与依赖不同，即使它们失败此用例仍会运行。不在本次运行中的用例会被忽略。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+    /// Why this case exists, shown in the HTML report and in failure details.
+    /// 此用例存在的原因，显示在 HTML 报告和失败详情中。
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Related links, e.g. the issue a case reproduces, shown next to the description.
+    /// 相关链接，例如用例所复现的 issue，显示在描述旁边。
+    #[serde(default)]
+    pub links: Vec<String>,
 }
 
 impl Default for TestCase {
     fn default() -> Self {
         Self {
             name: "unknown".to_string(),
+            id: None,
             features: "".to_string(),
             no_default_features: false,
             command: None,
+            kind: CaseKind::Test,
+            fuzz: FuzzSmoke::default(),
+            success_exit_codes: vec![],
             timeout_secs: None,
+            output_idle_timeout_secs: None,
+            kill_grace_secs: None,
             retries: None,
             allow_failure: vec![],
+            allow_timeout: false,
             arch: vec![],
+            tags: vec![],
+            concurrency_group: None,
+            expected_secs: None,
+            package: None,
+            build_only: false,
+            shards: None,
+            skip_tests: vec![],
+            ulimits: Ulimits::default(),
+            fixtures: vec![],
+            toolchain: None,
+            target: None,
+            cargo_unstable_flags: vec![],
+            components: vec![],
+            requires_network: false,
+            needs_ports: None,
+            sandbox: None,
+            force_c_locale: None,
+            env_locale: None,
+            env: BTreeMap::new(),
+            after: vec![],
+            description: None,
+            links: vec![],
+        }
+    }
+}
+
+impl TestCase {
+    /// The stable identity of the case: its `id`, or the id derived from its definition.
+    /// 用例的稳定标识：其 `id`，或由其定义派生的 ID。
+    pub fn case_id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| self.derived_id())
+    }
+
+    /// Derives an id from the fields defining what the case builds and runs: its package,
+    /// features (in any order), `no_default_features`, command, kind, `build_only`,
+    /// toolchain, target, `cargo_unstable_flags`, `env` and `skip_tests`. The name, timeouts,
+    /// retries, tags and descriptions are left out, so editing them keeps the id.
+    ///
+    /// # Returns
+    /// A 16-character lowercase hex string
+    ///
+    /// 根据定义用例构建和运行内容的字段派生 ID：其包、features（与顺序无关）、`no_default_features`、命令、类型、
+    /// `build_only`、工具链、目标、`cargo_unstable_flags`、`env` 和 `skip_tests`。名称、超时、重试、标签和描述不参与计算，因此修改它们会保留 ID。
+    /// 返回 16 个字符的小写十六进制字符串。
+    pub fn derived_id(&self) -> String {
+        let features: BTreeSet<&str> = self
+            .features
+            .split(',')
+            .map(str::trim)
+            .filter(|feature| !feature.is_empty())
+            .collect();
+        let definition = format!(
+            "{:?}\n{:?}\n{}\n{:?}\n{:?}\n{}\n{:?}\n{:?}\n{:?}\n{:?}\n{:?}",
+            self.package,
+            features,
+            self.no_default_features,
+            self.command,
+            self.kind,
+            self.build_only,
+            self.toolchain,
+            self.target,
+            self.cargo_unstable_flags,
+            self.env,
+            self.skip_tests
+        );
+        let digest = format!("{:x}", Sha256::digest(definition));
+        digest[..CASE_ID_LEN].to_string()
+    }
+
+    /// Checks whether a failure of the case is allowed on an OS on the given day,
+    /// i.e. whether an unexpired `allow_failure` entry names it.
+    /// 检查用例在给定日期、给定操作系统上的失败是否被允许，即是否有未到期的 `allow_failure` 条目指定了它。
+    pub fn allows_failure_on(&self, os: &str, today: NaiveDate) -> bool {
+        self.allow_failure.iter().any(|entry| entry.allows(os, today))
+    }
+
+    /// Whether the case's commands run in the C locale, given the matrix setting.
+    /// 根据矩阵设置，判断用例的命令是否在 C 语言环境下运行。
+    pub fn uses_c_locale(&self, matrix_default: bool) -> bool {
+        self.force_c_locale.unwrap_or(matrix_default)
+    }
+
+    /// Checks whether an exit code of the custom `command` counts as a pass.
+    /// A process killed by a signal has no exit code and never passes.
+    ///
+    /// 检查自定义 `command` 的退出码是否视为通过。被信号终止的进程没有退出码，永远不会通过。
+    pub fn is_success_exit_code(&self, code: Option<i32>) -> bool {
+        match code {
+            Some(code) if self.success_exit_codes.is_empty() => code == 0,
+            Some(code) => self.success_exit_codes.contains(&code),
+            None => false,
+        }
+    }
+
+    /// Checks that the case's `cargo_unstable_flags`, if any, are `-Z` flags and that its
+    /// `toolchain` is a nightly one, since stable cargo rejects them. A case without a
+    /// `toolchain` uses the project's active toolchain, which may be nightly through
+    /// `rust-toolchain.toml` or a rustup override; it is checked when the run starts
+    /// (see [`crate::infra::toolchain::check_unstable_flags_toolchain`]).
+    /// 检查用例的 `cargo_unstable_flags`（如果有）是否为 `-Z` 标志，并且其 `toolchain` 为 nightly 工具链，
+    /// 因为稳定版 cargo 会拒绝它们。未设置 `toolchain` 的用例使用项目当前激活的工具链，
+    /// 它可能通过 `rust-toolchain.toml` 或 rustup 覆盖设置为 nightly；该工具链在运行开始时检查。
+    pub fn validate_unstable_flags(&self) -> Result<()> {
+        if self.cargo_unstable_flags.is_empty() {
+            return Ok(());
+        }
+        if let Some(toolchain) = self.toolchain.as_deref()
+            && !is_nightly_toolchain(toolchain)
+        {
+            bail!(
+                "Case '{}' sets `cargo_unstable_flags`, which need a nightly toolchain, but its toolchain is '{}'",
+                self.name,
+                toolchain
+            );
+        }
+        if let Some(flag) = self.cargo_unstable_flags.iter().find(|flag| !flag.starts_with("-Z")) {
+            bail!(
+                "Case '{}' has '{}' in `cargo_unstable_flags`, which only takes `-Z` flags",
+                self.name,
+                flag
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the case does not ask for zero `shards`, which would run none of its tests.
+    /// 检查用例没有将 `shards` 设置为零，否则其测试一个都不会运行。
+    pub fn validate_shards(&self) -> Result<()> {
+        if self.shards == Some(0) {
+            bail!("Case '{}' has `shards = 0`, but needs at least one shard", self.name);
+        }
+        Ok(())
+    }
+
+    /// Checks that a `sandbox` is only set on cases running tests, and does not cut off
+    /// the network of a case that `requires_network`.
+    /// 检查 `sandbox` 只设置在运行测试的用例上，并且不会切断 `requires_network` 的用例的网络。
+    pub fn validate_sandbox(&self) -> Result<()> {
+        let Some(sandbox) = &self.sandbox else {
+            return Ok(());
+        };
+        if !self.kind.is_test() {
+            bail!(
+                "Case '{}' sets `sandbox`, which only applies to cases of kind \"test\"",
+                self.name
+            );
+        }
+        if self.requires_network && !sandbox.network {
+            bail!(
+                "Case '{}' sets `requires_network = true`, but its sandbox has `network = false`",
+                self.name
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that `fuzz` is only set on `kind = "fuzz-smoke"` cases and that its bounds
+    /// let the fuzz targets run at all.
+    /// 检查 `fuzz` 只设置在 `kind = "fuzz-smoke"` 的用例上，并且其上限允许模糊测试目标运行。
+    pub fn validate_fuzz(&self) -> Result<()> {
+        if self.fuzz.is_empty() {
+            return Ok(());
+        }
+        if self.kind != CaseKind::FuzzSmoke {
+            bail!(
+                "Case '{}' sets `fuzz`, which only applies to cases of kind \"fuzz-smoke\"",
+                self.name
+            );
+        }
+        if self.fuzz.runs == Some(0) || self.fuzz.max_secs == Some(0) {
+            bail!("Case '{}' bounds its fuzz targets to zero runs or seconds", self.name);
+        }
+        Ok(())
+    }
+
+    /// Checks that the names in `env` can be set on a process: not empty, and without `=`
+    /// or NUL characters.
+    /// 检查 `env` 中的名称可以设置到进程上：不为空，且不包含 `=` 或 NUL 字符。
+    pub fn validate_env(&self) -> Result<()> {
+        for (name, value) in &self.env {
+            if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+                bail!("Case '{}' sets the environment variable `{name}`, which cannot be set on a process", self.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the `to` of each fixture is a relative path inside the project directory,
+    /// so staging and removing it cannot touch files elsewhere.
+    /// 检查每个 fixture 的 `to` 是否为项目目录内的相对路径，使放置和删除它不会触及其他位置的文件。
+    pub fn validate_fixtures(&self) -> Result<()> {
+        for fixture in &self.fixtures {
+            let inside = fixture.to.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            if !inside || fixture.to.file_name().is_none() {
+                bail!(
+                    "Case '{}' stages a fixture to '{}', which must be a relative file path inside the project directory",
+                    self.name,
+                    fixture.to.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The `output_idle_timeout_secs` of the case as a duration.
+    /// 以时长表示的用例 `output_idle_timeout_secs`。
+    pub fn output_idle_timeout(&self) -> Option<std::time::Duration> {
+        self.output_idle_timeout_secs.map(std::time::Duration::from_secs)
+    }
+
+    /// The `kill_grace_secs` of the case as a duration.
+    /// 以时长表示的用例 `kill_grace_secs`。
+    pub fn kill_grace(&self) -> Option<std::time::Duration> {
+        self.kill_grace_secs.map(std::time::Duration::from_secs)
+    }
+}
+
+/// Resource limits for the process of a test case, mirroring `ulimit`.
+/// Unset limits are inherited from the runner.
+/// 测试用例进程的资源限制，与 `ulimit` 对应。未设置的限制继承自运行器。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Ulimits {
+    /// Maximum number of open file descriptors (`ulimit -n`) / 最大打开文件描述符数（`ulimit -n`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nofile: Option<Limit>,
+    /// Maximum size of core dumps in bytes (`ulimit -c`) / 核心转储的最大字节数（`ulimit -c`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core: Option<Limit>,
+    /// Maximum stack size in bytes (`ulimit -s`) / 最大栈大小（字节）（`ulimit -s`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Limit>,
+}
+
+impl Ulimits {
+    /// Returns `true` if no limit is set / 如果未设置任何限制，则返回 `true`
+    pub fn is_empty(&self) -> bool {
+        self.nofile.is_none() && self.core.is_none() && self.stack.is_none()
+    }
+}
+
+/// How the test process of a case is sandboxed. Unset options leave the process as it is.
+/// 用例测试进程的沙箱方式。未设置的选项不会改变进程。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Sandbox {
+    /// Whether the process can reach the network; without it only loopback is available
+    /// 进程是否可以访问网络；否则只有回环接口可用
+    #[serde(default = "default_sandbox_network")]
+    pub network: bool,
+    /// Whether the project directory is mounted read-only; its `target` directory stays writable
+    /// 项目目录是否以只读方式挂载；其 `target` 目录仍然可写
+    #[serde(default)]
+    pub readonly_project: bool,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            network: true,
+            readonly_project: false,
+        }
+    }
+}
+
+impl Sandbox {
+    /// Whether the sandbox restricts nothing / 沙箱是否未做任何限制
+    pub fn is_open(&self) -> bool {
+        self.network && !self.readonly_project
+    }
+}
+
+/// How long each fuzz target of a `kind = "fuzz-smoke"` case runs. The fuzzer stops at
+/// whichever bound it reaches first; the point is to catch targets that no longer build or
+/// crash right away, not to fuzz for real.
+/// 一个 `kind = "fuzz-smoke"` 用例的每个模糊测试目标运行多久。模糊测试器在先达到的上限处停止；
+/// 目的是发现不再能构建或立即崩溃的目标，而不是真正地进行模糊测试。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FuzzSmoke {
+    /// The number of inputs each target runs (`-runs`), 10000 by default / 每个目标运行的输入数（`-runs`），默认为 10000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runs: Option<u64>,
+    /// How long each target may run, in seconds (`-max_total_time`), 30 by default / 每个目标可运行的时长（秒，`-max_total_time`），默认为 30
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_secs: Option<u64>,
+    /// The targets to run; every target `cargo fuzz list` reports when empty / 要运行的目标；为空时运行 `cargo fuzz list` 报告的每个目标
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+}
+
+impl FuzzSmoke {
+    /// The number of inputs each target runs when `runs` is not set / 未设置 `runs` 时每个目标运行的输入数
+    pub const DEFAULT_RUNS: u64 = 10_000;
+    /// How long each target may run when `max_secs` is not set / 未设置 `max_secs` 时每个目标可运行的时长
+    pub const DEFAULT_MAX_SECS: u64 = 30;
+
+    /// Returns `true` if nothing is set / 如果未设置任何内容，则返回 `true`
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_none() && self.max_secs.is_none() && self.targets.is_empty()
+    }
+
+    /// The arguments bounding a run of the fuzzer, passed to libFuzzer after `--`.
+    /// 限制模糊测试器运行的参数，在 `--` 之后传递给 libFuzzer。
+    pub fn libfuzzer_args(&self) -> [String; 2] {
+        [
+            format!("-runs={}", self.runs.unwrap_or(Self::DEFAULT_RUNS)),
+            format!("-max_total_time={}", self.max_secs.unwrap_or(Self::DEFAULT_MAX_SECS)),
+        ]
+    }
+}
+
+/// A file a case needs at a fixed path, staged into the project directory while it runs.
+/// 用例在固定路径上需要的文件，在其运行期间放置到项目目录中。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    /// The file to stage, relative to the project directory / 要放置的文件，相对于项目目录
+    pub from: PathBuf,
+    /// Where the case expects it, relative to the project directory / 用例期望它所在的位置，相对于项目目录
+    pub to: PathBuf,
+    /// Whether the file is copied or symlinked / 文件是被复制还是被符号链接
+    #[serde(default, skip_serializing_if = "FixtureMode::is_copy")]
+    pub mode: FixtureMode,
+}
+
+/// How a fixture is staged.
+/// 放置 fixture 的方式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureMode {
+    /// Copy the file, so the case may modify its copy / 复制文件，用例可以修改其副本
+    #[default]
+    Copy,
+    /// Link to the file, for large inputs the case only reads / 链接到文件，用于用例只读取的大型输入
+    Symlink,
+}
+
+impl FixtureMode {
+    /// Whether this is the default mode / 是否为默认模式
+    pub fn is_copy(&self) -> bool {
+        *self == FixtureMode::Copy
+    }
+}
+
+/// A single resource limit: a number, or `"unlimited"` in TOML.
+/// 单个资源限制：一个数字，或在 TOML 中写作 `"unlimited"`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(try_from = "RawLimit", into = "RawLimit")]
+pub enum Limit {
+    /// A fixed limit / 固定的限制
+    Value(u64),
+    /// No limit / 无限制
+    Unlimited,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+enum RawLimit {
+    Value(u64),
+    Keyword(String),
+}
+
+impl TryFrom<RawLimit> for Limit {
+    type Error = String;
+
+    fn try_from(raw: RawLimit) -> std::result::Result<Self, Self::Error> {
+        match raw {
+            RawLimit::Value(value) => Ok(Limit::Value(value)),
+            RawLimit::Keyword(keyword) if keyword == "unlimited" => Ok(Limit::Unlimited),
+            RawLimit::Keyword(other) => Err(format!(
+                "invalid limit `{other}`, expected a number or \"unlimited\""
+            )),
+        }
+    }
+}
+
+impl From<Limit> for RawLimit {
+    fn from(limit: Limit) -> Self {
+        match limit {
+            Limit::Value(value) => RawLimit::Value(value),
+            Limit::Unlimited => RawLimit::Keyword("unlimited".to_string()),
+        }
+    }
+}
+
+/// What a case without a custom `command` runs.
+/// 没有自定义 `command` 的用例运行的内容。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseKind {
+    /// Build the tests and run them / 构建测试并运行
+    #[default]
+    Test,
+    /// Run the tests under `cargo tarpaulin` and record their coverage / 在 `cargo tarpaulin` 下运行测试并记录覆盖率
+    Tarpaulin,
+    /// Build the documentation, denying broken intra-doc links / 构建文档，并拒绝失效的文档内链接
+    Doc,
+    /// Build the `cargo fuzz` targets and run each briefly, failing on a crash / 构建 `cargo fuzz` 目标并各自短暂运行，崩溃时失败
+    #[serde(rename = "fuzz-smoke")]
+    FuzzSmoke,
+}
+
+impl CaseKind {
+    /// Whether this is the default kind / 是否为默认类型
+    pub fn is_test(&self) -> bool {
+        *self == CaseKind::Test
+    }
+
+    /// The name of the kind as written in the configuration / 配置中书写的类型名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaseKind::Test => "test",
+            CaseKind::Tarpaulin => "tarpaulin",
+            CaseKind::Doc => "doc",
+            CaseKind::FuzzSmoke => "fuzz-smoke",
+        }
+    }
+}
+
+/// An `allow_failure` entry: an OS, written as a plain string, or a table with an
+/// optional expiry date and the issue tracking the failure
+/// (`{ os = "windows", until = "2025-09-01", issue = "#123" }`).
+/// 一个 `allow_failure` 条目：写作普通字符串的操作系统，或带有可选到期日期及跟踪该失败的
+/// issue 的表（`{ os = "windows", until = "2025-09-01", issue = "#123" }`）。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(from = "RawAllowFailure", into = "RawAllowFailure")]
+pub struct AllowFailure {
+    /// The OS failures are allowed on / 允许失败的操作系统
+    pub os: String,
+    /// The last day failures are allowed; they count again afterwards / 允许失败的最后一天；之后失败将重新计入
+    pub until: Option<NaiveDate>,
+    /// The issue tracking the failure / 跟踪该失败的 issue
+    pub issue: Option<String>,
+}
+
+impl AllowFailure {
+    /// Checks whether the entry allows failures on an OS on the given day.
+    /// 检查该条目在给定日期是否允许给定操作系统上的失败。
+    pub fn allows(&self, os: &str, today: NaiveDate) -> bool {
+        self.os == os && self.until.is_none_or(|until| today <= until)
+    }
+}
+
+impl From<String> for AllowFailure {
+    fn from(os: String) -> Self {
+        Self {
+            os,
+            until: None,
+            issue: None,
+        }
+    }
+}
+
+impl From<&str> for AllowFailure {
+    fn from(os: &str) -> Self {
+        Self::from(os.to_string())
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+enum RawAllowFailure {
+    Os(String),
+    Entry {
+        os: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<NaiveDate>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        issue: Option<String>,
+    },
+}
+
+impl From<RawAllowFailure> for AllowFailure {
+    fn from(raw: RawAllowFailure) -> Self {
+        match raw {
+            RawAllowFailure::Os(os) => AllowFailure::from(os),
+            RawAllowFailure::Entry { os, until, issue } => AllowFailure { os, until, issue },
         }
     }
 }
 
+impl From<AllowFailure> for RawAllowFailure {
+    fn from(entry: AllowFailure) -> Self {
+        match entry {
+            AllowFailure {
+                os,
+                until: None,
+                issue: None,
+            } => RawAllowFailure::Os(os),
+            AllowFailure { os, until, issue } => RawAllowFailure::Entry { os, until, issue },
+        }
+    }
+}
+
+/// An `allow_failure` entry that has expired or expires soon, as reported by `validate`.
+/// 已到期或即将到期的 `allow_failure` 条目，由 `validate` 报告。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringAllowance {
+    /// The case the entry belongs to / 条目所属的用例
+    pub case: String,
+    /// The entry / 条目
+    pub entry: AllowFailure,
+    /// The days left until the entry expires; negative once it has / 距条目到期的剩余天数；到期后为负数
+    pub days_left: i64,
+}
+
 /// Represents the entire test matrix configuration, loaded from a TOML file.
 /// It contains global settings and a list of all test cases.
 /// 代表从 TOML 文件加载的整个测试矩阵配置。
 /// 它包含全局设置和所有测试用例的列表。
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TestMatrix {
     /// The language for the runner's output messages (e.g., "en", "zh-CN").
     /// Defaults to "en" if not specified.
@@ -93,23 +799,858 @@ pub struct TestMatrix {
     #[serde(default)]
     pub fast_fail: bool,
 
+    /// Limits for named concurrency groups, keyed by group name. Groups referenced by a
+    /// case but not listed here allow one case at a time.
+    /// 命名并发组的限制，以组名为键。被用例引用但未在此列出的组一次只允许运行一个用例。
+    #[serde(default)]
+    pub concurrency_groups: BTreeMap<String, ConcurrencyGroup>,
+
+    /// How far (as a ratio, in either direction) a case's duration may deviate from its
+    /// `expected_secs` before it is flagged as an anomaly. Defaults to 3.0.
+    /// 用例耗时可偏离其 `expected_secs` 的倍数（任一方向），超过则被标记为异常。默认为 3.0。
+    #[serde(default = "default_anomaly_factor")]
+    pub anomaly_factor: f64,
+
+    /// If `true`, the commands of the cases run with `LC_ALL=C`, `LANG=C` and
+    /// `CARGO_TERM_PROGRESS_WHEN=never`, so their output does not depend on the user's
+    /// locale. Cases can override it with their own `force_c_locale`.
+    /// 如果为 `true`，用例的命令将在 `LC_ALL=C`、`LANG=C` 和 `CARGO_TERM_PROGRESS_WHEN=never`
+    /// 下运行，使其输出不依赖于用户的语言环境。用例可以用自己的 `force_c_locale` 覆盖此设置。
+    #[serde(default)]
+    pub force_c_locale: bool,
+
+    /// How many times a build failing with a transient cargo error (a registry timeout,
+    /// a failed download or git fetch, ...) is retried, with a growing delay, before the
+    /// case fails. These retries are counted apart from the case's `retries`. Defaults to 2.
+    /// 因暂时性 cargo 错误（注册表超时、下载或 git 获取失败等）而失败的构建在用例失败之前
+    /// 以递增的延迟重试的次数。这些重试与用例的 `retries` 分开计数。默认为 2。
+    #[serde(default = "default_transient_build_retries")]
+    pub transient_build_retries: u8,
+
+    /// If `true`, test binaries run with core dumps enabled (on Unix), and the core dump of
+    /// a binary crashing with a signal is moved to the case's attachments and named in the
+    /// failure details.
+    /// 如果为 `true`，测试二进制文件将在启用核心转储的情况下运行（Unix 上），因信号而崩溃的二进制文件的
+    /// 核心转储会被移动到用例的附件中，并在失败详情中给出其路径。
+    #[serde(default)]
+    pub collect_core_dumps: bool,
+
+    /// If `true` and `cargo nextest` is installed, cases of the default flow are built and
+    /// run with `cargo nextest run` instead of `cargo test`. Without nextest, the run
+    /// falls back to `cargo test`.
+    /// 如果为 `true` 且已安装 `cargo nextest`，默认流程的用例将使用 `cargo nextest run`
+    /// 而不是 `cargo test` 构建和运行。未安装 nextest 时，运行回退到 `cargo test`。
+    #[serde(default)]
+    pub prefer_nextest: bool,
+
+    /// If `true`, the dependencies of the cases are built once into a shared target
+    /// directory before the cases start, and each case's build starts from a copy of it.
+    /// 如果为 `true`，用例的依赖会在用例开始之前一次性构建到共享的 target 目录中，
+    /// 每个用例的构建都从它的副本开始。
+    #[serde(default)]
+    pub prebuild_dependencies: bool,
+
+    /// The highest one-minute load average at which new cases are started. While the
+    /// machine is busier, the cases holding a job slot wait before they start, so several
+    /// matrices sharing a build machine do not overload it. `--max-load` overrides it.
+    /// 启动新用例时允许的最高一分钟平均负载。机器更繁忙时，持有任务槽位的用例会等待后再启动，
+    /// 使共享一台构建机器的多个矩阵不会使其过载。`--max-load` 会覆盖此设置。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_load: Option<f64>,
+
+    /// The most memory, in GiB, the whole system may use for new cases to be started,
+    /// like `max_load`. `--max-total-memory-gb` overrides it.
+    /// 启动新用例时整个系统最多可使用的内存（GiB），与 `max_load` 类似。`--max-total-memory-gb` 会覆盖此设置。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_memory_gb: Option<f64>,
+
+    /// The owners of cases, keyed by a case name glob (e.g. `"net-*" = "@net-team"`).
+    /// A case may match several patterns and then has several owners.
+    /// 用例的负责人，以用例名称通配符为键（例如 `"net-*" = "@net-team"`）。
+    /// 一个用例可以匹配多个模式，此时它有多个负责人。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub owners: BTreeMap<String, String>,
+
+    /// Environment variables set for the processes of every case (`[env]`). A case's own
+    /// `env` takes precedence for the variables both set.
+    /// 为每个用例的进程设置的环境变量（`[env]`）。对于两者都设置的变量，用例自身的 `env` 优先。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+
+    /// A command generating more cases when the matrix is loaded (`[generator]`).
+    /// 加载矩阵时生成更多用例的命令（`[generator]`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<CaseGenerator>,
+
+    /// Axes whose cartesian product is added to the cases when the matrix is loaded
+    /// (`[matrix]`), e.g. `features = ["", "a", "a,b"]` and `toolchain = ["stable", "nightly"]`.
+    /// 加载矩阵时其笛卡尔积会被加入用例的维度（`[matrix]`），例如 `features = ["", "a", "a,b"]` 和
+    /// `toolchain = ["stable", "nightly"]`。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixAxes>,
+
+    /// What happens when several cases have the same name, e.g. a listed case and a
+    /// generated one: an error (the default), keep the first or the last of them, or
+    /// rename the later ones with a numeric suffix.
+    /// 当多个用例同名时（例如列出的用例与生成的用例同名）的处理方式：报错（默认）、
+    /// 保留其中第一个或最后一个，或者为后出现的用例加上数字后缀重命名。
+    #[serde(default, skip_serializing_if = "DuplicatePolicy::is_error")]
+    pub on_duplicate: DuplicatePolicy,
+
+    /// What happens to cases whose `components` are missing from their toolchain and
+    /// were not installed: the run fails before starting (the default), or they are skipped.
+    /// 当用例的 `components` 在其工具链中缺失且未被安装时的处理方式：运行在开始前失败（默认），或跳过这些用例。
+    #[serde(default, skip_serializing_if = "MissingComponentPolicy::is_fail")]
+    pub on_missing_component: MissingComponentPolicy,
+
+    /// The `timeout_secs` and `retries` of the cases that set neither themselves nor
+    /// through one of their tags (`[defaults]`).
+    /// 用例自身和其标签都未设置时使用的 `timeout_secs` 和 `retries`（`[defaults]`）。
+    #[serde(default, skip_serializing_if = "CaseDefaults::is_empty")]
+    pub defaults: CaseDefaults,
+
+    /// The `timeout_secs` and `retries` of the cases with a tag, keyed by the tag
+    /// (`[tag_defaults.slow]`), for the cases that do not set them themselves.
+    /// 带有某个标签的用例的 `timeout_secs` 和 `retries`，以标签为键（`[tag_defaults.slow]`），
+    /// 用于未自行设置它们的用例。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tag_defaults: BTreeMap<String, CaseDefaults>,
+
+    /// The columns of the console summary and the HTML results table, in order, e.g.
+    /// `["status", "name", "target", "duration", "annotation:memory"]`. Empty keeps the
+    /// default columns.
+    /// 控制台摘要和 HTML 结果表的列（按顺序），例如 `["status", "name", "target", "duration", "annotation:memory"]`。
+    /// 为空时保留默认列。
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub summary_columns: Vec<SummaryColumn>,
+
     /// A vector containing all the test cases to be potentially executed.
-    /// 一个包含所有可能被执行的测试用例的向量。
+    /// May be empty (`cases = []`) when a generator or `[matrix]` provides the cases.
+    /// 一个包含所有可能被执行的测试用例的向量。当由生成器或 `[matrix]` 提供用例时可以为空（`cases = []`）。
     pub cases: Vec<TestCase>,
 }
 
-/// Loads a test matrix configuration from a file path.
-/// 从文件路径加载测试矩阵配置。
+impl Default for TestMatrix {
+    fn default() -> Self {
+        Self {
+            language: default_language(),
+            fast_fail: false,
+            concurrency_groups: BTreeMap::new(),
+            anomaly_factor: default_anomaly_factor(),
+            force_c_locale: false,
+            transient_build_retries: default_transient_build_retries(),
+            collect_core_dumps: false,
+            prefer_nextest: false,
+            prebuild_dependencies: false,
+            max_load: None,
+            max_total_memory_gb: None,
+            owners: BTreeMap::new(),
+            env: BTreeMap::new(),
+            generator: None,
+            matrix: None,
+            on_duplicate: DuplicatePolicy::Error,
+            on_missing_component: MissingComponentPolicy::Fail,
+            defaults: CaseDefaults::default(),
+            tag_defaults: BTreeMap::new(),
+            summary_columns: Vec::new(),
+            cases: vec![],
+        }
+    }
+}
+
+impl TestMatrix {
+    /// Gets the maximum number of cases of a concurrency group that may run at once.
+    /// 获取一个并发组中可同时运行的最大用例数。
+    pub fn group_limit(&self, group: &str) -> usize {
+        self.concurrency_groups
+            .get(group)
+            .map(|g| g.max_concurrent.max(1))
+            .unwrap_or(1)
+    }
+
+    /// Checks that `cargo_unstable_flags` are `-Z` flags of cases built with a nightly
+    /// toolchain, since stable cargo rejects them.
+    /// 检查 `cargo_unstable_flags` 是否为使用 nightly 工具链构建的用例的 `-Z` 标志，因为稳定版 cargo 会拒绝它们。
+    pub fn validate_unstable_flags(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_unstable_flags)
+    }
+
+    /// Checks that no case asks for zero `shards`, which would run none of its tests.
+    /// 检查没有用例将 `shards` 设置为零，否则其测试一个都不会运行。
+    pub fn validate_shards(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_shards)
+    }
+
+    /// Checks that `max_load` and `max_total_memory_gb` are positive, if set.
+    /// 检查 `max_load` 和 `max_total_memory_gb`（如果设置）为正数。
+    pub fn validate_resource_ceiling(&self) -> Result<()> {
+        for (key, value) in [("max_load", self.max_load), ("max_total_memory_gb", self.max_total_memory_gb)] {
+            if let Some(value) = value
+                && !(value.is_finite() && value > 0.0)
+            {
+                bail!("`{key} = {value}` must be a positive number");
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every `sandbox` is set on a test case that does not need the network.
+    /// 检查每个 `sandbox` 都设置在不需要网络的测试用例上。
+    pub fn validate_sandbox(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_sandbox)
+    }
+
+    /// Checks that every fixture is staged inside the project directory.
+    /// 检查每个 fixture 都被放置在项目目录内。
+    pub fn validate_fixtures(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_fixtures)
+    }
+
+    /// Checks that `fuzz` is only set on `kind = "fuzz-smoke"` cases, with bounds above zero.
+    /// 检查 `fuzz` 只设置在 `kind = "fuzz-smoke"` 的用例上，且其上限大于零。
+    pub fn validate_fuzz(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_fuzz)
+    }
+
+    /// Checks that every `env` of a case, including the matrix `[env]` merged into it,
+    /// names variables that can be set on a process.
+    /// 检查每个用例的 `env`（包括合并到其中的矩阵 `[env]`）所命名的变量都可以设置到进程上。
+    pub fn validate_env(&self) -> Result<()> {
+        self.cases.iter().try_for_each(TestCase::validate_env)
+    }
+
+    /// Resolves cases with the same name according to `on_duplicate`. Names are compared
+    /// after generated cases were added, so a generated case can clash with a listed one.
+    /// Renamed cases take the first free `<name>-<n>`, starting at 2, and keep that name
+    /// in every report.
+    ///
+    /// # Returns
+    /// The names of the duplicated cases, or an error listing them under the `error` policy
+    ///
+    /// 根据 `on_duplicate` 处理同名用例。名称在加入生成的用例之后进行比较，因此生成的用例可能与列出的用例冲突。
+    /// 被重命名的用例使用从 2 开始的第一个空闲的 `<name>-<n>`，并在所有报告中保持该名称。
+    /// 返回重复用例的名称；在 `error` 策略下返回列出这些名称的错误。
+    pub fn resolve_duplicates(&mut self) -> Result<Vec<String>> {
+        let mut seen = BTreeMap::new();
+        let mut duplicates = Vec::new();
+        for (index, case) in self.cases.iter().enumerate() {
+            if seen.contains_key(case.name.as_str()) {
+                if !duplicates.contains(&case.name) {
+                    duplicates.push(case.name.clone());
+                }
+            } else {
+                seen.insert(case.name.as_str(), index);
+            }
+        }
+        if duplicates.is_empty() {
+            return Ok(duplicates);
+        }
+
+        match self.on_duplicate {
+            DuplicatePolicy::Error => bail!(
+                "Several cases are named {} (set `on_duplicate` to \"first\", \"last\" or \"rename\" to allow it)",
+                duplicates.iter().map(|name| format!("'{name}'")).collect::<Vec<_>>().join(", ")
+            ),
+            DuplicatePolicy::First => {
+                let mut kept = BTreeSet::new();
+                self.cases.retain(|case| kept.insert(case.name.clone()));
+            }
+            DuplicatePolicy::Last => {
+                let mut last: BTreeMap<String, TestCase> = BTreeMap::new();
+                for case in &self.cases {
+                    last.insert(case.name.clone(), case.clone());
+                }
+                let mut placed = BTreeSet::new();
+                let cases = std::mem::take(&mut self.cases);
+                self.cases = cases
+                    .into_iter()
+                    .filter(|case| placed.insert(case.name.clone()))
+                    .map(|case| last.remove(&case.name).unwrap_or(case))
+                    .collect();
+            }
+            DuplicatePolicy::Rename => {
+                let mut taken: BTreeSet<String> = self.cases.iter().map(|case| case.name.clone()).collect();
+                let mut first = BTreeSet::new();
+                for case in &mut self.cases {
+                    if first.insert(case.name.clone()) {
+                        continue;
+                    }
+                    let name = (2..)
+                        .map(|n| format!("{}-{n}", case.name))
+                        .find(|name| !taken.contains(name))
+                        .unwrap_or_default();
+                    taken.insert(name.clone());
+                    case.name = name;
+                }
+            }
+        }
+        Ok(duplicates)
+    }
+
+    /// Gives every case without an `id` the id derived from its definition. Cases defined
+    /// the same way take the first free `<id>-<n>`, starting at 2, in the order of the matrix.
+    ///
+    /// # Returns
+    /// An error if an `id` is empty or set on several cases
+    ///
+    /// 为每个没有 `id` 的用例赋予由其定义派生的 ID。定义相同的用例按矩阵中的顺序使用从 2 开始的第一个空闲的 `<id>-<n>`。
+    /// 如果某个 `id` 为空或被多个用例设置，则返回错误。
+    pub fn assign_case_ids(&mut self) -> Result<()> {
+        let mut explicit: BTreeMap<String, &str> = BTreeMap::new();
+        for case in &self.cases {
+            let Some(id) = &case.id else { continue };
+            if id.trim().is_empty() {
+                bail!("Case '{}' has an empty `id`", case.name);
+            }
+            if let Some(other) = explicit.insert(id.clone(), &case.name) {
+                bail!("Cases '{}' and '{}' have the same id '{}'", other, case.name, id);
+            }
+        }
+        let mut taken: BTreeSet<String> = explicit.into_keys().collect();
+        for case in &mut self.cases {
+            if case.id.is_some() {
+                continue;
+            }
+            let derived = case.derived_id();
+            let id = if taken.contains(&derived) {
+                (2..)
+                    .map(|n| format!("{derived}-{n}"))
+                    .find(|id| !taken.contains(id))
+                    .unwrap_or_default()
+            } else {
+                derived
+            };
+            taken.insert(id.clone());
+            case.id = Some(id);
+        }
+        Ok(())
+    }
+
+    /// Fills the `timeout_secs` and `retries` the cases leave unset. A case's own value
+    /// wins; otherwise the first of its tags, in the case's order, with a value in
+    /// `tag_defaults`; otherwise `defaults`. The matrix `[env]` is merged into the `env` of
+    /// every case the same way, variable by variable.
+    /// 填充用例未设置的 `timeout_secs` 和 `retries`。用例自身的值优先；其次是按用例中标签顺序、
+    /// 在 `tag_defaults` 中有值的第一个标签；最后是 `defaults`。矩阵的 `[env]` 以相同方式逐个变量合并到每个用例的 `env` 中。
+    pub fn apply_case_defaults(&mut self) {
+        for case in &mut self.cases {
+            let layers: Vec<&CaseDefaults> = case
+                .tags
+                .iter()
+                .filter_map(|tag| self.tag_defaults.get(tag))
+                .chain([&self.defaults])
+                .collect();
+            if case.timeout_secs.is_none() {
+                case.timeout_secs = layers.iter().find_map(|layer| layer.timeout_secs);
+            }
+            if case.retries.is_none() {
+                case.retries = layers.iter().find_map(|layer| layer.retries);
+            }
+            for (name, value) in &self.env {
+                case.env.entry(name.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+
+    /// Whether any case sets `package`, i.e. the matrix tests several packages of a workspace.
+    /// 是否有用例设置了 `package`，即矩阵测试工作区中的多个包。
+    pub fn uses_packages(&self) -> bool {
+        self.cases.iter().any(|case| case.package.is_some())
+    }
+
+    /// Makes every case test `package`, in place of the `package` it sets or the package at
+    /// the project root, e.g. to run the matrix against one member of a workspace (`--package`).
+    /// 使每个用例都测试 `package`，取代用例设置的 `package` 或项目根目录的包，例如针对工作区的某个成员运行矩阵（`--package`）。
+    pub fn override_package(&mut self, package: &str) {
+        for case in &mut self.cases {
+            case.package = Some(package.to_string());
+        }
+    }
+
+    /// Prefixes the name of every case with its package (`pkg::case`) when the matrix
+    /// uses packages, so the results of cases in different packages are told apart in
+    /// every report and in the history. Cases without `package` get the default package,
+    /// and `after` is updated to the new names. Names that already carry their package
+    /// prefix are kept, so applying this twice changes nothing.
+    ///
+    /// # Arguments
+    /// * `default_package` - The package tested when a case does not set `package`
+    ///
+    /// 当矩阵使用包时，为每个用例的名称加上其包名前缀（`pkg::case`），使不同包中用例的结果
+    /// 在所有报告和历史记录中都能区分开。未设置 `package` 的用例使用默认包，`after` 也会更新为新名称。
+    /// 已带有其包名前缀的名称保持不变，因此重复应用不会改变任何内容。
+    pub fn namespace_by_package(&mut self, default_package: &str) {
+        if !self.uses_packages() {
+            return;
+        }
+        let mut renamed = BTreeMap::new();
+        for case in &mut self.cases {
+            let package = case.package.get_or_insert_with(|| default_package.to_string());
+            let prefix = format!("{package}{PACKAGE_SEPARATOR}");
+            if package.is_empty() || case.name.starts_with(&prefix) {
+                continue;
+            }
+            let name = format!("{prefix}{}", case.name);
+            renamed.insert(std::mem::replace(&mut case.name, name.clone()), name);
+        }
+        for case in &mut self.cases {
+            for after in &mut case.after {
+                if let Some(name) = renamed.get(after) {
+                    *after = name.clone();
+                }
+            }
+        }
+    }
+
+    /// Checks that every `after` names a case of the matrix and that the hints do not
+    /// form a cycle, which would make the cases wait for each other forever.
+    /// 检查每个 `after` 是否都指向矩阵中的用例，并且这些提示不会形成环（否则用例会永远相互等待）。
+    pub fn validate_after(&self) -> Result<()> {
+        let cases: BTreeMap<&str, &TestCase> =
+            self.cases.iter().map(|case| (case.name.as_str(), case)).collect();
+        for case in &self.cases {
+            if let Some(unknown) = case.after.iter().find(|name| !cases.contains_key(name.as_str())) {
+                bail!(
+                    "Case '{}' is ordered after '{}', but no case has that name",
+                    case.name,
+                    unknown
+                );
+            }
+        }
+
+        // Depth-first search; a case reached again while still on the path closes a cycle.
+        fn visit<'a>(
+            name: &'a str,
+            cases: &BTreeMap<&'a str, &'a TestCase>,
+            path: &mut Vec<&'a str>,
+            done: &mut BTreeSet<&'a str>,
+        ) -> Result<()> {
+            if let Some(start) = path.iter().position(|entry| *entry == name) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(name);
+                bail!("The `after` hints form a cycle: {}", cycle.join(" -> "));
+            }
+            if !done.insert(name) {
+                return Ok(());
+            }
+            path.push(name);
+            for before in &cases[name].after {
+                visit(before, cases, path, done)?;
+            }
+            path.pop();
+            Ok(())
+        }
+
+        let mut done = BTreeSet::new();
+        for name in cases.keys() {
+            visit(name, &cases, &mut Vec::new(), &mut done)?;
+        }
+        Ok(())
+    }
+
+    /// Lists the `allow_failure` entries that have expired, or expire within `warn_days`
+    /// days of `today`, so they can be fixed or extended before failures count again.
+    /// 列出已到期或将在 `today` 之后 `warn_days` 天内到期的 `allow_failure` 条目，
+    /// 以便在失败重新计入之前修复或延期。
+    pub fn expiring_allowances(&self, today: NaiveDate, warn_days: i64) -> Vec<ExpiringAllowance> {
+        self.cases
+            .iter()
+            .flat_map(|case| {
+                case.allow_failure.iter().filter_map(move |entry| {
+                    let days_left = (entry.until? - today).num_days();
+                    (days_left <= warn_days).then(|| ExpiringAllowance {
+                        case: case.name.clone(),
+                        entry: entry.clone(),
+                        days_left,
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+/// How a matrix resolves cases with the same name (`on_duplicate`).
+/// 矩阵如何处理同名用例（`on_duplicate`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    /// Reject the matrix / 拒绝该矩阵
+    #[default]
+    Error,
+    /// Keep the first case of each name / 保留每个名称的第一个用例
+    First,
+    /// Keep the last case of each name, in the place of the first / 保留每个名称的最后一个用例，放在第一个用例的位置
+    Last,
+    /// Rename the later cases of each name to `<name>-2`, `<name>-3`, ... / 将每个名称后出现的用例重命名为 `<name>-2`、`<name>-3`……
+    Rename,
+}
+
+impl DuplicatePolicy {
+    /// Whether this is the default policy / 是否为默认策略
+    pub fn is_error(&self) -> bool {
+        *self == DuplicatePolicy::Error
+    }
+}
+
+/// What a run does with cases lacking a rustup component (`on_missing_component`).
+/// 运行如何处理缺少 rustup 组件的用例（`on_missing_component`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingComponentPolicy {
+    /// Fail the run before any case starts / 在任何用例开始之前使运行失败
+    #[default]
+    Fail,
+    /// Skip the cases lacking a component, with a note / 跳过缺少组件的用例并附上说明
+    Skip,
+}
+
+impl MissingComponentPolicy {
+    /// Whether this is the default policy / 是否为默认策略
+    pub fn is_fail(&self) -> bool {
+        *self == MissingComponentPolicy::Fail
+    }
+}
+
+/// Case settings given once for many cases, in `[defaults]` or `[tag_defaults.<tag>]`.
+/// 为多个用例统一给出的用例设置，位于 `[defaults]` 或 `[tag_defaults.<tag>]` 中。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CaseDefaults {
+    /// The default `timeout_secs` / 默认的 `timeout_secs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// The default `retries` / 默认的 `retries`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u8>,
+}
+
+impl CaseDefaults {
+    /// Whether no setting is given / 是否未给出任何设置
+    pub fn is_empty(&self) -> bool {
+        self.timeout_secs.is_none() && self.retries.is_none()
+    }
+}
+
+/// Settings for a concurrency group shared by several test cases.
+/// 由多个测试用例共享的并发组设置。
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConcurrencyGroup {
+    /// The maximum number of cases in this group that may run at the same time.
+    /// 此组中可同时运行的最大用例数。
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+/// The number of hex characters of a derived case id / 派生用例 ID 的十六进制字符数
+const CASE_ID_LEN: usize = 16;
+
+/// Separates the package from the case name when a matrix uses packages, e.g. `core::unit`.
+/// 矩阵使用包时分隔包名与用例名称的分隔符，例如 `core::unit`。
+pub const PACKAGE_SEPARATOR: &str = "::";
+
+/// The name of a case without its package prefix, e.g. `unit` for `core::unit`.
+/// 去掉包名前缀的用例名称，例如 `core::unit` 对应 `unit`。
+pub fn unqualified_name(name: &str) -> &str {
+    name.split_once(PACKAGE_SEPARATOR).map_or(name, |(_, name)| name)
+}
+
+/// Whether a toolchain name, as set in a case or reported by rustup, is a nightly one,
+/// e.g. `nightly`, `nightly-2024-06-01` or `nightly-x86_64-unknown-linux-gnu`.
+/// 判断工具链名称（用例中设置的或 rustup 报告的）是否为 nightly，
+/// 例如 `nightly`、`nightly-2024-06-01` 或 `nightly-x86_64-unknown-linux-gnu`。
+pub fn is_nightly_toolchain(toolchain: &str) -> bool {
+    toolchain.starts_with("nightly")
+}
+
+/// The file name of the test matrix looked up when no `--config` is given.
+/// 未提供 `--config` 时查找的测试矩阵文件名。
+pub const DEFAULT_CONFIG_FILE: &str = "TestMatrix.toml";
+
+/// Finds the test matrix by walking up from `start` through its parent directories,
+/// the way cargo finds `Cargo.toml`.
+///
+/// # Returns
+/// The path of the nearest `TestMatrix.toml`, or `None` if no ancestor has one
+///
+/// 从 `start` 开始向上遍历父目录查找测试矩阵，就像 cargo 查找 `Cargo.toml` 一样。
+pub fn discover_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(DEFAULT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// A key of the matrix file that matches no setting of the matrix, of a case or of
+/// a concurrency group, e.g. the typo `no_default_feature`.
+/// 矩阵文件中与矩阵、用例或并发组的任何设置都不匹配的键，例如拼写错误的 `no_default_feature`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// Where the key is: empty for the top level, otherwise e.g. `cases[2] ("fast")` / 键所在的位置：顶层为空，否则例如 `cases[2] ("fast")`
+    pub location: String,
+    /// The unknown key / 未知的键
+    pub key: String,
+    /// The closest valid key, if it is close enough to be a likely typo / 最接近的有效键（如果足够接近，可能是拼写错误）
+    pub suggestion: Option<&'static str>,
+}
+
+impl std::fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown key `{}`", self.key)?;
+        if !self.location.is_empty() {
+            write!(f, " in {}", self.location)?;
+        }
+        if let Some(suggestion) = self.suggestion {
+            write!(f, " (did you mean `{suggestion}`?)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the keys of a parsed matrix file that match no setting: at the top level, in
+/// each case, in each concurrency group, in the generator, in `[matrix]` and in the case defaults.
+/// 查找已解析的矩阵文件中与任何设置都不匹配的键：包括顶层、每个用例、每个并发组、生成器、`[matrix]` 和用例默认值中的键。
+pub fn find_unknown_keys(table: &toml::Table) -> Vec<UnknownKey> {
+    let mut unknown = unknown_keys_in(table, struct_fields::<TestMatrix>(), String::new());
+    if let Some(toml::Value::Array(cases)) = table.get("cases") {
+        for (index, case) in cases.iter().enumerate() {
+            if let toml::Value::Table(case) = case {
+                let location = match case.get("name").and_then(toml::Value::as_str) {
+                    Some(name) => format!("cases[{index}] (\"{name}\")"),
+                    None => format!("cases[{index}]"),
+                };
+                unknown.extend(unknown_keys_in(case, struct_fields::<TestCase>(), location));
+            }
+        }
+    }
+    if let Some(toml::Value::Table(groups)) = table.get("concurrency_groups") {
+        for (name, group) in groups {
+            if let toml::Value::Table(group) = group {
+                let location = format!("concurrency_groups.{name}");
+                unknown.extend(unknown_keys_in(group, struct_fields::<ConcurrencyGroup>(), location));
+            }
+        }
+    }
+    if let Some(toml::Value::Table(generator)) = table.get("generator") {
+        unknown.extend(unknown_keys_in(generator, struct_fields::<CaseGenerator>(), "[generator]".to_string()));
+    }
+    if let Some(toml::Value::Table(axes)) = table.get("matrix") {
+        unknown.extend(unknown_keys_in(axes, struct_fields::<MatrixAxes>(), "[matrix]".to_string()));
+        if let Some(toml::Value::Array(exclude)) = axes.get("exclude") {
+            for (index, filter) in exclude.iter().enumerate() {
+                if let toml::Value::Table(filter) = filter {
+                    let location = format!("matrix.exclude[{index}]");
+                    unknown.extend(unknown_keys_in(filter, struct_fields::<AxisFilter>(), location));
+                }
+            }
+        }
+    }
+    if let Some(toml::Value::Table(defaults)) = table.get("defaults") {
+        unknown.extend(unknown_keys_in(defaults, struct_fields::<CaseDefaults>(), "[defaults]".to_string()));
+    }
+    if let Some(toml::Value::Table(tags)) = table.get("tag_defaults") {
+        for (tag, defaults) in tags {
+            if let toml::Value::Table(defaults) = defaults {
+                let location = format!("tag_defaults.{tag}");
+                unknown.extend(unknown_keys_in(defaults, struct_fields::<CaseDefaults>(), location));
+            }
+        }
+    }
+    unknown
+}
+
+fn unknown_keys_in(table: &toml::Table, fields: &'static [&'static str], location: String) -> Vec<UnknownKey> {
+    table
+        .keys()
+        .filter(|key| !fields.contains(&key.as_str()))
+        .map(|key| UnknownKey {
+            location: location.clone(),
+            key: key.clone(),
+            suggestion: nearest_field(key, fields),
+        })
+        .collect()
+}
+
+/// The field closest to `key` by edit distance, if at most a third of it differs.
+fn nearest_field(key: &str, fields: &'static [&'static str]) -> Option<&'static str> {
+    let max_distance = (key.chars().count() / 3).max(1);
+    fields
+        .iter()
+        .map(|field| (edit_distance(key, field), *field))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, field)| field)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The field names of a struct deriving `Deserialize`, as serde passes them to the
+/// deserializer. Keeping the list in the struct itself means new settings are never
+/// reported as unknown.
+pub(crate) fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    use serde::de::{self, Visitor};
+
+    struct FieldsProbe<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> de::Deserializer<'de> for FieldsProbe<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields probed"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+            enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsProbe(&mut fields));
+    fields
+}
+
+/// Loads a test matrix configuration from a file path, rejecting unknown keys.
+/// The cases expanded from `[matrix]` and those of a `[generator]` are appended to those
+/// listed in the file, in that order.
+/// 从文件路径加载测试矩阵配置，拒绝未知的键。由 `[matrix]` 展开的用例和 `[generator]` 生成的用例
+/// 会依次追加到文件中列出的用例之后。
 pub fn load_test_matrix<P: AsRef<Path>>(path: P) -> Result<TestMatrix> {
+    load_test_matrix_with(path, false).map(|(test_matrix, _)| test_matrix)
+}
+
+/// Loads a test matrix configuration from a file path. Unknown keys are an error
+/// listing all of them, unless `lenient` is set: they are then ignored and returned.
+///
+/// # Returns
+/// The matrix and the unknown keys that were ignored
+///
+/// 从文件路径加载测试矩阵配置。未知的键会导致列出所有未知键的错误，除非设置了 `lenient`：
+/// 此时它们会被忽略并返回。
+pub fn load_test_matrix_with<P: AsRef<Path>>(path: P, lenient: bool) -> Result<(TestMatrix, Vec<UnknownKey>)> {
     let content = fs::read_to_string(&path)
         .with_context(|| format!("Failed to read config file: {}", path.as_ref().display()))?;
     
-    let test_matrix = toml::from_str(&content)
+    let mut table: toml::Table = toml::from_str(&content)
+        .with_context(|| "Failed to parse TOML configuration")?;
+    let unknown_keys = find_unknown_keys(&table);
+    if !unknown_keys.is_empty() {
+        if !lenient {
+            let keys: Vec<String> = unknown_keys.iter().map(|key| format!("  - {key}")).collect();
+            bail!(
+                "The configuration has unknown keys (use --lenient to ignore them):\n{}",
+                keys.join("\n")
+            );
+        }
+        remove_unknown_keys(&mut table);
+    }
+    let mut test_matrix = TestMatrix::deserialize(toml::Value::Table(table))
         .with_context(|| "Failed to parse TOML configuration")?;
+    if let Some(axes) = &test_matrix.matrix {
+        let expanded = axes.expand();
+        test_matrix.cases.extend(expanded);
+    }
+    if let Some(generator) = &test_matrix.generator {
+        let working_dir = path
+            .as_ref()
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let generated = generate_cases(generator, working_dir)?;
+        test_matrix.cases.extend(generated);
+    }
+    test_matrix.resolve_duplicates()?;
+    test_matrix.assign_case_ids()?;
+    test_matrix.apply_case_defaults();
+    test_matrix.validate_after()?;
+    test_matrix.validate_unstable_flags()?;
+    test_matrix.validate_shards()?;
+    test_matrix.validate_resource_ceiling()?;
+    test_matrix.validate_sandbox()?;
+    test_matrix.validate_fixtures()?;
+    test_matrix.validate_fuzz()?;
+    test_matrix.validate_env()?;
     
-    Ok(test_matrix)
+    Ok((test_matrix, unknown_keys))
+}
+
+/// Removes the keys [`find_unknown_keys`] reports from a parsed matrix file.
+fn remove_unknown_keys(table: &mut toml::Table) {
+    let retain = |table: &mut toml::Table, fields: &'static [&'static str]| {
+        table.retain(|key, _| fields.contains(&key));
+    };
+    retain(table, struct_fields::<TestMatrix>());
+    if let Some(toml::Value::Array(cases)) = table.get_mut("cases") {
+        for case in cases.iter_mut().filter_map(toml::Value::as_table_mut) {
+            retain(case, struct_fields::<TestCase>());
+        }
+    }
+    if let Some(toml::Value::Table(groups)) = table.get_mut("concurrency_groups") {
+        for group in groups.iter_mut().filter_map(|(_, group)| group.as_table_mut()) {
+            retain(group, struct_fields::<ConcurrencyGroup>());
+        }
+    }
+    if let Some(toml::Value::Table(generator)) = table.get_mut("generator") {
+        retain(generator, struct_fields::<CaseGenerator>());
+    }
+    if let Some(toml::Value::Table(axes)) = table.get_mut("matrix") {
+        retain(axes, struct_fields::<MatrixAxes>());
+        if let Some(toml::Value::Array(exclude)) = axes.get_mut("exclude") {
+            for filter in exclude.iter_mut().filter_map(toml::Value::as_table_mut) {
+                retain(filter, struct_fields::<AxisFilter>());
+            }
+        }
+    }
+    if let Some(toml::Value::Table(defaults)) = table.get_mut("defaults") {
+        retain(defaults, struct_fields::<CaseDefaults>());
+    }
+    if let Some(toml::Value::Table(tags)) = table.get_mut("tag_defaults") {
+        for defaults in tags.iter_mut().filter_map(|(_, defaults)| defaults.as_table_mut()) {
+            retain(defaults, struct_fields::<CaseDefaults>());
+        }
+    }
 }
 
 fn default_language() -> String {
     "en".to_string()
+}
+
+fn default_anomaly_factor() -> f64 {
+    3.0
+}
+
+pub(crate) fn default_transient_build_retries() -> u8 {
+    2
+}
+
+fn default_sandbox_network() -> bool {
+    true
+}
+
+fn default_max_concurrent() -> usize {
+    1
 } 
\ No newline at end of file
@@ -0,0 +1,256 @@
+//! # Configuration Editing Module / 配置编辑模块
+//!
+//! This module edits a test matrix file in place, for the commands that write to a
+//! `TestMatrix.toml` instead of generating a new one. Unlike loading the matrix and
+//! serializing it again, it keeps everything the user wrote that the model does not
+//! hold: comments, the order of keys, blank lines, and whether the cases are written as
+//! `[[cases]]` tables or as an inline `cases = [...]` array.
+//!
+//! 此模块就地编辑测试矩阵文件，用于写入 `TestMatrix.toml` 而不是生成新文件的命令。
+//! 与加载矩阵后重新序列化不同，它会保留用户编写的、模型中不包含的所有内容：注释、键的顺序、空行，
+//! 以及用例是写成 `[[cases]]` 表还是内联的 `cases = [...]` 数组。
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike, Value};
+
+use super::{TestCase, TestMatrix, struct_fields};
+use crate::infra::fs::write_atomic;
+
+/// The keys every case is written with, even when they hold their default.
+/// 每个用例都会写出的键，即使它们是默认值。
+const REQUIRED_KEYS: &[&str] = &["name", "features", "no_default_features"];
+
+/// A test matrix file open for editing, keeping its comments and formatting.
+/// 一个打开以供编辑的测试矩阵文件，保留其注释和格式。
+#[derive(Debug, Clone)]
+pub struct MatrixDocument {
+    document: DocumentMut,
+}
+
+impl MatrixDocument {
+    /// Parses the content of a matrix file.
+    /// 解析矩阵文件的内容。
+    pub fn parse(content: &str) -> Result<Self> {
+        let document = content
+            .parse::<DocumentMut>()
+            .context("Failed to parse TOML configuration")?;
+        Ok(Self { document })
+    }
+
+    /// Reads a matrix file for editing.
+    /// 读取矩阵文件以供编辑。
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    /// Writes the edited matrix back, replacing the file at once.
+    /// 写回编辑后的矩阵，一次性替换文件。
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, self.to_string())
+            .with_context(|| format!("Failed to write config file: {}", path.display()))
+    }
+
+    /// Reads the edited document as a matrix, to check that an edit kept it valid.
+    /// Unlike [`super::load_test_matrix`], generated cases and defaults are not applied.
+    ///
+    /// 将编辑后的文档读取为矩阵，以检查编辑是否使其保持有效。
+    /// 与 [`super::load_test_matrix`] 不同，不会应用生成的用例和默认值。
+    pub fn to_matrix(&self) -> Result<TestMatrix> {
+        toml::from_str(&self.document.to_string()).context("Failed to parse TOML configuration")
+    }
+
+    /// The names of the cases listed in the file, in order.
+    /// 文件中列出的用例名称，按顺序排列。
+    pub fn case_names(&self) -> Vec<String> {
+        let name =
+            |case: &dyn TableLike| case.get("name").and_then(Item::as_str).map(str::to_string);
+        match self.document.get("cases") {
+            Some(Item::ArrayOfTables(cases)) => {
+                cases.iter().filter_map(|case| name(case)).collect()
+            }
+            Some(Item::Value(Value::Array(cases))) => cases
+                .iter()
+                .filter_map(Value::as_inline_table)
+                .filter_map(|case| name(case))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Appends a case, in the style of the cases already in the file. Only its name,
+    /// features, `no_default_features` and the fields that differ from their defaults are
+    /// written.
+    ///
+    /// # Returns
+    /// An error if a case with the same name already exists
+    ///
+    /// 以文件中已有用例的风格追加一个用例。只写出其名称、features、`no_default_features`
+    /// 以及与默认值不同的字段。
+    pub fn add_case(&mut self, case: &TestCase) -> Result<()> {
+        if self.case_names().contains(&case.name) {
+            bail!("A case named '{}' already exists", case.name);
+        }
+        let mut table = case_table(case)?;
+        match self.document.get_mut("cases") {
+            Some(Item::Value(Value::Array(cases))) => {
+                let mut inline = table.into_inline_table();
+                inline.fmt();
+                let mut case = Value::InlineTable(inline);
+                // Lay the new case out like the one before it, e.g. on a line of its own.
+                match cases.iter().last() {
+                    Some(last) => *case.decor_mut() = last.decor().clone(),
+                    None => case.decor_mut().clear(),
+                }
+                cases.push_formatted(case);
+            }
+            Some(Item::ArrayOfTables(cases)) => {
+                table.decor_mut().set_prefix("\n");
+                cases.push(table);
+            }
+            Some(_) => bail!("`cases` is neither an array of tables nor an array"),
+            None => {
+                if !self.document.is_empty() {
+                    table.decor_mut().set_prefix("\n");
+                }
+                let mut cases = ArrayOfTables::new();
+                cases.push(table);
+                self.document.insert("cases", Item::ArrayOfTables(cases));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes a case and the comments written above it.
+    ///
+    /// # Returns
+    /// Whether a case with that name was found
+    ///
+    /// 删除一个用例及其上方的注释。返回是否找到了该名称的用例。
+    pub fn remove_case(&mut self, name: &str) -> bool {
+        let Ok(index) = self.case_index(name) else {
+            return false;
+        };
+        match self.document.get_mut("cases") {
+            Some(Item::ArrayOfTables(cases)) => {
+                cases.remove(index);
+            }
+            Some(Item::Value(Value::Array(cases))) => {
+                cases.remove(index);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Sets a field of a case, keeping the comments around it. A field the case did not
+    /// have is appended after its other fields.
+    ///
+    /// # Returns
+    /// An error if there is no such case, or if the field is not a case field
+    ///
+    /// 设置用例的一个字段，保留其周围的注释。用例原本没有的字段会追加到其他字段之后。
+    pub fn set_field(&mut self, case: &str, field: &str, value: impl Into<Value>) -> Result<()> {
+        check_field(field)?;
+        let case_table = self.case_mut(case)?;
+        let mut value = value.into();
+        match case_table.get_mut(field).and_then(Item::as_value_mut) {
+            Some(existing) => {
+                // Keep the spacing and any trailing comment of the old value.
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            None => {
+                case_table.insert(field, Item::Value(value));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes a field of a case, so it takes its default again.
+    ///
+    /// # Returns
+    /// Whether the case had the field; an error if there is no such case, or if the field
+    /// is a required one
+    ///
+    /// 删除用例的一个字段，使其恢复默认值。返回用例是否有该字段；若没有该用例或该字段为必填字段则返回错误。
+    pub fn remove_field(&mut self, case: &str, field: &str) -> Result<bool> {
+        if REQUIRED_KEYS.contains(&field) {
+            bail!("`{field}` is required and cannot be removed");
+        }
+        Ok(self.case_mut(case)?.remove(field).is_some())
+    }
+
+    /// Sets the comment written on the lines above a case, e.g. to explain why it exists.
+    /// Each line of `comment` becomes a `#` comment line. Only `[[cases]]` tables have
+    /// room for such a comment; for inline cases this does nothing.
+    ///
+    /// 设置用例上方各行的注释，例如说明其存在的原因。`comment` 的每一行都会成为一行 `#` 注释。
+    /// 只有 `[[cases]]` 表才有放置此类注释的位置；对于内联用例，此操作不起作用。
+    pub fn set_case_comment(&mut self, case: &str, comment: &str) -> Result<()> {
+        let prefix: String = comment.lines().map(|line| format!("# {line}\n")).collect();
+        let index = self.case_index(case)?;
+        if let Some(Item::ArrayOfTables(cases)) = self.document.get_mut("cases")
+            && let Some(table) = cases.get_mut(index)
+        {
+            table.decor_mut().set_prefix(format!("\n{prefix}"));
+        }
+        Ok(())
+    }
+
+    fn case_index(&self, case: &str) -> Result<usize> {
+        self.case_names()
+            .iter()
+            .position(|name| name == case)
+            .ok_or_else(|| anyhow!("No case named '{case}'"))
+    }
+
+    fn case_mut(&mut self, case: &str) -> Result<&mut dyn TableLike> {
+        let index = self.case_index(case)?;
+        let table: Option<&mut dyn TableLike> = match self.document.get_mut("cases") {
+            Some(Item::ArrayOfTables(cases)) => {
+                cases.get_mut(index).map(|case| case as &mut dyn TableLike)
+            }
+            Some(Item::Value(Value::Array(cases))) => cases
+                .get_mut(index)
+                .and_then(Value::as_inline_table_mut)
+                .map(|case| case as &mut dyn TableLike),
+            _ => None,
+        };
+        table.ok_or_else(|| anyhow!("No case named '{case}'"))
+    }
+}
+
+impl fmt::Display for MatrixDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.document.fmt(f)
+    }
+}
+
+fn check_field(field: &str) -> Result<()> {
+    if !struct_fields::<TestCase>().contains(&field) {
+        bail!("`{field}` is not a field of a case");
+    }
+    Ok(())
+}
+
+/// The table of a case, with its fields in the order of [`TestCase`] and without those
+/// that hold their default.
+fn case_table(case: &TestCase) -> Result<Table> {
+    let content = toml::to_string(case).context("Failed to serialize the case")?;
+    let mut table = content
+        .parse::<DocumentMut>()
+        .context("Failed to serialize the case")?
+        .as_table()
+        .clone();
+    let values = toml::Table::try_from(case).context("Failed to serialize the case")?;
+    let defaults =
+        toml::Table::try_from(TestCase::default()).context("Failed to serialize the case")?;
+    table.retain(|key, _| REQUIRED_KEYS.contains(&key) || values.get(key) != defaults.get(key));
+    table.decor_mut().clear();
+    Ok(table)
+}
@@ -0,0 +1,97 @@
+//! # Run Context Module / 运行上下文模块
+//!
+//! This module defines [`RunContext`], the settings of a run that would otherwise be read
+//! from process-wide state: the language of its messages and the platform its cases are
+//! planned for. It is passed to the planner and carried by the execution context, so a
+//! program using the library can run two matrices at once, e.g. in different languages,
+//! without one changing the global locale under the other. The platform also decides
+//! which target triples the run can execute tests for.
+//!
+//! 此模块定义了 [`RunContext`]，即一次运行中原本需要从进程级状态读取的设置：其消息的语言，
+//! 以及其用例所针对的平台。它被传递给计划器并由执行上下文携带，因此使用本库的程序可以同时运行
+//! 两个矩阵（例如使用不同的语言），而不会有一个在另一个运行时更改全局语言环境。
+//! 该平台还决定了本次运行可以为哪些目标三元组执行测试。
+
+use std::env;
+
+/// The language and platform of a run.
+/// 一次运行的语言和平台。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunContext {
+    /// The locale of the run's messages, e.g. "en" or "zh-CN" / 运行消息的语言环境，例如 "en" 或 "zh-CN"
+    pub locale: String,
+    /// The operating system the cases are planned for, as in `std::env::consts::OS` / 用例所针对的操作系统，与 `std::env::consts::OS` 相同
+    pub os: String,
+    /// The architecture the cases are planned for, as in `std::env::consts::ARCH` / 用例所针对的架构，与 `std::env::consts::ARCH` 相同
+    pub arch: String,
+}
+
+impl RunContext {
+    /// Creates the context of a run in the given locale on the host platform.
+    /// 创建在主机平台上使用给定语言环境的运行上下文。
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+        }
+    }
+
+    /// Creates the context of a run in the locale currently set for the process, e.g. by
+    /// [`crate::init`], on the host platform.
+    /// 创建使用当前为进程设置的语言环境（例如由 [`crate::init`] 设置）并在主机平台上的运行上下文。
+    pub fn current() -> Self {
+        Self::new(rust_i18n::locale().to_string())
+    }
+
+    /// Whether binaries built for a target triple run natively on the platform of the run,
+    /// i.e. the triple names the same architecture and operating system. Triples without
+    /// an operating system, like `wasm32-unknown-unknown` or `thumbv7em-none-eabi`, never do.
+    /// 为某个目标三元组构建的二进制文件能否在本次运行的平台上原生运行，即该三元组指定了相同的架构和操作系统。
+    /// 没有操作系统的三元组（如 `wasm32-unknown-unknown` 或 `thumbv7em-none-eabi`）永远不能。
+    pub fn can_run(&self, target: &str) -> bool {
+        triple_arch(target) == self.arch && triple_os(target).is_some_and(|os| os == self.os)
+    }
+}
+
+/// The architecture of a target triple, named as in `std::env::consts::ARCH`.
+fn triple_arch(target: &str) -> &str {
+    let arch = target.split('-').next().unwrap_or_default();
+    match arch {
+        "i386" | "i586" | "i686" => "x86",
+        "arm64" | "arm64e" => "aarch64",
+        _ if arch.starts_with("arm") || arch.starts_with("thumb") => "arm",
+        _ if arch.starts_with("riscv64") => "riscv64",
+        _ if arch.starts_with("riscv32") => "riscv32",
+        _ => arch,
+    }
+}
+
+/// The operating system of a target triple, named as in `std::env::consts::OS`.
+fn triple_os(target: &str) -> Option<&'static str> {
+    let parts: Vec<&str> = target.split('-').skip(1).collect();
+    let has = |name: &str| parts.iter().any(|part| part.starts_with(name));
+    // Android triples also name `linux`, so they are checked first.
+    [
+        ("android", "android"),
+        ("linux", "linux"),
+        ("darwin", "macos"),
+        ("ios", "ios"),
+        ("windows", "windows"),
+        ("freebsd", "freebsd"),
+        ("netbsd", "netbsd"),
+        ("openbsd", "openbsd"),
+        ("dragonfly", "dragonfly"),
+        ("illumos", "illumos"),
+        ("solaris", "solaris"),
+    ]
+    .into_iter()
+    .find(|(name, _)| has(name))
+    .map(|(_, os)| os)
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self::current()
+    }
+}
@@ -0,0 +1,139 @@
+//! # Costs Module / 成本模块
+//!
+//! This module records what each case costs to keep in the matrix: the time spent
+//! building its tests, apart from running them, and the final size of its target
+//! directory. The costs are stored in the results and shown in the reports, so the most
+//! expensive feature combinations are easy to find.
+//!
+//! 此模块记录每个用例保留在矩阵中的成本：构建其测试所花费的时间（与运行分开），
+//! 以及其 target 目录的最终大小。成本会存储在结果中并显示在报告里，便于找出代价最高的 feature 组合。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::models::TestResult;
+
+/// The build time and disk usage of a case.
+/// 一个用例的构建时间和磁盘占用。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CaseCost {
+    /// The time spent building the tests, if they were built apart from running them.
+    /// Cases run by a single command (custom commands, nextest, tarpaulin) have none.
+    /// 构建测试所花费的时间（如果测试与运行分开构建）。由单个命令运行的用例
+    /// （自定义命令、nextest、tarpaulin）没有此项。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_duration: Option<Duration>,
+    /// The final size of the case's own target directory, in bytes / 用例自身 target 目录的最终大小（字节）
+    #[serde(default)]
+    pub target_bytes: u64,
+}
+
+impl CaseCost {
+    /// The time spent running the tests of a result, i.e. its duration without the build.
+    /// `None` if the build was not timed apart or the case did not run.
+    /// 运行结果中测试所花费的时间，即不含构建的耗时。如果构建未单独计时或用例未运行，则为 `None`。
+    pub fn run_duration(&self, result: &TestResult) -> Option<Duration> {
+        let build = self.build_duration?;
+        result
+            .get_duration()
+            .map(|total| total.saturating_sub(build))
+    }
+}
+
+/// The costs recorded during a run, shared by all case tasks.
+/// 运行期间记录的成本，由所有用例任务共享。
+#[derive(Debug, Clone, Default)]
+pub struct CostLog {
+    costs: Arc<Mutex<BTreeMap<String, CaseCost>>>,
+}
+
+impl CostLog {
+    /// Adds the time of a build to a case; a case built twice (e.g. to verify that it is
+    /// reproducible) counts both builds.
+    /// 将一次构建的时间累加到用例上；构建两次的用例（例如为了验证可复现性）会计入两次构建。
+    pub fn record_build(&self, case_name: &str, duration: Duration) {
+        let mut costs = self.costs.lock().unwrap_or_else(|e| e.into_inner());
+        let cost = costs.entry(case_name.to_string()).or_default();
+        cost.build_duration = Some(cost.build_duration.unwrap_or_default() + duration);
+    }
+
+    /// Records the size of a case's target directory, replacing an earlier one.
+    /// 记录用例 target 目录的大小，替换之前的记录。
+    pub fn record_target_size(&self, case_name: &str, bytes: u64) {
+        self.costs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(case_name.to_string())
+            .or_default()
+            .target_bytes = bytes;
+    }
+
+    /// Returns the costs recorded so far.
+    /// 返回目前已记录的成本。
+    pub fn snapshot(&self) -> BTreeMap<String, CaseCost> {
+        self.costs.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// How `--sort-by` orders the console summary, most expensive case first.
+/// `--sort-by` 对控制台摘要的排序方式，成本最高的用例在前。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummarySort {
+    /// By the size of the target directory / 按 target 目录的大小
+    Size,
+    /// By the build time / 按构建时间
+    BuildTime,
+}
+
+impl std::str::FromStr for SummarySort {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "size" => Ok(SummarySort::Size),
+            "build-time" => Ok(SummarySort::BuildTime),
+            other => Err(format!("expected `size` or `build-time`, found `{other}`")),
+        }
+    }
+}
+
+/// Orders results by their cost, most expensive first. Results without the cost in
+/// question keep their order after the others.
+/// 按成本对结果排序，成本最高的在前。没有相应成本的结果保持原有顺序排在其他结果之后。
+pub fn sort_by_cost<'a>(
+    results: &'a [TestResult],
+    costs: &BTreeMap<String, CaseCost>,
+    sort: SummarySort,
+) -> Vec<&'a TestResult> {
+    let mut sorted: Vec<&TestResult> = results.iter().collect();
+    sorted.sort_by_key(|result| {
+        let cost = costs.get(result.case_name());
+        let key = match sort {
+            SummarySort::Size => cost.map(|cost| u128::from(cost.target_bytes)),
+            SummarySort::BuildTime => cost
+                .and_then(|cost| cost.build_duration)
+                .map(|d| d.as_nanos()),
+        };
+        std::cmp::Reverse(key)
+    });
+    sorted
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 GiB`.
+/// 以二进制单位格式化字节数，例如 `1.5 GiB`。
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
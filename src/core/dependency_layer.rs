@@ -0,0 +1,137 @@
+//! # Dependency Layer Module / 依赖层模块
+//!
+//! This module implements `prebuild_dependencies`: before the cases fan out, the tests of
+//! every tested package are built once with the union of the cases' features into a
+//! shared target directory, `<state_dir>/deps-layer`. Each case's own build directory is
+//! then seeded with a copy of it, so cargo finds the dependencies already compiled and
+//! only builds the crate itself, and the dependencies whose features depend on the case's.
+//! The layer is kept between runs, so later runs only rebuild what changed.
+//!
+//! Only the default flow and nextest builds use the layer, and only for cases with the
+//! default toolchain and the host target, as the layer is built with those.
+//!
+//! 此模块实现 `prebuild_dependencies`：在展开用例之前，先使用所有用例 features 的并集，
+//! 将每个被测试包的测试构建一次到共享的 target 目录 `<state_dir>/deps-layer` 中。
+//! 然后每个用例自己的构建目录都以它的副本为起点，因此 cargo 会发现依赖已经编译好，
+//! 只需构建 crate 本身以及 features 取决于该用例的依赖。该层在多次运行之间保留，
+//! 因此之后的运行只会重新构建发生变化的部分。
+//!
+//! 只有默认流程和 nextest 的构建使用该层，并且仅限使用默认工具链和主机目标的用例，因为该层正是以此构建的。
+
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::core::config::{CaseKind, TestCase};
+use crate::infra::command;
+
+/// The directory of the layer, below the runner's state directory.
+/// 该层的目录，位于运行器的状态目录之下。
+pub const DEPS_LAYER_DIR: &str = "deps-layer";
+
+/// One `cargo test --no-run` building a package into the layer.
+/// 将一个包构建到该层中的一次 `cargo test --no-run`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerBuild {
+    /// The package to build; empty for the root package / 要构建的包；为空时表示根包
+    pub package: String,
+    /// Whether every case of the package disables the default features / 该包的所有用例是否都禁用了默认 features
+    pub no_default_features: bool,
+    /// The union of the features of the package's cases / 该包所有用例 features 的并集
+    pub features: Vec<String>,
+}
+
+/// Whether the build of a case can start from the layer: cases of the default flow (or
+/// nextest) without a custom toolchain or target.
+/// 用例的构建是否可以以该层为起点：不使用自定义工具链或目标的默认流程（或 nextest）用例。
+pub fn uses_layer(case: &TestCase) -> bool {
+    case.command.is_none()
+        && case.kind == CaseKind::Test
+        && case.toolchain.is_none()
+        && case.target.is_none()
+}
+
+/// Groups the cases using the layer by package and unites their features.
+/// Packages are built in name order, so the layer does not depend on the case order.
+///
+/// # Arguments
+/// * `cases` - The cases about to run
+/// * `default_package` - The package tested when a case does not set `package`
+///
+/// 按包对使用该层的用例进行分组，并合并它们的 features。包按名称顺序构建，因此该层不依赖于用例的顺序。
+pub fn layer_builds(cases: &[TestCase], default_package: &str) -> Vec<LayerBuild> {
+    let mut packages: BTreeMap<&str, (bool, BTreeSet<String>)> = BTreeMap::new();
+    for case in cases.iter().filter(|case| uses_layer(case)) {
+        let package = case.package.as_deref().unwrap_or(default_package);
+        let (no_default_features, features) = packages
+            .entry(package)
+            .or_insert_with(|| (true, BTreeSet::new()));
+        *no_default_features &= case.no_default_features;
+        features.extend(
+            case.features
+                .split([',', ' '])
+                .map(str::trim)
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string),
+        );
+    }
+    packages
+        .into_iter()
+        .map(|(package, (no_default_features, features))| LayerBuild {
+            package: package.to_string(),
+            no_default_features,
+            features: features.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Builds the layer in `<state_dir>/deps-layer`.
+///
+/// # Arguments
+/// * `project_root` - Path to the project root directory
+/// * `state_dir` - The runner's state directory
+/// * `builds` - The builds returned by [`layer_builds`]
+/// * `locale` - The locale of the build error shown when a build fails
+///
+/// # Returns
+/// The layer directory and how long it took to build, or the build output of the first
+/// failed build
+///
+/// 在 `<state_dir>/deps-layer` 中构建该层。返回该层目录及构建耗时，或第一个失败构建的输出。
+pub async fn build_layer(
+    project_root: &Path,
+    state_dir: &Path,
+    builds: &[LayerBuild],
+    locale: &str,
+) -> Result<(PathBuf, Duration)> {
+    let layer_dir = state_dir.join(DEPS_LAYER_DIR);
+    std::fs::create_dir_all(&layer_dir)
+        .with_context(|| format!("Failed to create {}", layer_dir.display()))?;
+    let start_time = Instant::now();
+    for build in builds {
+        let mut cmd = tokio::process::Command::new("cargo");
+        cmd.arg("test")
+            .arg("--no-run")
+            .arg("--message-format=json")
+            .arg("--target-dir")
+            .arg(&layer_dir);
+        if !build.package.is_empty() {
+            cmd.arg("-p").arg(&build.package);
+        }
+        if build.no_default_features {
+            cmd.arg("--no-default-features");
+        }
+        if !build.features.is_empty() {
+            cmd.arg("--features").arg(build.features.join(","));
+        }
+        cmd.kill_on_drop(true).current_dir(project_root);
+
+        let (status, output) =
+            command::spawn_and_capture_with_idle_timeout(cmd, None, locale).await;
+        if !status.context("Failed to run cargo")?.success() {
+            bail!("{}", command::format_build_error_output(&output, locale));
+        }
+    }
+    Ok((layer_dir, start_time.elapsed()))
+}
@@ -0,0 +1,88 @@
+//! # Error Module / 错误模块
+//!
+//! This module defines the structured error type of the runner. Each variant maps
+//! to a documented process exit code, so wrapper scripts can branch on the kind of
+//! failure instead of parsing messages:
+//!
+//! 此模块定义了运行器的结构化错误类型。每个变体都映射到一个有文档说明的进程退出码，
+//! 以便包装脚本可以根据失败类型进行分支，而无需解析消息：
+//!
+//! | Code | Meaning / 含义 |
+//! |------|----------------|
+//! | 0 | Success / 成功 |
+//! | 1 | Any other error / 其他错误 |
+//! | 2 | Configuration or usage error / 配置或用法错误 |
+//! | 3 | Environment error (project, cargo, file system) / 环境错误（项目、cargo、文件系统） |
+//! | 4 | Unexpected test failures / 意外的测试失败 |
+//! | 5 | The run was cancelled / 运行被取消 |
+
+use std::fmt;
+
+/// The categories of errors that end a run, each with its own exit code.
+/// 结束运行的错误类别，每个类别都有自己的退出码。
+#[derive(Debug)]
+pub enum MatrixError {
+    /// The configuration or command-line arguments are invalid.
+    /// 配置或命令行参数无效。
+    Config(anyhow::Error),
+    /// The environment is not usable, e.g. the project or `cargo` cannot be found.
+    /// 环境不可用，例如找不到项目或 `cargo`。
+    Environment(anyhow::Error),
+    /// One or more cases failed without being allowed to.
+    /// 一个或多个用例在不允许失败的情况下失败。
+    UnexpectedFailures {
+        /// The number of unexpected failures / 意外失败的数量
+        count: usize,
+    },
+    /// The run was interrupted before all cases finished.
+    /// 运行在所有用例完成之前被中断。
+    Cancelled,
+}
+
+impl MatrixError {
+    /// The exit code used for errors that are not a `MatrixError`.
+    /// 非 `MatrixError` 错误使用的退出码。
+    pub const GENERIC_EXIT_CODE: u8 = 1;
+
+    /// Gets the process exit code for this error.
+    /// 获取此错误的进程退出码。
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            MatrixError::Config(_) => 2,
+            MatrixError::Environment(_) => 3,
+            MatrixError::UnexpectedFailures { .. } => 4,
+            MatrixError::Cancelled => 5,
+        }
+    }
+
+    /// Gets the exit code for any error, looking for a `MatrixError` in its chain.
+    /// 获取任意错误的退出码，在其错误链中查找 `MatrixError`。
+    pub fn exit_code_for(error: &anyhow::Error) -> u8 {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<MatrixError>())
+            .map(MatrixError::exit_code)
+            .unwrap_or(Self::GENERIC_EXIT_CODE)
+    }
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::Config(e) | MatrixError::Environment(e) => write!(f, "{:#}", e),
+            MatrixError::UnexpectedFailures { .. } => {
+                write!(f, "Matrix tests failed with unexpected errors.")
+            }
+            MatrixError::Cancelled => write!(f, "The test run was cancelled."),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MatrixError::Config(e) | MatrixError::Environment(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! # Exported Execution Plan Module / 导出执行计划模块
+//!
+//! This module defines the file written by `plan export` and read by `run --from-plan`.
+//! It holds the cases that remain after selection, architecture filtering and runner
+//! splitting, together with the command each case runs, so that planning can happen on a
+//! machine with network and metadata access while the execution happens elsewhere.
+//!
+//! 此模块定义由 `plan export` 写出、由 `run --from-plan` 读取的文件。
+//! 它保存经过选择、架构过滤和运行器拆分后剩余的用例，以及每个用例运行的命令，
+//! 使计划可以在具有网络和元数据访问权限的机器上完成，而执行在其他环境中进行。
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::columns::SummaryColumn;
+use crate::core::config::{
+    ConcurrencyGroup, DuplicatePolicy, MissingComponentPolicy, TestCase, TestMatrix,
+    default_transient_build_retries,
+};
+use crate::core::execution::command_line;
+
+/// The version of the plan file format / 计划文件格式的版本
+pub const PLAN_VERSION: u32 = 1;
+
+/// A resolved execution plan that can be run on another machine.
+/// 可以在另一台机器上运行的已解析执行计划。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecPlan {
+    /// The plan file format version / 计划文件格式版本
+    pub version: u32,
+    /// When the plan was created / 计划的创建时间
+    pub created_at: DateTime<Utc>,
+    /// The absolute project root the commands run in / 命令运行所在的项目根目录绝对路径
+    pub project_root: PathBuf,
+    /// The package tested by cases without `package` / 未设置 `package` 的用例所测试的包
+    pub crate_name: String,
+    /// The matrix language / 矩阵语言
+    pub language: String,
+    /// Whether the run stops on the first unexpected failure / 是否在第一次意外失败时停止运行
+    pub fast_fail: bool,
+    /// The matrix `anomaly_factor` / 矩阵的 `anomaly_factor`
+    pub anomaly_factor: f64,
+    /// Whether the cases' commands run in the C locale by default / 用例的命令是否默认在 C 语言环境下运行
+    #[serde(default)]
+    pub force_c_locale: bool,
+    /// The matrix `transient_build_retries` / 矩阵的 `transient_build_retries`
+    #[serde(default = "default_transient_build_retries")]
+    pub transient_build_retries: u8,
+    /// The matrix `collect_core_dumps` / 矩阵的 `collect_core_dumps`
+    #[serde(default)]
+    pub collect_core_dumps: bool,
+    /// The matrix `prefer_nextest` / 矩阵的 `prefer_nextest`
+    #[serde(default)]
+    pub prefer_nextest: bool,
+    /// The matrix `prebuild_dependencies` / 矩阵的 `prebuild_dependencies`
+    #[serde(default)]
+    pub prebuild_dependencies: bool,
+    /// The matrix `max_load` / 矩阵的 `max_load`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_load: Option<f64>,
+    /// The matrix `max_total_memory_gb` / 矩阵的 `max_total_memory_gb`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_total_memory_gb: Option<f64>,
+    /// The matrix `on_missing_component` / 矩阵的 `on_missing_component`
+    #[serde(default)]
+    pub on_missing_component: MissingComponentPolicy,
+    /// The concurrency group limits / 并发组限制
+    #[serde(default)]
+    pub concurrency_groups: BTreeMap<String, ConcurrencyGroup>,
+    /// The owners of cases, keyed by a case name glob / 用例的负责人，以用例名称通配符为键
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub owners: BTreeMap<String, String>,
+    /// The columns of the console summary and the HTML results table / 控制台摘要和 HTML 结果表的列
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub summary_columns: Vec<SummaryColumn>,
+    /// Notes about how the cases were chosen (selection, filtering, splitting) / 关于用例如何被选出的说明（选择、过滤、拆分）
+    #[serde(default)]
+    pub notes: Vec<String>,
+    /// The cases to run, in order / 要运行的用例（按顺序）
+    pub cases: Vec<PlannedCase>,
+}
+
+/// A case of an exported plan, with the command it runs.
+/// 导出计划中的一个用例，以及它运行的命令。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedCase {
+    /// The case definition / 用例定义
+    #[serde(flatten)]
+    pub case: TestCase,
+    /// The command line the case runs in `project_root`, for reference / 用例在 `project_root` 中运行的命令行，仅供参考
+    pub resolved_command: String,
+}
+
+impl ExecPlan {
+    /// Creates a plan from the matrix settings and the cases left after planning.
+    /// 根据矩阵设置和计划后剩余的用例创建计划。
+    pub fn new(
+        matrix_settings: &TestMatrix,
+        cases: Vec<TestCase>,
+        project_root: PathBuf,
+        crate_name: String,
+        notes: Vec<String>,
+    ) -> Self {
+        let cases = cases
+            .into_iter()
+            .map(|case| PlannedCase {
+                resolved_command: command_line(&case, &crate_name),
+                case,
+            })
+            .collect();
+        Self {
+            version: PLAN_VERSION,
+            created_at: Utc::now(),
+            project_root,
+            crate_name,
+            language: matrix_settings.language.clone(),
+            fast_fail: matrix_settings.fast_fail,
+            anomaly_factor: matrix_settings.anomaly_factor,
+            force_c_locale: matrix_settings.force_c_locale,
+            transient_build_retries: matrix_settings.transient_build_retries,
+            collect_core_dumps: matrix_settings.collect_core_dumps,
+            prefer_nextest: matrix_settings.prefer_nextest,
+            prebuild_dependencies: matrix_settings.prebuild_dependencies,
+            max_load: matrix_settings.max_load,
+            max_total_memory_gb: matrix_settings.max_total_memory_gb,
+            on_missing_component: matrix_settings.on_missing_component,
+            concurrency_groups: matrix_settings.concurrency_groups.clone(),
+            owners: matrix_settings.owners.clone(),
+            summary_columns: matrix_settings.summary_columns.clone(),
+            notes,
+            cases,
+        }
+    }
+
+    /// Turns the plan back into a test matrix holding only the planned cases.
+    /// 将计划转换回仅包含计划用例的测试矩阵。
+    pub fn to_matrix(&self) -> TestMatrix {
+        TestMatrix {
+            language: self.language.clone(),
+            fast_fail: self.fast_fail,
+            concurrency_groups: self.concurrency_groups.clone(),
+            anomaly_factor: self.anomaly_factor,
+            force_c_locale: self.force_c_locale,
+            transient_build_retries: self.transient_build_retries,
+            collect_core_dumps: self.collect_core_dumps,
+            prefer_nextest: self.prefer_nextest,
+            prebuild_dependencies: self.prebuild_dependencies,
+            max_load: self.max_load,
+            max_total_memory_gb: self.max_total_memory_gb,
+            on_missing_component: self.on_missing_component,
+            // The defaults and `[env]` were applied to the cases when the plan was made.
+            defaults: Default::default(),
+            tag_defaults: BTreeMap::new(),
+            env: BTreeMap::new(),
+            owners: self.owners.clone(),
+            summary_columns: self.summary_columns.clone(),
+            // The plan already holds the generated and expanded cases.
+            generator: None,
+            matrix: None,
+            // Duplicates were resolved when the plan was made.
+            on_duplicate: DuplicatePolicy::Error,
+            cases: self
+                .cases
+                .iter()
+                .map(|planned| planned.case.clone())
+                .collect(),
+        }
+    }
+
+    /// Writes the plan as pretty-printed JSON.
+    /// 将计划写为格式化的 JSON。
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize plan")?;
+        fs::write(path, json).with_context(|| format!("Failed to write plan: {}", path.display()))
+    }
+
+    /// Reads a plan written by [`ExecPlan::save`].
+    /// 读取由 [`ExecPlan::save`] 写出的计划。
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plan: {}", path.display()))?;
+        let plan: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse plan: {}", path.display()))?;
+        if plan.version != PLAN_VERSION {
+            bail!(
+                "Unsupported plan version {} in {} (expected {})",
+                plan.version,
+                path.display(),
+                PLAN_VERSION
+            );
+        }
+        Ok(plan)
+    }
+}
@@ -9,45 +9,536 @@
 //! 包括超时、重试和结果收集。
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use colored::*;
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tempfile::TempDir;
 use tokio::sync::mpsc;
 
 use crate::{
     core::{
-        config::TestCase,
-        models::{BuildContext, BuiltTest, FailureReason, TestResult},
+        config::{CaseKind, FuzzSmoke, TestCase},
+        context::RunContext,
+        costs::CostLog,
+        dependency_layer,
+        fixtures::FixtureStage,
+        models::{BuildContext, BuiltTest, FailureReason, TestResult, TestTiming},
     },
-    infra::{command, t},
+    infra::{
+        checksum::{self, ChecksumLog},
+        command::{self, ShutdownTasks},
+        core_dump,
+        coverage::{self, CoverageLog},
+        limits,
+        ports::{PortBroker, ReservedPorts},
+        sandbox, t,
+    },
+    reporting::partial::PartialRun,
 };
 
+/// The environment variable through which custom commands receive the directory
+/// where they may drop files (screenshots, core dumps, ...) to attach to their result.
+/// 自定义命令通过此环境变量获取可放置附件文件（截图、核心转储等）的目录。
+pub const ATTACH_DIR_ENV: &str = "MATRIX_RUNNER_ATTACH_DIR";
+
+/// The environment variable through which test processes receive the file they may
+/// append `key=value` annotations (measured throughput, dataset version, ...) to.
+/// 测试进程通过此环境变量获取可追加 `key=value` 注解（测得的吞吐量、数据集版本等）的文件。
+pub const ANNOTATIONS_FILE_ENV: &str = "MATRIX_RUNNER_ANNOTATIONS_FILE";
+
+/// The environment variable set to `1` for cases with `requires_network = true`,
+/// so tests can tell that network access is expected to work.
+/// 为 `requires_network = true` 的用例设置为 `1` 的环境变量，使测试能够知道网络访问应当可用。
+pub const NETWORK_TESTS_ENV: &str = "NETWORK_TESTS";
+
+/// The environment variable telling each process of a case with `shards` which share of
+/// the tests to run, as `<index>/<count>` with a 1-based index, e.g. `MATRIX_SHARD=2/4`.
+/// 告知带有 `shards` 的用例的每个进程应运行哪一部分测试的环境变量，格式为 `<序号>/<总数>`，
+/// 序号从 1 开始，例如 `MATRIX_SHARD=2/4`。
+pub const SHARD_ENV: &str = "MATRIX_SHARD";
+
+/// The prefix of the environment variables passing the ports reserved for a case with
+/// `needs_ports` to its test process: `MATRIX_PORT_0`, `MATRIX_PORT_1`, ...
+/// 将为带有 `needs_ports` 的用例预留的端口传递给其测试进程的环境变量前缀：`MATRIX_PORT_0`、`MATRIX_PORT_1`……
+pub const PORT_ENV_PREFIX: &str = "MATRIX_PORT_";
+
+/// The exit code of `cargo nextest run` when the tests could not be built.
+/// 测试无法构建时 `cargo nextest run` 的退出码。
+pub const NEXTEST_BUILD_FAILED_EXIT_CODE: i32 = 101;
+
+/// The environment variable passing flags to rustdoc, which `kind = "doc"` cases extend
+/// to deny broken intra-doc links.
+/// 向 rustdoc 传递标志的环境变量，`kind = "doc"` 的用例会在其中追加拒绝失效文档内链接的标志。
+pub const RUSTDOCFLAGS_ENV: &str = "RUSTDOCFLAGS";
+
+/// The builds whose log is archived to the log directory with `--keep-build-logs`.
+/// 使用 `--keep-build-logs` 时将日志归档到日志目录的构建。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeepBuildLogs {
+    /// Every build, including warnings and timing of successful ones / 每次构建，包括成功构建的警告和耗时
+    All,
+    /// Only builds that failed / 仅失败的构建
+    Failed,
+    /// No build; failure details still write full logs / 不归档任何构建；失败详情仍会写入完整日志
+    #[default]
+    None,
+}
+
+impl KeepBuildLogs {
+    /// Whether the log of a build with the given outcome is kept / 是否保留具有给定结果的构建的日志
+    pub fn keeps(self, build_succeeded: bool) -> bool {
+        match self {
+            KeepBuildLogs::All => true,
+            KeepBuildLogs::Failed => !build_succeeded,
+            KeepBuildLogs::None => false,
+        }
+    }
+}
+
+impl std::str::FromStr for KeepBuildLogs {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "all" => Ok(KeepBuildLogs::All),
+            "failed" => Ok(KeepBuildLogs::Failed),
+            "none" => Ok(KeepBuildLogs::None),
+            other => Err(format!("expected `all`, `failed` or `none`, found `{other}`")),
+        }
+    }
+}
+
+/// The delay before the first retry of a build that failed with a transient cargo error;
+/// it doubles with every further retry.
+/// 因暂时性 cargo 错误而失败的构建在第一次重试前的延迟；之后每次重试翻倍。
+pub const TRANSIENT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// The number of builds retried after a transient cargo error, keyed by case name.
+/// Cloning yields a handle to the same counts.
+///
+/// 因暂时性 cargo 错误而重试的构建次数，以用例名称为键。克隆会得到指向同一组计数的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct TransientRetryLog {
+    retries: Arc<Mutex<BTreeMap<String, u32>>>,
+}
+
+impl TransientRetryLog {
+    /// Counts a retried build of a case / 为用例计入一次重试的构建
+    pub fn record(&self, case_name: &str) {
+        *self
+            .retries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(case_name.to_string())
+            .or_default() += 1;
+    }
+
+    /// Returns the counts recorded so far.
+    /// 返回目前已记录的计数。
+    pub fn snapshot(&self) -> BTreeMap<String, u32> {
+        self.retries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// The shared build directories a `--keep-going` run stopped using after a case failed in
+/// them, with the case that caused it. Cloning yields a handle to the same record.
+///
+/// `--keep-going` 运行在有用例于共享构建目录中失败后停止使用的那些目录，以及导致此结果的用例。
+/// 克隆会得到指向同一记录的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct IsolationLog {
+    events: Arc<Mutex<IsolationEvents>>,
+}
+
+/// The cases that made a run leave its shared build directories.
+/// 使一次运行离开其共享构建目录的用例。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IsolationEvents {
+    /// The case that failed to build from the dependency layer but built without it
+    /// 从依赖层构建失败、但不使用依赖层时构建成功的用例
+    pub layer_discarded_by: Option<String>,
+    /// The first custom command that failed in the project's target directory
+    /// 第一个在项目 target 目录中失败的自定义命令
+    pub shared_target_failed_in: Option<String>,
+}
+
+impl IsolationLog {
+    /// Stops seeding builds with the dependency layer. Returns whether this call did,
+    /// rather than an earlier one.
+    /// 停止以依赖层作为构建的起点。返回是否由本次调用（而非更早的调用）停止。
+    pub fn discard_layer(&self, case_name: &str) -> bool {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let first = events.layer_discarded_by.is_none();
+        events.layer_discarded_by.get_or_insert_with(|| case_name.to_string());
+        first
+    }
+
+    /// Moves the custom commands that start from now on out of the project's target
+    /// directory. Returns whether this call did, rather than an earlier one.
+    /// 将此后启动的自定义命令移出项目的 target 目录。返回是否由本次调用（而非更早的调用）移出。
+    pub fn leave_shared_target(&self, case_name: &str) -> bool {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let first = events.shared_target_failed_in.is_none();
+        events.shared_target_failed_in.get_or_insert_with(|| case_name.to_string());
+        first
+    }
+
+    /// Returns the events recorded so far.
+    /// 返回目前已记录的事件。
+    pub fn snapshot(&self) -> IsolationEvents {
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Run-wide information shared by the execution of every test case.
+/// 每个测试用例执行时共享的运行级信息。
+#[derive(Debug, Clone)]
+pub struct ExecutionContext {
+    /// Path to the project root directory / 项目根目录的路径
+    pub project_root: PathBuf,
+    /// Name of the crate being tested / 被测试的 crate 名称
+    pub crate_name: String,
+    /// Directory for the runner's own state, such as attachments / 运行器自身状态（如附件）的目录
+    pub state_dir: PathBuf,
+    /// Record the SHA-256 of each built test binary / 记录每个所构建测试二进制文件的 SHA-256
+    pub record_checksums: bool,
+    /// Build each case twice and fail it if the test binaries differ / 将每个用例构建两次，若测试二进制文件不同则判定失败
+    pub verify_reproducible: bool,
+    /// The checksums recorded so far / 目前已记录的校验和
+    pub checksums: ChecksumLog,
+    /// Which build logs to archive in the log directory / 要在日志目录中归档哪些构建日志
+    pub keep_build_logs: KeepBuildLogs,
+    /// Give custom commands their own target directory instead of the project's / 为自定义命令提供各自的 target 目录，而不是项目的目录
+    pub isolated_only: bool,
+    /// Keep a failure in a shared build directory from spreading to other cases / 防止共享构建目录中的失败波及其他用例
+    pub keep_going: bool,
+    /// The shared build directories left so far with `keep_going` / 目前因 `keep_going` 而离开的共享构建目录
+    pub isolation: IsolationLog,
+    /// The matrix `force_c_locale`, for cases that do not set their own / 矩阵的 `force_c_locale`，用于未自行设置的用例
+    pub force_c_locale: bool,
+    /// The matrix `transient_build_retries` / 矩阵的 `transient_build_retries`
+    pub transient_build_retries: u8,
+    /// The matrix `collect_core_dumps` / 矩阵的 `collect_core_dumps`
+    pub collect_core_dumps: bool,
+    /// The builds retried so far after a transient cargo error / 目前因暂时性 cargo 错误而重试的构建
+    pub transient_retries: TransientRetryLog,
+    /// The coverage of the `kind = "tarpaulin"` cases so far / 目前 `kind = "tarpaulin"` 用例的覆盖率
+    pub coverage: CoverageLog,
+    /// Build and run the default flow with `cargo nextest run` / 使用 `cargo nextest run` 构建和运行默认流程
+    pub use_nextest: bool,
+    /// The build time and target directory size of each case so far / 目前每个用例的构建时间和 target 目录大小
+    pub costs: CostLog,
+    /// The prebuilt dependencies copied into the build directory of each case, if any / 复制到每个用例构建目录中的预先构建的依赖（如果有）
+    pub dependency_layer: Option<PathBuf>,
+    /// The language of the run's messages and the platform it runs on / 运行消息的语言及其运行的平台
+    pub context: RunContext,
+    /// The date the run started, which decides whether `allow_failure` entries have expired / 运行开始的日期，决定 `allow_failure` 条目是否已过期
+    pub today: NaiveDate,
+    /// The fixtures staged by the running cases / 运行中的用例所放置的 fixture
+    pub fixtures: FixtureStage,
+    /// The ports reserved by the running cases / 运行中的用例所预留的端口
+    pub ports: PortBroker,
+    /// The cases finished so far, reported if the run panics / 目前已完成的用例，在运行发生 panic 时报告
+    pub partial: PartialRun,
+    /// The processes of dropped cases still shutting down / 被丢弃的用例中仍在关闭的进程
+    pub shutdowns: ShutdownTasks,
+}
+
+impl ExecutionContext {
+    /// Whether a case runs with `cargo nextest run`: only cases of the default flow that
+    /// run their tests outside a sandbox, and only when no test binary needs to be checksummed.
+    /// 用例是否使用 `cargo nextest run` 运行：仅限在沙箱之外运行其测试的默认流程用例，
+    /// 并且仅当不需要计算测试二进制文件的校验和时。
+    pub fn runs_with_nextest(&self, case: &TestCase) -> bool {
+        self.use_nextest
+            && case.command.is_none()
+            && case.kind == CaseKind::Test
+            && !self.is_build_only(case)
+            && case.sandbox.is_none()
+            && !self.record_checksums
+            && !self.verify_reproducible
+    }
+
+    /// Whether a case is only compiled and its tests are not run: `build_only` cases, and
+    /// cases built for a `target` the platform of the run cannot execute.
+    /// 用例是否仅被编译而不运行其测试：`build_only` 用例，以及为本次运行的平台无法执行的 `target` 构建的用例。
+    pub fn is_build_only(&self, case: &TestCase) -> bool {
+        case.build_only || self.foreign_target(case).is_some()
+    }
+
+    /// The `target` of a case, if the platform of the run cannot execute it.
+    fn foreign_target<'a>(&self, case: &'a TestCase) -> Option<&'a str> {
+        case.target.as_deref().filter(|target| !self.context.can_run(target))
+    }
+
+    /// Creates a context using the default state directory (`target/matrix-runner`).
+    /// 使用默认状态目录（`target/matrix-runner`）创建上下文。
+    pub fn new(project_root: PathBuf, crate_name: impl Into<String>) -> Self {
+        let state_dir = crate::infra::fs::state_dir(&project_root);
+        Self {
+            project_root,
+            crate_name: crate_name.into(),
+            state_dir,
+            record_checksums: false,
+            verify_reproducible: false,
+            checksums: ChecksumLog::default(),
+            keep_build_logs: KeepBuildLogs::None,
+            isolated_only: false,
+            keep_going: false,
+            isolation: IsolationLog::default(),
+            force_c_locale: false,
+            transient_build_retries: crate::core::config::default_transient_build_retries(),
+            collect_core_dumps: false,
+            transient_retries: TransientRetryLog::default(),
+            coverage: CoverageLog::default(),
+            use_nextest: false,
+            costs: CostLog::default(),
+            dependency_layer: None,
+            context: RunContext::current(),
+            today: chrono::Local::now().date_naive(),
+            fixtures: FixtureStage::default(),
+            ports: PortBroker::default(),
+            partial: PartialRun::default(),
+            shutdowns: ShutdownTasks::default(),
+        }
+    }
+
+    /// Whether the build of a case starts from the dependency layer: the run has one, the
+    /// case can use it, and no failure has discarded it.
+    /// 用例的构建是否以依赖层为起点：本次运行有依赖层、用例可以使用它，且它未因失败被丢弃。
+    fn seeds_from_layer(&self, case: &TestCase) -> bool {
+        self.dependency_layer.is_some()
+            && dependency_layer::uses_layer(case)
+            && self.isolation.snapshot().layer_discarded_by.is_none()
+    }
+
+    /// Whether a build that started from the dependency layer and failed is built again
+    /// in a fresh directory, to tell a broken case from a broken layer.
+    /// 从依赖层开始且失败的构建是否在全新目录中再次构建，以区分是用例损坏还是依赖层损坏。
+    fn rebuilds_without_layer(&self, case: &TestCase, from_layer: bool) -> bool {
+        if !(self.keep_going && from_layer) {
+            return false;
+        }
+        println!(
+            "{}",
+            t!("run.keep_going_fresh_build", locale = &self.context.locale, name = &case.name).yellow()
+        );
+        true
+    }
+
+    /// Records a build that only succeeded without the dependency layer: the layer is
+    /// what broke it, so no later case starts from it.
+    /// 记录一个仅在不使用依赖层时才成功的构建：是依赖层导致了失败，因此之后的用例都不再以它为起点。
+    fn discard_layer(&self, case: &TestCase) {
+        if self.isolation.discard_layer(&case.name) {
+            println!(
+                "{}",
+                t!("run.keep_going_layer_discarded", locale = &self.context.locale, name = &case.name).yellow()
+            );
+        }
+    }
+
+    /// Seeds the build directory of a case with the dependency layer, if the run has one.
+    /// A failed copy only costs the time of building the dependencies again.
+    /// 如果本次运行有依赖层，则以该层作为用例构建目录的起点。复制失败只会多花重新构建依赖的时间。
+    fn seed_build_dir(&self, case: &TestCase, build_dir: &Path) {
+        let Some(layer) = &self.dependency_layer else {
+            return;
+        };
+        if let Err(e) = crate::infra::fs::copy_dir_contents(layer, build_dir) {
+            eprintln!(
+                "{} {:#}",
+                t!("run.deps_layer_copy_failed", locale = &self.context.locale, name = &case.name).yellow(),
+                e
+            );
+        }
+    }
+}
+
+/// Builds the command line that runs a case outside the runner: its custom command,
+/// or `cargo test` (`cargo tarpaulin` for `kind = "tarpaulin"`, `cargo doc` for
+/// `kind = "doc"`) with the case's package and features. A `kind = "fuzz-smoke"` case
+/// runs `cargo fuzz run` for each of its targets. The variables of [`command_env`] are
+/// set for the command as POSIX shell assignments in front of it.
+///
+/// # Arguments
+/// * `case` - The test case
+/// * `default_package` - The package tested when the case does not set `package`
+///
+/// 构建在运行器之外运行用例的命令行：其自定义命令，或带有用例包和 features 的 `cargo test`
+/// （`kind = "tarpaulin"` 时为 `cargo tarpaulin`，`kind = "doc"` 时为 `cargo doc`）。
+/// `kind = "fuzz-smoke"` 的用例会对其每个目标运行 `cargo fuzz run`。
+/// [`command_env`] 中的变量以 POSIX shell 赋值的形式写在命令前面。
+pub fn command_line(case: &TestCase, default_package: &str) -> String {
+    let prefix = env_prefix(&command_env(case));
+    if case.command.is_none() && case.kind == CaseKind::FuzzSmoke {
+        // The fuzz command line runs several commands, so each of them gets the variables.
+        return fuzz_command_line(case, &prefix);
+    }
+    format!("{prefix}{}", bare_command_line(case, default_package))
+}
+
+/// Builds the command line of a case like [`command_line`], but without the variables of
+/// [`command_env`], for scripts that set them on their own lines.
+/// 与 [`command_line`] 一样构建用例的命令行，但不带 [`command_env`] 中的变量，供在单独的行中设置它们的脚本使用。
+pub fn bare_command_line(case: &TestCase, default_package: &str) -> String {
+    if let Some(command) = &case.command {
+        return command.clone();
+    }
+    if case.kind == CaseKind::FuzzSmoke {
+        return fuzz_command_line(case, "");
+    }
+    let mut args = vec!["cargo".to_string()];
+    if let Some(toolchain) = &case.toolchain {
+        args.push(format!("+{toolchain}"));
+    }
+    match case.kind {
+        CaseKind::Test => {
+            args.push("test".to_string());
+            if case.build_only {
+                args.push("--no-run".to_string());
+            }
+        }
+        CaseKind::Tarpaulin => args.push("tarpaulin".to_string()),
+        CaseKind::Doc => {
+            args.push("doc".to_string());
+            args.push("--no-deps".to_string());
+        }
+        CaseKind::FuzzSmoke => unreachable!("fuzz-smoke cases have their own command line"),
+    }
+    let package = case.package.as_deref().unwrap_or(default_package);
+    if !package.is_empty() {
+        args.push("-p".to_string());
+        args.push(package.to_string());
+    }
+    if case.no_default_features {
+        args.push("--no-default-features".to_string());
+    }
+    if !case.features.is_empty() {
+        args.push("--features".to_string());
+        args.push(case.features.clone());
+    }
+    if let Some(target) = &case.target {
+        args.push("--target".to_string());
+        args.push(target.clone());
+    }
+    args.extend(case.cargo_unstable_flags.iter().cloned());
+    if case.kind == CaseKind::Test && !case.build_only && !case.skip_tests.is_empty() {
+        args.push("--".to_string());
+        args.extend(skip_args(case).map(str::to_string));
+    }
+    args.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// The environment variables the command line of a case needs: the case's `env`, already
+/// merged with the matrix `[env]`, and for a `kind = "doc"` case the `RUSTDOCFLAGS` that
+/// deny broken intra-doc links.
+/// 用例命令行所需的环境变量：用例的 `env`（已与矩阵的 `[env]` 合并），以及对于
+/// `kind = "doc"` 的用例，拒绝损坏的文档内链接的 `RUSTDOCFLAGS`。
+pub fn command_env(case: &TestCase) -> BTreeMap<String, String> {
+    let mut env = case.env.clone();
+    if case.command.is_none() && case.kind == CaseKind::Doc {
+        let flags = doc_rustdocflags(case.env.get(RUSTDOCFLAGS_ENV).map(String::as_str));
+        env.insert(RUSTDOCFLAGS_ENV.to_string(), flags);
+    }
+    env
+}
+
+/// The `NAME=value ` assignments that set variables for one POSIX shell command.
+fn env_prefix(env: &BTreeMap<String, String>) -> String {
+    env.iter()
+        .map(|(name, value)| format!("{name}={} ", shell_quote(value)))
+        .collect()
+}
+
+/// Quotes a word for a POSIX shell, leaving it as is if it needs no quoting.
+fn shell_quote(arg: &str) -> String {
+    shlex::try_quote(arg).map(|q| q.into_owned()).unwrap_or_else(|_| arg.to_string())
+}
+
+/// The command line of a `kind = "fuzz-smoke"` case: `cargo fuzz run` for each of its
+/// `targets`, or a loop over `cargo fuzz list` when it names none. `prefix` is put in
+/// front of every `cargo fuzz` command.
+fn fuzz_command_line(case: &TestCase, prefix: &str) -> String {
+    let cargo_fuzz = match &case.toolchain {
+        Some(toolchain) => format!("{prefix}cargo {} fuzz", shell_quote(&format!("+{toolchain}"))),
+        None => format!("{prefix}cargo fuzz"),
+    };
+    let options: Vec<String> = fuzz_cargo_args(case)
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .chain(["--".to_string()])
+        .chain(case.fuzz.libfuzzer_args())
+        .collect();
+    let options = options.join(" ");
+    if case.fuzz.targets.is_empty() {
+        return format!(
+            "for target in $({cargo_fuzz} list); do {cargo_fuzz} run \"$target\" {options} || exit 1; done"
+        );
+    }
+    case.fuzz
+        .targets
+        .iter()
+        .map(|target| format!("{cargo_fuzz} run {} {options}", shell_quote(target)))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
 /// The main entry point for running a single test case.
 /// It wraps the core execution logic with timeout and retry handling.
 ///
 /// # Arguments
 /// * `case` - The test case configuration to execute
-/// * `project_root` - Path to the project root directory
-/// * `crate_name` - Name of the crate being tested
+/// * `ctx` - The run-wide execution context
+/// * `temp_dir_tx` - Channel receiving the temporary build directories to keep alive
 ///
 /// # Returns
 /// A `TestResult` indicating the outcome of the test execution
 pub async fn run_test_case(
     case: TestCase,
-    project_root: &PathBuf,
-    crate_name: &str,
+    ctx: &ExecutionContext,
     temp_dir_tx: mpsc::UnboundedSender<TempDir>,
 ) -> Result<TestResult> {
+    // The fixtures stay in place for every attempt and are removed when the case ends.
+    let _fixtures = match ctx.fixtures.stage(&ctx.project_root, &case.fixtures) {
+        Ok(staged) => staged,
+        Err(e) => {
+            return Ok(TestResult::Failed {
+                output: format!(
+                    "{} {:#}",
+                    t!("run.fixture_stage_failed", locale = &ctx.context.locale, name = &case.name),
+                    e
+                ),
+                case,
+                reason: FailureReason::TestFailed,
+                duration: Duration::ZERO,
+                attachments: Vec::new(),
+                test_timings: Vec::new(),
+                annotations: BTreeMap::new(),
+            });
+        }
+    };
     let max_attempts = 1 + case.retries.unwrap_or(0);
     let mut last_result: Option<TestResult> = None;
+    // Set when the previous attempt can be retried by rerunning only its failing tests.
+    let mut rerun: Option<Rerun> = None;
 
     for attempt in 1..=max_attempts {
         let case_name = case.name.clone();
         let timeout_dur = case.timeout_secs.map(std::time::Duration::from_secs);
 
-        let execution_future =
-            run_test_case_inner(case.clone(), project_root, crate_name, temp_dir_tx.clone());
+        let execution_future = command::with_kill_grace(
+            case.kill_grace(),
+            &ctx.shutdowns,
+            run_attempt(case.clone(), ctx, temp_dir_tx.clone(), rerun.as_ref()),
+        );
 
         let result = if let Some(duration) = timeout_dur {
             match tokio::time::timeout(duration, execution_future).await {
@@ -55,13 +546,19 @@ pub async fn run_test_case(
                 Err(_) => {
                     println!(
                         "{}",
-                        t!("run.test_timeout", name = case_name, timeout = duration.as_secs()).red()
+                        t!("run.test_timeout", locale = &ctx.context.locale, name = case_name, timeout = duration.as_secs()).red()
                     );
-                    Ok(TestResult::Failed {
-                        case: case.clone(),
-                        output: t!("run.test_timeout_message").to_string(),
-                        reason: FailureReason::Timeout,
-                        duration,
+                    Ok(Attempt {
+                        result: TestResult::Failed {
+                            case: case.clone(),
+                            output: t!("run.test_timeout_message", locale = &ctx.context.locale).to_string(),
+                            reason: FailureReason::Timeout,
+                            duration,
+                            attachments: Vec::new(),
+                            test_timings: Vec::new(),
+                            annotations: BTreeMap::new(),
+                        },
+                        rerun: None,
                     })
                 }
             }
@@ -69,42 +566,76 @@ pub async fn run_test_case(
             execution_future.await
         };
 
+        // A partial rerun only covers the failing tests, so it is merged into the earlier outcome.
+        let result = result.map(|attempt_result| match (&rerun, last_result.take()) {
+            (Some(previous_rerun), Some(previous)) if !attempt_result.result.is_timeout() => Attempt {
+                result: merge_rerun(
+                    previous,
+                    attempt_result.result,
+                    &previous_rerun.failed_tests,
+                    &ctx.context.locale,
+                ),
+                rerun: attempt_result.rerun,
+            },
+            _ => attempt_result,
+        });
+
         match result {
-            Ok(TestResult::Passed {
-                case,
-                output,
-                duration,
+            Ok(Attempt {
+                result: TestResult::Passed {
+                    case,
+                    output,
+                    duration,
+                    attachments,
+                    test_timings,
+                    annotations,
+                    ..
+                },
                 ..
             }) => {
                 let final_result = TestResult::Passed {
                     case,
                     output,
                     duration,
-                    retries: attempt as u8,
+                    retries: attempt,
+                    attachments,
+                    test_timings,
+                    annotations,
                 };
                 if attempt > 1 {
                     println!(
                         "{}",
-                        t!("run.test_passed_on_retry", name = case_name, retries = attempt - 1).green()
+                        t!("run.test_passed_on_retry", locale = &ctx.context.locale, name = case_name, retries = attempt - 1).green()
                     );
                 }
                 return Ok(final_result);
             }
-            Ok(res) => {
+            Ok(Attempt {
+                result: built @ TestResult::Built { .. },
+                ..
+            }) => return Ok(built),
+            Ok(Attempt { result: res, rerun: next_rerun }) => {
                 if res.is_timeout() {
                     return Ok(res);
                 }
                 if attempt < max_attempts {
                     println!(
                         "{}",
-                        t!("run.test_retrying", name = case_name, attempt = attempt, retries = max_attempts - 1).yellow()
+                        t!("run.test_retrying", locale = &ctx.context.locale, name = case_name, attempt = attempt, retries = max_attempts - 1).yellow()
                     );
+                    if let Some(next) = &next_rerun {
+                        println!(
+                            "{}",
+                            t!("run.rerunning_failed_tests", locale = &ctx.context.locale, count = next.failed_tests.len()).yellow()
+                        );
+                    }
                 } else {
                     println!(
                         "{}",
-                        t!("run.test_failed_after_retries", name = case_name, retries = case.retries.unwrap_or(0)).red()
+                        t!("run.test_failed_after_retries", locale = &ctx.context.locale, name = case_name, retries = case.retries.unwrap_or(0)).red()
                     );
                 }
+                rerun = next_rerun;
                 last_result = Some(res);
             }
             Err(e) => {
@@ -113,38 +644,775 @@ pub async fn run_test_case(
             }
         }
     }
-    Ok(last_result.unwrap_or(TestResult::Skipped))
+    Ok(last_result.unwrap_or_else(TestResult::skipped))
+}
+
+/// The outcome of a single attempt at running a case.
+/// 运行用例的单次尝试的结果。
+struct Attempt {
+    result: TestResult,
+    /// How to retry only the failing tests, if that is possible / 如果可能，如何仅重试失败的测试
+    rerun: Option<Rerun>,
+}
+
+/// A failed attempt whose failing tests can be rerun with the already built binary.
+/// 可以使用已构建的二进制文件重新运行其失败测试的失败尝试。
+struct Rerun {
+    executable_path: PathBuf,
+    failed_tests: Vec<String>,
+}
+
+impl Rerun {
+    /// Creates a rerun for a result of the test binary, if libtest reported which tests failed.
+    fn for_result(result: &TestResult, executable_path: PathBuf) -> Option<Self> {
+        match result {
+            TestResult::Failed {
+                reason: FailureReason::TestFailed,
+                output,
+                ..
+            } => command::parse_failed_tests(output).map(|failed_tests| Self {
+                executable_path,
+                failed_tests,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Runs one attempt: either the whole case, or only the failing tests of the previous attempt.
+async fn run_attempt(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+    rerun: Option<&Rerun>,
+) -> Result<Attempt> {
+    match rerun {
+        Some(rerun) => {
+            let result = run_test_binary(
+                case,
+                &rerun.executable_path,
+                Duration::ZERO,
+                ctx,
+                &rerun.failed_tests,
+                None,
+            )
+            .await?;
+            let rerun = Rerun::for_result(&result, rerun.executable_path.clone());
+            Ok(Attempt { result, rerun })
+        }
+        None => run_test_case_inner(case, ctx, temp_dir_tx).await,
+    }
+}
+
+/// Merges the result of rerunning only the failing tests into the outcome of the
+/// attempt they failed in: outputs are concatenated, durations added, and the
+/// timings of the rerun tests replaced.
+fn merge_rerun(
+    previous: TestResult,
+    rerun: TestResult,
+    rerun_tests: &[String],
+    locale: &str,
+) -> TestResult {
+    let previous_output = match &previous {
+        TestResult::Passed { output, .. }
+        | TestResult::Failed { output, .. }
+        | TestResult::Built { output, .. } => output.clone(),
+        TestResult::Skipped { .. } => String::new(),
+    };
+    let previous_duration = previous.get_duration().unwrap_or_default();
+    let mut timings: Vec<TestTiming> = previous
+        .get_test_timings()
+        .iter()
+        .filter(|timing| !rerun_tests.contains(&timing.name))
+        .cloned()
+        .collect();
+    timings.extend(rerun.get_test_timings().iter().cloned());
+    let attachments = previous.get_attachments().to_vec();
+    // Annotations written again by the rerun replace the earlier values.
+    let mut annotations = previous.get_annotations().clone();
+    annotations.extend(rerun.get_annotations().clone());
+    let header = t!("run.rerun_output_header", locale = locale, tests = rerun_tests.join(" "));
+
+    match rerun {
+        TestResult::Passed {
+            case,
+            output,
+            duration,
+            retries,
+            ..
+        } => TestResult::Passed {
+            case,
+            output: format!("{previous_output}\n{header}\n{output}"),
+            duration: previous_duration + duration,
+            retries,
+            attachments,
+            test_timings: timings,
+            annotations,
+        },
+        TestResult::Failed {
+            case,
+            output,
+            reason,
+            duration,
+            ..
+        } => TestResult::Failed {
+            case,
+            output: format!("{previous_output}\n{header}\n{output}"),
+            reason,
+            duration: previous_duration + duration,
+            attachments,
+            test_timings: timings,
+            annotations,
+        },
+        TestResult::Built { .. } | TestResult::Skipped { .. } => previous,
+    }
+}
+
+/// Dispatches to the correct execution flow based on whether a custom command is present
+/// and the case's kind.
+async fn run_test_case_inner(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<Attempt> {
+    if let Some(custom_command) = &case.command {
+        let result = run_custom_command_case(case.clone(), ctx, custom_command, temp_dir_tx).await?;
+        Ok(Attempt { result, rerun: None })
+    } else if case.kind == CaseKind::Tarpaulin {
+        let result = run_tarpaulin_case(case, ctx, temp_dir_tx).await?;
+        Ok(Attempt { result, rerun: None })
+    } else if case.kind == CaseKind::Doc {
+        let result = run_doc_case(case, ctx, temp_dir_tx).await?;
+        Ok(Attempt { result, rerun: None })
+    } else if case.kind == CaseKind::FuzzSmoke {
+        let result = run_fuzz_smoke_case(case, ctx, temp_dir_tx).await?;
+        Ok(Attempt { result, rerun: None })
+    } else if ctx.runs_with_nextest(&case) {
+        let result = run_nextest_case(case, ctx, temp_dir_tx).await?;
+        Ok(Attempt { result, rerun: None })
+    } else {
+        run_default_flow_case(case, ctx, temp_dir_tx).await
+    }
+}
+
+/// Executes a `kind = "tarpaulin"` case: runs its tests under `cargo tarpaulin` in its own
+/// target directory and records the line coverage from the JSON report, which is kept as
+/// an attachment of the case.
+async fn run_tarpaulin_case(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<TestResult> {
+    println!(
+        "{}",
+        t!("run.running_test", locale = &ctx.context.locale, name = case.name).blue()
+    );
+
+    let attach_dir = crate::infra::fs::create_attachment_dir(&ctx.state_dir, &case.name)?;
+    let annotations_file = crate::infra::fs::create_annotations_file(&ctx.state_dir, &case.name)?;
+    let (target_dir, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
+    temp_dir_tx
+        .send(temp_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
+
+    let mut cmd = tarpaulin_command(&case, ctx, &target_dir, &attach_dir);
+    cmd.env(ATTACH_DIR_ENV, &attach_dir)
+        .env(ANNOTATIONS_FILE_ENV, &annotations_file);
+    if case.requires_network {
+        cmd.env(NETWORK_TESTS_ENV, "1");
+    }
+    let _ports = apply_case_ports(&mut cmd, &case, ctx)?;
+    limits::apply_ulimits(&mut cmd, &case.ulimits, &ctx.context.locale);
+    apply_case_locale(&mut cmd, &case, ctx);
+    let command_log = format!(
+        "{} {}\n",
+        t!("run.command_prefix", locale = &ctx.context.locale).blue(),
+        format!("{:?}", cmd.as_std()).replace('"', "")
+    );
+
+    let start_time = Instant::now();
+    let (status_res, output) =
+        command::spawn_and_capture_with_idle_timeout(cmd, case.output_idle_timeout(), &ctx.context.locale).await;
+    let duration = start_time.elapsed();
+    record_target_size(ctx, &case.name, &target_dir).await;
+    let attachments = crate::infra::fs::collect_attachments(&attach_dir);
+    let annotations = crate::infra::fs::read_annotations(&annotations_file);
+    let test_timings = command::parse_test_timings(&output);
+    let output = format!("{command_log}{output}");
+
+    if let Err(e) = &status_res
+        && command::is_stalled(e)
+    {
+        return Ok(stalled_result(case, output, duration, attachments, annotations, &ctx.context.locale));
+    }
+    let status = status_res.context("Failed to get process status")?;
+
+    if !status.success() {
+        println!(
+            "{}",
+            t!("run.test_failed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).red()
+        );
+        return Ok(TestResult::Failed {
+            case,
+            output,
+            reason: FailureReason::TestFailed,
+            duration,
+            attachments,
+            test_timings,
+            annotations,
+        });
+    }
+
+    // A missing report only loses the coverage; the tests themselves passed.
+    match coverage::read_tarpaulin_report(&attach_dir) {
+        Ok(case_coverage) => {
+            ctx.coverage.record(&case.name, case_coverage);
+            println!(
+                "{}",
+                t!(
+                    "run.coverage_recorded",
+                    locale = &ctx.context.locale,
+                    name = &case.name,
+                    percent = format!("{:.2}", case_coverage.percent()),
+                    covered = case_coverage.covered,
+                    coverable = case_coverage.coverable
+                )
+                .green()
+            );
+        }
+        Err(e) => eprintln!(
+            "{} {:#}",
+            t!("run.coverage_report_missing", locale = &ctx.context.locale, name = &case.name).yellow(),
+            e
+        ),
+    }
+    println!(
+        "{}",
+        t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
+    );
+    Ok(TestResult::Passed {
+        case,
+        output,
+        duration,
+        retries: 1,
+        attachments,
+        test_timings,
+        annotations,
+    })
+}
+
+/// Executes a `kind = "doc"` case: builds the documentation of its package with its
+/// features in its own target directory, denying broken intra-doc links, since links often
+/// break only under some features. Failures are counted from rustdoc's diagnostics.
+async fn run_doc_case(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<TestResult> {
+    println!(
+        "{}",
+        t!("run.building_docs", locale = &ctx.context.locale, name = &case.name).blue()
+    );
+
+    let (target_dir, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
+    temp_dir_tx
+        .send(temp_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
+
+    let cmd = doc_command(&case, ctx, &target_dir);
+    let command_log = format!(
+        "{} {}\n",
+        t!("run.command_prefix", locale = &ctx.context.locale).blue(),
+        format!("{:?}", cmd.as_std()).replace('"', "")
+    );
+
+    let start_time = Instant::now();
+    let (status_res, output) = command::spawn_and_capture_with_idle_timeout(cmd, None, &ctx.context.locale).await;
+    let duration = start_time.elapsed();
+    ctx.costs.record_build(&case.name, duration);
+    record_target_size(ctx, &case.name, &target_dir).await;
+    let status = status_res.context("Failed to get process status")?;
+
+    if !status.success() {
+        let errors = command::parse_doc_errors(&output);
+        println!(
+            "{}",
+            t!(
+                "run.docs_failed",
+                locale = &ctx.context.locale,
+                name = &case.name,
+                broken_links = errors.broken_links,
+                other = errors.other
+            )
+            .red()
+        );
+        let error_output = command::format_build_error_output(&output, &ctx.context.locale);
+        return Ok(TestResult::Failed {
+            case,
+            output: format!("{command_log}{error_output}"),
+            reason: FailureReason::Doc,
+            duration,
+            attachments: Vec::new(),
+            test_timings: Vec::new(),
+            annotations: BTreeMap::new(),
+        });
+    }
+
+    println!(
+        "{}",
+        t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
+    );
+    Ok(TestResult::Passed {
+        case,
+        output: format!("{command_log}{}", command::format_build_log(&output)),
+        duration,
+        retries: 1,
+        attachments: Vec::new(),
+        test_timings: Vec::new(),
+        annotations: BTreeMap::new(),
+    })
+}
+
+/// Creates the `cargo doc --no-deps` command of a case, building into `target_dir`.
+fn doc_command(case: &TestCase, ctx: &ExecutionContext, target_dir: &Path) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cargo");
+    apply_case_env(&mut cmd, case);
+    if let Some(toolchain) = &case.toolchain {
+        cmd.arg(format!("+{toolchain}"));
+    }
+    cmd.arg("doc")
+        .arg("--no-deps")
+        .arg("--message-format=json")
+        .arg("--target-dir")
+        .arg(target_dir);
+    add_case_cargo_args(&mut cmd, case, ctx);
+    // The case's own `RUSTDOCFLAGS` come first, as in [`command_env`].
+    let existing = case.env.get(RUSTDOCFLAGS_ENV).cloned().or_else(|| std::env::var(RUSTDOCFLAGS_ENV).ok());
+    cmd.env(RUSTDOCFLAGS_ENV, doc_rustdocflags(existing.as_deref()));
+    cmd.kill_on_drop(true).current_dir(&ctx.project_root);
+    if case.uses_c_locale(ctx.force_c_locale) {
+        command::apply_c_locale(&mut cmd);
+    }
+    cmd
+}
+
+/// The `RUSTDOCFLAGS` of a `kind = "doc"` case: the flags already set, if any, followed by
+/// the one denying broken intra-doc links.
+fn doc_rustdocflags(existing: Option<&str>) -> String {
+    let deny = format!("-D {}", command::BROKEN_INTRA_DOC_LINKS);
+    match existing.map(str::trim).filter(|flags| !flags.is_empty()) {
+        Some(flags) => format!("{flags} {deny}"),
+        None => deny,
+    }
+}
+
+/// Executes a `kind = "fuzz-smoke"` case: builds each of its `cargo fuzz` targets with its
+/// features in its own target directory, then runs each for the bounded number of inputs
+/// or seconds. The fuzzer starts from the target's corpus in the project, but writes the
+/// inputs it finds to the build directory, so the source tree is left alone. A crash
+/// fails the case, with the crashing input saved as an attachment.
+async fn run_fuzz_smoke_case(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<TestResult> {
+    println!(
+        "{}",
+        t!("run.running_test", locale = &ctx.context.locale, name = case.name).blue()
+    );
+
+    let attach_dir = crate::infra::fs::create_attachment_dir(&ctx.state_dir, &case.name)?;
+    let (target_dir, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
+    temp_dir_tx
+        .send(temp_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
+
+    let start_time = Instant::now();
+    let build_failed = |case: TestCase, output: String| TestResult::Failed {
+        case,
+        output,
+        reason: FailureReason::Build,
+        duration: start_time.elapsed(),
+        attachments: Vec::new(),
+        test_timings: Vec::new(),
+        annotations: BTreeMap::new(),
+    };
+
+    let targets = if case.fuzz.targets.is_empty() {
+        let output = fuzz_command(&case, ctx, "list", None)
+            .output()
+            .await
+            .context("Failed to execute 'cargo fuzz list'")?;
+        if !output.status.success() {
+            println!(
+                "{}",
+                t!("run.fuzz_list_failed", locale = &ctx.context.locale, name = &case.name).red()
+            );
+            return Ok(build_failed(case, String::from_utf8_lossy(&output.stderr).into_owned()));
+        }
+        command::parse_fuzz_targets(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        case.fuzz.targets.clone()
+    };
+    if targets.is_empty() {
+        let message = t!("run.fuzz_no_targets", locale = &ctx.context.locale, name = &case.name);
+        println!("{}", message.red());
+        return Ok(build_failed(case, format!("{message}\n")));
+    }
+
+    let mut log = String::new();
+    for target in &targets {
+        let cmd = fuzz_command(&case, ctx, "build", Some((target, &target_dir)));
+        log.push_str(&command_log(&cmd, &ctx.context.locale));
+        let (status_res, output) =
+            command::spawn_and_capture_with_idle_timeout(cmd, None, &ctx.context.locale).await;
+        log.push_str(&output);
+        if !status_res.context("Failed to get process status")?.success() {
+            println!(
+                "{}",
+                t!("run.fuzz_build_failed", locale = &ctx.context.locale, name = &case.name, target = target).red()
+            );
+            return Ok(build_failed(case, log));
+        }
+    }
+    ctx.costs.record_build(&case.name, start_time.elapsed());
+    record_target_size(ctx, &case.name, &target_dir).await;
+
+    if ctx.is_build_only(&case) {
+        println!(
+            "{}",
+            t!("run.build_only_done", locale = &ctx.context.locale, name = &case.name).green()
+        );
+        return Ok(TestResult::Built {
+            case,
+            output: t!("run.build_only_message", locale = &ctx.context.locale).to_string(),
+            duration: start_time.elapsed(),
+        });
+    }
+
+    for target in &targets {
+        let corpus = target_dir.join("corpus").join(target);
+        fs::create_dir_all(&corpus)
+            .with_context(|| format!("Failed to create directory: {}", corpus.display()))?;
+        let mut cmd = fuzz_command(&case, ctx, "run", Some((target, &target_dir)));
+        cmd.arg(&corpus);
+        let seeds = ctx.project_root.join("fuzz").join("corpus").join(target);
+        if seeds.is_dir() {
+            cmd.arg(&seeds);
+        }
+        cmd.arg("--")
+            .args(case.fuzz.libfuzzer_args())
+            .arg(format!("-artifact_prefix={}", attach_dir.join(format!("{target}-")).display()));
+        if case.requires_network {
+            cmd.env(NETWORK_TESTS_ENV, "1");
+        }
+        limits::apply_ulimits(&mut cmd, &case.ulimits, &ctx.context.locale);
+        apply_case_locale(&mut cmd, &case, ctx);
+        println!(
+            "{}",
+            t!(
+                "run.fuzz_running",
+                locale = &ctx.context.locale,
+                name = &case.name,
+                target = target,
+                runs = case.fuzz.runs.unwrap_or(FuzzSmoke::DEFAULT_RUNS),
+                secs = case.fuzz.max_secs.unwrap_or(FuzzSmoke::DEFAULT_MAX_SECS)
+            )
+            .blue()
+        );
+        log.push_str(&command_log(&cmd, &ctx.context.locale));
+        let (status_res, output) =
+            command::spawn_and_capture_with_idle_timeout(cmd, case.output_idle_timeout(), &ctx.context.locale).await;
+        log.push_str(&output);
+        let attachments = crate::infra::fs::collect_attachments(&attach_dir);
+        if let Err(e) = &status_res
+            && command::is_stalled(e)
+        {
+            return Ok(stalled_result(case, log, start_time.elapsed(), attachments, BTreeMap::new(), &ctx.context.locale));
+        }
+        if !status_res.context("Failed to get process status")?.success() {
+            println!(
+                "{}",
+                t!("run.fuzz_crashed", locale = &ctx.context.locale, name = &case.name, target = target).red()
+            );
+            return Ok(TestResult::Failed {
+                case,
+                output: log,
+                reason: FailureReason::FuzzCrash,
+                duration: start_time.elapsed(),
+                attachments,
+                test_timings: Vec::new(),
+                annotations: BTreeMap::new(),
+            });
+        }
+    }
+
+    let duration = start_time.elapsed();
+    println!(
+        "{}",
+        t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
+    );
+    Ok(TestResult::Passed {
+        case,
+        output: log,
+        duration,
+        retries: 1,
+        attachments: crate::infra::fs::collect_attachments(&attach_dir),
+        test_timings: Vec::new(),
+        annotations: BTreeMap::new(),
+    })
+}
+
+/// Creates a `cargo fuzz <subcommand>` command of a case, for a fuzz target built into a
+/// target directory if given. `cargo fuzz` works on the fuzz crate in the project's `fuzz`
+/// directory, so the case's `package` does not apply.
+fn fuzz_command(
+    case: &TestCase,
+    ctx: &ExecutionContext,
+    subcommand: &str,
+    target: Option<(&str, &Path)>,
+) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cargo");
+    apply_case_env(&mut cmd, case);
+    if let Some(toolchain) = &case.toolchain {
+        cmd.arg(format!("+{toolchain}"));
+    }
+    cmd.arg("fuzz").arg(subcommand);
+    if let Some((target, target_dir)) = target {
+        cmd.arg(target)
+            .args(fuzz_cargo_args(case))
+            .arg("--target-dir")
+            .arg(target_dir);
+    }
+    cmd.kill_on_drop(true).current_dir(&ctx.project_root);
+    if case.uses_c_locale(ctx.force_c_locale) {
+        command::apply_c_locale(&mut cmd);
+    }
+    cmd
+}
+
+/// The options `cargo fuzz build` and `cargo fuzz run` take from a case: its features and target.
+fn fuzz_cargo_args(case: &TestCase) -> Vec<String> {
+    let mut args = Vec::new();
+    if case.no_default_features {
+        args.push("--no-default-features".to_string());
+    }
+    if !case.features.is_empty() {
+        args.push("--features".to_string());
+        args.push(case.features.clone());
+    }
+    if let Some(target) = &case.target {
+        args.push("--target".to_string());
+        args.push(target.clone());
+    }
+    args
 }
 
-/// Dispatches to the correct execution flow based on whether a custom command is present.
-async fn run_test_case_inner(
+/// The line logging a command before its output / 在命令输出之前记录该命令的行
+fn command_log(cmd: &tokio::process::Command, locale: &str) -> String {
+    format!(
+        "{} {}\n",
+        t!("run.command_prefix", locale = locale).blue(),
+        format!("{:?}", cmd.as_std()).replace('"', "")
+    )
+}
+
+/// Executes a case of the default flow with `cargo nextest run`, which builds and runs
+/// its tests in one step in the case's own target directory. A build failure is told
+/// apart from failing tests by nextest's exit code. With `--keep-going`, a build failing
+/// from the dependency layer is run again without it.
+async fn run_nextest_case(
     case: TestCase,
-    project_root: &PathBuf,
-    crate_name: &str,
+    ctx: &ExecutionContext,
     temp_dir_tx: mpsc::UnboundedSender<TempDir>,
 ) -> Result<TestResult> {
-    if let Some(custom_command) = &case.command {
-        run_custom_command_case(case.clone(), project_root, custom_command).await
+    let from_layer = ctx.seeds_from_layer(&case);
+    let result = run_nextest_case_in(case.clone(), ctx, temp_dir_tx.clone(), from_layer).await?;
+    let build_failed = matches!(&result, TestResult::Failed { reason: FailureReason::Build, .. });
+    if !(build_failed && ctx.rebuilds_without_layer(&case, from_layer)) {
+        return Ok(result);
+    }
+    let result = run_nextest_case_in(case.clone(), ctx, temp_dir_tx, false).await?;
+    if !matches!(&result, TestResult::Failed { reason: FailureReason::Build, .. }) {
+        ctx.discard_layer(&case);
+    }
+    Ok(result)
+}
+
+/// Runs a case with `cargo nextest run` once, in a build directory seeded with the
+/// dependency layer if `from_layer` is set.
+async fn run_nextest_case_in(
+    case: TestCase,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+    from_layer: bool,
+) -> Result<TestResult> {
+    println!(
+        "{}",
+        t!("run.running_test", locale = &ctx.context.locale, name = case.name).blue()
+    );
+
+    let annotations_file = crate::infra::fs::create_annotations_file(&ctx.state_dir, &case.name)?;
+    let (target_dir, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
+    temp_dir_tx
+        .send(temp_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
+    if from_layer {
+        ctx.seed_build_dir(&case, &target_dir);
+    }
+
+    let mut cmd = tokio::process::Command::new("cargo");
+    apply_case_env(&mut cmd, &case);
+    if let Some(toolchain) = &case.toolchain {
+        cmd.arg(format!("+{toolchain}"));
+    }
+    cmd.arg("nextest")
+        .arg("run")
+        .arg("--no-fail-fast")
+        .arg("--target-dir")
+        .arg(&target_dir);
+    add_case_cargo_args(&mut cmd, &case, ctx);
+    if !case.skip_tests.is_empty() {
+        cmd.arg("--").args(skip_args(&case));
+    }
+    cmd.env(ANNOTATIONS_FILE_ENV, &annotations_file)
+        .kill_on_drop(true)
+        .current_dir(&ctx.project_root);
+    if case.requires_network {
+        cmd.env(NETWORK_TESTS_ENV, "1");
+    }
+    let _ports = apply_case_ports(&mut cmd, &case, ctx)?;
+    limits::apply_ulimits(&mut cmd, &case.ulimits, &ctx.context.locale);
+    apply_case_locale(&mut cmd, &case, ctx);
+    let command_log = format!(
+        "{} {}\n",
+        t!("run.command_prefix", locale = &ctx.context.locale).blue(),
+        format!("{:?}", cmd.as_std()).replace('"', "")
+    );
+
+    let start_time = Instant::now();
+    let (status_res, output) =
+        command::spawn_and_capture_with_idle_timeout(cmd, case.output_idle_timeout(), &ctx.context.locale).await;
+    let duration = start_time.elapsed();
+    record_target_size(ctx, &case.name, &target_dir).await;
+    let annotations = crate::infra::fs::read_annotations(&annotations_file);
+    let test_timings = command::parse_test_timings(&output);
+    let output = format!("{command_log}{output}");
+
+    if !output.trim().is_empty() {
+        println!("{}", output.trim());
+    }
+    if let Err(e) = &status_res
+        && command::is_stalled(e)
+    {
+        return Ok(stalled_result(case, output, duration, Vec::new(), annotations, &ctx.context.locale));
+    }
+    let status = status_res.context("Failed to get process status")?;
+
+    if status.success() {
+        println!(
+            "{}",
+            t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
+        );
+        return Ok(TestResult::Passed {
+            case,
+            output,
+            duration,
+            retries: 1,
+            attachments: Vec::new(),
+            test_timings,
+            annotations,
+        });
+    }
+
+    let reason = if status.code() == Some(NEXTEST_BUILD_FAILED_EXIT_CODE) {
+        println!(
+            "{}",
+            t!("run.build_failed", locale = &ctx.context.locale, duration = duration.as_secs_f64()).red()
+        );
+        FailureReason::Build
     } else {
-        run_default_flow_case(case, project_root, crate_name, temp_dir_tx).await
+        println!(
+            "{}",
+            t!("run.test_failed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).red()
+        );
+        FailureReason::TestFailed
+    };
+    Ok(TestResult::Failed {
+        case,
+        output,
+        reason,
+        duration,
+        attachments: Vec::new(),
+        test_timings,
+        annotations,
+    })
+}
+
+/// Creates the `cargo tarpaulin` command of a case, writing its JSON report to `output_dir`.
+fn tarpaulin_command(
+    case: &TestCase,
+    ctx: &ExecutionContext,
+    target_dir: &Path,
+    output_dir: &Path,
+) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cargo");
+    apply_case_env(&mut cmd, case);
+    if let Some(toolchain) = &case.toolchain {
+        cmd.arg(format!("+{toolchain}"));
     }
+    cmd.arg("tarpaulin")
+        .arg("--out")
+        .arg("Json")
+        .arg("--output-dir")
+        .arg(output_dir)
+        .arg("--target-dir")
+        .arg(target_dir);
+    add_case_cargo_args(&mut cmd, case, ctx);
+    cmd.kill_on_drop(true).current_dir(&ctx.project_root);
+    cmd
 }
 
 /// Executes a test case defined by a custom shell command.
 async fn run_custom_command_case(
     case: TestCase,
-    project_root: &PathBuf,
+    ctx: &ExecutionContext,
     custom_command: &str,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
 ) -> Result<TestResult> {
     println!(
         "{}",
-        t!("run.running_test", name = case.name).blue()
+        t!("run.running_test", locale = &ctx.context.locale, name = case.name).blue()
     );
 
+    let attach_dir = crate::infra::fs::create_attachment_dir(&ctx.state_dir, &case.name)?;
+    let attach_dir_str = attach_dir.to_string_lossy().to_string();
+    let annotations_file = crate::infra::fs::create_annotations_file(&ctx.state_dir, &case.name)?;
+
+    let ports = reserve_case_ports(&case, ctx)?;
+    let port_vars = port_vars(&ports);
+
     let start_time = Instant::now();
-    let expanded_command = shellexpand::full(custom_command)
-        .with_context(|| format!("Failed to expand command: {custom_command}"))?
-        .to_string();
+    // `$MATRIX_RUNNER_ATTACH_DIR`, `$MATRIX_PORT_<n>` and the case's `env` may be referenced
+    // directly in the command string, so they are resolved before falling back to the
+    // process environment.
+    let expanded_command = shellexpand::full_with_context(
+        custom_command,
+        || std::env::var("HOME").ok(),
+        |var: &str| -> std::result::Result<Option<String>, std::env::VarError> {
+            if var == ATTACH_DIR_ENV {
+                Ok(Some(attach_dir_str.clone()))
+            } else if let Some((_, port)) = port_vars.iter().find(|(name, _)| name == var) {
+                Ok(Some(port.clone()))
+            } else if let Some(value) = case.env.get(var) {
+                Ok(Some(value.clone()))
+            } else {
+                std::env::var(var).map(Some)
+            }
+        },
+    )
+    .with_context(|| format!("Failed to expand command: {custom_command}"))?
+    .to_string();
 
     let parts = shlex::split(&expanded_command)
         .ok_or_else(|| anyhow::anyhow!("Failed to parse command: {}", expanded_command))?;
@@ -156,16 +1424,54 @@ async fn run_custom_command_case(
     let program = &parts[0];
     let args = &parts[1..];
 
-    let mut cmd = tokio::process::Command::new(program);
-    cmd.args(args).kill_on_drop(true).current_dir(project_root);
+    let mut cmd = sandbox::command(program, case.sandbox.as_ref(), &ctx.project_root, &ctx.state_dir)?;
+    apply_case_env(&mut cmd, &case);
+    cmd.args(args)
+        .env(ATTACH_DIR_ENV, &attach_dir)
+        .env(ANNOTATIONS_FILE_ENV, &annotations_file)
+        .envs(port_vars)
+        .kill_on_drop(true)
+        .current_dir(&ctx.project_root);
+    if case.requires_network {
+        cmd.env(NETWORK_TESTS_ENV, "1");
+    }
+    limits::apply_ulimits(&mut cmd, &case.ulimits, &ctx.context.locale);
+    apply_case_locale(&mut cmd, &case, ctx);
+    // With `--keep-going`, a failed custom command may have left partial artifacts in the
+    // project's target directory, so the commands after it get their own.
+    let isolated = ctx.isolated_only
+        || (ctx.keep_going && ctx.isolation.snapshot().shared_target_failed_in.is_some());
+    let isolated_target_dir = if isolated {
+        let (target_dir, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
+        temp_dir_tx
+            .send(temp_dir)
+            .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
+        cmd.env("CARGO_TARGET_DIR", &target_dir);
+        Some(target_dir)
+    } else {
+        None
+    };
 
-    let (status_res, output) = command::spawn_and_capture(cmd).await;
-    let status = status_res.context("Failed to get process status")?;
+    let (status_res, output) =
+        command::spawn_and_capture_with_idle_timeout(cmd, case.output_idle_timeout(), &ctx.context.locale).await;
     let duration = start_time.elapsed();
+    if let Some(target_dir) = &isolated_target_dir {
+        record_target_size(ctx, &case.name, target_dir).await;
+    }
+    let failed = !status_res.as_ref().is_ok_and(|status| case.is_success_exit_code(status.code()));
+    if failed && ctx.keep_going && isolated_target_dir.is_none() && ctx.isolation.leave_shared_target(&case.name) {
+        println!(
+            "{}",
+            t!("run.keep_going_shared_target_left", locale = &ctx.context.locale, name = &case.name).yellow()
+        );
+    }
+    let attachments = crate::infra::fs::collect_attachments(&attach_dir);
+    let annotations = crate::infra::fs::read_annotations(&annotations_file);
+    let test_timings = command::parse_test_timings(&output);
 
     let command_log = format!(
         "{} {}\n",
-        t!("run.command_prefix").blue(),
+        t!("run.command_prefix", locale = &ctx.context.locale).blue(),
         expanded_command
     );
     let output = format!("{command_log}{output}");
@@ -174,27 +1480,40 @@ async fn run_custom_command_case(
         println!("{}", output.trim());
     }
 
-    if status.success() {
+    if let Err(e) = &status_res
+        && command::is_stalled(e)
+    {
+        return Ok(stalled_result(case, output, duration, attachments, annotations, &ctx.context.locale));
+    }
+    let status = status_res.context("Failed to get process status")?;
+
+    if case.is_success_exit_code(status.code()) {
         println!(
             "{}",
-            t!("run.test_passed", name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
+            t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).green()
         );
         Ok(TestResult::Passed {
             case,
             output,
             duration,
             retries: 1,
+            attachments,
+            test_timings,
+            annotations,
         })
     } else {
         println!(
             "{}",
-            t!("run.test_failed", name = &case.name, duration = &duration.as_secs_f64().to_string()).red()
+            t!("run.test_failed", locale = &ctx.context.locale, name = &case.name, duration = &duration.as_secs_f64().to_string()).red()
         );
         Ok(TestResult::Failed {
             case,
             output,
             reason: FailureReason::CustomCommand,
             duration,
+            attachments,
+            test_timings,
+            annotations,
         })
     }
 }
@@ -202,12 +1521,52 @@ async fn run_custom_command_case(
 /// Executes the default test flow: build the test, then run the resulting binary.
 async fn run_default_flow_case(
     case: TestCase,
-    project_root: &PathBuf,
-    crate_name: &str,
+    ctx: &ExecutionContext,
     temp_dir_tx: mpsc::UnboundedSender<TempDir>,
-) -> Result<TestResult> {
-    match build_test_case(case.clone(), project_root, crate_name, temp_dir_tx).await {
-        Ok(built_test) => run_built_test(built_test, project_root).await,
+) -> Result<Attempt> {
+    let mut build = build_test_case(case.clone(), ctx, temp_dir_tx.clone()).await;
+    if let Ok(built_test) = &build
+        && (ctx.record_checksums || ctx.verify_reproducible)
+    {
+        let checksum = checksum::sha256_file(&built_test.executable_path)?;
+        ctx.checksums.record(&case.name, checksum.clone());
+        if ctx.verify_reproducible {
+            match verify_reproducible(&case, &checksum, ctx, temp_dir_tx).await? {
+                Ok(rebuilt) => build = Ok(rebuilt),
+                Err(result) => return Ok(Attempt { result, rerun: None }),
+            }
+        }
+    }
+
+    match build {
+        Ok(built_test) if ctx.is_build_only(&case) => {
+            let locale = &ctx.context.locale;
+            let (done, output) = match ctx.foreign_target(&case).filter(|_| !case.build_only) {
+                Some(target) => (
+                    t!("run.foreign_target_done", locale = locale, name = &case.name, target = target),
+                    t!("run.foreign_target_message", locale = locale, target = target),
+                ),
+                None => (
+                    t!("run.build_only_done", locale = locale, name = &case.name),
+                    t!("run.build_only_message", locale = locale),
+                ),
+            };
+            println!("{}", done.green());
+            Ok(Attempt {
+                result: TestResult::Built {
+                    case,
+                    output: output.to_string(),
+                    duration: built_test.duration,
+                },
+                rerun: None,
+            })
+        }
+        Ok(built_test) => {
+            let executable_path = built_test.executable_path.clone();
+            let result = run_built_test(built_test, ctx).await?;
+            let rerun = Rerun::for_result(&result, executable_path);
+            Ok(Attempt { result, rerun })
+        }
         Err(e) => {
             let error_string = e.to_string();
             let final_error_result = if let Ok(test_result) = e.downcast::<TestResult>() {
@@ -215,7 +1574,7 @@ async fn run_default_flow_case(
             } else {
                 println!(
                     "{}",
-                    t!("run.build_failed_unexpected").red()
+                    t!("run.build_failed_unexpected", locale = &ctx.context.locale).red()
                 );
                 println!("  Error: {}", error_string);
                 TestResult::Failed {
@@ -223,68 +1582,182 @@ async fn run_default_flow_case(
                     output: error_string,
                     reason: FailureReason::BuildFailed,
                     duration: Duration::from_secs(0),
+                    attachments: Vec::new(),
+                    test_timings: Vec::new(),
+                    annotations: BTreeMap::new(),
                 }
             };
-            Ok(final_error_result)
+            Ok(Attempt {
+                result: final_error_result,
+                rerun: None,
+            })
+        }
+    }
+}
+
+/// Builds a case a second time and compares its test binary with the first build's.
+///
+/// The rebuild reuses the case's build directory, replacing the first build, so the
+/// rebuilt test is returned for running.
+///
+/// # Returns
+/// The rebuilt test if both builds match, or the failure to report otherwise
+async fn verify_reproducible(
+    case: &TestCase,
+    checksum: &str,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<std::result::Result<BuiltTest, TestResult>> {
+    println!(
+        "{}",
+        t!("run.verifying_reproducible", locale = &ctx.context.locale, name = &case.name).blue()
+    );
+    let rebuilt = match build_test_case(case.clone(), ctx, temp_dir_tx).await {
+        Ok(rebuilt) => rebuilt,
+        Err(e) => {
+            let result = e
+                .downcast::<TestResult>()
+                .unwrap_or_else(|e| TestResult::Failed {
+                    case: case.clone(),
+                    output: e.to_string(),
+                    reason: FailureReason::BuildFailed,
+                    duration: Duration::from_secs(0),
+                    attachments: Vec::new(),
+                    test_timings: Vec::new(),
+                    annotations: BTreeMap::new(),
+                });
+            return Ok(Err(result));
         }
+    };
+
+    let rebuilt_checksum = checksum::sha256_file(&rebuilt.executable_path)?;
+    if rebuilt_checksum == checksum {
+        return Ok(Ok(rebuilt));
     }
+    println!(
+        "{}",
+        t!("run.not_reproducible", locale = &ctx.context.locale, name = &case.name).red()
+    );
+    Ok(Err(TestResult::Failed {
+        case: case.clone(),
+        output: t!(
+            "run.not_reproducible_message",
+            locale = &ctx.context.locale,
+            first = checksum,
+            second = rebuilt_checksum
+        )
+        .to_string(),
+        reason: FailureReason::NotReproducible,
+        duration: rebuilt.duration,
+        attachments: Vec::new(),
+        test_timings: Vec::new(),
+        annotations: BTreeMap::new(),
+    }))
 }
 
-/// Builds a single test case using `cargo test --no-run`.
+/// Builds a single test case using `cargo test --no-run`. With `--keep-going`, a build
+/// failing from the dependency layer is built again without it.
 async fn build_test_case(
     case: TestCase,
-    project_root: &PathBuf,
-    crate_name: &str,
+    ctx: &ExecutionContext,
+    temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+) -> Result<BuiltTest> {
+    let from_layer = ctx.seeds_from_layer(&case);
+    let build = build_test_case_in(case.clone(), ctx, temp_dir_tx.clone(), from_layer).await;
+    let build_failed = build.as_ref().is_err_and(|e| {
+        matches!(e.downcast_ref::<TestResult>(), Some(TestResult::Failed { reason: FailureReason::Build, .. }))
+    });
+    if !(build_failed && ctx.rebuilds_without_layer(&case, from_layer)) {
+        return build;
+    }
+    let build = build_test_case_in(case.clone(), ctx, temp_dir_tx, false).await;
+    if build.is_ok() {
+        ctx.discard_layer(&case);
+    }
+    build
+}
+
+/// Builds a test case once, in a build directory seeded with the dependency layer if
+/// `from_layer` is set.
+async fn build_test_case_in(
+    case: TestCase,
+    ctx: &ExecutionContext,
     temp_dir_tx: mpsc::UnboundedSender<TempDir>,
+    from_layer: bool,
 ) -> Result<BuiltTest> {
-    let (build_path, temp_dir) = crate::infra::fs::create_build_dir(project_root, &case.name)?;
+    let (build_path, temp_dir) = crate::infra::fs::create_build_dir(&ctx.project_root, &case.name)?;
     temp_dir_tx
         .send(temp_dir)
         .map_err(|e| anyhow::anyhow!("Failed to send temp dir through channel: {}", e))?;
-    let build_ctx = BuildContext::new(build_path);
+    // The copy of the dependency layer counts towards the build time of the case.
     let build_start_time = Instant::now();
-
-    let mut cmd = tokio::process::Command::new("cargo");
-    cmd.arg("test")
-        .arg("--no-run")
-        .arg("--message-format=json")
-        .arg("--target-dir")
-        .arg(&build_ctx.path)
-        .arg("-p")
-        .arg(crate_name);
-
-    if case.no_default_features {
-        cmd.arg("--no-default-features");
-    }
-    if !case.features.is_empty() {
-        cmd.arg("--features").arg(&case.features);
+    if from_layer {
+        ctx.seed_build_dir(&case, &build_path);
     }
-
-    cmd.kill_on_drop(true).current_dir(project_root);
+    let build_ctx = BuildContext::new(build_path);
 
     println!(
         "{}",
-        t!("run.building_test", name = &case.name).blue()
+        t!("run.building_test", locale = &ctx.context.locale, name = &case.name).blue()
     );
 
-    let command_string_for_log = format!("{:?}", cmd).replace('"', "");
+    let command_string_for_log =
+        format!("{:?}", cargo_build_command(&case, ctx, &build_ctx.path)).replace('"', "");
 
-    let (status_res, output) = command::spawn_and_capture(cmd).await;
+    // Builds failing with a transient cargo error are retried without failing the case.
+    let mut transient_retries = 0;
+    let (status_res, output) = loop {
+        let (status_res, output) = command::spawn_and_capture_with_idle_timeout(
+            cargo_build_command(&case, ctx, &build_ctx.path),
+            None,
+            &ctx.context.locale,
+        )
+        .await;
+        let failed = status_res.as_ref().is_ok_and(|status| !status.success());
+        if failed
+            && transient_retries < ctx.transient_build_retries
+            && command::is_transient_build_failure(&output)
+        {
+            transient_retries += 1;
+            ctx.transient_retries.record(&case.name);
+            let delay = TRANSIENT_RETRY_DELAY * 2u32.pow(u32::from(transient_retries) - 1);
+            println!(
+                "{}",
+                t!(
+                    "run.build_transient_retry",
+                    locale = &ctx.context.locale,
+                    name = &case.name,
+                    attempt = transient_retries,
+                    max = ctx.transient_build_retries,
+                    delay = delay.as_secs()
+                )
+                .yellow()
+            );
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+        break (status_res, output);
+    };
     let build_duration = build_start_time.elapsed();
+    ctx.costs.record_build(&case.name, build_duration);
+    record_target_size(ctx, &case.name, &build_ctx.path).await;
 
     let status = status_res.with_context(|| "Failed to get build process status")?;
+    if ctx.keep_build_logs.keeps(status.success()) {
+        archive_build_log(&case.name, ctx, &command_string_for_log, &output, build_duration);
+    }
 
     if !status.success() {
         println!(
             "{}",
-            t!("run.build_failed", duration = build_duration.as_secs_f64()).red()
+            t!("run.build_failed", locale = &ctx.context.locale, duration = build_duration.as_secs_f64()).red()
         );
 
         // Format and return the error output
-        let error_output = command::format_build_error_output(&output);
+        let error_output = command::format_build_error_output(&output, &ctx.context.locale);
         let command_log = format!(
             "{} {}\n",
-            t!("run.command_prefix").blue(),
+            t!("run.command_prefix", locale = &ctx.context.locale).blue(),
             command_string_for_log
         );
         let full_output = format!("{command_log}{error_output}");
@@ -293,28 +1766,21 @@ async fn build_test_case(
             output: full_output,
             reason: FailureReason::Build,
             duration: build_duration,
+            attachments: Vec::new(),
+            test_timings: Vec::new(),
+            annotations: BTreeMap::new(),
         }));
     }
 
-    let mut test_binary: Option<PathBuf> = None;
-
-    for line in output.lines() {
-        if let Ok(message) = serde_json::from_str::<crate::core::models::CargoMessage>(line) {
-            if let Some(artifact) = message.into_artifact() {
-                if let (Some(target), Some(executable)) = (artifact.target, artifact.executable) {
-                    if target.test {
-                        test_binary = Some(executable);
-                        break;
-                    }
-                }
-            }
-        }
-    }
+    let test_binary: Option<PathBuf> = output
+        .lines()
+        .filter_map(crate::core::models::CargoMessage::parse)
+        .find_map(|message| message.test_executable().map(PathBuf::from));
 
     let executable_path = test_binary.unwrap_or_default();
     println!(
         "{}",
-        t!("run.build_success", duration = build_duration.as_secs_f64()).green()
+        t!("run.build_success", locale = &ctx.context.locale, duration = build_duration.as_secs_f64()).green()
     );
 
     Ok(BuiltTest::new(
@@ -325,48 +1791,297 @@ async fn build_test_case(
     ))
 }
 
+/// The `cargo test --no-run` command building a test case into `build_path`.
+fn cargo_build_command(case: &TestCase, ctx: &ExecutionContext, build_path: &Path) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cargo");
+    apply_case_env(&mut cmd, case);
+    if let Some(toolchain) = &case.toolchain {
+        cmd.arg(format!("+{toolchain}"));
+    }
+    cmd.arg("test")
+        .arg("--no-run")
+        .arg("--message-format=json")
+        .arg("--target-dir")
+        .arg(build_path);
+    add_case_cargo_args(&mut cmd, case, ctx);
+
+    cmd.kill_on_drop(true).current_dir(&ctx.project_root);
+    if case.uses_c_locale(ctx.force_c_locale) {
+        command::apply_c_locale(&mut cmd);
+    }
+    cmd
+}
+
+/// Adds the case's package, features, target and unstable flags to a cargo command.
+fn add_case_cargo_args(cmd: &mut tokio::process::Command, case: &TestCase, ctx: &ExecutionContext) {
+    let package = case.package.as_deref().unwrap_or(&ctx.crate_name);
+    if !package.is_empty() {
+        cmd.arg("-p").arg(package);
+    }
+
+    if case.no_default_features {
+        cmd.arg("--no-default-features");
+    }
+    if !case.features.is_empty() {
+        cmd.arg("--features").arg(&case.features);
+    }
+    if let Some(target) = &case.target {
+        cmd.arg("--target").arg(target);
+    }
+    cmd.args(&case.cargo_unstable_flags);
+}
+
+/// The `--skip` arguments of the test binary leaving out the case's `skip_tests`.
+fn skip_args(case: &TestCase) -> impl Iterator<Item = &str> {
+    case.skip_tests.iter().flat_map(|test| ["--skip", test.as_str()])
+}
+
+/// Records the size of a case's target directory. The directory is walked on a blocking
+/// thread, as a target directory can hold many thousands of files.
+async fn record_target_size(ctx: &ExecutionContext, case_name: &str, target_dir: &Path) {
+    let dir = target_dir.to_path_buf();
+    let bytes = tokio::task::spawn_blocking(move || crate::infra::fs::dir_size(&dir))
+        .await
+        .unwrap_or_default();
+    ctx.costs.record_target_size(case_name, bytes);
+}
+
+/// Writes the command, duration and compiler diagnostics of a build to
+/// `<state_dir>/logs/<case>.build.log`. Like the reports, a log that cannot be written
+/// is reported without affecting the case.
+fn archive_build_log(
+    case_name: &str,
+    ctx: &ExecutionContext,
+    command_line: &str,
+    raw_output: &str,
+    duration: Duration,
+) {
+    let log_dir = ctx.state_dir.join(crate::reporting::console::LOGS_DIR);
+    let path = log_dir.join(format!("{}.build.log", crate::infra::fs::case_dir_name(case_name)));
+    let diagnostics = command::format_build_log(raw_output);
+    let content = format!(
+        "{command_line}\n{}\n\n{diagnostics}\n",
+        t!("run.build_log_duration", locale = &ctx.context.locale, duration = duration.as_secs_f64())
+    );
+    if let Err(e) = std::fs::create_dir_all(&log_dir).and_then(|_| std::fs::write(&path, content)) {
+        eprintln!(
+            "{} {}",
+            t!("run.build_log_write_failed", locale = &ctx.context.locale, path = path.display()).yellow(),
+            e
+        );
+    }
+}
+
 /// Executes a previously built test binary.
-async fn run_built_test(built_test: BuiltTest, project_root: &PathBuf) -> Result<TestResult> {
+async fn run_built_test(built_test: BuiltTest, ctx: &ExecutionContext) -> Result<TestResult> {
     let case = built_test.case.clone();
     if built_test.executable_path.as_os_str().is_empty() {
         println!(
             "{}",
-            t!("run.test_no_binaries", name = case.name).yellow()
+            t!("run.test_no_binaries", locale = &ctx.context.locale, name = case.name).yellow()
         );
         return Ok(TestResult::Passed {
             case,
-            output: t!("run.test_no_binaries_message").to_string(),
+            output: t!("run.test_no_binaries_message", locale = &ctx.context.locale).to_string(),
             duration: built_test.duration,
             retries: 1,
+            attachments: Vec::new(),
+            test_timings: Vec::new(),
+            annotations: BTreeMap::new(),
         });
     }
 
+    match case.shards {
+        Some(count) if count > 1 => {
+            run_sharded_test_binary(case, &built_test.executable_path, built_test.duration, ctx, count)
+                .await
+        }
+        _ => run_test_binary(case, &built_test.executable_path, built_test.duration, ctx, &[], None).await,
+    }
+}
+
+/// One of the processes a sharded test binary runs in / 分片测试二进制文件运行时的其中一个进程
+#[derive(Debug, Clone, Copy)]
+struct Shard {
+    /// The 1-based index of the shard / 分片的序号（从 1 开始）
+    index: u32,
+    /// The number of shards / 分片总数
+    count: u32,
+}
+
+impl std::fmt::Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Runs the shards of a test binary in parallel and merges their results: the outputs are
+/// concatenated in shard order, the case takes as long as its slowest shard, keeps the
+/// attachments of every shard, and it fails with the reason of the first failing shard if
+/// any shard failed.
+async fn run_sharded_test_binary(
+    case: TestCase,
+    executable_path: &Path,
+    build_duration: Duration,
+    ctx: &ExecutionContext,
+    count: u32,
+) -> Result<TestResult> {
+    println!(
+        "{}",
+        t!("run.running_shards", locale = &ctx.context.locale, name = &case.name, count = count).blue()
+    );
+    let shards = (1..=count).map(|index| {
+        let shard = Shard { index, count };
+        run_test_binary(case.clone(), executable_path, Duration::ZERO, ctx, &[], Some(shard))
+    });
+    let results = futures::future::try_join_all(shards).await?;
+
+    let mut output = String::new();
+    let mut run_duration = Duration::ZERO;
+    let mut test_timings = Vec::new();
+    let mut annotations = BTreeMap::new();
+    let mut attachments = Vec::new();
+    let mut failure = None;
+    for result in results {
+        run_duration = run_duration.max(result.get_duration().unwrap_or_default());
+        test_timings.extend(result.get_test_timings().iter().cloned());
+        annotations.extend(result.get_annotations().clone());
+        attachments.extend(result.get_attachments().iter().cloned());
+        match result {
+            TestResult::Passed { output: shard_output, .. } => output.push_str(&shard_output),
+            TestResult::Failed { output: shard_output, reason, .. } => {
+                output.push_str(&shard_output);
+                failure.get_or_insert(reason);
+            }
+            TestResult::Built { .. } | TestResult::Skipped { .. } => {}
+        }
+    }
+    let duration = build_duration + run_duration;
+
+    match failure {
+        None => {
+            println!(
+                "{}",
+                t!("run.test_passed", locale = &ctx.context.locale, name = &case.name, duration = duration.as_secs_f64().to_string())
+                    .green()
+            );
+            Ok(TestResult::Passed {
+                case,
+                output,
+                duration,
+                retries: 1,
+                attachments,
+                test_timings,
+                annotations,
+            })
+        }
+        Some(reason) => {
+            println!(
+                "{}",
+                t!("run.test_failed", locale = &ctx.context.locale, name = &case.name, duration = duration.as_secs_f64().to_string())
+                    .red()
+            );
+            Ok(TestResult::Failed {
+                case,
+                output,
+                reason,
+                duration,
+                attachments,
+                test_timings,
+                annotations,
+            })
+        }
+    }
+}
+
+/// Runs a test binary. If `filter` is not empty, only the tests with exactly these
+/// names are run, which is how retries rerun just the failing tests. With a `shard`,
+/// the process runs as that shard of the case, with [`SHARD_ENV`] set.
+async fn run_test_binary(
+    case: TestCase,
+    executable_path: &Path,
+    build_duration: Duration,
+    ctx: &ExecutionContext,
+    filter: &[String],
+    shard: Option<Shard>,
+) -> Result<TestResult> {
+    let name = match shard {
+        Some(shard) => t!("run.shard_name", locale = &ctx.context.locale, name = &case.name, shard = shard).to_string(),
+        None => case.name.clone(),
+    };
     println!(
         "{}",
-        t!("run.running_test", name = case.name).blue()
+        t!("run.running_test", locale = &ctx.context.locale, name = &name).blue()
     );
 
-    let mut cmd = tokio::process::Command::new(&built_test.executable_path);
-    cmd.kill_on_drop(true).current_dir(project_root);
+    let mut cmd = sandbox::command(executable_path, case.sandbox.as_ref(), &ctx.project_root, &ctx.state_dir)?;
+    apply_case_env(&mut cmd, &case);
+    if !filter.is_empty() {
+        cmd.arg("--exact").args(filter);
+    }
+    cmd.args(skip_args(&case));
+    // Each shard writes its own annotations, merged afterwards.
+    let annotations_name = match shard {
+        Some(shard) => format!("{}.shard-{}", case.name, shard.index),
+        None => case.name.clone(),
+    };
+    let annotations_file = crate::infra::fs::create_annotations_file(&ctx.state_dir, &annotations_name)?;
+    cmd.env(ANNOTATIONS_FILE_ENV, &annotations_file)
+        .kill_on_drop(true)
+        .current_dir(&ctx.project_root);
+    if let Some(shard) = shard {
+        cmd.env(SHARD_ENV, shard.to_string());
+    }
+    if case.requires_network {
+        cmd.env(NETWORK_TESTS_ENV, "1");
+    }
+    let _ports = apply_case_ports(&mut cmd, &case, ctx)?;
+    // A core size limit set by the case takes precedence.
+    if ctx.collect_core_dumps && case.ulimits.core.is_none() {
+        limits::raise_core_limit(&mut cmd);
+    }
+    limits::apply_ulimits(&mut cmd, &case.ulimits, &ctx.context.locale);
+    apply_case_locale(&mut cmd, &case, ctx);
 
     let run_start_time = Instant::now();
-    let (status_res, output) = command::spawn_and_capture(cmd).await;
+    let started_at = SystemTime::now();
+    let (status_res, output) =
+        command::spawn_and_capture_with_idle_timeout(cmd, case.output_idle_timeout(), &ctx.context.locale).await;
     let run_duration = run_start_time.elapsed();
-    let total_duration = built_test.duration + run_duration;
+    let total_duration = build_duration + run_duration;
+    let annotations = crate::infra::fs::read_annotations(&annotations_file);
 
+    let mut filter_log = if filter.is_empty() {
+        String::new()
+    } else {
+        format!(" --exact {}", filter.join(" "))
+    };
+    for arg in skip_args(&case) {
+        filter_log.push(' ');
+        filter_log.push_str(arg);
+    }
+    let shard_log = shard.map(|shard| format!("{SHARD_ENV}={shard} ")).unwrap_or_default();
     let command_log = format!(
-        "{} {}\n",
-        t!("run.command_prefix").blue(),
-        built_test.executable_path.display()
+        "{} {}{}{}\n",
+        t!("run.command_prefix", locale = &ctx.context.locale).blue(),
+        shard_log,
+        executable_path.display(),
+        filter_log
     );
-    let output = format!("{command_log}{output}");
+    let mut output = format!("{command_log}{output}");
 
     let status = match status_res {
         Ok(s) => s,
+        Err(e) if command::is_stalled(&e) => {
+            if !output.trim().is_empty() {
+                println!("{}", output.trim());
+            }
+            return Ok(stalled_result(case, output, total_duration, Vec::new(), annotations, &ctx.context.locale));
+        }
         Err(e) => {
             return Err(anyhow::anyhow!(
                 "Failed to get test process status for executable: '{}'. OS Error: {}",
-                built_test.executable_path.display(),
+                executable_path.display(),
                 e
             ));
         }
@@ -376,12 +2091,24 @@ async fn run_built_test(built_test: BuiltTest, project_root: &PathBuf) -> Result
         println!("{}", output.trim());
     }
 
+    let test_timings = command::parse_test_timings(&output);
+    let mut attachments = Vec::new();
+    if ctx.collect_core_dumps
+        && let Some(signal) = core_dump::crash_signal(&status)
+    {
+        let (note, core) = collect_core_dump(&case.name, ctx, &status, signal, started_at);
+        println!("{}", note.yellow());
+        output.push_str(&format!("\n{note}\n"));
+        attachments.extend(core);
+    }
+
     if status.success() {
         println!(
             "{}",
             t!(
                 "run.test_passed",
-                name = &case.name,
+                locale = &ctx.context.locale,
+                name = &name,
                 duration = &total_duration.as_secs_f64().to_string()
             )
             .green()
@@ -391,13 +2118,17 @@ async fn run_built_test(built_test: BuiltTest, project_root: &PathBuf) -> Result
             output,
             duration: total_duration,
             retries: 1,
+            attachments: Vec::new(),
+            test_timings,
+            annotations,
         })
     } else {
         println!(
             "{}",
             t!(
                 "run.test_failed",
-                name = &case.name,
+                locale = &ctx.context.locale,
+                name = &name,
                 duration = &total_duration.as_secs_f64().to_string()
             )
             .red()
@@ -407,6 +2138,127 @@ async fn run_built_test(built_test: BuiltTest, project_root: &PathBuf) -> Result
             output,
             reason: FailureReason::TestFailed,
             duration: total_duration,
+            attachments,
+            test_timings,
+            annotations,
         })
     }
-} 
\ No newline at end of file
+}
+
+/// Looks for the core dump of a test binary that crashed with a signal and moves it to
+/// the case's attachments (`collect_core_dumps`).
+///
+/// # Returns
+/// A note for the failure details, and the path of the collected dump if one was found
+fn collect_core_dump(
+    case_name: &str,
+    ctx: &ExecutionContext,
+    status: &std::process::ExitStatus,
+    signal: i32,
+    started_at: SystemTime,
+) -> (String, Option<PathBuf>) {
+    let locale = &ctx.context.locale;
+    if !core_dump::dumped_core(status) {
+        return (t!("run.core_dump_none", locale = locale, signal = signal).to_string(), None);
+    }
+    let (dir, prefix) = match core_dump::core_location(&ctx.project_root) {
+        Some(core_dump::CoreLocation::File { dir, prefix }) => (dir, prefix),
+        Some(core_dump::CoreLocation::Handler(handler)) => {
+            let note = t!("run.core_dump_handler", locale = locale, signal = signal, handler = handler);
+            return (note.to_string(), None);
+        }
+        None => {
+            let note = t!("run.core_dump_unknown_location", locale = locale, signal = signal);
+            return (note.to_string(), None);
+        }
+    };
+    let Some(core) = core_dump::find_core_dump(&dir, &prefix, started_at) else {
+        let note = t!("run.core_dump_not_found", locale = locale, signal = signal, dir = dir.display());
+        return (note.to_string(), None);
+    };
+    let dest_dir = ctx
+        .state_dir
+        .join("attachments")
+        .join(crate::infra::fs::case_dir_name(case_name));
+    match core_dump::collect(&core, &dest_dir) {
+        Ok(path) => {
+            let note = t!("run.core_dump_collected", locale = locale, signal = signal, path = path.display());
+            (note.to_string(), Some(path))
+        }
+        Err(e) => {
+            let note = t!("run.core_dump_collect_failed", locale = locale, signal = signal, path = core.display());
+            (format!("{note} {e:#}"), None)
+        }
+    }
+}
+
+/// Sets the case's `env` on a process it spawns. Called first, so the variables the runner
+/// sets itself, such as `MATRIX_RUNNER_ATTACH_DIR` or the locale, take precedence.
+fn apply_case_env(cmd: &mut tokio::process::Command, case: &TestCase) {
+    cmd.envs(&case.env);
+}
+
+/// Sets the locale of a test process: the case's `env_locale` if it has one, otherwise
+/// the C locale if `force_c_locale` applies.
+fn apply_case_locale(cmd: &mut tokio::process::Command, case: &TestCase, ctx: &ExecutionContext) {
+    if let Some(env_locale) = &case.env_locale {
+        command::apply_locale(cmd, env_locale);
+    } else if case.uses_c_locale(ctx.force_c_locale) {
+        command::apply_c_locale(cmd);
+    }
+}
+
+/// Reserves the ports a case `needs_ports` and passes them to its test process. The ports
+/// stay reserved until the returned guard is dropped, after the process ended.
+fn apply_case_ports(
+    cmd: &mut tokio::process::Command,
+    case: &TestCase,
+    ctx: &ExecutionContext,
+) -> Result<ReservedPorts> {
+    let reserved = reserve_case_ports(case, ctx)?;
+    cmd.envs(port_vars(&reserved));
+    Ok(reserved)
+}
+
+/// Reserves the ports a case `needs_ports`.
+fn reserve_case_ports(case: &TestCase, ctx: &ExecutionContext) -> Result<ReservedPorts> {
+    ctx.ports
+        .reserve(usize::from(case.needs_ports.unwrap_or_default()))
+        .with_context(|| format!("Failed to reserve ports for {}", case.name))
+}
+
+/// The environment variables passing reserved ports to a test process.
+fn port_vars(reserved: &ReservedPorts) -> Vec<(String, String)> {
+    reserved
+        .ports()
+        .iter()
+        .enumerate()
+        .map(|(i, port)| (format!("{PORT_ENV_PREFIX}{i}"), port.to_string()))
+        .collect()
+}
+
+/// Builds the result of a test process killed by `output_idle_timeout_secs`.
+fn stalled_result(
+    case: TestCase,
+    output: String,
+    duration: Duration,
+    attachments: Vec<PathBuf>,
+    annotations: BTreeMap<String, String>,
+    locale: &str,
+) -> TestResult {
+    let error = t!(
+        "common.output_stalled",
+        locale = locale,
+        secs = case.output_idle_timeout_secs.unwrap_or_default()
+    );
+    println!("{}", t!("run.test_stalled", locale = locale, name = &case.name, error = &error).red());
+    TestResult::Failed {
+        case,
+        output: format!("{output}{error}\n"),
+        reason: FailureReason::Stalled,
+        duration,
+        attachments,
+        test_timings: Vec::new(),
+        annotations,
+    }
+}
\ No newline at end of file
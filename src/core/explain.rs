@@ -0,0 +1,222 @@
+//! # Explain Module / 解释模块
+//!
+//! This module answers `run --explain <case>`: why a case was or was not selected to run
+//! on this runner. It goes through the same decisions as a run, in the same order, and
+//! records each of them: the `--select` expression, `--offline-only`, the architecture
+//! filter, the platforms the case may fail on, the split across runners and the cases it
+//! is ordered `after`. The runner split is taken from the planner itself, so the
+//! explanation cannot disagree with what the run does.
+//!
+//! 此模块回答 `run --explain <用例>`：某个用例为何被或未被选中在此运行器上运行。
+//! 它按与运行相同的顺序经历相同的决策，并记录每一项：`--select` 表达式、`--offline-only`、
+//! 架构过滤、用例允许失败的平台、在运行器之间的拆分，以及其 `after` 中的用例。
+//! 运行器拆分直接取自计划器，因此解释不会与运行的实际行为不一致。
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::core::config::TestMatrix;
+use crate::core::context::RunContext;
+use crate::core::execution::command_line;
+use crate::core::history::RunHistory;
+use crate::core::planner;
+use crate::core::selector::Selector;
+
+/// The filters of a run that decide which cases it keeps.
+/// 一次运行中决定保留哪些用例的过滤条件。
+#[derive(Debug, Clone, Copy)]
+pub struct CaseFilters<'a> {
+    /// The `--select` expression, if given / `--select` 表达式（如果提供）
+    pub selector: Option<&'a Selector>,
+    /// The history the selector's `status(last_run)` is read from / 选择器的 `status(last_run)` 所读取的历史
+    pub history: &'a RunHistory,
+    /// Whether cases needing network access are skipped / 是否跳过需要网络访问的用例
+    pub offline_only: bool,
+    /// The total number of runners and the index of this one, for split runs / 拆分运行时的运行器总数和此运行器的索引
+    pub runners: Option<(usize, usize)>,
+}
+
+/// One decision about a case. The checks are listed in the order a run makes them.
+/// 关于用例的一项决策。各项检查按运行执行它们的顺序列出。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    /// The `--select` expression, `None` if none was given / `--select` 表达式，未提供时为 `None`
+    Selector { expression: Option<String> },
+    /// `--offline-only` against the case's `requires_network` / `--offline-only` 与用例的 `requires_network`
+    Network {
+        offline_only: bool,
+        requires_network: bool,
+    },
+    /// The case's `arch` list against the host architecture / 用例的 `arch` 列表与主机架构
+    Arch { allowed: Vec<String>, host: String },
+    /// Whether the case's `allow_failure` covers the host OS today; never excludes the case
+    /// 用例的 `allow_failure` 今天是否涵盖主机操作系统；不会排除用例
+    AllowFailure { os: String },
+    /// The case's position in the planned order and the runner it falls to
+    /// 用例在计划顺序中的位置以及它所分配到的运行器
+    Runner {
+        /// The 0-based position among the cases kept by the checks before / 在之前检查所保留用例中的位置（从 0 开始）
+        position: usize,
+        /// The total number of runners and the index of this one, `None` for a single runner / 运行器总数和此运行器的索引，单个运行器时为 `None`
+        runners: Option<(usize, usize)>,
+    },
+    /// A case named in `after`, and whether it runs on this runner; never excludes the case
+    /// `after` 中指定的用例，以及它是否在此运行器上运行；不会排除用例
+    After { name: String },
+}
+
+impl Check {
+    /// Whether a failed check of this kind keeps the case out of the run.
+    /// 此类检查失败时是否会将用例排除在运行之外。
+    pub fn excludes(&self) -> bool {
+        !matches!(self, Check::AllowFailure { .. } | Check::After { .. })
+    }
+}
+
+/// A check and its result for the explained case.
+/// 针对被解释用例的一项检查及其结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The check / 检查
+    pub check: Check,
+    /// Whether the case passed it. For `AllowFailure`, whether failures are allowed; for
+    /// `After`, whether the named case runs on this runner.
+    /// 用例是否通过该检查。对于 `AllowFailure`，表示是否允许失败；对于 `After`，表示所指定的用例是否在此运行器上运行。
+    pub passed: bool,
+}
+
+/// The decision trail of one case.
+/// 一个用例的决策轨迹。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The name of the case / 用例名称
+    pub name: String,
+    /// The decisions, in the order a run makes them / 各项决策，按运行执行它们的顺序排列
+    pub steps: Vec<Step>,
+    /// Whether the case runs on this runner / 用例是否在此运行器上运行
+    pub selected: bool,
+    /// The command the case runs, as `command_line` resolves it / 用例运行的命令，由 `command_line` 解析
+    pub command: String,
+}
+
+/// Explains why a case is or is not selected to run.
+///
+/// # Arguments
+/// * `matrix` - The whole matrix, before any case was filtered out
+/// * `name` - The name of the case to explain
+/// * `filters` - The filters of the run
+/// * `context` - The platform the cases are planned for
+/// * `today` - The day that decides whether `allow_failure` entries have expired
+/// * `default_package` - The package tested when the case does not set `package`
+///
+/// # Returns
+/// The explanation, `None` if the matrix has no case of that name, or an error if the
+/// planner rejects the runner split
+///
+/// 解释某个用例为何被或未被选中运行。如果矩阵中没有该名称的用例则返回 `None`；
+/// 如果计划器拒绝该运行器拆分则返回错误。
+pub fn explain_case(
+    matrix: &TestMatrix,
+    name: &str,
+    filters: &CaseFilters,
+    context: &RunContext,
+    today: NaiveDate,
+    default_package: &str,
+) -> Result<Option<Explanation>> {
+    let Some(case) = matrix.cases.iter().find(|case| case.name == name) else {
+        return Ok(None);
+    };
+    let mut steps = Vec::new();
+
+    // The cases kept by `--select` and `--offline-only`, which the planner then orders.
+    let kept: Vec<_> = matrix
+        .cases
+        .iter()
+        .filter(|case| {
+            filters
+                .selector
+                .is_none_or(|selector| selector.matches(case, filters.history))
+        })
+        .filter(|case| !(filters.offline_only && case.requires_network))
+        .cloned()
+        .collect();
+
+    steps.push(Step {
+        check: Check::Selector {
+            expression: filters
+                .selector
+                .map(|selector| selector.source().to_string()),
+        },
+        passed: filters
+            .selector
+            .is_none_or(|selector| selector.matches(case, filters.history)),
+    });
+    steps.push(Step {
+        check: Check::Network {
+            offline_only: filters.offline_only,
+            requires_network: case.requires_network,
+        },
+        passed: !(filters.offline_only && case.requires_network),
+    });
+    steps.push(Step {
+        check: Check::Arch {
+            allowed: case.arch.clone(),
+            host: context.arch.clone(),
+        },
+        passed: case.arch.is_empty() || case.arch.contains(&context.arch),
+    });
+    steps.push(Step {
+        check: Check::AllowFailure {
+            os: context.os.clone(),
+        },
+        passed: case.allows_failure_on(&context.os, today),
+    });
+
+    // The order of all runners' cases, so the position decides the runner as in the planner.
+    let planned = planner::plan_execution(
+        TestMatrix {
+            cases: kept,
+            ..matrix.clone()
+        },
+        None,
+        None,
+        context,
+        today,
+    )?
+    .cases_to_run;
+    let on_this_runner = |position: usize| {
+        filters
+            .runners
+            .is_none_or(|(total, index)| position % total == index)
+    };
+    if let Some(position) = planned.iter().position(|planned| planned.name == case.name) {
+        steps.push(Step {
+            check: Check::Runner {
+                position,
+                runners: filters.runners,
+            },
+            passed: on_this_runner(position),
+        });
+    }
+    for after in &case.after {
+        steps.push(Step {
+            check: Check::After {
+                name: after.clone(),
+            },
+            passed: planned
+                .iter()
+                .position(|planned| &planned.name == after)
+                .is_some_and(on_this_runner),
+        });
+    }
+
+    let selected = steps
+        .iter()
+        .all(|step| step.passed || !step.check.excludes());
+    Ok(Some(Explanation {
+        name: case.name.clone(),
+        steps,
+        selected,
+        command: command_line(case, default_package),
+    }))
+}
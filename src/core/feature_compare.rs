@@ -0,0 +1,79 @@
+//! # Feature Comparison Module / Feature 比较模块
+//!
+//! This module compares the outcomes of the individual tests of a crate under two feature
+//! sets, for `matrix-runner compare-features`. It lists the tests that pass under one set
+//! and fail under the other, and the tests only compiled in under one of them, so a team
+//! can check that an additive feature really is additive.
+//!
+//! 此模块比较一个 crate 的各个测试在两组 feature 下的结果，用于 `matrix-runner compare-features`。
+//! 它列出在一组 feature 下通过而在另一组下失败的测试，以及仅在其中一组下被编译进来的测试，
+//! 使团队可以检查一个附加的 feature 是否真的只是附加的。
+
+use std::collections::BTreeMap;
+
+/// The outcome of a single test, as reported by libtest.
+/// 单个测试的结果，由 libtest 报告。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    /// The test passed / 测试通过
+    Passed,
+    /// The test failed / 测试失败
+    Failed,
+    /// The test was ignored / 测试被忽略
+    Ignored,
+}
+
+/// The differences between the test outcomes under feature set A and feature set B.
+/// 在 feature 集合 A 和 feature 集合 B 下测试结果之间的差异。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureComparison {
+    /// Tests passing under A and failing under B / 在 A 下通过而在 B 下失败的测试
+    pub fail_only_in_b: Vec<String>,
+    /// Tests passing under B and failing under A / 在 B 下通过而在 A 下失败的测试
+    pub fail_only_in_a: Vec<String>,
+    /// Tests compiled in under A only / 仅在 A 下被编译进来的测试
+    pub only_in_a: Vec<String>,
+    /// Tests compiled in under B only / 仅在 B 下被编译进来的测试
+    pub only_in_b: Vec<String>,
+    /// The number of tests run under both sets / 在两组 feature 下都运行的测试数量
+    pub common: usize,
+}
+
+impl FeatureComparison {
+    /// Compares the outcomes of the tests under A and under B. Ignored tests count as
+    /// compiled in, but never as passing or failing.
+    ///
+    /// # Arguments
+    /// * `a` - The outcome of every test under feature set A
+    /// * `b` - The outcome of every test under feature set B
+    ///
+    /// 比较测试在 A 和 B 下的结果。被忽略的测试算作已编译进来，但既不算通过也不算失败。
+    pub fn compare(a: &BTreeMap<String, TestOutcome>, b: &BTreeMap<String, TestOutcome>) -> Self {
+        let mut comparison = FeatureComparison::default();
+        for (name, outcome_a) in a {
+            match (outcome_a, b.get(name)) {
+                (_, None) => comparison.only_in_a.push(name.clone()),
+                (TestOutcome::Passed, Some(TestOutcome::Failed)) => {
+                    comparison.fail_only_in_b.push(name.clone())
+                }
+                (TestOutcome::Failed, Some(TestOutcome::Passed)) => {
+                    comparison.fail_only_in_a.push(name.clone())
+                }
+                _ => {}
+            }
+        }
+        comparison.only_in_b = b
+            .keys()
+            .filter(|name| !a.contains_key(*name))
+            .cloned()
+            .collect();
+        comparison.common = a.len() - comparison.only_in_a.len();
+        comparison
+    }
+
+    /// Whether some test passes under one feature set and fails under the other.
+    /// 是否有测试在一组 feature 下通过而在另一组下失败。
+    pub fn has_differing_outcomes(&self) -> bool {
+        !self.fail_only_in_a.is_empty() || !self.fail_only_in_b.is_empty()
+    }
+}
@@ -0,0 +1,191 @@
+//! # Failure Fingerprint Module / 失败指纹模块
+//!
+//! This module computes a stable fingerprint for each failure from the case id, the
+//! failure reason and the normalized error lines of its output. Numbers, addresses and
+//! temporary paths are masked, so the same failure keeps its fingerprint from run to run
+//! and dashboards can tell a failure persisting for several runs from a new regression.
+//!
+//! 此模块根据用例 ID、失败原因以及其输出中规范化后的错误行，为每个失败计算稳定的指纹。
+//! 数字、地址和临时路径会被屏蔽，因此同一失败在多次运行之间保持相同的指纹，
+//! 仪表盘可以据此区分持续多次运行的失败与新的回归。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::history::RunHistory;
+use crate::core::models::{FailureReason, TestResult};
+
+/// The number of hex characters kept of the SHA-256 / 保留的 SHA-256 十六进制字符数
+const FINGERPRINT_LEN: usize = 16;
+
+/// The number of trailing lines used when the output has no recognizable error line / 输出中没有可识别的错误行时使用的末尾行数
+const FALLBACK_LINES: usize = 3;
+
+/// The fingerprint of a failure of this run, as written to the results JSON.
+/// 本次运行中某个失败的指纹，写入结果 JSON。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FailureFingerprint {
+    /// The name of the failed case / 失败用例的名称
+    pub case: String,
+    /// The stable id of the failed case / 失败用例的稳定 ID
+    #[serde(default)]
+    pub id: String,
+    /// Why the case failed / 用例失败的原因
+    pub reason: FailureReason,
+    /// The stable fingerprint of the failure / 失败的稳定指纹
+    pub fingerprint: String,
+    /// The number of consecutive runs, this one included, that failed with this fingerprint / 以此指纹失败的连续运行次数（包括本次）
+    pub consecutive_runs: u32,
+}
+
+/// Computes the fingerprint of a failed result.
+///
+/// # Returns
+/// A 16-character lowercase hex string, or `None` if the result is not a failure
+///
+/// 计算失败结果的指纹。结果不是失败时返回 `None`。
+pub fn failure_fingerprint(result: &TestResult) -> Option<String> {
+    let TestResult::Failed {
+        case,
+        output,
+        reason,
+        ..
+    } = result
+    else {
+        return None;
+    };
+    // A timeout or stall is identified by the case alone; its output only shows how far it got.
+    let message = match reason {
+        FailureReason::Timeout | FailureReason::Stalled => String::new(),
+        _ => normalize_failure_message(output),
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\n{:?}\n{}", case.case_id(), reason, message));
+    let digest = format!("{:x}", hasher.finalize());
+    Some(digest[..FINGERPRINT_LEN].to_string())
+}
+
+/// Reduces a failure output to the lines identifying the failure, with everything that
+/// changes between runs of the same failure masked.
+///
+/// The identifying lines are compiler errors, failed libtest tests and panic messages;
+/// outputs without any fall back to their last lines.
+///
+/// 将失败输出精简为标识该失败的行，并屏蔽同一失败在多次运行之间会变化的所有内容。
+/// 标识行为编译器错误、失败的 libtest 测试和 panic 消息；没有这些行的输出回退为其最后几行。
+pub fn normalize_failure_message(output: &str) -> String {
+    let lines: Vec<String> = output
+        .lines()
+        .map(strip_ansi)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut identifying = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.contains("panicked at") {
+            identifying.push(line.as_str());
+            // Since Rust 1.73 the panic message follows on its own line.
+            if let Some(message) = lines.get(index + 1) {
+                identifying.push(message.as_str());
+            }
+        } else if is_identifying_line(line) {
+            identifying.push(line.as_str());
+        }
+    }
+    if identifying.is_empty() {
+        let start = lines.len().saturating_sub(FALLBACK_LINES);
+        identifying = lines[start..].iter().map(String::as_str).collect();
+    }
+    identifying
+        .into_iter()
+        .map(mask_volatile)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collects the fingerprints of the failures of a run. The history must already contain
+/// this run, so the number of consecutive runs includes it.
+/// 收集一次运行中失败的指纹。历史记录必须已包含本次运行，使连续运行次数包括本次。
+pub fn fingerprint_failures(
+    results: &[TestResult],
+    history: &RunHistory,
+) -> Vec<FailureFingerprint> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let TestResult::Failed { case, reason, .. } = result else {
+                return None;
+            };
+            let fingerprint = failure_fingerprint(result)?;
+            let consecutive_runs = history
+                .failure_of(case)
+                .filter(|streak| streak.fingerprint == fingerprint)
+                .map_or(1, |streak| streak.consecutive_runs);
+            Some(FailureFingerprint {
+                case: case.name.clone(),
+                id: case.case_id(),
+                reason: *reason,
+                fingerprint,
+                consecutive_runs,
+            })
+        })
+        .collect()
+}
+
+fn is_identifying_line(line: &str) -> bool {
+    line.starts_with("error:")
+        || line.starts_with("error[")
+        || (line.starts_with("test ") && line.ends_with("FAILED"))
+}
+
+/// Removes ANSI escape sequences (`ESC [ ... letter`) from a line.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Masks hex addresses, numbers (line numbers, durations, ids) and the names of
+/// temporary directories.
+fn mask_volatile(line: &str) -> String {
+    line.split(' ')
+        .map(|word| {
+            if word.contains(".tmp") || word.contains("matrix_runner_") {
+                return "<tmp>".to_string();
+            }
+            let mut masked = String::with_capacity(word.len());
+            let mut chars = word.chars().peekable();
+            while let Some(c) = chars.next() {
+                if c == '0' && chars.peek() == Some(&'x') {
+                    chars.next();
+                    while chars.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                        chars.next();
+                    }
+                    masked.push_str("0x#");
+                } else if c.is_ascii_digit() {
+                    while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                        chars.next();
+                    }
+                    masked.push('#');
+                } else {
+                    masked.push(c);
+                }
+            }
+            masked
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
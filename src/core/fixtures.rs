@@ -0,0 +1,184 @@
+//! # Fixtures Module / Fixture 模块
+//!
+//! This module stages the `fixtures` of a case: files it needs at a fixed path, relative
+//! to the project directory, instead of relying on where a custom command happens to run.
+//! They are copied or symlinked into place before the case starts and removed when it
+//! ends, however it ended. Cases running at the same time may stage the same file to the
+//! same path; it is then shared and removed when the last of them ends.
+//!
+//! 此模块放置用例的 `fixtures`：用例在相对于项目目录的固定路径上需要的文件，
+//! 而不必依赖自定义命令恰好在何处运行。它们在用例开始前被复制或符号链接到位，
+//! 并在用例结束时被删除，无论用例以何种方式结束。同时运行的用例可以将同一文件放置到同一路径；
+//! 此时该文件被共享，并在最后一个使用它的用例结束时被删除。
+
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::core::config::{Fixture, FixtureMode};
+
+/// The fixtures currently staged in the project directory by the running cases.
+/// Cloning yields a handle to the same fixtures.
+///
+/// 正在运行的用例当前放置在项目目录中的 fixture。克隆会得到指向同一组 fixture 的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct FixtureStage {
+    staged: Arc<Mutex<BTreeMap<PathBuf, StagedFile>>>,
+}
+
+#[derive(Debug)]
+struct StagedFile {
+    source: PathBuf,
+    /// The number of running cases using the file / 使用该文件的运行中用例数量
+    users: usize,
+    /// The directories created for the file, deepest first / 为该文件创建的目录，最深的在前
+    created_dirs: Vec<PathBuf>,
+}
+
+impl FixtureStage {
+    /// Stages the fixtures of a case into the project directory.
+    ///
+    /// # Arguments
+    /// * `project_root` - The project directory, which `from` and `to` are relative to
+    /// * `fixtures` - The fixtures of the case
+    ///
+    /// # Returns
+    /// A guard removing the fixtures when dropped, or an error if a source is missing or a
+    /// destination is taken by another file; fixtures staged before the error are removed
+    ///
+    /// 将用例的 fixture 放置到项目目录中。返回一个在被丢弃时删除这些 fixture 的守卫；
+    /// 如果某个源文件不存在或目标位置已被其他文件占用，则返回错误，出错之前已放置的 fixture 会被删除。
+    pub fn stage(&self, project_root: &Path, fixtures: &[Fixture]) -> Result<StagedFixtures> {
+        let mut staged = StagedFixtures {
+            stage: self.clone(),
+            destinations: Vec::new(),
+        };
+        for fixture in fixtures {
+            let destination = project_root.join(&fixture.to);
+            self.stage_file(project_root, fixture, &destination)?;
+            staged.destinations.push(destination);
+        }
+        Ok(staged)
+    }
+
+    fn stage_file(&self, project_root: &Path, fixture: &Fixture, destination: &Path) -> Result<()> {
+        let source = project_root.join(&fixture.from);
+        let mut staged = self.staged.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = staged.get_mut(destination) {
+            if file.source != source {
+                bail!(
+                    "Cannot stage '{}' to '{}': a running case staged '{}' there",
+                    fixture.from.display(),
+                    fixture.to.display(),
+                    file.source.display()
+                );
+            }
+            file.users += 1;
+            return Ok(());
+        }
+        if !source.is_file() {
+            bail!("The fixture '{}' is not a file", source.display());
+        }
+        if fs::symlink_metadata(destination).is_ok() {
+            bail!(
+                "Cannot stage '{}' to '{}': the file already exists",
+                fixture.from.display(),
+                fixture.to.display()
+            );
+        }
+
+        let created_dirs = create_parent_dirs(destination)?;
+        let placed = match fixture.mode {
+            FixtureMode::Copy => fs::copy(&source, destination).map(drop),
+            FixtureMode::Symlink => {
+                fs::canonicalize(&source).and_then(|source| symlink_file(&source, destination))
+            }
+        };
+        if let Err(e) = placed {
+            remove_dirs(&created_dirs);
+            return Err(e).with_context(|| {
+                format!(
+                    "Failed to stage '{}' to '{}'",
+                    source.display(),
+                    destination.display()
+                )
+            });
+        }
+        staged.insert(
+            destination.to_path_buf(),
+            StagedFile {
+                source,
+                users: 1,
+                created_dirs,
+            },
+        );
+        Ok(())
+    }
+
+    fn release(&self, destination: &Path) {
+        let mut staged = self.staged.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(file) = staged.get_mut(destination) else {
+            return;
+        };
+        file.users -= 1;
+        if file.users > 0 {
+            return;
+        }
+        if let Some(file) = staged.remove(destination) {
+            let _ = fs::remove_file(destination);
+            remove_dirs(&file.created_dirs);
+        }
+    }
+}
+
+/// The fixtures staged for a running case. They are removed when this is dropped.
+/// 为运行中的用例放置的 fixture。在此值被丢弃时删除。
+#[derive(Debug)]
+pub struct StagedFixtures {
+    stage: FixtureStage,
+    destinations: Vec<PathBuf>,
+}
+
+impl Drop for StagedFixtures {
+    fn drop(&mut self) {
+        for destination in self.destinations.iter().rev() {
+            self.stage.release(destination);
+        }
+    }
+}
+
+/// Creates the missing parent directories of a path, returning them deepest first.
+fn create_parent_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+    let missing: Vec<PathBuf> = path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    Ok(missing)
+}
+
+/// Removes directories created for a fixture, leaving those that are no longer empty.
+fn remove_dirs(dirs: &[PathBuf]) {
+    for dir in dirs {
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn symlink_file(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, destination)
+}
+
+#[cfg(windows)]
+fn symlink_file(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(source, destination)
+}
@@ -0,0 +1,62 @@
+//! # Flakiness Module / 不稳定性模块
+//!
+//! This module lists the cases that only passed after retries in a run, together with how
+//! often they needed retries in earlier runs and a flaky score from the run history. A
+//! retry turns a flaky failure into a pass, so without this summary a case growing more
+//! unstable from run to run would look as healthy as any other.
+//!
+//! 此模块列出在一次运行中仅在重试后才通过的用例，以及它们在以往运行中需要重试的频率和根据运行历史计算的不稳定分数。
+//! 重试会把不稳定的失败变成通过，因此没有这份摘要时，一个逐次运行越来越不稳定的用例看起来会和其他用例一样健康。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::core::history::RunHistory;
+use crate::core::models::TestResult;
+
+/// A case that needed retries to pass in this run, as written to the results JSON.
+/// 本次运行中需要重试才能通过的用例，写入结果 JSON。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FlakyCase {
+    /// The name of the case / 用例名称
+    pub case: String,
+    /// The stable id of the case / 用例的稳定 ID
+    #[serde(default)]
+    pub id: String,
+    /// The retries it needed in this run / 本次运行中所需的重试次数
+    pub retries: u8,
+    /// The runs it needed retries in, this one included / 需要重试的运行次数（包括本次）
+    pub retried_runs: u32,
+    /// The runs recorded for it, this one included / 为其记录的运行次数（包括本次）
+    pub runs: u32,
+    /// The flaky score from 0 to 1, weighing recent runs more / 不稳定分数，取值 0 到 1，近期运行的权重更大
+    pub flaky_score: f64,
+}
+
+/// Collects the cases that needed retries to pass, highest flaky score first. The history
+/// must already contain this run, so its statistics include it.
+/// 收集需要重试才能通过的用例，不稳定分数最高的排在最前。历史记录必须已包含本次运行，使其统计包括本次。
+pub fn flaky_cases(results: &[TestResult], history: &RunHistory) -> Vec<FlakyCase> {
+    let mut cases: Vec<FlakyCase> = results
+        .iter()
+        .filter(|result| result.get_retries() > 1)
+        .filter_map(|result| {
+            let case = result.get_case()?;
+            let stats = history.retries_of(case).copied().unwrap_or_default();
+            Some(FlakyCase {
+                case: case.name.clone(),
+                id: case.case_id(),
+                retries: result.get_retries() - 1,
+                retried_runs: stats.retried_runs,
+                runs: stats.runs,
+                flaky_score: stats.flaky_score,
+            })
+        })
+        .collect();
+    cases.sort_by(|a, b| {
+        b.flaky_score
+            .total_cmp(&a.flaky_score)
+            .then_with(|| a.case.cmp(&b.case))
+    });
+    cases
+}
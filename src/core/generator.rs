@@ -0,0 +1,71 @@
+//! # Case Generator Module / 用例生成器模块
+//!
+//! This module runs the `[generator]` command of a test matrix and parses the cases it
+//! prints, so projects can derive cases from external sources (a hardware inventory, a
+//! list of fuzz corpora) instead of templating the TOML file themselves.
+//!
+//! 此模块运行测试矩阵的 `[generator]` 命令并解析其打印的用例，使项目可以从外部来源
+//! （硬件清单、模糊测试语料列表）派生用例，而无需自己为 TOML 文件编写模板。
+
+use anyhow::{Context, Result, anyhow, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use crate::core::config::TestCase;
+
+/// The `[generator]` section of a test matrix.
+/// 测试矩阵的 `[generator]` 部分。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CaseGenerator {
+    /// The command printing a JSON array of cases on stdout, e.g. "python gen_cases.py".
+    /// It runs in the directory of the matrix file; environment variables are expanded.
+    /// 在标准输出上打印用例 JSON 数组的命令，例如 "python gen_cases.py"。
+    /// 它在矩阵文件所在目录中运行；环境变量会被展开。
+    pub command: String,
+}
+
+/// Runs the generator command and parses the cases it prints.
+///
+/// # Arguments
+/// * `generator` - The generator of the matrix
+/// * `working_dir` - The directory the command runs in, normally the matrix file's directory
+///
+/// # Returns
+/// The generated cases, or an error if the command cannot run, fails, or prints
+/// something other than a JSON array of cases
+///
+/// 运行生成器命令并解析其打印的用例。如果命令无法运行、执行失败或打印的内容不是
+/// 用例的 JSON 数组，则返回错误。
+pub fn generate_cases(generator: &CaseGenerator, working_dir: &Path) -> Result<Vec<TestCase>> {
+    let expanded = shellexpand::full(&generator.command)
+        .with_context(|| format!("Failed to expand generator command: {}", generator.command))?;
+    let parts = shlex::split(&expanded)
+        .ok_or_else(|| anyhow!("Failed to parse generator command: {}", expanded))?;
+    let Some((program, args)) = parts.split_first() else {
+        bail!("The generator command is empty");
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(working_dir)
+        .output()
+        .with_context(|| format!("Failed to run generator command: {}", generator.command))?;
+    if !output.status.success() {
+        bail!(
+            "Generator command `{}` failed with {}:\n{}",
+            generator.command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+
+    serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "Generator command `{}` did not print a JSON array of cases",
+            generator.command
+        )
+    })
+}
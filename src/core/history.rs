@@ -0,0 +1,342 @@
+//! # Run History Module / 运行历史模块
+//!
+//! This module persists the outcome of each case between runs, so that later runs
+//! can make decisions based on what happened last time (e.g. `status(last_run) == failed`),
+//! how many runs in a row each case has failed the same way, and how often each case
+//! only passed after retries. Cases are keyed by their stable id (see
+//! [`TestCase::case_id`]), so renaming a case keeps its history; the name is only kept
+//! as a label.
+//!
+//! It also keeps the results JSON of past runs in a `history` directory, pruned by a
+//! retention policy (see [`Retention`]) so long-lived projects don't accumulate them forever.
+//!
+//! 此模块在多次运行之间持久化每个用例的结果，
+//! 以便后续运行可以根据上一次的结果做出决策（例如 `status(last_run) == failed`），
+//! 并记录每个用例以相同方式连续失败了多少次运行，以及每个用例有多少次是在重试后才通过的。
+//! 用例以其稳定 ID（参见 [`TestCase::case_id`]）为键，因此重命名用例会保留其历史；名称仅作为标签保留。
+//! 它还会在 `history` 目录中保留以往运行的结果 JSON，并按保留策略（参见 [`Retention`]）进行清理，
+//! 使长期存在的项目不会无限累积这些文件。
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::config::TestCase;
+use crate::core::fingerprint::failure_fingerprint;
+use crate::core::models::TestResult;
+
+/// The file name of the history inside the runner's state directory.
+/// 运行器状态目录中历史文件的文件名。
+const HISTORY_FILE: &str = "last_run.json";
+
+/// The directory inside the runner's state directory holding the results of past runs.
+/// 运行器状态目录中保存以往运行结果的目录。
+pub const REPORTS_DIR: &str = "history";
+
+/// The format of the time in the file names of archived results, e.g. `run-20250301T101500.250Z.json`.
+const REPORT_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// The last known status of every case that has been run in this project.
+/// 此项目中每个已运行用例的最后已知状态。
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RunHistory {
+    /// Maps case ids to the status key of their most recent result.
+    /// 将用例 ID 映射到其最近一次结果的状态键。
+    #[serde(default)]
+    pub cases: BTreeMap<String, String>,
+    /// Maps the ids of the cases failing at the moment to their current failure streak.
+    /// 将当前失败的用例 ID 映射到其当前的连续失败记录。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub failures: BTreeMap<String, FailureStreak>,
+    /// Maps case ids to how often they needed retries to pass.
+    /// 将用例 ID 映射到其需要重试才能通过的频率。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub retries: BTreeMap<String, RetryStats>,
+    /// Maps case ids to the name of the case in its most recent run, so the file stays readable.
+    /// 将用例 ID 映射到用例在最近一次运行中的名称，使文件保持可读。
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub names: BTreeMap<String, String>,
+}
+
+/// The runs in a row a case has failed with the same fingerprint.
+/// 用例以相同指纹连续失败的运行次数。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureStreak {
+    /// The fingerprint of the failure / 失败的指纹
+    pub fingerprint: String,
+    /// The number of consecutive runs that failed with it / 以此指纹失败的连续运行次数
+    pub consecutive_runs: u32,
+}
+
+/// How often a case needed retries to pass, over the runs recorded for it.
+/// 在为用例记录的运行中，其需要重试才能通过的频率。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryStats {
+    /// The number of runs the case ran in / 用例参与的运行次数
+    pub runs: u32,
+    /// The number of those runs in which it passed only after retries / 其中仅在重试后才通过的运行次数
+    pub retried_runs: u32,
+    /// The flaky score, from 0 to 1: an average of the runs needing retries, in which each
+    /// run weighs [`FLAKY_SCORE_WEIGHT`] and older runs weigh less and less
+    /// 不稳定分数，取值 0 到 1：需要重试的运行的平均值，其中每次运行的权重为 [`FLAKY_SCORE_WEIGHT`]，越早的运行权重越小
+    pub flaky_score: f64,
+}
+
+/// The weight of the latest run in the flaky score, so a case growing unstable stands out
+/// within a few runs. / 最近一次运行在不稳定分数中的权重，使逐渐变得不稳定的用例在几次运行内就会显现。
+pub const FLAKY_SCORE_WEIGHT: f64 = 0.3;
+
+impl RetryStats {
+    /// Adds a run, in which the case did or did not need retries to pass.
+    /// 添加一次运行，用例在其中需要或不需要重试才能通过。
+    pub fn record(&mut self, retried: bool) {
+        self.runs += 1;
+        self.retried_runs += u32::from(retried);
+        let run = if retried { 1.0 } else { 0.0 };
+        self.flaky_score = self.flaky_score * (1.0 - FLAKY_SCORE_WEIGHT) + run * FLAKY_SCORE_WEIGHT;
+    }
+}
+
+impl RunHistory {
+    /// Loads the history from a state directory. A missing or unreadable file yields an empty history.
+    /// 从状态目录加载历史记录。文件缺失或无法读取时返回空历史。
+    pub fn load(state_dir: &Path) -> Self {
+        fs::read_to_string(history_path(state_dir))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Records the results of a run, overwriting the entries of the cases that were run.
+    /// A failure with the same fingerprint as last time extends the case's streak, any
+    /// other failure starts a new one, and a case that no longer fails ends it.
+    /// Every result also adds a run to the case's retry statistics.
+    /// Skipped results are not recorded, as the case did not run. Entries of a history
+    /// written before cases had ids, keyed by name, move to the case's id.
+    ///
+    /// 记录一次运行的结果，覆盖已运行用例的条目。与上次指纹相同的失败会延长用例的连续失败记录，
+    /// 其他失败会开始新的记录，不再失败的用例则结束记录。每个结果还会为用例的重试统计添加一次运行。
+    /// 跳过的结果不会被记录，因为用例没有运行。在用例拥有 ID 之前写入、以名称为键的历史条目会移到用例的 ID 下。
+    pub fn record(&mut self, results: &[TestResult]) {
+        for result in results {
+            let Some(case) = result
+                .get_case()
+                .filter(|_| !matches!(result, TestResult::Skipped { .. }))
+            else {
+                continue;
+            };
+            let id = case.case_id();
+            self.migrate_legacy_entries(&case.name, &id);
+            self.names.insert(id.clone(), case.name.clone());
+            self.cases
+                .insert(id.clone(), result.status_key().to_string());
+            // `get_retries` counts attempts, so a first-time pass has 1.
+            self.retries
+                .entry(id.clone())
+                .or_default()
+                .record(result.get_retries() > 1);
+            match failure_fingerprint(result) {
+                Some(fingerprint) => {
+                    let consecutive_runs = match self.failures.get(&id) {
+                        Some(streak) if streak.fingerprint == fingerprint => {
+                            streak.consecutive_runs + 1
+                        }
+                        _ => 1,
+                    };
+                    self.failures.insert(
+                        id,
+                        FailureStreak {
+                            fingerprint,
+                            consecutive_runs,
+                        },
+                    );
+                }
+                None => {
+                    self.failures.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Gets the current failure streak of a case, if it failed last time.
+    /// 获取用例当前的连续失败记录（如果它上次失败）。
+    pub fn failure_of(&self, case: &TestCase) -> Option<&FailureStreak> {
+        self.entry_of(&self.failures, case)
+    }
+
+    /// Gets the retry statistics of a case, if it was recorded before.
+    /// 获取用例的重试统计（如果之前有记录）。
+    pub fn retries_of(&self, case: &TestCase) -> Option<&RetryStats> {
+        self.entry_of(&self.retries, case)
+    }
+
+    /// Gets the last recorded status of a case, if any.
+    /// 获取用例最后记录的状态（如果有）。
+    pub fn status_of(&self, case: &TestCase) -> Option<&str> {
+        self.entry_of(&self.cases, case).map(String::as_str)
+    }
+
+    /// Looks up the entry of a case by its id, falling back to its name for a history
+    /// written before cases had ids. A name that is the id of a recorded case is not a
+    /// fallback, since it keys that case's entries.
+    fn entry_of<'a, T>(&self, entries: &'a BTreeMap<String, T>, case: &TestCase) -> Option<&'a T> {
+        entries.get(&case.case_id()).or_else(|| {
+            if self.names.contains_key(&case.name) {
+                None
+            } else {
+                entries.get(&case.name)
+            }
+        })
+    }
+
+    /// Moves the entries keyed by a case's name, left by a history written before cases
+    /// had ids, to its id.
+    fn migrate_legacy_entries(&mut self, name: &str, id: &str) {
+        if name == id || self.names.contains_key(name) {
+            return;
+        }
+        fn migrate<T>(entries: &mut BTreeMap<String, T>, name: &str, id: &str) {
+            if let Some(entry) = entries.remove(name) {
+                entries.entry(id.to_string()).or_insert(entry);
+            }
+        }
+        migrate(&mut self.cases, name, id);
+        migrate(&mut self.failures, name, id);
+        migrate(&mut self.retries, name, id);
+    }
+
+    /// Writes the history to a state directory.
+    /// 将历史记录写入状态目录。
+    pub fn save(&self, state_dir: &Path) -> Result<()> {
+        let path = history_path(state_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write run history: {}", path.display()))
+    }
+}
+
+/// Returns the path of the history file inside a state directory.
+/// 返回状态目录中历史文件的路径。
+pub fn history_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(HISTORY_FILE)
+}
+
+/// How many archived run results are kept. A result is pruned once it is older than
+/// `keep_days` or not among the `keep_runs` most recent ones; a limit of `0` is off.
+/// 保留多少份已归档的运行结果。结果一旦超过 `keep_days` 天或不在最近的 `keep_runs` 份之中就会被清理；
+/// 限制为 `0` 表示不启用。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// The number of most recent runs kept / 保留的最近运行数量
+    pub keep_runs: usize,
+    /// The number of days a run is kept / 运行结果保留的天数
+    pub keep_days: u32,
+}
+
+/// The results of a past run, archived in the history directory.
+/// 归档在历史目录中的一次以往运行的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedRun {
+    /// The path of the results JSON / 结果 JSON 的路径
+    pub path: PathBuf,
+    /// When the run was archived, taken from the file name / 运行被归档的时间，取自文件名
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Returns the path the results of a run finished at `recorded_at` are archived to.
+/// 返回在 `recorded_at` 完成的运行的结果归档到的路径。
+pub fn archived_report_path(state_dir: &Path, recorded_at: DateTime<Utc>) -> PathBuf {
+    state_dir.join(REPORTS_DIR).join(format!(
+        "run-{}.json",
+        recorded_at.format(REPORT_TIME_FORMAT)
+    ))
+}
+
+/// Lists the archived runs of a state directory, most recent first. Files not named like
+/// archived results are left out, so they are never pruned.
+/// 列出状态目录中已归档的运行，最近的在前。名称不符合归档结果格式的文件不会被列出，因此永远不会被清理。
+pub fn archived_runs(state_dir: &Path) -> Vec<ArchivedRun> {
+    let Ok(entries) = fs::read_dir(state_dir.join(REPORTS_DIR)) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<ArchivedRun> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter_map(|path| {
+            let time = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix("run-")?
+                .strip_suffix(".json")?;
+            let recorded_at = NaiveDateTime::parse_from_str(time, REPORT_TIME_FORMAT)
+                .ok()?
+                .and_utc();
+            Some(ArchivedRun { path, recorded_at })
+        })
+        .collect();
+    runs.sort_by(|a, b| {
+        b.recorded_at
+            .cmp(&a.recorded_at)
+            .then_with(|| b.path.cmp(&a.path))
+    });
+    runs
+}
+
+/// Selects the archived runs the retention policy no longer keeps.
+///
+/// # Arguments
+/// * `runs` - The archived runs, most recent first, as returned by [`archived_runs`]
+/// * `retention` - The retention policy
+/// * `now` - The current time, against which the age of the runs is measured
+///
+/// 选出保留策略不再保留的已归档运行。
+pub fn runs_to_prune(
+    runs: &[ArchivedRun],
+    retention: Retention,
+    now: DateTime<Utc>,
+) -> Vec<&ArchivedRun> {
+    let oldest_kept = now - chrono::Duration::days(i64::from(retention.keep_days));
+    runs.iter()
+        .enumerate()
+        .filter(|(index, run)| {
+            (retention.keep_runs > 0 && *index >= retention.keep_runs)
+                || (retention.keep_days > 0 && run.recorded_at < oldest_kept)
+        })
+        .map(|(_, run)| run)
+        .collect()
+}
+
+/// Deletes the archived runs the retention policy no longer keeps, or only lists them
+/// with `dry_run`.
+///
+/// # Returns
+/// The runs that were (or, with `dry_run`, would be) deleted, or the first deletion error
+///
+/// 删除保留策略不再保留的已归档运行；使用 `dry_run` 时仅列出它们。
+/// 返回已删除（或在 `dry_run` 下将被删除）的运行，或第一个删除错误。
+pub fn prune_reports(
+    state_dir: &Path,
+    retention: Retention,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<Vec<ArchivedRun>> {
+    let runs = archived_runs(state_dir);
+    let pruned: Vec<ArchivedRun> = runs_to_prune(&runs, retention, now)
+        .into_iter()
+        .cloned()
+        .collect();
+    if !dry_run {
+        for run in &pruned {
+            fs::remove_file(&run.path).with_context(|| {
+                format!("Failed to delete archived results: {}", run.path.display())
+            })?;
+        }
+    }
+    Ok(pruned)
+}
@@ -0,0 +1,97 @@
+//! # Live Log Module / 实时日志模块
+//!
+//! While a run is in progress, the output of every running case is appended line by line
+//! to its own file in `<state_dir>/live`. `matrix-runner tail <case>` follows that file
+//! from another shell, so one interesting case can be watched without streaming the
+//! output of every parallel case to the terminal. The files are cleared when the next
+//! run starts, so the logs of the last run can still be read after it ended.
+//!
+//! 在运行进行期间，每个正在运行的用例的输出都会逐行追加到 `<state_dir>/live` 中它自己的文件里。
+//! `matrix-runner tail <case>` 可在另一个 shell 中跟踪该文件，从而无需将所有并行用例的输出
+//! 都打印到终端，就能观察某一个感兴趣的用例。这些文件会在下一次运行开始时被清除，
+//! 因此上一次运行结束后仍可读取其日志。
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::infra::fs::case_dir_name;
+
+/// The directory of the live logs, below the runner's state directory.
+/// 实时日志的目录，位于运行器的状态目录之下。
+pub const LIVE_LOG_DIR: &str = "live";
+
+/// The path of the live log of a case / 用例实时日志的路径
+pub fn live_log_path(state_dir: &Path, case_name: &str) -> PathBuf {
+    state_dir
+        .join(LIVE_LOG_DIR)
+        .join(format!("{}.log", case_dir_name(case_name)))
+}
+
+/// Removes the live logs of the previous run.
+/// 删除上一次运行的实时日志。
+pub fn clear(state_dir: &Path) -> Result<()> {
+    let dir = state_dir.join(LIVE_LOG_DIR);
+    match fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {}", dir.display())),
+    }
+}
+
+/// Creates the live log of a case that is starting, replacing an earlier one of the same
+/// run (e.g. of a case started again after a cancellation).
+/// 为正在开始的用例创建实时日志，替换同一次运行中较早的日志（例如取消后再次启动的用例）。
+pub fn create(state_dir: &Path, case_name: &str) -> Result<File> {
+    let path = live_log_path(state_dir, case_name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    File::create(&path).with_context(|| format!("Failed to create {}", path.display()))
+}
+
+/// Reads what was appended to a live log since the last read.
+/// 读取自上次读取以来追加到实时日志中的内容。
+#[derive(Debug, Clone)]
+pub struct LogFollower {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl LogFollower {
+    /// Starts following a log from its beginning / 从头开始跟踪日志
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, offset: 0 }
+    }
+
+    /// Whether the log exists yet / 日志是否已存在
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    /// Reads the new content of the log.
+    ///
+    /// # Returns
+    /// The content appended since the last call, empty if there is none or the log does
+    /// not exist yet. A log that was replaced by a shorter one is read from its beginning.
+    ///
+    /// 读取日志的新内容。返回自上次调用以来追加的内容；如果没有新内容或日志尚不存在，则返回空字符串。
+    /// 被较短的日志替换的日志会从头读取。
+    pub fn read_new(&mut self) -> std::io::Result<String> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(String::new()),
+            Err(e) => return Err(e),
+        };
+        if file.metadata()?.len() < self.offset {
+            self.offset = 0;
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        self.offset += bytes.len() as u64;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
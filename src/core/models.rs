@@ -7,18 +7,108 @@
 //! 此模块定义了整个矩阵运行器中使用的核心数据结构。
 //! 它包括测试结果、构建上下文、失败原因和 cargo 特定消息格式的模型。
 
-use crate::core::config::TestCase;
+use crate::core::anomaly::DurationAnomaly;
+use crate::core::axis::AxisValueSummary;
+use crate::core::config::{TestCase, TestMatrix};
+use crate::core::context::RunContext;
+use crate::core::costs::CaseCost;
+use crate::core::fingerprint::FailureFingerprint;
+use crate::core::flakiness::FlakyCase;
+use crate::core::owners::OwnerFailures;
+use crate::core::retry_hint::RetryHint;
+use crate::infra::coverage::Coverage;
 use crate::infra::t;
+use chrono::NaiveDate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use std::time::Duration;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Run-level information shown alongside the results in reports,
+/// such as why cases were left out of the run.
+/// 与结果一起显示在报告中的运行级信息，例如为何有用例未被运行。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RunMetadata {
+    /// Human-readable notes on how the set of cases was chosen / 关于用例集合如何被选出的可读说明
+    #[serde(default)]
+    pub notes: Vec<String>,
+    /// The environment the run happened in / 运行所处的环境
+    #[serde(default)]
+    pub environment: Option<RunEnvironment>,
+    /// Cases whose duration deviated from their `expected_secs` / 耗时偏离其 `expected_secs` 的用例
+    #[serde(default)]
+    pub anomalies: Vec<DurationAnomaly>,
+    /// Results aggregated per feature, toolchain, target and package / 按 feature、工具链、目标和包汇总的结果
+    #[serde(default)]
+    pub axes: Vec<AxisValueSummary>,
+    /// SHA-256 of the test binary of each built case, if recorded / 每个已构建用例测试二进制文件的 SHA-256（如果已记录）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifact_checksums: BTreeMap<String, String>,
+    /// Builds retried after a transient cargo error, per case; not part of the case's retries / 每个用例因暂时性 cargo 错误而重试的构建次数；不计入用例的重试
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transient_build_retries: BTreeMap<String, u32>,
+    /// The line coverage of each `kind = "tarpaulin"` case / 每个 `kind = "tarpaulin"` 用例的行覆盖率
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub coverage: BTreeMap<String, Coverage>,
+    /// The build time and target directory size of each case / 每个用例的构建时间和 target 目录大小
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub case_costs: BTreeMap<String, CaseCost>,
+    /// The unexpected failures grouped by owner, if the matrix has `[owners]` / 按负责人分组的意外失败（如果矩阵配置了 `[owners]`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failure_owners: Vec<OwnerFailures>,
+    /// A stable fingerprint of every failure and for how many runs it has persisted / 每个失败的稳定指纹及其已持续的运行次数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failure_fingerprints: Vec<FailureFingerprint>,
+    /// The cases that only passed after retries, with their retry history and flaky score / 仅在重试后才通过的用例，及其重试历史和不稳定分数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flaky_cases: Vec<FlakyCase>,
+    /// For a split run, the failed cases a follow-up job can retry / 对于拆分运行，后续作业可以重试的失败用例
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryHint>,
+    /// The matrix the run was made from, after generated cases were added, defaults applied
+    /// and names resolved, but before `--select` and other run options picked its cases
+    /// 本次运行所依据的矩阵：已加入生成的用例、应用默认值并解析名称，但尚未经过 `--select` 等运行选项筛选用例
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<TestMatrix>,
+}
+
+/// A snapshot of the toolchain and machine a run happened on, for reproducibility.
+/// Fields that could not be determined are `None`.
+/// 运行所在工具链和机器的快照，用于复现。无法确定的字段为 `None`。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RunEnvironment {
+    /// When the environment was captured / 捕获环境的时间
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+    /// Output of `rustc -V` / `rustc -V` 的输出
+    pub rustc_version: Option<String>,
+    /// Output of `cargo -V` / `cargo -V` 的输出
+    pub cargo_version: Option<String>,
+    /// The active rustup toolchain / 当前激活的 rustup 工具链
+    pub toolchain: Option<String>,
+    /// The host target triple / 主机目标三元组
+    pub host: Option<String>,
+    /// The operating system (e.g., "linux") / 操作系统（例如 "linux"）
+    pub os: String,
+    /// The operating system version / 操作系统版本
+    pub os_version: Option<String>,
+    /// The CPU architecture (e.g., "x86_64") / CPU 架构（例如 "x86_64"）
+    pub arch: String,
+    /// Number of logical CPUs / 逻辑 CPU 数量
+    pub cpu_count: usize,
+    /// The CPU model name / CPU 型号名称
+    pub cpu_model: Option<String>,
+    /// Environment variables that influence builds / 影响构建的环境变量
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,
+}
 
 /// Enumerates the possible reasons for a test case failure.
 /// This helps in categorizing errors for reporting and handling.
 /// 枚举测试用例失败的可能原因。
 /// 这有助于对错误进行分类，以便报告和处理。
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub enum FailureReason {
     /// The test case failed during the `cargo build` or `cargo test --no-run` phase.
     /// 测试用例在 `cargo build` 或 `cargo test --no-run` 阶段失败。
@@ -29,22 +119,45 @@ pub enum FailureReason {
     /// The test case exceeded its configured timeout.
     /// 测试用例超出了其配置的超时时间。
     Timeout,
+    /// The test process printed nothing for longer than its `output_idle_timeout_secs`
+    /// and was killed.
+    /// 测试进程在超过其 `output_idle_timeout_secs` 的时间内没有任何输出，已被终止。
+    Stalled,
     /// A custom command defined in the test case failed.
     /// 测试用例中定义的自定义命令执行失败。
     CustomCommand,
     /// The `cargo build` phase itself failed.
     /// `cargo build` 阶段本身失败。
     BuildFailed,
+    /// Two builds of the test case produced different test binaries (`--verify-reproducible`).
+    /// 测试用例的两次构建产生了不同的测试二进制文件（`--verify-reproducible`）。
+    NotReproducible,
+    /// The documentation of a `kind = "doc"` case failed to build, e.g. on a broken intra-doc link.
+    /// `kind = "doc"` 用例的文档构建失败，例如遇到失效的文档内链接。
+    Doc,
+    /// A fuzz target of a `kind = "fuzz-smoke"` case crashed; the crashing input is attached.
+    /// `kind = "fuzz-smoke"` 用例的某个模糊测试目标崩溃；崩溃的输入作为附件保存。
+    FuzzCrash,
+}
+
+/// The duration of a single test function, as reported by libtest.
+/// 由 libtest 报告的单个测试函数的耗时。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TestTiming {
+    /// The full path of the test (e.g., `tests::it_works`) / 测试的完整路径（例如 `tests::it_works`）
+    pub name: String,
+    /// The time the test took / 测试所花费的时间
+    pub duration: Duration,
 }
 
 /// Represents the final result of a single test case execution.
 /// This enum captures all possible outcomes of running a test case,
-/// including success, various types of failures, and skipped tests.
+/// including success, various types of failures, build-only cases and skipped tests.
 ///
 /// 表示单个测试用例执行的最终结果。
 /// 此枚举捕获运行测试用例的所有可能结果，
-/// 包括成功、各种类型的失败和跳过的测试。
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 包括成功、各种类型的失败、仅构建的用例和跳过的测试。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum TestResult {
     /// The test case passed successfully.
     /// 测试用例成功通过。
@@ -58,6 +171,15 @@ pub enum TestResult {
         /// The number of attempts it took to pass the test (1 means it passed on the first try).
         /// 通过测试所需的尝试次数（1 表示第一次尝试就通过）。
         retries: u8,
+        /// Files attached to the result by the test process / 测试进程附加到结果的文件
+        #[serde(default)]
+        attachments: Vec<PathBuf>,
+        /// Durations of individual tests reported by libtest / libtest 报告的各个测试的耗时
+        #[serde(default)]
+        test_timings: Vec<TestTiming>,
+        /// Key/value annotations written by the test process / 测试进程写入的键值注解
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        annotations: BTreeMap<String, String>,
     },
     /// The test case failed for various reasons.
     /// 测试用例因各种原因失败。
@@ -70,30 +192,84 @@ pub enum TestResult {
         reason: FailureReason,
         /// The time taken before the failure occurred / 失败发生前所花费的时间
         duration: Duration,
+        /// Files attached to the result by the test process / 测试进程附加到结果的文件
+        #[serde(default)]
+        attachments: Vec<PathBuf>,
+        /// Durations of individual tests reported by libtest / libtest 报告的各个测试的耗时
+        #[serde(default)]
+        test_timings: Vec<TestTiming>,
+        /// Key/value annotations written by the test process / 测试进程写入的键值注解
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        annotations: BTreeMap<String, String>,
+    },
+    /// A `build_only` case compiled successfully; its tests were not run.
+    /// `build_only` 用例编译成功；其测试未被运行。
+    Built {
+        /// The test case configuration that was built / 构建的测试用例配置
+        case: TestCase,
+        /// The build output / 构建输出
+        output: String,
+        /// The time taken to build / 构建所花费的时间
+        duration: Duration,
+    },
+    /// The test case was skipped. Cases left out of the run on purpose, e.g. by
+    /// `--offline-only`, carry the case and the reason; cases that were not started
+    /// because the run stopped carry neither.
+    /// 测试用例被跳过。被有意排除在运行之外的用例（例如由 `--offline-only` 排除）带有用例和原因；
+    /// 因运行停止而未启动的用例两者都不带。
+    Skipped {
+        /// The test case that was left out / 被排除的测试用例
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        case: Option<TestCase>,
+        /// Why the case was left out / 用例被排除的原因
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
     },
-    /// The test case was skipped due to platform or architecture constraints.
-    /// 由于平台或架构约束，测试用例被跳过。
-    Skipped,
 }
 
 impl TestResult {
-    /// Checks if a test result is a failure that was not explicitly allowed.
-    /// A failure is "unexpected" if it's a `Failed` variant and the current OS
-    /// is not in the test case's `allow_failure` list.
-    pub fn is_unexpected_failure(&self) -> bool {
+    /// A skipped result for a case that was not started because the run stopped.
+    /// 因运行停止而未启动的用例的跳过结果。
+    pub fn skipped() -> Self {
+        TestResult::Skipped {
+            case: None,
+            reason: None,
+        }
+    }
+
+    /// A skipped result for a case that was left out of the run on purpose.
+    /// 被有意排除在运行之外的用例的跳过结果。
+    pub fn skipped_case(case: TestCase, reason: impl Into<String>) -> Self {
+        TestResult::Skipped {
+            case: Some(case),
+            reason: Some(reason.into()),
+        }
+    }
+
+    /// Gets the reason a skipped case was left out of the run, if it records one.
+    /// 获取被跳过的用例被排除在运行之外的原因（如果有记录）。
+    pub fn skip_reason(&self) -> Option<&str> {
         match self {
-            TestResult::Failed { case, .. } => {
-                !case.allow_failure.iter().any(|s| s == std::env::consts::OS)
-            }
-            _ => false, // Only failures can be unexpected.
+            TestResult::Skipped { reason, .. } => reason.as_deref(),
+            _ => None,
         }
     }
 
-    /// Checks if the test result is a failure that was explicitly allowed for the current platform.
-    pub fn is_allowed_failure(&self) -> bool {
+    /// Checks if a test result is a failure that was not explicitly allowed
+    /// (see [`TestResult::is_allowed_failure`]).
+    pub fn is_unexpected_failure(&self, context: &RunContext, today: NaiveDate) -> bool {
+        self.is_failure() && !self.is_allowed_failure(context, today)
+    }
+
+    /// Checks if the test result is a failure that was explicitly allowed: any failure
+    /// on a platform in the case's `allow_failure` list (unless the entry has expired),
+    /// and a timeout of a case with `allow_timeout` set. The platform is that of `context`,
+    /// and `today` decides whether an `allow_failure` entry has expired.
+    pub fn is_allowed_failure(&self, context: &RunContext, today: NaiveDate) -> bool {
         match self {
-            TestResult::Failed { case, .. } => {
-                case.allow_failure.iter().any(|s| s == std::env::consts::OS)
+            TestResult::Failed { case, reason, .. } => {
+                case.allows_failure_on(&context.os, today)
+                    || (case.allow_timeout && *reason == FailureReason::Timeout)
             }
             _ => false,
         }
@@ -105,47 +281,85 @@ impl TestResult {
     }
 
     /// Gets the appropriate CSS class for the test status.
-    pub fn get_status_class(&self) -> &str {
+    pub fn get_status_class(&self, context: &RunContext, today: NaiveDate) -> &str {
         match self {
             TestResult::Passed { .. } => "status-Passed",
             TestResult::Failed { reason, .. } => {
-                if self.is_allowed_failure() {
+                if self.is_allowed_failure(context, today) {
                     "status-Allowed-Failure"
-                } else if *reason == FailureReason::Timeout {
+                } else if matches!(reason, FailureReason::Timeout | FailureReason::Stalled) {
                     "status-Timeout"
                 } else {
                     "status-Failed"
                 }
             }
-            TestResult::Skipped => "status-Skipped",
+            TestResult::Built { .. } => "status-Built",
+            TestResult::Skipped { .. } => "status-Skipped",
         }
     }
 
-    /// Gets the name of the test case. Returns "Skipped" for skipped tests.
-    /// 获取测试用例的名称。对于跳过的测试，返回 "Skipped"。
+    /// Gets the name of the test case. Returns "Skipped" for skipped results without a case.
+    /// 获取测试用例的名称。对于不带用例的跳过结果，返回 "Skipped"。
     pub fn case_name(&self) -> &str {
         match self {
             TestResult::Passed { case, .. } => &case.name,
             TestResult::Failed { case, .. } => &case.name,
-            TestResult::Skipped => "Skipped",
+            TestResult::Built { case, .. } => &case.name,
+            TestResult::Skipped { case: Some(case), .. } => &case.name,
+            TestResult::Skipped { case: None, .. } => "Skipped",
+        }
+    }
+
+    /// Gets the stable id of the test case (see [`TestCase::case_id`]). Skipped results without a case have none.
+    /// 获取测试用例的稳定 ID（参见 [`TestCase::case_id`]）。不带用例的跳过结果没有 ID。
+    pub fn case_id(&self) -> Option<String> {
+        self.get_case().map(TestCase::case_id)
+    }
+
+    /// Gets the package of the test case, if it sets one. Skipped results without a case have none.
+    /// 获取测试用例的包（如果设置了）。不带用例的跳过结果没有包。
+    pub fn package(&self) -> Option<&str> {
+        match self {
+            TestResult::Passed { case, .. } | TestResult::Failed { case, .. } | TestResult::Built { case, .. } => {
+                case.package.as_deref()
+            }
+            TestResult::Skipped { case, .. } => case.as_ref()?.package.as_deref(),
         }
     }
 
     /// Gets the status of the test result as a string for display.
     /// 以字符串形式获取测试结果的状态以供显示。
-    pub fn get_status_str(&self, locale: &str) -> String {
+    pub fn get_status_str(&self, context: &RunContext, today: NaiveDate) -> String {
+        let locale = context.locale.as_str();
         match self {
             TestResult::Passed { .. } => t!("report.status_passed", locale = locale).to_string(),
-            TestResult::Failed { case, reason, .. } => {
+            TestResult::Failed { reason, .. } => {
                 if *reason == FailureReason::Timeout {
                     t!("report.status_timeout", locale = locale).to_string()
-                } else if case.allow_failure.iter().any(|s| s == std::env::consts::OS) {
+                } else if *reason == FailureReason::Stalled {
+                    t!("report.status_stalled", locale = locale).to_string()
+                } else if self.is_allowed_failure(context, today) {
                     t!("report.status_allowed_failure", locale = locale).to_string()
                 } else {
                     t!("report.status_failed", locale = locale).to_string()
                 }
             }
-            TestResult::Skipped => t!("report.status_skipped", locale = locale).to_string(),
+            TestResult::Built { .. } => t!("report.status_built", locale = locale).to_string(),
+            TestResult::Skipped { .. } => t!("report.status_skipped", locale = locale).to_string(),
+        }
+    }
+
+    /// Gets a stable, locale-independent status key (`passed`, `failed`, `timeout`, `built` or `skipped`).
+    /// Allowed failures are reported as `failed`.
+    /// 获取稳定且与语言无关的状态键（`passed`、`failed`、`timeout`、`built` 或 `skipped`）。
+    /// 允许的失败报告为 `failed`。
+    pub fn status_key(&self) -> &'static str {
+        match self {
+            TestResult::Passed { .. } => "passed",
+            TestResult::Failed { reason, .. } if *reason == FailureReason::Timeout => "timeout",
+            TestResult::Failed { .. } => "failed",
+            TestResult::Built { .. } => "built",
+            TestResult::Skipped { .. } => "skipped",
         }
     }
 
@@ -155,7 +369,19 @@ impl TestResult {
         match self {
             TestResult::Passed { output, .. } => output.clone(),
             TestResult::Failed { output, .. } => output.clone(),
-            TestResult::Skipped => String::new(),
+            TestResult::Built { output, .. } => output.clone(),
+            TestResult::Skipped { .. } => String::new(),
+        }
+    }
+
+    /// Gets the case the result belongs to. Returns None for skipped results without a case.
+    /// 获取结果所属的用例。对于不带用例的跳过结果返回 None。
+    pub fn get_case(&self) -> Option<&TestCase> {
+        match self {
+            TestResult::Passed { case, .. }
+            | TestResult::Failed { case, .. }
+            | TestResult::Built { case, .. } => Some(case),
+            TestResult::Skipped { case, .. } => case.as_ref(),
         }
     }
 
@@ -165,7 +391,8 @@ impl TestResult {
         match self {
             TestResult::Passed { case, .. } => &case.features,
             TestResult::Failed { case, .. } => &case.features,
-            TestResult::Skipped => "",
+            TestResult::Built { case, .. } => &case.features,
+            TestResult::Skipped { case, .. } => case.as_ref().map_or("", |case| &case.features),
         }
     }
 
@@ -175,7 +402,8 @@ impl TestResult {
         match self {
             TestResult::Passed { duration, .. } => Some(*duration),
             TestResult::Failed { duration, .. } => Some(*duration),
-            TestResult::Skipped => None,
+            TestResult::Built { duration, .. } => Some(*duration),
+            TestResult::Skipped { .. } => None,
         }
     }
 
@@ -188,6 +416,37 @@ impl TestResult {
         }
     }
 
+    /// Gets the files attached to the result. Returns an empty slice for built or skipped cases.
+    /// 获取附加到结果的文件。对于仅构建或跳过的用例，返回空切片。
+    pub fn get_attachments(&self) -> &[PathBuf] {
+        match self {
+            TestResult::Passed { attachments, .. } => attachments,
+            TestResult::Failed { attachments, .. } => attachments,
+            TestResult::Built { .. } | TestResult::Skipped { .. } => &[],
+        }
+    }
+
+    /// Gets the annotations the test process wrote. Returns an empty map for built or skipped cases.
+    /// 获取测试进程写入的注解。对于仅构建或跳过的用例，返回空映射。
+    pub fn get_annotations(&self) -> &BTreeMap<String, String> {
+        static NO_ANNOTATIONS: BTreeMap<String, String> = BTreeMap::new();
+        match self {
+            TestResult::Passed { annotations, .. } => annotations,
+            TestResult::Failed { annotations, .. } => annotations,
+            TestResult::Built { .. } | TestResult::Skipped { .. } => &NO_ANNOTATIONS,
+        }
+    }
+
+    /// Gets the per-test timings of the result. Returns an empty slice if none were reported.
+    /// 获取结果中各个测试的耗时。如果没有报告，则返回空切片。
+    pub fn get_test_timings(&self) -> &[TestTiming] {
+        match self {
+            TestResult::Passed { test_timings, .. } => test_timings,
+            TestResult::Failed { test_timings, .. } => test_timings,
+            TestResult::Built { .. } | TestResult::Skipped { .. } => &[],
+        }
+    }
+
     pub fn is_timeout(&self) -> bool {
         matches!(self, TestResult::Failed { reason, .. } if *reason == FailureReason::Timeout)
     }
@@ -278,50 +537,179 @@ impl BuiltTest {
 }
 
 /// Diagnostic information for cargo compiler messages.
-/// 诊断信息，用于cargo编译器消息。
+/// Fields missing from the output of some toolchains fall back to empty values.
+/// 诊断信息，用于cargo编译器消息。某些工具链输出中缺失的字段会回退为空值。
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CargoDiagnostic {
     /// The severity level of the diagnostic (e.g., "error", "warning").
     /// 诊断的严重级别（例如 "error", "warning"）。
+    #[serde(default)]
     pub level: String,
     /// The raw diagnostic message.
     /// 原始的诊断消息。
+    #[serde(default)]
     pub message: String,
     /// The ANSI color-coded, formatted message, if available.
-    /// 带有 ANSI 颜色代码的格式化消息（如果可用）。
+    /// Older toolchains emit `null` for some diagnostics.
+    /// 带有 ANSI 颜色代码的格式化消息（如果可用）。旧版工具链对某些诊断输出 `null`。
+    #[serde(default)]
     pub rendered: Option<String>,
+    /// The lint or error code, e.g. `rustdoc::broken_intra_doc_links` or `E0425`.
+    /// lint 或错误代码，例如 `rustdoc::broken_intra_doc_links` 或 `E0425`。
+    #[serde(default)]
+    pub code: Option<DiagnosticCode>,
+}
+
+/// The code of a compiler diagnostic.
+/// 编译器诊断的代码。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DiagnosticCode {
+    /// The code itself / 代码本身
+    pub code: String,
+}
+
+/// The kind of a message in Cargo's JSON output.
+/// Reasons this runner does not know, such as those added by newer toolchains, are `Unknown`.
+/// Cargo JSON 输出中消息的类型。本运行器不认识的原因（例如新版工具链新增的）为 `Unknown`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CargoReason {
+    /// A compiled artifact / 编译产物
+    CompilerArtifact,
+    /// A diagnostic from the compiler / 来自编译器的诊断
+    CompilerMessage,
+    /// A build script finished / 构建脚本执行完成
+    BuildScriptExecuted,
+    /// The build finished (cargo 1.44+) / 构建完成（cargo 1.44+）
+    BuildFinished,
+    /// Any other reason / 其他任何原因
+    #[serde(other)]
+    Unknown,
 }
 
 /// A structured representation of a message from Cargo's JSON output format.
+///
+/// Parsing is deliberately lenient: unknown fields are ignored and fields that only
+/// some cargo versions emit are optional, so the output of old and new toolchains
+/// alike can be read.
+///
 /// 来自Cargo的JSON输出格式的消息的结构化表示。
+/// 解析刻意保持宽松：未知字段会被忽略，仅部分 cargo 版本输出的字段是可选的，
+/// 因此新旧工具链的输出都可以读取。
 #[derive(Debug, Clone, Deserialize)]
 pub struct CargoMessage {
     /// The reason for the message (e.g., "compiler-artifact", "compiler-message").
     /// 消息的原因（例如 "compiler-artifact", "compiler-message"）。
-    pub reason: String,
+    pub reason: CargoReason,
     /// Information about the compilation target, present for artifact messages.
     /// 关于编译目标的信息，存在于产物消息中。
+    #[serde(default)]
     pub target: Option<CargoTarget>,
+    /// The profile the artifact was built with, present for artifact messages.
+    /// 构建产物所用的配置，存在于产物消息中。
+    #[serde(default)]
+    pub profile: Option<ArtifactProfile>,
     /// The path to the compiled executable, present for artifact messages.
     /// 指向已编译可执行文件的路径，存在于产物消息中。
+    #[serde(default)]
     pub executable: Option<PathBuf>,
     /// The diagnostic message, present for compiler messages.
     /// 诊断消息，存在于编译器消息中。
+    #[serde(default)]
     pub message: Option<CargoDiagnostic>,
     #[serde(default)]
     pub filenames: Vec<PathBuf>,
+    /// Whether the build succeeded, present for `build-finished` messages.
+    /// 构建是否成功，存在于 `build-finished` 消息中。
+    #[serde(default)]
+    pub success: Option<bool>,
 }
 
 impl CargoMessage {
+    /// Parses one line of cargo output. Lines that are not JSON objects, such as
+    /// plain text printed by build scripts, or that carry no `reason`, yield `None`.
+    ///
+    /// 解析 cargo 输出的一行。不是 JSON 对象的行（例如构建脚本打印的纯文本）
+    /// 或没有 `reason` 的行返回 `None`。
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') {
+            return None;
+        }
+        serde_json::from_str(line).ok()
+    }
+
     /// Returns the message if it's an artifact message, otherwise None.
     /// 如果是产物消息则返回该消息，否则返回None。
     pub fn into_artifact(self) -> Option<Self> {
-        if self.reason == "compiler-artifact" {
+        if self.reason == CargoReason::CompilerArtifact {
             Some(self)
         } else {
             None
         }
     }
+
+    /// Returns the executable of a test artifact. Toolchains that do not report
+    /// `target.test` are covered by the artifact's `profile.test`.
+    ///
+    /// 返回测试产物的可执行文件。对于不报告 `target.test` 的工具链，
+    /// 由产物的 `profile.test` 判断。
+    pub fn test_executable(&self) -> Option<&Path> {
+        if self.reason != CargoReason::CompilerArtifact {
+            return None;
+        }
+        let is_test = self.target.as_ref().is_some_and(|target| target.test)
+            || self.profile.as_ref().is_some_and(|profile| profile.test);
+        self.executable.as_deref().filter(|_| is_test)
+    }
+}
+
+/// The profile settings an artifact was built with.
+/// 构建产物时使用的配置设置。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ArtifactProfile {
+    /// The optimization level, e.g. "0", "3" or "s" / 优化级别，例如 "0"、"3" 或 "s"
+    #[serde(default)]
+    pub opt_level: String,
+    /// The debug info level, if any / 调试信息级别（如有）
+    #[serde(default)]
+    pub debuginfo: Option<DebugInfo>,
+    #[serde(default)]
+    pub debug_assertions: bool,
+    #[serde(default)]
+    pub overflow_checks: bool,
+    /// `true` if the artifact was built for testing / 如果产物是为测试而构建的，则为 `true`
+    #[serde(default)]
+    pub test: bool,
+}
+
+/// The debug info level of a profile. Cargo reports a number, while newer versions
+/// report named levels such as "line-tables-only" as a string.
+/// 配置的调试信息级别。Cargo 报告一个数字，而新版本会以字符串报告命名级别，例如 "line-tables-only"。
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum DebugInfo {
+    Level(u32),
+    Named(String),
+}
+
+/// A single event from libtest's JSON output (`--format json`).
+/// 来自 libtest JSON 输出（`--format json`）的单个事件。
+#[derive(Debug, Clone, Deserialize)]
+pub struct LibtestEvent {
+    /// The kind of event source, e.g. "suite" or "test".
+    /// 事件来源的类型，例如 "suite" 或 "test"。
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// What happened, e.g. "started", "ok" or "failed".
+    /// 发生的事件，例如 "started"、"ok" 或 "failed"。
+    pub event: String,
+    /// The test name, present for test events.
+    /// 测试名称，存在于测试事件中。
+    pub name: Option<String>,
+    /// The execution time in seconds, present with `--report-time`.
+    /// 执行时间（秒），在使用 `--report-time` 时存在。
+    pub exec_time: Option<f64>,
 }
 
 /// Information about a compilation target from Cargo.
@@ -331,9 +719,11 @@ pub struct CargoTarget {
     /// The name of the crate being compiled.
     /// 正在编译的 crate 的名称。
     pub name: String,
-    /// `true` if the artifact is a test executable.
-    /// 如果产物是测试可执行文件，则为 `true`。
+    /// `true` if the artifact is a test executable. Not reported by old toolchains.
+    /// 如果产物是测试可执行文件，则为 `true`。旧版工具链不报告此字段。
+    #[serde(default)]
     pub test: bool,
+    #[serde(default)]
     pub kind: Vec<String>,
 }
 
@@ -344,9 +734,9 @@ pub struct Package {
     pub name: String,
 }
 
-/// Cargo.toml manifest structure
-/// Cargo.toml清单结构
+/// Cargo.toml manifest structure. `package` is absent in a virtual workspace manifest.
+/// Cargo.toml清单结构。虚拟工作区清单中没有 `package`。
 #[derive(Debug, Clone, Deserialize)]
 pub struct Manifest {
-    pub package: Package,
+    pub package: Option<Package>,
 } 
\ No newline at end of file
@@ -0,0 +1,135 @@
+//! # Ownership Module / 归属模块
+//!
+//! This module maps case names to the people responsible for them through the
+//! matrix's `[owners]` table (a case name glob mapped to a team, handle or e-mail), and
+//! groups the failures of a run by owner, so on a large team everyone immediately sees
+//! which red cases are theirs.
+//!
+//! 此模块通过矩阵的 `[owners]` 表（用例名称通配符映射到团队、账号或电子邮件）
+//! 将用例名称映射到其负责人，并按负责人对运行中的失败进行分组，
+//! 使大型团队中的每个人都能立即看到哪些失败用例归自己处理。
+
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::config::unqualified_name;
+use crate::core::context::RunContext;
+use crate::core::models::TestResult;
+
+/// The failures routed to one owner.
+/// 归属于某个负责人的失败。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OwnerFailures {
+    /// The owner, or `None` for failures no pattern matches / 负责人；没有任何模式匹配的失败为 `None`
+    pub owner: Option<String>,
+    /// The names of the failed cases / 失败用例的名称
+    pub cases: Vec<String>,
+}
+
+/// Matches a case name against a glob pattern, where `*` matches any run of
+/// characters and `?` matches exactly one.
+/// 将用例名称与通配符模式匹配，其中 `*` 匹配任意字符序列，`?` 恰好匹配一个字符。
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The position after the last `*` seen, and the name position it was tried at.
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+            }
+            Some(c) if *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and try again.
+                Some((star_p, star_n)) => {
+                    backtrack = Some((star_p, star_n + 1));
+                    p = star_p;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Gets the owners of a case: the owner of every pattern its name matches. A case named
+/// `pkg::case` also matches the patterns of its bare name.
+/// 获取用例的负责人：其名称匹配的每个模式对应的负责人。名为 `pkg::case` 的用例也会匹配其不带包名的名称的模式。
+pub fn owners_of<'a>(owners: &'a BTreeMap<String, String>, case_name: &str) -> Vec<&'a str> {
+    let bare_name = unqualified_name(case_name);
+    let mut matched: Vec<&str> = owners
+        .iter()
+        .filter(|(pattern, _)| glob_matches(pattern, case_name) || glob_matches(pattern, bare_name))
+        .map(|(_, owner)| owner.as_str())
+        .collect();
+    matched.sort_unstable();
+    matched.dedup();
+    matched
+}
+
+/// Groups the unexpected failures of a run by owner. A case with several owners is
+/// listed under each of them. Whether a failure was allowed is decided on the platform
+/// of `context` as of `today`.
+///
+/// # Returns
+/// The groups sorted by owner, followed by the failures without an owner; empty if
+/// the matrix has no `[owners]` or nothing failed unexpectedly
+///
+/// 按负责人对运行中的意外失败进行分组。有多个负责人的用例会列在每个负责人之下。
+/// 失败是否被允许按 `context` 的平台和 `today` 的日期判断。
+/// 返回按负责人排序的分组，之后是没有负责人的失败；如果矩阵没有 `[owners]`
+/// 或没有意外失败，则返回空。
+pub fn group_failures_by_owner(
+    results: &[TestResult],
+    owners: &BTreeMap<String, String>,
+    context: &RunContext,
+    today: NaiveDate,
+) -> Vec<OwnerFailures> {
+    if owners.is_empty() {
+        return Vec::new();
+    }
+
+    let mut by_owner: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut unowned = Vec::new();
+    for result in results
+        .iter()
+        .filter(|result| result.is_unexpected_failure(context, today))
+    {
+        let name = result.case_name();
+        let case_owners = owners_of(owners, name);
+        if case_owners.is_empty() {
+            unowned.push(name.to_string());
+        }
+        for owner in case_owners {
+            by_owner.entry(owner).or_default().push(name.to_string());
+        }
+    }
+
+    let mut groups: Vec<OwnerFailures> = by_owner
+        .into_iter()
+        .map(|(owner, cases)| OwnerFailures {
+            owner: Some(owner.to_string()),
+            cases,
+        })
+        .collect();
+    if !unowned.is_empty() {
+        groups.push(OwnerFailures {
+            owner: None,
+            cases: unowned,
+        });
+    }
+    groups
+}
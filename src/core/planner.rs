@@ -1,15 +1,169 @@
 //! # Test Execution Planner Module / 测试执行计划模块
 //!
 //! This module handles planning and organizing the execution of test cases,
-//! including filtering by architecture, handling distributed execution,
-//! and prioritizing test cases.
+//! including expanding the axes of a `[matrix]` section into cases, filtering by
+//! architecture, handling distributed execution, and prioritizing test cases.
 //!
 //! 此模块处理测试用例的执行计划和组织，
-//! 包括按架构过滤、处理分布式执行和优先排序测试用例。
+//! 包括将 `[matrix]` 部分的维度展开为用例、按架构过滤、处理分布式执行和优先排序测试用例。
 
-use crate::core::config::TestCase;
-use anyhow::{bail, Result};
-use std::env;
+use crate::core::config::{PACKAGE_SEPARATOR, TestCase};
+use crate::core::context::RunContext;
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The `[matrix]` section of a test matrix: axes whose cartesian product becomes cases,
+/// so a grid of nearly identical cases does not have to be written out by hand. An
+/// axis left out has the single value a case has when it does not set the field.
+/// 测试矩阵的 `[matrix]` 部分：其笛卡尔积会成为用例的维度，使几乎相同的用例网格不必手写。
+/// 省略的维度只有一个取值，即用例未设置该字段时的值。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MatrixAxes {
+    /// The feature sets to build with, each a comma-separated `features` string (`""` for none)
+    /// 用于构建的 feature 集合，每个都是以逗号分隔的 `features` 字符串（`""` 表示无）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// The values of `no_default_features`, e.g. `[false, true]` / `no_default_features` 的取值，例如 `[false, true]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_default_features: Vec<bool>,
+    /// The toolchains to build with / 用于构建的工具链
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toolchain: Vec<String>,
+    /// The target triples to build for / 要构建的目标三元组
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target: Vec<String>,
+    /// The workspace packages to test / 要测试的工作区包
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub package: Vec<String>,
+    /// Combinations left out, e.g. `[{ features = "simd", toolchain = "stable" }]` / 被排除的组合，例如 `[{ features = "simd", toolchain = "stable" }]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<AxisFilter>,
+}
+
+/// An `exclude` entry of `[matrix]`: it leaves out every combination matching all the
+/// values it sets.
+/// `[matrix]` 的一个 `exclude` 条目：排除与其设置的所有值都匹配的每个组合。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AxisFilter {
+    /// The feature set to match / 要匹配的 feature 集合
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+    /// The `no_default_features` to match / 要匹配的 `no_default_features`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_default_features: Option<bool>,
+    /// The toolchain to match / 要匹配的工具链
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<String>,
+    /// The target to match / 要匹配的目标
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// The package to match / 要匹配的包
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+impl AxisFilter {
+    /// Whether a generated case matches every value the entry sets. Feature sets match
+    /// in any order.
+    /// 生成的用例是否与该条目设置的每个值都匹配。feature 集合的匹配与顺序无关。
+    fn matches(&self, case: &TestCase) -> bool {
+        self.features.as_deref().is_none_or(|features| feature_set(features) == feature_set(&case.features))
+            && self.no_default_features.is_none_or(|value| value == case.no_default_features)
+            && self.toolchain.as_ref().is_none_or(|value| case.toolchain.as_ref() == Some(value))
+            && self.target.as_ref().is_none_or(|value| case.target.as_ref() == Some(value))
+            && self.package.as_ref().is_none_or(|value| case.package.as_ref() == Some(value))
+    }
+}
+
+impl MatrixAxes {
+    /// Expands the axes into one case per combination left after `exclude`, in a fixed
+    /// order (packages, then features, `no_default_features`, toolchains and targets, each
+    /// in the order written).
+    ///
+    /// Each case is named after its values, e.g. `a+b-no-default-nightly`: the features
+    /// joined with `+` (`default` for none), `no-default` when default features are off,
+    /// then the toolchain and the target. Cases of a `package` are named `<package>::<name>`.
+    ///
+    /// 将各维度展开为 `exclude` 之后剩余的每个组合一个用例，顺序固定（包，然后是 features、
+    /// `no_default_features`、工具链和目标，各自按书写顺序）。每个用例以其取值命名，例如
+    /// `a+b-no-default-nightly`：以 `+` 连接的 features（无 feature 时为 `default`）、关闭默认 feature 时的
+    /// `no-default`，然后是工具链和目标。属于某个 `package` 的用例命名为 `<包>::<名称>`。
+    pub fn expand(&self) -> Vec<TestCase> {
+        fn axis<T: Clone>(values: &[T], unset: T) -> Vec<T> {
+            if values.is_empty() { vec![unset] } else { values.to_vec() }
+        }
+        let packages = axis(&self.package.iter().cloned().map(Some).collect::<Vec<_>>(), None);
+        let features = axis(&self.features, String::new());
+        let no_default_features = axis(&self.no_default_features, false);
+        let toolchains = axis(&self.toolchain.iter().cloned().map(Some).collect::<Vec<_>>(), None);
+        let targets = axis(&self.target.iter().cloned().map(Some).collect::<Vec<_>>(), None);
+
+        let mut cases = Vec::new();
+        for package in &packages {
+            for features in &features {
+                for &no_default_features in &no_default_features {
+                    for toolchain in &toolchains {
+                        for target in &targets {
+                            let mut case = TestCase {
+                                features: features.clone(),
+                                no_default_features,
+                                toolchain: toolchain.clone(),
+                                target: target.clone(),
+                                package: package.clone(),
+                                ..Default::default()
+                            };
+                            if self.exclude.iter().any(|filter| filter.matches(&case)) {
+                                continue;
+                            }
+                            case.name = generated_name(&case);
+                            cases.push(case);
+                        }
+                    }
+                }
+            }
+        }
+        cases
+    }
+}
+
+/// The name of a case expanded from `[matrix]`, see [`MatrixAxes::expand`].
+fn generated_name(case: &TestCase) -> String {
+    let features: Vec<&str> = case
+        .features
+        .split(',')
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .collect();
+    let mut parts = Vec::new();
+    if !features.is_empty() {
+        parts.push(features.join("+"));
+    } else if !case.no_default_features {
+        parts.push("default".to_string());
+    }
+    if case.no_default_features {
+        parts.push("no-default".to_string());
+    }
+    parts.extend(case.toolchain.clone());
+    parts.extend(case.target.clone());
+    let name = parts.join("-");
+    match &case.package {
+        Some(package) => format!("{package}{PACKAGE_SEPARATOR}{name}"),
+        None => name,
+    }
+}
+
+/// The features of a `features` string, ignoring their order and spacing.
+fn feature_set(features: &str) -> std::collections::BTreeSet<&str> {
+    features
+        .split(',')
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .collect()
+}
 
 /// Represents a complete execution plan for a test matrix.
 /// 表示测试矩阵的完整执行计划。
@@ -41,6 +195,8 @@ pub struct ExecutionPlan {
 /// * `test_matrix` - The complete test matrix configuration
 /// * `total_runners` - Optional total number of runners for distributed execution
 /// * `runner_index` - Optional index of this runner (0-based)
+/// * `context` - The platform the cases are planned for
+/// * `today` - The day that decides whether `allow_failure` entries have expired
 ///
 /// # Returns
 /// An `ExecutionPlan` with the filtered and potentially distributed test cases
@@ -48,26 +204,27 @@ pub fn plan_execution(
     test_matrix: crate::core::config::TestMatrix,
     total_runners: Option<usize>,
     runner_index: Option<usize>,
+    context: &RunContext,
+    today: NaiveDate,
 ) -> Result<ExecutionPlan> {
     let cases = test_matrix.cases;
 
     // Filter by architecture
-    let current_arch = env::consts::ARCH;
     let (arch_cases, filtered_arch_cases): (Vec<_>, Vec<_>) = cases
         .into_iter()
-        .partition(|case| case.arch.is_empty() || case.arch.iter().any(|a| a == current_arch));
+        .partition(|case| case.arch.is_empty() || case.arch.contains(&context.arch));
 
     // Separate flaky cases
-    let current_os = env::consts::OS;
     let (mut safe_cases, flaky_cases): (Vec<_>, Vec<_>) = arch_cases
         .into_iter()
-        .partition(|case| !case.allow_failure.iter().any(|os| os == current_os));
+        .partition(|case| !case.allows_failure_on(&context.os, today));
 
     // Sort cases by name for deterministic execution order
     safe_cases.sort_by(|a, b| a.name.cmp(&b.name));
 
     let mut combined_cases = safe_cases;
     combined_cases.extend(flaky_cases.clone());
+    let combined_cases = order_by_after(combined_cases);
 
     // Distribute cases if running in CI
     let (cases_to_run, is_distributed) =
@@ -95,4 +252,40 @@ pub fn plan_execution(
         flaky_cases_count: flaky_cases.len(),
         is_distributed,
     })
-} 
\ No newline at end of file
+} 
+
+/// Orders cases so that the cases named in an `after` come before the cases naming them,
+/// keeping the given order otherwise. A case is pulled forward together with what it is
+/// ordered after, so cache-priming cases start first. Cycles do not cause an error here.
+///
+/// 对用例排序，使 `after` 中指定的用例排在指定它们的用例之前，其余保持给定顺序。
+/// 用例会与其 `after` 中的用例一起被提前，使预热缓存的用例最先开始。此处的环不会导致错误。
+pub fn order_by_after(cases: Vec<TestCase>) -> Vec<TestCase> {
+    if cases.iter().all(|case| case.after.is_empty()) {
+        return cases;
+    }
+
+    fn place(index: usize, cases: &[TestCase], visited: &mut [bool], order: &mut Vec<usize>) {
+        if visited[index] {
+            return;
+        }
+        visited[index] = true;
+        for name in &cases[index].after {
+            if let Some(before) = cases.iter().position(|case| &case.name == name) {
+                place(before, cases, visited, order);
+            }
+        }
+        order.push(index);
+    }
+
+    let mut visited = vec![false; cases.len()];
+    let mut order = Vec::with_capacity(cases.len());
+    for index in 0..cases.len() {
+        place(index, &cases, &mut visited, &mut order);
+    }
+    let mut slots: Vec<Option<TestCase>> = cases.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| slots[index].take())
+        .collect()
+}
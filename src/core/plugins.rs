@@ -0,0 +1,279 @@
+//! # Plugins Module / 插件模块
+//!
+//! Executables on `PATH` named `matrix-runner-<plugin>` extend the tool without forking it.
+//! `matrix-runner <plugin> [args]` runs such an executable as a subcommand, the way cargo
+//! runs `cargo-<name>`. The plugins listed in the settings file also receive the events of
+//! every run: each is started as `matrix-runner-<plugin> events` and reads one JSON object
+//! per line on its standard input (see [`RunEvent`]) until the input is closed. Inside the
+//! runner's own process, an [`EventSink`] such as the live dashboard of `serve` receives
+//! the same events.
+//!
+//! 名为 `matrix-runner-<plugin>` 且位于 `PATH` 上的可执行文件可以在不 fork 本工具的情况下扩展它。
+//! `matrix-runner <plugin> [args]` 会像 cargo 运行 `cargo-<name>` 那样将其作为子命令运行。
+//! 设置文件中列出的插件还会接收每次运行的事件：每个插件以 `matrix-runner-<plugin> events`
+//! 启动，并在其标准输入上逐行读取 JSON 对象（参见 [`RunEvent`]），直到输入被关闭。
+//! 在运行器自身的进程内，[`EventSink`]（例如 `serve` 的实时仪表盘）会接收相同的事件。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::core::context::RunContext;
+use crate::core::models::TestResult;
+
+/// The prefix of plugin executables / 插件可执行文件的前缀
+pub const PLUGIN_PREFIX: &str = "matrix-runner-";
+
+/// The version of the event protocol, sent with `run_started` / 事件协议的版本，随 `run_started` 发送
+pub const EVENT_PROTOCOL_VERSION: u32 = 1;
+
+/// The argument a plugin is started with to receive run events / 启动插件以接收运行事件时使用的参数
+pub const EVENTS_ARG: &str = "events";
+
+/// An event of a run, sent to plugins as one JSON object per line with an `event` field
+/// naming its kind, e.g. `{"event":"case_started","case":"unit-tests"}`.
+/// 一次运行的事件，以每行一个 JSON 对象的形式发送给插件，其中 `event` 字段表示事件类型，
+/// 例如 `{"event":"case_started","case":"unit-tests"}`。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// The run is starting / 运行正在开始
+    RunStarted {
+        /// The protocol version / 协议版本
+        protocol: u32,
+        /// The root of the project under test / 被测项目的根目录
+        project_root: PathBuf,
+        /// The number of cases of the run, including resumed ones / 本次运行的用例数量，包括恢复的用例
+        total: usize,
+    },
+    /// A case started / 用例开始
+    CaseStarted {
+        /// The name of the case / 用例名称
+        case: String,
+    },
+    /// A case finished / 用例完成
+    CaseFinished {
+        /// The name of the case / 用例名称
+        case: String,
+        /// The status key (`passed`, `failed`, `timeout`, `built` or `skipped`) / 状态键
+        status: String,
+        /// Whether the failure is allowed by the matrix / 失败是否被矩阵允许
+        allowed_failure: bool,
+        /// How long the case took, in seconds / 用例耗时（秒）
+        duration_secs: Option<f64>,
+    },
+    /// The run finished / 运行完成
+    RunFinished {
+        /// The number of passed or built cases / 通过或已构建的用例数量
+        passed: usize,
+        /// The number of failed cases / 失败的用例数量
+        failed: usize,
+        /// The number of skipped cases / 跳过的用例数量
+        skipped: usize,
+        /// Whether the run succeeded, i.e. nothing failed unexpectedly / 运行是否成功，即没有意外失败
+        success: bool,
+    },
+    /// A scheduled run passed clearly less often than the runs before it; sent by
+    /// `matrix-runner schedule` after the run's own events.
+    /// 某次计划运行的通过率明显低于之前的运行；由 `matrix-runner schedule` 在该运行自身的事件之后发送。
+    PassRateDropped {
+        /// The root of the project under test / 被测项目的根目录
+        project_root: PathBuf,
+        /// The pass rate of the run, in percent / 该运行的通过率（百分比）
+        pass_rate: f64,
+        /// The average pass rate of the runs before it, in percent / 之前运行的平均通过率（百分比）
+        trailing_average: f64,
+        /// The number of runs averaged / 参与平均的运行数量
+        runs: usize,
+    },
+}
+
+impl RunEvent {
+    /// Creates the `case_finished` event of a result / 创建结果的 `case_finished` 事件
+    pub fn case_finished(
+        name: &str,
+        result: &TestResult,
+        context: &RunContext,
+        today: NaiveDate,
+    ) -> Self {
+        RunEvent::CaseFinished {
+            case: name.to_string(),
+            status: result.status_key().to_string(),
+            allowed_failure: result.is_allowed_failure(context, today),
+            duration_secs: result.get_duration().map(|d| d.as_secs_f64()),
+        }
+    }
+
+    /// Creates the `run_finished` event of a run / 创建运行的 `run_finished` 事件
+    pub fn run_finished(results: &[TestResult], context: &RunContext, today: NaiveDate) -> Self {
+        let count = |f: fn(&TestResult) -> bool| results.iter().filter(|r| f(r)).count();
+        RunEvent::RunFinished {
+            passed: count(|r| matches!(r, TestResult::Passed { .. } | TestResult::Built { .. })),
+            failed: count(TestResult::is_failure),
+            skipped: count(|r| matches!(r, TestResult::Skipped { .. })),
+            success: !results
+                .iter()
+                .any(|r| r.is_unexpected_failure(context, today)),
+        }
+    }
+}
+
+/// Finds the executable of a plugin on `PATH`.
+/// 在 `PATH` 上查找插件的可执行文件。
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}{}", PLUGIN_PREFIX, name, env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Runs a plugin as a subcommand with the given arguments and waits for it.
+///
+/// # Returns
+/// The plugin's exit code, or `None` if it was killed by a signal
+///
+/// 以给定参数将插件作为子命令运行并等待其结束。返回插件的退出码；被信号终止时返回 `None`。
+pub fn run_plugin(path: &Path, args: &[OsString]) -> Result<Option<i32>> {
+    let status = Command::new(path)
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run plugin: {}", path.display()))?;
+    Ok(status.code())
+}
+
+/// A receiver of the events of a run inside the runner's process, e.g. the live dashboard
+/// of `serve`. / 运行器进程内运行事件的接收者，例如 `serve` 的实时仪表盘。
+pub trait EventSink: Send + Sync + std::fmt::Debug {
+    /// Receives an event of the run / 接收运行的一个事件
+    fn send(&self, event: &RunEvent);
+    /// Called once the run sends no more events / 在运行不再发送事件时调用
+    fn close(&self);
+}
+
+/// The plugins and sinks receiving the events of the current run, shared by all case tasks.
+/// Sending is best-effort: a plugin that exits or cannot keep up never fails the run,
+/// it just stops receiving events.
+///
+/// 接收当前运行事件的插件和接收者，由所有用例任务共享。
+/// 发送是尽力而为的：退出或无法跟上的插件永远不会导致运行失败，只是不再接收事件。
+#[derive(Debug, Clone)]
+pub struct EventStream {
+    listeners: Arc<Mutex<Vec<Listener>>>,
+    sinks: Arc<Mutex<Vec<Arc<dyn EventSink>>>>,
+}
+
+#[derive(Debug)]
+struct Listener {
+    name: String,
+    child: Child,
+    stdin: Option<ChildStdin>,
+}
+
+impl EventStream {
+    /// Starts the listed plugins. Plugins that cannot be found or started are returned
+    /// with the error, so the caller can warn about them.
+    /// 启动列出的插件。找不到或无法启动的插件会与错误一起返回，以便调用方发出警告。
+    pub fn start(plugins: &[String]) -> (Self, Vec<(String, anyhow::Error)>) {
+        let mut listeners = Vec::new();
+        let mut failures = Vec::new();
+        for name in plugins {
+            match start_listener(name) {
+                Ok(listener) => listeners.push(listener),
+                Err(e) => failures.push((name.clone(), e)),
+            }
+        }
+        let stream = Self {
+            listeners: Arc::new(Mutex::new(listeners)),
+            sinks: Arc::new(Mutex::new(Vec::new())),
+        };
+        (stream, failures)
+    }
+
+    /// Also sends the events from now on to a sink in this process.
+    /// 从现在起还将事件发送给此进程内的一个接收者。
+    pub fn add_sink(&self, sink: Arc<dyn EventSink>) {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            sinks.push(sink);
+        }
+    }
+
+    /// Sends an event to every sink and every plugin still listening / 将事件发送给每个接收者和每个仍在监听的插件
+    pub fn send(&self, event: &RunEvent) {
+        if let Ok(sinks) = self.sinks.lock() {
+            for sink in sinks.iter() {
+                sink.send(event);
+            }
+        }
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let Ok(mut listeners) = self.listeners.lock() else {
+            return;
+        };
+        for listener in listeners.iter_mut() {
+            if let Some(stdin) = &mut listener.stdin
+                && writeln!(stdin, "{}", line)
+                    .and_then(|_| stdin.flush())
+                    .is_err()
+            {
+                listener.stdin = None;
+            }
+        }
+    }
+
+    /// Closes the sinks and the plugins' input and waits for the plugins to exit,
+    /// returning the names of those that exited unsuccessfully.
+    /// 关闭接收者和插件的输入并等待插件退出，返回未成功退出的插件名称。
+    pub fn finish(self) -> Vec<String> {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            for sink in sinks.drain(..) {
+                sink.close();
+            }
+        }
+        let Ok(mut listeners) = self.listeners.lock() else {
+            return Vec::new();
+        };
+        listeners
+            .drain(..)
+            .filter_map(|mut listener| {
+                drop(listener.stdin.take());
+                let success = listener.child.wait().is_ok_and(|status| status.success());
+                (!success).then_some(listener.name)
+            })
+            .collect()
+    }
+}
+
+fn start_listener(name: &str) -> Result<Listener> {
+    let path = find_plugin(name)
+        .with_context(|| format!("No `{}{}` executable found on PATH", PLUGIN_PREFIX, name))?;
+    let mut child = Command::new(&path)
+        .arg(EVENTS_ARG)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start plugin: {}", path.display()))?;
+    let stdin = child.stdin.take();
+    Ok(Listener {
+        name: name.to_string(),
+        child,
+        stdin,
+    })
+}
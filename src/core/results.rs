@@ -0,0 +1,257 @@
+//! # Results Module / 结果模块
+//!
+//! This module reads the results JSON written by a run and merges the reports of several
+//! runs, e.g. the shards of a split CI run, into one. The merge does not depend on the
+//! order of the reports: when the same case appears in several of them, the result kept
+//! is chosen by its outcome alone (see [`merge`]).
+//!
+//! 此模块读取运行写入的结果 JSON，并将多次运行（例如拆分 CI 运行的各个分片）的报告合并为一个。
+//! 合并与报告的顺序无关：当同一用例出现在多个报告中时，仅根据其结果选择保留哪一个（参见 [`merge`]）。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::core::axis;
+use crate::core::context::RunContext;
+use crate::core::models::{RunMetadata, TestResult};
+use crate::core::owners::OwnerFailures;
+use crate::infra::t;
+
+/// The results of a run together with its metadata, as stored in the results JSON.
+/// 一次运行的结果及其元数据，与结果 JSON 中存储的内容一致。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunReport {
+    /// Run-level information / 运行级信息
+    #[serde(default)]
+    pub metadata: RunMetadata,
+    /// The result of every case / 每个用例的结果
+    #[serde(default)]
+    pub results: Vec<TestResult>,
+}
+
+/// Reads a results JSON written by a run.
+/// 读取运行写入的结果 JSON。
+pub fn load_report(path: &Path) -> Result<RunReport> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read results: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse results: {}", path.display()))
+}
+
+/// Merges the reports of several runs into one, independently of their order.
+///
+/// When a case appears in more than one report, the most severe result is kept:
+/// an unexpected failure over an allowed failure, over a pass or build, over a skip,
+/// so a case only counts as passed if it passed everywhere it ran. Among equally severe
+/// results, the longest one is kept. Each such conflict adds a note to the metadata.
+/// A case left out of a run on purpose is only reported as skipped if no report ran it.
+///
+/// The metadata is merged as follows: notes are combined without duplicates, the
+/// earliest captured environment is kept, per-case entries (checksums, coverage, costs,
+/// fingerprints, flakiness, anomalies) come from the report whose result was kept, transient build
+/// retries are summed, owners and axes are derived again from the merged results, and
+/// the retry hint of a single shard is dropped. The configuration snapshot is kept only
+/// when every report carrying one recorded the same matrix.
+///
+/// Whether a failure was allowed is decided on the platform of `context` as of `today`,
+/// and the notes are written in its locale.
+///
+/// 将多次运行的报告合并为一个，与报告的顺序无关。
+///
+/// 当一个用例出现在多个报告中时，保留最严重的结果：意外失败优先于允许的失败，
+/// 允许的失败优先于通过或已构建，通过或已构建优先于跳过；因此只有在所有运行中都通过的用例才算通过。
+/// 严重程度相同时保留耗时最长的结果。每个这样的冲突都会在元数据中添加一条说明。
+/// 被有意排除在运行之外的用例，只有在没有任何报告运行它时才报告为跳过。
+///
+/// 元数据的合并方式：说明去重后合并，保留最早捕获的环境，按用例的条目（校验和、覆盖率、成本、
+/// 指纹、不稳定性、异常）取自其结果被保留的报告，暂时性构建重试次数相加，负责人和维度根据合并后的结果重新计算，
+/// 单个分片的重试提示被丢弃。仅当所有带有配置快照的报告记录的矩阵相同时，才保留该快照。
+///
+/// 失败是否被允许按 `context` 的平台和 `today` 的日期判断，说明使用其语言环境书写。
+pub fn merge(reports: Vec<RunReport>, context: &RunContext, today: NaiveDate) -> RunReport {
+    // For every case, the index of the report whose result is kept and how often it appeared.
+    let mut kept: BTreeMap<String, (usize, TestResult)> = BTreeMap::new();
+    let mut appearances: BTreeMap<String, usize> = BTreeMap::new();
+    let mut skipped = 0;
+    let mut skipped_cases: BTreeMap<String, TestResult> = BTreeMap::new();
+    for (index, report) in reports.iter().enumerate() {
+        for result in &report.results {
+            if let TestResult::Skipped { case, .. } = result {
+                match case {
+                    Some(case) => {
+                        skipped_cases
+                            .entry(case.name.clone())
+                            .or_insert_with(|| result.clone());
+                    }
+                    None => skipped += 1,
+                }
+                continue;
+            }
+            let name = result.case_name().to_string();
+            *appearances.entry(name.clone()).or_default() += 1;
+            match kept.get(&name) {
+                Some((_, current))
+                    if compare_results(result, current, context, today) != Ordering::Greater => {}
+                _ => {
+                    kept.insert(name, (index, result.clone()));
+                }
+            }
+        }
+    }
+
+    let mut metadata = RunMetadata::default();
+    let mut notes = BTreeSet::new();
+    for report in &reports {
+        notes.extend(report.metadata.notes.iter().cloned());
+        for (case, retries) in &report.metadata.transient_build_retries {
+            *metadata
+                .transient_build_retries
+                .entry(case.clone())
+                .or_default() += retries;
+        }
+    }
+    metadata.environment = reports
+        .iter()
+        .filter_map(|report| report.metadata.environment.clone())
+        .min_by_key(|environment| environment.captured_at);
+    let mut configs = reports
+        .iter()
+        .filter_map(|report| report.metadata.config.as_ref());
+    if let Some(first) = configs.next() {
+        let value = serde_json::to_value(first).ok();
+        if configs.all(|config| serde_json::to_value(config).ok() == value) {
+            metadata.config = Some(first.clone());
+        }
+    }
+
+    for (name, (index, result)) in &kept {
+        let source = &reports[*index].metadata;
+        if let Some(checksum) = source.artifact_checksums.get(name) {
+            metadata
+                .artifact_checksums
+                .insert(name.clone(), checksum.clone());
+        }
+        if let Some(coverage) = source.coverage.get(name) {
+            metadata.coverage.insert(name.clone(), *coverage);
+        }
+        if let Some(cost) = source.case_costs.get(name) {
+            metadata.case_costs.insert(name.clone(), *cost);
+        }
+        metadata
+            .anomalies
+            .extend(source.anomalies.iter().filter(|a| &a.case == name).cloned());
+        metadata.flaky_cases.extend(
+            source
+                .flaky_cases
+                .iter()
+                .filter(|f| &f.case == name)
+                .cloned(),
+        );
+        if result.is_failure() {
+            metadata.failure_fingerprints.extend(
+                source
+                    .failure_fingerprints
+                    .iter()
+                    .filter(|f| &f.case == name)
+                    .cloned(),
+            );
+        }
+        let count = appearances[name];
+        if count > 1 {
+            notes.insert(
+                t!(
+                    "results.merge_conflict",
+                    locale = &context.locale,
+                    name = name,
+                    count = count,
+                    status = result.status_key()
+                )
+                .to_string(),
+            );
+        }
+    }
+    metadata.notes = notes.into_iter().collect();
+    metadata.flaky_cases.sort_by(|a, b| {
+        b.flaky_score
+            .total_cmp(&a.flaky_score)
+            .then_with(|| a.case.cmp(&b.case))
+    });
+
+    // A case left out of one run but run in another counts as run.
+    skipped_cases.retain(|name, _| !kept.contains_key(name));
+    let results: Vec<TestResult> = kept
+        .into_values()
+        .map(|(_, result)| result)
+        .chain(skipped_cases.into_values())
+        .chain(std::iter::repeat_n(TestResult::skipped(), skipped))
+        .collect();
+    metadata.failure_owners = merge_owners(&reports, &results, context, today);
+    metadata.axes = axis::summarize_by_axis(&results);
+
+    RunReport { metadata, results }
+}
+
+/// Orders two results of the same case by severity, then by duration, then by output,
+/// so the choice never depends on which report came first.
+fn compare_results(
+    a: &TestResult,
+    b: &TestResult,
+    context: &RunContext,
+    today: NaiveDate,
+) -> Ordering {
+    let severity = |result: &TestResult| -> u8 {
+        match result {
+            TestResult::Failed { .. } if result.is_unexpected_failure(context, today) => 3,
+            TestResult::Failed { .. } => 2,
+            TestResult::Passed { .. } | TestResult::Built { .. } => 1,
+            TestResult::Skipped { .. } => 0,
+        }
+    };
+    severity(a)
+        .cmp(&severity(b))
+        .then_with(|| a.get_duration().cmp(&b.get_duration()))
+        .then_with(|| a.get_output().cmp(&b.get_output()))
+}
+
+/// Combines the owner groups of the reports, keeping only the cases that still failed
+/// unexpectedly after the merge. Unowned failures come last, as in a single run.
+fn merge_owners(
+    reports: &[RunReport],
+    results: &[TestResult],
+    context: &RunContext,
+    today: NaiveDate,
+) -> Vec<OwnerFailures> {
+    let failed: BTreeSet<&str> = results
+        .iter()
+        .filter(|result| result.is_unexpected_failure(context, today))
+        .map(TestResult::case_name)
+        .collect();
+    let mut by_owner: BTreeMap<Option<String>, BTreeSet<String>> = BTreeMap::new();
+    for group in reports
+        .iter()
+        .flat_map(|report| &report.metadata.failure_owners)
+    {
+        by_owner.entry(group.owner.clone()).or_default().extend(
+            group
+                .cases
+                .iter()
+                .filter(|case| failed.contains(case.as_str()))
+                .cloned(),
+        );
+    }
+    let unowned = by_owner.remove(&None);
+    by_owner
+        .into_iter()
+        .chain(unowned.map(|cases| (None, cases)))
+        .filter(|(_, cases)| !cases.is_empty())
+        .map(|(owner, cases)| OwnerFailures {
+            owner,
+            cases: cases.into_iter().collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! # Resume Module / 恢复模块
+//!
+//! This module keeps a journal of the cases that finished during a run, one JSON line per
+//! case, written as soon as the case finishes. If the run crashes or is cancelled,
+//! `run --resume` reads the journal back, keeps the cases that already passed and only
+//! re-runs the pending and failed ones. The journal lives in the runner's state directory
+//! unless a state file is given (`run --resume <state-file>`), e.g. one a CI cache keeps
+//! across a job that timed out and its retry.
+//!
+//! 此模块记录运行期间已完成用例的日志，每个用例一行 JSON，在用例完成后立即写入。
+//! 如果运行崩溃或被取消，`run --resume` 会读回该日志，保留已通过的用例，
+//! 只重新运行待处理和失败的用例。除非指定了状态文件（`run --resume <state-file>`），
+//! 例如由 CI 缓存在超时的作业及其重试之间保留的文件，否则日志位于运行器的状态目录中。
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::core::config::TestCase;
+use crate::core::models::TestResult;
+
+/// The file name of the journal inside the runner's state directory.
+/// 运行器状态目录中日志文件的文件名。
+const JOURNAL_FILE: &str = "run_journal.jsonl";
+
+/// An append-only journal of the finished cases of the current run.
+/// 当前运行中已完成用例的只追加日志。
+#[derive(Debug, Clone)]
+pub struct RunJournal {
+    file: Arc<Mutex<File>>,
+}
+
+impl RunJournal {
+    /// Starts a new journal at `path`, replacing any previous one and creating its
+    /// directory if needed. The results kept from a resumed run are written first, so the
+    /// journal stays complete if this run is interrupted too.
+    ///
+    /// 在 `path` 处开始新的日志，替换之前的日志，并在需要时创建其目录。从恢复的运行中保留的结果会先写入，
+    /// 以便在本次运行也被中断时日志仍然完整。
+    pub fn start(path: &Path, kept: &[TestResult]) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create run journal: {}", path.display()))?;
+        let journal = Self {
+            file: Arc::new(Mutex::new(file)),
+        };
+        for result in kept {
+            journal.record(result)?;
+        }
+        Ok(journal)
+    }
+
+    /// Appends a finished case. Skipped results carry no case and are not recorded.
+    /// 追加一个已完成的用例。跳过的结果不携带用例，因此不会被记录。
+    pub fn record(&self, result: &TestResult) -> Result<()> {
+        if matches!(result, TestResult::Skipped { .. }) {
+            return Ok(());
+        }
+        let line = serde_json::to_string(result).context("Failed to serialize result")?;
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Run journal lock poisoned"))?;
+        writeln!(file, "{line}").context("Failed to write run journal")?;
+        file.flush().context("Failed to write run journal")
+    }
+}
+
+/// Reads the journal of the previous run at `path`. Returns `None` if there is none.
+///
+/// A line that cannot be parsed, such as the last one of a run that crashed while
+/// writing it, is ignored.
+///
+/// 读取 `path` 处上一次运行的日志。如果不存在则返回 `None`。
+/// 无法解析的行（例如在写入时崩溃的运行的最后一行）会被忽略。
+pub fn load_journal(path: &Path) -> Option<Vec<TestResult>> {
+    let content = fs::read_to_string(path).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    )
+}
+
+/// Picks the journaled results that can be kept when resuming: the cases that passed
+/// (or were built) and whose definition has not changed since. Other cases run again.
+///
+/// # Arguments
+/// * `journal` - The results read from the journal
+/// * `cases` - The cases planned for this run
+///
+/// # Returns
+/// The results to keep, in journal order
+///
+/// 选出恢复时可以保留的日志结果：已通过（或已构建）且定义自那以后未改变的用例。
+/// 其他用例会重新运行。
+pub fn resumable_results(journal: Vec<TestResult>, cases: &[TestCase]) -> Vec<TestResult> {
+    let mut kept: Vec<TestResult> = Vec::new();
+    for result in journal {
+        let case = match &result {
+            TestResult::Passed { case, .. } | TestResult::Built { case, .. } => case,
+            TestResult::Failed { .. } | TestResult::Skipped { .. } => continue,
+        };
+        let unchanged = cases
+            .iter()
+            .any(|planned| planned.name == case.name && same_definition(planned, case));
+        if unchanged && !kept.iter().any(|k| k.case_name() == case.name) {
+            kept.push(result);
+        }
+    }
+    kept
+}
+
+/// Removes the journal at `path`, e.g. after a run that completed without failures.
+/// 删除 `path` 处的日志，例如在一次没有失败的完整运行之后。
+pub fn clear_journal(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+/// Returns the path of the journal file inside a state directory, used when no state
+/// file is given.
+/// 返回状态目录中日志文件的路径，在未指定状态文件时使用。
+pub fn journal_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(JOURNAL_FILE)
+}
+
+fn same_definition(a: &TestCase, b: &TestCase) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
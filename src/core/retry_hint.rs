@@ -0,0 +1,108 @@
+//! # Retry Hint Module / 重试提示模块
+//!
+//! When a run is one shard of a split CI run (`--total-runners`), this module derives a
+//! reduced matrix holding only the cases that failed unexpectedly, so a follow-up CI job
+//! can retry just those with `--config retry.toml`.
+//!
+//! 当一次运行是拆分 CI 运行（`--total-runners`）中的一个分片时，此模块生成一个仅包含
+//! 意外失败用例的精简矩阵，使后续 CI 作业可以通过 `--config retry.toml` 只重试这些用例。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::config::TestMatrix;
+use crate::core::context::RunContext;
+use crate::core::models::TestResult;
+
+/// The file name of the suggested retry matrix inside the runner's state directory.
+/// 运行器状态目录中建议的重试矩阵的文件名。
+pub const RETRY_CONFIG_FILE: &str = "retry.toml";
+
+/// The cases of a shard worth retrying, as recorded in the results JSON.
+/// 分片中值得重试的用例，记录在结果 JSON 中。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RetryHint {
+    /// The index of the runner that produced the results / 产生结果的运行器索引
+    pub runner_index: usize,
+    /// The total number of runners / 运行器总数
+    pub total_runners: usize,
+    /// The names of the cases that failed unexpectedly / 意外失败的用例名称
+    pub cases: Vec<String>,
+    /// The matrix holding only those cases / 仅包含这些用例的矩阵
+    pub config: PathBuf,
+}
+
+/// Builds the matrix to retry: the settings of the original matrix with only the cases
+/// that failed unexpectedly. `after` hints naming cases outside it are dropped, since
+/// the matrix would otherwise be rejected, and the generator is not run again.
+/// Whether a failure was allowed is decided on the platform of `context` as of `today`.
+///
+/// # Returns
+/// The reduced matrix, or `None` if nothing failed unexpectedly
+///
+/// 构建要重试的矩阵：原矩阵的设置加上仅意外失败的用例。指向矩阵之外用例的 `after`
+/// 提示会被移除，否则该矩阵会被拒绝；生成器也不会再次运行。失败是否被允许按 `context` 的平台和 `today` 的日期判断。
+pub fn retry_matrix(
+    matrix_settings: &TestMatrix,
+    results: &[TestResult],
+    context: &RunContext,
+    today: NaiveDate,
+) -> Option<TestMatrix> {
+    let mut cases: Vec<_> = results
+        .iter()
+        .filter(|result| result.is_unexpected_failure(context, today))
+        .filter_map(|result| match result {
+            TestResult::Failed { case, .. } => Some(case.clone()),
+            _ => None,
+        })
+        .collect();
+    if cases.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = cases.iter().map(|case| case.name.clone()).collect();
+    for case in &mut cases {
+        case.after.retain(|name| names.contains(name));
+    }
+    Some(TestMatrix {
+        cases,
+        // The failed cases already include the generated and expanded ones.
+        generator: None,
+        matrix: None,
+        ..matrix_settings.clone()
+    })
+}
+
+/// Writes the retry matrix into the state directory and returns the hint describing it.
+/// A stale retry matrix from an earlier run is removed if nothing needs a retry.
+///
+/// 将重试矩阵写入状态目录并返回描述它的提示。如果没有需要重试的用例，则删除之前运行留下的重试矩阵。
+pub fn write_retry_config(
+    matrix_settings: &TestMatrix,
+    results: &[TestResult],
+    state_dir: &Path,
+    runner_index: usize,
+    total_runners: usize,
+    context: &RunContext,
+    today: NaiveDate,
+) -> Result<Option<RetryHint>> {
+    let path = state_dir.join(RETRY_CONFIG_FILE);
+    let Some(matrix) = retry_matrix(matrix_settings, results, context, today) else {
+        let _ = fs::remove_file(&path);
+        return Ok(None);
+    };
+    fs::create_dir_all(state_dir)
+        .with_context(|| format!("Failed to create directory: {}", state_dir.display()))?;
+    let content = toml::to_string_pretty(&matrix).context("Failed to serialize retry matrix")?;
+    fs::write(&path, content)
+        .with_context(|| format!("Failed to write retry matrix: {}", path.display()))?;
+    Ok(Some(RetryHint {
+        runner_index,
+        total_runners,
+        cases: matrix.cases.iter().map(|case| case.name.clone()).collect(),
+        config: path,
+    }))
+}
@@ -0,0 +1,249 @@
+//! # Schedule Module / 调度模块
+//!
+//! This module supports `matrix-runner schedule`, which runs a matrix on a cron schedule
+//! for teams without a CI scheduler. It parses the five fields of a cron expression and
+//! computes the next time it matches, and it detects drift: a run whose pass rate is
+//! clearly below the average of the runs before it, read from the archived results.
+//!
+//! 此模块支持 `matrix-runner schedule`，它为没有 CI 调度器的团队按 cron 计划运行矩阵。
+//! 它解析 cron 表达式的五个字段并计算下一次匹配的时间，同时检测漂移：
+//! 即某次运行的通过率明显低于之前几次运行的平均值（从归档的结果中读取）。
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use std::str::FromStr;
+
+use crate::core::models::TestResult;
+
+/// How many days ahead a match is searched for; a day of the month that exists only in
+/// leap years still matches within this range.
+const SEARCH_DAYS: i64 = 366 * 8;
+
+/// A cron expression of five fields: minute, hour, day of the month, month and day of the
+/// week (`0` or `7` is Sunday). Each field is `*`, a value, a range `a-b`, a step `*/n`
+/// or `a-b/n`, or a comma-separated list of these. As in classic cron, a day matches if
+/// either the day of the month or the day of the week matches when both are restricted.
+///
+/// 由五个字段组成的 cron 表达式：分钟、小时、月中的日、月份和星期（`0` 或 `7` 为星期日）。
+/// 每个字段可以是 `*`、一个值、范围 `a-b`、步长 `*/n` 或 `a-b/n`，或者由它们组成的逗号分隔列表。
+/// 与经典 cron 一样，当月中的日和星期都受限时，只要其中之一匹配，该日即匹配。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    /// Whether the day of the month field is `*` / 月中的日字段是否为 `*`
+    any_day_of_month: bool,
+    /// Whether the day of the week field is `*` / 星期字段是否为 `*`
+    any_day_of_week: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            bail!(
+                "A cron expression needs 5 fields (minute hour day-of-month month day-of-week), got {}: '{}'",
+                fields.len(),
+                expression
+            );
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7, "day of the week")?;
+        // Both 0 and 7 are Sunday.
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31, "day of the month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+}
+
+impl CronSchedule {
+    /// Checks whether the schedule matches a minute, given as local date and time.
+    /// 检查计划是否匹配某一分钟（以本地日期和时间表示）。
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        self.matches_day(time.date())
+            && self.hours[time.hour() as usize]
+            && self.minutes[time.minute() as usize]
+    }
+
+    /// Computes the first matching minute after `after`.
+    ///
+    /// # Returns
+    /// The time of the next run, or `None` if the schedule never matches (e.g. `0 0 31 2 *`)
+    ///
+    /// 计算 `after` 之后第一个匹配的分钟。返回下一次运行的时间；如果计划永远不会匹配
+    /// （例如 `0 0 31 2 *`），则返回 `None`。
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_day = start.date() + Duration::days(SEARCH_DAYS);
+        let mut date = start.date();
+        while date <= last_day {
+            if self.matches_day(date) {
+                let first_minute = if date == start.date() {
+                    start.time()
+                } else {
+                    Default::default()
+                };
+                for hour in first_minute.hour()..24 {
+                    if !self.hours[hour as usize] {
+                        continue;
+                    }
+                    let from = if hour == first_minute.hour() {
+                        first_minute.minute()
+                    } else {
+                        0
+                    };
+                    for minute in from..60 {
+                        if !self.minutes[minute as usize] {
+                            continue;
+                        }
+                        let time = date.and_hms_opt(hour, minute, 0)?;
+                        // A time skipped by a daylight saving change does not exist locally.
+                        if let Some(time) = timezone.from_local_datetime(&time).earliest() {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !self.months[date.month() as usize - 1] {
+            return false;
+        }
+        let day_of_month = self.days_of_month[date.day() as usize - 1];
+        let day_of_week = self.days_of_week[date.weekday().num_days_from_sunday() as usize];
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (false, true) => day_of_month,
+            (true, false) => day_of_week,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+/// Parses one field into a table of the values `min..=max` it matches, indexed from `min`.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<Vec<bool>> {
+    let mut matched = vec![false; (max - min + 1) as usize];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .with_context(|| format!("Invalid step in the {name} field: '{part}'"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (
+                parse_value(first, min, max, name)?,
+                parse_value(last, min, max, name)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, name)?;
+            // `a/n` runs from `a` to the end of the range, as in most cron implementations.
+            (value, if step > 1 { max } else { value })
+        };
+        if first > last {
+            bail!("Invalid range in the {name} field: '{part}'");
+        }
+        for value in (first..=last).step_by(step as usize) {
+            matched[(value - min) as usize] = true;
+        }
+    }
+    Ok(matched)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, name: &str) -> Result<u32> {
+    value
+        .parse()
+        .ok()
+        .filter(|value| (min..=max).contains(value))
+        .with_context(|| {
+            format!("Invalid value in the {name} field: '{value}' (expected {min}-{max})")
+        })
+}
+
+/// The share of the cases of a run that passed or were built, in percent. Skipped cases
+/// are left out, so a cancelled run does not count as a drop.
+///
+/// # Returns
+/// The pass rate, or `None` if no case ran
+///
+/// 一次运行中通过或已构建的用例所占的百分比。跳过的用例不计入，因此被取消的运行不会被视为下降。
+/// 返回通过率；如果没有用例运行，则返回 `None`。
+pub fn pass_rate(results: &[TestResult]) -> Option<f64> {
+    let ran = results
+        .iter()
+        .filter(|r| !matches!(r, TestResult::Skipped { .. }))
+        .count();
+    if ran == 0 {
+        return None;
+    }
+    let passed = results
+        .iter()
+        .filter(|r| matches!(r, TestResult::Passed { .. } | TestResult::Built { .. }))
+        .count();
+    Some(passed as f64 * 100.0 / ran as f64)
+}
+
+/// A drop of the pass rate against the runs before.
+/// 通过率相对于之前运行的下降。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// The pass rate of the latest run, in percent / 最近一次运行的通过率（百分比）
+    pub pass_rate: f64,
+    /// The average pass rate of the runs before it, in percent / 之前运行的平均通过率（百分比）
+    pub trailing_average: f64,
+    /// The number of runs averaged / 参与平均的运行数量
+    pub runs: usize,
+}
+
+/// Compares the latest pass rate with the average of the runs before it.
+///
+/// # Arguments
+/// * `rates` - Pass rates in percent, most recent first; the first one is the latest run
+/// * `window` - How many of the runs before the latest one are averaged
+/// * `threshold` - How many percentage points below the average count as a drop
+///
+/// # Returns
+/// The drift, or `None` if the rate did not drop by more than `threshold` or there is no
+/// earlier run to compare with
+///
+/// 将最近的通过率与之前运行的平均值进行比较。如果通过率的下降未超过 `threshold`，
+/// 或者没有可比较的较早运行，则返回 `None`。
+pub fn detect_drift(rates: &[f64], window: usize, threshold: f64) -> Option<Drift> {
+    let (&pass_rate, earlier) = rates.split_first()?;
+    let earlier = &earlier[..earlier.len().min(window)];
+    if earlier.is_empty() {
+        return None;
+    }
+    let trailing_average = earlier.iter().sum::<f64>() / earlier.len() as f64;
+    (trailing_average - pass_rate > threshold).then_some(Drift {
+        pass_rate,
+        trailing_average,
+        runs: earlier.len(),
+    })
+}
@@ -0,0 +1,414 @@
+//! # Scheduler Module / 调度器模块
+//!
+//! This module decides when a test case may start. Every case needs one of the
+//! `--jobs` slots, and cases in a concurrency group additionally need a slot of
+//! their group, so cases sharing an external resource (database, port, GPU) can
+//! be limited among themselves while the rest of the matrix runs fully parallel.
+//! A case with `after` hints also waits until the cases it names have finished.
+//! With `--smoke-first`, the cases tagged `smoke` run before all others. Under a
+//! resource ceiling (`--max-load`, `max_total_memory_gb`), a case that got its slots
+//! still waits while the machine is busier than the ceiling allows.
+//!
+//! 此模块决定测试用例何时可以开始。每个用例都需要一个 `--jobs` 槽位，
+//! 并发组中的用例还需要该组的一个槽位，从而使共享外部资源（数据库、端口、GPU）
+//! 的用例可以相互限制，而矩阵的其余部分则完全并行运行。
+//! 带有 `after` 提示的用例还会等待其指定的用例完成。
+//! 使用 `--smoke-first` 时，带有 `smoke` 标签的用例先于其他所有用例运行。
+//! 在资源上限（`--max-load`、`max_total_memory_gb`）下，已获得槽位的用例在机器繁忙程度超过上限时仍会等待。
+
+use colored::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore, watch};
+
+use crate::core::config::{TestCase, TestMatrix};
+use crate::infra::system_load::{self, SystemLoad};
+use crate::infra::t;
+
+/// The tag marking the cases run first with `--smoke-first` / 使用 `--smoke-first` 时先运行的用例的标签
+pub const SMOKE_TAG: &str = "smoke";
+
+/// Checks whether a case belongs to the smoke subset / 检查用例是否属于冒烟子集
+pub fn is_smoke_case(case: &TestCase) -> bool {
+    case.tags.iter().any(|tag| tag == SMOKE_TAG)
+}
+
+/// How often the system's load is sampled while a case waits for the resource ceiling.
+/// 用例等待资源上限时对系统负载采样的频率。
+pub const RESOURCE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The ceiling on the system's load under which new cases are started.
+/// 启动新用例时系统负载的上限。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceCeiling {
+    /// The highest one-minute load average (`--max-load`) / 最高的一分钟平均负载（`--max-load`）
+    pub max_load: Option<f64>,
+    /// The most memory in use by the whole system, in GiB (`max_total_memory_gb`) / 整个系统使用的最多内存，单位 GiB（`max_total_memory_gb`）
+    pub max_total_memory_gb: Option<f64>,
+}
+
+impl ResourceCeiling {
+    /// Whether no ceiling is set / 是否未设置任何上限
+    pub fn is_empty(&self) -> bool {
+        self.max_load.is_none() && self.max_total_memory_gb.is_none()
+    }
+
+    /// Describes the part of the ceiling a sample exceeds, or `None` if it is within the
+    /// ceiling, in the given locale. Values that could not be measured never exceed it.
+    /// 以给定的语言环境描述采样超出上限的部分；若在上限之内则返回 `None`。无法测量的值永远不会超出上限。
+    pub fn exceeded_by(&self, sample: &SystemLoad, locale: &str) -> Option<String> {
+        if let (Some(max), Some(load)) = (self.max_load, sample.load)
+            && load > max
+        {
+            return Some(
+                t!(
+                    "scheduler.load_exceeded",
+                    locale = locale,
+                    load = format!("{load:.2}"),
+                    max = max
+                )
+                .to_string(),
+            );
+        }
+        if let (Some(max), Some(used)) = (self.max_total_memory_gb, sample.used_memory_gb)
+            && used > max
+        {
+            return Some(
+                t!(
+                    "scheduler.memory_exceeded",
+                    locale = locale,
+                    used = format!("{used:.1}"),
+                    max = max
+                )
+                .to_string(),
+            );
+        }
+        None
+    }
+}
+
+/// Parses a `--max-load` or `--max-total-memory-gb` value, which must be a positive number.
+/// 解析 `--max-load` 或 `--max-total-memory-gb` 的值，它必须是正数。
+pub fn parse_ceiling(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(ceiling) if ceiling.is_finite() && ceiling > 0.0 => Ok(ceiling),
+        _ => Err(format!("expected a positive number, found `{value}`")),
+    }
+}
+
+/// Holds back the start of cases while the system's load is above the ceiling.
+/// 在系统负载高于上限时推迟用例的启动。
+struct ResourceGate {
+    ceiling: ResourceCeiling,
+    sample: Box<dyn Fn() -> SystemLoad + Send + Sync>,
+    poll_interval: Duration,
+    /// The locale of the messages about waiting cases / 关于等待中用例的消息的语言环境
+    locale: String,
+    /// Whether the last case started only after waiting for the ceiling. Cases start one
+    /// at a time, and after such a start the next one first gives the load one poll
+    /// interval to show the case that just started.
+    throttled: Mutex<bool>,
+    /// How many times a case had to wait / 用例不得不等待的次数
+    waits: AtomicUsize,
+}
+
+impl fmt::Debug for ResourceGate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceGate")
+            .field("ceiling", &self.ceiling)
+            .field("poll_interval", &self.poll_interval)
+            .field("locale", &self.locale)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResourceGate {
+    async fn wait(&self, case_name: &str) {
+        let mut throttled = self.throttled.lock().await;
+        if *throttled {
+            tokio::time::sleep(self.poll_interval).await;
+        }
+        let mut waited = false;
+        while let Some(exceeded) = self.ceiling.exceeded_by(&(self.sample)(), &self.locale) {
+            if !waited {
+                self.waits.fetch_add(1, Ordering::Relaxed);
+                println!(
+                    "{}",
+                    t!(
+                        "scheduler.waiting_for_resources",
+                        locale = &self.locale,
+                        name = case_name,
+                        reason = exceeded
+                    )
+                    .yellow()
+                );
+            }
+            waited = true;
+            tokio::time::sleep(self.poll_interval).await;
+        }
+        *throttled = waited;
+    }
+}
+
+/// Hands out execution slots to test cases.
+/// 向测试用例分发执行槽位。
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// Global slots, one per parallel job / 全局槽位，每个并行任务一个
+    jobs: Arc<Semaphore>,
+    /// Slots for each concurrency group referenced by a case / 每个被用例引用的并发组的槽位
+    groups: HashMap<String, Arc<Semaphore>>,
+    /// Completion signals of the cases named in another case's `after` / 在其他用例的 `after` 中被指定的用例的完成信号
+    finished: HashMap<String, Arc<watch::Sender<bool>>>,
+    /// The smoke phase the other cases wait for, with `--smoke-first` / 使用 `--smoke-first` 时其他用例等待的冒烟阶段
+    smoke: Option<Arc<SmokePhase>>,
+    /// The resource ceiling cases wait for before they start / 用例启动前等待的资源上限
+    resources: Option<Arc<ResourceGate>>,
+}
+
+/// The smoke cases of a run, which run before all other cases.
+/// 本次运行的冒烟用例，它们先于其他所有用例运行。
+#[derive(Debug)]
+struct SmokePhase {
+    cases: HashSet<String>,
+    remaining: watch::Sender<usize>,
+    stop_on_failure: bool,
+    failed: AtomicBool,
+}
+
+/// Signals that a case has finished when it is dropped, however the case ended.
+/// 在被丢弃时发出用例已完成的信号，无论用例以何种方式结束。
+#[derive(Debug)]
+pub struct CompletionGuard {
+    signal: Option<Arc<watch::Sender<bool>>>,
+    smoke: Option<Arc<SmokePhase>>,
+}
+
+impl Drop for CompletionGuard {
+    fn drop(&mut self) {
+        if let Some(signal) = &self.signal {
+            signal.send_replace(true);
+        }
+        if let Some(smoke) = &self.smoke {
+            smoke
+                .remaining
+                .send_modify(|remaining| *remaining = remaining.saturating_sub(1));
+        }
+    }
+}
+
+/// The slots held by a running case. They are released when this is dropped.
+/// 运行中的用例持有的槽位。在此值被丢弃时释放。
+#[derive(Debug)]
+pub struct SlotPermit {
+    _group: Option<OwnedSemaphorePermit>,
+    _job: OwnedSemaphorePermit,
+}
+
+impl Scheduler {
+    /// Creates a scheduler with `jobs` global slots and one semaphore for every
+    /// concurrency group referenced by the matrix's cases.
+    /// 创建一个具有 `jobs` 个全局槽位的调度器，并为矩阵用例引用的每个并发组创建一个信号量。
+    pub fn new(jobs: usize, test_matrix: &TestMatrix) -> Self {
+        let groups = test_matrix
+            .cases
+            .iter()
+            .filter_map(|case| case.concurrency_group.as_deref())
+            .map(|group| {
+                let limit = test_matrix.group_limit(group);
+                (group.to_string(), Arc::new(Semaphore::new(limit)))
+            })
+            .collect();
+
+        Self {
+            jobs: Arc::new(Semaphore::new(jobs.max(1))),
+            groups,
+            finished: HashMap::new(),
+            smoke: None,
+            resources: None,
+        }
+    }
+
+    /// Starts cases only while the system's load is within `ceiling`, sampled every
+    /// [`RESOURCE_POLL_INTERVAL`]. Has no effect if the ceiling is empty. Cases that wait
+    /// say so in `locale`.
+    /// 仅在系统负载处于 `ceiling` 之内时启动用例，每隔 [`RESOURCE_POLL_INTERVAL`] 采样一次。
+    /// 如果上限为空则无效。等待的用例以 `locale` 输出提示。
+    pub fn with_resource_ceiling(self, ceiling: ResourceCeiling, locale: &str) -> Self {
+        self.with_resource_sampler(ceiling, RESOURCE_POLL_INTERVAL, system_load::sample, locale)
+    }
+
+    /// Like [`Scheduler::with_resource_ceiling`], with the load taken from `sample`
+    /// every `poll_interval`, e.g. to follow the load of a remote machine.
+    /// 与 [`Scheduler::with_resource_ceiling`] 相同，但每隔 `poll_interval` 从 `sample` 获取负载，
+    /// 例如用于跟踪远程机器的负载。
+    pub fn with_resource_sampler(
+        mut self,
+        ceiling: ResourceCeiling,
+        poll_interval: Duration,
+        sample: impl Fn() -> SystemLoad + Send + Sync + 'static,
+        locale: &str,
+    ) -> Self {
+        self.resources = (!ceiling.is_empty()).then(|| {
+            Arc::new(ResourceGate {
+                ceiling,
+                sample: Box::new(sample),
+                poll_interval,
+                locale: locale.to_string(),
+                throttled: Mutex::new(false),
+                waits: AtomicUsize::new(0),
+            })
+        });
+        self
+    }
+
+    /// How many times a case waited for the resource ceiling before it started.
+    /// 用例在启动前等待资源上限的次数。
+    pub fn resource_waits(&self) -> usize {
+        self.resources
+            .as_ref()
+            .map_or(0, |resources| resources.waits.load(Ordering::Relaxed))
+    }
+
+    /// Enables the `after` hints among the cases of this run. Hints naming cases that do
+    /// not run are ignored, so a case never waits for a case that will not finish.
+    /// 启用本次运行用例之间的 `after` 提示。指向未运行用例的提示会被忽略，
+    /// 因此用例永远不会等待一个不会完成的用例。
+    pub fn with_order(mut self, cases: &[TestCase]) -> Self {
+        self.finished = cases
+            .iter()
+            .filter(|case| cases.iter().any(|other| other.after.contains(&case.name)))
+            .map(|case| (case.name.clone(), Arc::new(watch::Sender::new(false))))
+            .collect();
+        self
+    }
+
+    /// Returns the guard that marks a case as finished for the cases ordered after it.
+    /// Hold it for as long as the case runs.
+    /// 返回一个守卫，用于为排在此用例之后的用例标记其已完成。在用例运行期间应持有它。
+    pub fn completion_guard(&self, case_name: &str) -> CompletionGuard {
+        CompletionGuard {
+            signal: self.finished.get(case_name).cloned(),
+            smoke: self.smoke_phase_of(case_name).cloned(),
+        }
+    }
+
+    /// Runs the cases tagged `smoke` first: every other case waits until all of them have
+    /// finished, so they get the full parallelism. Has no effect if no case is tagged.
+    ///
+    /// # Arguments
+    /// * `cases` - The cases of this run
+    /// * `stop_on_failure` - Skip the other cases if a smoke case fails unexpectedly
+    ///
+    /// 先运行带有 `smoke` 标签的用例：其他每个用例都会等待它们全部完成，使它们获得全部并行度。
+    /// 如果没有用例带有该标签则无效。
+    pub fn with_smoke_first(mut self, cases: &[TestCase], stop_on_failure: bool) -> Self {
+        let smoke_cases: HashSet<String> = cases
+            .iter()
+            .filter(|case| is_smoke_case(case))
+            .map(|case| case.name.clone())
+            .collect();
+        self.smoke = (!smoke_cases.is_empty()).then(|| {
+            let count = cases.iter().filter(|case| is_smoke_case(case)).count();
+            Arc::new(SmokePhase {
+                cases: smoke_cases,
+                remaining: watch::Sender::new(count),
+                stop_on_failure,
+                failed: AtomicBool::new(false),
+            })
+        });
+        self
+    }
+
+    /// Records an unexpected failure of a case; only failures of smoke cases matter here.
+    /// Call it before the case's completion guard is dropped.
+    /// 记录用例的一次意外失败；此处只关心冒烟用例的失败。应在用例的完成守卫被丢弃之前调用。
+    pub fn record_failure(&self, case_name: &str) {
+        if let Some(smoke) = self.smoke_phase_of(case_name) {
+            smoke.failed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether a smoke case failed unexpectedly / 是否有冒烟用例意外失败
+    pub fn smoke_failed(&self) -> bool {
+        self.smoke
+            .as_ref()
+            .is_some_and(|smoke| smoke.failed.load(Ordering::SeqCst))
+    }
+
+    /// Whether a case that may start now should be skipped instead, because a smoke case
+    /// failed and the run stops after a failed smoke phase.
+    /// 可以开始的用例是否应改为跳过，因为有冒烟用例失败且运行在冒烟阶段失败后停止。
+    pub fn skips_after_smoke(&self, case: &TestCase) -> bool {
+        self.smoke.as_ref().is_some_and(|smoke| {
+            smoke.stop_on_failure
+                && !smoke.cases.contains(&case.name)
+                && smoke.failed.load(Ordering::SeqCst)
+        })
+    }
+
+    fn smoke_phase_of(&self, case_name: &str) -> Option<&Arc<SmokePhase>> {
+        self.smoke
+            .as_ref()
+            .filter(|smoke| smoke.cases.contains(case_name))
+    }
+
+    /// Waits until the case may start. The cases it is ordered after must have finished,
+    /// then the group slot is acquired first so that a case blocked on its group does not
+    /// hold one of the global job slots while waiting. During the smoke phase, the other
+    /// cases wait for all smoke cases to finish. Last, under a resource ceiling, cases
+    /// holding their slots start one at a time while the load is within the ceiling.
+    /// 等待直到用例可以开始。其 `after` 中的用例必须已完成，然后首先获取组槽位，
+    /// 这样因组而阻塞的用例在等待时不会占用全局任务槽位。
+    /// 在冒烟阶段，其他用例等待所有冒烟用例完成。最后，在资源上限下，持有槽位的用例在负载处于上限之内时逐个启动。
+    pub async fn acquire(&self, case: &TestCase) -> SlotPermit {
+        let smoke = self.smoke.as_deref();
+        let is_smoke = self.smoke_phase_of(&case.name).is_some();
+        for name in &case.after {
+            // A smoke case never waits for a case that itself waits for the smoke phase.
+            if is_smoke && self.smoke_phase_of(name).is_none() {
+                continue;
+            }
+            if let Some(signal) = self.finished.get(name) {
+                let _ = signal.subscribe().wait_for(|finished| *finished).await;
+            }
+        }
+        if let Some(smoke) = smoke
+            && !is_smoke
+        {
+            let _ = smoke
+                .remaining
+                .subscribe()
+                .wait_for(|remaining| *remaining == 0)
+                .await;
+        }
+        let group = match case
+            .concurrency_group
+            .as_deref()
+            .and_then(|group| self.groups.get(group))
+        {
+            Some(semaphore) => Some(
+                Arc::clone(semaphore)
+                    .acquire_owned()
+                    .await
+                    .expect("scheduler semaphores are never closed"),
+            ),
+            None => None,
+        };
+        let job = Arc::clone(&self.jobs)
+            .acquire_owned()
+            .await
+            .expect("scheduler semaphores are never closed");
+        if let Some(resources) = &self.resources {
+            resources.wait(&case.name).await;
+        }
+
+        SlotPermit {
+            _group: group,
+            _job: job,
+        }
+    }
+}
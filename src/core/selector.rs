@@ -0,0 +1,432 @@
+//! # Case Selector Module / 用例选择器模块
+//!
+//! This module implements the small expression language behind `run --select`.
+//! Expressions are evaluated against each case's metadata and the results of the
+//! previous run, for example `status(last_run) == failed || tag == "fast"`.
+//!
+//! 此模块实现了 `run --select` 背后的小型表达式语言。
+//! 表达式会针对每个用例的元数据和上一次运行的结果进行求值，
+//! 例如 `status(last_run) == failed || tag == "fast"`。
+//!
+//! ## Grammar / 语法
+//!
+//! ```text
+//! expr       := and ( "||" and )*
+//! and        := unary ( "&&" unary )*
+//! unary      := "!" unary | "(" expr ")" | comparison
+//! comparison := field ( "==" | "!=" ) value
+//! field      := "name" | "tag" | "feature" | "kind" | "status" "(" "last_run" ")"
+//! value      := identifier | "quoted string"
+//! ```
+//!
+//! List-valued fields (`tag`, `feature`) match with `==` if any element is equal.
+//! `kind` is `command` for custom commands. Other cases have two kinds, `cargo` and
+//! their configured `kind`: `test`, `tarpaulin`, `doc` or `fuzz-smoke`.
+//! `status(last_run)` is one of `passed`, `failed`, `timeout`, `built`, `skipped` or `unknown`.
+//!
+//! 列表类型的字段（`tag`、`feature`）在任一元素相等时与 `==` 匹配。
+//! `kind` 对自定义命令为 `command`。其他用例有两个类型：`cargo` 和其配置的 `kind`：
+//! `test`、`tarpaulin`、`doc` 或 `fuzz-smoke`。
+//! `status(last_run)` 的取值为 `passed`、`failed`、`timeout`、`built`、`skipped` 或 `unknown`。
+
+use std::fmt;
+
+use crate::core::config::{TestCase, unqualified_name};
+use crate::core::history::RunHistory;
+
+/// An error produced while parsing a selector expression.
+/// It points at the offending character so the message can show a caret under it.
+///
+/// 解析选择器表达式时产生的错误。
+/// 它指向出错的字符，以便消息可以在其下方显示插入符号。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong / 出错的原因
+    pub message: String,
+    /// The character offset in the expression where the error was detected / 检测到错误的字符偏移量
+    pub position: usize,
+    /// The full expression being parsed / 正在解析的完整表达式
+    pub input: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (at column {})", self.message, self.position + 1)?;
+        writeln!(f, "  {}", self.input)?;
+        write!(f, "  {}^", " ".repeat(self.position))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A case attribute that can be compared in an expression.
+/// 可在表达式中比较的用例属性。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Tag,
+    Feature,
+    Kind,
+    LastRunStatus,
+}
+
+/// The parsed form of a selector expression.
+/// 选择器表达式的解析形式。
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        field: Field,
+        negated: bool,
+        value: String,
+    },
+}
+
+/// A compiled `--select` expression.
+/// 已编译的 `--select` 表达式。
+#[derive(Debug, Clone)]
+pub struct Selector {
+    expr: Expr,
+    source: String,
+}
+
+impl Selector {
+    /// Parses a selector expression.
+    /// 解析选择器表达式。
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            input,
+            tokens,
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            (Token::End, _) => Ok(Self {
+                expr,
+                source: input.to_string(),
+            }),
+            (token, position) => Err(parser.error(
+                format!(
+                    "unexpected {} after the end of the expression",
+                    token.describe()
+                ),
+                position,
+            )),
+        }
+    }
+
+    /// Checks whether a case is selected by this expression.
+    /// 检查用例是否被此表达式选中。
+    pub fn matches(&self, case: &TestCase, history: &RunHistory) -> bool {
+        eval(&self.expr, case, history)
+    }
+
+    /// The expression as it was written.
+    /// 按原样书写的表达式。
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+fn eval(expr: &Expr, case: &TestCase, history: &RunHistory) -> bool {
+    match expr {
+        Expr::Or(lhs, rhs) => eval(lhs, case, history) || eval(rhs, case, history),
+        Expr::And(lhs, rhs) => eval(lhs, case, history) && eval(rhs, case, history),
+        Expr::Not(inner) => !eval(inner, case, history),
+        Expr::Compare {
+            field,
+            negated,
+            value,
+        } => {
+            let found = field_values(*field, case, history)
+                .iter()
+                .any(|v| v == value);
+            found != *negated
+        }
+    }
+}
+
+fn field_values(field: Field, case: &TestCase, history: &RunHistory) -> Vec<String> {
+    match field {
+        // A case named `pkg::case` also answers to its bare name.
+        Field::Name => {
+            let mut names = vec![case.name.clone()];
+            let bare = unqualified_name(&case.name);
+            if bare != case.name {
+                names.push(bare.to_string());
+            }
+            names
+        }
+        Field::Tag => case.tags.clone(),
+        Field::Feature => case
+            .features
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(String::from)
+            .collect(),
+        // A custom command replaces whatever its kind would run.
+        Field::Kind if case.command.is_some() => vec!["command".to_string()],
+        Field::Kind => vec!["cargo".to_string(), case.kind.name().to_string()],
+        Field::LastRunStatus => vec![history.status_of(case).unwrap_or("unknown").to_string()],
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    LParen,
+    RParen,
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(name) => format!("identifier `{name}`"),
+            Token::Str(value) => format!("string \"{value}\""),
+            Token::LParen => "`(`".to_string(),
+            Token::RParen => "`)`".to_string(),
+            Token::Eq => "`==`".to_string(),
+            Token::Ne => "`!=`".to_string(),
+            Token::And => "`&&`".to_string(),
+            Token::Or => "`||`".to_string(),
+            Token::Not => "`!`".to_string(),
+            Token::End => "end of expression".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let error = |message: String, position: usize| ParseError {
+        message,
+        position,
+        input: input.to_string(),
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => tokens.push((Token::LParen, start)),
+            ')' => tokens.push((Token::RParen, start)),
+            '=' | '!' | '&' | '|' => {
+                let next = chars.get(i + 1).copied();
+                let token = match (c, next) {
+                    ('=', Some('=')) => Token::Eq,
+                    ('!', Some('=')) => Token::Ne,
+                    ('&', Some('&')) => Token::And,
+                    ('|', Some('|')) => Token::Or,
+                    ('!', _) => Token::Not,
+                    ('=', _) => {
+                        return Err(error("expected `==`, found a single `=`".into(), start));
+                    }
+                    _ => {
+                        return Err(error(
+                            format!("expected `{c}{c}`, found a single `{c}`"),
+                            start,
+                        ));
+                    }
+                };
+                if token != Token::Not {
+                    i += 1;
+                }
+                tokens.push((token, start));
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some(&ch) if ch == quote => break,
+                        Some(&ch) => value.push(ch),
+                        None => return Err(error("unterminated string literal".into(), start)),
+                    }
+                    i += 1;
+                }
+                tokens.push((Token::Str(value), start));
+            }
+            c if is_ident_char(c) => {
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                let ident: String = chars[start..i].iter().collect();
+                tokens.push((Token::Ident(ident), start));
+                continue;
+            }
+            other => return Err(error(format!("unexpected character `{other}`"), start)),
+        }
+        i += 1;
+    }
+
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/' | '+' | '*')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> (Token, usize) {
+        self.tokens[self.pos].clone()
+    }
+
+    fn advance(&mut self) -> (Token, usize) {
+        let token = self.peek();
+        if token.0 != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: String, position: usize) -> ParseError {
+        ParseError {
+            message,
+            position,
+            input: self.input.to_string(),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        let (token, position) = self.advance();
+        if token == expected {
+            Ok(())
+        } else {
+            Err(self.error(
+                format!(
+                    "expected {}, found {}",
+                    expected.describe(),
+                    token.describe()
+                ),
+                position,
+            ))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_and()?;
+        while self.peek().0 == Token::Or {
+            self.advance();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_unary()?;
+        while self.peek().0 == Token::And {
+            self.advance();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek().0 {
+            Token::Not => {
+                self.advance();
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Token::LParen => {
+                self.advance();
+                let expr = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ParseError> {
+        let field = self.parse_field()?;
+        let negated = match self.advance() {
+            (Token::Eq, _) => false,
+            (Token::Ne, _) => true,
+            (token, position) => {
+                return Err(self.error(
+                    format!("expected `==` or `!=`, found {}", token.describe()),
+                    position,
+                ));
+            }
+        };
+        let value = match self.advance() {
+            (Token::Ident(value), _) | (Token::Str(value), _) => value,
+            (token, position) => {
+                return Err(self.error(
+                    format!(
+                        "expected a value to compare against, found {}",
+                        token.describe()
+                    ),
+                    position,
+                ));
+            }
+        };
+        Ok(Expr::Compare {
+            field,
+            negated,
+            value,
+        })
+    }
+
+    fn parse_field(&mut self) -> Result<Field, ParseError> {
+        let (token, position) = self.advance();
+        let Token::Ident(name) = token else {
+            return Err(self.error(
+                format!("expected a field name, found {}", token.describe()),
+                position,
+            ));
+        };
+        match name.as_str() {
+            "name" => Ok(Field::Name),
+            "tag" | "tags" => Ok(Field::Tag),
+            "feature" | "features" => Ok(Field::Feature),
+            "kind" => Ok(Field::Kind),
+            "status" => {
+                self.expect(Token::LParen)?;
+                match self.advance() {
+                    (Token::Ident(arg), _) if arg == "last_run" => {}
+                    (token, position) => {
+                        return Err(self.error(
+                            format!("expected `last_run`, found {}", token.describe()),
+                            position,
+                        ));
+                    }
+                }
+                self.expect(Token::RParen)?;
+                Ok(Field::LastRunStatus)
+            }
+            other => Err(self.error(
+                format!(
+                    "unknown field `{other}` (expected one of: name, tag, feature, kind, status(last_run))"
+                ),
+                position,
+            )),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! # Settings Module / 设置模块
+//!
+//! This module handles `.matrixrunner.toml`, an optional file in the project directory
+//! that controls how the tool behaves (default job count, report paths, colors, locale,
+//! state directory). It is kept separate from the test matrix so that per-developer
+//! preferences don't end up in the shared matrix definition.
+//!
+//! Every value is resolved with the precedence: command line > settings file > built-in default.
+//!
+//! 此模块处理 `.matrixrunner.toml`，这是项目目录中的一个可选文件，
+//! 用于控制工具的行为（默认任务数、报告路径、颜色、语言、状态目录）。
+//! 它与测试矩阵分开，使个人偏好不会混入共享的矩阵定义中。
+//!
+//! 每个值的解析优先级为：命令行 > 设置文件 > 内置默认值。
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::history::Retention;
+
+/// The file name of the settings file inside the project directory.
+/// 项目目录中设置文件的文件名。
+pub const SETTINGS_FILE: &str = ".matrixrunner.toml";
+
+/// When to use colored console output.
+/// 何时使用彩色控制台输出。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Detect from the terminal and environment / 根据终端和环境自动检测
+    #[default]
+    Auto,
+    /// Always emit colors / 始终输出颜色
+    Always,
+    /// Never emit colors / 从不输出颜色
+    Never,
+}
+
+/// Tool settings loaded from `.matrixrunner.toml`. Every field is optional.
+/// 从 `.matrixrunner.toml` 加载的工具设置。所有字段均为可选。
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Default number of parallel jobs / 默认的并行任务数量
+    pub jobs: Option<usize>,
+    /// Default path for the HTML report / HTML 报告的默认路径
+    pub html: Option<PathBuf>,
+    /// Default path for a copy of the JSON results / JSON 结果副本的默认路径
+    pub json: Option<PathBuf>,
+    /// Default path for the JUnit XML report / JUnit XML 报告的默认路径
+    pub junit: Option<PathBuf>,
+    /// Console color mode / 控制台颜色模式
+    pub color: Option<ColorMode>,
+    /// Default language code (e.g., "en", "zh-CN") / 默认语言代码（例如 "en", "zh-CN"）
+    pub language: Option<String>,
+    /// Directory for the runner's own state (attachments, run history) / 运行器自身状态（附件、运行历史）的目录
+    pub cache_dir: Option<PathBuf>,
+    /// Plugins receiving the events of every run (`matrix-runner-<name>` on PATH) / 接收每次运行事件的插件（PATH 上的 `matrix-runner-<name>`）
+    pub plugins: Vec<String>,
+    /// How many archived run results to keep; `0` keeps any number / 保留多少份已归档的运行结果；`0` 表示不限数量
+    pub history_keep_runs: Option<usize>,
+    /// How many days to keep archived run results; `0` keeps them forever / 已归档运行结果的保留天数；`0` 表示永久保留
+    pub history_keep_days: Option<u32>,
+}
+
+/// The number of archived run results kept by default / 默认保留的已归档运行结果数量
+pub const DEFAULT_HISTORY_KEEP_RUNS: usize = 100;
+
+/// The number of days archived run results are kept by default / 已归档运行结果的默认保留天数
+pub const DEFAULT_HISTORY_KEEP_DAYS: u32 = 90;
+
+impl Settings {
+    /// Resolves the number of parallel jobs.
+    /// 解析并行任务数量。
+    pub fn jobs(&self, cli: Option<usize>) -> usize {
+        cli.or(self.jobs)
+            .unwrap_or_else(|| num_cpus::get() / 2 + 1)
+            .max(1)
+    }
+
+    /// Resolves the HTML report path. Relative paths from the settings file are
+    /// taken relative to the project directory.
+    /// 解析 HTML 报告路径。设置文件中的相对路径相对于项目目录。
+    pub fn html_report(&self, cli: Option<PathBuf>, project_root: &Path) -> Option<PathBuf> {
+        cli.or_else(|| self.html.as_ref().map(|p| project_root.join(p)))
+    }
+
+    /// Resolves the path of the JSON results copy, like [`Settings::html_report`].
+    /// 解析 JSON 结果副本的路径，与 [`Settings::html_report`] 相同。
+    pub fn json_report(&self, cli: Option<PathBuf>, project_root: &Path) -> Option<PathBuf> {
+        cli.or_else(|| self.json.as_ref().map(|p| project_root.join(p)))
+    }
+
+    /// Resolves the JUnit XML report path, like [`Settings::html_report`].
+    /// 解析 JUnit XML 报告路径，与 [`Settings::html_report`] 相同。
+    pub fn junit_report(&self, cli: Option<PathBuf>, project_root: &Path) -> Option<PathBuf> {
+        cli.or_else(|| self.junit.as_ref().map(|p| project_root.join(p)))
+    }
+
+    /// Resolves the language override, if any. `None` means the matrix or system language applies.
+    /// 解析语言覆盖（如果有）。`None` 表示使用矩阵或系统语言。
+    pub fn language(&self, cli: Option<String>) -> Option<String> {
+        cli.or_else(|| self.language.clone())
+    }
+
+    /// Resolves the runner's state directory.
+    /// 解析运行器的状态目录。
+    pub fn state_dir(&self, project_root: &Path) -> PathBuf {
+        match &self.cache_dir {
+            Some(dir) => project_root.join(dir),
+            None => crate::infra::fs::state_dir(project_root),
+        }
+    }
+
+    /// Resolves the retention policy of the archived run results.
+    /// 解析已归档运行结果的保留策略。
+    pub fn history_retention(&self) -> Retention {
+        Retention {
+            keep_runs: self.history_keep_runs.unwrap_or(DEFAULT_HISTORY_KEEP_RUNS),
+            keep_days: self.history_keep_days.unwrap_or(DEFAULT_HISTORY_KEEP_DAYS),
+        }
+    }
+
+    /// Applies the color mode to the console output.
+    /// 将颜色模式应用于控制台输出。
+    pub fn apply_color(&self) {
+        match self.color.unwrap_or_default() {
+            ColorMode::Auto => colored::control::unset_override(),
+            ColorMode::Always => colored::control::set_override(true),
+            ColorMode::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+/// Loads the settings file from a project directory. A missing file yields the defaults.
+///
+/// # Arguments
+/// * `project_dir` - Path to the project directory
+///
+/// # Returns
+/// The parsed settings, or an error if the file exists but cannot be read or parsed
+///
+/// 从项目目录加载设置文件。文件缺失时返回默认值。
+pub fn load_settings(project_dir: &Path) -> Result<Settings> {
+    let path = project_dir.join(SETTINGS_FILE);
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read settings file: {}", path.display()))?;
+    toml::from_str(&content)
+        .with_context(|| format!("Failed to parse settings file: {}", path.display()))
+}
@@ -0,0 +1,201 @@
+//! # Run Status Module / 运行状态模块
+//!
+//! This module keeps a small status file in the state directory up to date while a run
+//! is in progress: the runner's process id, the cases running right now and how many
+//! have finished. `matrix-runner status` reads it from another shell to show the
+//! progress and an estimate of the remaining time of a long run.
+//!
+//! 此模块在运行进行期间持续更新状态目录中的一个小型状态文件：运行器的进程 ID、
+//! 当前正在运行的用例以及已完成的数量。`matrix-runner status` 可在另一个 shell 中读取它，
+//! 以显示长时间运行的进度和剩余时间的估计。
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::models::TestResult;
+
+/// The file name of the status file inside the runner's state directory.
+/// 运行器状态目录中状态文件的文件名。
+pub const STATUS_FILE: &str = "status.json";
+
+/// A snapshot of a run in progress.
+/// 正在进行的运行的快照。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunStatus {
+    /// The process id of the runner / 运行器的进程 ID
+    pub pid: u32,
+    /// When the run started / 运行开始的时间
+    pub started_at: DateTime<Utc>,
+    /// When the file was last written / 文件最后一次写入的时间
+    pub updated_at: DateTime<Utc>,
+    /// The number of cases of the run, including resumed ones / 本次运行的用例数量，包括恢复的用例
+    pub total: usize,
+    /// Cases kept from an interrupted run, counted as passed / 从中断的运行中保留的用例，计为通过
+    #[serde(default)]
+    pub resumed: usize,
+    /// Finished cases that passed or were built / 已完成且通过或已构建的用例
+    pub passed: usize,
+    /// Finished cases that failed / 已完成且失败的用例
+    pub failed: usize,
+    /// Cases skipped because the run or the case was cancelled / 因运行或用例被取消而跳过的用例
+    pub skipped: usize,
+    /// The cases running right now and when each started / 当前正在运行的用例及其开始时间
+    #[serde(default)]
+    pub running: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl RunStatus {
+    /// Creates the status of a run that is just starting.
+    /// 创建刚开始的运行的状态。
+    pub fn new(total: usize, resumed: usize, now: DateTime<Utc>) -> Self {
+        Self {
+            pid: std::process::id(),
+            started_at: now,
+            updated_at: now,
+            total,
+            resumed,
+            passed: resumed,
+            failed: 0,
+            skipped: 0,
+            running: BTreeMap::new(),
+        }
+    }
+
+    /// The number of finished cases / 已完成用例的数量
+    pub fn completed(&self) -> usize {
+        self.passed + self.failed + self.skipped
+    }
+
+    /// Estimates the remaining time from the rate cases finished at so far in this run.
+    ///
+    /// # Returns
+    /// The estimate, or `None` until a case of this run has finished
+    ///
+    /// 根据本次运行中到目前为止用例完成的速度估计剩余时间。
+    /// 在本次运行有用例完成之前返回 `None`。
+    pub fn eta(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let finished = self.completed().saturating_sub(self.resumed);
+        if finished == 0 {
+            return None;
+        }
+        let elapsed = (now - self.started_at).to_std().unwrap_or_default();
+        let remaining = self.total.saturating_sub(self.completed());
+        Some(elapsed.mul_f64(remaining as f64 / finished as f64))
+    }
+
+    fn record(&mut self, result: &TestResult) {
+        match result {
+            TestResult::Passed { .. } | TestResult::Built { .. } => self.passed += 1,
+            TestResult::Failed { .. } => self.failed += 1,
+            TestResult::Skipped { .. } => self.skipped += 1,
+        }
+    }
+}
+
+/// The status file of the current run, shared by all case tasks.
+/// Updates are best-effort: a status file that cannot be written never fails a case.
+///
+/// 当前运行的状态文件，由所有用例任务共享。
+/// 更新是尽力而为的：无法写入状态文件永远不会导致用例失败。
+#[derive(Debug, Clone)]
+pub struct StatusFile {
+    path: PathBuf,
+    status: Arc<Mutex<RunStatus>>,
+}
+
+impl StatusFile {
+    /// Writes the status of a run that is starting, replacing any previous one.
+    /// 写入正在开始的运行的状态，替换之前的状态。
+    pub fn start(state_dir: &Path, total: usize, resumed: usize) -> Result<Self> {
+        fs::create_dir_all(state_dir)
+            .with_context(|| format!("Failed to create directory: {}", state_dir.display()))?;
+        let status = RunStatus::new(total, resumed, Utc::now());
+        let file = Self {
+            path: status_path(state_dir),
+            status: Arc::new(Mutex::new(status.clone())),
+        };
+        file.write(&status)?;
+        Ok(file)
+    }
+
+    /// Marks a case as running / 将用例标记为正在运行
+    pub fn case_started(&self, name: &str) {
+        self.update(|status| {
+            status.running.insert(name.to_string(), Utc::now());
+        });
+    }
+
+    /// Marks a case as finished with the given result / 将用例标记为以给定结果完成
+    pub fn case_finished(&self, name: &str, result: &TestResult) {
+        self.update(|status| {
+            status.running.remove(name);
+            status.record(result);
+        });
+    }
+
+    /// Removes the status file once the run is over / 运行结束后删除状态文件
+    pub fn finish(self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    fn update(&self, change: impl FnOnce(&mut RunStatus)) {
+        let Ok(mut status) = self.status.lock() else {
+            return;
+        };
+        change(&mut status);
+        status.updated_at = Utc::now();
+        let _ = self.write(&status);
+    }
+
+    /// Writes to a temporary file first, so a reader never sees a half-written file.
+    fn write(&self, status: &RunStatus) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(status).context("Failed to serialize run status")?;
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write run status: {}", temp_path.display()))?;
+        fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Failed to write run status: {}", self.path.display()))
+    }
+}
+
+/// The path of the status file / 状态文件的路径
+pub fn status_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(STATUS_FILE)
+}
+
+/// Reads the status of the run in progress. Returns `None` if there is none.
+/// 读取正在进行的运行的状态。如果不存在则返回 `None`。
+pub fn load_status(state_dir: &Path) -> Option<RunStatus> {
+    let content = fs::read_to_string(status_path(state_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Checks whether the process that wrote a status file still exists, so the status of a
+/// crashed run is not shown as live. Always `true` where this cannot be checked.
+///
+/// 检查写入状态文件的进程是否仍然存在，以免崩溃运行的状态被显示为进行中。
+/// 在无法检查的平台上始终为 `true`。
+pub fn process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return false;
+        };
+        // Signal 0 only checks that the process exists; EPERM means it belongs to another user.
+        // SAFETY: `kill` with signal 0 sends nothing and has no memory-safety requirements.
+        let result = unsafe { libc::kill(pid, 0) };
+        result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
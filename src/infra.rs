@@ -1,13 +1,27 @@
 //! # Infrastructure Module / 基础设施模块
 //!
 //! This module provides infrastructure services for Matrix Runner,
-//! including command execution, file system operations, and i18n support.
+//! including command execution, artifact checksums, CI configuration scanning, run control, resource limits, core dump collection, port allocation, sandboxing, system load sampling, file system operations,
+//! environment capture, workspace detection, toolchain installation and i18n support (including the translation audit).
 //!
 //! 此模块为 Matrix Runner 提供基础设施服务，
-//! 包括命令执行、文件系统操作和国际化支持。
+//! 包括命令执行、构建产物校验和、CI 配置扫描、运行控制、资源限制、核心转储收集、端口分配、沙箱、系统负载采样、文件系统操作、环境捕获、工作区检测、工具链安装和国际化支持（包括翻译审计）。
 
+pub mod checksum;
+pub mod ci_config;
 pub mod command;
+pub mod control;
+pub mod core_dump;
+pub mod coverage;
+pub mod environment;
 pub mod fs;
+pub mod i18n_audit;
+pub mod limits;
+pub mod ports;
+pub mod sandbox;
+pub mod system_load;
+pub mod toolchain;
+pub mod workspace;
 
 // Re-export i18n functions for easier access
 pub use rust_i18n::t; 
\ No newline at end of file
@@ -0,0 +1,55 @@
+//! # Artifact Checksum Module / 构建产物校验和模块
+//!
+//! This module computes SHA-256 checksums of built test binaries and collects them
+//! per case during a run, so the results can back supply-chain attestations and
+//! reveal feature combinations whose builds are not reproducible.
+//!
+//! 此模块计算所构建测试二进制文件的 SHA-256 校验和，并在运行期间按用例收集，
+//! 使结果可以支持供应链证明，并揭示构建不可复现的 feature 组合。
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Computes the SHA-256 of a file as a lowercase hex string.
+/// 以小写十六进制字符串形式计算文件的 SHA-256。
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The checksums recorded during a run, keyed by case name.
+/// Cloning yields a handle to the same checksums.
+///
+/// 运行期间记录的校验和，以用例名称为键。克隆会得到指向同一组校验和的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct ChecksumLog {
+    checksums: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl ChecksumLog {
+    /// Records the checksum of a case's test binary, replacing an earlier one.
+    /// 记录用例测试二进制文件的校验和，替换之前的记录。
+    pub fn record(&self, case_name: &str, checksum: String) {
+        self.checksums
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(case_name.to_string(), checksum);
+    }
+
+    /// Returns the checksums recorded so far.
+    /// 返回目前已记录的校验和。
+    pub fn snapshot(&self) -> BTreeMap<String, String> {
+        self.checksums
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
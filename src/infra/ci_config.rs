@@ -0,0 +1,115 @@
+//! # CI Configuration Module / CI 配置模块
+//!
+//! This module finds the target triples a project's CI configuration builds for, so the
+//! matrix advisor can point out targets CI covers and the matrix does not. The CI files
+//! are not parsed as YAML: every word shaped like a target triple counts, wherever it is
+//! written (`--target`, a `targets:` list, a job matrix), which keeps this independent of
+//! the CI service.
+//!
+//! 此模块查找项目的 CI 配置所构建的目标三元组，使矩阵建议器能够指出 CI 覆盖而矩阵未覆盖的目标。
+//! CI 文件不会被解析为 YAML：每个形如目标三元组的单词都会被计入，无论其写在何处（`--target`、
+//! `targets:` 列表、作业矩阵），这使其与具体的 CI 服务无关。
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// CI files at a fixed path, relative to the repository root / 位于固定路径（相对于仓库根目录）的 CI 文件
+const CI_FILES: &[&str] = &[
+    ".gitlab-ci.yml",
+    ".circleci/config.yml",
+    "azure-pipelines.yml",
+    ".travis.yml",
+];
+
+/// The directory holding the GitHub Actions workflows / 存放 GitHub Actions 工作流的目录
+const GITHUB_WORKFLOWS_DIR: &str = ".github/workflows";
+
+/// The first component of the target triples recognized, so that ordinary hyphenated
+/// words are not mistaken for triples.
+const TRIPLE_ARCHES: &[&str] = &[
+    "x86_64",
+    "i586",
+    "i686",
+    "aarch64",
+    "arm",
+    "thumb",
+    "wasm32",
+    "wasm64",
+    "riscv",
+    "mips",
+    "powerpc",
+    "s390x",
+    "sparc",
+    "loongarch64",
+];
+
+/// The CI configuration files of the repository containing `project_root`. They are
+/// looked for in `project_root` and its parents, up to the root of the git repository,
+/// since the crate may live in a subdirectory of it.
+///
+/// 包含 `project_root` 的仓库的 CI 配置文件。会在 `project_root` 及其父目录中查找，直到 git 仓库的根目录，
+/// 因为 crate 可能位于仓库的子目录中。
+pub fn ci_config_files(project_root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in project_root.ancestors() {
+        files.extend(
+            CI_FILES
+                .iter()
+                .map(|file| dir.join(file))
+                .filter(|path| path.is_file()),
+        );
+        if let Ok(entries) = fs::read_dir(dir.join(GITHUB_WORKFLOWS_DIR)) {
+            let mut workflows: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == "yml" || ext == "yaml")
+                })
+                .collect();
+            workflows.sort();
+            files.extend(workflows);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    files
+}
+
+/// The target triples the CI configuration of the repository containing `project_root`
+/// builds for. Unreadable files are skipped.
+/// 包含 `project_root` 的仓库的 CI 配置所构建的目标三元组。无法读取的文件会被跳过。
+pub fn ci_targets(project_root: &Path) -> BTreeSet<String> {
+    ci_config_files(project_root)
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| parse_target_triples(&content))
+        .collect()
+}
+
+/// Finds the words of `content` shaped like target triples, e.g. `x86_64-pc-windows-msvc`,
+/// `thumbv7em-none-eabihf` or `wasm32-wasip1`.
+/// 查找 `content` 中形如目标三元组的单词，例如 `x86_64-pc-windows-msvc`、`thumbv7em-none-eabihf` 或 `wasm32-wasip1`。
+pub fn parse_target_triples(content: &str) -> BTreeSet<String> {
+    content
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        .map(|word| word.trim_matches(|c| c == '.' || c == '-'))
+        .filter(|word| is_target_triple(word))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_target_triple(word: &str) -> bool {
+    let parts: Vec<&str> = word.split('-').collect();
+    let Some((arch, rest)) = parts.split_first() else {
+        return false;
+    };
+    // Only the architecture may hold a dot, as in `thumbv8m.main-none-eabi`.
+    let min_parts = if arch.starts_with("wasm") { 2 } else { 3 };
+    (min_parts..=4).contains(&parts.len())
+        && TRIPLE_ARCHES.iter().any(|prefix| arch.starts_with(prefix))
+        && rest.iter().all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
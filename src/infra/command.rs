@@ -6,11 +6,172 @@
 //! 此模块提供用于生成进程、捕获其输出和格式化命令结果的实用功能，
 //! 特别是针对 Cargo 命令。
 
+use crate::core::feature_compare::TestOutcome;
+use crate::core::models::{CargoMessage, CargoReason, LibtestEvent, TestTiming};
+use crate::infra::t;
 use colored::*;
+use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use crate::core::models::CargoMessage;
-use crate::infra::t;
+
+/// Extracts per-test durations from libtest or nextest output.
+/// The human-readable format (`test foo ... ok <0.012s>`, printed with the
+/// nightly `--report-time` flag), the JSON format (`--format json`) and nextest's status
+/// lines (`PASS [   0.012s] my-crate foo`) are understood.
+/// Tests without a reported time are left out.
+///
+/// # Arguments
+/// * `output` - The captured output of a test binary or `cargo test` invocation.
+///
+/// # Returns
+/// The timings in the order the tests finished.
+///
+/// 从 libtest 或 nextest 输出中提取各个测试的耗时。
+/// 支持人类可读格式（`test foo ... ok <0.012s>`，由 nightly 的 `--report-time` 标志输出）、
+/// JSON 格式（`--format json`）以及 nextest 的状态行（`PASS [   0.012s] my-crate foo`）。
+/// 没有报告时间的测试会被忽略。
+pub fn parse_test_timings(output: &str) -> Vec<TestTiming> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('{') {
+                parse_json_timing(line)
+            } else if line.starts_with("test ") {
+                parse_text_timing(line)
+            } else {
+                parse_nextest_timing(line)
+            }
+        })
+        .collect()
+}
+
+fn parse_json_timing(line: &str) -> Option<TestTiming> {
+    let event = serde_json::from_str::<LibtestEvent>(line).ok()?;
+    if event.kind != "test" || event.event == "started" {
+        return None;
+    }
+    let duration = Duration::try_from_secs_f64(event.exec_time?).ok()?;
+    Some(TestTiming {
+        name: event.name?,
+        duration,
+    })
+}
+
+/// Parses a nextest status line, e.g. `PASS [   0.004s] my-crate tests::it_works`.
+fn parse_nextest_timing(line: &str) -> Option<TestTiming> {
+    let rest = line
+        .strip_prefix("PASS [")
+        .or_else(|| line.strip_prefix("FAIL ["))?;
+    let (time, rest) = rest.split_once("s]")?;
+    let duration = Duration::try_from_secs_f64(time.trim().parse().ok()?).ok()?;
+    Some(TestTiming {
+        name: rest.split_whitespace().last()?.to_string(),
+        duration,
+    })
+}
+
+fn parse_text_timing(line: &str) -> Option<TestTiming> {
+    let (name, rest) = line.strip_prefix("test ")?.split_once(" ... ")?;
+    let time = rest.rsplit_once('<')?.1.strip_suffix("s>")?;
+    let duration = Duration::try_from_secs_f64(time.parse().ok()?).ok()?;
+    Some(TestTiming {
+        name: name.to_string(),
+        duration,
+    })
+}
+
+/// Extracts the names of the failed tests from libtest output.
+/// Both the human-readable and the JSON format are understood.
+///
+/// # Arguments
+/// * `output` - The captured output of a test binary.
+///
+/// # Returns
+/// The failed test names, or `None` if the test suite did not report a normal
+/// failure summary (e.g. the binary crashed), in which case the names may be incomplete.
+///
+/// 从 libtest 输出中提取失败测试的名称。支持人类可读格式和 JSON 格式。
+/// 如果测试套件没有报告正常的失败摘要（例如二进制文件崩溃），则返回 `None`，
+/// 因为此时名称可能不完整。
+pub fn parse_failed_tests(output: &str) -> Option<Vec<String>> {
+    let mut failed = Vec::new();
+    let mut suite_failed = false;
+
+    for line in output.lines().map(str::trim) {
+        if line.starts_with('{') {
+            let Ok(event) = serde_json::from_str::<LibtestEvent>(line) else {
+                continue;
+            };
+            match (event.kind.as_str(), event.event.as_str()) {
+                ("test", "failed") => failed.extend(event.name),
+                ("suite", "failed") => suite_failed = true,
+                _ => {}
+            }
+        } else if line.starts_with("test result: FAILED") {
+            suite_failed = true;
+        } else if let Some((name, rest)) = line
+            .strip_prefix("test ")
+            .and_then(|l| l.split_once(" ... "))
+            && rest.starts_with("FAILED")
+        {
+            failed.push(name.to_string());
+        }
+    }
+
+    (suite_failed && !failed.is_empty()).then_some(failed)
+}
+
+/// Extracts the outcome of every test from libtest output, in the human-readable
+/// (`test foo ... ok`) or the JSON format. A test reported twice keeps its last outcome.
+///
+/// # Arguments
+/// * `output` - The captured output of a test binary.
+///
+/// # Returns
+/// The outcome of every test, by name.
+///
+/// 从 libtest 输出（人类可读格式 `test foo ... ok` 或 JSON 格式）中提取每个测试的结果。
+/// 被报告两次的测试保留其最后一次的结果。
+pub fn parse_test_outcomes(output: &str) -> std::collections::BTreeMap<String, TestOutcome> {
+    let mut outcomes = std::collections::BTreeMap::new();
+    for line in output.lines().map(str::trim) {
+        let (name, outcome) = if line.starts_with('{') {
+            let Ok(event) = serde_json::from_str::<LibtestEvent>(line) else {
+                continue;
+            };
+            let outcome = match (event.kind.as_str(), event.event.as_str()) {
+                ("test", "ok") => TestOutcome::Passed,
+                ("test", "failed") => TestOutcome::Failed,
+                ("test", "ignored") => TestOutcome::Ignored,
+                _ => continue,
+            };
+            let Some(name) = event.name else {
+                continue;
+            };
+            (name, outcome)
+        } else if let Some((name, rest)) = line
+            .strip_prefix("test ")
+            .and_then(|l| l.split_once(" ... "))
+        {
+            let outcome = if rest.starts_with("ok") {
+                TestOutcome::Passed
+            } else if rest.starts_with("FAILED") {
+                TestOutcome::Failed
+            } else if rest.starts_with("ignored") {
+                TestOutcome::Ignored
+            } else {
+                continue;
+            };
+            (name.to_string(), outcome)
+        } else {
+            continue;
+        };
+        outcomes.insert(name, outcome);
+    }
+    outcomes
+}
 
 /// Extracts and formats compiler errors from `cargo` JSON output.
 /// It filters for compiler messages, extracts error diagnostics, and prefers
@@ -18,6 +179,7 @@ use crate::infra::t;
 ///
 /// # Arguments
 /// * `raw_output` - The raw string output from a `cargo` command.
+/// * `locale` - The locale of the note shown when no error can be parsed.
 ///
 /// # Returns
 /// A formatted string containing only the error messages, or a snippet of the
@@ -28,22 +190,22 @@ use crate::infra::t;
 ///
 /// # Arguments
 /// * `raw_output` - `cargo` 命令的原始字符串输出。
+/// * `locale` - 无法解析出错误时所显示说明的语言环境。
 ///
 /// # Returns
 /// 一个格式化的字符串，仅包含错误消息；如果无法解析出特定错误，则返回原始输出的摘要。
-pub fn format_build_error_output(raw_output: &str) -> String {
+pub fn format_build_error_output(raw_output: &str, locale: &str) -> String {
     let error_messages: Vec<String> = raw_output
         .lines()
-        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter_map(CargoMessage::parse)
         .filter_map(|msg| {
-            if msg.reason == "compiler-message" {
-                if let Some(diag) = msg.message {
-                    if diag.level == "error" {
-                        // Prefer the colorful rendered output if available
-                        // 如果有带颜色的渲染输出，则优先使用
-                        return diag.rendered.or(Some(diag.message));
-                    }
-                }
+            if msg.reason == CargoReason::CompilerMessage
+                && let Some(diag) = msg.message
+                && diag.level == "error"
+            {
+                // Prefer the colorful rendered output if available
+                // 如果有带颜色的渲染输出，则优先使用
+                return diag.rendered.or(Some(diag.message));
             }
             None
         })
@@ -57,7 +219,7 @@ pub fn format_build_error_output(raw_output: &str) -> String {
         let snippet = raw_output.lines().take(50).collect::<Vec<_>>().join("\n");
         format!(
             "{}\n\n{}",
-            t!("run.compiler_error_parse_failed").yellow(),
+            t!("run.compiler_error_parse_failed", locale = locale).yellow(),
             snippet
         )
     } else {
@@ -65,8 +227,306 @@ pub fn format_build_error_output(raw_output: &str) -> String {
     }
 }
 
+/// Collects every compiler diagnostic (warnings, notes and errors) from `cargo` JSON
+/// output, as rendered by the compiler, for archiving the log of a build.
+///
+/// # Arguments
+/// * `raw_output` - The raw string output from a `cargo` command.
+///
+/// # Returns
+/// The rendered diagnostics, one after another; empty if the build emitted none.
+///
+/// 从 `cargo` 的 JSON 输出中收集所有编译器诊断（警告、注释和错误），
+/// 使用编译器渲染的形式，用于归档构建日志。
+pub fn format_build_log(raw_output: &str) -> String {
+    raw_output
+        .lines()
+        .filter_map(CargoMessage::parse)
+        .filter(|msg| msg.reason == CargoReason::CompilerMessage)
+        .filter_map(|msg| msg.message)
+        .map(|diag| diag.rendered.unwrap_or(diag.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The lint rustdoc reports a link to a missing item with / rustdoc 报告指向不存在条目的链接时所用的 lint
+pub const BROKEN_INTRA_DOC_LINKS: &str = "rustdoc::broken_intra_doc_links";
+
+/// The errors of a `cargo doc` run, counted by kind.
+/// 一次 `cargo doc` 运行的错误，按类型计数。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocErrors {
+    /// Links to items that do not exist under the case's features / 指向在用例的 features 下不存在的条目的链接
+    pub broken_links: usize,
+    /// Any other error, e.g. code that fails to compile / 其他任何错误，例如无法编译的代码
+    pub other: usize,
+}
+
+impl DocErrors {
+    /// The number of errors of any kind / 所有类型错误的数量
+    pub fn total(&self) -> usize {
+        self.broken_links + self.other
+    }
+}
+
+/// Counts the errors rustdoc reported in the JSON output of `cargo doc`. The closing
+/// "aborting due to ..." message is not an error of its own.
+///
+/// 统计 rustdoc 在 `cargo doc` 的 JSON 输出中报告的错误。结尾的 "aborting due to ..." 消息本身不算错误。
+pub fn parse_doc_errors(raw_output: &str) -> DocErrors {
+    let mut errors = DocErrors::default();
+    let diagnostics = raw_output
+        .lines()
+        .filter_map(CargoMessage::parse)
+        .filter(|msg| msg.reason == CargoReason::CompilerMessage)
+        .filter_map(|msg| msg.message)
+        .filter(|diag| diag.level == "error" && !diag.message.starts_with("aborting due to"));
+    for diag in diagnostics {
+        if diag.code.is_some_and(|code| code.code == BROKEN_INTRA_DOC_LINKS) {
+            errors.broken_links += 1;
+        } else {
+            errors.other += 1;
+        }
+    }
+    errors
+}
+
+/// Reads the names of the fuzz targets from the output of `cargo fuzz list`, one per line.
+/// 从 `cargo fuzz list` 的输出中读取模糊测试目标的名称，每行一个。
+pub fn parse_fuzz_targets(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Messages cargo prints when fetching dependencies fails for reasons unrelated to the
+/// code being built, such as a registry timeout or a git fetch cut short. Matched in lowercase.
+/// cargo 在获取依赖因与所构建代码无关的原因（例如注册表超时或 git 获取中断）失败时输出的消息。以小写匹配。
+pub const TRANSIENT_BUILD_ERRORS: &[&str] = &[
+    "spurious network error",
+    "failed to download",
+    "failed to fetch",
+    "failed to update registry",
+    "failed to query replaced source registry",
+    "couldn't resolve host",
+    "connection reset",
+    "connection refused",
+    "operation timed out",
+    "ssl connect error",
+];
+
+/// Checks whether a failed build failed because of a transient cargo error (see
+/// [`TRANSIENT_BUILD_ERRORS`]) rather than the code. Compiler diagnostics are ignored,
+/// so a compile error mentioning these words never counts.
+///
+/// 检查失败的构建是否由暂时性 cargo 错误（见 [`TRANSIENT_BUILD_ERRORS`]）而非代码导致。
+/// 编译器诊断会被忽略，因此提及这些词的编译错误永远不会被计入。
+pub fn is_transient_build_failure(raw_output: &str) -> bool {
+    raw_output
+        .lines()
+        .filter(|line| CargoMessage::parse(line).is_none())
+        .map(str::to_lowercase)
+        .any(|line| TRANSIENT_BUILD_ERRORS.iter().any(|error| line.contains(error)))
+}
+
+/// The environment that makes cargo, the compiler and test binaries print the same,
+/// parseable output whatever the user's locale (`force_c_locale`).
+/// 无论用户的语言环境如何，都使 cargo、编译器和测试二进制文件输出相同且可解析内容的环境变量（`force_c_locale`）。
+pub const C_LOCALE_ENV: &[(&str, &str)] = &[
+    ("LC_ALL", "C"),
+    ("LANG", "C"),
+    ("LANGUAGE", "C"),
+    ("CARGO_TERM_PROGRESS_WHEN", "never"),
+];
+
+/// Sets [`C_LOCALE_ENV`] on a command before it is spawned.
+/// 在命令启动前为其设置 [`C_LOCALE_ENV`]。
+pub fn apply_c_locale(cmd: &mut tokio::process::Command) {
+    cmd.envs(C_LOCALE_ENV.iter().copied());
+}
+
+/// Turns a language tag such as `zh-CN` into the POSIX locale name `zh_CN.UTF-8`.
+/// `C`, `POSIX` and names that already carry an encoding are kept as they are.
+/// 将 `zh-CN` 等语言标签转换为 POSIX 语言环境名称 `zh_CN.UTF-8`。`C`、`POSIX` 和已带编码的名称保持不变。
+pub fn posix_locale(tag: &str) -> String {
+    if tag == "C" || tag == "POSIX" || tag.contains('.') {
+        return tag.to_string();
+    }
+    format!("{}.UTF-8", tag.replace('-', "_"))
+}
+
+/// Makes a command run in the given locale (`env_locale`) by setting `LC_ALL`, `LANG`
+/// and `LANGUAGE`.
+/// 通过设置 `LC_ALL`、`LANG` 和 `LANGUAGE` 使命令在给定的语言环境（`env_locale`）下运行。
+pub fn apply_locale(cmd: &mut tokio::process::Command, tag: &str) {
+    let locale = posix_locale(tag);
+    let language = locale.split('.').next().unwrap_or(&locale).to_string();
+    cmd.env("LC_ALL", &locale)
+        .env("LANG", &locale)
+        .env("LANGUAGE", language);
+}
+
+tokio::task_local! {
+    static LIVE_LOG: Option<Arc<std::sync::Mutex<std::fs::File>>>;
+    static KILL_GRACE: (Option<Duration>, ShutdownTasks);
+}
+
+/// The tasks of one run shutting down processes whose case was dropped, e.g. on a timeout.
+/// Cloning yields a handle to the same tasks.
+///
+/// 一次运行中正在关闭其用例已被丢弃（例如超时）的进程的任务。克隆会得到指向同一组任务的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownTasks {
+    tasks: Arc<std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>>,
+}
+
+impl ShutdownTasks {
+    fn push(&self, task: tokio::task::JoinHandle<()>) {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner()).push(task);
+    }
+
+    /// Waits until the processes of this run still shutting down after their case was
+    /// dropped have exited or been killed, so the run does not end while they are in their
+    /// grace period. Processes of other runs are left alone.
+    /// 等待本次运行中在其用例被丢弃后仍在关闭的进程退出或被强制终止，使运行不会在它们处于宽限期时结束。
+    /// 其他运行的进程不受影响。
+    pub async fn wait(&self) {
+        loop {
+            let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+            if tasks.is_empty() {
+                break;
+            }
+            for task in tasks {
+                let _ = task.await;
+            }
+        }
+    }
+}
+
+/// Runs a future with a live log: every line captured by [`spawn_and_capture`] within it
+/// is also appended to the file as it is read, for `matrix-runner tail`.
+///
+/// # Arguments
+/// * `live_log` - The file to append to, or `None` to only capture the output
+/// * `future` - The future running the commands, e.g. a test case
+///
+/// 在带有实时日志的情况下运行 future：其中由 [`spawn_and_capture`] 捕获的每一行在读取时也会追加到该文件中，
+/// 供 `matrix-runner tail` 使用。
+pub async fn with_live_log<F: std::future::Future>(
+    live_log: Option<std::fs::File>,
+    future: F,
+) -> F::Output {
+    LIVE_LOG
+        .scope(live_log.map(|file| Arc::new(std::sync::Mutex::new(file))), future)
+        .await
+}
+
+/// Runs a future with a grace period for the processes spawned by [`spawn_and_capture`]
+/// within it: when such a process has to be stopped (the future is dropped, e.g. on a
+/// timeout, or its output stalls), its process group is sent SIGTERM and only killed if it
+/// has not exited once the grace period is over. Without a grace period, or on Windows,
+/// processes are killed right away.
+///
+/// # Arguments
+/// * `grace` - How long the processes may take to shut down, or `None` to kill them right away
+/// * `shutdowns` - The run's tasks that processes still in their grace period are added to
+/// * `future` - The future running the commands, e.g. a test case
+///
+/// 在带有宽限期的情况下运行 future：其中由 [`spawn_and_capture`] 启动的进程需要被停止时
+/// （future 被丢弃，例如超时，或其输出停滞），其进程组会收到 SIGTERM，宽限期结束后仍未退出才会被强制终止。
+/// 没有宽限期或在 Windows 上时，进程会被立即终止。
+pub async fn with_kill_grace<F: std::future::Future>(
+    grace: Option<Duration>,
+    shutdowns: &ShutdownTasks,
+    future: F,
+) -> F::Output {
+    KILL_GRACE.scope((grace, shutdowns.clone()), future).await
+}
+
+/// A signal sent to the process group of a command / 发送给命令进程组的信号
+#[derive(Clone, Copy)]
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+#[cfg(unix)]
+fn signal_group(process: &tokio::process::Child, signal: Signal) {
+    let signal = match signal {
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    // The process leads its own process group (see `spawn_and_capture_with_idle_timeout`).
+    if let Some(pid) = process.id() {
+        unsafe {
+            libc::kill(-(pid as libc::pid_t), signal);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(_process: &tokio::process::Child, _signal: Signal) {}
+
+/// Stops a process: SIGTERM first, then SIGKILL once the grace period is over.
+async fn shut_down(process: &mut tokio::process::Child, grace: Duration) {
+    signal_group(process, Signal::Terminate);
+    if tokio::time::timeout(grace, process.wait()).await.is_err() {
+        signal_group(process, Signal::Kill);
+        let _ = process.kill().await;
+    }
+}
+
+/// A spawned process given a grace period to shut down when it is stopped or dropped
+/// before it exits. Without a grace period, the command's `kill_on_drop` applies.
+struct RunningProcess {
+    process: Option<tokio::process::Child>,
+    /// The grace period, and the run's tasks a shutdown after a drop is added to
+    grace: Option<(Duration, ShutdownTasks)>,
+}
+
+impl RunningProcess {
+    fn process(&mut self) -> &mut tokio::process::Child {
+        self.process.as_mut().expect("the process is only taken when dropped")
+    }
+
+    async fn stop(&mut self) {
+        match self.grace.as_ref().map(|(grace, _)| *grace) {
+            Some(grace) => shut_down(self.process(), grace).await,
+            None => {
+                let _ = self.process().kill().await;
+            }
+        }
+    }
+}
+
+impl Drop for RunningProcess {
+    fn drop(&mut self) {
+        let (Some((grace, shutdowns)), Some(mut process)) = (self.grace.take(), self.process.take()) else {
+            return;
+        };
+        if matches!(process.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let shutdown = runtime.spawn(async move { shut_down(&mut process, grace).await });
+                shutdowns.push(shutdown);
+            }
+            Err(_) => {
+                signal_group(&process, Signal::Kill);
+                let _ = process.start_kill();
+            }
+        }
+    }
+}
+
 /// Spawns a command, captures its stdout and stderr.
 /// The output streams are read concurrently and combined into a single string.
+/// Equivalent to [`spawn_and_capture_with_idle_timeout`] without an idle timeout, with
+/// errors in the locale of the process.
 ///
 /// # Arguments
 /// * `cmd` - The `tokio::process::Command` to execute.
@@ -87,8 +547,44 @@ pub fn format_build_error_output(raw_output: &str) -> String {
 /// - 进程的 `ExitStatus`（包装在 `io::Result` 中）。
 /// - 合并的 stdout 和 stderr，为一个 `String`。
 pub async fn spawn_and_capture(
+    cmd: tokio::process::Command,
+) -> (std::io::Result<std::process::ExitStatus>, String) {
+    spawn_and_capture_with_idle_timeout(cmd, None, &rust_i18n::locale()).await
+}
+
+/// Spawns a command and captures its stdout and stderr like [`spawn_and_capture`], but
+/// kills the process once it has printed no line on either stream for `idle_timeout`.
+/// A process killed this way yields an error for which [`is_stalled`] returns `true`;
+/// the output captured until then is still returned.
+///
+/// # Arguments
+/// * `cmd` - The `tokio::process::Command` to execute.
+/// * `idle_timeout` - How long the process may stay silent, or `None` to wait indefinitely.
+/// * `locale` - The locale of the errors, e.g. of a process killed for being silent.
+///
+/// 像 [`spawn_and_capture`] 一样启动命令并捕获其 stdout 和 stderr，但当进程在
+/// `idle_timeout` 内未在任一流上输出任何行时将其终止。以这种方式被终止的进程会产生一个
+/// 使 [`is_stalled`] 返回 `true` 的错误；仍会返回在此之前捕获的输出。
+pub async fn spawn_and_capture_with_idle_timeout(
     mut cmd: tokio::process::Command,
+    idle_timeout: Option<Duration>,
+    locale: &str,
 ) -> (std::io::Result<std::process::ExitStatus>, String) {
+    // Start the process in its own process group, so a Ctrl-C in the terminal only reaches
+    // the runner, which decides whether running cases finish or are aborted.
+    // 在独立的进程组中启动进程，使终端中的 Ctrl-C 只会到达运行器，由运行器决定正在运行的用例是完成还是被中止。
+    #[cfg(unix)]
+    cmd.process_group(0);
+    // A process given a grace period is stopped by `RunningProcess` instead of being killed on drop.
+    let grace = KILL_GRACE
+        .try_with(|(grace, shutdowns)| grace.map(|grace| (grace, shutdowns.clone())))
+        .ok()
+        .flatten()
+        .filter(|_| cfg!(unix));
+    if grace.is_some() {
+        cmd.kill_on_drop(false);
+    }
+
     // Configure the command to capture stdout and stderr.
     // 配置命令以捕获 stdout 和 stderr。
     let mut child = match cmd
@@ -108,7 +604,7 @@ pub async fn spawn_and_capture(
         Some(stdout) => stdout,
         None => {
             return (
-                Err(std::io::Error::other(t!("common.capture_stdout_failed"))),
+                Err(std::io::Error::other(t!("common.capture_stdout_failed", locale = locale))),
                 String::new(),
             );
         }
@@ -117,7 +613,7 @@ pub async fn spawn_and_capture(
         Some(stderr) => stderr,
         None => {
             return (
-                Err(std::io::Error::other(t!("common.capture_stderr_failed"))),
+                Err(std::io::Error::other(t!("common.capture_stderr_failed", locale = locale))),
                 String::new(),
             );
         }
@@ -126,14 +622,24 @@ pub async fn spawn_and_capture(
     // Use an Arc<Mutex<String>> to allow concurrent writes from stdout and stderr tasks.
     // 使用 Arc<Mutex<String>> 来允许多个任务（stdout 和 stderr）并发写入。
     let output = Arc::new(tokio::sync::Mutex::new(String::new()));
+    // The time the last line was read, on either stream.
+    // 最后一次读取到行（任一流）的时间。
+    let last_output = Arc::new(std::sync::Mutex::new(tokio::time::Instant::now()));
+    // The reading tasks do not inherit the task-local live log, so it is passed to them.
+    // 读取任务不会继承任务本地的实时日志，因此将其传递给它们。
+    let live_log = LIVE_LOG.try_with(Clone::clone).ok().flatten();
 
     // Spawn a task to read stdout line by line.
     // 派生一个任务来逐行读取 stdout。
     let stdout_output = Arc::clone(&output);
+    let stdout_last_output = Arc::clone(&last_output);
+    let stdout_live_log = live_log.clone();
     let stdout_handle = tokio::spawn(async move {
         let reader = BufReader::new(stdout);
         let mut lines = reader.lines();
         while let Ok(Some(line)) = lines.next_line().await {
+            touch(&stdout_last_output);
+            append_live(stdout_live_log.as_deref(), &line);
             let mut output = stdout_output.lock().await;
             output.push_str(&line);
             output.push('\n');
@@ -143,28 +649,89 @@ pub async fn spawn_and_capture(
     // Spawn a task to read stderr line by line.
     // 派生一个任务来逐行读取 stderr。
     let stderr_output = Arc::clone(&output);
+    let stderr_last_output = Arc::clone(&last_output);
+    let stderr_live_log = live_log;
     let stderr_handle = tokio::spawn(async move {
         let reader = BufReader::new(stderr);
         let mut lines = reader.lines();
         while let Ok(Some(line)) = lines.next_line().await {
+            touch(&stderr_last_output);
+            append_live(stderr_live_log.as_deref(), &line);
             let mut output = stderr_output.lock().await;
             output.push_str(&line);
             output.push('\n');
         }
     });
 
-    // Wait for the process to exit.
-    // 等待进程退出。
-    let status = child.wait().await;
+    let mut child = RunningProcess {
+        process: Some(child),
+        grace,
+    };
+
+    // Wait for the process to exit, or until it has been silent for too long.
+    // 等待进程退出，或直到其静默时间过长。
+    let status = match idle_timeout {
+        None => child.process().wait().await,
+        Some(idle_timeout) => loop {
+            let deadline = last_output_at(&last_output) + idle_timeout;
+            tokio::select! {
+                status = child.process().wait() => break status,
+                _ = tokio::time::sleep_until(deadline) => {}
+            }
+            // A line may have arrived while sleeping; only a full silent period counts.
+            if last_output_at(&last_output) + idle_timeout <= tokio::time::Instant::now() {
+                child.stop().await;
+                // Processes started by the child may still hold the pipes open.
+                stdout_handle.abort();
+                stderr_handle.abort();
+                break Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    t!("common.output_stalled", locale = locale, secs = idle_timeout.as_secs()),
+                ));
+            }
+        },
+    };
 
     // Wait for the stdout and stderr reading tasks to complete to ensure all output is captured.
     // 等待 stdout 和 stderr 读取任务完成，以确保所有输出都被捕获。
-    if let Err(e) = stdout_handle.await {
+    if let Err(e) = stdout_handle.await
+        && !e.is_cancelled()
+    {
         eprintln!("Failed to join stdout task: {}", e);
     }
-    if let Err(e) = stderr_handle.await {
+    if let Err(e) = stderr_handle.await
+        && !e.is_cancelled()
+    {
         eprintln!("Failed to join stderr task: {}", e);
     }
 
     (status, output.lock().await.clone())
+}
+
+/// Checks whether a process error returned by [`spawn_and_capture_with_idle_timeout`]
+/// means the process was killed for being silent too long.
+/// 检查 [`spawn_and_capture_with_idle_timeout`] 返回的进程错误是否表示进程因静默过久而被终止。
+pub fn is_stalled(error: &std::io::Error) -> bool {
+    error.kind() == std::io::ErrorKind::TimedOut
+}
+
+/// Appends a line to the live log, if any. Best-effort: a failed write never fails the case.
+fn append_live(live_log: Option<&std::sync::Mutex<std::fs::File>>, line: &str) {
+    if let Some(live_log) = live_log
+        && let Ok(mut file) = live_log.lock()
+    {
+        let _ = file.write_all(format!("{line}\n").as_bytes());
+    }
+}
+
+fn touch(last_output: &std::sync::Mutex<tokio::time::Instant>) {
+    if let Ok(mut last_output) = last_output.lock() {
+        *last_output = tokio::time::Instant::now();
+    }
+}
+
+fn last_output_at(last_output: &std::sync::Mutex<tokio::time::Instant>) -> tokio::time::Instant {
+    last_output
+        .lock()
+        .map_or_else(|_| tokio::time::Instant::now(), |last_output| *last_output)
 } 
\ No newline at end of file
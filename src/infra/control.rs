@@ -0,0 +1,259 @@
+//! # Run Control Module / 运行控制模块
+//!
+//! This module implements a small control channel for a run in progress. The runner
+//! watches a control file in its state directory; every line appended to it is a
+//! command, for example:
+//!
+//! ```text
+//! echo "fast-fail on" >> .matrix-runner/control
+//! ```
+//!
+//! Supported commands: `fast-fail on|off`, `cancel case <name>`,
+//! `pause scheduling` and `resume scheduling`.
+//!
+//! 此模块为正在进行的运行实现了一个小型控制通道。运行器会监视其状态目录中的控制文件；
+//! 追加到该文件的每一行都是一条命令。
+//!
+//! 支持的命令：`fast-fail on|off`、`cancel case <name>`、
+//! `pause scheduling` 和 `resume scheduling`。
+
+use colored::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::infra::t;
+
+/// The file name of the control file inside the state directory.
+/// 状态目录中控制文件的文件名。
+pub const CONTROL_FILE: &str = "control";
+
+/// How often the control file is checked for new commands / 检查控制文件新命令的频率
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A command accepted on the control channel.
+/// 控制通道接受的命令。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Turns fast-fail on or off / 开启或关闭快速失败
+    FastFail(bool),
+    /// Cancels a single case, whether it is waiting or running / 取消单个用例，无论其在等待还是运行中
+    CancelCase(String),
+    /// Stops starting new cases; running cases continue / 停止启动新用例；正在运行的用例继续执行
+    PauseScheduling,
+    /// Starts new cases again / 重新开始启动新用例
+    ResumeScheduling,
+}
+
+/// Parses a single control command line.
+///
+/// # Returns
+/// The command, or a message describing why the line is not a valid command
+///
+/// 解析单行控制命令。
+pub fn parse_command(line: &str) -> Result<ControlCommand, String> {
+    // Case names may contain spaces, so the name is the rest of the line.
+    if let Some(name) = cancel_case_name(line) {
+        return Ok(ControlCommand::CancelCase(name.to_string()));
+    }
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["fast-fail", "on"] => Ok(ControlCommand::FastFail(true)),
+        ["fast-fail", "off"] => Ok(ControlCommand::FastFail(false)),
+        ["pause", "scheduling"] => Ok(ControlCommand::PauseScheduling),
+        ["resume", "scheduling"] => Ok(ControlCommand::ResumeScheduling),
+        _ => Err(format!(
+            "unknown command `{}` (expected one of: fast-fail on|off, cancel case <name>, pause scheduling, resume scheduling)",
+            line.trim()
+        )),
+    }
+}
+
+/// Gets the case name of a `cancel case <name>` line.
+fn cancel_case_name(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("cancel")?;
+    let rest = rest
+        .strip_prefix(char::is_whitespace)?
+        .trim_start()
+        .strip_prefix("case")?;
+    let name = rest.strip_prefix(char::is_whitespace)?.trim();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The state of a run that can be changed while it is in progress.
+/// Cloning yields a handle to the same state.
+///
+/// 运行中可以更改的运行状态。克隆会得到指向同一状态的句柄。
+#[derive(Debug, Clone)]
+pub struct RunControl {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    fast_fail: AtomicBool,
+    /// Set once a case failed unexpectedly, so turning fast-fail on later still stops the run
+    failed: AtomicBool,
+    fast_fail_token: CancellationToken,
+    paused: watch::Sender<bool>,
+    case_tokens: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl RunControl {
+    /// Creates the control state for a run.
+    /// 为一次运行创建控制状态。
+    pub fn new(fast_fail: bool) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                fast_fail: AtomicBool::new(fast_fail),
+                failed: AtomicBool::new(false),
+                fast_fail_token: CancellationToken::new(),
+                paused: watch::Sender::new(false),
+                case_tokens: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Whether fast-fail is currently on / 快速失败当前是否开启
+    pub fn fast_fail(&self) -> bool {
+        self.inner.fast_fail.load(Ordering::SeqCst)
+    }
+
+    /// The token cancelled when fast-fail stops the run / 快速失败停止运行时被取消的令牌
+    pub fn fast_fail_token(&self) -> CancellationToken {
+        self.inner.fast_fail_token.clone()
+    }
+
+    /// Records an unexpected failure, stopping the run if fast-fail is on.
+    /// 记录一次意外失败，如果快速失败已开启则停止运行。
+    pub fn record_failure(&self) {
+        self.inner.failed.store(true, Ordering::SeqCst);
+        if self.fast_fail() {
+            self.inner.fast_fail_token.cancel();
+        }
+    }
+
+    /// Gets the token that cancels a single case.
+    /// 获取取消单个用例的令牌。
+    pub fn case_token(&self, case_name: &str) -> CancellationToken {
+        let mut tokens = self
+            .inner
+            .case_tokens
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        tokens.entry(case_name.to_string()).or_default().clone()
+    }
+
+    /// Waits until scheduling is not paused.
+    /// 等待直到调度未被暂停。
+    pub async fn wait_while_paused(&self) {
+        let mut paused = self.inner.paused.subscribe();
+        // The sender lives in `self`, so the channel cannot close while waiting.
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+
+    /// Applies a control command.
+    /// 应用一条控制命令。
+    pub fn apply(&self, command: &ControlCommand) {
+        match command {
+            ControlCommand::FastFail(on) => {
+                self.inner.fast_fail.store(*on, Ordering::SeqCst);
+                if *on && self.inner.failed.load(Ordering::SeqCst) {
+                    self.inner.fast_fail_token.cancel();
+                }
+            }
+            ControlCommand::CancelCase(name) => self.case_token(name).cancel(),
+            ControlCommand::PauseScheduling => {
+                self.inner.paused.send_replace(true);
+            }
+            ControlCommand::ResumeScheduling => {
+                self.inner.paused.send_replace(false);
+            }
+        }
+    }
+}
+
+/// Returns the path of the control file inside a state directory.
+/// 返回状态目录中控制文件的路径。
+pub fn control_path(state_dir: &Path) -> PathBuf {
+    state_dir.join(CONTROL_FILE)
+}
+
+/// Watches the control file and applies the commands appended to it until `stop` is cancelled.
+/// The file is emptied first, so commands left over from an earlier run are not replayed.
+///
+/// # Arguments
+/// * `path` - Path to the control file
+/// * `control` - The run state the commands are applied to
+/// * `stop` - Cancelled when the run is over
+/// * `locale` - The locale for the printed messages
+///
+/// 监视控制文件，并应用追加到其中的命令，直到 `stop` 被取消。
+/// 文件会先被清空，因此不会重放之前运行遗留的命令。
+pub async fn watch_control_file(
+    path: PathBuf,
+    control: RunControl,
+    stop: CancellationToken,
+    locale: String,
+) {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if std::fs::write(&path, "").is_err() {
+        return;
+    }
+
+    let mut consumed = 0;
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => return,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+        // Read bytes: a partly flushed write may end in the middle of a multi-byte character.
+        let Ok(content) = std::fs::read(&path) else {
+            continue;
+        };
+        // The file was truncated or replaced; start over.
+        if content.len() < consumed {
+            consumed = 0;
+        }
+        // Only complete lines are consumed, so a command being written is not read half-way.
+        let Some(end) = content[consumed..].iter().rposition(|&b| b == b'\n') else {
+            continue;
+        };
+        let lines = String::from_utf8_lossy(&content[consumed..consumed + end]);
+        for line in lines.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_command(line) {
+                Ok(command) => {
+                    println!(
+                        "{}",
+                        t!(
+                            "run.control_command",
+                            locale = &locale,
+                            command = line.trim()
+                        )
+                        .cyan()
+                    );
+                    control.apply(&command);
+                }
+                Err(message) => eprintln!(
+                    "{}",
+                    t!(
+                        "run.control_command_invalid",
+                        locale = &locale,
+                        message = message
+                    )
+                    .yellow()
+                ),
+            }
+        }
+        consumed += end + 1;
+    }
+}
@@ -0,0 +1,154 @@
+//! # Core Dump Module / 核心转储模块
+//!
+//! This module collects the core dump of a test binary that crashed with a signal, for
+//! post-mortem debugging (`collect_core_dumps = true`). The test process is started with
+//! its soft core size limit raised to the hard limit, and after a crash the dump is looked
+//! for where the kernel writes it: on Linux according to `/proc/sys/kernel/core_pattern`,
+//! on macOS in `/cores`. A dump found there is moved next to the case's attachments.
+//! When the pattern pipes dumps to a handler such as `systemd-coredump`, no file is
+//! written, so only the handler is named in the failure details.
+//!
+//! 此模块收集因信号而崩溃的测试二进制文件的核心转储，用于事后调试（`collect_core_dumps = true`）。
+//! 测试进程启动时其核心转储大小的软限制会被提高到硬限制；崩溃后会在内核写入转储的位置查找它：
+//! Linux 上依据 `/proc/sys/kernel/core_pattern`，macOS 上为 `/cores`。找到的转储会被移动到用例的附件旁。
+//! 当该模式将转储通过管道交给 `systemd-coredump` 等处理程序时，不会写入任何文件，
+//! 因此失败详情中只会指出该处理程序。
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Duration, SystemTime};
+
+/// How much earlier than the start of a process a dump may be stamped: file times come
+/// from a coarser clock than `SystemTime::now`, so a dump written right away can seem older.
+const CLOCK_SLACK: Duration = Duration::from_secs(1);
+
+/// Where the kernel puts the core dump of a crashed process.
+/// 内核放置崩溃进程核心转储的位置。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreLocation {
+    /// A file in a directory, whose name starts with a known prefix / 目录中的一个文件，其名称以已知前缀开头
+    File {
+        /// The directory the dump is written to / 写入转储的目录
+        dir: PathBuf,
+        /// The start of the file name, e.g. `core` / 文件名的开头，例如 `core`
+        prefix: String,
+    },
+    /// Piped to a handler program, e.g. `systemd-coredump` / 通过管道交给处理程序，例如 `systemd-coredump`
+    Handler(String),
+}
+
+/// Parses a Linux `core_pattern`. A relative pattern is relative to the working
+/// directory of the crashed process, and the file name prefix ends at the first
+/// `%` specifier, since the rest (pid, time, ...) differs between crashes.
+///
+/// # Arguments
+/// * `pattern` - The content of `/proc/sys/kernel/core_pattern`
+/// * `working_dir` - The working directory of the crashed process
+///
+/// 解析 Linux 的 `core_pattern`。相对模式相对于崩溃进程的工作目录；文件名前缀截止于第一个
+/// `%` 说明符，因为其余部分（pid、时间等）在每次崩溃时都不同。
+pub fn parse_core_pattern(pattern: &str, working_dir: &Path) -> CoreLocation {
+    let pattern = pattern.trim();
+    if let Some(handler) = pattern.strip_prefix('|') {
+        let program = handler.split_whitespace().next().unwrap_or_default();
+        return CoreLocation::Handler(program.to_string());
+    }
+    // An empty pattern writes `core`, as in the kernel.
+    let pattern = if pattern.is_empty() { "core" } else { pattern };
+    let path = working_dir.join(pattern);
+    let dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| working_dir.to_path_buf());
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let prefix = file_name.split('%').next().unwrap_or_default().to_string();
+    CoreLocation::File { dir, prefix }
+}
+
+/// Finds where the kernel of this system puts core dumps.
+///
+/// # Returns
+/// The location, or `None` on platforms where it is not known
+///
+/// 查找本系统内核放置核心转储的位置。在位置未知的平台上返回 `None`。
+pub fn core_location(working_dir: &Path) -> Option<CoreLocation> {
+    if cfg!(target_os = "linux") {
+        let pattern = fs::read_to_string("/proc/sys/kernel/core_pattern").ok()?;
+        Some(parse_core_pattern(&pattern, working_dir))
+    } else if cfg!(target_os = "macos") {
+        Some(CoreLocation::File {
+            dir: PathBuf::from("/cores"),
+            prefix: "core".to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Finds the newest core dump written in a directory since a process started.
+/// 查找自进程启动以来在目录中写入的最新核心转储。
+pub fn find_core_dump(dir: &Path, prefix: &str, since: SystemTime) -> Option<PathBuf> {
+    let since = since.checked_sub(CLOCK_SLACK).unwrap_or(since);
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            (metadata.is_file() && modified >= since).then(|| (modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Moves a core dump into a directory, copying it if it lives on another file system.
+///
+/// # Returns
+/// The new path of the dump
+///
+/// 将核心转储移动到某个目录中；如果它位于另一个文件系统上，则复制它。返回转储的新路径。
+pub fn collect(core: &Path, dest_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dest_dir)
+        .with_context(|| format!("Failed to create {}", dest_dir.display()))?;
+    let dest = dest_dir.join(core.file_name().unwrap_or(core.as_os_str()));
+    if fs::rename(core, &dest).is_err() {
+        fs::copy(core, &dest)
+            .with_context(|| format!("Failed to copy {} to {}", core.display(), dest.display()))?;
+        let _ = fs::remove_file(core);
+    }
+    Ok(dest)
+}
+
+/// The signal that killed a process, if it crashed with one.
+/// 如果进程因信号而崩溃，则返回杀死它的信号。
+#[cfg(unix)]
+pub fn crash_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// The signal that killed a process; processes are not killed by signals on this platform.
+#[cfg(not(unix))]
+pub fn crash_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Whether the kernel produced a core dump of a crashed process.
+/// 内核是否为崩溃的进程生成了核心转储。
+#[cfg(unix)]
+pub fn dumped_core(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    status.core_dumped()
+}
+
+/// Whether the kernel produced a core dump; never on this platform.
+#[cfg(not(unix))]
+pub fn dumped_core(_status: &ExitStatus) -> bool {
+    false
+}
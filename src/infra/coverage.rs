@@ -0,0 +1,113 @@
+//! # Coverage Module / 覆盖率模块
+//!
+//! This module reads the JSON report written by `cargo tarpaulin --out Json` and
+//! collects the coverage of each `kind = "tarpaulin"` case during a run, so the results
+//! and the HTML report can show how much of the code each feature combination covers.
+//!
+//! 此模块读取 `cargo tarpaulin --out Json` 写入的 JSON 报告，并在运行期间收集每个
+//! `kind = "tarpaulin"` 用例的覆盖率，使结果和 HTML 报告能够显示每种 feature 组合覆盖了多少代码。
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// The file name of the JSON report written by tarpaulin / tarpaulin 写入的 JSON 报告的文件名
+pub const TARPAULIN_REPORT_FILE: &str = "tarpaulin-report.json";
+
+/// The line coverage of a case.
+/// 一个用例的行覆盖率。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Coverage {
+    /// The number of covered lines / 已覆盖的行数
+    pub covered: u64,
+    /// The number of lines that can be covered / 可被覆盖的行数
+    pub coverable: u64,
+}
+
+impl Coverage {
+    /// The covered share of the coverable lines, in percent; 0 if nothing is coverable.
+    /// 已覆盖行占可覆盖行的百分比；没有可覆盖的行时为 0。
+    pub fn percent(&self) -> f64 {
+        if self.coverable == 0 {
+            0.0
+        } else {
+            self.covered as f64 * 100.0 / self.coverable as f64
+        }
+    }
+
+    /// Sums the coverage of several cases / 汇总多个用例的覆盖率
+    pub fn total<'a>(coverages: impl IntoIterator<Item = &'a Coverage>) -> Coverage {
+        coverages
+            .into_iter()
+            .fold(Coverage::default(), |total, coverage| Coverage {
+                covered: total.covered + coverage.covered,
+                coverable: total.coverable + coverage.coverable,
+            })
+    }
+}
+
+/// The parts of tarpaulin's JSON report that are read. Older versions only have the
+/// per-file counts, so the totals are summed from the files when missing.
+#[derive(Deserialize)]
+struct TarpaulinReport {
+    #[serde(default)]
+    covered: Option<u64>,
+    #[serde(default)]
+    coverable: Option<u64>,
+    #[serde(default)]
+    files: Vec<Coverage>,
+}
+
+/// Parses tarpaulin's JSON report.
+/// 解析 tarpaulin 的 JSON 报告。
+pub fn parse_tarpaulin_report(json: &str) -> Result<Coverage> {
+    let report: TarpaulinReport =
+        serde_json::from_str(json).context("Failed to parse tarpaulin report")?;
+    let from_files = Coverage::total(&report.files);
+    Ok(Coverage {
+        covered: report.covered.unwrap_or(from_files.covered),
+        coverable: report.coverable.unwrap_or(from_files.coverable),
+    })
+}
+
+/// Reads the JSON report tarpaulin wrote into a directory.
+/// 读取 tarpaulin 写入某目录的 JSON 报告。
+pub fn read_tarpaulin_report(dir: &Path) -> Result<Coverage> {
+    let path = dir.join(TARPAULIN_REPORT_FILE);
+    let json = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read tarpaulin report: {}", path.display()))?;
+    parse_tarpaulin_report(&json)
+}
+
+/// The coverage recorded during a run, keyed by case name.
+/// Cloning yields a handle to the same coverage.
+///
+/// 运行期间记录的覆盖率，以用例名称为键。克隆会得到指向同一组覆盖率的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct CoverageLog {
+    coverage: Arc<Mutex<BTreeMap<String, Coverage>>>,
+}
+
+impl CoverageLog {
+    /// Records the coverage of a case, replacing an earlier one.
+    /// 记录用例的覆盖率，替换之前的记录。
+    pub fn record(&self, case_name: &str, coverage: Coverage) {
+        self.coverage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(case_name.to_string(), coverage);
+    }
+
+    /// Returns the coverage recorded so far.
+    /// 返回目前已记录的覆盖率。
+    pub fn snapshot(&self) -> BTreeMap<String, Coverage> {
+        self.coverage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
@@ -0,0 +1,135 @@
+//! # Environment Capture Module / 环境捕获模块
+//!
+//! This module records the environment a run happened in (toolchain versions, host,
+//! OS, CPU and relevant environment variables), so that archived results are
+//! self-describing. Every probe is best-effort: a missing tool yields `None`
+//! instead of failing the run.
+//!
+//! 此模块记录运行所处的环境（工具链版本、主机、操作系统、CPU 以及相关环境变量），
+//! 使归档的结果能够自我描述。每项探测都是尽力而为的：缺少的工具会得到 `None`，
+//! 而不会导致运行失败。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::core::models::RunEnvironment;
+
+/// Environment variables that influence how cases are built and run.
+/// 影响用例构建和运行方式的环境变量。
+const RELEVANT_ENV_VARS: &[&str] = &[
+    "RUSTFLAGS",
+    "RUSTDOCFLAGS",
+    "RUSTC_WRAPPER",
+    "RUSTUP_TOOLCHAIN",
+    "RUST_BACKTRACE",
+    "RUST_TEST_THREADS",
+    "CI",
+];
+
+/// Captures the environment of the current run.
+///
+/// # Arguments
+/// * `project_root` - Path to the project root, so a `rust-toolchain` file there is honored
+///
+/// # Returns
+/// The captured environment; fields that could not be determined are `None`
+///
+/// 捕获当前运行的环境。
+pub async fn capture_environment(project_root: &Path) -> RunEnvironment {
+    let rustc_verbose = command_output(project_root, "rustc", &["-vV"]).await;
+    let rustc_version = rustc_verbose
+        .as_deref()
+        .and_then(|out| out.lines().next())
+        .map(str::to_string);
+    let host = rustc_verbose.as_deref().and_then(|out| {
+        out.lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .map(str::to_string)
+    });
+    let cargo_version = command_output(project_root, "cargo", &["-V"]).await;
+    let toolchain = command_output(project_root, "rustup", &["show", "active-toolchain"])
+        .await
+        .and_then(|out| out.split_whitespace().next().map(str::to_string));
+
+    RunEnvironment {
+        captured_at: chrono::Utc::now(),
+        rustc_version,
+        cargo_version,
+        toolchain,
+        host,
+        os: std::env::consts::OS.to_string(),
+        os_version: os_version().await,
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_count: num_cpus::get(),
+        cpu_model: cpu_model().await,
+        env_vars: relevant_env_vars(),
+    }
+}
+
+/// Runs a command and returns its trimmed stdout if it succeeded.
+async fn command_output(dir: &Path, program: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+async fn os_version() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let release = std::fs::read_to_string("/etc/os-release").ok()?;
+        release.lines().find_map(|line| {
+            line.strip_prefix("PRETTY_NAME=")
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    } else if cfg!(target_os = "macos") {
+        command_output(Path::new("."), "sw_vers", &["-productVersion"]).await
+    } else if cfg!(windows) {
+        command_output(Path::new("."), "cmd", &["/C", "ver"]).await
+    } else {
+        None
+    }
+}
+
+async fn cpu_model() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+        cpuinfo.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "model name").then(|| value.trim().to_string())
+        })
+    } else if cfg!(target_os = "macos") {
+        command_output(
+            Path::new("."),
+            "sysctl",
+            &["-n", "machdep.cpu.brand_string"],
+        )
+        .await
+    } else {
+        std::env::var("PROCESSOR_IDENTIFIER").ok()
+    }
+}
+
+/// Collects the relevant environment variables, plus any `CARGO_*` variable.
+/// Variables that look like credentials are never recorded.
+fn relevant_env_vars() -> BTreeMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| {
+            RELEVANT_ENV_VARS.contains(&name.as_str()) || name.starts_with("CARGO_")
+        })
+        .filter(|(name, _)| !is_secret(name))
+        .collect()
+}
+
+fn is_secret(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    ["TOKEN", "SECRET", "PASSWORD", "CREDENTIAL"]
+        .iter()
+        .any(|marker| upper.contains(marker))
+}
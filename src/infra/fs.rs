@@ -7,10 +7,26 @@
 //! 如创建临时构建目录和复制文件。
 
 use anyhow::{Context, Result};
-use fs_extra::dir::{copy, CopyOptions};
+use fs_extra::dir::{CopyOptions, copy};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use tempfile::{tempdir, TempDir};
+use tempfile::{TempDir, tempdir};
+
+/// The classic Windows `MAX_PATH` limit that many tools (including parts of the
+/// MSVC toolchain) still enforce.
+/// 许多工具（包括部分 MSVC 工具链）仍然强制执行的经典 Windows `MAX_PATH` 限制。
+pub const WINDOWS_MAX_PATH: usize = 260;
+
+/// Room reserved for the paths cargo creates below a target directory
+/// (e.g. `debug/build/<crate>-<hash>/out/...`).
+/// 为 cargo 在 target 目录下创建的路径预留的长度（例如 `debug/build/<crate>-<hash>/out/...`）。
+pub const CARGO_PATH_HEADROOM: usize = 120;
+
+/// Case names longer than this are shortened to a prefix plus a hash when used as directory names.
+/// 作为目录名使用时，超过此长度的用例名称会被缩短为前缀加哈希。
+const MAX_CASE_DIR_NAME_LEN: usize = 32;
 
 /// Creates a unique, temporary build directory for a test case.
 ///
@@ -21,38 +37,360 @@ use tempfile::{tempdir, TempDir};
 /// # Returns
 /// A `BuildContext` containing the temporary directory information
 pub fn create_build_dir(project_root: &Path, case_name: &str) -> Result<(PathBuf, TempDir)> {
-    let sanitized_name = case_name
+    let target_dir = extended_length_path(&project_root.join("target"));
+    // The directory gets a fresh, random name, so no leftovers of a previous run can be in it.
+    let temp_dir = tempdir()
+        .or_else(|_| tempdir_in(&target_dir, case_name))
+        .with_context(|| "Failed to create temporary build directory".to_string())?;
+
+    let path = extended_length_path(temp_dir.path());
+
+    Ok((path, temp_dir))
+}
+
+/// Turns a case name into a single, length-bounded path component.
+/// Non-alphanumeric characters become `_`. Names that this changes, or that are longer
+/// than 32 characters, are suffixed with a short hash of the full name (after truncating
+/// them), so distinct cases such as `linux x86` and `linux-x86` never share a directory.
+///
+/// # Arguments
+/// * `case_name` - Name of the test case
+///
+/// # Returns
+/// A directory name safe to use on every platform
+pub fn case_dir_name(case_name: &str) -> String {
+    let sanitized: String = case_name
         .chars()
         .map(|c| if c.is_alphanumeric() { c } else { '_' })
-        .collect::<String>();
-        
-    let temp_dir_name = format!("matrix_runner_{}", sanitized_name);
-    let target_dir = project_root.join("target");
-    let temp_dir_path = target_dir.join(&temp_dir_name);
-
-    // Clean up old directory if it exists, to ensure a fresh build environment.
-    if temp_dir_path.exists() {
-        fs::remove_dir_all(&temp_dir_path).with_context(|| {
-            format!(
-                "Failed to clean up old build directory: {}",
-                temp_dir_path.display()
-            )
+        .collect();
+
+    if sanitized == case_name && sanitized.chars().count() <= MAX_CASE_DIR_NAME_LEN {
+        return sanitized;
+    }
+
+    let prefix: String = sanitized.chars().take(MAX_CASE_DIR_NAME_LEN - 9).collect();
+    format!("{}_{}", prefix, short_hash(case_name))
+}
+
+/// Computes a stable 8-character hexadecimal hash (FNV-1a) of a string.
+/// Unlike `DefaultHasher`, the result does not change between Rust releases.
+///
+/// # Arguments
+/// * `input` - The string to hash
+///
+/// # Returns
+/// An 8-character lowercase hex string
+pub fn short_hash(input: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in input.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:08x}", (hash ^ (hash >> 32)) as u32)
+}
+
+/// Converts an absolute Windows path into its extended-length (`\\?\`) form so that
+/// file operations are not limited by `MAX_PATH`. UNC paths (`\\server\share`) become
+/// `\\?\UNC\server\share`. Relative paths, already-prefixed paths, and paths on
+/// other platforms are returned unchanged.
+///
+/// # Arguments
+/// * `path` - Path to convert
+///
+/// # Returns
+/// The converted path
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    if !cfg!(windows) {
+        return path.to_path_buf();
+    }
+    to_extended_length(&path.to_string_lossy())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// The platform-independent part of [`extended_length_path`], operating on the string form.
+/// Returns `None` if the path should be left untouched.
+pub fn to_extended_length(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", unc));
+    }
+    let bytes = path.as_bytes();
+    let is_drive_absolute = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    if is_drive_absolute {
+        // Verbatim paths do not accept forward slashes as separators.
+        return Some(format!(r"\\?\{}", path.replace('/', r"\")));
+    }
+    None
+}
+
+/// Estimates whether cargo builds below `target_dir` risk exceeding the Windows
+/// `MAX_PATH` limit, given the extra depth cargo adds beneath it.
+///
+/// # Arguments
+/// * `target_dir` - The target directory builds will use
+///
+/// # Returns
+/// The estimated longest path length if it exceeds the limit, `None` otherwise
+pub fn max_path_risk(target_dir: &Path) -> Option<usize> {
+    let estimated = target_dir.as_os_str().len() + CARGO_PATH_HEADROOM;
+    (estimated > WINDOWS_MAX_PATH).then_some(estimated)
+}
+
+/// Returns the default directory where the runner keeps its own state between runs
+/// (`target/matrix-runner`), such as attachments and the last run's results.
+///
+/// # Arguments
+/// * `project_root` - Path to the project root directory
+pub fn state_dir(project_root: &Path) -> PathBuf {
+    project_root.join("target").join("matrix-runner")
+}
+
+/// Creates an empty attachment directory for a test case under
+/// `<state_dir>/attachments/<case>`. Any leftovers from a previous run are removed;
+/// since [`case_dir_name`] gives every case its own directory, this never touches the
+/// attachments of another case.
+///
+/// # Arguments
+/// * `state_dir` - The runner's state directory (see [`state_dir`])
+/// * `case_name` - Name of the test case
+///
+/// # Returns
+/// The absolute path of the attachment directory
+pub fn create_attachment_dir(state_dir: &Path, case_name: &str) -> Result<PathBuf> {
+    let dir = state_dir
+        .join("attachments")
+        .join(case_dir_name(case_name));
+
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| {
+            format!("Failed to clean up old attachment directory: {}", dir.display())
         })?;
     }
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create attachment directory: {}", dir.display()))?;
 
-    let temp_dir = tempdir()
-        .or_else(|_| tempdir_in(target_dir))
-        .with_context(|| "Failed to create temporary build directory".to_string())?;
-        
-    let path = temp_dir.path().to_path_buf();
-    
-    Ok((path, temp_dir))
+    Ok(dir)
 }
 
-/// A wrapper around `tempfile::tempdir_in` to provide more context on failure.
-fn tempdir_in<P: AsRef<Path>>(dir: P) -> std::io::Result<TempDir> {
+/// Creates an empty annotations file for a test case at
+/// `<state_dir>/annotations/<case>.txt`, replacing one left by a previous run.
+///
+/// # Arguments
+/// * `state_dir` - The runner's state directory (see [`state_dir`])
+/// * `case_name` - Name of the test case
+///
+/// # Returns
+/// The absolute path of the annotations file
+pub fn create_annotations_file(state_dir: &Path, case_name: &str) -> Result<PathBuf> {
+    let dir = state_dir.join("annotations");
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create annotations directory: {}", dir.display()))?;
+    let path = dir.join(format!("{}.txt", case_dir_name(case_name)));
+    fs::write(&path, "")
+        .with_context(|| format!("Failed to create annotations file: {}", path.display()))?;
+    Ok(path)
+}
+
+/// Parses annotations written by a test process: one `key=value` pair per line.
+/// Blank lines, `#` comments and lines without `=` are ignored; a key written again
+/// replaces its earlier value.
+///
+/// 解析测试进程写入的注解：每行一个 `key=value` 对。空行、`#` 注释和不含 `=` 的行会被忽略；
+/// 再次写入的键会替换其之前的值。
+pub fn parse_annotations(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// Reads the annotations file of a test case. A missing or unreadable file yields no annotations.
+/// 读取测试用例的注解文件。缺失或无法读取的文件不产生任何注解。
+pub fn read_annotations(path: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(path)
+        .map(|content| parse_annotations(&content))
+        .unwrap_or_default()
+}
+
+/// Recursively lists all files inside an attachment directory, sorted by path.
+/// A missing directory yields an empty list.
+///
+/// # Arguments
+/// * `dir` - The attachment directory to scan
+///
+/// # Returns
+/// The paths of all attached files
+pub fn collect_attachments(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Computes the total size of the files in a directory, recursively. Symbolic links are
+/// not followed and unreadable entries are left out, so the result is a lower bound.
+///
+/// 递归计算目录中文件的总大小。不跟随符号链接，无法读取的条目会被忽略，因此结果是一个下限。
+pub fn dir_size(dir: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+
+    total
+}
+
+/// Writes a file by writing a temporary file next to it and renaming it into place, so
+/// readers and concurrent writers never see a half-written file.
+///
+/// # Arguments
+/// * `path` - The file to write; its parent directory must exist
+/// * `contents` - The bytes to write
+///
+/// 先在目标旁写入临时文件再将其重命名到位，使读取者和并发写入者永远不会看到写了一半的文件。
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut file = tempfile::Builder::new()
+        .prefix(".matrix_runner_")
+        .tempfile_in(dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+    file.write_all(contents.as_ref())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    file.persist(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// An exclusive lock on a `<file>.lock` file next to a shared output, released on drop.
+/// 对共享输出旁的 `<file>.lock` 文件持有的排他锁，在释放（drop）时解锁。
+#[derive(Debug)]
+pub struct FileLock {
+    _file: fs::File,
+}
+
+/// Takes an exclusive lock for a file shared between processes, e.g. a report several
+/// shards write to on a shared volume. Blocks until other holders release it.
+///
+/// # Arguments
+/// * `path` - The shared file; the lock is taken on `<path>.lock`
+///
+/// 为多个进程共享的文件获取排他锁，例如多个分片在共享卷上写入的报告。阻塞直到其他持有者释放它。
+pub fn lock_file(path: &Path) -> Result<FileLock> {
+    let mut lock_name = path.as_os_str().to_os_string();
+    lock_name.push(".lock");
+    let lock_path = PathBuf::from(lock_name);
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open lock file: {}", lock_path.display()))?;
+    file.lock()
+        .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+    Ok(FileLock { _file: file })
+}
+
+/// The file cargo locks in each profile directory of a target directory while it builds.
+/// cargo 构建时在 target 目录的每个 profile 目录中锁定的文件。
+pub const CARGO_LOCK_FILE: &str = ".cargo-lock";
+
+/// Finds a build lock of `target_dir` held by another cargo process, such as a
+/// `cargo build` a developer started in the same project.
+///
+/// # Returns
+/// The path of the held lock file, or `None` if no build is running there
+///
+/// 查找 `target_dir` 中被另一个 cargo 进程持有的构建锁，例如开发者在同一项目中启动的 `cargo build`。
+/// 如果那里没有正在运行的构建，则返回 `None`。
+pub fn held_cargo_lock(target_dir: &Path) -> Option<PathBuf> {
+    let entries = fs::read_dir(target_dir).ok()?;
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(CARGO_LOCK_FILE))
+        .find(|lock_path| {
+            // Taking the lock for a moment is the only portable way to test it; it is
+            // released again when the file is closed.
+            fs::File::open(lock_path).is_ok_and(|file| {
+                matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock))
+            })
+        })
+}
+
+/// Finds the process holding a lock on a file, from `/proc/locks`.
+/// Always `None` where this cannot be checked.
+///
+/// 从 `/proc/locks` 查找持有文件锁的进程。在无法检查的平台上始终为 `None`。
+pub fn lock_holder(lock_path: &Path) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let inode = fs::metadata(lock_path).ok()?.ino();
+        let locks = fs::read_to_string("/proc/locks").ok()?;
+        // e.g. `1: FLOCK  ADVISORY  WRITE 4242 fd:01:1234567 0 EOF`; waiters have `->` after the index.
+        locks.lines().find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.get(1) == Some(&"->") {
+                return None;
+            }
+            let (pid, file) = (fields.get(4)?, fields.get(5)?);
+            let lock_inode: u64 = file.rsplit(':').next()?.parse().ok()?;
+            (lock_inode == inode).then(|| pid.parse().ok()).flatten()
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = lock_path;
+        None
+    }
+}
+
+/// A wrapper around `tempfile::tempdir_in` to provide more context on failure. A short
+/// hash keeps the directory name bounded no matter how long the case name is.
+fn tempdir_in<P: AsRef<Path>>(dir: P, case_name: &str) -> std::io::Result<TempDir> {
     tempfile::Builder::new()
-        .prefix("matrix_runner_")
+        .prefix(&format!("matrix_runner_{}_", short_hash(case_name)))
         .tempdir_in(dir)
 }
 
@@ -72,6 +410,23 @@ pub fn copy_dir_all(from: &Path, to: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Copies the contents of a directory into another, existing one, e.g. to seed a build
+/// directory with prebuilt dependencies. Files already present are overwritten.
+///
+/// # Arguments
+/// * `from` - The directory whose contents are copied
+/// * `to` - The directory receiving them
+///
+/// 将一个目录的内容复制到另一个已存在的目录中，例如以预先构建的依赖作为构建目录的起点。已存在的文件会被覆盖。
+pub fn copy_dir_contents(from: &Path, to: &Path) -> Result<()> {
+    let mut options = CopyOptions::new();
+    options.overwrite = true;
+    options.content_only = true;
+    copy(from, to, &options)
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), to.display()))?;
+    Ok(())
+}
+
 /// Checks if a path exists and is a directory.
 ///
 /// # Arguments
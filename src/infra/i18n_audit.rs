@@ -0,0 +1,145 @@
+//! # I18n Audit Module / 国际化审计模块
+//!
+//! This module compares the translation keys used in the code with the locale files:
+//! keys missing from a locale, keys no code uses any more, and a stub file to start a
+//! new locale from. The keys used in the code are collected by the build script.
+//!
+//! 此模块将代码中使用的翻译键与本地化文件进行比较：某个语言缺失的键、代码不再使用的键，
+//! 以及用于开始新语言的存根文件。代码中使用的键由构建脚本收集。
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// The keys used with `t!` in the sources, sorted / 源代码中通过 `t!` 使用的键（已排序）
+pub const USED_KEYS: &[&str] = include!(concat!(env!("OUT_DIR"), "/i18n_keys.rs"));
+
+/// The locale the other locales are compared with and stubs are copied from / 其他语言与之比较、存根从中复制的语言
+pub const BASE_LOCALE: &str = "en";
+
+/// The translations of a locale, keyed by their dotted key / 一种语言的翻译，以点分隔的键为键
+pub type Translations = BTreeMap<String, String>;
+
+/// The findings of an audit.
+/// 审计的结果。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditReport {
+    /// For each locale, the keys used in the code or defined by another locale that it lacks / 每种语言缺少的、在代码中使用或由其他语言定义的键
+    pub missing: BTreeMap<String, Vec<String>>,
+    /// Keys defined by a locale but not used in the code / 由某种语言定义但代码中未使用的键
+    pub unused: Vec<String>,
+}
+
+impl AuditReport {
+    /// Whether some locale lacks a key / 是否有语言缺少键
+    pub fn has_missing(&self) -> bool {
+        self.missing.values().any(|keys| !keys.is_empty())
+    }
+}
+
+/// Flattens a locale file into dotted keys (`[run]` + `resumed` becomes `run.resumed`).
+/// 将本地化文件展平为点分隔的键（`[run]` + `resumed` 变为 `run.resumed`）。
+pub fn parse_locale(content: &str) -> Result<Translations> {
+    fn flatten(prefix: &str, table: &toml::Table, out: &mut Translations) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match value {
+                toml::Value::Table(table) => flatten(&key, table, out),
+                toml::Value::String(text) => {
+                    out.insert(key, text.clone());
+                }
+                other => {
+                    out.insert(key, other.to_string());
+                }
+            }
+        }
+    }
+
+    let table: toml::Table = toml::from_str(content).context("Failed to parse locale file")?;
+    let mut translations = Translations::new();
+    flatten("", &table, &mut translations);
+    Ok(translations)
+}
+
+/// Reads every `<locale>.toml` file of a directory.
+/// 读取目录中的每个 `<locale>.toml` 文件。
+pub fn load_locales(dir: &Path) -> Result<BTreeMap<String, Translations>> {
+    let mut locales = BTreeMap::new();
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read locale directory: {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read locale file: {}", path.display()))?;
+        let translations =
+            parse_locale(&content).with_context(|| format!("In {}", path.display()))?;
+        locales.insert(locale.to_string(), translations);
+    }
+    Ok(locales)
+}
+
+/// Compares the keys used in the code with the locales.
+/// 将代码中使用的键与各语言进行比较。
+pub fn audit(used: &[&str], locales: &BTreeMap<String, Translations>) -> AuditReport {
+    let used: BTreeSet<&str> = used.iter().copied().collect();
+    let defined: BTreeSet<&str> = locales
+        .values()
+        .flat_map(|translations| translations.keys().map(String::as_str))
+        .collect();
+    let expected: BTreeSet<&str> = used.union(&defined).copied().collect();
+
+    let missing = locales
+        .iter()
+        .map(|(locale, translations)| {
+            let keys = expected
+                .iter()
+                .filter(|key| !translations.contains_key(**key))
+                .map(|key| key.to_string())
+                .collect();
+            (locale.clone(), keys)
+        })
+        .collect();
+    let unused = defined
+        .difference(&used)
+        .map(|key| key.to_string())
+        .collect();
+    AuditReport { missing, unused }
+}
+
+/// Renders a locale file for a new locale, with the base locale's texts as placeholders.
+/// 为新语言生成本地化文件，以基础语言的文本作为占位符。
+pub fn stub_locale(base: &Translations, locale: &str) -> String {
+    let mut sections: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for (key, text) in base {
+        let (section, name) = key.rsplit_once('.').unwrap_or(("", key.as_str()));
+        sections.entry(section).or_default().push((name, text));
+    }
+
+    let mut out = format!(
+        "# Locale stub for `{locale}`, generated from `{BASE_LOCALE}`. Translate every value.\n"
+    );
+    for (section, entries) in sections {
+        if !section.is_empty() {
+            out.push_str(&format!("\n[{section}]\n"));
+        }
+        for (name, text) in entries {
+            out.push_str(&format!(
+                "{} = {}\n",
+                name,
+                toml::Value::String(text.to_string())
+            ));
+        }
+    }
+    out
+}
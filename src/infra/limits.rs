@@ -0,0 +1,129 @@
+//! # Resource Limits Module / 资源限制模块
+//!
+//! This module applies a case's `ulimits` to the process spawned for it, so stress tests
+//! that need raised file descriptor or core dump limits don't have to be wrapped in shell
+//! scripts. Limits are set between `fork` and `exec`, so only the child is affected.
+//! On platforms without `setrlimit` (Windows), the limits are ignored with a warning.
+//!
+//! 此模块将用例的 `ulimits` 应用于为其启动的进程，使需要提高文件描述符或核心转储限制的
+//! 压力测试不必再包装在 shell 脚本中。限制在 `fork` 与 `exec` 之间设置，因此只影响子进程。
+//! 在没有 `setrlimit` 的平台（Windows）上，这些限制会被忽略并给出警告。
+
+use crate::core::config::Ulimits;
+
+/// Applies resource limits to a command before it is spawned.
+/// Raising a limit above the current hard limit requires the corresponding privileges;
+/// otherwise spawning the command fails.
+///
+/// 在命令启动前对其应用资源限制。
+/// 将限制提高到当前硬限制以上需要相应权限；否则启动命令会失败。
+#[cfg(unix)]
+pub fn apply_ulimits(cmd: &mut tokio::process::Command, ulimits: &Ulimits, _locale: &str) {
+    use crate::core::config::Limit;
+
+    if ulimits.is_empty() {
+        return;
+    }
+    let to_rlim = |limit: Option<Limit>| {
+        limit.map(|limit| match limit {
+            Limit::Value(value) => value as libc::rlim_t,
+            Limit::Unlimited => libc::RLIM_INFINITY,
+        })
+    };
+    let nofile = to_rlim(ulimits.nofile);
+    let core = to_rlim(ulimits.core);
+    let stack = to_rlim(ulimits.stack);
+
+    // SAFETY: the closure runs in the forked child before `exec`; it only calls
+    // `getrlimit`/`setrlimit`, which are async-signal-safe, and does not allocate.
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(value) = nofile {
+                set_limit(libc::RLIMIT_NOFILE, value)?;
+            }
+            if let Some(value) = core {
+                set_limit(libc::RLIMIT_CORE, value)?;
+            }
+            if let Some(value) = stack {
+                set_limit(libc::RLIMIT_STACK, value)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Raises the soft core dump size limit of a command to its hard limit before it is
+/// spawned, so a crash leaves a core dump (`collect_core_dumps`). Unlike a case's
+/// `ulimits`, this never needs privileges.
+///
+/// 在命令启动前将其核心转储大小的软限制提高到硬限制，使崩溃会留下核心转储（`collect_core_dumps`）。
+/// 与用例的 `ulimits` 不同，这从不需要特殊权限。
+#[cfg(unix)]
+pub fn raise_core_limit(cmd: &mut tokio::process::Command) {
+    // SAFETY: the closure runs in the forked child before `exec`; it only calls
+    // `getrlimit`/`setrlimit`, which are async-signal-safe, and does not allocate.
+    unsafe {
+        cmd.pre_exec(|| {
+            let mut current = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            if libc::getrlimit(libc::RLIMIT_CORE, &mut current) == 0 {
+                current.rlim_cur = current.rlim_max;
+                libc::setrlimit(libc::RLIMIT_CORE, &current);
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Core dumps are not collected on this platform.
+#[cfg(not(unix))]
+pub fn raise_core_limit(_cmd: &mut tokio::process::Command) {}
+
+/// Applies nothing but warns, since resource limits are not supported on this platform.
+#[cfg(not(unix))]
+pub fn apply_ulimits(_cmd: &mut tokio::process::Command, ulimits: &Ulimits, locale: &str) {
+    use colored::*;
+
+    if !ulimits.is_empty() {
+        println!(
+            "{}",
+            crate::infra::t!("run.ulimits_unsupported", locale = locale).yellow()
+        );
+    }
+}
+
+/// The type of the resource argument of `getrlimit`/`setrlimit`, which glibc declares differently.
+#[cfg(all(unix, target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(unix, not(all(target_os = "linux", target_env = "gnu"))))]
+type Resource = libc::c_int;
+
+/// Sets the soft limit of a resource, raising the hard limit too if needed.
+#[cfg(unix)]
+fn set_limit(resource: Resource, value: libc::rlim_t) -> std::io::Result<()> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `current` is a valid, writable `rlimit`.
+    if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let raises_hard_limit = current.rlim_max != libc::RLIM_INFINITY
+        && (value == libc::RLIM_INFINITY || value > current.rlim_max);
+    let limit = libc::rlimit {
+        rlim_cur: value,
+        rlim_max: if raises_hard_limit {
+            value
+        } else {
+            current.rlim_max
+        },
+    };
+    // SAFETY: `limit` is a valid `rlimit`.
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! # Port Broker Module / 端口代理模块
+//!
+//! This module hands out free TCP ports to test processes declaring `needs_ports`, so
+//! integration tests running at the same time never pick the same port. A port is found
+//! by letting the OS bind an ephemeral port on localhost; it is then released for the test
+//! to bind, and kept reserved in the broker until the process ends, so another case is
+//! never given it in the meantime.
+//!
+//! 此模块为声明了 `needs_ports` 的测试进程分配空闲 TCP 端口，使同时运行的集成测试永远不会选中同一端口。
+//! 端口通过让操作系统在 localhost 上绑定一个临时端口来获得；随后该端口被释放以供测试绑定，
+//! 并在代理中保持预留直到进程结束，因此在此期间不会再分配给其他用例。
+
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeSet;
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::{Arc, Mutex};
+
+/// How many ports the OS may offer per requested port before the broker gives up, in case
+/// it keeps offering ports that are already reserved.
+const ATTEMPTS_PER_PORT: usize = 16;
+
+/// The ports reserved by the running test processes. Cloning yields a handle to the same
+/// reservations.
+///
+/// 正在运行的测试进程所预留的端口。克隆会得到指向同一组预留的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct PortBroker {
+    reserved: Arc<Mutex<BTreeSet<u16>>>,
+}
+
+impl PortBroker {
+    /// Reserves free ports that no running test process holds.
+    ///
+    /// # Arguments
+    /// * `count` - How many ports to reserve
+    ///
+    /// # Returns
+    /// A guard releasing the ports when dropped, or an error if no free port can be bound
+    ///
+    /// 预留没有被任何运行中的测试进程持有的空闲端口。返回一个在被丢弃时释放这些端口的守卫；
+    /// 如果无法绑定空闲端口则返回错误。
+    pub fn reserve(&self, count: usize) -> Result<ReservedPorts> {
+        let mut reserved = self.reserved.lock().unwrap_or_else(|e| e.into_inner());
+        // The listeners stay open until all ports are found, so the OS does not offer one twice.
+        let mut listeners = Vec::with_capacity(count);
+        let mut ports = Vec::with_capacity(count);
+        for _ in 0..count * ATTEMPTS_PER_PORT {
+            if ports.len() == count {
+                break;
+            }
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+                .context("Failed to bind a free port")?;
+            let port = listener
+                .local_addr()
+                .context("Failed to read a bound port")?
+                .port();
+            if !reserved.contains(&port) {
+                ports.push(port);
+            }
+            listeners.push(listener);
+        }
+        if ports.len() < count {
+            bail!("Found only {} of {} free ports", ports.len(), count);
+        }
+        reserved.extend(&ports);
+        Ok(ReservedPorts {
+            broker: self.clone(),
+            ports,
+        })
+    }
+
+    /// Whether a port is reserved by a running test process / 端口是否被某个运行中的测试进程预留
+    pub fn is_reserved(&self, port: u16) -> bool {
+        self.reserved
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&port)
+    }
+
+    fn release(&self, ports: &[u16]) {
+        let mut reserved = self.reserved.lock().unwrap_or_else(|e| e.into_inner());
+        for port in ports {
+            reserved.remove(port);
+        }
+    }
+}
+
+/// Ports reserved for a test process. They are released when this is dropped.
+/// 为测试进程预留的端口。在此值被丢弃时释放。
+#[derive(Debug)]
+pub struct ReservedPorts {
+    broker: PortBroker,
+    ports: Vec<u16>,
+}
+
+impl ReservedPorts {
+    /// The reserved ports, in the order they are numbered in the environment / 预留的端口，按其在环境变量中的编号排序
+    pub fn ports(&self) -> &[u16] {
+        &self.ports
+    }
+}
+
+impl Drop for ReservedPorts {
+    fn drop(&mut self) {
+        self.broker.release(&self.ports);
+    }
+}
@@ -0,0 +1,273 @@
+//! # Sandbox Module / 沙箱模块
+//!
+//! This module runs the test process of a case with a `sandbox` on Linux: without network
+//! access (only loopback is up) and/or with the project directory mounted read-only, so a
+//! matrix entry can verify that the crate works offline and that its tests do not write to
+//! the source tree. When `bwrap` (bubblewrap) is on `PATH`, the process is started through
+//! it; otherwise the runner creates the namespaces itself with `unshare(2)` between `fork`
+//! and `exec`, which needs unprivileged user namespaces. Other platforms have no sandbox,
+//! so such cases fail with an error instead of running unprotected.
+//!
+//! 此模块在 Linux 上以沙箱运行带有 `sandbox` 的用例的测试进程：不允许网络访问（只有回环接口可用）
+//! 和/或以只读方式挂载项目目录，使矩阵条目可以验证 crate 能够离线工作，且其测试不会写入源码树。
+//! 当 `bwrap`（bubblewrap）在 `PATH` 上时，进程通过它启动；否则运行器会在 `fork` 与 `exec`
+//! 之间自行使用 `unshare(2)` 创建命名空间，这需要非特权用户命名空间。其他平台没有沙箱，
+//! 因此这类用例会以错误失败，而不是在无保护的情况下运行。
+
+use anyhow::{Result, bail};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::core::config::Sandbox;
+
+/// The bubblewrap executable looked up on `PATH` / 在 `PATH` 上查找的 bubblewrap 可执行文件
+pub const BWRAP: &str = "bwrap";
+
+/// The arguments running a program under bubblewrap with the given sandbox, up to and
+/// including the `--` before the program. The writable paths are bound back over a
+/// read-only project directory.
+///
+/// # Arguments
+/// * `sandbox` - The sandbox of the case
+/// * `project_root` - The project directory
+/// * `writable` - The paths inside the project directory that stay writable
+///
+/// 在给定沙箱下通过 bubblewrap 运行程序的参数，直到并包括程序前的 `--`。
+/// 可写路径会重新绑定到只读的项目目录之上。
+pub fn bwrap_args(sandbox: &Sandbox, project_root: &Path, writable: &[PathBuf]) -> Vec<OsString> {
+    let mut args: Vec<OsString> = ["--dev-bind", "/", "/", "--die-with-parent"]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    if sandbox.readonly_project {
+        args.extend(["--ro-bind".into(), project_root.into(), project_root.into()]);
+        for path in writable {
+            args.extend(["--bind".into(), path.into(), path.into()]);
+        }
+    }
+    if !sandbox.network {
+        args.push("--unshare-net".into());
+    }
+    args.push("--".into());
+    args
+}
+
+/// The paths inside the project directory that stay writable in a read-only project:
+/// its `target` directory and the runner's state directory, if they exist.
+/// 在只读项目中仍然可写的项目目录内路径：其 `target` 目录和运行器的状态目录（如果存在）。
+pub fn writable_paths(project_root: &Path, state_dir: &Path) -> Vec<PathBuf> {
+    let target = project_root.join("target");
+    let mut paths = vec![target.clone()];
+    if state_dir.starts_with(project_root) && !state_dir.starts_with(&target) {
+        paths.push(state_dir.to_path_buf());
+    }
+    paths.retain(|path| path.is_dir());
+    paths
+}
+
+/// Creates the command running a program in a sandbox. Without a sandbox, or with one
+/// restricting nothing, this is a plain command.
+///
+/// # Returns
+/// The command, or an error on platforms without a sandbox
+///
+/// 创建在沙箱中运行程序的命令。没有沙箱或沙箱未做任何限制时，返回普通命令。
+/// 在没有沙箱的平台上返回错误。
+pub fn command(
+    program: impl Into<OsString>,
+    sandbox: Option<&Sandbox>,
+    project_root: &Path,
+    state_dir: &Path,
+) -> Result<tokio::process::Command> {
+    let program = program.into();
+    let Some(sandbox) = sandbox.filter(|sandbox| !sandbox.is_open()) else {
+        return Ok(tokio::process::Command::new(program));
+    };
+    if !cfg!(target_os = "linux") {
+        bail!("`sandbox` is only supported on Linux");
+    }
+    let writable = writable_paths(project_root, state_dir);
+    if let Some(bwrap) = find_bwrap() {
+        let mut cmd = tokio::process::Command::new(bwrap);
+        cmd.args(bwrap_args(sandbox, project_root, &writable))
+            .arg(program);
+        return Ok(cmd);
+    }
+    let mut cmd = tokio::process::Command::new(program);
+    unshare(&mut cmd, sandbox, project_root, &writable)?;
+    Ok(cmd)
+}
+
+fn find_bwrap() -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(BWRAP))
+        .find(|path| path.is_file())
+}
+
+/// Moves the process into new namespaces before `exec`: a user namespace mapping the
+/// runner's own user, a network namespace with loopback up, and a mount namespace in
+/// which the project directory is remounted read-only.
+#[cfg(target_os = "linux")]
+fn unshare(
+    cmd: &mut tokio::process::Command,
+    sandbox: &Sandbox,
+    project_root: &Path,
+    writable: &[PathBuf],
+) -> Result<()> {
+    use anyhow::Context;
+    use std::ffi::CString;
+    use std::io::Error;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path =
+        |path: &Path| CString::new(path.as_os_str().as_bytes()).context("Path contains a NUL byte");
+    let root = c_path(project_root)?;
+    let writable = writable
+        .iter()
+        .map(|path| c_path(path))
+        .collect::<Result<Vec<_>>>()?;
+    // A remount inside a user namespace must keep the flags locked by the parent namespace.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `root` is a valid C string and `stat` a valid buffer.
+    if unsafe { libc::statvfs(root.as_ptr(), &mut stat) } != 0 {
+        return Err(Error::last_os_error())
+            .context("Failed to read the mount flags of the project directory");
+    }
+    let locked_flags = [
+        (libc::ST_NOSUID, libc::MS_NOSUID),
+        (libc::ST_NODEV, libc::MS_NODEV),
+        (libc::ST_NOEXEC, libc::MS_NOEXEC),
+        (libc::ST_NOATIME, libc::MS_NOATIME),
+        (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+        (libc::ST_RELATIME, libc::MS_RELATIME),
+    ]
+    .into_iter()
+    .filter(|(st, _)| stat.f_flag & *st != 0)
+    .fold(0, |flags, (_, ms)| flags | ms);
+
+    // SAFETY: these calls cannot fail for a process.
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    let uid_map = format!("{uid} {uid} 1");
+    let gid_map = format!("{gid} {gid} 1");
+    let network = sandbox.network;
+    let readonly_project = sandbox.readonly_project;
+    let mut cwd = vec![0 as libc::c_char; libc::PATH_MAX as usize];
+
+    // SAFETY: the closure runs in the forked child before `exec`. It only makes system
+    // calls on buffers prepared before the fork, and does not allocate.
+    unsafe {
+        cmd.pre_exec(move || {
+            let mut flags = libc::CLONE_NEWUSER;
+            if !network {
+                flags |= libc::CLONE_NEWNET;
+            }
+            if readonly_project {
+                flags |= libc::CLONE_NEWNS;
+            }
+            if libc::unshare(flags) != 0 {
+                return Err(Error::last_os_error());
+            }
+            write_proc(c"/proc/self/setgroups", b"deny")?;
+            write_proc(c"/proc/self/uid_map", uid_map.as_bytes())?;
+            write_proc(c"/proc/self/gid_map", gid_map.as_bytes())?;
+            if !network {
+                loopback_up()?;
+            }
+            if readonly_project {
+                let null = std::ptr::null();
+                let mount = |source: *const libc::c_char,
+                             target: &CString,
+                             flags: libc::c_ulong| {
+                    if libc::mount(source, target.as_ptr(), null, flags, std::ptr::null()) != 0 {
+                        return Err(Error::last_os_error());
+                    }
+                    Ok(())
+                };
+                // Keep the mounts below from propagating back to the runner's namespace.
+                if libc::mount(
+                    null,
+                    c"/".as_ptr(),
+                    null,
+                    libc::MS_REC | libc::MS_PRIVATE,
+                    std::ptr::null(),
+                ) != 0
+                {
+                    return Err(Error::last_os_error());
+                }
+                mount(root.as_ptr(), &root, libc::MS_BIND | libc::MS_REC)?;
+                // Bound before the remount, the writable paths keep their own mount flags.
+                for path in &writable {
+                    mount(path.as_ptr(), path, libc::MS_BIND | libc::MS_REC)?;
+                }
+                mount(
+                    null,
+                    &root,
+                    libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY | locked_flags,
+                )?;
+                // The working directory was entered before the mounts; entering it again
+                // by path puts the process in the read-only project.
+                if libc::getcwd(cwd.as_mut_ptr(), cwd.len()).is_null()
+                    || libc::chdir(cwd.as_ptr()) != 0
+                {
+                    return Err(Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unshare(
+    _cmd: &mut tokio::process::Command,
+    _sandbox: &Sandbox,
+    _project_root: &Path,
+    _writable: &[PathBuf],
+) -> Result<()> {
+    bail!("`sandbox` is only supported on Linux")
+}
+
+/// Writes a whole buffer to a file under `/proc`, without allocating.
+#[cfg(target_os = "linux")]
+fn write_proc(path: &std::ffi::CStr, content: &[u8]) -> std::io::Result<()> {
+    // SAFETY: `path` is a valid C string and `content` a valid buffer of its length.
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let written = libc::write(fd, content.as_ptr().cast(), content.len());
+        let result = if written < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        };
+        libc::close(fd);
+        result
+    }
+}
+
+/// Brings up the loopback interface of a new network namespace, which starts down.
+#[cfg(target_os = "linux")]
+fn loopback_up() -> std::io::Result<()> {
+    // SAFETY: `ifreq` is plain data, and the socket is closed on every path.
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut request: libc::ifreq = std::mem::zeroed();
+        for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+            *dst = *src as libc::c_char;
+        }
+        request.ifr_ifru.ifru_flags = (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+        let result = if libc::ioctl(fd, libc::SIOCSIFFLAGS, &request) < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        };
+        libc::close(fd);
+        result
+    }
+}
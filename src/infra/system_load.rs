@@ -0,0 +1,70 @@
+//! # System Load Module / 系统负载模块
+//!
+//! This module samples how busy the machine is, for the resource ceiling of a run
+//! (`--max-load`, `max_total_memory_gb`): the one-minute load average and the memory in
+//! use by the whole system, not only by the run's own cases, so several developers
+//! sharing a build machine keep each other's matrices in check. The load average is read
+//! with `getloadavg` on Unix and the memory from `/proc/meminfo` on Linux; elsewhere the
+//! value is unknown and its ceiling is not enforced.
+//!
+//! 此模块对机器的繁忙程度进行采样，用于一次运行的资源上限（`--max-load`、`max_total_memory_gb`）：
+//! 一分钟平均负载和整个系统（而不仅是本次运行的用例）使用的内存，使共享构建机器的多位开发者
+//! 可以相互约束各自的矩阵。平均负载在 Unix 上通过 `getloadavg` 读取，内存在 Linux 上从
+//! `/proc/meminfo` 读取；在其他平台上该值未知，其上限不会生效。
+
+/// The number of bytes in a gibibyte / 一个 GiB 的字节数
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// A sample of the system's load. Values that cannot be measured on this platform are `None`.
+/// 系统负载的一次采样。在此平台上无法测量的值为 `None`。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SystemLoad {
+    /// The one-minute load average / 一分钟平均负载
+    pub load: Option<f64>,
+    /// The memory in use by the whole system, in GiB / 整个系统使用的内存（GiB）
+    pub used_memory_gb: Option<f64>,
+}
+
+/// Samples the load of the system now.
+/// 立即对系统负载进行采样。
+pub fn sample() -> SystemLoad {
+    SystemLoad {
+        load: load_average(),
+        used_memory_gb: used_memory_gb(),
+    }
+}
+
+#[cfg(unix)]
+fn load_average() -> Option<f64> {
+    let mut loads = [0.0f64; 3];
+    // SAFETY: `loads` has room for the 3 samples requested.
+    let count = unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) };
+    (count >= 1).then_some(loads[0])
+}
+
+#[cfg(not(unix))]
+fn load_average() -> Option<f64> {
+    None
+}
+
+fn used_memory_gb() -> Option<f64> {
+    let content = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_meminfo(&content).map(|bytes| bytes as f64 / BYTES_PER_GB)
+}
+
+/// Reads the memory in use, in bytes, from the content of `/proc/meminfo`: the total
+/// memory minus what is available to new processes without swapping.
+///
+/// 从 `/proc/meminfo` 的内容中读取正在使用的内存（字节）：总内存减去新进程无需交换即可使用的内存。
+pub fn parse_meminfo(content: &str) -> Option<u64> {
+    let field = |name: &str| {
+        content.lines().find_map(|line| {
+            let value = line.strip_prefix(name)?.strip_prefix(':')?;
+            let kib: u64 = value.trim().trim_end_matches("kB").trim().parse().ok()?;
+            Some(kib * 1024)
+        })
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    Some(total.saturating_sub(available))
+}
@@ -0,0 +1,452 @@
+//! # Toolchain Module / 工具链模块
+//!
+//! This module checks that the rustup toolchains and targets referenced by cases are
+//! installed before the run starts, and installs missing ones on request
+//! (`--auto-install-toolchains`), so a matrix spanning several toolchains does not fail
+//! half-way through because one of them is missing.
+//!
+//! 此模块在运行开始前检查用例引用的 rustup 工具链和目标是否已安装，
+//! 并在请求时（`--auto-install-toolchains`）安装缺失的部分，
+//! 使跨多个工具链的矩阵不会因为缺少其中一个而在中途失败。
+
+use anyhow::{Context, Result, bail};
+use colored::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::core::config::{TestCase, is_nightly_toolchain};
+use crate::infra::t;
+
+/// A toolchain and the targets the cases need from it.
+/// 一个工具链以及用例需要它提供的目标。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainRequirement {
+    /// The toolchain name, or `None` for the project's active toolchain / 工具链名称；为 `None` 时表示项目当前激活的工具链
+    pub toolchain: Option<String>,
+    /// The target triples needed / 需要的目标三元组
+    pub targets: Vec<String>,
+    /// The rustup components needed / 需要的 rustup 组件
+    pub components: Vec<String>,
+}
+
+/// Components a toolchain lacks after [`ensure_toolchains`].
+/// [`ensure_toolchains`] 之后工具链仍缺少的组件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingComponents {
+    /// The toolchain name, or `None` for the project's active toolchain / 工具链名称；为 `None` 时表示项目当前激活的工具链
+    pub toolchain: Option<String>,
+    /// The missing components / 缺失的组件
+    pub components: Vec<String>,
+}
+
+/// Collects the toolchains and targets used by cargo cases, and the components declared
+/// by any case. The active toolchain is only listed when cases need extra targets or
+/// components from it.
+///
+/// 收集 cargo 用例使用的工具链和目标，以及任何用例声明的组件。
+/// 仅当用例需要额外的目标或组件时才会列出当前激活的工具链。
+pub fn toolchain_requirements(cases: &[TestCase]) -> Vec<ToolchainRequirement> {
+    let mut requirements: BTreeMap<Option<String>, (BTreeSet<String>, BTreeSet<String>)> =
+        BTreeMap::new();
+    for case in cases {
+        if case.command.is_some() && case.components.is_empty() {
+            continue;
+        }
+        let (targets, components) = requirements.entry(case.toolchain.clone()).or_default();
+        if case.command.is_none() {
+            targets.extend(case.target.clone());
+        }
+        components.extend(case.components.iter().cloned());
+    }
+    requirements
+        .into_iter()
+        .filter(|(toolchain, (targets, components))| {
+            toolchain.is_some() || !targets.is_empty() || !components.is_empty()
+        })
+        .map(|(toolchain, (targets, components))| ToolchainRequirement {
+            toolchain,
+            targets: targets.into_iter().collect(),
+            components: components.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Finds the cases that declare a component missing from their toolchain.
+///
+/// # Returns
+/// The missing components of each affected case, keyed by case name
+///
+/// 找出声明了其工具链所缺少组件的用例。返回每个受影响用例缺失的组件，以用例名称为键。
+pub fn cases_missing_components(
+    cases: &[TestCase],
+    missing: &[MissingComponents],
+) -> BTreeMap<String, Vec<String>> {
+    cases
+        .iter()
+        .filter_map(|case| {
+            let lacking = missing.iter().find(|m| m.toolchain == case.toolchain)?;
+            let components: Vec<String> = case
+                .components
+                .iter()
+                .filter(|component| lacking.components.contains(component))
+                .cloned()
+                .collect();
+            (!components.is_empty()).then(|| (case.name.clone(), components))
+        })
+        .collect()
+}
+
+/// Parses the output of `rustup toolchain list` or `rustup target list --installed`
+/// into the listed names.
+///
+/// 将 `rustup toolchain list` 或 `rustup target list --installed` 的输出解析为所列名称。
+pub fn parse_rustup_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a toolchain name such as `nightly` matches an installed toolchain such as
+/// `nightly-x86_64-unknown-linux-gnu`.
+///
+/// 判断诸如 `nightly` 的工具链名称是否匹配诸如 `nightly-x86_64-unknown-linux-gnu` 的已安装工具链。
+pub fn is_toolchain_installed(installed: &[String], toolchain: &str) -> bool {
+    installed.iter().any(|name| {
+        name == toolchain
+            || name
+                .strip_prefix(toolchain)
+                .is_some_and(|rest| rest.starts_with('-'))
+    })
+}
+
+/// Whether a component such as `miri` or `llvm-tools-preview` matches an installed one
+/// such as `miri-x86_64-unknown-linux-gnu` or `llvm-tools-x86_64-unknown-linux-gnu`.
+/// rustup lists host-specific components with the host triple, and components that
+/// left preview without their `-preview` suffix.
+///
+/// 判断诸如 `miri` 或 `llvm-tools-preview` 的组件是否匹配诸如 `miri-x86_64-unknown-linux-gnu`
+/// 或 `llvm-tools-x86_64-unknown-linux-gnu` 的已安装组件。rustup 列出与主机相关的组件时带有主机三元组，
+/// 并且列出已结束预览的组件时不带 `-preview` 后缀。
+pub fn is_component_installed(installed: &[String], component: &str) -> bool {
+    let stable_name = component.strip_suffix("-preview").unwrap_or(component);
+    is_toolchain_installed(installed, component) || is_toolchain_installed(installed, stable_name)
+}
+
+/// Makes sure the toolchains, targets and components used by the cases are installed.
+///
+/// Missing ones are installed with `rustup` if `install` is set. Otherwise missing
+/// toolchains stop the run before it starts, with an error listing each of them and the
+/// cases using it; missing targets are reported as a warning and the affected cases fail
+/// when they are built, while missing components are returned, for the run to skip or
+/// fail the cases declaring them. Nothing is checked if rustup is not available.
+///
+/// # Arguments
+/// * `project_root` - The project root, so a `rust-toolchain` file there is honored
+/// * `cases` - The cases that are about to run
+/// * `install` - Whether to install missing toolchains, targets and components
+/// * `locale` - The locale for the printed messages
+///
+/// # Returns
+/// The components still missing, or an error if a toolchain is missing or an installation failed
+///
+/// 确保用例使用的工具链、目标和组件已安装。返回仍然缺失的组件；缺少工具链或安装失败时返回错误。
+pub async fn ensure_toolchains(
+    project_root: &Path,
+    cases: &[TestCase],
+    install: bool,
+    locale: &str,
+) -> Result<Vec<MissingComponents>> {
+    let requirements = toolchain_requirements(cases);
+    if requirements.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(installed) = rustup_list(project_root, &["toolchain", "list"]).await else {
+        println!("{}", t!("run.rustup_unavailable", locale = locale).yellow());
+        return Ok(Vec::new());
+    };
+
+    let mut missing_components = Vec::new();
+    let mut missing_toolchains = Vec::new();
+    for requirement in requirements {
+        if let Some(toolchain) = &requirement.toolchain
+            && !is_toolchain_installed(&installed, toolchain)
+        {
+            if !install {
+                missing_toolchains.push(toolchain.clone());
+                continue;
+            }
+            println!(
+                "{}",
+                t!(
+                    "run.installing_toolchain",
+                    locale = locale,
+                    toolchain = toolchain
+                )
+                .cyan()
+            );
+            let mut args = vec![
+                "toolchain",
+                "install",
+                toolchain.as_str(),
+                "--profile",
+                "minimal",
+            ];
+            for target in &requirement.targets {
+                args.extend(["--target", target.as_str()]);
+            }
+            for component in &requirement.components {
+                args.extend(["--component", component.as_str()]);
+            }
+            run_rustup(project_root, &args).await?;
+            continue;
+        }
+
+        ensure_targets(project_root, &requirement, install, locale).await?;
+        let components = ensure_components(project_root, &requirement, install, locale).await?;
+        if !components.is_empty() {
+            missing_components.push(MissingComponents {
+                toolchain: requirement.toolchain.clone(),
+                components,
+            });
+        }
+    }
+    if !missing_toolchains.is_empty() {
+        bail!(
+            "{}",
+            t!(
+                "run.toolchains_missing",
+                locale = locale,
+                toolchains = describe_missing_toolchains(cases, &missing_toolchains)
+            )
+        );
+    }
+    Ok(missing_components)
+}
+
+/// Lists missing toolchains with the cases using each, e.g.
+/// `nightly (miri, simd); 1.70 (msrv)`.
+///
+/// 列出缺失的工具链以及使用每个工具链的用例，例如 `nightly (miri, simd); 1.70 (msrv)`。
+pub fn describe_missing_toolchains(cases: &[TestCase], missing: &[String]) -> String {
+    missing
+        .iter()
+        .map(|toolchain| {
+            let users: Vec<&str> = cases
+                .iter()
+                .filter(|case| case.toolchain.as_ref() == Some(toolchain))
+                .map(|case| case.name.as_str())
+                .collect();
+            format!("{toolchain} ({})", users.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The `rustup` arguments selecting the toolchain of a requirement.
+fn toolchain_args(requirement: &ToolchainRequirement) -> Vec<&str> {
+    match &requirement.toolchain {
+        Some(toolchain) => vec!["--toolchain", toolchain],
+        None => Vec::new(),
+    }
+}
+
+/// Checks the targets of an installed toolchain, adding the missing ones if `install` is set.
+async fn ensure_targets(
+    project_root: &Path,
+    requirement: &ToolchainRequirement,
+    install: bool,
+    locale: &str,
+) -> Result<()> {
+    if requirement.targets.is_empty() {
+        return Ok(());
+    }
+    let label = requirement.toolchain.as_deref().unwrap_or("default");
+    let mut list_args = vec!["target", "list", "--installed"];
+    list_args.extend(toolchain_args(requirement));
+    let installed_targets = rustup_list(project_root, &list_args)
+        .await
+        .unwrap_or_default();
+    let missing: Vec<&str> = requirement
+        .targets
+        .iter()
+        .filter(|target| !installed_targets.contains(target))
+        .map(String::as_str)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let targets = missing.join(", ");
+    if !install {
+        println!(
+            "{}",
+            t!(
+                "run.targets_missing",
+                locale = locale,
+                toolchain = label,
+                targets = targets
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+    println!(
+        "{}",
+        t!(
+            "run.adding_targets",
+            locale = locale,
+            toolchain = label,
+            targets = targets
+        )
+        .cyan()
+    );
+    let mut args = vec!["target", "add"];
+    args.extend(toolchain_args(requirement));
+    args.extend(&missing);
+    run_rustup(project_root, &args).await
+}
+
+/// Checks the components of an installed toolchain, adding the missing ones if `install`
+/// is set.
+///
+/// # Returns
+/// The components that are still missing
+async fn ensure_components(
+    project_root: &Path,
+    requirement: &ToolchainRequirement,
+    install: bool,
+    locale: &str,
+) -> Result<Vec<String>> {
+    if requirement.components.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut list_args = vec!["component", "list", "--installed"];
+    list_args.extend(toolchain_args(requirement));
+    let installed_components = rustup_list(project_root, &list_args)
+        .await
+        .unwrap_or_default();
+    let missing: Vec<String> = requirement
+        .components
+        .iter()
+        .filter(|component| !is_component_installed(&installed_components, component))
+        .cloned()
+        .collect();
+    if missing.is_empty() || !install {
+        return Ok(missing);
+    }
+    println!(
+        "{}",
+        t!(
+            "run.adding_components",
+            locale = locale,
+            toolchain = requirement.toolchain.as_deref().unwrap_or("default"),
+            components = missing.join(", ")
+        )
+        .cyan()
+    );
+    let mut args = vec!["component", "add"];
+    args.extend(toolchain_args(requirement));
+    args.extend(missing.iter().map(String::as_str));
+    run_rustup(project_root, &args).await?;
+    Ok(Vec::new())
+}
+
+/// Finds the cases that set `cargo_unstable_flags` without a `toolchain` of their own,
+/// when the project's active toolchain is not a nightly one.
+///
+/// # Returns
+/// The names of the affected cases
+///
+/// 当项目当前激活的工具链不是 nightly 时，找出设置了 `cargo_unstable_flags` 但自身未设置
+/// `toolchain` 的用例。返回受影响用例的名称。
+pub fn cases_needing_nightly<'a>(cases: &'a [TestCase], active_toolchain: &str) -> Vec<&'a str> {
+    if is_nightly_toolchain(active_toolchain) {
+        return Vec::new();
+    }
+    cases
+        .iter()
+        .filter(|case| !case.cargo_unstable_flags.is_empty() && case.toolchain.is_none())
+        .map(|case| case.name.as_str())
+        .collect()
+}
+
+/// Checks that the project's active toolchain is a nightly one when cases without a
+/// `toolchain` set `cargo_unstable_flags`. The active toolchain honors
+/// `rust-toolchain.toml` and rustup overrides. Nothing is checked if rustup is not available.
+///
+/// # Returns
+/// Success, or an error naming the affected cases and the active toolchain
+///
+/// 当未设置 `toolchain` 的用例设置了 `cargo_unstable_flags` 时，检查项目当前激活的工具链是否为 nightly。
+/// 当前激活的工具链会遵循 `rust-toolchain.toml` 和 rustup 覆盖设置。rustup 不可用时不进行检查。
+pub async fn check_unstable_flags_toolchain(
+    project_root: &Path,
+    cases: &[TestCase],
+    locale: &str,
+) -> Result<()> {
+    if !cases
+        .iter()
+        .any(|case| !case.cargo_unstable_flags.is_empty() && case.toolchain.is_none())
+    {
+        return Ok(());
+    }
+    let Some(active) = rustup_list(project_root, &["show", "active-toolchain"])
+        .await
+        .and_then(|names| names.into_iter().next())
+    else {
+        return Ok(());
+    };
+    let affected = cases_needing_nightly(cases, &active);
+    if !affected.is_empty() {
+        bail!(
+            "{}",
+            t!(
+                "run.unstable_flags_need_nightly",
+                locale = locale,
+                cases = affected.join(", "),
+                toolchain = active
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Checks whether `cargo nextest` is installed for the project's active toolchain.
+/// 检查项目当前激活的工具链是否安装了 `cargo nextest`。
+pub async fn nextest_installed(project_root: &Path) -> bool {
+    tokio::process::Command::new("cargo")
+        .args(["nextest", "--version"])
+        .current_dir(project_root)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Runs a rustup listing command and parses its output.
+async fn rustup_list(dir: &Path, args: &[&str]) -> Option<Vec<String>> {
+    let output = tokio::process::Command::new("rustup")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_rustup_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Runs rustup with its output shown, so download progress is visible.
+async fn run_rustup(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = tokio::process::Command::new("rustup")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .await
+        .context("Failed to execute 'rustup'")?;
+    if !status.success() {
+        bail!("'rustup {}' failed with {}", args.join(" "), status);
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! # Workspace Detection Module / 工作区检测模块
+//!
+//! This module asks `cargo metadata` for the members of a cargo workspace,
+//! so that commands can offer per-member behavior instead of assuming a single package,
+//! and for the package a project's `Cargo.toml` defines, along with the features,
+//! `rust-version` and docs.rs metadata of each package.
+//!
+//! 此模块通过 `cargo metadata` 获取 cargo 工作区的成员，
+//! 使命令能够提供按成员的行为，而不是假设只有单个包；同时获取项目 `Cargo.toml` 所定义的包，
+//! 以及每个包的 features、`rust-version` 和 docs.rs 元数据。
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A package that belongs to the workspace.
+/// 属于工作区的一个包。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMember {
+    /// The package name / 包名
+    pub name: String,
+    /// The directory containing the member's `Cargo.toml` / 包含该成员 `Cargo.toml` 的目录
+    pub dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    name: String,
+    manifest_path: PathBuf,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    rust_version: Option<String>,
+    /// `[package.metadata]`, free-form; only docs.rs's part is read, leniently, so an
+    /// unexpected table of another tool cannot break the parsing of the package.
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+/// A package of the workspace, with what its manifest says about how it is built.
+/// 工作区中的一个包，以及其清单中关于如何构建它的信息。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageManifest {
+    /// The package name / 包名
+    pub name: String,
+    /// The `[features]` table: each feature and what it enables / `[features]` 表：每个 feature 及其启用的内容
+    pub features: BTreeMap<String, Vec<String>>,
+    /// The minimum supported Rust version (`rust-version`) / 最低支持的 Rust 版本（`rust-version`）
+    pub rust_version: Option<String>,
+    /// How docs.rs builds the documentation (`[package.metadata.docs.rs]`) / docs.rs 如何构建文档（`[package.metadata.docs.rs]`）
+    pub docs_rs: Option<DocsRsMetadata>,
+}
+
+/// The `[package.metadata.docs.rs]` table, as far as it decides what docs.rs builds.
+/// `[package.metadata.docs.rs]` 表中决定 docs.rs 构建内容的部分。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct DocsRsMetadata {
+    /// Build with `--all-features` / 使用 `--all-features` 构建
+    pub all_features: bool,
+    /// The features to enable / 要启用的 features
+    pub features: Vec<String>,
+    /// Build with `--no-default-features` / 使用 `--no-default-features` 构建
+    pub no_default_features: bool,
+    /// The target shown by default / 默认显示的目标
+    pub default_target: Option<String>,
+    /// The targets to document / 要生成文档的目标
+    pub targets: Vec<String>,
+}
+
+/// Lists the workspace members of the project in `dir`.
+///
+/// # Returns
+/// The members sorted by name, or `None` if `cargo metadata` is unavailable or fails
+/// (e.g. there is no `Cargo.toml`)
+///
+/// 列出 `dir` 中项目的工作区成员。
+pub async fn workspace_members(dir: &Path) -> Option<Vec<WorkspaceMember>> {
+    let output = tokio::process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(dir)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_workspace_members(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Asks `cargo metadata` for the name of the package defined by the `Cargo.toml` in `dir`.
+/// Unlike reading the manifest by hand, this follows workspace inheritance and fails
+/// with cargo's own message on an invalid manifest.
+///
+/// # Returns
+/// The package name, or an empty string for a virtual workspace manifest, which has no
+/// package of its own
+///
+/// 通过 `cargo metadata` 获取 `dir` 中 `Cargo.toml` 所定义的包名。与手动读取清单不同，
+/// 它会遵循工作区继承，并在清单无效时以 cargo 自身的消息失败。返回包名；虚拟工作区清单没有自己的包，返回空字符串。
+pub async fn package_name(dir: &Path) -> Result<String> {
+    let metadata = cargo_metadata(dir).await?;
+    parse_package_name(&metadata, &dir.join("Cargo.toml"))
+        .context("Failed to parse the output of `cargo metadata`")
+}
+
+/// Asks `cargo metadata` for the manifests of the packages of the project in `dir`.
+///
+/// # Returns
+/// The packages sorted by name, or an error if `cargo metadata` fails
+///
+/// 通过 `cargo metadata` 获取 `dir` 中项目各个包的清单。返回按名称排序的包；`cargo metadata` 失败时返回错误。
+pub async fn package_manifests(dir: &Path) -> Result<Vec<PackageManifest>> {
+    let metadata = cargo_metadata(dir).await?;
+    parse_package_manifests(&metadata).context("Failed to parse the output of `cargo metadata`")
+}
+
+async fn cargo_metadata(dir: &Path) -> Result<String> {
+    let output = tokio::process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(dir)
+        .output()
+        .await
+        .context("Failed to execute 'cargo metadata'")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`cargo metadata` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Finds the package whose manifest is `manifest_path` in the output of
+/// `cargo metadata --no-deps --format-version 1`.
+///
+/// # Returns
+/// The package name, an empty string if no package has that manifest (a virtual
+/// manifest), or `None` if the output cannot be parsed
+///
+/// 在 `cargo metadata --no-deps --format-version 1` 的输出中查找清单为 `manifest_path` 的包。
+pub fn parse_package_name(metadata_json: &str, manifest_path: &Path) -> Option<String> {
+    let metadata: Metadata = serde_json::from_str(metadata_json).ok()?;
+    Some(
+        metadata
+            .packages
+            .into_iter()
+            .find(|package| package.manifest_path == manifest_path)
+            .map(|package| package.name)
+            .unwrap_or_default(),
+    )
+}
+
+/// Parses the output of `cargo metadata --no-deps --format-version 1`.
+/// 解析 `cargo metadata --no-deps --format-version 1` 的输出。
+pub fn parse_workspace_members(metadata_json: &str) -> Option<Vec<WorkspaceMember>> {
+    let metadata: Metadata = serde_json::from_str(metadata_json).ok()?;
+    let mut members: Vec<WorkspaceMember> = metadata
+        .packages
+        .into_iter()
+        .map(|package| WorkspaceMember {
+            name: package.name,
+            dir: package
+                .manifest_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        })
+        .collect();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(members)
+}
+
+/// Parses the packages of the output of `cargo metadata --no-deps --format-version 1`.
+/// 解析 `cargo metadata --no-deps --format-version 1` 输出中的包。
+pub fn parse_package_manifests(metadata_json: &str) -> Option<Vec<PackageManifest>> {
+    let metadata: Metadata = serde_json::from_str(metadata_json).ok()?;
+    let mut packages: Vec<PackageManifest> = metadata
+        .packages
+        .into_iter()
+        .map(|package| PackageManifest {
+            name: package.name,
+            features: package.features,
+            rust_version: package.rust_version,
+            docs_rs: package
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("docs")?.get("rs"))
+                .and_then(|docs_rs| serde_json::from_value(docs_rs.clone()).ok()),
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(packages)
+}
@@ -1,4 +1,4 @@
-use matrix_runner::{cli, init};
+use matrix_runner::{cli, core::MatrixError, init};
 use std::process::ExitCode;
 
 #[tokio::main]
@@ -7,10 +7,10 @@ async fn main() -> ExitCode {
     // to ensure help messages are translated correctly.
     let args: Vec<String> = std::env::args().collect();
     let mut lang_override = None;
-    if let Some(pos) = args.iter().position(|s| s == "--lang") {
-        if let Some(val) = args.get(pos + 1) {
-            lang_override = Some(val.clone());
-        }
+    if let Some(pos) = args.iter().position(|s| s == "--lang")
+        && let Some(val) = args.get(pos + 1)
+    {
+        lang_override = Some(val.clone());
     }
 
     if let Some(lang) = lang_override {
@@ -27,7 +27,7 @@ async fn main() -> ExitCode {
         Ok(_) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("Error: {}", e);
-            ExitCode::FAILURE
+            ExitCode::from(MatrixError::exit_code_for(&e))
         }
     }
 }
@@ -1,15 +1,24 @@
 //! # Reporting Module / 报告模块
 //!
 //! This module handles the generation and display of test reports in multiple formats.
-//! It provides functionality for creating styled HTML reports and printing colorful,
-//! formatted summaries to the console with internationalization support.
+//! It provides functionality for creating styled HTML reports, JSON results, JUnit XML reports, reproduction bundles
+//! for failed cases, a live dashboard of a run in progress and printing colorful, formatted
+//! summaries to the console with internationalization support.
 //!
 //! 此模块处理多种格式的测试报告生成和显示。
-//! 它提供创建样式化 HTML 报告和在控制台打印彩色格式化摘要的功能，支持国际化。
+//! 它提供创建样式化 HTML 报告、JSON 结果、JUnit XML 报告、失败用例的复现包、进行中运行的实时仪表盘和在控制台打印彩色格式化摘要的功能，支持国际化。
 
 pub mod console;
+pub mod dashboard;
+pub mod diff;
+pub mod extract;
 pub mod html;
+pub mod json;
+pub mod junit;
+pub mod partial;
+pub mod repro;
 
 // Re-export common reporting functions
 pub use console::{print_summary, print_unexpected_failure_details};
-pub use html::generate_html_report; 
\ No newline at end of file
+pub use html::generate_html_report;
+pub use json::generate_json_report; 
\ No newline at end of file
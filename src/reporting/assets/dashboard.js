@@ -0,0 +1,100 @@
+// Follows the events of the run and keeps the counts and the table of cases up to date.
+(function () {
+    const labels = JSON.parse(document.getElementById('labels').textContent);
+    const cases = document.getElementById('cases');
+    const runState = document.getElementById('run-state');
+    const counts = { total: 0, running: 0, passed: 0, failed: 0, skipped: 0 };
+    const rows = new Map();
+    let finished = false;
+
+    // The CSS class, label and count of a finished case, from its status key.
+    const statuses = {
+        passed: ['Passed', 'passed'],
+        built: ['Built', 'passed'],
+        failed: ['Failed', 'failed'],
+        timeout: ['Timeout', 'failed'],
+        skipped: ['Skipped', 'skipped'],
+    };
+
+    function rowOf(name) {
+        let row = rows.get(name);
+        if (!row) {
+            row = document.createElement('tr');
+            const status = document.createElement('td');
+            status.className = 'status-col';
+            status.appendChild(document.createElement('div'));
+            const caseName = document.createElement('td');
+            caseName.textContent = name;
+            const duration = document.createElement('td');
+            duration.className = 'duration-cell';
+            row.append(status, caseName, duration);
+            cases.appendChild(row);
+            rows.set(name, row);
+        }
+        return row;
+    }
+
+    function setStatus(row, className, label) {
+        const status = row.cells[0].firstChild;
+        status.className = 'status-cell status-' + className;
+        status.textContent = label;
+    }
+
+    function render() {
+        for (const [key, count] of Object.entries(counts)) {
+            document.getElementById('count-' + key).textContent = count;
+        }
+    }
+
+    function apply(event) {
+        switch (event.event) {
+            case 'run_started':
+                counts.total = event.total;
+                runState.textContent = labels.running;
+                break;
+            case 'case_started':
+                counts.running += 1;
+                setStatus(rowOf(event.case), 'Running', labels.status.running);
+                break;
+            case 'case_finished': {
+                const row = rowOf(event.case);
+                if (row.cells[0].firstChild.classList.contains('status-Running')) {
+                    counts.running = Math.max(0, counts.running - 1);
+                }
+                const [className, count] = statuses[event.status] || statuses.failed;
+                if (event.allowed_failure) {
+                    setStatus(row, 'Allowed-Failure', labels.status.allowed_failure);
+                } else {
+                    setStatus(row, className, labels.status[event.status] || event.status);
+                }
+                counts[count] += 1;
+                if (event.duration_secs != null) {
+                    row.cells[2].textContent = event.duration_secs.toFixed(2) + 's';
+                }
+                break;
+            }
+            case 'run_finished':
+                finished = true;
+                counts.running = 0;
+                counts.passed = event.passed;
+                counts.failed = event.failed;
+                counts.skipped = event.skipped;
+                runState.textContent = event.success ? labels.succeeded : labels.failed;
+                runState.className = 'run-state ' + (event.success ? 'status-Passed' : 'status-Failed');
+                break;
+        }
+        render();
+    }
+
+    const source = new EventSource('events');
+    source.onmessage = function (message) {
+        apply(JSON.parse(message.data));
+    };
+    // The stream ends with the run; only an earlier end means the runner went away.
+    source.onerror = function () {
+        source.close();
+        if (!finished) {
+            runState.textContent = labels.disconnected;
+        }
+    };
+})();
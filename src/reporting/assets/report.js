@@ -5,4 +5,20 @@ function toggleOutput(id) {
     } else {
         element.style.display = 'none';
     }
-} 
\ No newline at end of file
+} 
+
+// Full logs of large reports live in sidecar scripts that are only loaded when opened.
+function loadOutput(details) {
+    if (!details.open || details.dataset.loaded) {
+        return;
+    }
+    details.dataset.loaded = 'true';
+    const script = document.createElement('script');
+    script.src = details.dataset.src;
+    document.body.appendChild(script);
+}
+
+// Called by a sidecar script with the full log it holds.
+function matrixRunnerOutput(id, text) {
+    document.getElementById(id).textContent = text;
+}
@@ -7,10 +7,44 @@
 //! 此模块处理控制台中测试报告的生成和显示。
 //! 它提供打印彩色格式化摘要的功能，支持国际化。
 
-use colored::*;
-use crate::core::models::{FailureReason, TestResult};
-use crate::infra::t;
+use crate::core::anomaly::DurationAnomaly;
+use crate::core::axis::{Axis, AxisValueSummary};
+use crate::core::batch::{ProjectOutcome, ProjectResult};
+use crate::core::columns::SummaryColumn;
+use crate::core::context::RunContext;
+use crate::core::costs::{self, CaseCost, SummarySort};
+use crate::core::error::MatrixError;
+use crate::core::explain::{Check, Explanation};
+use crate::core::feature_compare::FeatureComparison;
+use crate::core::flakiness::FlakyCase;
+use crate::core::models::{FailureReason, TestResult, TestTiming};
+use crate::core::owners::OwnerFailures;
 use crate::infra::command::format_build_error_output;
+use crate::infra::fs::case_dir_name;
+use crate::infra::t;
+use crate::reporting::diff::unified_diff;
+use crate::reporting::extract::{assertion_values, extract_failure_context};
+use chrono::NaiveDate;
+use colored::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of individual tests listed under "slowest tests" in the summary.
+/// 摘要中“最慢的测试”部分列出的单个测试数量。
+const SLOWEST_TESTS_SHOWN: usize = 10;
+
+/// The directory inside the runner's state directory that full failure logs are written to.
+/// 运行器状态目录中写入完整失败日志的目录。
+pub const LOGS_DIR: &str = "logs";
+
+/// The prefix of the line `--stable-output` prints for each finished case.
+/// `--stable-output` 为每个完成的用例打印的行的前缀。
+pub const STABLE_RESULT_PREFIX: &str = "MATRIX-RUNNER-RESULT";
+
+/// The prefix of the final status line printed by `--stable-output`.
+/// `--stable-output` 打印的最终状态行的前缀。
+pub const STABLE_STATUS_PREFIX: &str = "MATRIX-RUNNER-STATUS";
 
 /// Prints a formatted summary of test results to the console.
 /// Displays a table with test status, name, duration, and retry information,
@@ -22,9 +56,11 @@ use crate::infra::command::format_build_error_output;
 ///
 /// # Arguments / 参数
 /// * `results` - A slice of test results to summarize
-///               要总结的测试结果切片
-/// * `locale` - The language locale to use for messages
-///              用于消息的语言区域设置
+///   要总结的测试结果切片
+/// * `context` - The language of the messages and the platform allowed failures are decided on
+///   消息的语言以及判断允许失败所依据的平台
+/// * `today` - The date allowed failures are decided on
+///   判断允许失败所依据的日期
 ///
 /// # Output Format / 输出格式
 /// ```text
@@ -35,11 +71,43 @@ use crate::infra::command::format_build_error_output;
 ///   - Allowed Failure  | test_case_3                             |     2.10s
 ///   - Skipped          | test_case_4                             |       N/A
 /// ```
-pub fn print_summary(results: &[TestResult], locale: &str) {
+pub fn print_summary(results: &[TestResult], context: &RunContext, today: NaiveDate) {
+    print_summary_with_costs(results, &BTreeMap::new(), None, &[], context, today);
+}
+
+/// Prints the summary like [`print_summary`], adding the build time and target directory
+/// size of each case that recorded them, optionally sorted by one of them. With
+/// `columns`, the rows show those columns instead, under a header row.
+///
+/// 像 [`print_summary`] 一样打印摘要，并为记录了构建时间和 target 目录大小的用例添加这两项，
+/// 可以按其中一项排序。给出 `columns` 时，各行改为在表头行下显示这些列。
+///
+/// # Output Format / 输出格式
+/// ```text
+///   - Passed           | big-features                            |    41.20s | build 38.90s |   1.2 GiB
+/// ```
+pub fn print_summary_with_costs(
+    results: &[TestResult],
+    costs: &BTreeMap<String, CaseCost>,
+    sort_by: Option<SummarySort>,
+    columns: &[SummaryColumn],
+    context: &RunContext,
+    today: NaiveDate,
+) {
+    let locale = context.locale.as_str();
     println!("\n{}", t!("report.summary_banner", locale = locale).bold());
 
-    for result in results {
-        let status_str = result.get_status_str(locale);
+    let ordered: Vec<&TestResult> = match sort_by {
+        Some(sort) => costs::sort_by_cost(results, costs, sort),
+        None => results.iter().collect(),
+    };
+    if !columns.is_empty() {
+        print_summary_columns(&ordered, costs, columns, context, today);
+        print_slowest_tests(results, locale);
+        return;
+    }
+    for result in ordered {
+        let status_str = result.get_status_str(context, today);
         let duration_str = result
             .get_duration()
             .map(|d| format!("{:.2?}", d))
@@ -55,52 +123,431 @@ pub fn print_summary(results: &[TestResult], locale: &str) {
             }
         };
 
-        let status_colored = match result {
-            TestResult::Passed { .. } => status_str.green(),
-            TestResult::Failed { case, .. } => {
-                let current_os = std::env::consts::OS;
-                if case.allow_failure.iter().any(|os| os == current_os) {
-                    status_str.yellow()
-                } else {
-                    status_str.red()
-                }
+        let status_colored = color_status(result, &status_str, context, today);
+
+        let cost_str = match costs.get(name) {
+            Some(cost) => {
+                let build = cost
+                    .build_duration
+                    .map(|d| format!("{:.2?}", d))
+                    .unwrap_or_else(|| "N/A".to_string());
+                format!(
+                    " | {} {:>10} | {:>9}",
+                    t!("report.build_time_label", locale = locale),
+                    build,
+                    costs::format_size(cost.target_bytes)
+                )
             }
-            TestResult::Skipped => status_str.dimmed(),
+            None => String::new(),
         };
 
         println!(
-            "  - {:<18} | {:<40} | {:>10} {}",
-            status_colored, name, duration_str, retries_str
+            "  - {:<18} | {:<40} | {:>10}{} {}",
+            status_colored, name, duration_str, cost_str, retries_str
+        );
+    }
+
+    print_slowest_tests(results, locale);
+}
+
+/// Colors the status text of a result as the summary shows it.
+fn color_status(result: &TestResult, text: &str, context: &RunContext, today: NaiveDate) -> ColoredString {
+    match result {
+        TestResult::Passed { .. } => text.green(),
+        TestResult::Failed { .. } if result.is_allowed_failure(context, today) => text.yellow(),
+        TestResult::Failed { .. } => text.red(),
+        TestResult::Built { .. } => text.cyan(),
+        TestResult::Skipped { .. } => text.dimmed(),
+    }
+}
+
+/// Prints the summary rows with the given columns under a header row. Each column is as
+/// wide as its longest value; numeric columns are aligned to the right.
+/// 在表头行下按给定的列打印摘要行。每列的宽度等于其最长的值；数字列右对齐。
+fn print_summary_columns(
+    results: &[&TestResult],
+    costs: &BTreeMap<String, CaseCost>,
+    columns: &[SummaryColumn],
+    context: &RunContext,
+    today: NaiveDate,
+) {
+    let headers: Vec<String> = columns.iter().map(|column| column.header(&context.locale)).collect();
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| {
+            columns
+                .iter()
+                .map(|column| column.value(result, costs.get(result.case_name()), context, today))
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([headers[i].chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let pad = |cell: &str, i: usize| {
+        if columns[i].is_numeric() {
+            format!("{cell:>width$}", width = widths[i])
+        } else {
+            format!("{cell:<width$}", width = widths[i])
+        }
+    };
+
+    let header: Vec<String> = headers.iter().enumerate().map(|(i, cell)| pad(cell, i)).collect();
+    println!("    {}", header.join(" | ").bold());
+    for (result, row) in results.iter().zip(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match columns[i] {
+                SummaryColumn::Status => color_status(result, &pad(cell, i), context, today).to_string(),
+                _ => pad(cell, i),
+            })
+            .collect();
+        println!("  - {}", cells.join(" | "));
+    }
+}
+
+/// Prints the slowest individual tests across the whole matrix.
+/// Nothing is printed unless libtest reported per-test timings.
+///
+/// 打印整个矩阵中最慢的单个测试。
+/// 仅当 libtest 报告了各个测试的耗时时才会打印。
+fn print_slowest_tests(results: &[TestResult], locale: &str) {
+    let mut timings: Vec<(&str, &TestTiming)> = results
+        .iter()
+        .flat_map(|r| r.get_test_timings().iter().map(move |t| (r.case_name(), t)))
+        .collect();
+    if timings.is_empty() {
+        return;
+    }
+    timings.sort_by_key(|(_, timing)| std::cmp::Reverse(timing.duration));
+
+    println!("\n{}", t!("report.slowest_tests", locale = locale).bold());
+    for (case_name, timing) in timings.into_iter().take(SLOWEST_TESTS_SHOWN) {
+        println!(
+            "  {:>10} | {} :: {}",
+            format!("{:.2?}", timing.duration),
+            case_name.cyan(),
+            timing.name
+        );
+    }
+}
+
+/// Prints the cases whose duration deviated from their `expected_secs`.
+/// Nothing is printed if there are none.
+///
+/// 打印耗时偏离其 `expected_secs` 的用例。如果没有则不打印任何内容。
+pub fn print_anomalies(anomalies: &[DurationAnomaly], locale: &str) {
+    if anomalies.is_empty() {
+        return;
+    }
+
+    println!("\n{}", t!("report.anomalies_banner", locale = locale).yellow().bold());
+    for anomaly in anomalies {
+        println!(
+            "  - {:<40} | {}",
+            anomaly.case,
+            t!(
+                "report.anomaly_detail",
+                locale = locale,
+                actual = format!("{:.2}", anomaly.actual_secs),
+                expected = anomaly.expected_secs,
+                ratio = format!("{:.1}", anomaly.ratio)
+            )
+        );
+    }
+}
+
+/// Prints the cases that only passed after retries, highest flaky score first.
+/// Nothing is printed if there are none.
+///
+/// 打印仅在重试后才通过的用例，不稳定分数最高的排在最前。如果没有则不打印任何内容。
+pub fn print_flakiness(cases: &[FlakyCase], locale: &str) {
+    if cases.is_empty() {
+        return;
+    }
+
+    println!("\n{}", t!("report.flakiness_banner", locale = locale).yellow().bold());
+    for case in cases {
+        println!(
+            "  - {:<40} | {}",
+            case.case,
+            t!(
+                "report.flakiness_detail",
+                locale = locale,
+                retries = case.retries,
+                retried_runs = case.retried_runs,
+                runs = case.runs,
+                score = format!("{:.0}", case.flaky_score * 100.0)
+            )
         );
     }
 }
 
+/// Prints the results aggregated per axis value, lowest pass rate first within each axis.
+/// Nothing is printed if no axis tells cases apart.
+///
+/// 打印按维度取值汇总的结果，每个维度内通过率最低的排在最前。如果没有维度能区分用例则不打印任何内容。
+pub fn print_axis_summary(axes: &[AxisValueSummary], locale: &str) {
+    if axes.is_empty() {
+        return;
+    }
+
+    println!("\n{}", t!("report.axes_banner", locale = locale).bold());
+    let mut current_axis = None;
+    for summary in axes {
+        if current_axis != Some(summary.axis) {
+            current_axis = Some(summary.axis);
+            println!("  {}", axis_label(summary.axis, locale).bold());
+        }
+        let detail = t!(
+            "report.axis_detail",
+            locale = locale,
+            passed = summary.passed,
+            total = summary.total(),
+            rate = format!("{:.0}", summary.pass_rate() * 100.0)
+        );
+        let detail = if summary.passed == 0 {
+            detail.red()
+        } else if summary.failed > 0 {
+            detail.yellow()
+        } else {
+            detail.green()
+        };
+        println!("    - {:<36} | {}", summary.value, detail);
+    }
+}
+
+/// Prints the decision trail of `run --explain`: each check with its result, whether the
+/// case runs on this runner and the command it runs. Checks that keep the case out of
+/// the run are red; those that only inform are not colored.
+///
+/// 打印 `run --explain` 的决策轨迹：每项检查及其结果、用例是否在此运行器上运行以及它运行的命令。
+/// 将用例排除在运行之外的检查显示为红色；仅提供信息的检查不着色。
+pub fn print_explanation(explanation: &Explanation, locale: &str) {
+    println!("\n{}", t!("explain.banner", locale = locale, name = &explanation.name).bold());
+    for step in &explanation.steps {
+        let line = explain_check(&step.check, step.passed, locale);
+        let line = match (step.check.excludes(), step.passed) {
+            (true, true) => line.green(),
+            (true, false) => line.red(),
+            (false, _) => line.normal(),
+        };
+        println!("  - {}", line);
+    }
+    let verdict = if explanation.selected {
+        t!("explain.selected", locale = locale).green().bold()
+    } else {
+        t!("explain.not_selected", locale = locale).red().bold()
+    };
+    println!("{}", verdict);
+    println!("{}", t!("explain.command", locale = locale, command = &explanation.command));
+}
+
+fn explain_check(check: &Check, passed: bool, locale: &str) -> String {
+    match check {
+        Check::Selector { expression: None } => t!("explain.select_none", locale = locale),
+        Check::Selector { expression: Some(expression) } if passed => {
+            t!("explain.select_matched", locale = locale, expression = expression)
+        }
+        Check::Selector { expression: Some(expression) } => {
+            t!("explain.select_not_matched", locale = locale, expression = expression)
+        }
+        Check::Network { offline_only: false, .. } => t!("explain.offline_not_given", locale = locale),
+        Check::Network { .. } if passed => t!("explain.offline_kept", locale = locale),
+        Check::Network { .. } => t!("explain.offline_skipped", locale = locale),
+        Check::Arch { allowed, .. } if allowed.is_empty() => t!("explain.arch_any", locale = locale),
+        Check::Arch { allowed, host } if passed => {
+            t!("explain.arch_matched", locale = locale, allowed = allowed.join(", "), host = host)
+        }
+        Check::Arch { allowed, host } => {
+            t!("explain.arch_not_matched", locale = locale, allowed = allowed.join(", "), host = host)
+        }
+        Check::AllowFailure { os } if passed => t!("explain.allow_failure_yes", locale = locale, os = os),
+        Check::AllowFailure { os } => t!("explain.allow_failure_no", locale = locale, os = os),
+        Check::Runner { runners: None, .. } => t!("explain.runner_single", locale = locale),
+        Check::Runner {
+            position,
+            runners: Some((total, _)),
+        } if passed => t!(
+            "explain.runner_assigned",
+            locale = locale,
+            position = position,
+            runner = position % total,
+            total = total
+        ),
+        Check::Runner {
+            position,
+            runners: Some((total, index)),
+        } => t!(
+            "explain.runner_elsewhere",
+            locale = locale,
+            position = position,
+            runner = position % total,
+            total = total,
+            index = index
+        ),
+        Check::After { name } if passed => t!("explain.after_here", locale = locale, name = name),
+        Check::After { name } => t!("explain.after_absent", locale = locale, name = name),
+    }
+    .to_string()
+}
+
+/// Prints the unexpected failures grouped by owner, so everyone sees which cases are theirs.
+/// Nothing is printed unless the matrix has `[owners]` and something failed.
+///
+/// 按负责人分组打印意外失败，使每个人都能看到哪些用例归自己处理。
+/// 仅当矩阵配置了 `[owners]` 且有失败时才会打印。
+pub fn print_failures_by_owner(groups: &[OwnerFailures], locale: &str) {
+    if groups.is_empty() {
+        return;
+    }
+
+    println!("\n{}", t!("report.owners_banner", locale = locale).bold());
+    for group in groups {
+        let owner = match &group.owner {
+            Some(owner) => owner.bold().to_string(),
+            None => t!("report.owners_unowned", locale = locale).dimmed().to_string(),
+        };
+        println!("  - {}: {}", owner, group.cases.join(", ").red());
+    }
+}
+
+/// The localized name of an axis.
+/// 维度的本地化名称。
+pub fn axis_label(axis: Axis, locale: &str) -> String {
+    match axis {
+        Axis::Feature => t!("report.axis_feature", locale = locale),
+        Axis::Toolchain => t!("report.axis_toolchain", locale = locale),
+        Axis::Target => t!("report.axis_target", locale = locale),
+        Axis::Package => t!("report.axis_package", locale = locale),
+    }
+    .to_string()
+}
+
+/// Prints the outcome of every project of a `batch` run, followed by the totals.
+/// 打印 `batch` 运行中每个项目的结果，然后打印总计。
+pub fn print_batch_summary(results: &[ProjectResult], locale: &str) {
+    println!("\n{}", t!("batch.summary_banner", locale = locale).bold());
+    for result in results {
+        let label = batch_outcome_label(result.outcome, locale);
+        let label = match result.outcome {
+            ProjectOutcome::Passed => label.green(),
+            ProjectOutcome::Failed | ProjectOutcome::Error => label.red(),
+            ProjectOutcome::Cancelled => label.yellow(),
+            ProjectOutcome::Skipped => label.dimmed(),
+        };
+        let duration = match result.outcome {
+            ProjectOutcome::Skipped => "N/A".to_string(),
+            _ => format!("{:.2?}", result.duration),
+        };
+        println!(
+            "  - {:<18} | {:<40} | {:>10}",
+            label,
+            result.entry.project_dir.display(),
+            duration
+        );
+    }
+    let passed = results
+        .iter()
+        .filter(|result| result.outcome == ProjectOutcome::Passed)
+        .count();
+    let totals = t!(
+        "batch.totals",
+        locale = locale,
+        passed = passed,
+        total = results.len()
+    );
+    if passed == results.len() {
+        println!("{}", totals.green().bold());
+    } else {
+        println!("{}", totals.red().bold());
+    }
+}
+
+/// Prints the tests whose outcome differs between two feature sets, for `compare-features`.
+/// 打印在两组 feature 之间结果不同的测试，用于 `compare-features`。
+pub fn print_feature_comparison(comparison: &FeatureComparison, features_a: &str, features_b: &str, locale: &str) {
+    println!("\n{}", t!("compare.summary_banner", locale = locale).bold());
+    println!("{}", t!("compare.sets", locale = locale, a = features_a, b = features_b));
+    let sections = [
+        (&comparison.fail_only_in_b, t!("compare.fail_only_in_b", locale = locale, count = comparison.fail_only_in_b.len())),
+        (&comparison.fail_only_in_a, t!("compare.fail_only_in_a", locale = locale, count = comparison.fail_only_in_a.len())),
+        (&comparison.only_in_a, t!("compare.only_in_a", locale = locale, count = comparison.only_in_a.len())),
+        (&comparison.only_in_b, t!("compare.only_in_b", locale = locale, count = comparison.only_in_b.len())),
+    ];
+    for (tests, header) in sections {
+        if tests.is_empty() {
+            continue;
+        }
+        println!("\n{}", header.yellow());
+        for test in tests {
+            println!("  - {test}");
+        }
+    }
+    if comparison.has_differing_outcomes() {
+        let count = comparison.fail_only_in_a.len() + comparison.fail_only_in_b.len();
+        println!("\n{}", t!("compare.differs", locale = locale, count = count).red().bold());
+    } else {
+        println!(
+            "\n{}",
+            t!("compare.consistent", locale = locale, count = comparison.common).green().bold()
+        );
+    }
+}
+
+/// The localized label of a project outcome.
+/// 项目结果的本地化标签。
+pub fn batch_outcome_label(outcome: ProjectOutcome, locale: &str) -> String {
+    match outcome {
+        ProjectOutcome::Passed => t!("batch.outcome_passed", locale = locale),
+        ProjectOutcome::Failed => t!("batch.outcome_failed", locale = locale),
+        ProjectOutcome::Error => t!("batch.outcome_error", locale = locale),
+        ProjectOutcome::Cancelled => t!("batch.outcome_cancelled", locale = locale),
+        ProjectOutcome::Skipped => t!("batch.outcome_skipped", locale = locale),
+    }
+    .to_string()
+}
+
 /// Prints detailed information about unexpected test failures.
-/// Shows the full output and error details for each test that failed unexpectedly,
+/// Shows the relevant part of the output and error details for each test that failed unexpectedly,
 /// helping developers debug issues. Only displays failures that were not marked
 /// as allowed failures for the current platform.
 ///
 /// 打印意外测试失败的详细信息。
-/// 显示每个意外失败测试的完整输出和错误详情，
+/// 显示每个意外失败测试输出的相关部分和错误详情，
 /// 帮助开发者调试问题。仅显示在当前平台上未标记为允许失败的失败测试。
 ///
 /// # Arguments / 参数
 /// * `unexpected_failures` - A slice of test results that failed unexpectedly
-///                           意外失败的测试结果切片
+///   意外失败的测试结果切片
+/// * `log_dir` - Directory to write the full logs of trimmed outputs to (写入被裁剪输出的完整日志的目录)
 /// * `locale` - The language locale to use for messages
-///              用于消息的语言区域设置
+///   用于消息的语言区域设置
 ///
 /// # Behavior / 行为
 /// - Returns early if no unexpected failures are found
 /// - Formats build errors differently from test execution errors
+/// - Shows only the relevant part of long logs; the full log is written to `log_dir`
 /// - Uses colored output to improve readability
 /// - Includes separator lines for visual clarity
 ///
 /// - 如果没有发现意外失败则提前返回
 /// - 构建错误和测试执行错误的格式不同
+/// - 长日志只显示相关部分；完整日志写入 `log_dir`
 /// - 使用彩色输出提高可读性
 /// - 包含分隔线以提高视觉清晰度
-pub fn print_unexpected_failure_details(unexpected_failures: &[&TestResult], locale: &str) {
+pub fn print_unexpected_failure_details(
+    unexpected_failures: &[&TestResult],
+    log_dir: Option<&Path>,
+    locale: &str,
+) {
     if unexpected_failures.is_empty() {
         return;
     }
@@ -116,19 +563,78 @@ pub fn print_unexpected_failure_details(unexpected_failures: &[&TestResult], loc
             t!("report.report_header_failure", locale = locale, name = result.case_name()).red(),
             result.case_name().cyan()
         );
+        if let Some(case) = result.get_case() {
+            if let Some(description) = &case.description {
+                println!("{}", description.dimmed());
+            }
+            for link in &case.links {
+                println!("  {} {}", t!("report.link", locale = locale).dimmed(), link);
+            }
+            if !case.skip_tests.is_empty() {
+                println!(
+                    "  {} {}",
+                    t!("report.skipped_tests", locale = locale).dimmed(),
+                    case.skip_tests.join(", ")
+                );
+            }
+        }
 
         if let TestResult::Failed { output, reason, .. } = result {
             let log_header = match reason {
-                FailureReason::Build | FailureReason::BuildFailed => t!("run.build_log", locale = locale),
+                FailureReason::Build | FailureReason::BuildFailed | FailureReason::Doc => {
+                    t!("run.build_log", locale = locale)
+                }
                 _ => t!("run.test_log", locale = locale),
             };
             println!("\n--- {} ---\n", log_header.yellow());
-            println!("{}", output);
+            match extract_failure_context(output) {
+                Some(context) => {
+                    println!("{}", context);
+                    let full_log = log_dir.map(|dir| write_full_log(dir, result.case_name(), output));
+                    match full_log {
+                        Some(Ok(path)) => println!(
+                            "\n{}",
+                            t!("report.full_log_written", locale = locale, path = path.display()).dimmed()
+                        ),
+                        _ => println!("\n{}", t!("report.full_log_trimmed", locale = locale).dimmed()),
+                    }
+                }
+                None => println!("{}", output),
+            }
+
+            for values in assertion_values(output) {
+                println!("\n{}", t!("report.assertion_diff", locale = locale).yellow());
+                print!("{}", unified_diff(&values));
+            }
+
+            let attachments = result.get_attachments();
+            if !attachments.is_empty() {
+                println!("\n{}", t!("report.attachments", locale = locale).yellow());
+                for path in attachments {
+                    println!("  - {}", path.display());
+                }
+            }
+
+            let annotations = result.get_annotations();
+            if !annotations.is_empty() {
+                println!("\n{}", t!("report.annotations", locale = locale).yellow());
+                for (key, value) in annotations {
+                    println!("  {key} = {value}");
+                }
+            }
             println!("\n{}", "-".repeat(80));
         }
     }
 }
 
+/// Writes the full log of a case to `<log_dir>/<case>.log`.
+fn write_full_log(log_dir: &Path, case_name: &str, output: &str) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(log_dir)?;
+    let path = log_dir.join(format!("{}.log", case_dir_name(case_name)));
+    fs::write(&path, output)?;
+    Ok(path)
+}
+
 /// Gets the error output from a test result for display.
 ///
 /// 获取测试结果的错误输出以供显示。
@@ -144,11 +650,52 @@ pub fn get_error_output_from_result(result: &TestResult, locale: &str) -> String
         TestResult::Failed { output, reason, .. } => {
             // For build errors, try to format the output as Cargo JSON messages
             if *reason == FailureReason::Build || *reason == FailureReason::BuildFailed {
-                format_build_error_output(output)
+                format_build_error_output(output, locale)
             } else {
                 output.clone()
             }
         }
         _ => t!("run.no_error_output", locale = locale).to_string(),
     }
-} 
\ No newline at end of file
+} 
+
+/// Formats the `--stable-output` line of a finished case. Unlike the localized messages,
+/// it never depends on the locale or the colors, so scripts can match it, e.g.
+/// `MATRIX-RUNNER-RESULT status=failed allowed=false case=net-tests`. The case name
+/// comes last, as it may contain spaces.
+///
+/// 格式化已完成用例的 `--stable-output` 行。与本地化的消息不同，它从不依赖于语言环境或颜色，
+/// 因此脚本可以匹配它，例如 `MATRIX-RUNNER-RESULT status=failed allowed=false case=net-tests`。
+/// 用例名称放在最后，因为它可能包含空格。
+pub fn stable_result_line(name: &str, result: &TestResult, context: &RunContext, today: NaiveDate) -> String {
+    format!(
+        "{STABLE_RESULT_PREFIX} status={} allowed={} case={name}",
+        result.status_key(),
+        result.is_allowed_failure(context, today)
+    )
+}
+
+/// Formats the final `--stable-output` line of a run from its results and the error it
+/// ends with, if any, e.g.
+/// `MATRIX-RUNNER-STATUS outcome=passed exit_code=0 passed=3 failed=0 skipped=1`.
+/// The outcome is `passed`, `failed` or `cancelled`.
+///
+/// 根据运行的结果及其结束时的错误（如果有）格式化最终的 `--stable-output` 行，例如
+/// `MATRIX-RUNNER-STATUS outcome=passed exit_code=0 passed=3 failed=0 skipped=1`。
+/// 结果为 `passed`、`failed` 或 `cancelled`。
+pub fn stable_status_line(results: &[TestResult], error: Option<&MatrixError>) -> String {
+    let outcome = match error {
+        None => "passed",
+        Some(MatrixError::Cancelled) => "cancelled",
+        Some(_) => "failed",
+    };
+    let count = |f: fn(&TestResult) -> bool| results.iter().filter(|r| f(r)).count();
+    format!(
+        "{STABLE_STATUS_PREFIX} outcome={outcome} exit_code={} passed={} failed={} skipped={}",
+        error.map_or(0, MatrixError::exit_code),
+        count(|r| matches!(r, TestResult::Passed { .. } | TestResult::Built { .. })),
+        count(TestResult::is_failure),
+        count(|r| matches!(r, TestResult::Skipped { .. })),
+    )
+}
+
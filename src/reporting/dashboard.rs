@@ -0,0 +1,290 @@
+//! # Dashboard Module / 仪表盘模块
+//!
+//! This module serves the live dashboard of `matrix-runner serve`: a small web page, built
+//! from the styles of the HTML report, showing the status of every case as the run goes.
+//! The page follows the events of the run (see [`RunEvent`]) as server-sent events from
+//! `/events`. A browser connecting late first receives every event sent so far, so it
+//! shows the same state as one that was open from the start.
+//!
+//! 此模块为 `matrix-runner serve` 提供实时仪表盘：一个使用 HTML 报告样式构建的小型网页，
+//! 随运行进行显示每个用例的状态。页面通过 `/events` 的服务器发送事件跟踪运行的事件（参见 [`RunEvent`]）。
+//! 较晚连接的浏览器会先收到迄今为止发送的所有事件，因此它显示的状态与从一开始就打开的浏览器相同。
+
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::core::plugins::{EventSink, RunEvent};
+use crate::infra::t;
+
+/// Embedded CSS styles shared with the HTML report / 与 HTML 报告共享的嵌入式 CSS 样式
+const REPORT_STYLE: &str = include_str!("assets/report.css");
+
+/// Embedded JavaScript following the events of the run / 跟踪运行事件的嵌入式 JavaScript
+const DASHBOARD_SCRIPT: &str = include_str!("assets/dashboard.js");
+
+/// The path of the event stream / 事件流的路径
+pub const EVENTS_PATH: &str = "/events";
+
+/// The most bytes read of a request line and its headers / 请求行及其头部最多读取的字节数
+const MAX_REQUEST_HEAD: u64 = 16 * 1024;
+
+/// The events of the run, kept for late browsers, and the browsers following them.
+#[derive(Debug, Default)]
+struct Feed {
+    events: Vec<String>,
+    clients: Vec<mpsc::UnboundedSender<String>>,
+    closed: bool,
+}
+
+/// The live dashboard of a run: receives the run's events as an [`EventSink`] and serves
+/// them to browsers.
+/// 一次运行的实时仪表盘：作为 [`EventSink`] 接收运行的事件，并将其提供给浏览器。
+#[derive(Debug)]
+pub struct Dashboard {
+    feed: Mutex<Feed>,
+    locale: String,
+}
+
+impl EventSink for Dashboard {
+    fn send(&self, event: &RunEvent) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        let Ok(mut feed) = self.feed.lock() else {
+            return;
+        };
+        feed.clients
+            .retain(|client| client.send(line.clone()).is_ok());
+        feed.events.push(line);
+    }
+
+    fn close(&self) {
+        if let Ok(mut feed) = self.feed.lock() {
+            feed.closed = true;
+            feed.clients.clear();
+        }
+    }
+}
+
+impl Dashboard {
+    /// Creates a dashboard whose page is shown in the given locale.
+    /// 创建一个以给定语言环境显示页面的仪表盘。
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            feed: Mutex::default(),
+            locale: locale.into(),
+        }
+    }
+
+    /// Serves the dashboard on a listener until `stop` is cancelled.
+    /// 在监听器上提供仪表盘，直到 `stop` 被取消。
+    pub async fn serve(self: Arc<Self>, listener: TcpListener, stop: CancellationToken) {
+        loop {
+            tokio::select! {
+                _ = stop.cancelled() => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else {
+                        continue;
+                    };
+                    let dashboard = self.clone();
+                    let stop = stop.clone();
+                    tokio::spawn(async move {
+                        // A browser going away mid-response is not an error of the run.
+                        let _ = dashboard.handle(stream, stop).await;
+                    });
+                }
+            }
+        }
+    }
+
+    /// The events sent so far and a channel of the ones to come, which is closed right
+    /// away once the run is over.
+    fn subscribe(&self) -> (Vec<String>, mpsc::UnboundedReceiver<String>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let Ok(mut feed) = self.feed.lock() else {
+            return (Vec::new(), receiver);
+        };
+        if !feed.closed {
+            feed.clients.push(sender);
+        }
+        (feed.events.clone(), receiver)
+    }
+
+    async fn handle(&self, mut stream: TcpStream, stop: CancellationToken) -> std::io::Result<()> {
+        let mut head = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD));
+        let mut request_line = String::new();
+        head.read_line(&mut request_line).await?;
+        // The headers are not needed; they are read so the browser sees its request consumed.
+        let mut header = String::new();
+        while head.read_line(&mut header).await? > 0 && !header.trim().is_empty() {
+            header.clear();
+        }
+        drop(head);
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let target = parts.next().unwrap_or_default();
+        let path = target.split('?').next().unwrap_or_default();
+        match (method, path) {
+            ("GET", "/" | "/index.html") => {
+                let page = render_page(&self.locale);
+                respond(&mut stream, "200 OK", "text/html; charset=utf-8", &page).await
+            }
+            ("GET", EVENTS_PATH) => self.stream_events(stream, stop).await,
+            ("GET", _) => {
+                respond(
+                    &mut stream,
+                    "404 Not Found",
+                    "text/plain; charset=utf-8",
+                    "Not Found",
+                )
+                .await
+            }
+            _ => {
+                respond(
+                    &mut stream,
+                    "405 Method Not Allowed",
+                    "text/plain; charset=utf-8",
+                    "Method Not Allowed",
+                )
+                .await
+            }
+        }
+    }
+
+    /// Sends the events of the run as server-sent events until the run is over or the
+    /// server stops.
+    async fn stream_events(
+        &self,
+        mut stream: TcpStream,
+        stop: CancellationToken,
+    ) -> std::io::Result<()> {
+        let (backlog, mut receiver) = self.subscribe();
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+        for line in backlog {
+            stream
+                .write_all(format!("data: {line}\n\n").as_bytes())
+                .await?;
+        }
+        stream.flush().await?;
+        loop {
+            tokio::select! {
+                _ = stop.cancelled() => break,
+                line = receiver.recv() => {
+                    let Some(line) = line else {
+                        break;
+                    };
+                    stream.write_all(format!("data: {line}\n\n").as_bytes()).await?;
+                    stream.flush().await?;
+                }
+            }
+        }
+        stream.shutdown().await
+    }
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Renders the dashboard page. The texts the script shows are passed to it as JSON.
+/// 渲染仪表盘页面。脚本显示的文本以 JSON 形式传递给它。
+pub fn render_page(locale: &str) -> String {
+    let labels = serde_json::json!({
+        "running": t!("dashboard.running", locale = locale),
+        "succeeded": t!("dashboard.succeeded", locale = locale),
+        "failed": t!("dashboard.failed", locale = locale),
+        "disconnected": t!("dashboard.disconnected", locale = locale),
+        "status": {
+            "running": t!("dashboard.status_running", locale = locale),
+            "passed": t!("report.status_passed", locale = locale),
+            "failed": t!("report.status_failed", locale = locale),
+            "allowed_failure": t!("report.status_allowed_failure", locale = locale),
+            "timeout": t!("report.status_timeout", locale = locale),
+            "built": t!("report.status_built", locale = locale),
+            "skipped": t!("report.status_skipped", locale = locale),
+        },
+    });
+    let summary_item = |id: &str, class: &str, label: String| {
+        format!(
+            "<div class='summary-item'><span class='count {class}' id='count-{id}'>0</span><span class='label'>{label}</span></div>"
+        )
+    };
+
+    let mut html = String::new();
+    html.push_str(&format!(
+        "<!DOCTYPE html><html><head><meta charset='utf-8'><title>{}</title>",
+        t!("dashboard.title", locale = locale)
+    ));
+    html.push_str("<style>");
+    html.push_str(REPORT_STYLE);
+    html.push_str("</style></head><body>");
+    html.push_str(&format!(
+        "<h1>{}</h1>",
+        t!("dashboard.title", locale = locale)
+    ));
+    html.push_str("<div class='summary-container'>");
+    html.push_str(&summary_item(
+        "total",
+        "",
+        t!("html_report.summary.total", locale = locale).to_string(),
+    ));
+    html.push_str(&summary_item(
+        "running",
+        "running-text",
+        t!("dashboard.summary_running", locale = locale).to_string(),
+    ));
+    html.push_str(&summary_item(
+        "passed",
+        "passed-text",
+        t!("html_report.summary.passed", locale = locale).to_string(),
+    ));
+    html.push_str(&summary_item(
+        "failed",
+        "failed-text",
+        t!("html_report.summary.failed", locale = locale).to_string(),
+    ));
+    html.push_str(&summary_item(
+        "skipped",
+        "skipped-text",
+        t!("html_report.summary.skipped", locale = locale).to_string(),
+    ));
+    html.push_str("</div>");
+    html.push_str(&format!(
+        "<p class='run-state' id='run-state'>{}</p>",
+        t!("dashboard.waiting", locale = locale)
+    ));
+    html.push_str(&format!(
+        "<table><thead><tr><th class='status-col'>{}</th><th>{}</th><th class='duration-cell'>{}</th></tr></thead><tbody id='cases'></tbody></table>",
+        t!("html_report.table.header.status", locale = locale),
+        t!("html_report.table.header.name", locale = locale),
+        t!("html_report.table.header.duration", locale = locale)
+    ));
+    // `</` cannot appear in the JSON of a script element.
+    html.push_str(&format!(
+        "<script type='application/json' id='labels'>{}</script>",
+        labels.to_string().replace("</", "<\\/")
+    ));
+    html.push_str("<script>");
+    html.push_str(DASHBOARD_SCRIPT);
+    html.push_str("</script></body></html>");
+    html
+}
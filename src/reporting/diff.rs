@@ -0,0 +1,122 @@
+//! # Assertion Diff Module / 断言差异模块
+//!
+//! This module renders the values of a failed `assert_eq!` as a diff, so failure details
+//! show what differs instead of two long values to compare by eye: a colored unified
+//! diff on the console and a side-by-side table in the HTML report. Within changed lines,
+//! the words that differ are emphasized.
+//!
+//! 此模块将失败的 `assert_eq!` 的值渲染为差异，使失败详情显示不同之处，而不是两个需要肉眼比较的长值：
+//! 控制台中为彩色的统一差异，HTML 报告中为并排表格。在变化的行中，不同的词会被突出显示。
+
+use crate::reporting::extract::AssertionValues;
+use crate::reporting::html::escape_html;
+use colored::*;
+use similar::{ChangeTag, InlineChange, TextDiff};
+
+/// The number of unchanged lines shown around each change / 每处变化周围显示的未变化行数
+const CONTEXT_LINES: usize = 3;
+
+/// Renders the values of an assertion as a unified diff from `left` to `right`, with
+/// removed lines in red and added lines in green.
+///
+/// 将断言的值渲染为从 `left` 到 `right` 的统一差异，删除的行为红色，新增的行为绿色。
+pub fn unified_diff(values: &AssertionValues) -> String {
+    let diff = TextDiff::from_lines(&values.left, &values.right);
+    let mut text = format!("{}\n{}\n", "--- left".red(), "+++ right".green());
+    for hunk in diff
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .iter_hunks()
+    {
+        text.push_str(&format!("{}\n", hunk.header().to_string().cyan()));
+        for op in hunk.ops() {
+            for change in diff.iter_inline_changes(op) {
+                let sign = match change.tag() {
+                    ChangeTag::Delete => "-",
+                    ChangeTag::Insert => "+",
+                    ChangeTag::Equal => " ",
+                };
+                text.push_str(&paint(change.tag(), sign, false).to_string());
+                for (emphasized, value) in change.iter_strings_lossy() {
+                    text.push_str(
+                        &paint(change.tag(), value.trim_end_matches('\n'), emphasized).to_string(),
+                    );
+                }
+                text.push('\n');
+            }
+        }
+    }
+    text
+}
+
+/// Colors a part of a diff line by its change.
+fn paint(tag: ChangeTag, text: &str, emphasized: bool) -> ColoredString {
+    let text = match tag {
+        ChangeTag::Delete => text.red(),
+        ChangeTag::Insert => text.green(),
+        ChangeTag::Equal => text.normal(),
+    };
+    if emphasized {
+        text.bold().underline()
+    } else {
+        text
+    }
+}
+
+/// Renders the values of an assertion as an HTML table with `left` and `right` side by
+/// side, changed lines aligned next to each other.
+///
+/// 将断言的值渲染为 `left` 与 `right` 并排的 HTML 表格，变化的行彼此对齐。
+pub fn side_by_side_html(values: &AssertionValues) -> String {
+    let diff = TextDiff::from_lines(&values.left, &values.right);
+    let mut rows = String::new();
+    for (i, group) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
+        if i > 0 {
+            rows.push_str("<tr class='diff-gap'><td>…</td><td>…</td></tr>");
+        }
+        for op in group {
+            let changes: Vec<_> = diff.iter_inline_changes(op).collect();
+            let left: Vec<_> = changes
+                .iter()
+                .filter(|change| change.tag() != ChangeTag::Insert)
+                .collect();
+            let right: Vec<_> = changes
+                .iter()
+                .filter(|change| change.tag() != ChangeTag::Delete)
+                .collect();
+            for row in 0..left.len().max(right.len()) {
+                rows.push_str(&format!(
+                    "<tr>{}{}</tr>",
+                    diff_cell(left.get(row).copied()),
+                    diff_cell(right.get(row).copied())
+                ));
+            }
+        }
+    }
+    format!(
+        "<table class='assertion-diff'><tr><th>left</th><th>right</th></tr>{}</table>",
+        rows
+    )
+}
+
+/// Renders one side of a side-by-side row; an empty cell where the other side has a line.
+fn diff_cell(change: Option<&InlineChange<'_, str>>) -> String {
+    let Some(change) = change else {
+        return "<td class='diff-empty'></td>".to_string();
+    };
+    let class = match change.tag() {
+        ChangeTag::Delete => "diff-del",
+        ChangeTag::Insert => "diff-ins",
+        ChangeTag::Equal => "diff-equal",
+    };
+    let mut line = String::new();
+    for (emphasized, value) in change.iter_strings_lossy() {
+        let value = escape_html(value.trim_end_matches('\n'));
+        if emphasized {
+            line.push_str(&format!("<span class='diff-emph'>{value}</span>"));
+        } else {
+            line.push_str(&value);
+        }
+    }
+    format!("<td class='{class}'>{line}</td>")
+}
@@ -0,0 +1,204 @@
+//! # Failure Context Extraction Module / 失败上下文提取模块
+//!
+//! This module picks the relevant part out of a failed case's log, so failure details
+//! show the panic message or assertion diff instead of hundreds of lines of output.
+//! The heuristics are tried in order:
+//!
+//! 1. The libtest `---- <test> stdout ----` sections of the failed tests
+//! 2. Panic messages (`panicked at`) with the lines that follow, e.g. `left`/`right` diffs
+//! 3. Compiler `error` diagnostics
+//! 4. The last lines before the process exited
+//!
+//! It also extracts the values of failed `assert_eq!` assertions, which the reports show
+//! as a diff.
+//!
+//! 此模块从失败用例的日志中挑选出相关部分，使失败详情显示 panic 消息或断言差异，
+//! 而不是数百行输出。启发式规则按以下顺序尝试：
+//!
+//! 1. 失败测试的 libtest `---- <test> stdout ----` 部分
+//! 2. panic 消息（`panicked at`）及其后续行，例如 `left`/`right` 差异
+//! 3. 编译器 `error` 诊断
+//! 4. 进程退出前的最后若干行
+//!
+//! 它还会提取失败的 `assert_eq!` 断言的值，报告会将其显示为差异。
+
+/// Logs with at most this many lines are shown in full / 不超过此行数的日志会完整显示
+const MAX_UNTRIMMED_LINES: usize = 40;
+
+/// The number of lines kept when no better section is found / 未找到更合适的部分时保留的行数
+const TAIL_LINES: usize = 30;
+
+/// The maximum number of lines kept per extracted section / 每个提取部分保留的最大行数
+const MAX_SECTION_LINES: usize = 40;
+
+/// Extracts the relevant part of a failure log.
+///
+/// # Arguments
+/// * `output` - The full output of the failed case
+///
+/// # Returns
+/// The extracted context, or `None` if the log is short enough to be shown in full
+///
+/// 提取失败日志的相关部分。
+pub fn extract_failure_context(output: &str) -> Option<String> {
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() <= MAX_UNTRIMMED_LINES {
+        return None;
+    }
+
+    let sections = [
+        test_stdout_sections(&lines),
+        sections_starting_at(&lines, |line| line.contains("panicked at")),
+        sections_starting_at(&lines, is_compiler_error),
+    ]
+    .into_iter()
+    .find(|sections| !sections.is_empty());
+
+    let extracted = match sections {
+        Some(sections) => sections.join("\n\n"),
+        None => lines[lines.len() - TAIL_LINES..].join("\n"),
+    };
+    Some(extracted)
+}
+
+/// Collects the `---- <test> stdout ----` sections libtest prints for failed tests.
+fn test_stdout_sections(lines: &[&str]) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in lines {
+        let is_header = line.starts_with("---- ") && line.ends_with(" ----");
+        if is_header || line.trim() == "failures:" {
+            if let Some(section) = current.take() {
+                sections.push(cap(&section));
+            }
+            if is_header {
+                current = Some(vec![line]);
+            }
+            continue;
+        }
+        if let Some(section) = current.as_mut() {
+            section.push(line);
+        }
+    }
+    if let Some(section) = current {
+        sections.push(cap(&section));
+    }
+    sections
+}
+
+/// Collects sections that start at a matching line and end at the next blank line.
+fn sections_starting_at(lines: &[&str], starts: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if starts(lines[i]) {
+            let end = lines[i..]
+                .iter()
+                .position(|line| line.trim().is_empty())
+                .map_or(lines.len(), |offset| i + offset);
+            sections.push(cap(&lines[i..end]));
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+    sections
+}
+
+fn is_compiler_error(line: &str) -> bool {
+    (line.starts_with("error[") || line.starts_with("error:"))
+        && !line.starts_with("error: could not compile")
+        && !line.starts_with("error: test failed")
+}
+
+/// Joins a section, keeping at most `MAX_SECTION_LINES` lines.
+fn cap(section: &[&str]) -> String {
+    let mut text = section
+        .iter()
+        .take(MAX_SECTION_LINES)
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if section.len() > MAX_SECTION_LINES {
+        text.push_str("\n...");
+    }
+    text.trim_end().to_string()
+}
+
+/// The two values of a failed `assert_eq!`, as printed in its `left:`/`right:` lines.
+/// String values are unescaped, so multi-line strings diff line by line.
+///
+/// 失败的 `assert_eq!` 的两个值，取自其 `left:`/`right:` 行。字符串值会被反转义，使多行字符串按行比较。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionValues {
+    /// The `left` value / `left` 值
+    pub left: String,
+    /// The `right` value / `right` 值
+    pub right: String,
+}
+
+/// Extracts the values of the failed equality assertions in a log, in both the current
+/// (`  left: 1`) and the pre-1.73 (``  left: `1`,``) formats. Assertions whose values
+/// are equal, such as a failed `assert_ne!`, have nothing to diff and are left out.
+///
+/// 提取日志中失败的相等断言的值，支持当前（`  left: 1`）和 1.73 之前（``  left: `1`,``）的格式。
+/// 值相等的断言（例如失败的 `assert_ne!`）没有可比较的差异，因此被忽略。
+pub fn assertion_values(output: &str) -> Vec<AssertionValues> {
+    let lines: Vec<&str> = output.lines().collect();
+    lines
+        .windows(2)
+        .filter_map(|pair| {
+            let left = pair[0].trim_start().strip_prefix("left: ")?;
+            let right = pair[1].trim_start().strip_prefix("right: ")?;
+            let values = AssertionValues {
+                left: debug_value(left),
+                right: debug_value(right),
+            };
+            (values.left != values.right).then_some(values)
+        })
+        .collect()
+}
+
+/// Turns a `Debug`-printed value back into text: the backticks of the old format are
+/// removed, and a string literal is unescaped.
+fn debug_value(printed: &str) -> String {
+    let printed = printed.trim_end();
+    let value = printed
+        .strip_prefix('`')
+        .and_then(|value| value.strip_suffix("`,").or_else(|| value.strip_suffix('`')))
+        .unwrap_or(printed);
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .and_then(unescape)
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// Unescapes the body of a `Debug`-printed string literal, or returns `None` if it holds
+/// an escape `Debug` does not print.
+fn unescape(literal: &str) -> Option<String> {
+    let mut text = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        let unescaped = match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+            }
+            _ => return None,
+        };
+        text.push(unescaped);
+    }
+    Some(text)
+}
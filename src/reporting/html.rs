@@ -4,16 +4,34 @@
 //! It creates styled HTML files with test statistics, detailed results tables,
 //! and interactive features for viewing test output.
 //!
+//! Large matrices can be kept within a size budget: outputs above a cap are trimmed
+//! in the page and their full logs moved to sidecar files loaded on demand, or the
+//! outputs can be left out entirely with a summary-only report.
+//!
 //! 此模块处理 HTML 测试报告的生成。
 //! 它创建带有测试统计、详细结果表格和查看测试输出的交互功能的样式化 HTML 文件。
+//! 大型矩阵可以保持在大小预算之内：超过上限的输出会在页面中被截断，
+//! 其完整日志移至按需加载的附属文件；也可以使用仅摘要报告完全省略输出。
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::core::models::TestResult;
+use crate::core::axis::AxisValueSummary;
+use crate::core::columns::SummaryColumn;
+use crate::core::config::TestMatrix;
+use crate::core::context::RunContext;
+use crate::core::flakiness::FlakyCase;
+use crate::core::models::{RunEnvironment, RunMetadata, TestResult};
+use crate::core::owners::OwnerFailures;
+use crate::infra::coverage::Coverage;
+use crate::infra::fs::{lock_file, write_atomic};
 use crate::infra::t;
-use crate::reporting::console::get_error_output_from_result;
+use crate::reporting::console::{axis_label, get_error_output_from_result};
+use crate::reporting::diff::side_by_side_html;
+use crate::reporting::extract::{assertion_values, extract_failure_context};
 
 /// Embedded CSS styles for HTML reports / HTML 报告的嵌入式 CSS 样式
 const HTML_STYLE: &str = include_str!("assets/report.css");
@@ -21,6 +39,36 @@ const HTML_STYLE: &str = include_str!("assets/report.css");
 /// Embedded JavaScript for HTML report interactivity / HTML 报告交互性的嵌入式 JavaScript
 const HTML_SCRIPT: &str = include_str!("assets/report.js");
 
+/// Options controlling the size of the HTML report.
+/// 控制 HTML 报告大小的选项。
+#[derive(Debug, Clone, Default)]
+pub struct HtmlOptions {
+    /// Cap in KB for the output embedded per case; larger full logs go to sidecar files
+    /// 每个用例嵌入输出的上限（KB）；更大的完整日志会放入附属文件
+    pub max_output_kb: Option<usize>,
+    /// Leave case outputs out of the report / 在报告中省略用例输出
+    pub summary_only: bool,
+    /// List only the failed cases, with their outputs expanded / 仅列出失败的用例，并展开其输出
+    pub failures_only: bool,
+    /// Hold a lock on `<report>.lock` while writing, for reports shared between shards
+    /// 写入时持有 `<report>.lock` 上的锁，用于分片之间共享的报告
+    pub lock: bool,
+    /// The columns of the results table; empty for the default ones / 结果表的列；为空时使用默认列
+    pub columns: Vec<SummaryColumn>,
+}
+
+/// Returns the directory holding the sidecar files of a report,
+/// e.g. `report_files` for `report.html`.
+///
+/// 返回存放报告附属文件的目录，例如 `report.html` 对应 `report_files`。
+pub fn sidecar_dir(output_path: &Path) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "report".to_string());
+    output_path.with_file_name(format!("{stem}_files"))
+}
+
 /// Generates a comprehensive HTML report from test results.
 /// Creates a styled HTML file with test statistics, detailed results table,
 /// and interactive features for viewing test output.
@@ -30,29 +78,40 @@ const HTML_SCRIPT: &str = include_str!("assets/report.js");
 ///
 /// # Arguments / 参数
 /// * `results` - A slice of test results to include in the report
-///               要包含在报告中的测试结果切片
+///   要包含在报告中的测试结果切片
+/// * `metadata` - Run-level notes, e.g. why no cases were run
+///   运行级说明，例如为何没有运行任何用例
 /// * `output_path` - The file path where the HTML report will be saved
-///                   保存 HTML 报告的文件路径
-/// * `locale` - The locale to use for internationalization
-///              用于国际化使用的语言环境
+///   保存 HTML 报告的文件路径
+/// * `options` - Output caps, summary-only and failures-only modes / 输出上限、仅摘要和仅失败模式
+/// * `context` - The locale to use for internationalization and the platform allowed failures are decided on
+///   用于国际化使用的语言环境以及判断允许失败所依据的平台
+/// * `today` - The date allowed failures are decided on
+///   判断允许失败所依据的日期
 ///
 /// # Returns / 返回值
 /// * `Result<()>` - Success or error information
-///                  成功或错误信息
+///   成功或错误信息
 ///
 /// # Errors / 错误
 /// This function will return an error if:
-/// - The output file cannot be written to the specified path
+/// - The output file or a sidecar file cannot be written
 /// - File system permissions prevent writing
 ///
 /// 此函数在以下情况下会返回错误：
-/// - 无法将输出文件写入指定路径
+/// - 无法写入输出文件或附属文件
 /// - 文件系统权限阻止写入
 pub fn generate_html_report(
     results: &[TestResult],
+    metadata: &RunMetadata,
     output_path: &Path,
-    locale: &str,
+    options: &HtmlOptions,
+    context: &RunContext,
+    today: NaiveDate,
 ) -> Result<()> {
+    let locale = context.locale.as_str();
+    // Sidecar files and the page are replaced together while the lock is held.
+    let _lock = options.lock.then(|| lock_file(output_path)).transpose()?;
     let mut html = String::new();
     html.push_str(&format!(
         "<!DOCTYPE html><html><head><title>{}</title>",
@@ -77,9 +136,13 @@ pub fn generate_html_report(
         .iter()
         .filter(|r| r.is_failure())
         .count();
+    let built = results
+        .iter()
+        .filter(|r| matches!(r, TestResult::Built { .. }))
+        .count();
     let skipped = results
         .iter()
-        .filter(|r| matches!(r, TestResult::Skipped))
+        .filter(|r| matches!(r, TestResult::Skipped { .. }))
         .count();
 
     html.push_str("<div class='summary-container'>");
@@ -98,98 +161,659 @@ pub fn generate_html_report(
         failed,
         t!("html_report.summary.failed", locale = locale)
     ));
+    // Only shown when the matrix has build-only cases
+    if built > 0 {
+        html.push_str(&format!(
+            "<div class='summary-item'><span class='count passed-text'>{}</span><span class='label'>{}</span></div>",
+            built,
+            t!("html_report.summary.built", locale = locale)
+        ));
+    }
     html.push_str(&format!(
         "<div class='summary-item'><span class='count skipped-text'>{}</span><span class='label'>{}</span></div>",
         skipped,
         t!("html_report.summary.skipped", locale = locale)
     ));
     html.push_str("</div>");
+    let packages = package_counts(results);
+    html.push_str(&render_package_summary(&packages, locale));
 
+    html.push_str(&render_notes(results, metadata, locale));
+    if options.summary_only {
+        html.push_str(&format!(
+            "<p class='summary-only'>{}</p>",
+            t!("html_report.summary_only", locale = locale)
+        ));
+    }
+    if options.failures_only {
+        html.push_str(&format!(
+            "<p class='failures-only'>{}</p>",
+            t!(
+                "html_report.failures_only",
+                locale = locale,
+                passed = passed + built,
+                skipped = skipped
+            )
+        ));
+    }
 
-    // Add results table
+    // Add results table. The cost columns are only shown by default when the run recorded them.
+    let show_costs = !metadata.case_costs.is_empty();
+    let table_columns = if options.columns.is_empty() {
+        default_columns(show_costs)
+    } else {
+        options.columns.clone()
+    };
     html.push_str("<table><thead><tr>");
-    html.push_str(&format!(
-        "<th>{}</th>",
-        t!("html_report.table.header.name", locale = locale)
-    ));
-    html.push_str(&format!(
-        "<th class='status-col'>{}</th>",
-        t!("html_report.table.header.status", locale = locale)
-    ));
-    html.push_str(&format!(
-        "<th class='duration-cell'>{}</th>",
-        t!("html_report.table.header.duration", locale = locale)
-    ));
-    html.push_str(&format!(
-        "<th class='retries-cell'>{}</th>",
-        t!("html_report.table.header.retries", locale = locale)
-    ));
+    for column in &table_columns {
+        html.push_str(&format!(
+            "<th{}>{}</th>",
+            column_class(column),
+            escape_html(&column.header(locale))
+        ));
+    }
+    let columns = table_columns.len();
     html.push_str("</tr></thead><tbody>");
 
 
-    for (i, result) in results.iter().enumerate() {
-        let status_str = result.get_status_str(locale);
-        let status_class = result.get_status_class();
-        let duration_str = result
-            .get_duration()
-            .map(|d| format!("{:.2}s", d.as_secs_f64()))
-            .unwrap_or_else(|| "N/A".to_string());
-        
-        let retries_str = {
-            let retries = result.get_retries();
-            if retries > 1 {
-                format!("{}", retries - 1)
-            } else {
-                String::new()
+    let mut rows: Vec<(usize, &TestResult)> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| !options.failures_only || result.is_failure())
+        .collect();
+    // With packages, the rows are grouped under a header per package; skipped results come last.
+    if !packages.is_empty() {
+        rows.sort_by_key(|(_, result)| (result.package().is_none(), result.package()));
+    }
+    let mut current_package = None;
+    for (i, result) in rows {
+        if !packages.is_empty() && result.package() != current_package {
+            current_package = result.package();
+            if let Some(package) = current_package.and_then(|name| packages.iter().find(|p| p.name == name)) {
+                html.push_str(&format!(
+                    "<tr class='package-row'><td colspan='{}'>{} <span class='package-counts'>{}</span></td></tr>",
+                    columns,
+                    escape_html(&package.name),
+                    t!(
+                        "html_report.packages.counts",
+                        locale = locale,
+                        passed = package.passed,
+                        failed = package.failed,
+                        total = package.total
+                    )
+                ));
             }
-        };
+        }
+        let status_str = result.get_status_str(context, today);
+        let status_class = result.get_status_class(context, today);
 
         let output_id = format!("output-{}", i);
-        let error_details = if let TestResult::Failed { .. } = result {
+        let error_details = if let TestResult::Failed { .. } = result
+            && !options.summary_only
+        {
             let error_output = get_error_output_from_result(result, locale);
-            let escaped_output = escape_html(&error_output);
+            let mut content = match options.max_output_kb {
+                Some(kb) if error_output.len() > kb * 1024 => {
+                    render_sidecar_output(&error_output, &output_id, output_path, kb * 1024, locale)?
+                }
+                _ => render_output(&error_output, locale),
+            };
+            for values in assertion_values(&error_output) {
+                content.push_str(&format!(
+                    "<div class='assertion-diff-title'>{}</div>{}",
+                    t!("html_report.assertion_diff", locale = locale),
+                    side_by_side_html(&values)
+                ));
+            }
+            // A failures-only report is about the outputs, so they start expanded.
+            let display = if options.failures_only { "" } else { " style='display:none;'" };
             format!(
-                "<tr id='{}' style='display:none;'><td colspan='4'><pre class='output-content'>{}</pre></td></tr>",
+                "<tr id='{}'{}><td colspan='{}'>{}</td></tr>",
                 output_id,
-                escaped_output
+                display,
+                columns,
+                content
             )
         } else {
             String::new()
         };
 
-        let output_toggle = if result.is_failure() {
+        let output_toggle = if result.is_failure() && !options.summary_only {
             format!("<div class='output-toggle' onclick=\"toggleOutput('{}')\">{}</div>", output_id, t!("html_report.toggle_output", locale=locale))
         } else {
             String::new()
         };
         
+        let attachment_links = render_attachments(result, locale);
+        let annotations = render_annotations(result, locale);
+        let skip_reason = result
+            .skip_reason()
+            .map(|reason| format!("<div class='skip-reason'>{}</div>", escape_html(reason)))
+            .unwrap_or_default();
+
+        let cost = metadata.case_costs.get(result.case_name());
         html.push_str("<tr>");
-        html.push_str(&format!("<td>{}</td>", result.case_name()));
-        html.push_str(&format!(
-            "<td class='status-col'><div class='status-cell {}'>{}</div>{}</td>",
-            status_class, status_str, output_toggle
-        ));
-        html.push_str(&format!(
-            "<td class='duration-cell'>{}</td>",
-            duration_str
-        ));
-        html.push_str(&format!("<td class='retries-cell'>{}</td>", retries_str));
+        for column in &table_columns {
+            let cell = match column {
+                SummaryColumn::Name => render_case_name(result, locale),
+                SummaryColumn::Status => format!(
+                    "<div class='status-cell {}'>{}</div>{}{}{}{}",
+                    status_class, status_str, skip_reason, output_toggle, attachment_links, annotations
+                ),
+                column => escape_html(&column.value(result, cost, context, today)),
+            };
+            html.push_str(&format!("<td{}>{}</td>", column_class(column), cell));
+        }
         html.push_str("</tr>");
         html.push_str(&error_details);
     }
 
     html.push_str("</tbody></table>");
+    html.push_str(&render_owners(&metadata.failure_owners, locale));
+    html.push_str(&render_flakiness(&metadata.flaky_cases, locale));
+    html.push_str(&render_axes(&metadata.axes, locale));
+    html.push_str(&render_coverage(&metadata.coverage, locale));
+    if let Some(environment) = &metadata.environment {
+        html.push_str(&render_environment(environment, locale));
+    }
+    // The snapshot names every case, which a failures-only report leaves out.
+    if let Some(config) = metadata.config.as_ref().filter(|_| !options.failures_only) {
+        html.push_str(&render_config(config, locale));
+    }
     html.push_str("<script>");
     html.push_str(HTML_SCRIPT);
     html.push_str("</script></body></html>");
 
-    fs::write(output_path, html)?;
-    Ok(())
+    write_atomic(output_path, html)
+}
+
+/// Renders a failure output in the page.
+/// Long logs show their relevant part, with the full log one click away.
+fn render_output(output: &str, locale: &str) -> String {
+    match extract_failure_context(output) {
+        Some(context) => format!(
+            "<pre class='output-content'>{}</pre><details class='full-log'><summary>{}</summary><pre class='output-content'>{}</pre></details>",
+            escape_html(&context),
+            t!("html_report.full_log", locale = locale),
+            escape_html(output)
+        ),
+        None => format!("<pre class='output-content'>{}</pre>", escape_html(output)),
+    }
+}
+
+/// Renders a failure output that exceeds the size cap: the relevant part is trimmed to
+/// `limit` bytes, and the full log is written to a sidecar script that the page only
+/// loads when it is opened. Scripts, unlike `fetch`, also load from `file://` reports.
+fn render_sidecar_output(
+    output: &str,
+    output_id: &str,
+    output_path: &Path,
+    limit: usize,
+    locale: &str,
+) -> Result<String> {
+    let dir = sidecar_dir(output_path);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let file_name = format!("{output_id}.js");
+    let full_id = format!("{output_id}-full");
+    let script = format!(
+        "matrixRunnerOutput({}, {});\n",
+        serde_json::to_string(&full_id)?,
+        serde_json::to_string(output)?
+    );
+    let path = dir.join(&file_name);
+    write_atomic(&path, script)?;
+
+    let dir_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let context = extract_failure_context(output).unwrap_or_else(|| output.to_string());
+    Ok(format!(
+        "<pre class='output-content'>{}</pre><details class='full-log' data-src='{}' ontoggle='loadOutput(this)'><summary>{}</summary><pre class='output-content' id='{}'>…</pre></details>",
+        escape_html(&tail_within(&context, limit)),
+        escape_html(&format!("{dir_name}/{file_name}")),
+        t!("html_report.full_log_sidecar", locale = locale, size = output.len().div_ceil(1024)),
+        full_id
+    ))
+}
+
+/// Keeps the end of a text within `limit` bytes, where failures are usually reported.
+fn tail_within(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let mut start = text.len() - limit;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    // Start at a line boundary so the first line is not cut in half.
+    let start = text[start..].find('\n').map_or(start, |offset| start + offset + 1);
+    format!("...\n{}", &text[start..])
+}
+
+/// Renders the run notes, and explains an empty report when no cases were run.
+/// 渲染运行说明，并在没有运行任何用例时解释报告为何为空。
+fn render_notes(results: &[TestResult], metadata: &RunMetadata, locale: &str) -> String {
+    let mut html = String::new();
+    if results.is_empty() {
+        html.push_str(&format!(
+            "<p class='empty-run'>{}</p>",
+            t!("html_report.empty_run", locale = locale)
+        ));
+    }
+    if !metadata.notes.is_empty() {
+        html.push_str(&format!(
+            "<div class='run-notes'><h2>{}</h2><ul>",
+            t!("html_report.notes", locale = locale)
+        ));
+        for note in &metadata.notes {
+            html.push_str(&format!("<li>{}</li>", escape_html(note)));
+        }
+        html.push_str("</ul></div>");
+    }
+    html
+}
+
+/// Renders the "Failures by owner" section, or an empty string if failures have no owners.
+/// 渲染“按负责人分组的失败”部分；如果失败没有负责人，则返回空字符串。
+fn render_owners(groups: &[OwnerFailures], locale: &str) -> String {
+    if groups.is_empty() {
+        return String::new();
+    }
+
+    let mut html = format!(
+        "<div class='owners'><h2>{}</h2><table><thead><tr><th>{}</th><th>{}</th></tr></thead><tbody>",
+        t!("html_report.owners.title", locale = locale),
+        t!("html_report.owners.owner", locale = locale),
+        t!("html_report.owners.cases", locale = locale),
+    );
+    for group in groups {
+        let owner = match &group.owner {
+            Some(owner) => escape_html(owner),
+            None => format!(
+                "<em>{}</em>",
+                t!("html_report.owners.unowned", locale = locale)
+            ),
+        };
+        let cases: Vec<String> = group
+            .cases
+            .iter()
+            .map(|case| format!("<code>{}</code>", escape_html(case)))
+            .collect();
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td></tr>",
+            owner,
+            cases.join(", ")
+        ));
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// The results of the cases of one package.
+struct PackageCounts {
+    name: String,
+    total: usize,
+    passed: usize,
+    failed: usize,
+}
+
+/// Counts the results per package, sorted by package; empty if no case sets a package.
+fn package_counts(results: &[TestResult]) -> Vec<PackageCounts> {
+    let mut counts: BTreeMap<&str, PackageCounts> = BTreeMap::new();
+    for result in results {
+        let Some(package) = result.package() else {
+            continue;
+        };
+        let entry = counts.entry(package).or_insert_with(|| PackageCounts {
+            name: package.to_string(),
+            total: 0,
+            passed: 0,
+            failed: 0,
+        });
+        entry.total += 1;
+        if result.is_failure() {
+            entry.failed += 1;
+        } else {
+            entry.passed += 1;
+        }
+    }
+    counts.into_values().collect()
+}
+
+/// Renders the counts per package below the summary, or an empty string without packages.
+/// 在摘要下方渲染每个包的计数；没有包时返回空字符串。
+fn render_package_summary(packages: &[PackageCounts], locale: &str) -> String {
+    if packages.is_empty() {
+        return String::new();
+    }
+
+    let mut html = format!(
+        "<div class='packages'><table><thead><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr></thead><tbody>",
+        t!("html_report.packages.package", locale = locale),
+        t!("html_report.summary.total", locale = locale),
+        t!("html_report.summary.passed", locale = locale),
+        t!("html_report.summary.failed", locale = locale),
+    );
+    for package in packages {
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&package.name),
+            package.total,
+            package.passed,
+            package.failed
+        ));
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// Renders the "Flakiness" section, or an empty string if no case needed retries.
+/// 渲染“不稳定性”部分；如果没有用例需要重试，则返回空字符串。
+fn render_flakiness(cases: &[FlakyCase], locale: &str) -> String {
+    if cases.is_empty() {
+        return String::new();
+    }
+
+    let mut html = format!(
+        "<div class='flakiness'><h2>{}</h2><table><thead><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr></thead><tbody>",
+        t!("html_report.flakiness.title", locale = locale),
+        t!("html_report.flakiness.case", locale = locale),
+        t!("html_report.flakiness.retries", locale = locale),
+        t!("html_report.flakiness.history", locale = locale),
+        t!("html_report.flakiness.score", locale = locale),
+    );
+    for case in cases {
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}/{}</td><td>{:.0}%</td></tr>",
+            escape_html(&case.case),
+            case.retries,
+            case.retried_runs,
+            case.runs,
+            case.flaky_score * 100.0
+        ));
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// Renders the "Results by axis" section, or an empty string if no axis tells cases apart.
+/// 渲染“按维度汇总的结果”部分；如果没有维度能区分用例，则返回空字符串。
+fn render_axes(axes: &[AxisValueSummary], locale: &str) -> String {
+    if axes.is_empty() {
+        return String::new();
+    }
+
+    let mut html = format!(
+        "<div class='axes'><h2>{}</h2><table><thead><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr></thead><tbody>",
+        t!("html_report.axes.title", locale = locale),
+        t!("html_report.axes.axis", locale = locale),
+        t!("html_report.axes.value", locale = locale),
+        t!("html_report.summary.passed", locale = locale),
+        t!("html_report.summary.failed", locale = locale),
+        t!("html_report.axes.pass_rate", locale = locale),
+    );
+    for summary in axes {
+        let class = if summary.passed == 0 {
+            "axis-all-failed"
+        } else if summary.failed > 0 {
+            "axis-some-failed"
+        } else {
+            "axis-passed"
+        };
+        html.push_str(&format!(
+            "<tr class='{}'><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td><td>{:.0}%</td></tr>",
+            class,
+            escape_html(&axis_label(summary.axis, locale)),
+            escape_html(&summary.value),
+            summary.passed,
+            summary.failed,
+            summary.pass_rate() * 100.0
+        ));
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// The columns of the results table when the matrix does not choose them.
+fn default_columns(show_costs: bool) -> Vec<SummaryColumn> {
+    let mut columns = vec![
+        SummaryColumn::Name,
+        SummaryColumn::Status,
+        SummaryColumn::Duration,
+        SummaryColumn::Retries,
+    ];
+    if show_costs {
+        columns.extend([SummaryColumn::BuildTime, SummaryColumn::RunTime, SummaryColumn::TargetSize]);
+    }
+    columns
+}
+
+/// The class attribute of the header and cells of a column.
+fn column_class(column: &SummaryColumn) -> &'static str {
+    match column {
+        SummaryColumn::Status => " class='status-col'",
+        SummaryColumn::Retries => " class='retries-cell'",
+        column if column.is_numeric() => " class='duration-cell'",
+        _ => "",
+    }
+}
+
+/// Renders the "Coverage" section with the line coverage of each `kind = "tarpaulin"`
+/// case and of all of them together, or an empty string if no case recorded coverage.
+/// 渲染“覆盖率”部分，包含每个 `kind = "tarpaulin"` 用例及其总计的行覆盖率；
+/// 如果没有用例记录覆盖率，则返回空字符串。
+fn render_coverage(coverage: &BTreeMap<String, Coverage>, locale: &str) -> String {
+    if coverage.is_empty() {
+        return String::new();
+    }
+
+    let mut html = format!(
+        "<div class='coverage'><h2>{}</h2><table><thead><tr><th>{}</th><th>{}</th><th>{}</th></tr></thead><tbody>",
+        t!("html_report.coverage.title", locale = locale),
+        t!("html_report.table.header.name", locale = locale),
+        t!("html_report.coverage.lines", locale = locale),
+        t!("html_report.coverage.percent", locale = locale),
+    );
+    let row = |name: String, coverage: &Coverage, class: &str| {
+        format!(
+            "<tr class='{}'><td>{}</td><td>{}/{}</td><td>{:.2}%</td></tr>",
+            class,
+            name,
+            coverage.covered,
+            coverage.coverable,
+            coverage.percent()
+        )
+    };
+    for (case, case_coverage) in coverage {
+        html.push_str(&row(escape_html(case), case_coverage, "coverage-case"));
+    }
+    html.push_str(&row(
+        t!("html_report.coverage.total", locale = locale).to_string(),
+        &Coverage::total(coverage.values()),
+        "coverage-total",
+    ));
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// Renders the "Environment" section describing where the run happened.
+/// 渲染描述运行所在位置的“环境”部分。
+fn render_environment(environment: &RunEnvironment, locale: &str) -> String {
+    let unknown = t!("html_report.environment.unknown", locale = locale).to_string();
+    let or_unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| unknown.clone());
+    let mut rows = vec![
+        (t!("html_report.environment.captured_at", locale = locale), environment.captured_at.to_rfc3339()),
+        (t!("html_report.environment.rustc", locale = locale), or_unknown(&environment.rustc_version)),
+        (t!("html_report.environment.cargo", locale = locale), or_unknown(&environment.cargo_version)),
+        (t!("html_report.environment.toolchain", locale = locale), or_unknown(&environment.toolchain)),
+        (t!("html_report.environment.host", locale = locale), or_unknown(&environment.host)),
+        (
+            t!("html_report.environment.os", locale = locale),
+            format!("{} ({})", environment.os, or_unknown(&environment.os_version)),
+        ),
+        (
+            t!("html_report.environment.cpu", locale = locale),
+            format!(
+                "{} × {} ({})",
+                environment.cpu_count,
+                or_unknown(&environment.cpu_model),
+                environment.arch
+            ),
+        ),
+    ];
+    for (name, value) in &environment.env_vars {
+        rows.push((name.clone().into(), value.clone()));
+    }
+
+    let mut html = format!(
+        "<div class='environment'><h2>{}</h2><table><tbody>",
+        t!("html_report.environment.title", locale = locale)
+    );
+    for (label, value) in rows {
+        html.push_str(&format!(
+            "<tr><th>{}</th><td><code>{}</code></td></tr>",
+            escape_html(&label),
+            escape_html(&value)
+        ));
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// Renders the collapsed "Configuration" section with the resolved matrix the run was made from.
+/// 渲染折叠的“配置”部分，其中包含本次运行所依据的已解析矩阵。
+fn render_config(config: &TestMatrix, locale: &str) -> String {
+    let text = toml::to_string_pretty(config)
+        .or_else(|_| serde_json::to_string_pretty(config))
+        .unwrap_or_default();
+    format!(
+        "<div class='config'><details><summary>{}</summary><pre class='output-content'>{}</pre></details></div>",
+        t!("html_report.config.title", locale = locale),
+        escape_html(&text)
+    )
+}
+
+/// Renders the name of a case. A case with a description, links, a test locale or skipped
+/// tests gets an expander showing them.
+/// 渲染用例名称。带有描述、链接、测试语言环境或被跳过测试的用例会有一个显示它们的展开器。
+fn render_case_name(result: &TestResult, locale: &str) -> String {
+    let name = escape_html(result.case_name());
+    let Some(case) = result
+        .get_case()
+        .filter(|case| {
+            case.description.is_some()
+                || !case.links.is_empty()
+                || case.env_locale.is_some()
+                || !case.skip_tests.is_empty()
+        })
+    else {
+        return name;
+    };
+
+    let mut info = String::new();
+    if let Some(description) = &case.description {
+        info.push_str(&format!("<p>{}</p>", escape_html(description)));
+    }
+    if !case.links.is_empty() {
+        let links: Vec<String> = case
+            .links
+            .iter()
+            .map(|link| format!("<a href='{0}'>{0}</a>", escape_html(link)))
+            .collect();
+        info.push_str(&format!(
+            "<div class='case-links'>{}: {}</div>",
+            t!("html_report.links", locale = locale),
+            links.join(", ")
+        ));
+    }
+    if let Some(env_locale) = &case.env_locale {
+        info.push_str(&format!(
+            "<div class='case-links'>{}: <code>{}</code></div>",
+            t!("html_report.env_locale", locale = locale),
+            escape_html(env_locale)
+        ));
+    }
+    if !case.skip_tests.is_empty() {
+        let tests: Vec<String> = case
+            .skip_tests
+            .iter()
+            .map(|test| format!("<code>{}</code>", escape_html(test)))
+            .collect();
+        info.push_str(&format!(
+            "<div class='case-links'>{}: {}</div>",
+            t!("html_report.skipped_tests", locale = locale),
+            tests.join(", ")
+        ));
+    }
+    format!("<details class='case-info'><summary>{}</summary>{}</details>", name, info)
+}
+
+/// Renders the annotations written by the test process, or an empty string if it wrote none.
+/// 渲染测试进程写入的注解；如果没有写入，则返回空字符串。
+fn render_annotations(result: &TestResult, locale: &str) -> String {
+    let annotations = result.get_annotations();
+    if annotations.is_empty() {
+        return String::new();
+    }
+
+    let pairs: Vec<String> = annotations
+        .iter()
+        .map(|(key, value)| format!("<code>{}={}</code>", escape_html(key), escape_html(value)))
+        .collect();
+
+    format!(
+        "<div class='annotations'>{}: {}</div>",
+        t!("html_report.annotations", locale = locale),
+        pairs.join(", ")
+    )
+}
+
+/// Renders the list of attachment links for a result, or an empty string if it has none.
+/// 渲染结果的附件链接列表；如果没有附件，则返回空字符串。
+fn render_attachments(result: &TestResult, locale: &str) -> String {
+    let attachments = result.get_attachments();
+    if attachments.is_empty() {
+        return String::new();
+    }
+
+    let links: Vec<String> = attachments
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            format!(
+                "<a href='{}'>{}</a>",
+                escape_html(&to_file_url(path)),
+                escape_html(&name)
+            )
+        })
+        .collect();
+
+    format!(
+        "<div class='attachments'>{}: {}</div>",
+        t!("html_report.attachments", locale = locale),
+        links.join(", ")
+    )
+}
+
+/// Converts a local file path into a `file://` URL usable from the report.
+/// 将本地文件路径转换为可在报告中使用的 `file://` URL。
+fn to_file_url(path: &Path) -> String {
+    let normalized = path.to_string_lossy().replace('\\', "/");
+    if normalized.starts_with('/') {
+        format!("file://{}", normalized)
+    } else {
+        format!("file:///{}", normalized)
+    }
 }
 
 /// Simple HTML escape function to replace special characters with their HTML entities
 /// 简单的 HTML 转义函数，用 HTML 实体替换特殊字符
-fn escape_html(input: &str) -> String {
+pub(crate) fn escape_html(input: &str) -> String {
     input
         .replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -0,0 +1,144 @@
+//! # JSON Reporting Module / JSON 报告模块
+//!
+//! This module writes the results of a run, together with its metadata,
+//! as a single JSON document that other tools can consume. The format is described by a
+//! versioned JSON Schema generated from the models (`matrix-runner schema`), which
+//! documents can be validated against (`run --validate-output`).
+//!
+//! 此模块将一次运行的结果及其元数据写成单个 JSON 文档，供其他工具使用。
+//! 该格式由从数据模型生成的带版本的 JSON Schema 描述（`matrix-runner schema`），
+//! 文档可以根据它进行验证（`run --validate-output`）。
+
+use anyhow::{Context, Result};
+use schemars::{JsonSchema, generate::SchemaSettings};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::core::models::{RunMetadata, TestResult};
+use crate::infra::fs::write_atomic;
+
+/// The file name of the results document inside the runner's state directory.
+/// 运行器状态目录中结果文档的文件名。
+pub const RESULTS_FILE: &str = "results.json";
+
+/// The version of the results format, raised on changes that break consumers.
+/// It is written into every document and fixed in the published schema.
+///
+/// 结果格式的版本，在发生破坏使用者的更改时提高。它会写入每个文档，并固定在发布的模式中。
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The results of a matrix-runner run, read back as [`crate::core::results::RunReport`]
+/// / matrix-runner 一次运行的结果，读取时对应 [`crate::core::results::RunReport`]
+#[derive(Serialize, JsonSchema)]
+#[schemars(title = "matrix-runner results")]
+struct JsonReport<'a> {
+    /// The version of the results format / 结果格式的版本
+    schema_version: u32,
+    /// Run-level information / 运行级信息
+    metadata: &'a RunMetadata,
+    /// The result of every case / 每个用例的结果
+    results: &'a [TestResult],
+}
+
+/// Writes the results and run metadata as pretty-printed JSON.
+///
+/// # Arguments
+/// * `results` - The test results to write
+/// * `metadata` - Run-level information such as notes and the captured environment
+/// * `output_path` - The file path to write to; parent directories are created
+///
+/// # Returns
+/// Success, or an error if the file could not be written
+///
+/// 将结果和运行元数据写为格式化的 JSON。
+pub fn generate_json_report(
+    results: &[TestResult],
+    metadata: &RunMetadata,
+    output_path: &Path,
+) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let report = JsonReport {
+        schema_version: SCHEMA_VERSION,
+        metadata,
+        results,
+    };
+    let json = serde_json::to_string_pretty(&report).context("Failed to serialize results")?;
+    write_atomic(output_path, json)
+        .with_context(|| format!("Failed to write results: {}", output_path.display()))
+}
+
+/// The JSON Schema of the results document, in draft 2020-12. It describes the documents
+/// as they are written, and `schema_version` only accepts [`SCHEMA_VERSION`].
+///
+/// 结果文档的 JSON Schema（draft 2020-12）。它描述写出时的文档，并且 `schema_version` 只接受 [`SCHEMA_VERSION`]。
+pub fn results_schema() -> Value {
+    let generator = SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<JsonReport>().to_value();
+    schema["properties"]["schema_version"] = serde_json::json!({
+        "description": "The version of the results format",
+        "const": SCHEMA_VERSION,
+    });
+    schema
+}
+
+/// A place where a results document does not match the schema.
+/// 结果文档中与模式不匹配的位置。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// The JSON pointer of the offending value, e.g. `/results/0/Passed/duration` / 出错值的 JSON 指针，例如 `/results/0/Passed/duration`
+    pub pointer: String,
+    /// What is wrong with it / 出错的原因
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+/// Validates a results document against [`results_schema`].
+///
+/// # Returns
+/// Every violation found, empty if the document is valid
+///
+/// 根据 [`results_schema`] 验证结果文档。返回找到的所有违规之处，文档有效时为空。
+pub fn validate_results(document: &Value) -> Vec<SchemaViolation> {
+    let schema = results_schema();
+    let validator =
+        jsonschema::validator_for(&schema).expect("the results schema is a valid JSON Schema");
+    validator
+        .iter_errors(document)
+        .map(|error| SchemaViolation {
+            pointer: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+/// Reads a results file and validates it against [`results_schema`].
+///
+/// # Returns
+/// Every violation found, or an error if the file cannot be read as JSON
+///
+/// 读取结果文件并根据 [`results_schema`] 验证它。返回找到的所有违规之处；如果无法将文件读取为 JSON 则返回错误。
+pub fn validate_results_file(path: &Path) -> Result<Vec<SchemaViolation>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read results: {}", path.display()))?;
+    let document: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse results: {}", path.display()))?;
+    Ok(validate_results(&document))
+}
@@ -0,0 +1,320 @@
+//! # JUnit Reporting Module / JUnit 报告模块
+//!
+//! This module writes the results of a run as JUnit XML, the format GitLab, Jenkins and
+//! Azure DevOps read natively for their test views and test-trend tracking. Every case
+//! becomes a `<testsuite>`, and every test libtest reported for it a `<testcase>`, so
+//! a single failing test shows up by name. Cases whose individual tests are not known
+//! (a failed build, a timeout, a custom `command`, a run by nextest) are reported as a
+//! single test named after the case. Allowed failures and `build_only` cases are
+//! reported as skipped, so the report agrees with the exit code of the run.
+//!
+//! 此模块将一次运行的结果写为 JUnit XML，这是 GitLab、Jenkins 和 Azure DevOps 的测试视图和
+//! 测试趋势跟踪原生读取的格式。每个用例成为一个 `<testsuite>`，libtest 为其报告的每个测试成为一个
+//! `<testcase>`，因此单个失败的测试会按名称显示。无法得知其各个测试的用例（构建失败、超时、
+//! 自定义 `command`、由 nextest 运行）会被报告为一个以用例命名的测试。允许的失败和 `build_only`
+//! 用例被报告为跳过，使报告与运行的退出码一致。
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::core::context::RunContext;
+use crate::core::feature_compare::TestOutcome;
+use crate::core::models::{RunMetadata, TestResult};
+use crate::infra::command::parse_test_outcomes;
+use crate::infra::fs::write_atomic;
+use crate::reporting::extract::extract_failure_context;
+
+/// A `<testcase>` element / 一个 `<testcase>` 元素
+struct JunitCase {
+    name: String,
+    time: Option<Duration>,
+    outcome: JunitOutcome,
+}
+
+/// The outcome of a `<testcase>` / 一个 `<testcase>` 的结果
+enum JunitOutcome {
+    Passed,
+    Failed {
+        kind: String,
+        message: String,
+        details: String,
+    },
+    Skipped {
+        message: String,
+    },
+}
+
+/// Writes the results of a run as a JUnit XML report.
+///
+/// # Arguments
+/// * `results` - The test results to write
+/// * `metadata` - Run-level information; the time the environment was captured becomes the suites' timestamp
+/// * `output_path` - The file path to write to; parent directories are created
+/// * `context` - The platform allowed failures are decided on
+/// * `today` - The date allowed failures are decided on
+///
+/// # Returns
+/// Success, or an error if the file could not be written
+///
+/// 将一次运行的结果写为 JUnit XML 报告。
+pub fn generate_junit_report(
+    results: &[TestResult],
+    metadata: &RunMetadata,
+    output_path: &Path,
+    context: &RunContext,
+    today: NaiveDate,
+) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    write_atomic(
+        output_path,
+        render_junit_report(results, metadata, context, today),
+    )
+    .with_context(|| format!("Failed to write JUnit report: {}", output_path.display()))
+}
+
+/// Renders the results of a run as a JUnit XML document. Skipped results without a case
+/// are left out; cases left out of the run on purpose and allowed failures, decided on the
+/// platform of `context` as of `today`, are reported as skipped.
+///
+/// 将一次运行的结果渲染为 JUnit XML 文档。不带用例的跳过结果被省略；被有意排除在运行之外的用例
+/// 以及允许的失败（按 `context` 的平台和 `today` 的日期判断）报告为跳过。
+pub fn render_junit_report(
+    results: &[TestResult],
+    metadata: &RunMetadata,
+    context: &RunContext,
+    today: NaiveDate,
+) -> String {
+    let timestamp = metadata.environment.as_ref().map(|environment| {
+        environment
+            .captured_at
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string()
+    });
+
+    let mut suites = String::new();
+    let (mut total, mut failures, mut skipped, mut time) = (0, 0, 0, Duration::ZERO);
+    for result in results {
+        let Some(case) = result.get_case() else {
+            continue;
+        };
+        let cases = junit_cases(result, context, today);
+        let suite_failures = cases
+            .iter()
+            .filter(|c| matches!(c.outcome, JunitOutcome::Failed { .. }))
+            .count();
+        let suite_skipped = cases
+            .iter()
+            .filter(|c| matches!(c.outcome, JunitOutcome::Skipped { .. }))
+            .count();
+        let suite_time = result.get_duration().unwrap_or_default();
+        total += cases.len();
+        failures += suite_failures;
+        skipped += suite_skipped;
+        time += suite_time;
+
+        let _ = write!(
+            suites,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{suite_failures}\" errors=\"0\" skipped=\"{suite_skipped}\" time=\"{:.3}\"",
+            escape_xml(&case.name),
+            cases.len(),
+            suite_time.as_secs_f64()
+        );
+        if let Some(timestamp) = &timestamp {
+            let _ = write!(suites, " timestamp=\"{timestamp}\"");
+        }
+        suites.push_str(">\n    <properties>\n");
+        let properties = [
+            ("id", Some(case.case_id())),
+            ("package", case.package.clone()),
+            ("features", Some(case.features.clone())),
+            (
+                "no_default_features",
+                Some(case.no_default_features.to_string()),
+            ),
+            ("toolchain", case.toolchain.clone()),
+            ("target", case.target.clone()),
+        ];
+        for (name, value) in properties {
+            if let Some(value) = value {
+                let _ = writeln!(
+                    suites,
+                    "      <property name=\"{name}\" value=\"{}\"/>",
+                    escape_xml(&value)
+                );
+            }
+        }
+        suites.push_str("    </properties>\n");
+        for junit_case in &cases {
+            write_case(&mut suites, &case.name, junit_case);
+        }
+        suites.push_str("  </testsuite>\n");
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"matrix-runner\" tests=\"{total}\" failures=\"{failures}\" errors=\"0\" skipped=\"{skipped}\" time=\"{:.3}\">",
+        time.as_secs_f64()
+    );
+    xml.push_str(&suites);
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// The `<testcase>` elements of a result: one per test libtest reported, or one for the
+/// whole case when its tests are not known or do not explain its failure.
+fn junit_cases(result: &TestResult, context: &RunContext, today: NaiveDate) -> Vec<JunitCase> {
+    let case_name = result.case_name().to_string();
+    let output = result.get_output();
+    let whole_case = |outcome| {
+        vec![JunitCase {
+            name: case_name.clone(),
+            time: result.get_duration(),
+            outcome,
+        }]
+    };
+
+    match result {
+        TestResult::Built { .. } => {
+            return whole_case(JunitOutcome::Skipped {
+                message: "built only, tests not run".to_string(),
+            });
+        }
+        TestResult::Failed { reason, .. } if result.is_allowed_failure(context, today) => {
+            return whole_case(JunitOutcome::Skipped {
+                message: format!("allowed failure ({reason:?})"),
+            });
+        }
+        TestResult::Skipped { reason, .. } => {
+            return whole_case(JunitOutcome::Skipped {
+                message: reason.clone().unwrap_or_else(|| "skipped".to_string()),
+            });
+        }
+        _ => {}
+    }
+
+    let timings: BTreeMap<&str, Duration> = result
+        .get_test_timings()
+        .iter()
+        .map(|timing| (timing.name.as_str(), timing.duration))
+        .collect();
+    let mut cases: Vec<JunitCase> = parse_test_outcomes(&output)
+        .into_iter()
+        .map(|(name, outcome)| {
+            let outcome = match outcome {
+                TestOutcome::Passed => JunitOutcome::Passed,
+                TestOutcome::Ignored => JunitOutcome::Skipped {
+                    message: "ignored".to_string(),
+                },
+                TestOutcome::Failed => JunitOutcome::Failed {
+                    kind: "TestFailed".to_string(),
+                    message: "test failed".to_string(),
+                    details: test_section(&output, &name).unwrap_or_default(),
+                },
+            };
+            JunitCase {
+                time: timings.get(name.as_str()).copied(),
+                name,
+                outcome,
+            }
+        })
+        .collect();
+
+    if let TestResult::Failed { reason, .. } = result {
+        let explained = cases
+            .iter()
+            .any(|c| matches!(c.outcome, JunitOutcome::Failed { .. }));
+        if !explained {
+            let details = extract_failure_context(&output).unwrap_or(output.clone());
+            cases.push(JunitCase {
+                name: case_name.clone(),
+                time: result.get_duration(),
+                outcome: JunitOutcome::Failed {
+                    kind: format!("{reason:?}"),
+                    message: format!("{reason:?}"),
+                    details,
+                },
+            });
+        }
+    }
+    if cases.is_empty() {
+        return whole_case(JunitOutcome::Passed);
+    }
+    cases
+}
+
+fn write_case(xml: &mut String, class_name: &str, case: &JunitCase) {
+    let _ = write!(
+        xml,
+        "    <testcase classname=\"{}\" name=\"{}\"",
+        escape_xml(class_name),
+        escape_xml(&case.name)
+    );
+    if let Some(time) = case.time {
+        let _ = write!(xml, " time=\"{:.3}\"", time.as_secs_f64());
+    }
+    match &case.outcome {
+        JunitOutcome::Passed => xml.push_str("/>\n"),
+        JunitOutcome::Skipped { message } => {
+            let _ = writeln!(
+                xml,
+                ">\n      <skipped message=\"{}\"/>\n    </testcase>",
+                escape_xml(message)
+            );
+        }
+        JunitOutcome::Failed {
+            kind,
+            message,
+            details,
+        } => {
+            let _ = writeln!(
+                xml,
+                ">\n      <failure type=\"{}\" message=\"{}\">{}</failure>\n    </testcase>",
+                escape_xml(kind),
+                escape_xml(message),
+                escape_xml(details)
+            );
+        }
+    }
+}
+
+/// The `---- <test> stdout ----` section libtest printed for a failed test, if any.
+fn test_section(output: &str, test: &str) -> Option<String> {
+    let header = format!("---- {test} stdout ----");
+    let mut lines = output.lines().skip_while(|line| line.trim() != header);
+    lines.next()?;
+    let section: Vec<&str> = lines
+        .take_while(|line| {
+            !(line.starts_with("---- ") && line.ends_with(" ----")) && line.trim() != "failures:"
+        })
+        .collect();
+    Some(section.join("\n").trim().to_string())
+}
+
+/// Escapes text for XML attributes and content. Characters XML 1.0 does not allow, such
+/// as the escape codes of colored output, are dropped.
+/// 为 XML 属性和内容转义文本。XML 1.0 不允许的字符（例如彩色输出的转义码）会被删除。
+fn escape_xml(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,147 @@
+//! # Partial Results Module / 部分结果模块
+//!
+//! This module keeps track of the cases that have finished during a run, so that if the
+//! runner itself panics mid-run, a panic hook can still print the summary of the finished
+//! cases and write partial JSON results instead of losing everything.
+//!
+//! Each run keeps its own [`PartialRun`] and registers its own hook, which only acts on
+//! panics of the thread that started the run: panics inside spawned case tasks are already
+//! turned into failed results by the executor.
+//!
+//! 此模块跟踪运行期间已完成的用例，以便当运行器本身在运行中途发生 panic 时，
+//! panic 钩子仍然可以打印已完成用例的摘要并写出部分 JSON 结果，而不是丢失所有信息。
+//!
+//! 每次运行持有自己的 [`PartialRun`] 并注册自己的钩子，该钩子只处理启动该运行的线程的 panic：
+//! 用例任务内部的 panic 已由执行器转换为失败结果。
+
+use chrono::NaiveDate;
+use colored::*;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::ThreadId;
+
+use crate::core::context::RunContext;
+use crate::core::models::{RunMetadata, TestResult};
+use crate::infra::t;
+use crate::reporting::{console::print_summary, json};
+
+/// The state of the run in progress / 正在进行的运行的状态
+#[derive(Debug)]
+struct RunState {
+    results: Vec<TestResult>,
+    metadata: RunMetadata,
+    state_dir: PathBuf,
+    context: RunContext,
+    today: NaiveDate,
+}
+
+/// The cases finished so far by one run, reported by the panic hook the run registers.
+/// Cloning yields a handle to the same run.
+///
+/// 一次运行目前已完成的用例，由该运行注册的 panic 钩子报告。克隆会得到指向同一运行的句柄。
+#[derive(Debug, Clone, Default)]
+pub struct PartialRun {
+    state: Arc<Mutex<Option<RunState>>>,
+}
+
+impl PartialRun {
+    /// Starts tracking the run and registers its panic hook, which reports the run's
+    /// finished cases when the thread calling this panics.
+    ///
+    /// # Arguments
+    /// * `metadata` - The run metadata to include in partial results
+    /// * `state_dir` - The directory the partial JSON results are written to
+    /// * `context` - The locale for the printed summary and the platform allowed failures are decided on
+    /// * `today` - The date allowed failures are decided on
+    ///
+    /// 开始跟踪运行并注册其 panic 钩子，当调用此方法的线程发生 panic 时，该钩子会报告运行中已完成的用例。
+    pub fn begin(
+        &self,
+        metadata: RunMetadata,
+        state_dir: PathBuf,
+        context: &RunContext,
+        today: NaiveDate,
+    ) {
+        if let Ok(mut guard) = self.state.lock() {
+            *guard = Some(RunState {
+                results: Vec::new(),
+                metadata,
+                state_dir,
+                context: context.clone(),
+                today,
+            });
+        }
+        register_panic_hook(Arc::downgrade(&self.state), std::thread::current().id());
+    }
+
+    /// Records a finished case.
+    /// 记录一个已完成的用例。
+    pub fn record(&self, result: &TestResult) {
+        if let Ok(mut guard) = self.state.lock()
+            && let Some(run) = guard.as_mut()
+        {
+            run.results.push(result.clone());
+        }
+    }
+
+    /// Stops tracking the run once its full results are available; its hook then does nothing.
+    /// 在完整结果可用后停止跟踪运行；此后其钩子不再执行任何操作。
+    pub fn finish(&self) {
+        if let Ok(mut guard) = self.state.lock() {
+            *guard = None;
+        }
+    }
+}
+
+/// Chains a hook for one run in front of the hooks already set. It only holds the run
+/// weakly, so it is inert once the run is finished or dropped.
+fn register_panic_hook(state: Weak<Mutex<Option<RunState>>>, thread: ThreadId) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        if std::thread::current().id() == thread
+            && let Some(state) = state.upgrade()
+        {
+            report_partial_results(&state);
+        }
+    }));
+}
+
+/// Prints and writes whatever finished before the panic. The lock is only tried,
+/// never waited on, so a panic while it is held cannot deadlock the hook.
+fn report_partial_results(state: &Mutex<Option<RunState>>) {
+    let Ok(mut guard) = state.try_lock() else {
+        return;
+    };
+    let Some(mut run) = guard.take() else {
+        return;
+    };
+    let locale = run.context.locale.as_str();
+
+    eprintln!(
+        "\n{}",
+        t!(
+            "run.panic_partial_summary",
+            locale = locale,
+            count = run.results.len()
+        )
+        .red()
+        .bold()
+    );
+    print_summary(&run.results, &run.context, run.today);
+
+    run.metadata
+        .notes
+        .push(t!("run.partial_results_note", locale = locale).to_string());
+    let path = run.state_dir.join(json::RESULTS_FILE);
+    if json::generate_json_report(&run.results, &run.metadata, &path).is_ok() {
+        eprintln!(
+            "{}",
+            t!(
+                "run.partial_results_written",
+                locale = locale,
+                path = path.display()
+            )
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! # Reproduction Bundle Module / 复现包模块
+//!
+//! This module writes a "repro bundle" for every failed case: a directory with
+//! everything needed to reproduce the failure outside the runner, plus a zip of
+//! that directory for attaching to bug reports. A bundle contains:
+//!
+//! - `case.toml`: the case definition, ready to paste into a matrix
+//! - `environment.json`: the captured run environment
+//! - `commands.txt`: the exact command, with the case's environment, and working directory
+//! - `seeds.env`: seed-like environment variables (e.g. `PROPTEST_RNG_SEED`)
+//! - `output.log`: the full output of the failed case
+//! - `repro.sh` / `repro.ps1`: scripts that rerun the case with the same environment
+//!
+//! 此模块为每个失败的用例写出一个“复现包”：一个包含在运行器之外复现该失败所需全部内容的目录，
+//! 以及该目录的 zip 压缩包，便于附加到缺陷报告中。
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::core::config::TestCase;
+use crate::core::execution::{bare_command_line, command_env, command_line};
+use crate::core::models::{RunMetadata, TestResult};
+use crate::infra::fs::case_dir_name;
+
+/// Writes a repro bundle for every failed result.
+///
+/// # Arguments
+/// * `results` - The results of the run; only failures get a bundle
+/// * `metadata` - The run metadata, providing the captured environment
+/// * `project_root` - The directory the commands are run in
+/// * `default_package` - The package tested when a case does not set `package`
+/// * `bundle_dir` - The directory the bundles are written to
+///
+/// # Returns
+/// The paths of the zipped bundles
+///
+/// 为每个失败的结果写出一个复现包。
+pub fn write_repro_bundles(
+    results: &[TestResult],
+    metadata: &RunMetadata,
+    project_root: &Path,
+    default_package: &str,
+    bundle_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    results
+        .iter()
+        .filter_map(|result| match result {
+            TestResult::Failed { case, output, .. } => Some((case, output)),
+            _ => None,
+        })
+        .map(|(case, output)| {
+            write_bundle(
+                case,
+                output,
+                metadata,
+                project_root,
+                default_package,
+                bundle_dir,
+            )
+        })
+        .collect()
+}
+
+fn write_bundle(
+    case: &TestCase,
+    output: &str,
+    metadata: &RunMetadata,
+    project_root: &Path,
+    default_package: &str,
+    bundle_dir: &Path,
+) -> Result<PathBuf> {
+    let dir = bundle_dir.join(case_dir_name(&case.name));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let command = command_line(case, default_package);
+    let bare_command = bare_command_line(case, default_package);
+    let mut env_vars = metadata
+        .environment
+        .as_ref()
+        .map(|env| env.env_vars.clone())
+        .unwrap_or_default();
+    let seeds = seed_vars();
+    env_vars.extend(seeds.clone());
+    // The case's own variables win, as they do when the runner spawns the case.
+    env_vars.extend(command_env(case));
+
+    #[derive(Serialize)]
+    struct CaseSnippet<'a> {
+        cases: [&'a TestCase; 1],
+    }
+    let files = [
+        (
+            "case.toml",
+            toml::to_string_pretty(&CaseSnippet { cases: [case] })
+                .context("Failed to serialize case")?,
+        ),
+        (
+            "environment.json",
+            serde_json::to_string_pretty(&metadata.environment)
+                .context("Failed to serialize environment")?,
+        ),
+        (
+            "commands.txt",
+            format!("cd {}\n{}\n", project_root.display(), command),
+        ),
+        ("seeds.env", to_env_file(&seeds)),
+        ("output.log", output.to_string()),
+        (
+            "repro.sh",
+            shell_script(project_root, &env_vars, &bare_command),
+        ),
+        (
+            "repro.ps1",
+            powershell_script(project_root, &env_vars, &bare_command),
+        ),
+    ];
+    for (name, content) in &files {
+        let path = dir.join(name);
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(dir.join("repro.sh"), fs::Permissions::from_mode(0o755));
+    }
+
+    let zip_path = dir.with_extension("zip");
+    write_zip(&zip_path, &files)?;
+    Ok(zip_path)
+}
+
+/// Environment variables of the runner that look like random seeds.
+fn seed_vars() -> BTreeMap<String, String> {
+    std::env::vars()
+        .filter(|(name, _)| name.to_ascii_uppercase().contains("SEED"))
+        .collect()
+}
+
+fn to_env_file(vars: &BTreeMap<String, String>) -> String {
+    vars.iter().map(|(k, v)| format!("{k}={v}\n")).collect()
+}
+
+fn shell_script(project_root: &Path, env_vars: &BTreeMap<String, String>, command: &str) -> String {
+    let quote = |s: &str| {
+        shlex::try_quote(s)
+            .map(|q| q.into_owned())
+            .unwrap_or_else(|_| s.to_string())
+    };
+    let mut script = String::from("#!/bin/sh\nset -e\n");
+    script.push_str(&format!(
+        "cd {}\n",
+        quote(&project_root.display().to_string())
+    ));
+    for (name, value) in env_vars {
+        script.push_str(&format!("export {}={}\n", name, quote(value)));
+    }
+    script.push_str(command);
+    script.push('\n');
+    script
+}
+
+fn powershell_script(
+    project_root: &Path,
+    env_vars: &BTreeMap<String, String>,
+    command: &str,
+) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let mut script = String::from("$ErrorActionPreference = 'Stop'\n");
+    script.push_str(&format!(
+        "Set-Location {}\n",
+        quote(&project_root.display().to_string())
+    ));
+    for (name, value) in env_vars {
+        script.push_str(&format!("$env:{} = {}\n", name, quote(value)));
+    }
+    script.push_str(command);
+    script.push('\n');
+    script
+}
+
+fn write_zip(path: &Path, files: &[(&str, String)]) -> Result<()> {
+    let file =
+        fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in files {
+        zip.start_file(*name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
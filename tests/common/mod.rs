@@ -9,9 +9,12 @@
 #![allow(dead_code)]
 
 // Shared test helpers for integration tests
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::models::{FailureReason, TestResult};
 use std::fs;
 use std::path::PathBuf;
-use tempfile::{tempdir, TempDir};
+use std::time::Duration;
+use tempfile::{TempDir, tempdir};
 
 pub fn setup_test_environment() -> TempDir {
     let temp_dir = tempdir().expect("Failed to create temporary directory");
@@ -117,3 +120,43 @@ arch = ["x86_64", "aarch64"]
     fs::write(&matrix_path, content).unwrap();
     matrix_path
 }
+
+/// Helper function to create a passed result of a case that took `duration` over `retries` attempts
+/// 创建用例通过结果的辅助函数，耗时 `duration`，共 `retries` 次尝试
+pub fn passed_in(case: TestCase, duration: Duration, retries: u8) -> TestResult {
+    TestResult::Passed {
+        case,
+        output: String::new(),
+        duration,
+        retries,
+        attachments: Vec::new(),
+        test_timings: Vec::new(),
+        annotations: Default::default(),
+    }
+}
+
+/// Helper function to create a passed result of a case, taking one second in one attempt
+/// 创建用例通过结果的辅助函数，一次尝试耗时一秒
+pub fn passed(case: TestCase) -> TestResult {
+    passed_in(case, Duration::from_secs(1), 1)
+}
+
+/// Helper function to create a failed result of a case with the given reason and output
+/// 创建具有给定原因和输出的用例失败结果的辅助函数
+pub fn failed_with(case: TestCase, reason: FailureReason, output: &str, duration: Duration) -> TestResult {
+    TestResult::Failed {
+        case,
+        output: output.to_string(),
+        reason,
+        duration,
+        attachments: Vec::new(),
+        test_timings: Vec::new(),
+        annotations: Default::default(),
+    }
+}
+
+/// Helper function to create a result of a case whose tests failed after one second, without output
+/// 创建用例测试失败结果的辅助函数，耗时一秒，无输出
+pub fn failed(case: TestCase) -> TestResult {
+    failed_with(case, FailureReason::TestFailed, "", Duration::from_secs(1))
+}
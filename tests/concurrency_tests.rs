@@ -73,7 +73,7 @@ mod tests {
                     // Due to the race condition, we can't know the exact number of tasks
                     // that completed, but it must be between 1 and NUM_TASKS.
                     assert!(
-                        final_count >= 1 && final_count <= NUM_TASKS,
+                        (1..=NUM_TASKS).contains(&final_count),
                         "Final count was {}",
                         final_count
                     );
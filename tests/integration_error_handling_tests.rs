@@ -114,6 +114,85 @@ cases = []
     }
 }
 
+#[cfg(test)]
+mod selector_tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_select_expression() {
+        let temp_dir = TempDir::new().unwrap();
+        let matrix_path = temp_dir.path().join("select.toml");
+        let content = r#"
+language = "en"
+
+[[cases]]
+name = "tagged-case"
+command = "echo tagged"
+features = ""
+no_default_features = false
+tags = ["fast"]
+"#;
+        fs::write(&matrix_path, content).unwrap();
+
+        let mut cmd = Command::cargo_bin("matrix-runner").unwrap();
+        cmd.arg("run")
+            .arg("--lang")
+            .arg("en")
+            .arg("--config")
+            .arg(&matrix_path)
+            .arg("--project-dir")
+            .arg("tests/sample_project")
+            .arg("--select")
+            .arg("tag = fast");
+
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid --select expression"))
+            .stderr(predicate::str::contains("expected `==`, found a single `=`"));
+    }
+
+    #[test]
+    fn test_select_by_tag() {
+        let temp_dir = TempDir::new().unwrap();
+        let matrix_path = temp_dir.path().join("select.toml");
+        let content = r#"
+language = "en"
+
+[[cases]]
+name = "fast-case"
+command = "echo fast-case-ran"
+features = ""
+no_default_features = false
+tags = ["fast"]
+
+[[cases]]
+name = "slow-case"
+command = "echo slow-case-ran"
+features = ""
+no_default_features = false
+tags = ["slow"]
+"#;
+        fs::write(&matrix_path, content).unwrap();
+
+        let mut cmd = Command::cargo_bin("matrix-runner").unwrap();
+        cmd.arg("run")
+            .arg("--lang")
+            .arg("en")
+            .arg("--config")
+            .arg(&matrix_path)
+            .arg("--project-dir")
+            .arg("tests/sample_project")
+            .arg("--select")
+            .arg(r#"tag == "fast""#);
+
+        cmd.assert()
+            .success()
+            .stdout(predicate::str::contains("Selected 1 of 2 cases with --select."))
+            .stdout(predicate::str::contains("fast-case-ran"))
+            .stdout(predicate::str::contains("slow-case-ran").not());
+    }
+}
+
 #[cfg(test)]
 mod project_error_tests {
     use super::*;
@@ -515,3 +594,69 @@ no_default_features = false
         ));
     }
 }
+
+#[cfg(test)]
+mod exit_code_tests {
+    use super::*;
+
+    #[test]
+    fn test_config_error_exit_code() {
+        let mut cmd = Command::cargo_bin("matrix-runner").unwrap();
+        cmd.arg("run")
+            .arg("--lang")
+            .arg("en")
+            .arg("--config")
+            .arg("nonexistent_file.toml")
+            .arg("--project-dir")
+            .arg("tests/sample_project");
+
+        cmd.assert().code(2);
+    }
+
+    #[test]
+    fn test_environment_error_exit_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let matrix_path = temp_dir.path().join("valid.toml");
+        fs::write(&matrix_path, "language = \"en\"\ncases = []\n").unwrap();
+
+        let mut cmd = Command::cargo_bin("matrix-runner").unwrap();
+        cmd.arg("run")
+            .arg("--lang")
+            .arg("en")
+            .arg("--config")
+            .arg(&matrix_path)
+            .arg("--project-dir")
+            .arg(temp_dir.path().join("missing_project"));
+
+        cmd.assert().code(3);
+    }
+
+    #[test]
+    fn test_unexpected_failure_exit_code() {
+        let temp_dir = TempDir::new().unwrap();
+        let matrix_path = temp_dir.path().join("failing.toml");
+        let content = r#"
+language = "en"
+
+[[cases]]
+name = "failing-command"
+command = "cargo this-subcommand-does-not-exist"
+features = ""
+no_default_features = false
+"#;
+        fs::write(&matrix_path, content).unwrap();
+
+        let mut cmd = Command::cargo_bin("matrix-runner").unwrap();
+        cmd.arg("run")
+            .arg("--lang")
+            .arg("en")
+            .arg("--config")
+            .arg(&matrix_path)
+            .arg("--project-dir")
+            .arg("tests/sample_project");
+
+        cmd.assert()
+            .code(4)
+            .stderr(predicate::str::contains("Matrix tests failed with unexpected errors."));
+    }
+}
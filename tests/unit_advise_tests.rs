@@ -0,0 +1,207 @@
+//! # Advise Module Unit Tests / 建议模块单元测试
+//!
+//! This module contains unit tests for the `advise.rs` module,
+//! testing which gaps in a matrix are found and which cases are suggested to close them.
+//!
+//! 此模块包含 `advise.rs` 模块的单元测试，
+//! 测试会发现矩阵中的哪些缺口，以及会建议哪些用例来弥补它们。
+
+use matrix_runner::core::advise::{Advice, ProjectFacts, advise};
+use matrix_runner::core::config::{CaseKind, TestCase};
+use matrix_runner::core::context::RunContext;
+use matrix_runner::infra::workspace::DocsRsMetadata;
+use std::collections::BTreeMap;
+
+/// Helper function to create a case / 创建用例的辅助函数
+fn case(name: &str, features: &str, no_default_features: bool) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        features: features.to_string(),
+        no_default_features,
+        ..Default::default()
+    }
+}
+
+/// The features of a crate with `std` on by default and `serde` off / 默认启用 `std`、不启用 `serde` 的 crate 的 features
+fn facts() -> ProjectFacts {
+    let features: BTreeMap<String, Vec<String>> = [
+        ("default", vec!["std"]),
+        ("std", vec![]),
+        ("serde", vec!["dep:serde", "std"]),
+        ("full", vec!["serde", "rand?/std"]),
+        ("rand", vec!["dep:rand"]),
+        ("__internal", vec![]),
+    ]
+    .into_iter()
+    .map(|(name, enables)| {
+        (
+            name.to_string(),
+            enables.into_iter().map(str::to_string).collect(),
+        )
+    })
+    .collect();
+    ProjectFacts {
+        features,
+        ..Default::default()
+    }
+}
+
+/// A Linux x86_64 run / 一次 Linux x86_64 运行
+fn linux() -> RunContext {
+    RunContext {
+        os: "linux".to_string(),
+        arch: "x86_64".to_string(),
+        ..RunContext::new("en")
+    }
+}
+
+fn advice(cases: &[TestCase], facts: &ProjectFacts) -> Vec<Advice> {
+    advise(cases, facts, &linux())
+        .into_iter()
+        .map(|suggestion| suggestion.advice)
+        .collect()
+}
+
+#[cfg(test)]
+mod feature_advice_tests {
+    use super::*;
+
+    #[test]
+    fn test_features_enabled_through_others_count_as_tested() {
+        let cases = [case("plain", "", false), case("full", "full", false)];
+        assert_eq!(
+            advice(&cases, &facts()),
+            [Advice::NoDefaultFeaturesUntested]
+        );
+    }
+
+    #[test]
+    fn test_untested_features_are_suggested_with_a_case() {
+        let suggestions = advise(&[case("plain", "", false)], &facts(), &linux());
+        let advice: Vec<&Advice> = suggestions
+            .iter()
+            .map(|suggestion| &suggestion.advice)
+            .collect();
+        assert_eq!(
+            advice,
+            [
+                &Advice::FeatureNeverEnabled {
+                    feature: "full".to_string()
+                },
+                &Advice::FeatureNeverEnabled {
+                    feature: "rand".to_string()
+                },
+                &Advice::FeatureNeverEnabled {
+                    feature: "serde".to_string()
+                },
+                &Advice::NoDefaultFeaturesUntested,
+                &Advice::AllFeaturesUntested,
+            ]
+        );
+        assert_eq!(suggestions[2].case.features, "serde");
+        assert!(suggestions[3].case.no_default_features);
+        assert_eq!(suggestions[4].case.features, "full,rand,serde,std");
+    }
+
+    #[test]
+    fn test_features_only_tested_with_the_defaults() {
+        let cases = [
+            case("all", "full,rand", false),
+            case("bare", "", true),
+            case("rand-only", "rand", true),
+        ];
+        let suggestions = advise(&cases, &facts(), &linux());
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            suggestions[0].advice,
+            Advice::FeatureNeverWithoutDefaults {
+                feature: "full".to_string()
+            }
+        );
+        assert_eq!(suggestions[1].case.name, "serde-no-default");
+        assert!(suggestions[1].case.no_default_features);
+    }
+}
+
+#[cfg(test)]
+mod toolchain_and_target_advice_tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_version_needs_a_case_on_that_toolchain() {
+        let facts = ProjectFacts {
+            rust_version: Some("1.70.0".to_string()),
+            ..Default::default()
+        };
+        let suggestions = advise(&[case("plain", "", false)], &facts, &linux());
+        assert_eq!(
+            suggestions[0].advice,
+            Advice::MsrvUntested {
+                version: "1.70.0".to_string()
+            }
+        );
+        assert_eq!(suggestions[0].case.toolchain.as_deref(), Some("1.70.0"));
+
+        let mut msrv = case("msrv", "", false);
+        msrv.toolchain = Some("1.70".to_string());
+        assert!(advice(&[msrv], &facts).is_empty());
+    }
+
+    #[test]
+    fn test_docs_rs_metadata_needs_a_doc_case() {
+        let facts = ProjectFacts {
+            docs_rs: Some(DocsRsMetadata {
+                all_features: true,
+                targets: vec![
+                    "x86_64-unknown-linux-gnu".to_string(),
+                    "wasm32-unknown-unknown".to_string(),
+                ],
+                ..Default::default()
+            }),
+            ..facts()
+        };
+        let cases = [
+            case("bare", "full,rand", true),
+            case("all", "full,rand", false),
+        ];
+        let suggestions = advise(&cases, &facts, &linux());
+        assert_eq!(suggestions[0].advice, Advice::DocsRsUncovered);
+        assert_eq!(suggestions[0].case.kind, CaseKind::Doc);
+        assert_eq!(
+            suggestions[1].advice,
+            Advice::DocsRsTargetUncovered {
+                target: "wasm32-unknown-unknown".to_string()
+            }
+        );
+        assert_eq!(
+            suggestions[1].case.target.as_deref(),
+            Some("wasm32-unknown-unknown")
+        );
+        assert_eq!(suggestions.len(), 2);
+
+        let mut docs = case("docs", "full,rand", false);
+        docs.kind = CaseKind::Doc;
+        docs.target = Some("wasm32-unknown-unknown".to_string());
+        assert!(advice(&[case("bare", "full,rand", true), docs], &facts).is_empty());
+    }
+
+    #[test]
+    fn test_ci_targets_the_host_cannot_run_need_a_case() {
+        let facts = ProjectFacts {
+            ci_targets: ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            ..Default::default()
+        };
+        let suggestions = advise(&[case("plain", "", false)], &facts, &linux());
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(
+            suggestions[0].advice,
+            Advice::CiTargetUncovered {
+                target: "aarch64-apple-darwin".to_string()
+            }
+        );
+        assert_eq!(suggestions[0].case.name, "aarch64-apple-darwin");
+    }
+}
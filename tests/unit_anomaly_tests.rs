@@ -0,0 +1,82 @@
+//! # Anomaly Module Unit Tests / Anomaly 模块单元测试
+//!
+//! This module contains unit tests for the `anomaly.rs` module,
+//! testing how durations are compared against `expected_secs`.
+//!
+//! 此模块包含 `anomaly.rs` 模块的单元测试，
+//! 测试耗时如何与 `expected_secs` 进行比较。
+
+mod common;
+use crate::common::{failed_with, passed_in};
+use matrix_runner::core::anomaly::detect_anomalies;
+use matrix_runner::core::config::{TestCase, TestMatrix};
+use matrix_runner::core::models::{FailureReason, TestResult};
+use std::time::Duration;
+
+/// Helper function to create a passed result / 创建通过结果的辅助函数
+fn passed(name: &str, expected_secs: Option<u64>, secs: u64) -> TestResult {
+    let case = TestCase {
+        name: name.to_string(),
+        expected_secs,
+        ..Default::default()
+    };
+    passed_in(case, Duration::from_secs(secs), 1)
+}
+
+#[cfg(test)]
+mod anomaly_tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_passing_case_is_flagged() {
+        let results = vec![passed("slow", Some(10), 31), passed("normal", Some(10), 25)];
+        let anomalies = detect_anomalies(&results, 3.0);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].case, "slow");
+        assert_eq!(anomalies[0].expected_secs, 10);
+        assert!((anomalies[0].ratio - 3.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_much_faster_case_is_flagged() {
+        let results = vec![passed("suspiciously-fast", Some(30), 5)];
+        assert_eq!(detect_anomalies(&results, 3.0).len(), 1);
+    }
+
+    #[test]
+    fn test_failed_cases_are_checked_and_unset_expectations_ignored() {
+        let case = TestCase {
+            name: "failed-slow".to_string(),
+            expected_secs: Some(1),
+            ..Default::default()
+        };
+        let failed = failed_with(case, FailureReason::TestFailed, "", Duration::from_secs(10));
+        let results = vec![
+            failed,
+            passed("no-expectation", None, 1000),
+            TestResult::skipped(),
+        ];
+        let anomalies = detect_anomalies(&results, 3.0);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].case, "failed-slow");
+    }
+
+    #[test]
+    fn test_config_fields() {
+        let matrix: TestMatrix = toml::from_str(
+            r#"
+            anomaly_factor = 2.5
+
+            [[cases]]
+            name = "timed"
+            features = ""
+            no_default_features = false
+            expected_secs = 30
+            "#,
+        )
+        .unwrap();
+        assert_eq!(matrix.anomaly_factor, 2.5);
+        assert_eq!(matrix.cases[0].expected_secs, Some(30));
+        assert_eq!(TestMatrix::default().anomaly_factor, 3.0);
+    }
+}
@@ -0,0 +1,82 @@
+//! # Axis Module Unit Tests / Axis 模块单元测试
+//!
+//! This module contains unit tests for the `axis.rs` module,
+//! testing how results are aggregated per feature, toolchain, target and package.
+//!
+//! 此模块包含 `axis.rs` 模块的单元测试，
+//! 测试如何按 feature、工具链、目标和包汇总结果。
+
+mod common;
+use crate::common::{failed, passed};
+use matrix_runner::core::axis::{Axis, AxisValueSummary, summarize_by_axis};
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::models::TestResult;
+
+#[cfg(test)]
+mod summarize_by_axis_tests {
+    use super::*;
+
+    fn case(features: &str) -> TestCase {
+        TestCase {
+            features: features.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn summary(axis: Axis, value: &str, passed: usize, failed: usize) -> AxisValueSummary {
+        AxisValueSummary {
+            axis,
+            value: value.to_string(),
+            passed,
+            failed,
+        }
+    }
+
+    #[test]
+    fn test_features_lowest_pass_rate_first() {
+        let results = vec![
+            failed(case("simd,std")),
+            failed(case("simd")),
+            passed(case("std")),
+            passed(case("")),
+            TestResult::skipped(),
+        ];
+
+        assert_eq!(
+            summarize_by_axis(&results),
+            vec![
+                summary(Axis::Feature, "simd", 0, 2),
+                summary(Axis::Feature, "std", 1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_axes_without_variation_are_left_out() {
+        let results = vec![passed(case("std")), failed(case("std"))];
+        assert!(summarize_by_axis(&results).is_empty());
+    }
+
+    #[test]
+    fn test_toolchain_axis_uses_default_for_unset() {
+        let nightly = TestCase {
+            toolchain: Some("nightly".to_string()),
+            ..Default::default()
+        };
+        let results = vec![failed(nightly), passed(TestCase::default())];
+
+        assert_eq!(
+            summarize_by_axis(&results),
+            vec![
+                summary(Axis::Toolchain, "nightly", 0, 1),
+                summary(Axis::Toolchain, "default", 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pass_rate() {
+        assert_eq!(summary(Axis::Target, "host", 3, 1).pass_rate(), 0.75);
+        assert_eq!(summary(Axis::Target, "host", 0, 0).pass_rate(), 1.0);
+    }
+}
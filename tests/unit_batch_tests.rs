@@ -0,0 +1,76 @@
+//! # Batch Module Unit Tests / 批处理模块单元测试
+//!
+//! This module contains unit tests for the `batch.rs` module,
+//! testing manifest parsing and the mapping of exit codes to project outcomes.
+//!
+//! 此模块包含 `batch.rs` 模块的单元测试，
+//! 测试清单解析以及退出码到项目结果的映射。
+
+use matrix_runner::core::batch::{BatchEntry, ProjectOutcome, parse_manifest};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let content = r#"
+# fleet of crates
+crates/a/TestMatrix.toml
+crates/b/ci.toml crates/b
+"with space/TestMatrix.toml" /abs/project
+"#;
+        let entries = parse_manifest(content, Path::new("/base")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                BatchEntry {
+                    config: PathBuf::from("/base/crates/a/TestMatrix.toml"),
+                    project_dir: PathBuf::from("/base/crates/a"),
+                },
+                BatchEntry {
+                    config: PathBuf::from("/base/crates/b/ci.toml"),
+                    project_dir: PathBuf::from("/base/crates/b"),
+                },
+                BatchEntry {
+                    config: PathBuf::from("/base/with space/TestMatrix.toml"),
+                    project_dir: PathBuf::from("/abs/project"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_malformed_lines() {
+        let err = parse_manifest("a.toml\na.toml dir extra\n", Path::new(".")).unwrap_err();
+        assert!(err.to_string().contains("Line 2"));
+
+        let err = parse_manifest("\"unbalanced.toml\n", Path::new(".")).unwrap_err();
+        assert!(err.to_string().contains("unbalanced quotes"));
+    }
+
+    #[test]
+    fn test_outcome_from_exit_code() {
+        assert_eq!(
+            ProjectOutcome::from_exit_code(Some(0)),
+            ProjectOutcome::Passed
+        );
+        assert_eq!(
+            ProjectOutcome::from_exit_code(Some(4)),
+            ProjectOutcome::Failed
+        );
+        assert_eq!(
+            ProjectOutcome::from_exit_code(Some(5)),
+            ProjectOutcome::Cancelled
+        );
+        assert_eq!(
+            ProjectOutcome::from_exit_code(Some(2)),
+            ProjectOutcome::Error
+        );
+        assert_eq!(
+            ProjectOutcome::from_exit_code(None),
+            ProjectOutcome::Cancelled
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! # Builder Module Unit Tests / Builder 模块单元测试
+//!
+//! This module contains unit tests for the `builder.rs` module,
+//! testing that cases and matrices built in code match their TOML equivalents and are checked alike.
+//!
+//! 此模块包含 `builder.rs` 模块的单元测试，
+//! 测试在代码中构建的用例和矩阵与其 TOML 等价形式一致，并受到同样的检查。
+
+use matrix_runner::core::config::{AllowFailure, CaseKind, TestCase, TestMatrix};
+
+#[cfg(test)]
+mod test_case_builder_tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_the_toml_case() {
+        let built = TestCase::builder()
+            .name("nightly-simd")
+            .features(["simd", "std"])
+            .no_default_features(true)
+            .toolchain("nightly")
+            .cargo_unstable_flags(["-Zbuild-std=core"])
+            .retries(2)
+            .allow_failure("windows")
+            .tags(["slow"])
+            .shards(4)
+            .build()
+            .unwrap();
+
+        let parsed: TestMatrix = toml::from_str(
+            r#"
+            [[cases]]
+            name = "nightly-simd"
+            features = "simd,std"
+            no_default_features = true
+            toolchain = "nightly"
+            cargo_unstable_flags = ["-Zbuild-std=core"]
+            retries = 2
+            allow_failure = ["windows"]
+            tags = ["slow"]
+            shards = 4
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&parsed.cases[0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unset_fields_keep_their_defaults() {
+        let case = TestCase::builder().name("plain").build().unwrap();
+        assert_eq!(case.features, "");
+        assert!(!case.no_default_features);
+        assert_eq!(case.kind, CaseKind::Test);
+        assert_eq!(case.command, None);
+        assert_eq!(case.retries, None);
+        assert!(case.allow_failure.is_empty());
+    }
+
+    #[test]
+    fn test_allow_failure_takes_full_entries() {
+        let entry = AllowFailure {
+            os: "macos".to_string(),
+            until: chrono::NaiveDate::from_ymd_opt(2025, 9, 1),
+            issue: Some("#123".to_string()),
+        };
+        let case = TestCase::builder()
+            .name("flaky")
+            .allow_failure("windows")
+            .allow_failure(entry.clone())
+            .build()
+            .unwrap();
+        assert_eq!(
+            case.allow_failure,
+            vec![AllowFailure::from("windows"), entry]
+        );
+    }
+
+    #[test]
+    fn test_build_checks_the_case() {
+        let err = TestCase::builder().features(["a"]).build().unwrap_err();
+        assert!(err.to_string().contains("needs a name"));
+
+        let err = TestCase::builder()
+            .name("stable-z")
+            .toolchain("stable")
+            .cargo_unstable_flags(["-Zbuild-std=core"])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("need a nightly toolchain"));
+
+        let err = TestCase::builder()
+            .name("no-shards")
+            .shards(0)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("`shards = 0`"));
+    }
+}
+
+#[cfg(test)]
+mod from_cases_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_cases_uses_the_default_settings() {
+        let warmup = TestCase::builder().name("warmup").build().unwrap();
+        let main = TestCase::builder()
+            .name("main")
+            .after(["warmup"])
+            .build()
+            .unwrap();
+        let matrix = TestMatrix::from_cases([warmup, main]).unwrap();
+
+        let defaults = TestMatrix::default();
+        assert_eq!(matrix.language, defaults.language);
+        assert_eq!(
+            matrix.transient_build_retries,
+            defaults.transient_build_retries
+        );
+        let names: Vec<&str> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, vec!["warmup", "main"]);
+    }
+
+    #[test]
+    fn test_from_cases_checks_the_matrix() {
+        let lonely = TestCase::builder()
+            .name("main")
+            .after(["missing"])
+            .build()
+            .unwrap();
+        let err = TestMatrix::from_cases([lonely]).unwrap_err();
+        assert!(err.to_string().contains("no case has that name"));
+    }
+}
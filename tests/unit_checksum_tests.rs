@@ -0,0 +1,47 @@
+//! # Checksum Module Unit Tests / Checksum 模块单元测试
+//!
+//! This module contains unit tests for the `checksum.rs` module,
+//! testing the SHA-256 of files and how checksums are collected per case.
+//!
+//! 此模块包含 `checksum.rs` 模块的单元测试，
+//! 测试文件的 SHA-256 以及如何按用例收集校验和。
+
+use matrix_runner::infra::checksum::{ChecksumLog, sha256_file};
+use std::fs;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod checksum_tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("artifact");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_sha256_missing_file() {
+        let dir = tempdir().unwrap();
+        assert!(sha256_file(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_checksum_log_shared_between_clones() {
+        let log = ChecksumLog::default();
+        let handle = log.clone();
+        handle.record("case-a", "aa".to_string());
+        handle.record("case-b", "bb".to_string());
+        handle.record("case-a", "cc".to_string());
+
+        let snapshot = log.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot["case-a"], "cc");
+        assert_eq!(snapshot["case-b"], "bb");
+    }
+}
@@ -0,0 +1,75 @@
+//! # CI Config Module Unit Tests / CI 配置模块单元测试
+//!
+//! This module contains unit tests for the `ci_config.rs` module,
+//! testing how target triples are found in CI configuration files.
+//!
+//! 此模块包含 `ci_config.rs` 模块的单元测试，
+//! 测试如何在 CI 配置文件中查找目标三元组。
+
+use matrix_runner::infra::ci_config::{ci_config_files, ci_targets, parse_target_triples};
+use std::fs;
+
+#[cfg(test)]
+mod ci_config_tests {
+    use super::*;
+
+    #[test]
+    fn test_target_triples_are_found_wherever_they_are_written() {
+        let workflow = r#"
+jobs:
+  build:
+    runs-on: ubuntu-24.04-arm
+    strategy:
+      matrix:
+        target: [x86_64-pc-windows-msvc, "wasm32-wasip1", thumbv8m.main-none-eabi]
+    steps:
+      - run: cargo build --target=aarch64-unknown-linux-gnu.
+      - run: rustup target add i686-unknown-linux-gnu
+      - uses: actions/upload-artifact@v4
+        with: { name: app-x86_64-apple-darwin.tar.gz, path: target/x86_64-unknown-linux-musl/release }
+"#;
+        let targets: Vec<String> = parse_target_triples(workflow).into_iter().collect();
+        assert_eq!(
+            targets,
+            [
+                "aarch64-unknown-linux-gnu",
+                "i686-unknown-linux-gnu",
+                "thumbv8m.main-none-eabi",
+                "wasm32-wasip1",
+                "x86_64-pc-windows-msvc",
+                "x86_64-unknown-linux-musl",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ci_files_are_found_up_to_the_repository_root() {
+        let repo = tempfile::tempdir().unwrap();
+        let crate_dir = repo.path().join("crates/app");
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::create_dir_all(repo.path().join(".git")).unwrap();
+        fs::create_dir_all(repo.path().join(".github/workflows")).unwrap();
+        fs::write(
+            repo.path().join(".github/workflows/ci.yml"),
+            "run: cargo build --target wasm32-unknown-unknown",
+        )
+        .unwrap();
+        fs::write(
+            repo.path().join(".github/workflows/notes.md"),
+            "x86_64-pc-windows-gnu",
+        )
+        .unwrap();
+        fs::write(
+            repo.path().join(".gitlab-ci.yml"),
+            "TARGET: riscv64gc-unknown-linux-gnu",
+        )
+        .unwrap();
+
+        assert_eq!(ci_config_files(&crate_dir).len(), 2);
+        let targets: Vec<String> = ci_targets(&crate_dir).into_iter().collect();
+        assert_eq!(
+            targets,
+            ["riscv64gc-unknown-linux-gnu", "wasm32-unknown-unknown"]
+        );
+    }
+}
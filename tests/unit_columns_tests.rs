@@ -0,0 +1,106 @@
+//! # Columns Module Unit Tests / 列模块单元测试
+//!
+//! This module contains unit tests for the `columns.rs` module,
+//! testing how summary columns are parsed from the matrix and what they show for a result.
+//!
+//! 此模块包含 `columns.rs` 模块的单元测试，
+//! 测试摘要列如何从矩阵中解析，以及它们为一个结果显示什么。
+
+mod common;
+use crate::common::passed_in;
+use chrono::NaiveDate;
+use matrix_runner::core::columns::SummaryColumn;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::costs::CaseCost;
+use matrix_runner::core::models::TestResult;
+use std::time::Duration;
+
+#[cfg(test)]
+mod summary_column_tests {
+    use super::*;
+
+    fn passed() -> TestResult {
+        let case = TestCase {
+            name: "cross".to_string(),
+            target: Some("thumbv7em-none-eabihf".to_string()),
+            ..Default::default()
+        };
+        let mut result = passed_in(case, Duration::from_millis(1500), 3);
+        if let TestResult::Passed { annotations, .. } = &mut result {
+            annotations.insert("memory".to_string(), "42 MiB".to_string());
+        }
+        result
+    }
+
+    #[test]
+    fn test_columns_parse_and_print_their_names() {
+        for name in [
+            "status",
+            "name",
+            "target",
+            "toolchain",
+            "target_size",
+            "annotation:memory",
+        ] {
+            let column: SummaryColumn = name.parse().unwrap();
+            assert_eq!(column.to_string(), name);
+        }
+        assert_eq!(
+            "annotation:memory".parse::<SummaryColumn>().unwrap(),
+            SummaryColumn::Annotation("memory".to_string())
+        );
+
+        let err = "memory".parse::<SummaryColumn>().unwrap_err();
+        assert!(err.contains("unknown summary column `memory`"));
+        assert!(err.contains("`annotation:memory`"));
+        assert!("annotation:".parse::<SummaryColumn>().is_err());
+    }
+
+    #[test]
+    fn test_columns_deserialize_from_the_matrix() {
+        let columns: Vec<SummaryColumn> =
+            toml::from_str::<toml::Table>(r#"columns = ["status", "annotation:memory"]"#).unwrap()
+                ["columns"]
+                .clone()
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                SummaryColumn::Status,
+                SummaryColumn::Annotation("memory".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_column_values_of_a_result() {
+        let result = passed();
+        let cost = CaseCost {
+            build_duration: Some(Duration::from_millis(500)),
+            target_bytes: 0,
+        };
+        let context = RunContext::new("en");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let value = |column: SummaryColumn| column.value(&result, Some(&cost), &context, today);
+        assert_eq!(value(SummaryColumn::Name), "cross");
+        assert_eq!(value(SummaryColumn::Target), "thumbv7em-none-eabihf");
+        assert_eq!(value(SummaryColumn::Toolchain), "");
+        assert_eq!(value(SummaryColumn::Duration), "1.50s");
+        assert_eq!(value(SummaryColumn::Retries), "2");
+        assert_eq!(value(SummaryColumn::BuildTime), "0.50s");
+        assert_eq!(value(SummaryColumn::RunTime), "1.00s");
+        assert_eq!(
+            value(SummaryColumn::Annotation("memory".to_string())),
+            "42 MiB"
+        );
+        assert_eq!(value(SummaryColumn::Annotation("missing".to_string())), "");
+        assert_eq!(
+            SummaryColumn::Duration.value(&TestResult::skipped(), None, &context, today),
+            "N/A"
+        );
+        assert!(SummaryColumn::Duration.is_numeric());
+        assert!(!SummaryColumn::Target.is_numeric());
+    }
+}
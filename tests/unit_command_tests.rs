@@ -1,12 +1,21 @@
 //! # Command Module Unit Tests / Command 模块单元测试
 //!
 //! This module contains comprehensive unit tests for the `command.rs` module,
-//! testing both the `format_build_error_output` and `spawn_and_capture` functions.
+//! testing the `format_build_error_output` and `format_build_log`, rustdoc error counting, fuzz target listing, transient build failure detection, libtest output parsing and `spawn_and_capture` functions,
+//! including the idle timeout and the shutdown of dropped processes.
 //!
 //! 此模块包含 `command.rs` 模块的全面单元测试，
-//! 测试 `format_build_error_output` 和 `spawn_and_capture` 函数。
-
-use matrix_runner::infra::command::{format_build_error_output, spawn_and_capture};
+//! 测试 `format_build_error_output` 和 `format_build_log`、rustdoc 错误计数、模糊测试目标列表解析、暂时性构建失败检测、libtest 输出解析和 `spawn_and_capture` 函数，
+//! 包括空闲超时和被丢弃进程的关闭。
+
+use matrix_runner::core::execution::KeepBuildLogs;
+use matrix_runner::infra::command::{
+    ShutdownTasks, apply_locale, format_build_error_output, format_build_log, is_stalled,
+    is_transient_build_failure, parse_doc_errors, parse_failed_tests, parse_fuzz_targets,
+    parse_test_timings, posix_locale, spawn_and_capture, spawn_and_capture_with_idle_timeout,
+    with_kill_grace,
+};
+use std::time::Duration;
 use tokio::process::Command;
 
 /// Initialize i18n for tests / 为测试初始化 i18n
@@ -27,7 +36,7 @@ mod format_build_error_output_tests {
         // 模拟包含编译器错误的 JSON 输出
         let json_output = r#"{"reason":"compiler-message","package_id":"test 0.1.0 (path+file:///test)","manifest_path":"/test/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"test","src_path":"/test/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"message":{"message":"cannot find function `non_existent_function` in this scope","code":{"code":"E0425","explanation":"An unresolved name was used.\n\nErroneous code examples:\n\n```compile_fail,E0425\nfoo::bar(); // error: unresolved name `foo::bar`\n// or\nbaz(); // error: unresolved name `baz`\n```\n\nPlease verify that the name wasn't misspelled and ensure that the identifier\nis declared. Example:\n\n```\nfn bar() {}\nfoo::bar(); // ok!\n// or\nfn baz() {}\nbaz(); // ok!\n```\n\nShadowing and scoping rules can sometimes make it look like a path or a name\nis valid, but actually isn't. Example:\n\n```compile_fail,E0425\nstruct Foo;\nlet binding = Foo;\nlet another_binding = binding();\n// error: unresolved name `binding`\n```\n\nIn this example, `binding` is created as a variable, not a function. So when\nwe try to call it as a function, we get this error. Let's fix it:\n\n```\nstruct Foo;\nlet binding = Foo;\nlet another_binding = binding;\n// ok!\n```\n"},"level":"error","spans":[{"file_name":"src/lib.rs","byte_start":123,"byte_end":144,"line_start":10,"line_end":10,"column_start":5,"column_end":26,"is_primary":true,"text":[{"text":"    non_existent_function();","highlight_start":5,"highlight_end":26}],"label":"not found in this scope","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[],"rendered":"\u001b[0m\u001b[1m\u001b[38;5;9merror[E0425]\u001b[0m\u001b[0m\u001b[1m: cannot find function `non_existent_function` in this scope\u001b[0m\n\u001b[0m  \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;12m--> \u001b[0m\u001b[0msrc/lib.rs:10:5\u001b[0m\n\u001b[0m   \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;12m|\u001b[0m\n\u001b[0m\u001b[1m\u001b[38;5;12m10\u001b[0m\u001b[0m \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;12m|\u001b[0m\u001b[0m \u001b[0m\u001b[0m    non_existent_function();\u001b[0m\n\u001b[0m   \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;12m|\u001b[0m\u001b[0m \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;9m    ^^^^^^^^^^^^^^^^^^^^^\u001b[0m\u001b[0m \u001b[0m\u001b[0m\u001b[1m\u001b[38;5;9mnot found in this scope\u001b[0m\n\n"}}"#;
 
-        let result = format_build_error_output(json_output);
+        let result = format_build_error_output(json_output, "en");
 
         // 应该返回带颜色的渲染输出
         assert!(result.contains("error[E0425]"));
@@ -43,7 +52,7 @@ mod format_build_error_output_tests {
         let json_output = r#"{"reason":"compiler-message","message":{"message":"first error","level":"error","rendered":"First Error Message"}}
 {"reason":"compiler-message","message":{"message":"second error","level":"error","rendered":"Second Error Message"}}"#;
 
-        let result = format_build_error_output(json_output);
+        let result = format_build_error_output(json_output, "en");
 
         // 应该包含两个错误消息
         assert!(result.contains("First Error Message"));
@@ -58,7 +67,7 @@ mod format_build_error_output_tests {
         // 模拟只包含警告的 JSON 输出
         let json_output = r#"{"reason":"compiler-message","message":{"message":"unused variable","level":"warning","rendered":"Warning Message"}}"#;
 
-        let result = format_build_error_output(json_output);
+        let result = format_build_error_output(json_output, "en");
 
         // 应该返回原始输出的摘要，因为没有错误级别的消息
         assert!(result.contains("Could not parse specific compiler errors"));
@@ -72,7 +81,7 @@ mod format_build_error_output_tests {
         // 模拟没有 rendered 字段的错误消息
         let json_output = r#"{"reason":"compiler-message","message":{"message":"raw error message","level":"error"}}"#;
 
-        let result = format_build_error_output(json_output);
+        let result = format_build_error_output(json_output, "en");
 
         // 应该返回原始的错误消息
         assert_eq!(result, "raw error message");
@@ -85,7 +94,7 @@ mod format_build_error_output_tests {
         // 模拟无效的 JSON 输出
         let invalid_json = "This is not valid JSON\nSome error occurred\nAnother line";
 
-        let result = format_build_error_output(invalid_json);
+        let result = format_build_error_output(invalid_json, "en");
 
         // 应该返回原始输出的摘要
         assert!(result.contains("Could not parse specific compiler errors"));
@@ -97,12 +106,21 @@ mod format_build_error_output_tests {
     fn test_format_build_error_output_with_empty_input() {
         setup_i18n();
 
-        let result = format_build_error_output("");
+        let result = format_build_error_output("", "en");
 
         // 应该返回解析失败的消息
         assert!(result.contains("Could not parse specific compiler errors"));
     }
 
+    #[test]
+    fn test_format_build_error_output_uses_the_given_locale() {
+        setup_i18n();
+
+        // 使用给定的语言环境，而不是全局语言环境
+        let result = format_build_error_output("", "zh-CN");
+        assert!(result.contains("无法解析特定的编译器错误"));
+    }
+
     #[test]
     fn test_format_build_error_output_with_non_compiler_messages() {
         setup_i18n();
@@ -111,7 +129,7 @@ mod format_build_error_output_tests {
         let json_output = r#"{"reason":"compiler-artifact","target":{"name":"test","kind":["bin"]}}
 {"reason":"build-script-executed","package_id":"test 0.1.0"}"#;
 
-        let result = format_build_error_output(json_output);
+        let result = format_build_error_output(json_output, "en");
 
         // 应该返回原始输出的摘要，因为没有编译器错误消息
         assert!(result.contains("Could not parse specific compiler errors"));
@@ -127,7 +145,7 @@ mod format_build_error_output_tests {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let result = format_build_error_output(&long_output);
+        let result = format_build_error_output(&long_output, "en");
 
         // 应该只包含前 50 行
         assert!(result.contains("Line 0"));
@@ -137,6 +155,85 @@ mod format_build_error_output_tests {
     }
 }
 
+#[cfg(test)]
+mod format_build_log_tests {
+    use super::*;
+
+    #[test]
+    fn test_format_build_log_keeps_warnings_of_successful_builds() {
+        let json_output = r#"{"reason":"compiler-message","message":{"message":"unused variable","level":"warning","rendered":"warning: unused variable: `x`"}}
+{"reason":"compiler-artifact","target":{"name":"demo","test":true},"executable":"/tmp/demo"}
+not json at all
+{"reason":"compiler-message","message":{"message":"1 warning emitted","level":"warning"}}
+{"reason":"build-finished","success":true}"#;
+
+        let log = format_build_log(json_output);
+
+        assert_eq!(log, "warning: unused variable: `x`\n1 warning emitted");
+        assert!(format_build_log(r#"{"reason":"build-finished","success":true}"#).is_empty());
+    }
+
+    #[test]
+    fn test_parse_doc_errors_counts_broken_links() {
+        let json_output = r#"{"reason":"compiler-message","message":{"message":"unresolved link to `helper`","level":"error","code":{"code":"rustdoc::broken_intra_doc_links","explanation":null},"rendered":"error: unresolved link to `helper`"}}
+{"reason":"compiler-message","message":{"message":"unresolved link to `other`","level":"error","code":{"code":"rustdoc::broken_intra_doc_links","explanation":null}}}
+{"reason":"compiler-message","message":{"message":"cannot find type `Missing` in this scope","level":"error","code":{"code":"E0412","explanation":null}}}
+{"reason":"compiler-message","message":{"message":"missing documentation for a function","level":"warning","code":{"code":"missing_docs","explanation":null}}}
+{"reason":"compiler-message","message":{"message":"aborting due to 3 previous errors","level":"error","code":null}}
+{"reason":"build-finished","success":false}"#;
+
+        let errors = parse_doc_errors(json_output);
+
+        assert_eq!(errors.broken_links, 2);
+        assert_eq!(errors.other, 1);
+        assert_eq!(errors.total(), 3);
+        assert_eq!(parse_doc_errors("").total(), 0);
+    }
+
+    #[test]
+    fn test_parse_fuzz_targets_reads_one_per_line() {
+        assert_eq!(parse_fuzz_targets("parse\n  decode  \n\n"), ["parse", "decode"]);
+        assert!(parse_fuzz_targets("").is_empty());
+    }
+
+    #[test]
+    fn test_keep_build_logs_policy() {
+        assert_eq!("all".parse(), Ok(KeepBuildLogs::All));
+        assert_eq!("failed".parse(), Ok(KeepBuildLogs::Failed));
+        assert_eq!("none".parse(), Ok(KeepBuildLogs::None));
+        assert!("some".parse::<KeepBuildLogs>().is_err());
+
+        assert!(KeepBuildLogs::All.keeps(true) && KeepBuildLogs::All.keeps(false));
+        assert!(!KeepBuildLogs::Failed.keeps(true) && KeepBuildLogs::Failed.keeps(false));
+        assert!(!KeepBuildLogs::None.keeps(true) && !KeepBuildLogs::None.keeps(false));
+        assert_eq!(KeepBuildLogs::default(), KeepBuildLogs::None);
+    }
+}
+
+#[cfg(test)]
+mod transient_build_failure_tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_and_network_errors_are_transient() {
+        let output = "    Updating crates.io index
+warning: spurious network error (2 tries remaining): [28] Timeout was reached
+error: failed to download from `https://static.crates.io/crates/serde/1.0.0/download`";
+        assert!(is_transient_build_failure(output));
+        assert!(is_transient_build_failure(
+            "error: failed to fetch `https://github.com/example/dep`\n\nCaused by:\n  Connection reset by peer"
+        ));
+    }
+
+    #[test]
+    fn test_compile_errors_are_not_transient() {
+        // The compiler message quotes a string that looks like a network error.
+        let output = r#"{"reason":"compiler-message","message":{"message":"mismatched types","level":"error","rendered":"error[E0308]: expected `u32`, found \"failed to download\""}}
+error: could not compile `demo` (lib) due to 1 previous error"#;
+        assert!(!is_transient_build_failure(output));
+    }
+}
+
 #[cfg(test)]
 mod spawn_and_capture_tests {
     use super::*;
@@ -232,10 +329,7 @@ mod spawn_and_capture_tests {
         };
 
         #[cfg(not(target_os = "windows"))]
-        let cmd = {
-            let mut cmd = Command::new("true");
-            cmd
-        };
+        let cmd = Command::new("true");
 
         let (status_result, output) = spawn_and_capture(cmd).await;
 
@@ -245,4 +339,181 @@ mod spawn_and_capture_tests {
         // 输出可能为空或只包含换行符
         assert!(output.is_empty() || output.trim().is_empty());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_silent_process_is_killed_after_idle_timeout() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo started; sleep 30"]);
+
+        let start = std::time::Instant::now();
+        let (status_result, output) =
+            spawn_and_capture_with_idle_timeout(cmd, Some(Duration::from_millis(300)), "en").await;
+
+        assert!(is_stalled(&status_result.unwrap_err()));
+        assert!(output.contains("started"));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_steady_output_resets_the_idle_timeout() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "for i in 1 2 3 4 5; do echo $i; sleep 0.2; done"]);
+
+        let (status_result, output) =
+            spawn_and_capture_with_idle_timeout(cmd, Some(Duration::from_millis(700)), "en").await;
+
+        assert!(status_result.unwrap().success());
+        assert!(output.contains('5'));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_runs_only_wait_for_their_own_shutdowns() {
+        let this_run = ShutdownTasks::default();
+        let other_run = ShutdownTasks::default();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "trap '' TERM; echo started; sleep 30"]);
+        let capture = with_kill_grace(
+            Some(Duration::from_secs(1)),
+            &this_run,
+            spawn_and_capture_with_idle_timeout(cmd, None, "en"),
+        );
+        // Dropping the capture leaves the process in its grace period.
+        let _ = tokio::time::timeout(Duration::from_millis(300), capture).await;
+
+        let start = std::time::Instant::now();
+        other_run.wait().await;
+        assert!(start.elapsed() < Duration::from_millis(500));
+        this_run.wait().await;
+        assert!(start.elapsed() >= Duration::from_millis(500));
+    }
+}
+
+#[cfg(test)]
+mod parse_test_timings_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_report_time_text_output() {
+        let output = "\
+running 3 tests
+test tests::fast ... ok <0.001s>
+test tests::slow ... FAILED <1.250s>
+test tests::ignored ... ignored
+test result: FAILED. 1 passed; 1 failed; 1 ignored";
+
+        let timings = parse_test_timings(output);
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].name, "tests::fast");
+        assert_eq!(timings[0].duration, Duration::from_millis(1));
+        assert_eq!(timings[1].name, "tests::slow");
+        assert_eq!(timings[1].duration, Duration::from_millis(1250));
+    }
+
+    #[test]
+    fn test_parse_json_output() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "a::one" }
+{ "type": "test", "name": "a::one", "event": "ok", "exec_time": 0.5 }
+{ "type": "test", "name": "a::two", "event": "failed", "exec_time": 2.0, "stdout": "boom" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "exec_time": 2.5 }"#;
+
+        let timings = parse_test_timings(output);
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].name, "a::one");
+        assert_eq!(timings[0].duration, Duration::from_millis(500));
+        assert_eq!(timings[1].name, "a::two");
+        assert_eq!(timings[1].duration, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_nextest_output() {
+        let output = "\
+    Starting 2 tests across 1 binary
+        PASS [   0.004s] sample_project tests::it_works
+        FAIL [   1.250s] sample_project tests::it_breaks
+     Summary [   1.260s] 2 tests run: 1 passed, 1 failed, 0 skipped";
+
+        let timings = parse_test_timings(output);
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].name, "tests::it_works");
+        assert_eq!(timings[0].duration, Duration::from_millis(4));
+        assert_eq!(timings[1].name, "tests::it_breaks");
+        assert_eq!(timings[1].duration, Duration::from_millis(1250));
+    }
+
+    #[test]
+    fn test_output_without_times_yields_nothing() {
+        let output = "test tests::it_works ... ok\ntest result: ok. 1 passed";
+        assert!(parse_test_timings(output).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod parse_failed_tests_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_failed_tests_from_text_output() {
+        let output = "\
+running 3 tests
+test tests::ok ... ok
+test tests::broken ... FAILED
+test tests::also_broken ... FAILED <0.010s>
+
+failures:
+    tests::broken
+    tests::also_broken
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored";
+
+        assert_eq!(
+            parse_failed_tests(output),
+            Some(vec!["tests::broken".to_string(), "tests::also_broken".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_failed_tests_from_json_output() {
+        let output = r#"{ "type": "test", "event": "started", "name": "a::bad" }
+{ "type": "test", "name": "a::bad", "event": "failed", "stdout": "boom" }
+{ "type": "suite", "event": "failed", "passed": 0, "failed": 1 }"#;
+
+        assert_eq!(parse_failed_tests(output), Some(vec!["a::bad".to_string()]));
+    }
+
+    #[test]
+    fn test_crashed_suite_is_not_parsed() {
+        // No summary line: the binary died before finishing, so the list may be incomplete.
+        let output = "running 2 tests\ntest tests::broken ... FAILED\nSegmentation fault";
+        assert_eq!(parse_failed_tests(output), None);
+    }
+}
+
+#[cfg(test)]
+mod locale_tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_locale_from_language_tag() {
+        assert_eq!(posix_locale("zh-CN"), "zh_CN.UTF-8");
+        assert_eq!(posix_locale("de_DE"), "de_DE.UTF-8");
+        assert_eq!(posix_locale("ja_JP.eucJP"), "ja_JP.eucJP");
+        assert_eq!(posix_locale("C"), "C");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_apply_locale_sets_the_locale_variables() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo \"$LC_ALL $LANG $LANGUAGE\""]);
+        apply_locale(&mut cmd, "zh-CN");
+
+        let (status_result, output) = spawn_and_capture(cmd).await;
+
+        assert!(status_result.unwrap().success());
+        assert_eq!(output.trim(), "zh_CN.UTF-8 zh_CN.UTF-8 zh_CN");
+    }
 }
@@ -0,0 +1,113 @@
+//! # Config Edit Module Unit Tests / 配置编辑模块单元测试
+//!
+//! This module contains unit tests for the `config/edit.rs` module,
+//! testing that edits of a matrix file keep its comments and formatting.
+//!
+//! 此模块包含 `config/edit.rs` 模块的单元测试，
+//! 测试对矩阵文件的编辑是否保留其注释和格式。
+
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::config::edit::MatrixDocument;
+
+const TABLES: &str = r#"# Our matrix / 我们的矩阵
+language = "en"
+
+# The plain build
+[[cases]]
+name = "default"
+features = ""
+no_default_features = false
+timeout_secs = 60 # CI is slow
+
+# Everything on
+[[cases]]
+name = "full"
+features = "full"
+no_default_features = false
+"#;
+
+#[cfg(test)]
+mod config_edit_tests {
+    use super::*;
+
+    #[test]
+    fn test_edits_keep_comments_and_formatting() {
+        let mut document = MatrixDocument::parse(TABLES).unwrap();
+        document.set_field("default", "timeout_secs", 120).unwrap();
+        document.set_field("full", "retries", 2).unwrap();
+        assert!(!document.remove_case("missing"));
+
+        let edited = document.to_string();
+        assert!(edited.starts_with(
+            "# Our matrix / 我们的矩阵\nlanguage = \"en\"\n\n# The plain build\n[[cases]]"
+        ));
+        assert!(edited.contains("timeout_secs = 120 # CI is slow\n"));
+        assert!(edited.contains("# Everything on\n[[cases]]\nname = \"full\""));
+        assert!(edited.contains("no_default_features = false\nretries = 2\n"));
+
+        assert!(document.remove_field("default", "timeout_secs").unwrap());
+        assert!(document.remove_case("full"));
+        assert_eq!(document.case_names(), vec!["default"]);
+        assert!(!document.to_string().contains("Everything on"));
+        assert_eq!(document.to_matrix().unwrap().cases[0].timeout_secs, None);
+    }
+
+    #[test]
+    fn test_added_cases_only_write_what_differs_from_the_defaults() {
+        let mut document = MatrixDocument::parse(TABLES).unwrap();
+        let case = TestCase::builder()
+            .name("serde")
+            .features(["serde"])
+            .build_only(true)
+            .build()
+            .unwrap();
+        document.add_case(&case).unwrap();
+        document
+            .set_case_comment("serde", "Serialization only")
+            .unwrap();
+
+        let edited = document.to_string();
+        assert!(edited.ends_with(
+            "\n# Serialization only\n[[cases]]\nname = \"serde\"\nfeatures = \"serde\"\nno_default_features = false\nbuild_only = true\n"
+        ));
+        let matrix = document.to_matrix().unwrap();
+        assert_eq!(matrix.cases.len(), 3);
+        assert!(matrix.cases[2].build_only);
+
+        let err = document.add_case(&case).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_inline_cases_stay_inline() {
+        let mut document = MatrixDocument::parse(
+            "cases = [\n    { name = \"a\", features = \"\", no_default_features = false },\n]\n",
+        )
+        .unwrap();
+        let case = TestCase::builder()
+            .name("b")
+            .no_default_features(true)
+            .build()
+            .unwrap();
+        document.add_case(&case).unwrap();
+        document.set_field("a", "features", "std").unwrap();
+
+        let edited = document.to_string();
+        assert_eq!(
+            edited,
+            "cases = [\n    { name = \"a\", features = \"std\", no_default_features = false },\n    { name = \"b\", features = \"\", no_default_features = true },\n]\n"
+        );
+        assert_eq!(document.case_names(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_invalid_edits_are_rejected() {
+        let mut document = MatrixDocument::parse(TABLES).unwrap();
+        let err = document.set_field("default", "timeout", 10).unwrap_err();
+        assert!(err.to_string().contains("not a field of a case"));
+        let err = document.set_field("missing", "retries", 1).unwrap_err();
+        assert!(err.to_string().contains("No case named 'missing'"));
+        let err = document.remove_field("default", "features").unwrap_err();
+        assert!(err.to_string().contains("required"));
+    }
+}
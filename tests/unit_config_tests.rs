@@ -6,7 +6,12 @@
 //! 此模块包含 `config.rs` 模块的全面单元测试，
 //! 测试 `TestCase` 和 `TestMatrix` 结构体及其序列化/反序列化。
 
-use matrix_runner::core::config::{TestCase, TestMatrix};
+use chrono::NaiveDate;
+use matrix_runner::core::config::{
+    AllowFailure, CaseKind, DEFAULT_CONFIG_FILE, DuplicatePolicy, Fixture, FixtureMode, FuzzSmoke,
+    Limit, MissingComponentPolicy, TestCase, TestMatrix, Ulimits, discover_config,
+    find_unknown_keys, load_test_matrix, load_test_matrix_with, unqualified_name,
+};
 
 #[cfg(test)]
 mod test_case_tests {
@@ -23,6 +28,7 @@ mod test_case_tests {
             arch: vec![],
             retries: None,
             timeout_secs: None,
+            ..Default::default()
         };
 
         let toml_str = toml::to_string(&test_case).unwrap();
@@ -39,10 +45,11 @@ mod test_case_tests {
             features: "".to_string(),
             no_default_features: true,
             command: Some("cargo test --release".to_string()),
-            allow_failure: vec!["windows".to_string()],
+            allow_failure: vec!["windows".into()],
             arch: vec!["x86_64".to_string(), "aarch64".to_string()],
             retries: None,
             timeout_secs: None,
+            ..Default::default()
         };
 
         let toml_str = toml::to_string(&test_case).unwrap();
@@ -82,6 +89,7 @@ mod test_case_tests {
             no_default_features = true
             command = "custom command"
             allow_failure = ["linux", "macos"]
+            allow_timeout = true
             arch = ["x86_64"]
         "#;
 
@@ -91,7 +99,11 @@ mod test_case_tests {
         assert_eq!(test_case.features, "feature1,feature2");
         assert!(test_case.no_default_features);
         assert_eq!(test_case.command, Some("custom command".to_string()));
-        assert_eq!(test_case.allow_failure, vec!["linux", "macos"]);
+        assert_eq!(
+            test_case.allow_failure,
+            vec![AllowFailure::from("linux"), AllowFailure::from("macos")]
+        );
+        assert!(test_case.allow_timeout);
         assert_eq!(test_case.arch, vec!["x86_64"]);
         assert!(test_case.retries.is_none());
         assert!(test_case.timeout_secs.is_none());
@@ -104,10 +116,11 @@ mod test_case_tests {
             features: "feature1".to_string(),
             no_default_features: true,
             command: Some("test command".to_string()),
-            allow_failure: vec!["windows".to_string()],
+            allow_failure: vec!["windows".into()],
             arch: vec!["x86_64".to_string()],
             retries: None,
             timeout_secs: None,
+            ..Default::default()
         };
 
         let cloned = original.clone();
@@ -121,6 +134,170 @@ mod test_case_tests {
         assert_eq!(original.retries, cloned.retries);
         assert_eq!(original.timeout_secs, cloned.timeout_secs);
     }
+
+    #[test]
+    fn test_test_case_ulimits() {
+        let case: TestCase = toml::from_str(
+            r#"
+            name = "stress"
+            features = ""
+            no_default_features = false
+            ulimits = { nofile = 65535, core = "unlimited" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            case.ulimits,
+            Ulimits {
+                nofile: Some(Limit::Value(65535)),
+                core: Some(Limit::Unlimited),
+                stack: None,
+            }
+        );
+        assert!(TestCase::default().ulimits.is_empty());
+    }
+
+    #[test]
+    fn test_test_case_toolchain_and_target() {
+        let case: TestCase = toml::from_str(
+            r#"
+            name = "wasm-nightly"
+            features = ""
+            no_default_features = false
+            toolchain = "nightly"
+            target = "wasm32-unknown-unknown"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(case.toolchain.as_deref(), Some("nightly"));
+        assert_eq!(case.target.as_deref(), Some("wasm32-unknown-unknown"));
+        assert!(!case.requires_network);
+    }
+
+    #[test]
+    fn test_test_case_success_exit_codes() {
+        let case = TestCase::default();
+        assert!(case.is_success_exit_code(Some(0)));
+        assert!(!case.is_success_exit_code(Some(2)));
+        assert!(!case.is_success_exit_code(None));
+
+        let case: TestCase = toml::from_str(
+            r#"
+            name = "clippy-warnings"
+            features = ""
+            no_default_features = false
+            command = "cargo clippy"
+            success_exit_codes = [0, 2]
+            "#,
+        )
+        .unwrap();
+        assert!(case.is_success_exit_code(Some(0)));
+        assert!(case.is_success_exit_code(Some(2)));
+        assert!(!case.is_success_exit_code(Some(1)));
+        assert!(!case.is_success_exit_code(None));
+    }
+
+    #[test]
+    fn test_test_case_c_locale_overrides_matrix() {
+        let matrix: TestMatrix = toml::from_str(
+            r#"
+            force_c_locale = true
+
+            [[cases]]
+            name = "inherits"
+            features = ""
+            no_default_features = false
+
+            [[cases]]
+            name = "native-locale"
+            features = ""
+            no_default_features = false
+            force_c_locale = false
+            "#,
+        )
+        .unwrap();
+
+        assert!(matrix.force_c_locale);
+        assert!(matrix.cases[0].uses_c_locale(matrix.force_c_locale));
+        assert!(!matrix.cases[1].uses_c_locale(matrix.force_c_locale));
+        assert!(!TestMatrix::default().force_c_locale);
+        assert!(!TestCase::default().uses_c_locale(false));
+    }
+
+    #[test]
+    fn test_test_case_env_locale() {
+        let case: TestCase = toml::from_str(
+            r#"
+            name = "cli-output-zh"
+            features = ""
+            no_default_features = false
+            env_locale = "zh-CN"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(case.env_locale.as_deref(), Some("zh-CN"));
+        assert!(TestCase::default().env_locale.is_none());
+        assert!(!toml::to_string(&TestCase::default()).unwrap().contains("env_locale"));
+    }
+
+    #[test]
+    fn test_matrix_transient_build_retries() {
+        let matrix: TestMatrix = toml::from_str("cases = []").unwrap();
+        assert_eq!(matrix.transient_build_retries, 2);
+        assert_eq!(TestMatrix::default().transient_build_retries, 2);
+
+        let matrix: TestMatrix = toml::from_str("transient_build_retries = 0\ncases = []").unwrap();
+        assert_eq!(matrix.transient_build_retries, 0);
+    }
+
+    #[test]
+    fn test_matrix_prefer_nextest() {
+        let matrix: TestMatrix = toml::from_str("cases = []").unwrap();
+        assert!(!matrix.prefer_nextest);
+
+        let matrix: TestMatrix = toml::from_str("prefer_nextest = true\ncases = []").unwrap();
+        assert!(matrix.prefer_nextest);
+    }
+
+    #[test]
+    fn test_case_components_and_missing_component_policy() {
+        let toml_str = r#"
+            on_missing_component = "skip"
+
+            [[cases]]
+            name = "miri"
+            features = ""
+            no_default_features = false
+            components = ["miri", "rust-src"]
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.on_missing_component, MissingComponentPolicy::Skip);
+        assert_eq!(matrix.cases[0].components, vec!["miri", "rust-src"]);
+        assert!(TestMatrix::default().on_missing_component.is_fail());
+        assert!(TestCase::default().components.is_empty());
+    }
+
+    #[test]
+    fn test_matrix_prebuild_dependencies() {
+        assert!(!TestMatrix::default().prebuild_dependencies);
+        let matrix: TestMatrix = toml::from_str("prebuild_dependencies = true\ncases = []").unwrap();
+        assert!(matrix.prebuild_dependencies);
+    }
+
+    #[test]
+    fn test_test_case_invalid_ulimit() {
+        let result: Result<TestCase, _> = toml::from_str(
+            r#"
+            name = "stress"
+            features = ""
+            no_default_features = false
+            ulimits = { nofile = "lots" }
+            "#,
+        );
+        assert!(result.is_err());
+    }
 }
 
 #[cfg(test)]
@@ -203,7 +380,7 @@ mod test_matrix_tests {
         // Verify third case
         assert_eq!(matrix.cases[2].name, "custom-test");
         assert_eq!(matrix.cases[2].command, Some("echo test".to_string()));
-        assert_eq!(matrix.cases[2].allow_failure, vec!["windows"]);
+        assert_eq!(matrix.cases[2].allow_failure, vec![AllowFailure::from("windows")]);
         assert_eq!(matrix.cases[2].arch, vec!["x86_64", "aarch64"]);
     }
 
@@ -221,19 +398,22 @@ mod test_matrix_tests {
                     arch: vec![],
                     retries: None,
                     timeout_secs: None,
+                    ..Default::default()
                 },
                 TestCase {
                     name: "test2".to_string(),
                     features: "".to_string(),
                     no_default_features: true,
                     command: Some("custom command".to_string()),
-                    allow_failure: vec!["linux".to_string()],
+                    allow_failure: vec!["linux".into()],
                     arch: vec!["x86_64".to_string()],
                     retries: None,
                     timeout_secs: None,
+                    ..Default::default()
                 },
             ],
             fast_fail: false,
+            ..Default::default()
         };
 
         let toml_str = toml::to_string_pretty(&matrix).unwrap();
@@ -245,6 +425,309 @@ mod test_matrix_tests {
         assert!(toml_str.contains("allow_failure = [\"linux\"]"));
     }
 
+    #[test]
+    fn test_test_matrix_concurrency_groups() {
+        let toml_str = r#"
+            [concurrency_groups.db]
+            max_concurrent = 2
+
+            [[cases]]
+            name = "db-test"
+            features = ""
+            no_default_features = false
+            concurrency_group = "db"
+        "#;
+
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+
+        assert_eq!(matrix.cases[0].concurrency_group.as_deref(), Some("db"));
+        assert_eq!(matrix.group_limit("db"), 2);
+        // Undeclared groups default to one case at a time.
+        assert_eq!(matrix.group_limit("gpu"), 1);
+    }
+
+    #[test]
+    fn test_test_matrix_after_hints() {
+        let toml_str = r#"
+            cases = [
+                { name = "warmup", features = "", no_default_features = false },
+                { name = "a", features = "", no_default_features = false, after = ["warmup"] },
+            ]
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.cases[1].after, vec!["warmup".to_string()]);
+        assert!(matrix.validate_after().is_ok());
+
+        let mut unknown = matrix.clone();
+        unknown.cases[1].after = vec!["warmpu".to_string()];
+        let err = unknown.validate_after().unwrap_err();
+        assert!(err.to_string().contains("no case has that name"));
+
+        let mut cycle = matrix;
+        cycle.cases[0].after = vec!["a".to_string()];
+        let err = cycle.validate_after().unwrap_err();
+        assert!(err.to_string().contains("a -> warmup -> a"));
+    }
+
+    #[test]
+    fn test_test_matrix_unstable_flags_need_nightly() {
+        let toml_str = r#"
+            [[cases]]
+            name = "build-std"
+            features = ""
+            no_default_features = false
+            toolchain = "nightly-2024-06-01"
+            cargo_unstable_flags = ["-Zbuild-std=core,alloc"]
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.cases[0].cargo_unstable_flags, vec!["-Zbuild-std=core,alloc"]);
+        assert!(matrix.validate_unstable_flags().is_ok());
+
+        let mut stable = matrix.clone();
+        stable.cases[0].toolchain = Some("1.75.0".to_string());
+        let err = stable.validate_unstable_flags().unwrap_err();
+        assert!(err.to_string().contains("need a nightly toolchain"));
+
+        // The active toolchain may be nightly; it is checked when the run starts.
+        let mut default_toolchain = matrix.clone();
+        default_toolchain.cases[0].toolchain = None;
+        assert!(default_toolchain.validate_unstable_flags().is_ok());
+
+        let mut not_unstable = matrix;
+        not_unstable.cases[0].cargo_unstable_flags = vec!["--release".to_string()];
+        let err = not_unstable.validate_unstable_flags().unwrap_err();
+        assert!(err.to_string().contains("only takes `-Z` flags"));
+    }
+
+    #[test]
+    fn test_test_matrix_shards() {
+        let toml_str = r#"
+            [[cases]]
+            name = "huge-integration"
+            features = ""
+            no_default_features = false
+            shards = 4
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.cases[0].shards, Some(4));
+        assert!(matrix.validate_shards().is_ok());
+
+        let mut zero = matrix;
+        zero.cases[0].shards = Some(0);
+        let err = zero.validate_shards().unwrap_err();
+        assert!(err.to_string().contains("`shards = 0`"));
+    }
+
+    #[test]
+    fn test_matrix_env_is_merged_into_every_case() {
+        let toml_str = r#"
+            env = { RUST_LOG = "info", MY_FLAG = "1" }
+
+            [[cases]]
+            name = "plain"
+            features = ""
+            no_default_features = false
+
+            [[cases]]
+            name = "verbose"
+            features = ""
+            no_default_features = false
+            env = { RUST_LOG = "debug" }
+        "#;
+        let mut matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        matrix.apply_case_defaults();
+        assert_eq!(matrix.cases[0].env, matrix.env);
+        assert_eq!(matrix.cases[1].env["RUST_LOG"], "debug");
+        assert_eq!(matrix.cases[1].env["MY_FLAG"], "1");
+        assert!(matrix.validate_env().is_ok());
+
+        matrix.cases[0].env.insert("A=B".to_string(), "1".to_string());
+        let err = matrix.validate_env().unwrap_err();
+        assert!(err.to_string().contains("`A=B`"), "{err}");
+    }
+
+    #[test]
+    fn test_test_matrix_fuzz_smoke() {
+        let toml_str = r#"
+            [[cases]]
+            name = "fuzz"
+            features = "arbitrary"
+            no_default_features = false
+            kind = "fuzz-smoke"
+            fuzz = { max_secs = 5, targets = ["parse"] }
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        let case = &matrix.cases[0];
+        assert_eq!(case.kind, CaseKind::FuzzSmoke);
+        assert_eq!(case.fuzz.targets, ["parse"]);
+        assert_eq!(case.fuzz.libfuzzer_args(), ["-runs=10000", "-max_total_time=5"]);
+        assert!(matrix.validate_fuzz().is_ok());
+        assert!(toml::to_string(&matrix).unwrap().contains("kind = \"fuzz-smoke\""));
+
+        let mut misplaced = matrix.clone();
+        misplaced.cases[0].kind = CaseKind::Test;
+        let err = misplaced.validate_fuzz().unwrap_err();
+        assert!(err.to_string().contains("only applies to cases of kind \"fuzz-smoke\""));
+
+        let mut unbounded = matrix;
+        unbounded.cases[0].fuzz = FuzzSmoke {
+            runs: Some(0),
+            ..Default::default()
+        };
+        assert!(unbounded.validate_fuzz().is_err());
+    }
+
+    #[test]
+    fn test_matrix_axes_are_expanded_after_the_listed_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("TestMatrix.toml");
+        std::fs::write(
+            &path,
+            r#"
+                cases = [{ name = "custom", features = "", no_default_features = false, command = "true" }]
+
+                [matrix]
+                features = ["", "serde"]
+                toolchain = ["stable", "nightly"]
+                exclude = [{ features = "serde", toolchain = "stable" }]
+            "#,
+        )
+        .unwrap();
+        let matrix = load_test_matrix(&path).unwrap();
+        let names: Vec<_> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, ["custom", "default-stable", "default-nightly", "serde-nightly"]);
+
+        std::fs::write(&path, "cases = []\n[matrix]\nfeature = [\"a\"]\n").unwrap();
+        let err = load_test_matrix(&path).unwrap_err();
+        assert!(err.to_string().contains("unknown key `feature` in [matrix] (did you mean `features`?)"), "{err}");
+    }
+
+    #[test]
+    fn test_test_matrix_resource_ceiling() {
+        let toml_str = r#"
+            max_load = 12.5
+            max_total_memory_gb = 48
+            cases = []
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.max_load, Some(12.5));
+        assert_eq!(matrix.max_total_memory_gb, Some(48.0));
+        assert!(matrix.validate_resource_ceiling().is_ok());
+
+        let zero = TestMatrix {
+            max_load: Some(0.0),
+            ..matrix
+        };
+        let err = zero.validate_resource_ceiling().unwrap_err();
+        assert!(err.to_string().contains("`max_load = 0` must be a positive number"));
+    }
+
+    #[test]
+    fn test_test_matrix_fixtures() {
+        let toml_str = r#"
+            [[cases]]
+            name = "big-input"
+            features = ""
+            no_default_features = false
+            fixtures = [
+                { from = "tests/data/big.bin", to = "data/big.bin", mode = "symlink" },
+                { from = "tests/data/small.txt", to = "small.txt" },
+            ]
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        let fixtures = &matrix.cases[0].fixtures;
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[0].to, std::path::Path::new("data/big.bin"));
+        assert_eq!(fixtures[0].mode, FixtureMode::Symlink);
+        assert_eq!(fixtures[1].mode, FixtureMode::Copy);
+        assert!(matrix.validate_fixtures().is_ok());
+
+        // A fixture may not be staged outside the project directory.
+        // fixture 不能被放置到项目目录之外。
+        for to in ["../outside.bin", "/tmp/big.bin", "data/.."] {
+            let mut outside = matrix.clone();
+            outside.cases[0].fixtures = vec![Fixture {
+                from: "big.bin".into(),
+                to: to.into(),
+                mode: FixtureMode::Copy,
+            }];
+            let err = outside.validate_fixtures().unwrap_err();
+            assert!(err.to_string().contains("inside the project directory"), "{to}");
+        }
+    }
+
+    fn matrix_with_duplicates(policy: DuplicatePolicy) -> TestMatrix {
+        let case = |name: &str, features: &str| TestCase {
+            name: name.to_string(),
+            features: features.to_string(),
+            ..Default::default()
+        };
+        TestMatrix {
+            on_duplicate: policy,
+            cases: vec![
+                case("std", "a"),
+                case("no-std", ""),
+                case("std", "b"),
+                case("std-2", ""),
+                case("std", "c"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn names_and_features(matrix: &TestMatrix) -> Vec<(&str, &str)> {
+        matrix
+            .cases
+            .iter()
+            .map(|case| (case.name.as_str(), case.features.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_on_duplicate_policies() {
+        let toml_str = r#"
+            on_duplicate = "rename"
+            cases = []
+        "#;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        assert_eq!(matrix.on_duplicate, DuplicatePolicy::Rename);
+        assert_eq!(TestMatrix::default().on_duplicate, DuplicatePolicy::Error);
+
+        let mut error = matrix_with_duplicates(DuplicatePolicy::Error);
+        let err = error.resolve_duplicates().unwrap_err();
+        assert!(err.to_string().contains("'std'"));
+
+        let mut first = matrix_with_duplicates(DuplicatePolicy::First);
+        assert_eq!(first.resolve_duplicates().unwrap(), vec!["std"]);
+        assert_eq!(
+            names_and_features(&first),
+            vec![("std", "a"), ("no-std", ""), ("std-2", "")]
+        );
+
+        let mut last = matrix_with_duplicates(DuplicatePolicy::Last);
+        last.resolve_duplicates().unwrap();
+        assert_eq!(
+            names_and_features(&last),
+            vec![("std", "c"), ("no-std", ""), ("std-2", "")]
+        );
+
+        // `std-2` is taken by a listed case, so the renamed ones skip it.
+        let mut rename = matrix_with_duplicates(DuplicatePolicy::Rename);
+        rename.resolve_duplicates().unwrap();
+        assert_eq!(
+            names_and_features(&rename),
+            vec![("std", "a"), ("no-std", ""), ("std-3", "b"), ("std-2", ""), ("std-4", "c")]
+        );
+    }
+
+    #[test]
+    fn test_unique_names_are_left_alone() {
+        let mut matrix = matrix_with_duplicates(DuplicatePolicy::Error);
+        matrix.cases.retain(|case| case.features != "b" && case.features != "c");
+        assert!(matrix.resolve_duplicates().unwrap().is_empty());
+        assert_eq!(matrix.cases.len(), 3);
+    }
+
     #[test]
     fn test_test_matrix_empty_cases() {
         let toml_str = r#"
@@ -267,12 +750,14 @@ mod test_matrix_tests {
                 features: "feature1,feature2".to_string(),
                 no_default_features: true,
                 command: Some("test command".to_string()),
-                allow_failure: vec!["windows".to_string(), "linux".to_string()],
+                allow_failure: vec!["windows".into(), "linux".into()],
                 arch: vec!["x86_64".to_string()],
                 retries: None,
                 timeout_secs: None,
+                ..Default::default()
             }],
             fast_fail: false,
+            ..Default::default()
         };
 
         // Serialize to TOML
@@ -332,3 +817,451 @@ mod test_matrix_tests {
         assert_eq!(matrix.cases[0].features, "功能1,功能2");
     }
 }
+
+#[cfg(test)]
+mod allow_failure_tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_allow_failure_accepts_strings_and_tables() {
+        let toml_str = r##"
+            [[cases]]
+            name = "mixed"
+            features = ""
+            no_default_features = false
+            allow_failure = ["linux", { os = "windows", until = "2025-09-01", issue = "#123" }]
+        "##;
+        let matrix: TestMatrix = toml::from_str(toml_str).unwrap();
+        let entries = &matrix.cases[0].allow_failure;
+        assert_eq!(entries[0], AllowFailure::from("linux"));
+        assert_eq!(entries[1].os, "windows");
+        assert_eq!(entries[1].until, Some(date("2025-09-01")));
+        assert_eq!(entries[1].issue.as_deref(), Some("#123"));
+
+        // Plain entries are written back as strings.
+        let written = toml::to_string(&matrix).unwrap();
+        assert!(written.contains("\"linux\""));
+        assert!(written.contains("until = \"2025-09-01\""));
+        let reread: TestMatrix = toml::from_str(&written).unwrap();
+        assert_eq!(&reread.cases[0].allow_failure, entries);
+    }
+
+    #[test]
+    fn test_allow_failure_expires_after_until() {
+        let case = TestCase {
+            name: "expiring".to_string(),
+            allow_failure: vec![
+                AllowFailure {
+                    os: "windows".to_string(),
+                    until: Some(date("2025-09-01")),
+                    issue: None,
+                },
+                "macos".into(),
+            ],
+            ..Default::default()
+        };
+        assert!(case.allows_failure_on("windows", date("2025-09-01")));
+        assert!(!case.allows_failure_on("windows", date("2025-09-02")));
+        assert!(case.allows_failure_on("macos", date("2030-01-01")));
+        assert!(!case.allows_failure_on("linux", date("2025-01-01")));
+    }
+
+    #[test]
+    fn test_expiring_allowances_lists_expired_and_soon_expiring_entries() {
+        let entry = |until: &str| AllowFailure {
+            os: "windows".to_string(),
+            until: Some(date(until)),
+            issue: Some("#7".to_string()),
+        };
+        let matrix = TestMatrix {
+            cases: vec![
+                TestCase {
+                    name: "expired".to_string(),
+                    allow_failure: vec![entry("2025-08-31")],
+                    ..Default::default()
+                },
+                TestCase {
+                    name: "soon".to_string(),
+                    allow_failure: vec![entry("2025-09-10"), "linux".into()],
+                    ..Default::default()
+                },
+                TestCase {
+                    name: "later".to_string(),
+                    allow_failure: vec![entry("2025-12-01")],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let expiring = matrix.expiring_allowances(date("2025-09-01"), 14);
+        let summary: Vec<_> = expiring
+            .iter()
+            .map(|e| (e.case.as_str(), e.days_left))
+            .collect();
+        assert_eq!(summary, vec![("expired", -1), ("soon", 9)]);
+    }
+}
+
+#[cfg(test)]
+mod discover_config_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_discover_config_walks_up_to_nearest_matrix() {
+        let root = tempdir().unwrap();
+        let nested = root.path().join("crates/member/src");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.path().join(DEFAULT_CONFIG_FILE), "cases = []").unwrap();
+
+        assert_eq!(
+            discover_config(&nested),
+            Some(root.path().join(DEFAULT_CONFIG_FILE))
+        );
+
+        // A closer matrix wins over one further up.
+        let member = root.path().join("crates/member");
+        fs::write(member.join(DEFAULT_CONFIG_FILE), "cases = []").unwrap();
+        assert_eq!(discover_config(&nested), Some(member.join(DEFAULT_CONFIG_FILE)));
+    }
+
+    #[test]
+    fn test_discover_config_without_matrix() {
+        let root = tempdir().unwrap();
+        // The temp dir's ancestors are not expected to contain a TestMatrix.toml.
+        let found = discover_config(root.path());
+        assert!(found.is_none_or(|path| !path.starts_with(root.path())));
+    }
+}
+
+#[cfg(test)]
+mod unknown_keys_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const TYPO_MATRIX: &str = r#"
+language = "en"
+fast_fials = true
+
+[concurrency_groups.db]
+max_concurent = 2
+
+[[cases]]
+name = "no-std"
+features = ""
+no_default_features = false
+no_default_feature = true
+"#;
+
+    #[test]
+    fn test_unknown_keys_are_found_with_suggestions() {
+        let table: toml::Table = toml::from_str(TYPO_MATRIX).unwrap();
+        let unknown = find_unknown_keys(&table);
+        let found: Vec<(&str, &str, Option<&str>)> = unknown
+            .iter()
+            .map(|key| (key.location.as_str(), key.key.as_str(), key.suggestion))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("", "fast_fials", Some("fast_fail")),
+                ("cases[0] (\"no-std\")", "no_default_feature", Some("no_default_features")),
+                ("concurrency_groups.db", "max_concurent", Some("max_concurrent")),
+            ]
+        );
+        assert_eq!(
+            unknown[1].to_string(),
+            "unknown key `no_default_feature` in cases[0] (\"no-std\") (did you mean `no_default_features`?)"
+        );
+    }
+
+    #[test]
+    fn test_unrelated_key_has_no_suggestion() {
+        let table: toml::Table = toml::from_str("colour_scheme = \"dark\"\ncases = []").unwrap();
+        let unknown = find_unknown_keys(&table);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].suggestion, None);
+    }
+
+    #[test]
+    fn test_strict_loading_rejects_unknown_keys() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("TestMatrix.toml");
+        fs::write(&path, TYPO_MATRIX).unwrap();
+
+        let error = format!("{:#}", load_test_matrix(&path).unwrap_err());
+        assert!(error.contains("unknown key `fast_fials` (did you mean `fast_fail`?)"));
+        assert!(error.contains("no_default_feature"));
+        assert!(error.contains("--lenient"));
+    }
+
+    #[test]
+    fn test_lenient_loading_ignores_unknown_keys() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("TestMatrix.toml");
+        fs::write(&path, TYPO_MATRIX).unwrap();
+
+        let (matrix, unknown) = load_test_matrix_with(&path, true).unwrap();
+        assert_eq!(unknown.len(), 3);
+        assert!(!matrix.fast_fail);
+        assert!(!matrix.cases[0].no_default_features);
+        assert_eq!(matrix.group_limit("db"), 1);
+    }
+
+    #[test]
+    fn test_unknown_keys_in_nested_tables_are_still_rejected() {
+        let result: Result<TestCase, _> = toml::from_str(
+            "name = \"a\"\nfeatures = \"\"\nno_default_features = false\nunknown = 1",
+        );
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod case_defaults_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_case_defaults_resolve_case_then_tag_then_global() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("TestMatrix.toml");
+        fs::write(
+            &path,
+            r#"
+[defaults]
+timeout_secs = 300
+
+[tag_defaults.slow]
+timeout_secs = 1200
+retries = 1
+
+[tag_defaults.flaky]
+retries = 3
+
+[[cases]]
+name = "plain"
+features = ""
+no_default_features = false
+
+[[cases]]
+name = "slow"
+features = ""
+no_default_features = false
+tags = ["slow"]
+
+[[cases]]
+name = "flaky-and-slow"
+features = ""
+no_default_features = false
+tags = ["flaky", "slow"]
+
+[[cases]]
+name = "own-settings"
+features = ""
+no_default_features = false
+tags = ["slow"]
+timeout_secs = 60
+retries = 0
+"#,
+        )
+        .unwrap();
+
+        let matrix = load_test_matrix(&path).unwrap();
+        let settings: Vec<(&str, Option<u64>, Option<u8>)> = matrix
+            .cases
+            .iter()
+            .map(|case| (case.name.as_str(), case.timeout_secs, case.retries))
+            .collect();
+        assert_eq!(
+            settings,
+            vec![
+                ("plain", Some(300), None),
+                ("slow", Some(1200), Some(1)),
+                ("flaky-and-slow", Some(1200), Some(3)),
+                ("own-settings", Some(60), Some(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_in_tag_defaults_are_reported() {
+        let table: toml::Table =
+            toml::from_str("cases = []\n[tag_defaults.slow]\ntimeout_sec = 5").unwrap();
+        let unknown = find_unknown_keys(&table);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].location, "tag_defaults.slow");
+        assert_eq!(unknown[0].suggestion, Some("timeout_secs"));
+    }
+}
+
+#[cfg(test)]
+mod package_namespace_tests {
+    use super::*;
+
+    fn case(name: &str, package: Option<&str>, after: &[&str]) -> TestCase {
+        TestCase {
+            name: name.to_string(),
+            package: package.map(str::to_string),
+            after: after.iter().map(|name| name.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cases_are_prefixed_with_their_package() {
+        let mut matrix = TestMatrix::from_cases([
+            case("unit", Some("client"), &[]),
+            case("smoke", None, &["unit"]),
+        ])
+        .unwrap();
+        matrix.namespace_by_package("server");
+
+        let names: Vec<_> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, ["client::unit", "server::smoke"]);
+        assert_eq!(matrix.cases[1].package.as_deref(), Some("server"));
+        assert_eq!(matrix.cases[1].after, ["client::unit"]);
+        matrix.validate_after().unwrap();
+
+        let before = matrix.clone();
+        matrix.namespace_by_package("server");
+        assert_eq!(matrix.cases[0].name, before.cases[0].name);
+        assert_eq!(matrix.cases[1].after, before.cases[1].after);
+    }
+
+    #[test]
+    fn test_override_package_replaces_every_package() {
+        let mut matrix = TestMatrix::from_cases([
+            case("unit", Some("client"), &[]),
+            case("smoke", None, &["unit"]),
+        ])
+        .unwrap();
+        matrix.override_package("alpha");
+        matrix.namespace_by_package("server");
+
+        let names: Vec<_> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, ["alpha::unit", "alpha::smoke"]);
+        assert!(matrix.cases.iter().all(|case| case.package.as_deref() == Some("alpha")));
+        assert_eq!(matrix.cases[1].after, ["alpha::unit"]);
+    }
+
+    #[test]
+    fn test_matrices_without_packages_keep_their_names() {
+        let mut matrix = TestMatrix::from_cases([case("unit", None, &[])]).unwrap();
+        matrix.namespace_by_package("server");
+        assert_eq!(matrix.cases[0].name, "unit");
+        assert_eq!(matrix.cases[0].package, None);
+    }
+
+    #[test]
+    fn test_unqualified_name_drops_the_package() {
+        assert_eq!(unqualified_name("client::unit"), "unit");
+        assert_eq!(unqualified_name("unit"), "unit");
+    }
+}
+
+#[cfg(test)]
+mod case_id_tests {
+    use super::*;
+
+    fn case(name: &str, features: &str) -> TestCase {
+        TestCase {
+            name: name.to_string(),
+            features: features.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_derived_ids_follow_the_definition_not_the_name() {
+        let id = case("std", "serde,std").derived_id();
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+
+        // Renaming, reordering features or tuning the timeout keeps the id.
+        assert_eq!(case("renamed", "std, serde").derived_id(), id);
+        let tuned = TestCase {
+            timeout_secs: Some(60),
+            tags: vec!["slow".to_string()],
+            ..case("std", "serde,std")
+        };
+        assert_eq!(tuned.case_id(), id);
+
+        assert_ne!(case("std", "std").derived_id(), id);
+        let nightly = TestCase {
+            toolchain: Some("nightly".to_string()),
+            ..case("std", "serde,std")
+        };
+        assert_ne!(nightly.derived_id(), id);
+
+        let explicit = TestCase {
+            id: Some("std-build".to_string()),
+            ..case("std", "serde,std")
+        };
+        assert_eq!(explicit.case_id(), "std-build");
+    }
+
+    #[test]
+    fn test_matrices_give_every_case_a_unique_id() {
+        let matrix = TestMatrix::from_cases([
+            case("a", "std"),
+            case("b", "std"),
+            TestCase {
+                id: Some("pinned".to_string()),
+                ..case("c", "std")
+            },
+        ])
+        .unwrap();
+        let derived = case("a", "std").derived_id();
+        let ids: Vec<_> = matrix.cases.iter().map(|case| case.id.clone().unwrap()).collect();
+        assert_eq!(ids, [derived.clone(), format!("{derived}-2"), "pinned".to_string()]);
+    }
+
+    #[test]
+    fn test_env_only_variants_keep_their_ids_when_reordered() {
+        let variant = |name: &str, value: &str| TestCase {
+            env: std::collections::BTreeMap::from([("RUST_LOG".to_string(), value.to_string())]),
+            ..case(name, "std")
+        };
+        let ids = |cases: [TestCase; 2]| -> Vec<(String, String)> {
+            let matrix = TestMatrix::from_cases(cases).unwrap();
+            let mut ids: Vec<_> = matrix
+                .cases
+                .iter()
+                .map(|case| (case.name.clone(), case.id.clone().unwrap()))
+                .collect();
+            ids.sort();
+            ids
+        };
+        let ordered = ids([variant("debug", "debug"), variant("trace", "trace")]);
+        let reordered = ids([variant("trace", "trace"), variant("debug", "debug")]);
+        assert_eq!(ordered, reordered);
+        assert_ne!(ordered[0].1, ordered[1].1);
+        assert!(ordered.iter().all(|(_, id)| !id.contains('-')));
+
+        let skipping = TestCase {
+            skip_tests: vec!["slow_test".to_string()],
+            ..case("std", "std")
+        };
+        assert_ne!(skipping.derived_id(), case("std", "std").derived_id());
+    }
+
+    #[test]
+    fn test_duplicate_or_empty_ids_are_rejected() {
+        let pinned = |name: &str, id: &str| TestCase {
+            id: Some(id.to_string()),
+            ..case(name, "")
+        };
+        let err = TestMatrix::from_cases([pinned("a", "same"), pinned("b", "same")]).unwrap_err();
+        assert!(err.to_string().contains("Cases 'a' and 'b' have the same id 'same'"));
+        let err = TestMatrix::from_cases([pinned("a", " ")]).unwrap_err();
+        assert!(err.to_string().contains("Case 'a' has an empty `id`"));
+    }
+}
@@ -0,0 +1,50 @@
+//! # Context Module Unit Tests / 上下文模块单元测试
+//!
+//! This module contains unit tests for the `context.rs` module,
+//! testing which target triples the platform of a run can execute.
+//!
+//! 此模块包含 `context.rs` 模块的单元测试，
+//! 测试运行的平台可以执行哪些目标三元组。
+
+use matrix_runner::core::context::RunContext;
+
+/// Helper function to create the context of a run on a platform / 创建某平台上运行上下文的辅助函数
+fn platform(os: &str, arch: &str) -> RunContext {
+    RunContext {
+        os: os.to_string(),
+        arch: arch.to_string(),
+        ..RunContext::new("en")
+    }
+}
+
+#[cfg(test)]
+mod can_run_tests {
+    use super::*;
+
+    #[test]
+    fn test_the_host_runs_triples_of_its_platform() {
+        let linux = platform("linux", "x86_64");
+        assert!(linux.can_run("x86_64-unknown-linux-gnu"));
+        assert!(linux.can_run("x86_64-unknown-linux-musl"));
+        assert!(platform("macos", "aarch64").can_run("aarch64-apple-darwin"));
+        assert!(platform("windows", "x86").can_run("i686-pc-windows-msvc"));
+        assert!(platform("linux", "arm").can_run("armv7-unknown-linux-gnueabihf"));
+    }
+
+    #[test]
+    fn test_other_architectures_and_systems_are_not_runnable() {
+        let linux = platform("linux", "x86_64");
+        assert!(!linux.can_run("aarch64-unknown-linux-gnu"));
+        assert!(!linux.can_run("x86_64-pc-windows-gnu"));
+        assert!(!linux.can_run("i686-unknown-linux-gnu"));
+        assert!(!platform("linux", "aarch64").can_run("aarch64-linux-android"));
+    }
+
+    #[test]
+    fn test_triples_without_an_operating_system_are_not_runnable() {
+        let linux = platform("linux", "x86_64");
+        assert!(!linux.can_run("wasm32-unknown-unknown"));
+        assert!(!linux.can_run("wasm32-wasip1"));
+        assert!(!platform("none", "arm").can_run("thumbv7em-none-eabihf"));
+    }
+}
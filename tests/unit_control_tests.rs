@@ -0,0 +1,147 @@
+//! # Control Module Unit Tests / Control 模块单元测试
+//!
+//! This module contains unit tests for the `control.rs` module,
+//! testing how control commands are parsed and applied to a run.
+//!
+//! 此模块包含 `control.rs` 模块的单元测试，
+//! 测试控制命令如何被解析并应用于运行。
+
+use matrix_runner::infra::control::{
+    ControlCommand, RunControl, control_path, parse_command, watch_control_file,
+};
+use std::io::Write;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+#[cfg(test)]
+mod parse_command_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_commands() {
+        assert_eq!(
+            parse_command("fast-fail on"),
+            Ok(ControlCommand::FastFail(true))
+        );
+        assert_eq!(
+            parse_command("  fast-fail   off "),
+            Ok(ControlCommand::FastFail(false))
+        );
+        assert_eq!(
+            parse_command("cancel case slow-case"),
+            Ok(ControlCommand::CancelCase("slow-case".to_string()))
+        );
+        assert_eq!(
+            parse_command("pause scheduling"),
+            Ok(ControlCommand::PauseScheduling)
+        );
+        assert_eq!(
+            parse_command("resume scheduling"),
+            Ok(ControlCommand::ResumeScheduling)
+        );
+    }
+
+    #[test]
+    fn test_parse_cancel_case_with_spaces() {
+        assert_eq!(
+            parse_command("cancel case  linux nightly (all features) "),
+            Ok(ControlCommand::CancelCase(
+                "linux nightly (all features)".to_string()
+            ))
+        );
+        assert!(parse_command("cancel case").is_err());
+        assert!(parse_command("cancelcase slow-case").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_command() {
+        let err = parse_command("fast-fail maybe").unwrap_err();
+        assert!(err.contains("unknown command `fast-fail maybe`"));
+    }
+}
+
+#[cfg(test)]
+mod run_control_tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_fail_turned_on_after_failure_stops_run() {
+        let control = RunControl::new(false);
+        control.record_failure();
+        assert!(!control.fast_fail_token().is_cancelled());
+
+        control.apply(&ControlCommand::FastFail(true));
+        assert!(control.fast_fail());
+        assert!(control.fast_fail_token().is_cancelled());
+    }
+
+    #[test]
+    fn test_fast_fail_turned_off_ignores_failures() {
+        let control = RunControl::new(true);
+        control.apply(&ControlCommand::FastFail(false));
+        control.record_failure();
+        assert!(!control.fast_fail_token().is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_case_only_cancels_that_case() {
+        let control = RunControl::new(false);
+        let token = control.case_token("a");
+        control.apply(&ControlCommand::CancelCase("a".to_string()));
+        assert!(token.is_cancelled());
+        assert!(!control.case_token("b").is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_pause_blocks_until_resumed() {
+        let control = RunControl::new(false);
+        control.apply(&ControlCommand::PauseScheduling);
+
+        let waiting =
+            tokio::time::timeout(Duration::from_millis(50), control.wait_while_paused()).await;
+        assert!(waiting.is_err(), "scheduling should be paused");
+
+        control.apply(&ControlCommand::ResumeScheduling);
+        tokio::time::timeout(Duration::from_millis(50), control.wait_while_paused())
+            .await
+            .expect("scheduling should resume");
+    }
+}
+
+#[cfg(test)]
+mod watch_control_file_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_partly_written_multibyte_name_is_read_once_complete() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = control_path(dir.path());
+        let control = RunControl::new(false);
+        let stop = CancellationToken::new();
+        let watcher = tokio::spawn(watch_control_file(
+            path.clone(),
+            control.clone(),
+            stop.clone(),
+            "en".to_string(),
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        let line = "cancel case café\n".as_bytes();
+        // Stop in the middle of `é`.
+        let split = line.len() - 2;
+        file.write_all(&line[..split]).unwrap();
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(!control.case_token("café").is_cancelled());
+
+        file.write_all(&line[split..]).unwrap();
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert!(control.case_token("café").is_cancelled());
+
+        stop.cancel();
+        watcher.await.unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+//! # Core Dump Module Unit Tests / 核心转储模块单元测试
+//!
+//! This module contains unit tests for the `core_dump.rs` module,
+//! testing how `core_pattern` is parsed and how core dumps are found and collected.
+//!
+//! 此模块包含 `core_dump.rs` 模块的单元测试，
+//! 测试 `core_pattern` 如何被解析，以及核心转储如何被查找和收集。
+
+use matrix_runner::infra::core_dump::{CoreLocation, collect, find_core_dump, parse_core_pattern};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn file(dir: &str, prefix: &str) -> CoreLocation {
+    CoreLocation::File {
+        dir: PathBuf::from(dir),
+        prefix: prefix.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod core_pattern_tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_and_empty_patterns_are_in_the_working_directory() {
+        let working_dir = Path::new("/work/project");
+        assert_eq!(
+            parse_core_pattern("core\n", working_dir),
+            file("/work/project", "core")
+        );
+        assert_eq!(
+            parse_core_pattern("", working_dir),
+            file("/work/project", "core")
+        );
+        assert_eq!(
+            parse_core_pattern("dumps/core.%p", working_dir),
+            file("/work/project/dumps", "core.")
+        );
+    }
+
+    #[test]
+    fn test_absolute_patterns_end_the_prefix_at_the_first_specifier() {
+        let working_dir = Path::new("/work/project");
+        assert_eq!(
+            parse_core_pattern("/var/crash/core.%e.%p", working_dir),
+            file("/var/crash", "core.")
+        );
+        assert_eq!(
+            parse_core_pattern("/tmp/%e.core", working_dir),
+            file("/tmp", "")
+        );
+    }
+
+    #[test]
+    fn test_piped_patterns_name_the_handler() {
+        assert_eq!(
+            parse_core_pattern(
+                "|/usr/lib/systemd/systemd-coredump %P %u %g %s %t",
+                Path::new("/work")
+            ),
+            CoreLocation::Handler("/usr/lib/systemd/systemd-coredump".to_string())
+        );
+    }
+}
+
+#[cfg(test)]
+mod collect_tests {
+    use super::*;
+
+    #[test]
+    fn test_find_core_dump_picks_a_dump_written_since_the_start() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("core.123"), b"dump").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), b"").unwrap();
+
+        let started_at = SystemTime::now() - Duration::from_secs(60);
+        assert_eq!(
+            find_core_dump(dir.path(), "core", started_at),
+            Some(dir.path().join("core.123"))
+        );
+        // A dump older than the process is left to whoever produced it.
+        // 早于该进程的转储留给产生它的人处理。
+        let later = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(find_core_dump(dir.path(), "core", later), None);
+        assert_eq!(
+            find_core_dump(&dir.path().join("missing"), "core", started_at),
+            None
+        );
+    }
+
+    #[test]
+    fn test_collect_moves_the_dump() {
+        let dir = tempdir().unwrap();
+        let core = dir.path().join("core");
+        fs::write(&core, b"dump").unwrap();
+
+        let dest_dir = dir.path().join("attachments").join("crashing");
+        let collected = collect(&core, &dest_dir).unwrap();
+        assert_eq!(collected, dest_dir.join("core"));
+        assert_eq!(fs::read(&collected).unwrap(), b"dump");
+        assert!(!core.exists());
+    }
+}
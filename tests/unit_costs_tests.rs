@@ -0,0 +1,102 @@
+//! # Costs Module Unit Tests / 成本模块单元测试
+//!
+//! This module contains unit tests for the `costs.rs` module,
+//! testing how build times add up, how the run time is derived and how results are
+//! sorted by their cost.
+//!
+//! 此模块包含 `costs.rs` 模块的单元测试，
+//! 测试构建时间如何累加、运行时间如何推导以及结果如何按成本排序。
+
+mod common;
+use crate::common::passed_in;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::costs::{CaseCost, CostLog, SummarySort, format_size, sort_by_cost};
+use matrix_runner::core::models::TestResult;
+use std::time::Duration;
+
+fn passed(name: &str, secs: u64) -> TestResult {
+    let case = TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    passed_in(case, Duration::from_secs(secs), 1)
+}
+
+#[cfg(test)]
+mod costs_tests {
+    use super::*;
+
+    #[test]
+    fn test_log_adds_builds_and_replaces_sizes() {
+        let log = CostLog::default();
+        log.record_build("case", Duration::from_secs(3));
+        log.record_target_size("case", 100);
+        log.record_build("case", Duration::from_secs(2));
+        log.record_target_size("case", 150);
+        log.record_target_size("nextest-case", 10);
+
+        let costs = log.snapshot();
+        assert_eq!(
+            costs["case"],
+            CaseCost {
+                build_duration: Some(Duration::from_secs(5)),
+                target_bytes: 150,
+            }
+        );
+        assert_eq!(costs["nextest-case"].build_duration, None);
+    }
+
+    #[test]
+    fn test_run_duration_excludes_the_build() {
+        let cost = CaseCost {
+            build_duration: Some(Duration::from_secs(8)),
+            target_bytes: 0,
+        };
+        assert_eq!(
+            cost.run_duration(&passed("case", 10)),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(cost.run_duration(&TestResult::skipped()), None);
+        assert_eq!(CaseCost::default().run_duration(&passed("case", 10)), None);
+    }
+
+    #[test]
+    fn test_sort_by_cost_puts_the_most_expensive_first() {
+        let results = vec![passed("small", 1), passed("unknown", 1), passed("big", 1)];
+        let log = CostLog::default();
+        log.record_build("small", Duration::from_secs(30));
+        log.record_target_size("small", 10);
+        log.record_build("big", Duration::from_secs(20));
+        log.record_target_size("big", 1000);
+        let costs = log.snapshot();
+
+        let names = |sort| -> Vec<&str> {
+            sort_by_cost(&results, &costs, sort)
+                .into_iter()
+                .map(TestResult::case_name)
+                .collect()
+        };
+        assert_eq!(names(SummarySort::Size), vec!["big", "small", "unknown"]);
+        assert_eq!(
+            names(SummarySort::BuildTime),
+            vec!["small", "big", "unknown"]
+        );
+    }
+
+    #[test]
+    fn test_summary_sort_parses_cli_values() {
+        assert_eq!("size".parse::<SummarySort>(), Ok(SummarySort::Size));
+        assert_eq!(
+            "build-time".parse::<SummarySort>(),
+            Ok(SummarySort::BuildTime)
+        );
+        assert!("duration".parse::<SummarySort>().is_err());
+    }
+
+    #[test]
+    fn test_format_size_uses_binary_units() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
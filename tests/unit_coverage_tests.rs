@@ -0,0 +1,117 @@
+//! # Coverage Module Unit Tests / 覆盖率模块单元测试
+//!
+//! This module contains unit tests for the `coverage.rs` module,
+//! testing how tarpaulin's JSON report is read and how coverage is summed.
+//!
+//! 此模块包含 `coverage.rs` 模块的单元测试，
+//! 测试如何读取 tarpaulin 的 JSON 报告以及如何汇总覆盖率。
+
+use matrix_runner::core::config::{CaseKind, TestCase};
+use matrix_runner::infra::coverage::{
+    Coverage, CoverageLog, TARPAULIN_REPORT_FILE, parse_tarpaulin_report, read_tarpaulin_report,
+};
+use std::fs;
+use tempfile::tempdir;
+
+const REPORT: &str = r#"{
+  "files": [
+    { "path": ["/", "src", "lib.rs"], "content": "", "traces": [], "covered": 8, "coverable": 10 },
+    { "path": ["/", "src", "util.rs"], "content": "", "traces": [], "covered": 1, "coverable": 10 }
+  ],
+  "coverage": 45.0,
+  "covered": 9,
+  "coverable": 20
+}"#;
+
+#[cfg(test)]
+mod tarpaulin_report_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_report_totals() {
+        let coverage = parse_tarpaulin_report(REPORT).unwrap();
+        assert_eq!(
+            coverage,
+            Coverage {
+                covered: 9,
+                coverable: 20
+            }
+        );
+        assert_eq!(coverage.percent(), 45.0);
+    }
+
+    #[test]
+    fn test_parse_report_without_totals_sums_the_files() {
+        let report =
+            r#"{"files": [{"covered": 3, "coverable": 4}, {"covered": 1, "coverable": 4}]}"#;
+        let coverage = parse_tarpaulin_report(report).unwrap();
+        assert_eq!(
+            coverage,
+            Coverage {
+                covered: 4,
+                coverable: 8
+            }
+        );
+        assert!(parse_tarpaulin_report("not json").is_err());
+    }
+
+    #[test]
+    fn test_read_report_from_output_dir() {
+        let dir = tempdir().unwrap();
+        assert!(read_tarpaulin_report(dir.path()).is_err());
+        fs::write(dir.path().join(TARPAULIN_REPORT_FILE), REPORT).unwrap();
+        assert_eq!(read_tarpaulin_report(dir.path()).unwrap().covered, 9);
+    }
+
+    #[test]
+    fn test_total_and_log() {
+        let log = CoverageLog::default();
+        log.record(
+            "a",
+            Coverage {
+                covered: 1,
+                coverable: 4,
+            },
+        );
+        log.record(
+            "b",
+            Coverage {
+                covered: 3,
+                coverable: 4,
+            },
+        );
+        let snapshot = log.snapshot();
+        let total = Coverage::total(snapshot.values());
+        assert_eq!(
+            total,
+            Coverage {
+                covered: 4,
+                coverable: 8
+            }
+        );
+        assert_eq!(total.percent(), 50.0);
+        assert_eq!(Coverage::default().percent(), 0.0);
+    }
+
+    #[test]
+    fn test_case_kind_in_config() {
+        let case: TestCase = toml::from_str(
+            r#"
+            name = "coverage"
+            features = ""
+            no_default_features = false
+            kind = "tarpaulin"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(case.kind, CaseKind::Tarpaulin);
+        assert!(
+            toml::to_string(&case)
+                .unwrap()
+                .contains("kind = \"tarpaulin\"")
+        );
+
+        let default_case = TestCase::default();
+        assert!(!toml::to_string(&default_case).unwrap().contains("kind"));
+    }
+}
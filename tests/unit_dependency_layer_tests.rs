@@ -0,0 +1,89 @@
+//! # Dependency Layer Module Unit Tests / 依赖层模块单元测试
+//!
+//! This module contains unit tests for the `dependency_layer.rs` module,
+//! testing which cases use the layer and how their features are united per package.
+//!
+//! 此模块包含 `dependency_layer.rs` 模块的单元测试，
+//! 测试哪些用例使用该层以及如何按包合并它们的 features。
+
+use matrix_runner::core::config::{CaseKind, TestCase};
+use matrix_runner::core::dependency_layer::{LayerBuild, layer_builds, uses_layer};
+
+fn case(name: &str, features: &str, no_default_features: bool) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        features: features.to_string(),
+        no_default_features,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod dependency_layer_tests {
+    use super::*;
+
+    #[test]
+    fn test_only_default_flow_cases_use_the_layer() {
+        assert!(uses_layer(&case("plain", "", false)));
+
+        let mut custom = case("custom", "", false);
+        custom.command = Some("make test".to_string());
+        let mut tarpaulin = case("coverage", "", false);
+        tarpaulin.kind = CaseKind::Tarpaulin;
+        let mut doc = case("doc", "", false);
+        doc.kind = CaseKind::Doc;
+        let mut nightly = case("nightly", "", false);
+        nightly.toolchain = Some("nightly".to_string());
+        let mut cross = case("cross", "", false);
+        cross.target = Some("aarch64-unknown-linux-gnu".to_string());
+        for other in [custom, tarpaulin, doc, nightly, cross] {
+            assert!(!uses_layer(&other), "{}", other.name);
+        }
+    }
+
+    #[test]
+    fn test_features_are_united_per_package() {
+        let mut core = case("core-simd", "simd", true);
+        core.package = Some("core".to_string());
+        let mut nightly = case("nightly", "unstable", false);
+        nightly.toolchain = Some("nightly".to_string());
+        let cases = vec![
+            case("std", "std, serde", true),
+            case("no-std", "alloc", true),
+            core,
+            nightly,
+        ];
+
+        assert_eq!(
+            layer_builds(&cases, "app"),
+            vec![
+                LayerBuild {
+                    package: "app".to_string(),
+                    no_default_features: true,
+                    features: vec!["alloc".to_string(), "serde".to_string(), "std".to_string()],
+                },
+                LayerBuild {
+                    package: "core".to_string(),
+                    no_default_features: true,
+                    features: vec!["simd".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_features_are_kept_if_any_case_uses_them() {
+        let cases = vec![case("minimal", "", true), case("default", "", false)];
+        let builds = layer_builds(&cases, "");
+        assert_eq!(builds.len(), 1);
+        assert!(!builds[0].no_default_features);
+        assert!(builds[0].features.is_empty());
+    }
+
+    #[test]
+    fn test_no_layer_without_eligible_cases() {
+        let mut custom = case("custom", "", false);
+        custom.command = Some("true".to_string());
+        assert!(layer_builds(&[custom], "app").is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! # Diff Module Unit Tests / Diff 模块单元测试
+//!
+//! This module contains unit tests for the `diff.rs` module,
+//! testing how the values of a failed assertion are rendered as a diff.
+//!
+//! 此模块包含 `diff.rs` 模块的单元测试，
+//! 测试失败断言的值如何被渲染为差异。
+
+use matrix_runner::reporting::diff::{side_by_side_html, unified_diff};
+use matrix_runner::reporting::extract::AssertionValues;
+
+/// Helper function to create assertion values / 创建断言值的辅助函数
+fn values(left: &str, right: &str) -> AssertionValues {
+    AssertionValues {
+        left: left.to_string(),
+        right: right.to_string(),
+    }
+}
+
+/// Helper function to create twenty lines changed at the second and the nineteenth
+/// 创建在第二行和第十九行有变化的二十行的辅助函数
+fn distant_changes() -> (String, String) {
+    let left = (0..20).map(|i| format!("{i}\n")).collect();
+    let right = (0..20)
+        .map(|i| match i {
+            1 => "one\n".to_string(),
+            18 => "eighteen\n".to_string(),
+            _ => format!("{i}\n"),
+        })
+        .collect();
+    (left, right)
+}
+
+#[cfg(test)]
+mod unified_diff_tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_lines_are_marked() {
+        colored::control::set_override(false);
+        let diff = unified_diff(&values("a\nb\nc\n", "a\nx\nc\n"));
+        assert_eq!(
+            diff,
+            "--- left\n+++ right\n@@ -1,3 +1,3 @@\n a\n-b\n+x\n c\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_their_own_hunks() {
+        colored::control::set_override(false);
+        let (left, right) = distant_changes();
+        let diff = unified_diff(&values(&left, &right));
+        assert_eq!(diff.matches("@@ -").count(), 2);
+        assert!(diff.contains("-18\n+eighteen\n"));
+        assert!(!diff.contains(" 9\n"));
+    }
+}
+
+#[cfg(test)]
+mod side_by_side_tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_lines_are_side_by_side() {
+        let html = side_by_side_html(&values("same\nold <line>\n", "same\nnew <line>\nextra\n"));
+        assert!(
+            html.starts_with("<table class='assertion-diff'><tr><th>left</th><th>right</th></tr>")
+        );
+        assert!(
+            html.contains(
+                "<tr><td class='diff-equal'>same</td><td class='diff-equal'>same</td></tr>"
+            )
+        );
+        assert!(html.contains(
+            "<tr><td class='diff-del'><span class='diff-emph'>old</span> &lt;line&gt;</td><td class='diff-ins'><span class='diff-emph'>new</span> &lt;line&gt;</td></tr>"
+        ));
+        assert!(html.contains("<tr><td class='diff-empty'></td><td class='diff-ins'><span class='diff-emph'>extra</span></td></tr>"));
+    }
+
+    #[test]
+    fn test_distant_changes_are_separated() {
+        let (left, right) = distant_changes();
+        let html = side_by_side_html(&values(&left, &right));
+        assert_eq!(html.matches("<tr class='diff-gap'>").count(), 1);
+    }
+}
@@ -0,0 +1,80 @@
+//! # Error Module Unit Tests / Error 模块单元测试
+//!
+//! This module contains unit tests for the `error.rs` module,
+//! testing the mapping from errors to process exit codes.
+//!
+//! 此模块包含 `error.rs` 模块的单元测试，
+//! 测试从错误到进程退出码的映射。
+
+use anyhow::Context;
+use matrix_runner::core::MatrixError;
+
+#[cfg(test)]
+mod exit_code_tests {
+    use super::*;
+
+    #[test]
+    fn test_documented_exit_codes() {
+        assert_eq!(MatrixError::Config(anyhow::anyhow!("bad")).exit_code(), 2);
+        assert_eq!(
+            MatrixError::Environment(anyhow::anyhow!("bad")).exit_code(),
+            3
+        );
+        assert_eq!(MatrixError::UnexpectedFailures { count: 1 }.exit_code(), 4);
+        assert_eq!(MatrixError::Cancelled.exit_code(), 5);
+    }
+
+    #[test]
+    fn test_exit_code_through_context() {
+        let error: anyhow::Error = Err::<(), _>(MatrixError::Cancelled)
+            .context("while running")
+            .unwrap_err();
+
+        assert_eq!(MatrixError::exit_code_for(&error), 5);
+    }
+
+    #[test]
+    fn test_exit_code_from_error_source() {
+        #[derive(Debug)]
+        struct Wrapper(MatrixError);
+
+        impl std::fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "wrapped")
+            }
+        }
+
+        impl std::error::Error for Wrapper {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let error = anyhow::Error::new(Wrapper(MatrixError::UnexpectedFailures { count: 2 }))
+            .context("while running");
+
+        assert_eq!(MatrixError::exit_code_for(&error), 4);
+    }
+
+    #[test]
+    fn test_generic_errors_exit_with_one() {
+        let error = anyhow::anyhow!("something else");
+
+        assert_eq!(
+            MatrixError::exit_code_for(&error),
+            MatrixError::GENERIC_EXIT_CODE
+        );
+    }
+
+    #[test]
+    fn test_config_error_display_includes_cause() {
+        let inner = Err::<(), _>(std::io::Error::other("file missing"))
+            .context("Failed to read config")
+            .unwrap_err();
+
+        let message = MatrixError::Config(inner).to_string();
+
+        assert!(message.contains("Failed to read config"));
+        assert!(message.contains("file missing"));
+    }
+}
@@ -0,0 +1,109 @@
+//! # Exec Plan Module Unit Tests / 执行计划模块单元测试
+//!
+//! This module contains unit tests for the `exec_plan.rs` module,
+//! testing that exported plans round-trip and that unknown versions are rejected.
+//!
+//! 此模块包含 `exec_plan.rs` 模块的单元测试，
+//! 测试导出的计划能够往返读写，并且未知版本会被拒绝。
+
+use matrix_runner::core::config::{TestCase, TestMatrix};
+use matrix_runner::core::exec_plan::{ExecPlan, PLAN_VERSION};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod exec_plan_tests {
+    use super::*;
+
+    fn sample_plan() -> ExecPlan {
+        let matrix = TestMatrix {
+            language: "zh-CN".to_string(),
+            fast_fail: true,
+            anomaly_factor: 3.0,
+            force_c_locale: true,
+            prefer_nextest: true,
+            ..Default::default()
+        };
+        let case = TestCase {
+            name: "case".to_string(),
+            features: "a".to_string(),
+            retries: Some(2),
+            ..Default::default()
+        };
+        ExecPlan::new(
+            &matrix,
+            vec![case],
+            PathBuf::from("/work/project"),
+            "my-crate".to_string(),
+            vec!["note".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_plan_resolves_commands() {
+        let plan = sample_plan();
+        assert_eq!(plan.version, PLAN_VERSION);
+        assert_eq!(plan.cases.len(), 1);
+        assert_eq!(
+            plan.cases[0].resolved_command,
+            "cargo test -p my-crate --features a"
+        );
+    }
+
+    #[test]
+    fn test_plan_command_carries_case_env() {
+        let case = TestCase {
+            name: "case".to_string(),
+            env: [("RUST_LOG".to_string(), "trace".to_string())].into(),
+            ..Default::default()
+        };
+        let plan = ExecPlan::new(
+            &TestMatrix::default(),
+            vec![case],
+            PathBuf::from("/work/project"),
+            "my-crate".to_string(),
+            vec![],
+        );
+        assert_eq!(
+            plan.cases[0].resolved_command,
+            "RUST_LOG=trace cargo test -p my-crate"
+        );
+    }
+
+    #[test]
+    fn test_plan_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested/plan.json");
+        sample_plan().save(&path).unwrap();
+
+        let plan = ExecPlan::load(&path).unwrap();
+        assert_eq!(plan.project_root, PathBuf::from("/work/project"));
+        assert_eq!(plan.crate_name, "my-crate");
+        assert_eq!(plan.notes, vec!["note".to_string()]);
+
+        let matrix = plan.to_matrix();
+        assert_eq!(matrix.language, "zh-CN");
+        assert!(matrix.fast_fail);
+        assert_eq!(matrix.anomaly_factor, 3.0);
+        assert!(matrix.force_c_locale);
+        assert!(matrix.prefer_nextest);
+        assert_eq!(matrix.cases.len(), 1);
+        assert_eq!(matrix.cases[0].name, "case");
+        assert_eq!(matrix.cases[0].retries, Some(2));
+    }
+
+    #[test]
+    fn test_plan_rejects_unknown_version() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        sample_plan().save(&path).unwrap();
+        let content = fs::read_to_string(&path)
+            .unwrap()
+            .replace(&format!("\"version\": {PLAN_VERSION}"), "\"version\": 99");
+        fs::write(&path, content).unwrap();
+
+        let err = ExecPlan::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Unsupported plan version 99"));
+    }
+}
@@ -0,0 +1,89 @@
+//! # Execution Module Unit Tests / 执行模块单元测试
+//!
+//! This module contains unit tests for the `execution.rs` module,
+//! testing the record of shared build directories left by a `--keep-going` run
+//! and which cases are only built.
+//!
+//! 此模块包含 `execution.rs` 模块的单元测试，
+//! 测试 `--keep-going` 运行所离开的共享构建目录的记录，以及哪些用例仅被构建。
+
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::execution::{ExecutionContext, IsolationEvents, IsolationLog};
+
+#[cfg(test)]
+mod isolation_log_tests {
+    use super::*;
+
+    #[test]
+    fn test_the_first_case_leaving_a_shared_directory_is_kept() {
+        let log = IsolationLog::default();
+        assert_eq!(log.snapshot(), IsolationEvents::default());
+
+        assert!(log.discard_layer("first"));
+        assert!(!log.discard_layer("second"));
+        assert!(log.leave_shared_target("custom"));
+        assert!(!log.leave_shared_target("other"));
+
+        assert_eq!(
+            log.snapshot(),
+            IsolationEvents {
+                layer_discarded_by: Some("first".to_string()),
+                shared_target_failed_in: Some("custom".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_clones_share_the_record() {
+        let log = IsolationLog::default();
+        let handle = log.clone();
+        handle.leave_shared_target("custom");
+        assert_eq!(
+            log.snapshot().shared_target_failed_in.as_deref(),
+            Some("custom")
+        );
+        assert!(log.snapshot().layer_discarded_by.is_none());
+    }
+}
+
+#[cfg(test)]
+mod build_only_tests {
+    use super::*;
+
+    fn linux_context() -> ExecutionContext {
+        let mut ctx = ExecutionContext::new(std::env::temp_dir(), "sample");
+        ctx.context = RunContext {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            ..RunContext::new("en")
+        };
+        ctx
+    }
+
+    fn case_for(target: Option<&str>) -> TestCase {
+        TestCase {
+            name: "case".to_string(),
+            target: target.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cases_for_targets_the_platform_cannot_run_are_only_built() {
+        let ctx = linux_context();
+        assert!(!ctx.is_build_only(&case_for(None)));
+        assert!(!ctx.is_build_only(&case_for(Some("x86_64-unknown-linux-musl"))));
+        assert!(ctx.is_build_only(&case_for(Some("wasm32-unknown-unknown"))));
+        assert!(ctx.is_build_only(&case_for(Some("aarch64-unknown-linux-gnu"))));
+    }
+
+    #[test]
+    fn test_build_only_cases_are_only_built() {
+        let case = TestCase {
+            build_only: true,
+            ..case_for(None)
+        };
+        assert!(linux_context().is_build_only(&case));
+    }
+}
@@ -0,0 +1,180 @@
+//! # Explain Module Unit Tests / 解释模块单元测试
+//!
+//! This module contains unit tests for the `explain.rs` module,
+//! testing the decision trail of cases kept or dropped by each check.
+//!
+//! 此模块包含 `explain.rs` 模块的单元测试，
+//! 测试被各项检查保留或排除的用例的决策轨迹。
+
+use chrono::NaiveDate;
+use matrix_runner::core::config::{TestCase, TestMatrix};
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::explain::{CaseFilters, Check, Explanation, Step, explain_case};
+use matrix_runner::core::history::RunHistory;
+use matrix_runner::core::selector::Selector;
+
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn matrix(cases: Vec<TestCase>) -> TestMatrix {
+    TestMatrix {
+        cases,
+        ..Default::default()
+    }
+}
+
+fn context() -> RunContext {
+    RunContext {
+        locale: "en".to_string(),
+        os: "linux".to_string(),
+        arch: "x86_64".to_string(),
+    }
+}
+
+/// The day the cases are planned on / 计划用例的日期
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+}
+
+fn explain(matrix: &TestMatrix, name: &str, filters: &CaseFilters) -> Explanation {
+    explain_case(matrix, name, filters, &context(), today(), "sample")
+        .unwrap()
+        .unwrap()
+}
+
+fn step(explanation: &Explanation, wanted: fn(&Check) -> bool) -> &Step {
+    explanation
+        .steps
+        .iter()
+        .find(|step| wanted(&step.check))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    #[test]
+    fn test_a_case_kept_by_every_check() {
+        let history = RunHistory::default();
+        let filters = CaseFilters {
+            selector: None,
+            history: &history,
+            offline_only: false,
+            runners: None,
+        };
+        let matrix = matrix(vec![TestCase {
+            features: "feature_a".to_string(),
+            ..case("a")
+        }]);
+
+        let explanation = explain(&matrix, "a", &filters);
+        assert!(explanation.selected);
+        assert!(
+            explanation
+                .steps
+                .iter()
+                .all(|step| step.passed || !step.check.excludes())
+        );
+        assert_eq!(
+            explanation.command,
+            "cargo test -p sample --features feature_a"
+        );
+        assert_eq!(
+            explain_case(&matrix, "missing", &filters, &context(), today(), "sample").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checks_that_drop_a_case() {
+        let history = RunHistory::default();
+        let selector = Selector::parse(r#"tag == "fast""#).unwrap();
+        let filters = CaseFilters {
+            selector: Some(&selector),
+            history: &history,
+            offline_only: true,
+            runners: None,
+        };
+        let matrix = matrix(vec![TestCase {
+            arch: vec!["aarch64".to_string()],
+            requires_network: true,
+            ..case("a")
+        }]);
+
+        let explanation = explain(&matrix, "a", &filters);
+        assert!(!explanation.selected);
+        let failed: Vec<_> = explanation
+            .steps
+            .iter()
+            .filter(|step| !step.passed)
+            .collect();
+        assert_eq!(failed.len(), 4, "{failed:?}");
+        assert_eq!(
+            step(&explanation, |check| matches!(
+                check,
+                Check::Selector { .. }
+            ))
+            .check,
+            Check::Selector {
+                expression: Some(r#"tag == "fast""#.to_string())
+            }
+        );
+        // A case dropped before planning has no place among the runners.
+        // 在计划之前被排除的用例在运行器之间没有位置。
+        assert!(
+            !explanation
+                .steps
+                .iter()
+                .any(|step| matches!(step.check, Check::Runner { .. }))
+        );
+    }
+
+    #[test]
+    fn test_the_runner_and_after_hints_follow_the_plan() {
+        let history = RunHistory::default();
+        let filters = CaseFilters {
+            selector: None,
+            history: &history,
+            offline_only: false,
+            runners: Some((2, 1)),
+        };
+        // The plan orders them a, b, c: a and c go to runner 0, b to runner 1.
+        // 计划将它们排序为 a、b、c：a 和 c 分配给运行器 0，b 分配给运行器 1。
+        let matrix = matrix(vec![
+            case("c"),
+            TestCase {
+                after: vec!["a".to_string(), "missing".to_string()],
+                ..case("b")
+            },
+            case("a"),
+        ]);
+
+        let explanation = explain(&matrix, "b", &filters);
+        assert!(explanation.selected);
+        let runner = step(&explanation, |check| matches!(check, Check::Runner { .. }));
+        assert_eq!(
+            runner.check,
+            Check::Runner {
+                position: 1,
+                runners: Some((2, 1))
+            }
+        );
+        // Neither `after` case runs on runner 1, so both hints are ignored.
+        // 两个 `after` 用例都不在运行器 1 上运行，因此两个提示都被忽略。
+        let after: Vec<_> = explanation
+            .steps
+            .iter()
+            .filter(|step| matches!(step.check, Check::After { .. }))
+            .collect();
+        assert_eq!(after.len(), 2);
+        assert!(after.iter().all(|step| !step.passed));
+
+        let explanation = explain(&matrix, "c", &filters);
+        assert!(!explanation.selected);
+    }
+}
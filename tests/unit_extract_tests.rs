@@ -0,0 +1,110 @@
+//! # Extract Module Unit Tests / Extract 模块单元测试
+//!
+//! This module contains unit tests for the `extract.rs` module,
+//! testing which part of a failure log is picked as its relevant context.
+//!
+//! 此模块包含 `extract.rs` 模块的单元测试，
+//! 测试失败日志中的哪一部分被选为其相关上下文。
+
+use matrix_runner::reporting::extract::extract_failure_context;
+
+/// Helper function to create filler output lines / 创建填充输出行的辅助函数
+fn noise(count: usize) -> String {
+    (0..count).map(|i| format!("noise line {i}\n")).collect()
+}
+
+#[cfg(test)]
+mod extract_failure_context_tests {
+    use super::*;
+
+    #[test]
+    fn test_short_log_is_not_trimmed() {
+        assert!(extract_failure_context("one\ntwo\nthree").is_none());
+    }
+
+    #[test]
+    fn test_libtest_stdout_section_extracted() {
+        let output = format!(
+            "{}failures:\n\n---- tests::it_fails stdout ----\n\nthread 'tests::it_fails' panicked at src/lib.rs:3:5:\nassertion `left == right` failed\n  left: 1\n right: 2\n\nfailures:\n    tests::it_fails\n\ntest result: FAILED. 1 passed; 1 failed\n",
+            noise(50)
+        );
+        let context = extract_failure_context(&output).unwrap();
+        assert!(context.starts_with("---- tests::it_fails stdout ----"));
+        assert!(context.contains("right: 2"));
+        assert!(!context.contains("noise line"));
+        assert!(!context.contains("test result"));
+    }
+
+    #[test]
+    fn test_panic_extracted_without_libtest_sections() {
+        let output = format!(
+            "{}thread 'main' panicked at src/main.rs:1:1:\nboom\n\n{}",
+            noise(30),
+            noise(30)
+        );
+        assert_eq!(
+            extract_failure_context(&output).unwrap(),
+            "thread 'main' panicked at src/main.rs:1:1:\nboom"
+        );
+    }
+
+    #[test]
+    fn test_compiler_errors_extracted() {
+        let output = format!(
+            "{}error[E0425]: cannot find value `x` in this scope\n --> src/lib.rs:1:1\n\nerror: could not compile `demo`\n",
+            noise(50)
+        );
+        let context = extract_failure_context(&output).unwrap();
+        assert!(context.starts_with("error[E0425]"));
+        assert!(!context.contains("could not compile"));
+    }
+
+    #[test]
+    fn test_falls_back_to_last_lines() {
+        let context = extract_failure_context(&noise(100)).unwrap();
+        assert_eq!(context.lines().count(), 30);
+        assert!(context.ends_with("noise line 99"));
+    }
+}
+
+#[cfg(test)]
+mod assertion_values_tests {
+    use matrix_runner::reporting::extract::{AssertionValues, assertion_values};
+
+    #[test]
+    fn test_string_values_are_unescaped() {
+        let output = "thread 'it_fails' panicked at src/lib.rs:3:5:\nassertion `left == right` failed\n  left: \"a\\nb \\\"quoted\\\"\\n\"\n right: \"a\\nc\\u{e9}\\n\"\n";
+        assert_eq!(
+            assertion_values(output),
+            [AssertionValues {
+                left: "a\nb \"quoted\"\n".to_string(),
+                right: "a\ncé\n".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_old_format_and_other_values_are_kept_as_printed() {
+        let output = "assertion failed: `(left == right)`\n  left: `[1, 2]`,\n right: `[1, 3]`\n\nassertion `left == right` failed\n  left: Some(1)\n right: None\n";
+        assert_eq!(
+            assertion_values(output),
+            [
+                AssertionValues {
+                    left: "[1, 2]".to_string(),
+                    right: "[1, 3]".to_string(),
+                },
+                AssertionValues {
+                    left: "Some(1)".to_string(),
+                    right: "None".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_equal_values_and_other_output_are_ignored() {
+        let output =
+            "assertion `left != right` failed\n  left: 1\n right: 1\nleft: without a right\n";
+        assert!(assertion_values(output).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! # Feature Comparison Module Unit Tests / Feature 比较模块单元测试
+//!
+//! This module contains unit tests for the `feature_compare.rs` module,
+//! testing how the test outcomes under two feature sets are compared, and for the
+//! parsing of those outcomes from libtest output.
+//!
+//! 此模块包含 `feature_compare.rs` 模块的单元测试，
+//! 测试如何比较两组 feature 下的测试结果，以及如何从 libtest 输出中解析这些结果。
+
+use matrix_runner::core::feature_compare::{FeatureComparison, TestOutcome};
+use matrix_runner::infra::command::parse_test_outcomes;
+use std::collections::BTreeMap;
+
+/// Helper function to build the outcomes of a run / 构建一次运行结果的辅助函数
+fn outcomes(tests: &[(&str, TestOutcome)]) -> BTreeMap<String, TestOutcome> {
+    tests
+        .iter()
+        .map(|(name, outcome)| (name.to_string(), *outcome))
+        .collect()
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use super::*;
+
+    #[test]
+    fn test_differing_outcomes_and_compiled_in_tests_are_listed() {
+        let a = outcomes(&[
+            ("broken_by_b", TestOutcome::Passed),
+            ("fixed_by_b", TestOutcome::Failed),
+            ("stable", TestOutcome::Passed),
+            ("dropped_by_b", TestOutcome::Passed),
+        ]);
+        let b = outcomes(&[
+            ("broken_by_b", TestOutcome::Failed),
+            ("fixed_by_b", TestOutcome::Passed),
+            ("stable", TestOutcome::Passed),
+            ("added_by_b", TestOutcome::Failed),
+        ]);
+
+        let comparison = FeatureComparison::compare(&a, &b);
+        assert_eq!(comparison.fail_only_in_b, vec!["broken_by_b"]);
+        assert_eq!(comparison.fail_only_in_a, vec!["fixed_by_b"]);
+        assert_eq!(comparison.only_in_a, vec!["dropped_by_b"]);
+        assert_eq!(comparison.only_in_b, vec!["added_by_b"]);
+        assert_eq!(comparison.common, 3);
+        assert!(comparison.has_differing_outcomes());
+    }
+
+    #[test]
+    fn test_ignored_tests_and_new_tests_do_not_differ() {
+        let a = outcomes(&[
+            ("slow", TestOutcome::Ignored),
+            ("fast", TestOutcome::Passed),
+        ]);
+        let b = outcomes(&[
+            ("slow", TestOutcome::Failed),
+            ("fast", TestOutcome::Passed),
+            ("extra", TestOutcome::Passed),
+        ]);
+
+        let comparison = FeatureComparison::compare(&a, &b);
+        assert!(!comparison.has_differing_outcomes());
+        assert_eq!(comparison.only_in_b, vec!["extra"]);
+        assert_eq!(comparison.common, 2);
+    }
+}
+
+#[cfg(test)]
+mod parse_tests {
+    use super::*;
+
+    #[test]
+    fn test_human_readable_outcomes_are_parsed() {
+        let output = "running 3 tests\n\
+                      test tests::a ... ok\n\
+                      test tests::b ... FAILED\n\
+                      test tests::c ... ignored, slow\n\
+                      \n\
+                      test result: FAILED. 1 passed; 1 failed; 1 ignored\n";
+        assert_eq!(
+            parse_test_outcomes(output),
+            outcomes(&[
+                ("tests::a", TestOutcome::Passed),
+                ("tests::b", TestOutcome::Failed),
+                ("tests::c", TestOutcome::Ignored),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_outcomes_are_parsed() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "a" }
+{ "type": "test", "name": "a", "event": "ok" }
+{ "type": "test", "name": "b", "event": "failed", "stdout": "boom" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1 }"#;
+        assert_eq!(
+            parse_test_outcomes(output),
+            outcomes(&[("a", TestOutcome::Passed), ("b", TestOutcome::Failed)])
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! # Fingerprint Module Unit Tests / 指纹模块单元测试
+//!
+//! This module contains unit tests for the `fingerprint.rs` module,
+//! testing that fingerprints ignore what changes between runs and how the history
+//! counts the runs a failure persists for.
+//!
+//! 此模块包含 `fingerprint.rs` 模块的单元测试，
+//! 测试指纹会忽略多次运行之间变化的内容，以及历史记录如何统计失败持续的运行次数。
+
+mod common;
+use crate::common::failed_with;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::fingerprint::{
+    failure_fingerprint, fingerprint_failures, normalize_failure_message,
+};
+use matrix_runner::core::history::RunHistory;
+use matrix_runner::core::models::{FailureReason, TestResult};
+use std::time::Duration;
+
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        id: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn failed(name: &str, reason: FailureReason, output: &str) -> TestResult {
+    failed_with(case(name), reason, output, Duration::from_secs(1))
+}
+
+fn passed(name: &str) -> TestResult {
+    common::passed(case(name))
+}
+
+const PANIC_RUN_1: &str = "running 2 tests
+test tests::ok ... ok
+test tests::parses ... FAILED
+
+---- tests::parses stdout ----
+thread 'tests::parses' panicked at src/lib.rs:42:9:
+assertion `left == right` failed
+  left: 3
+ right: 4
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; finished in 0.12s";
+
+const PANIC_RUN_2: &str = "running 2 tests
+test tests::parses ... FAILED
+test tests::ok ... ok
+
+---- tests::parses stdout ----
+thread 'tests::parses' panicked at src/lib.rs:45:9:
+assertion `left == right` failed
+  left: 7
+ right: 8
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; finished in 0.31s";
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable_across_runs_of_the_same_failure() {
+        let first =
+            failure_fingerprint(&failed("case", FailureReason::TestFailed, PANIC_RUN_1)).unwrap();
+        let second =
+            failure_fingerprint(&failed("case", FailureReason::TestFailed, PANIC_RUN_2)).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 16);
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_case_reason_and_message() {
+        let base = failure_fingerprint(&failed("case", FailureReason::TestFailed, PANIC_RUN_1));
+        let other_case =
+            failure_fingerprint(&failed("other", FailureReason::TestFailed, PANIC_RUN_1));
+        let other_reason =
+            failure_fingerprint(&failed("case", FailureReason::CustomCommand, PANIC_RUN_1));
+        let other_message = failure_fingerprint(&failed(
+            "case",
+            FailureReason::TestFailed,
+            &PANIC_RUN_1.replace("tests::parses", "tests::formats"),
+        ));
+        assert_ne!(base, other_case);
+        assert_ne!(base, other_reason);
+        assert_ne!(base, other_message);
+        assert!(failure_fingerprint(&passed("case")).is_none());
+    }
+
+    #[test]
+    fn test_timeouts_ignore_the_output() {
+        let a = failure_fingerprint(&failed("slow", FailureReason::Timeout, "got to step 3"));
+        let b = failure_fingerprint(&failed("slow", FailureReason::Timeout, "got to step 9"));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_normalize_keeps_identifying_lines_and_masks_volatile_parts() {
+        let output = "\u{1b}[1m\u{1b}[38;5;9merror[E0425]\u{1b}[0m: cannot find value `x` in this scope\n  --> src/main.rs:12:5\nerror: could not compile `demo` (bin \"demo\") due to 1 previous error\nwarning: build failed, waiting for other jobs to finish...\n/tmp/.tmpAb12Cd/debug/demo at 0x7ffee4b2";
+        let normalized = normalize_failure_message(output);
+        assert_eq!(
+            normalized,
+            "error[E#]: cannot find value `x` in this scope\nerror: could not compile `demo` (bin \"demo\") due to # previous error"
+        );
+
+        // Without an identifying line, the last lines are used.
+        let tail = normalize_failure_message("step 1\nstep 2\n\nexit code 101 after 0x1f\n");
+        assert_eq!(tail, "step #\nstep #\nexit code # after 0x#");
+    }
+}
+
+#[cfg(test)]
+mod failure_streak_tests {
+    use super::*;
+
+    #[test]
+    fn test_history_counts_runs_with_the_same_failure() {
+        let mut history = RunHistory::default();
+
+        history.record(&[failed("case", FailureReason::TestFailed, PANIC_RUN_1)]);
+        assert_eq!(
+            history.failure_of(&case("case")).unwrap().consecutive_runs,
+            1
+        );
+
+        let second_run = [failed("case", FailureReason::TestFailed, PANIC_RUN_2)];
+        history.record(&second_run);
+        let fingerprints = fingerprint_failures(&second_run, &history);
+        assert_eq!(fingerprints.len(), 1);
+        assert_eq!(fingerprints[0].case, "case");
+        assert_eq!(fingerprints[0].consecutive_runs, 2);
+
+        // A different failure starts a new streak.
+        history.record(&[failed(
+            "case",
+            FailureReason::Build,
+            "error: linker `cc` not found",
+        )]);
+        assert_eq!(
+            history.failure_of(&case("case")).unwrap().consecutive_runs,
+            1
+        );
+
+        // Skipped results keep the streak, a pass ends it.
+        history.record(&[TestResult::skipped()]);
+        assert!(history.failure_of(&case("case")).is_some());
+        history.record(&[passed("case")]);
+        assert!(history.failure_of(&case("case")).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! # Fixtures Module Unit Tests / Fixture 模块单元测试
+//!
+//! This module contains unit tests for the `fixtures.rs` module,
+//! testing how fixtures are staged, shared between running cases and removed.
+//!
+//! 此模块包含 `fixtures.rs` 模块的单元测试，
+//! 测试 fixture 如何被放置、在运行中的用例之间共享以及被删除。
+
+use matrix_runner::core::config::{Fixture, FixtureMode};
+use matrix_runner::core::fixtures::FixtureStage;
+use std::fs;
+use tempfile::{TempDir, tempdir};
+
+fn fixture(from: &str, to: &str, mode: FixtureMode) -> Fixture {
+    Fixture {
+        from: from.into(),
+        to: to.into(),
+        mode,
+    }
+}
+
+/// Helper function to create a project with a data file / 创建带有数据文件的项目的辅助函数
+fn project() -> TempDir {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("tests/data")).unwrap();
+    fs::write(dir.path().join("tests/data/input.txt"), "input").unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod fixtures_tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_staged_and_removed() {
+        let dir = project();
+        let root = dir.path();
+        let stage = FixtureStage::default();
+        let fixtures = [fixture(
+            "tests/data/input.txt",
+            "data/nested/input.txt",
+            FixtureMode::Copy,
+        )];
+
+        let staged = stage.stage(root, &fixtures).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("data/nested/input.txt")).unwrap(),
+            "input"
+        );
+
+        // The directories created for the fixture are removed with it.
+        // 为 fixture 创建的目录会与其一起被删除。
+        drop(staged);
+        assert!(!root.join("data").exists());
+        assert!(root.join("tests/data/input.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_fixtures_point_to_the_source() {
+        let dir = project();
+        let root = dir.path();
+        let stage = FixtureStage::default();
+        let staged = stage
+            .stage(
+                root,
+                &[fixture(
+                    "tests/data/input.txt",
+                    "input.txt",
+                    FixtureMode::Symlink,
+                )],
+            )
+            .unwrap();
+        let link = root.join("input.txt");
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&link).unwrap(), "input");
+
+        drop(staged);
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(root.join("tests/data/input.txt").exists());
+    }
+
+    #[test]
+    fn test_running_cases_share_the_same_fixture() {
+        let dir = project();
+        let root = dir.path();
+        let stage = FixtureStage::default();
+        let fixtures = [fixture(
+            "tests/data/input.txt",
+            "input.txt",
+            FixtureMode::Copy,
+        )];
+
+        let first = stage.stage(root, &fixtures).unwrap();
+        let second = stage.clone().stage(root, &fixtures).unwrap();
+        drop(first);
+        assert!(root.join("input.txt").exists());
+        drop(second);
+        assert!(!root.join("input.txt").exists());
+    }
+
+    #[test]
+    fn test_existing_files_are_not_replaced() {
+        let dir = project();
+        let root = dir.path();
+        fs::write(root.join("tests/data/other.txt"), "other").unwrap();
+        fs::write(root.join("taken.txt"), "mine").unwrap();
+        let stage = FixtureStage::default();
+
+        let err = stage
+            .stage(
+                root,
+                &[fixture(
+                    "tests/data/input.txt",
+                    "taken.txt",
+                    FixtureMode::Copy,
+                )],
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert_eq!(fs::read_to_string(root.join("taken.txt")).unwrap(), "mine");
+
+        // Another source staged to a path in use by a running case is an error too.
+        // 将其他源文件放置到运行中用例正在使用的路径同样是错误。
+        let _staged = stage
+            .stage(
+                root,
+                &[fixture(
+                    "tests/data/input.txt",
+                    "input.txt",
+                    FixtureMode::Copy,
+                )],
+            )
+            .unwrap();
+        let err = stage
+            .stage(
+                root,
+                &[fixture(
+                    "tests/data/other.txt",
+                    "input.txt",
+                    FixtureMode::Copy,
+                )],
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("a running case staged"));
+        assert_eq!(fs::read_to_string(root.join("input.txt")).unwrap(), "input");
+    }
+
+    #[test]
+    fn test_fixtures_staged_before_an_error_are_removed() {
+        let dir = project();
+        let root = dir.path();
+        let stage = FixtureStage::default();
+        let fixtures = [
+            fixture("tests/data/input.txt", "input.txt", FixtureMode::Copy),
+            fixture("tests/data/missing.txt", "missing.txt", FixtureMode::Copy),
+        ];
+
+        let err = stage.stage(root, &fixtures).unwrap_err();
+        assert!(err.to_string().contains("is not a file"));
+        assert!(!root.join("input.txt").exists());
+    }
+}
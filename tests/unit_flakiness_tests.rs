@@ -0,0 +1,101 @@
+//! # Flakiness Module Unit Tests / 不稳定性模块单元测试
+//!
+//! This module contains unit tests for the `flakiness.rs` module,
+//! testing how the history counts the runs needing retries, how the flaky score
+//! follows recent runs and which cases the flakiness summary lists.
+//!
+//! 此模块包含 `flakiness.rs` 模块的单元测试，
+//! 测试历史记录如何统计需要重试的运行、不稳定分数如何跟随近期运行，以及不稳定性摘要列出哪些用例。
+
+mod common;
+use crate::common::passed_in;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::flakiness::flaky_cases;
+use matrix_runner::core::history::{FLAKY_SCORE_WEIGHT, RetryStats, RunHistory};
+use matrix_runner::core::models::TestResult;
+use std::time::Duration;
+
+/// A case with its name as id / 以名称作为 ID 的用例
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        id: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+/// A pass after `attempts` attempts / 经过 `attempts` 次尝试后的通过
+fn passed(name: &str, attempts: u8) -> TestResult {
+    passed_in(case(name), Duration::from_secs(1), attempts)
+}
+
+#[cfg(test)]
+mod retry_stats_tests {
+    use super::*;
+
+    #[test]
+    fn test_history_counts_runs_needing_retries() {
+        let mut history = RunHistory::default();
+        history.record(&[passed("flaky", 2), passed("stable", 1)]);
+        history.record(&[passed("flaky", 1), passed("stable", 1)]);
+        history.record(&[passed("flaky", 3), TestResult::skipped()]);
+
+        let flaky = history.retries_of(&case("flaky")).unwrap();
+        assert_eq!((flaky.retried_runs, flaky.runs), (2, 3));
+        let stable = history.retries_of(&case("stable")).unwrap();
+        assert_eq!((stable.retried_runs, stable.runs), (0, 2));
+        assert_eq!(stable.flaky_score, 0.0);
+    }
+
+    #[test]
+    fn test_recent_runs_weigh_more_in_the_score() {
+        let mut recovering = RetryStats::default();
+        recovering.record(true);
+        recovering.record(false);
+        let mut worsening = RetryStats::default();
+        worsening.record(false);
+        worsening.record(true);
+
+        assert_eq!(recovering.retried_runs, worsening.retried_runs);
+        assert!(worsening.flaky_score > recovering.flaky_score);
+        assert!((worsening.flaky_score - FLAKY_SCORE_WEIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_score_approaches_one_when_every_run_needs_retries() {
+        let mut stats = RetryStats::default();
+        for _ in 0..20 {
+            stats.record(true);
+        }
+        assert!(stats.flaky_score > 0.99 && stats.flaky_score <= 1.0);
+    }
+}
+
+#[cfg(test)]
+mod flaky_cases_tests {
+    use super::*;
+
+    #[test]
+    fn test_only_cases_retried_this_run_are_listed_highest_score_first() {
+        let mut history = RunHistory::default();
+        history.record(&[passed("often", 2), passed("once", 1)]);
+        let results = [passed("often", 2), passed("once", 3), passed("stable", 1)];
+        history.record(&results);
+
+        let cases = flaky_cases(&results, &history);
+        let names: Vec<_> = cases.iter().map(|case| case.case.as_str()).collect();
+        assert_eq!(names, ["often", "once"]);
+        assert_eq!(cases[0].retries, 1);
+        assert_eq!((cases[0].retried_runs, cases[0].runs), (2, 2));
+        assert_eq!(cases[1].retries, 2);
+        assert_eq!((cases[1].retried_runs, cases[1].runs), (1, 2));
+    }
+
+    #[test]
+    fn test_a_run_without_retries_has_no_flaky_cases() {
+        let mut history = RunHistory::default();
+        let results = [passed("stable", 1)];
+        history.record(&results);
+        assert!(flaky_cases(&results, &history).is_empty());
+    }
+}
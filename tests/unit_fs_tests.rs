@@ -0,0 +1,236 @@
+//! # File System Module Unit Tests / 文件系统模块单元测试
+//!
+//! This module contains unit tests for the `fs.rs` module,
+//! testing path naming, attachment directories, Windows path-length hardening, shared-file writing helpers, result annotations and cargo lock detection.
+//!
+//! 此模块包含 `fs.rs` 模块的单元测试，
+//! 测试路径命名、附件目录、Windows 路径长度加固、共享文件写入辅助函数、结果注解和 cargo 锁检测。
+
+use matrix_runner::infra::fs::{
+    CARGO_LOCK_FILE, WINDOWS_MAX_PATH, case_dir_name, create_annotations_file,
+    create_attachment_dir, dir_size, held_cargo_lock, lock_file, max_path_risk, parse_annotations,
+    read_annotations, short_hash, to_extended_length, write_atomic,
+};
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod naming_tests {
+    use super::*;
+
+    #[test]
+    fn test_short_hash_is_stable_and_short() {
+        assert_eq!(short_hash("case-a"), short_hash("case-a"));
+        assert_ne!(short_hash("case-a"), short_hash("case-b"));
+        assert_eq!(short_hash("case-a").len(), 8);
+    }
+
+    #[test]
+    fn test_case_dir_name_short_names_are_sanitized() {
+        assert_eq!(
+            case_dir_name("std / no-default"),
+            format!("std___no_default_{}", short_hash("std / no-default"))
+        );
+        assert_eq!(case_dir_name("std"), "std");
+    }
+
+    #[test]
+    fn test_case_dir_name_sanitized_names_do_not_collide() {
+        assert_ne!(case_dir_name("linux x86"), case_dir_name("linux-x86"));
+        assert_ne!(case_dir_name("linux_x86"), case_dir_name("linux-x86"));
+    }
+
+    #[test]
+    fn test_case_dir_name_long_names_are_bounded() {
+        let long_a = format!("{}-a", "very-long-case-name".repeat(10));
+        let long_b = format!("{}-b", "very-long-case-name".repeat(10));
+
+        let dir_a = case_dir_name(&long_a);
+        let dir_b = case_dir_name(&long_b);
+
+        assert_eq!(dir_a.chars().count(), 32);
+        assert_ne!(dir_a, dir_b);
+    }
+}
+
+#[cfg(test)]
+mod attachment_dir_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_attachment_dir_leaves_other_cases_alone() {
+        let state_dir = tempdir().unwrap();
+        let first = create_attachment_dir(state_dir.path(), "linux x86").unwrap();
+        std::fs::write(first.join("screenshot.png"), "png").unwrap();
+
+        let second = create_attachment_dir(state_dir.path(), "linux-x86").unwrap();
+
+        assert_ne!(first, second);
+        assert!(first.join("screenshot.png").exists());
+    }
+
+    #[test]
+    fn test_attachment_dir_removes_leftovers_of_the_same_case() {
+        let state_dir = tempdir().unwrap();
+        let dir = create_attachment_dir(state_dir.path(), "linux x86").unwrap();
+        std::fs::write(dir.join("old.log"), "old").unwrap();
+
+        let dir = create_attachment_dir(state_dir.path(), "linux x86").unwrap();
+
+        assert!(!dir.join("old.log").exists());
+    }
+}
+
+#[cfg(test)]
+mod long_path_tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_length_drive_path() {
+        assert_eq!(
+            to_extended_length(r"C:\work\project/target").as_deref(),
+            Some(r"\\?\C:\work\project\target")
+        );
+    }
+
+    #[test]
+    fn test_extended_length_unc_path() {
+        assert_eq!(
+            to_extended_length(r"\\server\share\project").as_deref(),
+            Some(r"\\?\UNC\server\share\project")
+        );
+    }
+
+    #[test]
+    fn test_extended_length_leaves_other_paths_alone() {
+        assert!(to_extended_length(r"\\?\C:\already").is_none());
+        assert!(to_extended_length(r"relative\path").is_none());
+        assert!(to_extended_length("/unix/path").is_none());
+    }
+
+    #[test]
+    fn test_max_path_risk() {
+        assert!(max_path_risk(&PathBuf::from(r"C:\p\target")).is_none());
+
+        let deep = PathBuf::from(format!(r"C:\{}\target", "d".repeat(WINDOWS_MAX_PATH)));
+        assert!(max_path_risk(&deep).unwrap() > WINDOWS_MAX_PATH);
+    }
+}
+
+#[cfg(test)]
+mod shared_write_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_atomic_replaces_the_file_without_leftovers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("report.html");
+        std::fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_dir_size_counts_nested_files() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("debug/deps")).unwrap();
+        std::fs::write(dir.path().join("debug/deps/a"), [0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("b"), [0u8; 20]).unwrap();
+
+        assert_eq!(dir_size(dir.path()), 120);
+        assert_eq!(dir_size(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn test_lock_file_serializes_writers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("report.html");
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let lock = lock_file(&path).unwrap();
+        assert!(dir.path().join("report.html.lock").exists());
+        let waiter = {
+            let path = path.clone();
+            let order = order.clone();
+            std::thread::spawn(move || {
+                let _lock = lock_file(&path).unwrap();
+                order.lock().unwrap().push("second");
+            })
+        };
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        order.lock().unwrap().push("first");
+        drop(lock);
+        waiter.join().unwrap();
+
+        assert_eq!(*order.lock().unwrap(), ["first", "second"]);
+    }
+}
+
+#[cfg(test)]
+mod cargo_lock_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_held_cargo_lock_finds_a_locked_profile_directory() {
+        let target = tempdir().unwrap();
+        std::fs::create_dir_all(target.path().join("debug")).unwrap();
+        let lock_path = target.path().join("debug").join(CARGO_LOCK_FILE);
+        std::fs::write(&lock_path, "").unwrap();
+
+        assert_eq!(held_cargo_lock(target.path()), None);
+
+        // Stands in for another cargo process building into the directory.
+        let file = std::fs::File::open(&lock_path).unwrap();
+        file.lock().unwrap();
+        assert_eq!(held_cargo_lock(target.path()), Some(lock_path.clone()));
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            matrix_runner::infra::fs::lock_holder(&lock_path),
+            Some(std::process::id())
+        );
+
+        drop(file);
+        assert_eq!(held_cargo_lock(target.path()), None);
+    }
+
+    #[test]
+    fn test_missing_target_dir_has_no_lock() {
+        let dir = tempdir().unwrap();
+        assert_eq!(held_cargo_lock(&dir.path().join("target")), None);
+    }
+}
+
+#[cfg(test)]
+mod annotations_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_annotations_keeps_the_last_value_of_each_key() {
+        let annotations = parse_annotations(
+            "throughput_mb_s=412\n# written by the bench harness\n\ndataset = v3\nnot an annotation\nthroughput_mb_s=420\n=orphan\n",
+        );
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations["throughput_mb_s"], "420");
+        assert_eq!(annotations["dataset"], "v3");
+    }
+
+    #[test]
+    fn test_annotations_file_starts_empty_for_every_run() {
+        let state_dir = tempdir().unwrap();
+        let path = create_annotations_file(state_dir.path(), "bench").unwrap();
+        std::fs::write(&path, "dataset=v3\n").unwrap();
+        assert_eq!(read_annotations(&path).len(), 1);
+
+        let path = create_annotations_file(state_dir.path(), "bench").unwrap();
+        assert!(read_annotations(&path).is_empty());
+        assert!(read_annotations(&state_dir.path().join("missing.txt")).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! # Generator Module Unit Tests / 生成器模块单元测试
+//!
+//! This module contains unit tests for the `generator.rs` module,
+//! testing how generated cases are parsed and merged into a loaded matrix.
+//!
+//! 此模块包含 `generator.rs` 模块的单元测试，
+//! 测试生成的用例如何被解析并合并到加载的矩阵中。
+
+#![cfg(unix)]
+
+use matrix_runner::core::config::load_test_matrix;
+use matrix_runner::core::generator::{CaseGenerator, generate_cases};
+use std::fs;
+use tempfile::tempdir;
+
+fn generator(command: &str) -> CaseGenerator {
+    CaseGenerator {
+        command: command.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_cases_parses_stdout() {
+        let dir = tempdir().unwrap();
+        let cases = generate_cases(
+            &generator(
+                r#"echo '[{"name": "board-a", "features": "hw", "no_default_features": false, "tags": ["hw"]}]'"#,
+            ),
+            dir.path(),
+        )
+        .unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].name, "board-a");
+        assert_eq!(cases[0].features, "hw");
+        assert_eq!(cases[0].tags, vec!["hw"]);
+    }
+
+    #[test]
+    fn test_generate_cases_runs_in_working_dir() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("cases.json"),
+            r#"[{"name": "from-file", "features": "", "no_default_features": true}]"#,
+        )
+        .unwrap();
+        let cases = generate_cases(&generator("cat cases.json"), dir.path()).unwrap();
+        assert_eq!(cases[0].name, "from-file");
+        assert!(cases[0].no_default_features);
+    }
+
+    #[test]
+    fn test_generate_cases_failing_command() {
+        let dir = tempdir().unwrap();
+        let err = generate_cases(
+            &generator("sh -c 'echo inventory offline >&2; exit 3'"),
+            dir.path(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("failed"), "{err}");
+        assert!(err.contains("inventory offline"), "{err}");
+    }
+
+    #[test]
+    fn test_generate_cases_invalid_output() {
+        let dir = tempdir().unwrap();
+        let err = generate_cases(&generator("echo not-json"), dir.path()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("did not print a JSON array of cases")
+        );
+    }
+
+    #[test]
+    fn test_load_test_matrix_merges_generated_cases() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("gen.sh"),
+            r#"echo '[{"name": "generated", "features": "", "no_default_features": false, "after": ["static"]}]'"#,
+        )
+        .unwrap();
+        let config = dir.path().join("TestMatrix.toml");
+        fs::write(
+            &config,
+            r#"
+            [generator]
+            command = "sh gen.sh"
+
+            [[cases]]
+            name = "static"
+            features = ""
+            no_default_features = false
+            "#,
+        )
+        .unwrap();
+
+        let matrix = load_test_matrix(&config).unwrap();
+        let names: Vec<_> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, vec!["static", "generated"]);
+    }
+
+    #[test]
+    fn test_load_test_matrix_with_only_generated_cases() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("TestMatrix.toml");
+        fs::write(
+            &config,
+            r#"
+            cases = []
+
+            [generator]
+            command = "echo '[{\"name\": \"only\", \"features\": \"\", \"no_default_features\": false}]'"
+            "#,
+        )
+        .unwrap();
+
+        let matrix = load_test_matrix(&config).unwrap();
+        assert_eq!(matrix.cases.len(), 1);
+        assert_eq!(matrix.cases[0].name, "only");
+    }
+}
@@ -0,0 +1,201 @@
+//! # History Module Unit Tests / History 模块单元测试
+//!
+//! This module contains unit tests for the `history.rs` module,
+//! testing how cases are keyed by id, the archive of past run results and its retention policy.
+//!
+//! 此模块包含 `history.rs` 模块的单元测试，
+//! 测试用例如何以 ID 为键、以往运行结果的归档及其保留策略。
+
+mod common;
+use crate::common::passed;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::history::{
+    ArchivedRun, REPORTS_DIR, Retention, RunHistory, archived_report_path, archived_runs,
+    prune_reports, runs_to_prune,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// Helper function to archive an empty result at a given time / 在给定时间归档空结果的辅助函数
+fn archive(state_dir: &Path, recorded_at: DateTime<Utc>) {
+    let path = archived_report_path(state_dir, recorded_at);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, "{}").unwrap();
+}
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+}
+
+#[cfg(test)]
+mod case_id_tests {
+    use super::*;
+
+    #[test]
+    fn test_a_renamed_case_keeps_its_history() {
+        let case = TestCase {
+            name: "std".to_string(),
+            features: "std".to_string(),
+            ..Default::default()
+        };
+        let mut history = RunHistory::default();
+        history.record(&[passed(case.clone())]);
+        assert_eq!(
+            history.names.get(&case.case_id()).map(String::as_str),
+            Some("std")
+        );
+
+        let renamed = TestCase {
+            name: "with-std".to_string(),
+            ..case.clone()
+        };
+        assert_eq!(history.status_of(&renamed), Some("passed"));
+        history.record(&[passed(renamed.clone())]);
+        assert_eq!(history.retries_of(&renamed).unwrap().runs, 2);
+        assert_eq!(
+            history.names.get(&case.case_id()).map(String::as_str),
+            Some("with-std")
+        );
+    }
+
+    #[test]
+    fn test_entries_keyed_by_name_move_to_the_id() {
+        let case = TestCase {
+            name: "std".to_string(),
+            ..Default::default()
+        };
+        let legacy = r#"{
+            "cases": {"std": "failed"},
+            "retries": {"std": {"runs": 3, "retried_runs": 1, "flaky_score": 0.3}}
+        }"#;
+        let mut history: RunHistory = serde_json::from_str(legacy).unwrap();
+        assert_eq!(history.status_of(&case), Some("failed"));
+
+        history.record(&[passed(case.clone())]);
+        assert!(!history.cases.contains_key("std"));
+        assert_eq!(history.status_of(&case), Some("passed"));
+        assert_eq!(history.retries_of(&case).unwrap().runs, 4);
+    }
+}
+
+#[cfg(test)]
+mod archive_tests {
+    use super::*;
+
+    #[test]
+    fn test_archived_runs_are_listed_most_recent_first() {
+        let dir = tempdir().unwrap();
+        archive(dir.path(), now() - Duration::days(2));
+        archive(dir.path(), now());
+        archive(dir.path(), now() - Duration::days(1));
+        fs::write(dir.path().join(REPORTS_DIR).join("notes.txt"), "kept").unwrap();
+
+        let runs = archived_runs(dir.path());
+        let times: Vec<DateTime<Utc>> = runs.iter().map(|run| run.recorded_at).collect();
+        assert_eq!(
+            times,
+            vec![now(), now() - Duration::days(1), now() - Duration::days(2)]
+        );
+        assert_eq!(
+            runs[0].path.file_name().unwrap().to_str(),
+            Some("run-20250301T120000.000Z.json")
+        );
+    }
+
+    #[test]
+    fn test_missing_history_directory_has_no_runs() {
+        let dir = tempdir().unwrap();
+        assert!(archived_runs(dir.path()).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod retention_tests {
+    use super::*;
+
+    fn runs(days_ago: &[i64]) -> Vec<ArchivedRun> {
+        days_ago
+            .iter()
+            .map(|days| ArchivedRun {
+                path: format!("run-{days}.json").into(),
+                recorded_at: now() - Duration::days(*days),
+            })
+            .collect()
+    }
+
+    fn pruned_days(runs: &[ArchivedRun], retention: Retention) -> Vec<i64> {
+        runs_to_prune(runs, retention, now())
+            .iter()
+            .map(|run| (now() - run.recorded_at).num_days())
+            .collect()
+    }
+
+    #[test]
+    fn test_runs_beyond_the_count_are_pruned() {
+        let runs = runs(&[0, 1, 2, 3]);
+        let retention = Retention {
+            keep_runs: 2,
+            keep_days: 0,
+        };
+        assert_eq!(pruned_days(&runs, retention), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_runs_older_than_the_age_are_pruned() {
+        let runs = runs(&[0, 10, 100, 200]);
+        let retention = Retention {
+            keep_runs: 0,
+            keep_days: 90,
+        };
+        assert_eq!(pruned_days(&runs, retention), vec![100, 200]);
+    }
+
+    #[test]
+    fn test_either_limit_prunes_a_run() {
+        let runs = runs(&[0, 1, 100]);
+        let retention = Retention {
+            keep_runs: 2,
+            keep_days: 90,
+        };
+        assert_eq!(pruned_days(&runs, retention), vec![100]);
+
+        let retention = Retention {
+            keep_runs: 1,
+            keep_days: 90,
+        };
+        assert_eq!(pruned_days(&runs, retention), vec![1, 100]);
+    }
+
+    #[test]
+    fn test_zero_limits_keep_everything() {
+        let runs = runs(&[0, 1000]);
+        let retention = Retention {
+            keep_runs: 0,
+            keep_days: 0,
+        };
+        assert!(pruned_days(&runs, retention).is_empty());
+    }
+
+    #[test]
+    fn test_prune_reports_deletes_unless_dry_run() {
+        let dir = tempdir().unwrap();
+        archive(dir.path(), now());
+        archive(dir.path(), now() - Duration::days(1));
+        let retention = Retention {
+            keep_runs: 1,
+            keep_days: 0,
+        };
+
+        let listed = prune_reports(dir.path(), retention, now(), true).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].path.exists());
+        assert_eq!(archived_runs(dir.path()).len(), 2);
+
+        let deleted = prune_reports(dir.path(), retention, now(), false).unwrap();
+        assert_eq!(deleted, listed);
+        assert!(!deleted[0].path.exists());
+        assert_eq!(archived_runs(dir.path()).len(), 1);
+    }
+}
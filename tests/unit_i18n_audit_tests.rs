@@ -0,0 +1,86 @@
+//! # I18n Audit Module Unit Tests / 国际化审计模块单元测试
+//!
+//! This module contains unit tests for the `i18n_audit.rs` module,
+//! testing locale flattening, the audit findings and locale stubs, and that the
+//! shipped locale files define every key used in the code.
+//!
+//! 此模块包含 `i18n_audit.rs` 模块的单元测试，
+//! 测试本地化文件展平、审计结果和语言存根，以及随附的本地化文件定义了代码中使用的每个键。
+
+use matrix_runner::infra::i18n_audit::{
+    Translations, USED_KEYS, audit, load_locales, parse_locale, stub_locale,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[cfg(test)]
+mod i18n_audit_tests {
+    use super::*;
+
+    fn translations(pairs: &[(&str, &str)]) -> Translations {
+        pairs
+            .iter()
+            .map(|(key, text)| (key.to_string(), text.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_locale_flattens_sections() {
+        let parsed = parse_locale(
+            r#"
+[run]
+started = "Started"
+
+[html_report.summary]
+total = "Total"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            translations(&[
+                ("run.started", "Started"),
+                ("html_report.summary.total", "Total")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_audit_reports_missing_and_unused_keys() {
+        let locales = BTreeMap::from([
+            (
+                "en".to_string(),
+                translations(&[("a.used", "A"), ("a.old", "Old")]),
+            ),
+            ("zh-CN".to_string(), translations(&[("a.used", "甲")])),
+        ]);
+        let report = audit(&["a.used", "a.new"], &locales);
+
+        assert!(report.has_missing());
+        assert_eq!(report.missing["en"], vec!["a.new".to_string()]);
+        assert_eq!(
+            report.missing["zh-CN"],
+            vec!["a.new".to_string(), "a.old".to_string()]
+        );
+        assert_eq!(report.unused, vec!["a.old".to_string()]);
+    }
+
+    #[test]
+    fn test_stub_round_trips() {
+        let base = translations(&[
+            ("run.started", "Started \"%{name}\""),
+            ("html_report.summary.total", "Total"),
+        ]);
+        let stub = stub_locale(&base, "fr");
+        assert!(stub.starts_with("# Locale stub for `fr`"));
+        assert_eq!(parse_locale(&stub).unwrap(), base);
+    }
+
+    #[test]
+    fn test_shipped_locales_define_every_used_key() {
+        let locales = load_locales(Path::new("locales")).unwrap();
+        assert!(locales.contains_key("en") && locales.contains_key("zh-CN"));
+        let report = audit(USED_KEYS, &locales);
+        assert!(!report.has_missing(), "missing keys: {:?}", report.missing);
+    }
+}
@@ -0,0 +1,167 @@
+//! # Results Schema Unit Tests / 结果模式单元测试
+//!
+//! This module contains unit tests for the results schema of the `json.rs` module,
+//! testing that the published schema is up to date and that written results match it.
+//!
+//! 此模块包含 `json.rs` 模块中结果模式的单元测试，
+//! 测试发布的模式是否为最新，以及写出的结果是否与之匹配。
+
+use matrix_runner::core::anomaly::DurationAnomaly;
+use matrix_runner::core::config::{AllowFailure, Fixture, FixtureMode, Limit, TestCase, Ulimits};
+use matrix_runner::core::costs::CaseCost;
+use matrix_runner::core::fingerprint::FailureFingerprint;
+use matrix_runner::core::models::{FailureReason, RunMetadata, TestResult, TestTiming};
+use matrix_runner::reporting::json::{
+    SCHEMA_VERSION, generate_json_report, results_schema, validate_results, validate_results_file,
+};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// The schema published in the repository / 仓库中发布的模式
+const PUBLISHED_SCHEMA: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/schemas/results-v2.schema.json"
+);
+
+fn case() -> TestCase {
+    TestCase {
+        name: "full".to_string(),
+        features: "feature_a".to_string(),
+        allow_failure: vec![
+            AllowFailure::from("windows"),
+            AllowFailure {
+                os: "macos".to_string(),
+                until: chrono::NaiveDate::from_ymd_opt(2026, 1, 1),
+                issue: Some("#1".to_string()),
+            },
+        ],
+        ulimits: Ulimits {
+            nofile: Some(Limit::Value(1024)),
+            core: Some(Limit::Unlimited),
+            stack: None,
+        },
+        fixtures: vec![Fixture {
+            from: "data/in.bin".into(),
+            to: "in.bin".into(),
+            mode: FixtureMode::Symlink,
+        }],
+        ..Default::default()
+    }
+}
+
+fn results() -> Vec<TestResult> {
+    vec![
+        TestResult::Passed {
+            case: case(),
+            output: "ok".to_string(),
+            duration: Duration::from_millis(1500),
+            retries: 1,
+            attachments: vec!["target/out.log".into()],
+            test_timings: vec![TestTiming {
+                name: "tests::it_works".to_string(),
+                duration: Duration::from_millis(3),
+            }],
+            annotations: BTreeMap::from([("seed".to_string(), "42".to_string())]),
+        },
+        TestResult::Failed {
+            case: case(),
+            output: "boom".to_string(),
+            reason: FailureReason::Timeout,
+            duration: Duration::from_secs(60),
+            attachments: Vec::new(),
+            test_timings: Vec::new(),
+            annotations: BTreeMap::new(),
+        },
+        TestResult::Built {
+            case: case(),
+            output: String::new(),
+            duration: Duration::from_secs(2),
+        },
+        TestResult::skipped(),
+    ]
+}
+
+fn metadata() -> RunMetadata {
+    RunMetadata {
+        notes: vec!["Selected 4 of 5 cases with --select.".to_string()],
+        anomalies: vec![DurationAnomaly {
+            case: "full".to_string(),
+            expected_secs: 10,
+            actual_secs: 60.0,
+            ratio: 6.0,
+        }],
+        case_costs: BTreeMap::from([(
+            "full".to_string(),
+            CaseCost {
+                build_duration: Some(Duration::from_secs(5)),
+                target_bytes: 1 << 20,
+            },
+        )]),
+        failure_fingerprints: vec![FailureFingerprint {
+            case: "full".to_string(),
+            id: "0123456789abcdef".to_string(),
+            reason: FailureReason::Timeout,
+            fingerprint: "abc".to_string(),
+            consecutive_runs: 2,
+        }],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod schema_tests {
+    use super::*;
+
+    #[test]
+    fn test_the_published_schema_is_up_to_date() {
+        // Regenerate it with `matrix-runner schema -o schemas/results-v2.schema.json`;
+        // a change that breaks consumers also raises `SCHEMA_VERSION`.
+        // 使用 `matrix-runner schema -o schemas/results-v2.schema.json` 重新生成；
+        // 破坏使用者的更改还需要提高 `SCHEMA_VERSION`。
+        let published: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(PUBLISHED_SCHEMA).unwrap()).unwrap();
+        assert_eq!(published, results_schema());
+        assert_eq!(
+            published["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn test_written_results_match_the_schema() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        generate_json_report(&results(), &metadata(), &path).unwrap();
+        assert_eq!(validate_results_file(&path).unwrap(), Vec::new());
+
+        generate_json_report(&[], &RunMetadata::default(), &path).unwrap();
+        assert_eq!(validate_results_file(&path).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn test_violations_point_at_the_offending_value() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        generate_json_report(&results(), &metadata(), &path).unwrap();
+        let mut document: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        document["schema_version"] = serde_json::json!(SCHEMA_VERSION + 1);
+        document["results"][0]["Passed"]["duration"] = serde_json::json!("1.5s");
+
+        let pointers: Vec<String> = validate_results(&document)
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect();
+        assert!(
+            pointers.contains(&"/schema_version".to_string()),
+            "{pointers:?}"
+        );
+        assert!(
+            pointers
+                .iter()
+                .any(|pointer| pointer.starts_with("/results/0")),
+            "{pointers:?}"
+        );
+    }
+}
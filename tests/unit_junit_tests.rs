@@ -0,0 +1,143 @@
+//! # JUnit Module Unit Tests / JUnit 模块单元测试
+//!
+//! This module contains unit tests for the `junit.rs` module,
+//! testing how results are rendered as JUnit XML.
+//!
+//! 此模块包含 `junit.rs` 模块的单元测试，
+//! 测试结果如何被渲染为 JUnit XML。
+
+mod common;
+use crate::common::{failed_with, passed};
+use chrono::NaiveDate;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::models::{FailureReason, RunMetadata, TestResult, TestTiming};
+use matrix_runner::reporting::junit::render_junit_report;
+use std::time::Duration;
+
+/// Helper function to create a case / 创建用例的辅助函数
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        features: "serde".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Helper function to create a failed result / 创建失败结果的辅助函数
+fn failed(name: &str, output: &str, reason: FailureReason) -> TestResult {
+    failed_with(case(name), reason, output, Duration::from_millis(2500))
+}
+
+/// Renders the results of a run on this platform / 渲染在本平台上运行的结果
+fn render(results: &[TestResult]) -> String {
+    let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+    render_junit_report(
+        results,
+        &RunMetadata::default(),
+        &RunContext::new("en"),
+        today,
+    )
+}
+
+#[cfg(test)]
+mod junit_tests {
+    use super::*;
+
+    #[test]
+    fn test_every_libtest_test_becomes_a_testcase() {
+        let output = "\
+running 3 tests
+test tests::adds ... ok
+test tests::slow ... ignored
+test tests::divides ... FAILED
+
+failures:
+
+---- tests::divides stdout ----
+thread 'tests::divides' panicked at src/lib.rs:9:9:
+attempt to divide by zero
+
+failures:
+    tests::divides
+";
+        let mut passing = passed(case("passing"));
+        if let TestResult::Passed {
+            output,
+            test_timings,
+            ..
+        } = &mut passing
+        {
+            output.push_str("test tests::adds ... ok\n");
+            test_timings.push(TestTiming {
+                name: "tests::adds".to_string(),
+                duration: Duration::from_millis(250),
+            });
+        }
+        let results = vec![
+            passing,
+            failed("failing", output, FailureReason::TestFailed),
+        ];
+        let xml = render(&results);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<testsuites name=\"matrix-runner\" tests=\"4\" failures=\"1\" errors=\"0\" skipped=\"1\" time=\"3.500\">"));
+        assert!(xml.contains("<testsuite name=\"passing\" tests=\"1\" failures=\"0\""));
+        assert!(
+            xml.contains("<testcase classname=\"passing\" name=\"tests::adds\" time=\"0.250\"/>")
+        );
+        assert!(xml.contains("<property name=\"features\" value=\"serde\"/>"));
+        assert!(xml.contains("<testcase classname=\"failing\" name=\"tests::slow\">\n      <skipped message=\"ignored\"/>"));
+        assert!(xml.contains(
+            "<failure type=\"TestFailed\" message=\"test failed\">thread &apos;tests::divides&apos; panicked at src/lib.rs:9:9:\nattempt to divide by zero</failure>"
+        ));
+    }
+
+    #[test]
+    fn test_failure_without_failed_tests_is_reported_for_the_case() {
+        let results = vec![failed(
+            "compile",
+            "error: \u{1b}[31mbroken\u{1b}[0m <here>",
+            FailureReason::Build,
+        )];
+        let xml = render(&results);
+
+        assert!(xml.contains("<testcase classname=\"compile\" name=\"compile\" time=\"2.500\">"));
+        assert!(xml.contains(
+            "<failure type=\"Build\" message=\"Build\">error: [31mbroken[0m &lt;here&gt;</failure>"
+        ));
+    }
+
+    #[test]
+    fn test_allowed_failures_and_built_cases_are_skipped() {
+        let mut allowed = case("allowed");
+        allowed.allow_timeout = true;
+        let results = vec![
+            failed_with(allowed, FailureReason::Timeout, "", Duration::from_secs(1)),
+            TestResult::Built {
+                case: case("built"),
+                output: String::new(),
+                duration: Duration::from_secs(1),
+            },
+            TestResult::skipped(),
+        ];
+        let xml = render(&results);
+
+        assert!(xml.contains("tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"2\""));
+        assert!(xml.contains("<skipped message=\"allowed failure (Timeout)\"/>"));
+        assert!(xml.contains("<skipped message=\"built only, tests not run\"/>"));
+        assert!(!xml.contains("<failure"));
+    }
+
+    #[test]
+    fn test_cases_left_out_are_skipped_with_the_reason() {
+        let results = vec![TestResult::skipped_case(
+            case("network"),
+            "Requires network access",
+        )];
+        let xml = render(&results);
+
+        assert!(xml.contains("tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert!(xml.contains("<testcase classname=\"network\" name=\"network\">\n      <skipped message=\"Requires network access\"/>"));
+    }
+}
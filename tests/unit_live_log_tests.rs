@@ -0,0 +1,82 @@
+//! # Live Log Module Unit Tests / 实时日志模块单元测试
+//!
+//! This module contains unit tests for the `live_log.rs` module,
+//! testing the log paths, following a growing log and the capture of command output into it.
+//!
+//! 此模块包含 `live_log.rs` 模块的单元测试，
+//! 测试日志路径、跟踪不断增长的日志以及将命令输出捕获到其中。
+
+use matrix_runner::core::live_log::{self, LogFollower, live_log_path};
+use matrix_runner::infra::command::{spawn_and_capture, with_live_log};
+use matrix_runner::infra::fs::case_dir_name;
+use std::fs;
+use std::io::Write;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod live_log_tests {
+    use super::*;
+
+    #[test]
+    fn test_live_log_path_is_a_single_file_per_case() {
+        let dir = tempdir().unwrap();
+        let path = live_log_path(dir.path(), "linux / nightly");
+        assert_eq!(
+            path,
+            dir.path()
+                .join("live")
+                .join(format!("{}.log", case_dir_name("linux / nightly")))
+        );
+        assert_eq!(path.parent().unwrap(), dir.path().join("live"));
+    }
+
+    #[test]
+    fn test_follower_reads_only_new_content() {
+        let dir = tempdir().unwrap();
+        let mut follower = LogFollower::new(live_log_path(dir.path(), "case"));
+        assert!(!follower.exists());
+        assert_eq!(follower.read_new().unwrap(), "");
+
+        let mut file = live_log::create(dir.path(), "case").unwrap();
+        assert!(follower.exists());
+        file.write_all(b"first\n").unwrap();
+        assert_eq!(follower.read_new().unwrap(), "first\n");
+        assert_eq!(follower.read_new().unwrap(), "");
+        file.write_all(b"second\n").unwrap();
+        assert_eq!(follower.read_new().unwrap(), "second\n");
+
+        // A log created again for the same case is read from its beginning.
+        // 为同一用例重新创建的日志会从头读取。
+        drop(file);
+        let mut file = live_log::create(dir.path(), "case").unwrap();
+        file.write_all(b"again\n").unwrap();
+        assert_eq!(follower.read_new().unwrap(), "again\n");
+    }
+
+    #[test]
+    fn test_clear_removes_the_logs_of_the_previous_run() {
+        let dir = tempdir().unwrap();
+        live_log::clear(dir.path()).unwrap();
+        live_log::create(dir.path(), "case").unwrap();
+        live_log::clear(dir.path()).unwrap();
+        assert!(!dir.path().join("live").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_captured_output_is_appended_to_the_live_log() {
+        let dir = tempdir().unwrap();
+        let file = live_log::create(dir.path(), "case").unwrap();
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2"]);
+
+        let (status, output) = with_live_log(Some(file), spawn_and_capture(cmd)).await;
+        assert!(status.unwrap().success());
+
+        let log = fs::read_to_string(live_log_path(dir.path(), "case")).unwrap();
+        let mut lines: Vec<&str> = log.lines().collect();
+        lines.sort();
+        assert_eq!(lines, ["err", "out"]);
+        assert!(output.contains("out") && output.contains("err"));
+    }
+}
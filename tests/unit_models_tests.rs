@@ -6,9 +6,13 @@
 //! 此模块包含 `models.rs` 模块的全面单元测试，
 //! 测试各种数据结构及其行为。
 
-use matrix_runner::core::config::TestCase;
+mod common;
+use crate::common::failed_with;
+use chrono::NaiveDate;
+use matrix_runner::core::config::{AllowFailure, TestCase};
+use matrix_runner::core::context::RunContext;
 use matrix_runner::core::models::{
-    CargoDiagnostic, CargoMessage, CargoTarget, FailureReason, TestResult,
+    CargoDiagnostic, CargoMessage, CargoReason, CargoTarget, DebugInfo, FailureReason, TestResult,
 };
 use std::time::Duration;
 
@@ -23,9 +27,20 @@ fn create_test_case(name: &str) -> TestCase {
         arch: vec![],
         retries: None,
         timeout_secs: None,
+        ..Default::default()
     }
 }
 
+/// The platform results are judged on / 判断结果所依据的平台
+fn context() -> RunContext {
+    RunContext::new("en")
+}
+
+/// The day results are judged on / 判断结果所依据的日期
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+}
+
 #[cfg(test)]
 mod test_result_tests {
     use super::*;
@@ -38,6 +53,9 @@ mod test_result_tests {
             output: "Test passed successfully".to_string(),
             duration: Duration::from_secs(1),
             retries: 1,
+            attachments: vec![],
+            test_timings: vec![],
+            annotations: Default::default(),
         };
 
         match &result {
@@ -52,18 +70,18 @@ mod test_result_tests {
             _ => panic!("Expected Passed variant"),
         }
 
-        assert!(!result.is_unexpected_failure());
+        assert!(!result.is_unexpected_failure(&context(), today()));
     }
 
     #[test]
     fn test_test_result_failed_unexpected() {
         let case = create_test_case("failed-test");
-        let result = TestResult::Failed {
-            case: case.clone(),
-            output: "Test failed".to_string(),
-            reason: FailureReason::TestFailed,
-            duration: Duration::from_secs(1),
-        };
+        let result = failed_with(
+            case.clone(),
+            FailureReason::TestFailed,
+            "Test failed",
+            Duration::from_secs(1),
+        );
 
         match &result {
             TestResult::Failed {
@@ -80,37 +98,153 @@ mod test_result_tests {
         }
 
         // Should be unexpected failure since allow_failure is empty
-        assert!(result.is_unexpected_failure());
+        assert!(result.is_unexpected_failure(&context(), today()));
     }
 
     #[test]
     fn test_test_result_failed_allowed() {
         let mut case = create_test_case("allowed-failure-test");
-        case.allow_failure = vec![std::env::consts::OS.to_string()];
+        case.allow_failure = vec![std::env::consts::OS.into()];
+
+        let result = failed_with(
+            case.clone(),
+            FailureReason::Build,
+            "Test failed but allowed",
+            Duration::from_secs(1),
+        );
+
+        // Should not be unexpected failure since current OS is in allow_failure list
+        assert!(!result.is_unexpected_failure(&context(), today()));
+    }
+
+    #[test]
+    fn test_allowed_failure_is_judged_on_the_given_platform_and_day() {
+        let mut case = create_test_case("windows-only");
+        case.allow_failure = vec![AllowFailure {
+            os: "windows".to_string(),
+            until: NaiveDate::from_ymd_opt(2025, 9, 1),
+            issue: None,
+        }];
+        let result = failed_with(case, FailureReason::TestFailed, "", Duration::from_secs(1));
+        let windows = RunContext {
+            os: "windows".to_string(),
+            ..context()
+        };
+        let linux = RunContext {
+            os: "linux".to_string(),
+            ..context()
+        };
+        let expired = NaiveDate::from_ymd_opt(2025, 9, 2).unwrap();
+
+        assert!(result.is_allowed_failure(&windows, today()));
+        assert!(!result.is_allowed_failure(&linux, today()));
+        assert!(!result.is_allowed_failure(&windows, expired));
+        assert_eq!(result.get_status_class(&windows, today()), "status-Allowed-Failure");
+        assert_eq!(result.get_status_class(&linux, today()), "status-Failed");
+    }
+
+    #[test]
+    fn test_test_result_timeout_allowed_by_allow_timeout() {
+        let mut case = create_test_case("slow-test");
+        case.allow_timeout = true;
+
+        let timeout = failed_with(
+            case.clone(),
+            FailureReason::Timeout,
+            "Timed out",
+            Duration::from_secs(60),
+        );
+        assert!(timeout.is_allowed_failure(&context(), today()));
+        assert!(!timeout.is_unexpected_failure(&context(), today()));
+
+        // Only timeouts are allowed; other failures of the case still count
+        let failed = failed_with(
+            case,
+            FailureReason::TestFailed,
+            "Test failed",
+            Duration::from_secs(1),
+        );
+        assert!(!failed.is_allowed_failure(&context(), today()));
+        assert!(failed.is_unexpected_failure(&context(), today()));
+    }
+
+    #[test]
+    fn test_test_result_timeout_unexpected_by_default() {
+        let result = failed_with(
+            create_test_case("slow-test"),
+            FailureReason::Timeout,
+            "Timed out",
+            Duration::from_secs(60),
+        );
+        assert!(result.is_unexpected_failure(&context(), today()));
+    }
+
+    #[test]
+    fn test_test_result_stalled_is_not_a_timeout() {
+        let mut case = create_test_case("hung-test");
+        case.allow_timeout = true;
+        let result = failed_with(
+            case,
+            FailureReason::Stalled,
+            "No output for 30s,
+            the process was killed",
+            Duration::from_secs(30),
+        );
+        assert!(!result.is_timeout());
+        assert!(result.is_unexpected_failure(&context(), today()));
+        assert_eq!(result.status_key(), "failed");
+        assert_eq!(result.get_status_class(&context(), today()), "status-Timeout");
+    }
 
+    #[test]
+    fn test_test_result_attachments() {
+        let case = create_test_case("attachment-test");
         let result = TestResult::Failed {
-            case: case.clone(),
-            output: "Test failed but allowed".to_string(),
-            reason: FailureReason::Build,
+            case,
+            output: "Test failed".to_string(),
+            reason: FailureReason::CustomCommand,
             duration: Duration::from_secs(1),
+            attachments: vec![std::path::PathBuf::from("screenshot.png")],
+            test_timings: vec![],
+            annotations: Default::default(),
         };
 
-        // Should not be unexpected failure since current OS is in allow_failure list
-        assert!(!result.is_unexpected_failure());
+        assert_eq!(
+            result.get_attachments(),
+            &[std::path::PathBuf::from("screenshot.png")]
+        );
+        assert!(TestResult::skipped().get_attachments().is_empty());
     }
 
     #[test]
     fn test_test_result_skipped() {
-        let result = TestResult::Skipped;
+        let result = TestResult::skipped();
 
         match &result {
-            TestResult::Skipped => {
+            TestResult::Skipped { .. } => {
                 // Expected
             }
             _ => panic!("Expected Skipped variant"),
         }
 
-        assert!(!result.is_unexpected_failure());
+        assert!(!result.is_unexpected_failure(&context(), today()));
+    }
+
+    #[test]
+    fn test_test_result_built() {
+        let case = create_test_case("built-test");
+        let result = TestResult::Built {
+            case,
+            output: "Built".to_string(),
+            duration: Duration::from_secs(3),
+        };
+
+        assert!(!result.is_failure());
+        assert!(!result.is_unexpected_failure(&context(), today()));
+        assert_eq!(result.status_key(), "built");
+        assert_eq!(result.get_status_class(&context(), today()), "status-Built");
+        assert_eq!(result.case_name(), "built-test");
+        assert_eq!(result.get_duration(), Some(Duration::from_secs(3)));
     }
 
     #[test]
@@ -121,6 +255,9 @@ mod test_result_tests {
             output: "Original output".to_string(),
             duration: Duration::from_secs(5),
             retries: 2,
+            attachments: vec![],
+            test_timings: vec![],
+            annotations: Default::default(),
         };
 
         let cloned = original.clone();
@@ -161,6 +298,7 @@ mod failure_reason_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_failure_reason_clone() {
         let original = FailureReason::Build;
         let cloned = original.clone();
@@ -212,6 +350,7 @@ mod cargo_diagnostic_tests {
             level: "error".to_string(),
             message: "test message".to_string(),
             rendered: Some("rendered message".to_string()),
+            code: None,
         };
 
         let cloned = original.clone();
@@ -239,7 +378,7 @@ mod cargo_message_tests {
 
         let message: CargoMessage = serde_json::from_str(json).unwrap();
 
-        assert_eq!(message.reason, "compiler-message");
+        assert_eq!(message.reason, CargoReason::CompilerMessage);
         assert!(message.message.is_some());
         assert!(message.target.is_none());
         assert!(message.executable.is_none());
@@ -263,7 +402,7 @@ mod cargo_message_tests {
 
         let message: CargoMessage = serde_json::from_str(json).unwrap();
 
-        assert_eq!(message.reason, "compiler-artifact");
+        assert_eq!(message.reason, CargoReason::CompilerArtifact);
         assert!(message.target.is_some());
         assert!(message.executable.is_some());
         assert!(message.message.is_none());
@@ -277,6 +416,115 @@ mod cargo_message_tests {
     }
 }
 
+#[cfg(test)]
+mod cargo_version_compat_tests {
+    use super::*;
+    use std::path::Path;
+
+    /// `cargo test --no-run --message-format=json` from cargo 1.40: no `target.test`,
+    /// no `build-finished`.
+    const CARGO_1_40: &str = r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0 (path+file:///work/demo)","target":{"kind":["lib"],"crate_types":["lib"],"name":"demo","src_path":"/work/demo/src/lib.rs","edition":"2018","doctest":true},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":true},"features":[],"filenames":["/work/demo/target/debug/deps/demo-1a2b3c"],"executable":"/work/demo/target/debug/deps/demo-1a2b3c","fresh":false}
+{"reason":"compiler-message","package_id":"demo 0.1.0 (path+file:///work/demo)","target":{"kind":["lib"],"crate_types":["lib"],"name":"demo","src_path":"/work/demo/src/lib.rs","edition":"2018","doctest":true},"message":{"rendered":null,"children":[],"code":null,"level":"warning","message":"unused variable: `x`","spans":[]}}"#;
+
+    /// The same build from cargo 1.60: `target.test` and `build-finished` are present.
+    const CARGO_1_60: &str = r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0 (path+file:///work/demo)","manifest_path":"/work/demo/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"demo","src_path":"/work/demo/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"profile":{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":true},"features":[],"filenames":["/work/demo/target/debug/deps/demo-4d5e6f"],"executable":"/work/demo/target/debug/deps/demo-4d5e6f","fresh":false}
+{"reason":"build-finished","success":true}"#;
+
+    /// The same build from cargo 1.80: new package id format, named debug info levels
+    /// and messages this runner does not know.
+    const CARGO_1_80: &str = r#"{"reason":"build-script-executed","package_id":"path+file:///work/demo#0.1.0","linked_libs":[],"linked_paths":[],"cfgs":[],"env":[],"out_dir":"/work/demo/target/debug/build/demo-0f0f0f/out"}
+{"reason":"compiler-artifact","package_id":"path+file:///work/demo#0.1.0","manifest_path":"/work/demo/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"demo","src_path":"/work/demo/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"profile":{"opt_level":"1","debuginfo":"line-tables-only","debug_assertions":true,"overflow_checks":true,"test":true},"features":["default"],"filenames":["/work/demo/target/debug/deps/demo-7a8b9c"],"executable":"/work/demo/target/debug/deps/demo-7a8b9c","fresh":true}
+{"reason":"timing-info","package_id":"path+file:///work/demo#0.1.0","target":{"kind":["lib"],"name":"demo"},"mode":"test","duration":0.5,"rmeta_time":0.1}
+{"reason":"build-finished","success":false}"#;
+
+    fn parse_all(output: &str) -> Vec<CargoMessage> {
+        output.lines().filter_map(CargoMessage::parse).collect()
+    }
+
+    fn find_test_executable(output: &str) -> Option<std::path::PathBuf> {
+        parse_all(output)
+            .iter()
+            .find_map(|message| message.test_executable().map(Path::to_path_buf))
+    }
+
+    #[test]
+    fn test_every_line_of_each_version_parses() {
+        assert_eq!(parse_all(CARGO_1_40).len(), 2);
+        assert_eq!(parse_all(CARGO_1_60).len(), 2);
+        assert_eq!(parse_all(CARGO_1_80).len(), 4);
+    }
+
+    #[test]
+    fn test_test_executable_found_in_each_version() {
+        assert_eq!(
+            find_test_executable(CARGO_1_40).unwrap(),
+            Path::new("/work/demo/target/debug/deps/demo-1a2b3c")
+        );
+        assert_eq!(
+            find_test_executable(CARGO_1_60).unwrap(),
+            Path::new("/work/demo/target/debug/deps/demo-4d5e6f")
+        );
+        assert_eq!(
+            find_test_executable(CARGO_1_80).unwrap(),
+            Path::new("/work/demo/target/debug/deps/demo-7a8b9c")
+        );
+    }
+
+    #[test]
+    fn test_missing_target_test_falls_back_to_profile() {
+        let message = CargoMessage::parse(CARGO_1_40.lines().next().unwrap()).unwrap();
+        assert!(!message.target.as_ref().unwrap().test);
+        assert!(message.profile.as_ref().unwrap().test);
+        assert!(message.test_executable().is_some());
+    }
+
+    #[test]
+    fn test_null_rendered_diagnostic() {
+        let message = CargoMessage::parse(CARGO_1_40.lines().nth(1).unwrap()).unwrap();
+        let diagnostic = message.message.unwrap();
+        assert_eq!(diagnostic.level, "warning");
+        assert!(diagnostic.rendered.is_none());
+    }
+
+    #[test]
+    fn test_build_finished() {
+        let messages = parse_all(CARGO_1_80);
+        let finished = messages.last().unwrap();
+        assert_eq!(finished.reason, CargoReason::BuildFinished);
+        assert_eq!(finished.success, Some(false));
+    }
+
+    #[test]
+    fn test_debuginfo_as_number_or_name() {
+        let profiles: Vec<_> = [CARGO_1_60, CARGO_1_80]
+            .iter()
+            .flat_map(|output| parse_all(output))
+            .filter_map(|message| message.profile)
+            .collect();
+        assert_eq!(profiles[0].debuginfo, Some(DebugInfo::Level(2)));
+        assert_eq!(
+            profiles[1].debuginfo,
+            Some(DebugInfo::Named("line-tables-only".to_string()))
+        );
+        assert_eq!(profiles[1].opt_level, "1");
+    }
+
+    #[test]
+    fn test_unknown_reason_is_tolerated() {
+        let messages = parse_all(CARGO_1_80);
+        assert_eq!(messages[0].reason, CargoReason::BuildScriptExecuted);
+        assert_eq!(messages[2].reason, CargoReason::Unknown);
+        assert!(messages[2].test_executable().is_none());
+    }
+
+    #[test]
+    fn test_non_json_lines_are_skipped() {
+        assert!(CargoMessage::parse("   Compiling demo v0.1.0 (/work/demo)").is_none());
+        assert!(CargoMessage::parse("").is_none());
+        assert!(CargoMessage::parse(r#"{"no_reason":true}"#).is_none());
+    }
+}
+
 #[cfg(test)]
 mod cargo_target_tests {
     use super::*;
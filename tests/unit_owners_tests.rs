@@ -0,0 +1,136 @@
+//! # Owners Module Unit Tests / 归属模块单元测试
+//!
+//! This module contains unit tests for the `owners.rs` module,
+//! testing case name globs and how failures are grouped by owner.
+//!
+//! 此模块包含 `owners.rs` 模块的单元测试，
+//! 测试用例名称通配符以及失败如何按负责人分组。
+
+mod common;
+use crate::common::{failed, passed};
+use chrono::NaiveDate;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::owners::{
+    OwnerFailures, glob_matches, group_failures_by_owner, owners_of,
+};
+use std::collections::BTreeMap;
+
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn owners(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries
+        .iter()
+        .map(|(pattern, owner)| (pattern.to_string(), owner.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod glob_tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("net-*", "net-tls"));
+        assert!(glob_matches("net-*", "net-"));
+        assert!(glob_matches("*-wasm", "core-wasm"));
+        assert!(glob_matches("*tls*", "net-tls-ring"));
+        assert!(glob_matches("db-?", "db-1"));
+        assert!(glob_matches("exact", "exact"));
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("a*b*c", "a-x-b-y-c"));
+    }
+
+    #[test]
+    fn test_glob_does_not_match() {
+        assert!(!glob_matches("net-*", "core-net"));
+        assert!(!glob_matches("db-?", "db-12"));
+        assert!(!glob_matches("exact", "exactly"));
+        assert!(!glob_matches("a*b*c", "a-x-c-y-b"));
+    }
+
+    #[test]
+    fn test_owners_of_collects_every_match() {
+        let owners = owners(&[
+            ("net-*", "@net-team"),
+            ("*-tls", "sec@example.com"),
+            ("db-*", "@db"),
+        ]);
+        assert_eq!(
+            owners_of(&owners, "net-tls"),
+            vec!["@net-team", "sec@example.com"]
+        );
+        assert_eq!(owners_of(&owners, "db-pg"), vec!["@db"]);
+        assert!(owners_of(&owners, "core").is_empty());
+    }
+
+    #[test]
+    fn test_owners_of_a_package_case_match_its_bare_name() {
+        let owners = owners(&[("net-*", "@net-team"), ("client::*", "@client")]);
+        assert_eq!(
+            owners_of(&owners, "client::net-tls"),
+            vec!["@client", "@net-team"]
+        );
+    }
+}
+
+#[cfg(test)]
+mod grouping_tests {
+    use super::*;
+
+    #[test]
+    fn test_group_failures_by_owner() {
+        let owners = owners(&[("net-*", "@net-team"), ("db-*", "@db")]);
+        let allowed = TestCase {
+            allow_failure: vec![std::env::consts::OS.into()],
+            ..case("db-flaky")
+        };
+        let results = vec![
+            failed(case("net-tls")),
+            failed(case("db-pg")),
+            passed(case("net-http")),
+            failed(allowed),
+            failed(case("core")),
+            failed(case("net-dns")),
+        ];
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let groups = group_failures_by_owner(&results, &owners, &RunContext::new("en"), today);
+        assert_eq!(
+            groups,
+            vec![
+                OwnerFailures {
+                    owner: Some("@db".to_string()),
+                    cases: vec!["db-pg".to_string()],
+                },
+                OwnerFailures {
+                    owner: Some("@net-team".to_string()),
+                    cases: vec!["net-tls".to_string(), "net-dns".to_string()],
+                },
+                OwnerFailures {
+                    owner: None,
+                    cases: vec!["core".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_groups_without_owners_or_failures() {
+        let context = RunContext::new("en");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let results = vec![failed(case("net-tls"))];
+        assert!(group_failures_by_owner(&results, &BTreeMap::new(), &context, today).is_empty());
+
+        let owners = owners(&[("net-*", "@net-team")]);
+        assert!(
+            group_failures_by_owner(&[passed(case("net-tls"))], &owners, &context, today)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! # Partial Results Module Unit Tests / 部分结果模块单元测试
+//!
+//! This module contains unit tests for the `partial.rs` module,
+//! testing that a panic of the thread running a run writes the results it finished so far.
+//!
+//! 此模块包含 `partial.rs` 模块的单元测试，
+//! 测试运行所在的线程 panic 时是否会写出该运行到目前为止已完成的结果。
+
+mod common;
+use chrono::NaiveDate;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::models::{RunMetadata, TestResult};
+use matrix_runner::reporting::{json::RESULTS_FILE, partial::PartialRun};
+use std::fs;
+use tempfile::tempdir;
+
+/// Helper function to create a passed result / 创建通过结果的辅助函数
+fn passed(name: &str) -> TestResult {
+    common::passed(TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod partial_tests {
+    use super::*;
+
+    /// Starts a run on its own thread, which then panics mid-run the way the runner would
+    /// 在独立线程上启动一次运行，随后该线程以运行器的方式在运行中途 panic
+    fn panic_after(start: impl FnOnce() -> PartialRun + Send + 'static) {
+        let handle = std::thread::spawn(move || {
+            let _run = start();
+            panic!("runner bug");
+        });
+        assert!(handle.join().is_err());
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_panic_writes_partial_results() {
+        let state_dir = tempdir().unwrap();
+        let path = state_dir.path().to_path_buf();
+        panic_after(move || {
+            let run = PartialRun::default();
+            run.begin(
+                RunMetadata::default(),
+                path,
+                &RunContext::new("en"),
+                today(),
+            );
+            run.record(&passed("finished-case"));
+            run
+        });
+
+        let content = fs::read_to_string(state_dir.path().join(RESULTS_FILE)).unwrap();
+        let results: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(results["results"].as_array().unwrap().len(), 1);
+        assert_eq!(results["metadata"]["notes"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_finished_run_writes_nothing() {
+        let state_dir = tempdir().unwrap();
+        let path = state_dir.path().to_path_buf();
+        panic_after(move || {
+            let run = PartialRun::default();
+            run.begin(
+                RunMetadata::default(),
+                path,
+                &RunContext::new("en"),
+                today(),
+            );
+            run.record(&passed("finished-case"));
+            run.finish();
+            run
+        });
+
+        assert!(!state_dir.path().join(RESULTS_FILE).exists());
+    }
+
+    #[test]
+    fn test_panic_only_reports_its_own_run() {
+        let other_dir = tempdir().unwrap();
+        let other = PartialRun::default();
+        other.begin(
+            RunMetadata::default(),
+            other_dir.path().to_path_buf(),
+            &RunContext::new("en"),
+            today(),
+        );
+        other.record(&passed("other-case"));
+
+        let state_dir = tempdir().unwrap();
+        let path = state_dir.path().to_path_buf();
+        panic_after(move || {
+            let run = PartialRun::default();
+            run.begin(
+                RunMetadata::default(),
+                path,
+                &RunContext::new("en"),
+                today(),
+            );
+            run.record(&passed("first-case"));
+            run.record(&passed("second-case"));
+            run
+        });
+
+        let content = fs::read_to_string(state_dir.path().join(RESULTS_FILE)).unwrap();
+        let results: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(results["results"].as_array().unwrap().len(), 2);
+        assert!(!other_dir.path().join(RESULTS_FILE).exists());
+        other.finish();
+    }
+}
@@ -0,0 +1,178 @@
+//! # Planner Module Unit Tests / Planner 模块单元测试
+//!
+//! This module contains unit tests for the `planner.rs` module,
+//! testing that cases are ordered after the cases named in their `after` hints,
+//! that cases are planned for the platform of the run context, and that the axes of
+//! `[matrix]` expand into cases.
+//!
+//! 此模块包含 `planner.rs` 模块的单元测试，
+//! 测试用例是否被排在其 `after` 提示所指定的用例之后、用例是否针对运行上下文的平台进行计划，
+//! 以及 `[matrix]` 的维度是否被展开为用例。
+
+use chrono::NaiveDate;
+use matrix_runner::core::config::{AllowFailure, TestCase, TestMatrix};
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::planner::{AxisFilter, MatrixAxes, order_by_after, plan_execution};
+
+/// Helper function to create a case ordered after others / 创建排在其他用例之后的用例的辅助函数
+fn create_test_case(name: &str, after: &[&str]) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        after: after.iter().map(|name| name.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// The day the cases are planned on / 计划用例的日期
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+}
+
+fn names(cases: &[TestCase]) -> Vec<&str> {
+    cases.iter().map(|case| case.name.as_str()).collect()
+}
+
+#[cfg(test)]
+mod planner_tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_runs_warmup_case_first() {
+        let matrix = TestMatrix {
+            cases: vec![
+                create_test_case("a", &["z-warmup"]),
+                create_test_case("b", &[]),
+                create_test_case("z-warmup", &[]),
+            ],
+            ..Default::default()
+        };
+        let plan = plan_execution(matrix, None, None, &RunContext::new("en"), today()).unwrap();
+        assert_eq!(names(&plan.cases_to_run), vec!["z-warmup", "a", "b"]);
+    }
+
+    #[test]
+    fn test_plan_uses_the_platform_of_the_context() {
+        let matrix = TestMatrix {
+            cases: vec![
+                TestCase {
+                    arch: vec!["riscv64".to_string()],
+                    ..create_test_case("riscv-only", &[])
+                },
+                TestCase {
+                    allow_failure: vec![AllowFailure {
+                        os: "freebsd".to_string(),
+                        until: None,
+                        issue: None,
+                    }],
+                    ..create_test_case("flaky-on-freebsd", &[])
+                },
+                create_test_case("anywhere", &[]),
+            ],
+            ..Default::default()
+        };
+        let context = RunContext {
+            os: "freebsd".to_string(),
+            arch: "riscv64".to_string(),
+            ..RunContext::new("en")
+        };
+        let plan = plan_execution(matrix.clone(), None, None, &context, today()).unwrap();
+        assert_eq!(plan.filtered_arch_count, 0);
+        assert_eq!(plan.flaky_cases_count, 1);
+        // Flaky cases run after the others.
+        // 不稳定的用例在其他用例之后运行。
+        assert_eq!(
+            names(&plan.cases_to_run),
+            vec!["anywhere", "riscv-only", "flaky-on-freebsd"]
+        );
+
+        let context = RunContext {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            ..RunContext::new("en")
+        };
+        let plan = plan_execution(matrix, None, None, &context, today()).unwrap();
+        assert_eq!(plan.filtered_arch_count, 1);
+        assert_eq!(plan.flaky_cases_count, 0);
+        assert_eq!(
+            names(&plan.cases_to_run),
+            vec!["anywhere", "flaky-on-freebsd"]
+        );
+    }
+
+    #[test]
+    fn test_order_ignores_cases_outside_the_run() {
+        let cases = vec![
+            create_test_case("a", &["missing"]),
+            create_test_case("b", &[]),
+        ];
+        assert_eq!(names(&order_by_after(cases)), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_order_tolerates_cycles() {
+        let cases = vec![create_test_case("a", &["b"]), create_test_case("b", &["a"])];
+        assert_eq!(names(&order_by_after(cases)), vec!["b", "a"]);
+    }
+}
+
+#[cfg(test)]
+mod matrix_axes_tests {
+    use super::*;
+
+    #[test]
+    fn test_axes_expand_into_their_cartesian_product() {
+        let axes = MatrixAxes {
+            features: vec!["".to_string(), "a, b".to_string()],
+            no_default_features: vec![false, true],
+            toolchain: vec!["stable".to_string(), "nightly".to_string()],
+            exclude: vec![AxisFilter {
+                features: Some("b,a".to_string()),
+                toolchain: Some("stable".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let cases = axes.expand();
+
+        assert_eq!(
+            names(&cases),
+            [
+                "default-stable",
+                "default-nightly",
+                "no-default-stable",
+                "no-default-nightly",
+                "a+b-nightly",
+                "a+b-no-default-nightly",
+            ]
+        );
+        assert_eq!(cases[4].features, "a, b");
+        assert_eq!(cases[5].toolchain.as_deref(), Some("nightly"));
+        assert!(cases[5].no_default_features);
+        assert_eq!(axes.expand().len(), cases.len());
+    }
+
+    #[test]
+    fn test_package_cases_carry_their_package_in_the_name() {
+        let axes = MatrixAxes {
+            package: vec!["alpha".to_string(), "beta".to_string()],
+            target: vec!["wasm32-unknown-unknown".to_string()],
+            ..Default::default()
+        };
+        let cases = axes.expand();
+        assert_eq!(
+            names(&cases),
+            [
+                "alpha::default-wasm32-unknown-unknown",
+                "beta::default-wasm32-unknown-unknown"
+            ]
+        );
+        assert_eq!(cases[1].package.as_deref(), Some("beta"));
+        assert!(
+            MatrixAxes::default()
+                .expand()
+                .iter()
+                .map(|case| &case.name)
+                .eq(["default"].iter())
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! # Plugins Module Unit Tests / 插件模块单元测试
+//!
+//! This module contains unit tests for the `plugins.rs` module,
+//! testing the JSON form of the run events sent to plugins.
+//!
+//! 此模块包含 `plugins.rs` 模块的单元测试，
+//! 测试发送给插件的运行事件的 JSON 形式。
+
+mod common;
+use crate::common::failed_with;
+use chrono::NaiveDate;
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::models::{FailureReason, TestResult};
+use matrix_runner::core::plugins::RunEvent;
+use std::time::Duration;
+
+fn failed(name: &str, allow_failure: bool) -> TestResult {
+    let case = TestCase {
+        name: name.to_string(),
+        allow_failure: if allow_failure {
+            vec![std::env::consts::OS.into()]
+        } else {
+            vec![]
+        },
+        ..Default::default()
+    };
+    failed_with(
+        case,
+        FailureReason::TestFailed,
+        "",
+        Duration::from_millis(1500),
+    )
+}
+
+#[cfg(test)]
+mod run_event_tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_tagged_json_lines() {
+        let event = RunEvent::CaseStarted {
+            case: "unit".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"case_started","case":"unit"}"#
+        );
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let finished = RunEvent::case_finished(
+            "flaky",
+            &failed("flaky", true),
+            &RunContext::new("en"),
+            today,
+        );
+        let json: serde_json::Value = serde_json::to_value(&finished).unwrap();
+        assert_eq!(json["event"], "case_finished");
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["allowed_failure"], true);
+        assert_eq!(json["duration_secs"], 1.5);
+    }
+
+    #[test]
+    fn test_run_finished_counts_results() {
+        let context = RunContext::new("en");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let results = vec![failed("allowed", true), TestResult::skipped()];
+        assert_eq!(
+            RunEvent::run_finished(&results, &context, today),
+            RunEvent::RunFinished {
+                passed: 0,
+                failed: 1,
+                skipped: 1,
+                success: true,
+            }
+        );
+
+        let results = vec![failed("allowed", true), failed("broken", false)];
+        let RunEvent::RunFinished {
+            failed, success, ..
+        } = RunEvent::run_finished(&results, &context, today)
+        else {
+            panic!("expected run_finished");
+        };
+        assert_eq!(failed, 2);
+        assert!(!success);
+    }
+}
@@ -0,0 +1,56 @@
+//! # Port Broker Module Unit Tests / 端口代理模块单元测试
+//!
+//! This module contains unit tests for the `ports.rs` module,
+//! testing that reserved ports are unique, bindable and released when dropped.
+//!
+//! 此模块包含 `ports.rs` 模块的单元测试，
+//! 测试预留的端口是否唯一、可绑定，并在被丢弃时释放。
+
+use matrix_runner::infra::ports::PortBroker;
+use std::collections::BTreeSet;
+use std::net::{Ipv4Addr, TcpListener};
+
+#[cfg(test)]
+mod port_broker_tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_ports_are_unique_across_reservations() {
+        let broker = PortBroker::default();
+        let first = broker.reserve(3).unwrap();
+        let second = broker.clone().reserve(3).unwrap();
+
+        let ports: BTreeSet<u16> = first
+            .ports()
+            .iter()
+            .chain(second.ports())
+            .copied()
+            .collect();
+        assert_eq!(ports.len(), 6);
+    }
+
+    #[test]
+    fn test_reserved_ports_can_be_bound() {
+        let reserved = PortBroker::default().reserve(2).unwrap();
+        for port in reserved.ports() {
+            TcpListener::bind((Ipv4Addr::LOCALHOST, *port)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_ports_are_released_when_dropped() {
+        let broker = PortBroker::default();
+        let reserved = broker.reserve(2).unwrap();
+        let ports = reserved.ports().to_vec();
+        assert!(ports.iter().all(|port| broker.is_reserved(*port)));
+
+        drop(reserved);
+        assert!(ports.iter().all(|port| !broker.is_reserved(*port)));
+    }
+
+    #[test]
+    fn test_reserving_no_ports_is_empty() {
+        let reserved = PortBroker::default().reserve(0).unwrap();
+        assert!(reserved.ports().is_empty());
+    }
+}
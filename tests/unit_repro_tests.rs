@@ -0,0 +1,255 @@
+//! # Repro Module Unit Tests / Repro 模块单元测试
+//!
+//! This module contains unit tests for the `repro.rs` module,
+//! testing the command lines and the reproduction bundles written for failed cases.
+//!
+//! 此模块包含 `repro.rs` 模块的单元测试，
+//! 测试命令行以及为失败用例写出的复现包。
+
+mod common;
+use crate::common::{failed, failed_with, passed};
+use matrix_runner::core::config::{CaseKind, FuzzSmoke, TestCase};
+use matrix_runner::core::execution::command_line;
+use matrix_runner::core::models::{FailureReason, RunMetadata};
+use matrix_runner::infra::fs::case_dir_name;
+use matrix_runner::reporting::repro::write_repro_bundles;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod command_line_tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_case_command() {
+        let case = TestCase {
+            name: "case".to_string(),
+            features: "a,b".to_string(),
+            no_default_features: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "my-crate"),
+            "cargo test -p my-crate --no-default-features --features 'a,b'"
+        );
+    }
+
+    #[test]
+    fn test_case_package_overrides_default() {
+        let case = TestCase {
+            package: Some("member".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(command_line(&case, "root"), "cargo test -p member");
+    }
+
+    #[test]
+    fn test_tarpaulin_case_command() {
+        let case = TestCase {
+            kind: CaseKind::Tarpaulin,
+            features: "a".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "cargo tarpaulin -p root --features a"
+        );
+    }
+
+    #[test]
+    fn test_doc_case_command() {
+        let case = TestCase {
+            kind: CaseKind::Doc,
+            no_default_features: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "RUSTDOCFLAGS='-D rustdoc::broken_intra_doc_links' cargo doc --no-deps -p root --no-default-features"
+        );
+    }
+
+    #[test]
+    fn test_case_env_prefixes_command() {
+        let case = TestCase {
+            env: [
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("APP_MODE".to_string(), "two words".to_string()),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "APP_MODE='two words' RUST_LOG=debug cargo test -p root"
+        );
+    }
+
+    #[test]
+    fn test_doc_case_appends_to_case_rustdocflags() {
+        let case = TestCase {
+            kind: CaseKind::Doc,
+            env: [("RUSTDOCFLAGS".to_string(), "--cfg docsrs".to_string())].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "RUSTDOCFLAGS='--cfg docsrs -D rustdoc::broken_intra_doc_links' cargo doc --no-deps -p root"
+        );
+    }
+
+    #[test]
+    fn test_fuzz_smoke_case_env_prefixes_every_invocation() {
+        let case = TestCase {
+            kind: CaseKind::FuzzSmoke,
+            env: [("ASAN_OPTIONS".to_string(), "detect_leaks=0".to_string())].into(),
+            fuzz: FuzzSmoke {
+                targets: vec!["parse".to_string(), "decode".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "ASAN_OPTIONS='detect_leaks=0' cargo fuzz run parse -- -runs=10000 -max_total_time=30 && ASAN_OPTIONS='detect_leaks=0' cargo fuzz run decode -- -runs=10000 -max_total_time=30"
+        );
+    }
+
+    #[test]
+    fn test_fuzz_smoke_case_command() {
+        let mut case = TestCase {
+            kind: CaseKind::FuzzSmoke,
+            features: "a".to_string(),
+            toolchain: Some("nightly".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "for target in $(cargo +nightly fuzz list); do cargo +nightly fuzz run \"$target\" --features a -- -runs=10000 -max_total_time=30 || exit 1; done"
+        );
+
+        case.fuzz = FuzzSmoke {
+            runs: Some(100),
+            targets: vec!["parse".to_string(), "decode".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "cargo +nightly fuzz run parse --features a -- -runs=100 -max_total_time=30 && cargo +nightly fuzz run decode --features a -- -runs=100 -max_total_time=30"
+        );
+    }
+
+    #[test]
+    fn test_case_toolchain_and_target() {
+        let case = TestCase {
+            toolchain: Some("nightly".to_string()),
+            target: Some("wasm32-unknown-unknown".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "cargo +nightly test -p root --target wasm32-unknown-unknown"
+        );
+    }
+
+    #[test]
+    fn test_case_unstable_flags() {
+        let case = TestCase {
+            toolchain: Some("nightly".to_string()),
+            target: Some("thumbv7em-none-eabihf".to_string()),
+            cargo_unstable_flags: vec!["-Zbuild-std=core,alloc".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&case, "root"),
+            "cargo +nightly test -p root --target thumbv7em-none-eabihf '-Zbuild-std=core,alloc'"
+        );
+    }
+
+    #[test]
+    fn test_custom_command_is_used_verbatim() {
+        let case = TestCase {
+            command: Some("make check".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(command_line(&case, "root"), "make check");
+    }
+}
+
+#[cfg(test)]
+mod write_repro_bundles_tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_written_only_for_failures() {
+        let dir = tempdir().unwrap();
+        let case = TestCase {
+            name: "broken case".to_string(),
+            ..Default::default()
+        };
+        let failed = failed_with(
+            case,
+            FailureReason::TestFailed,
+            "assertion failed",
+            Duration::from_secs(1),
+        );
+
+        let bundles = write_repro_bundles(
+            &[failed, passed(TestCase::default())],
+            &RunMetadata::default(),
+            dir.path(),
+            "my-crate",
+            dir.path(),
+        )
+        .unwrap();
+
+        let case_dir = case_dir_name("broken case");
+        assert_eq!(bundles, vec![dir.path().join(format!("{case_dir}.zip"))]);
+        assert!(bundles[0].exists());
+        let bundle_dir = dir.path().join(case_dir);
+        assert_eq!(
+            std::fs::read_to_string(bundle_dir.join("output.log")).unwrap(),
+            "assertion failed"
+        );
+        let script = std::fs::read_to_string(bundle_dir.join("repro.sh")).unwrap();
+        assert!(script.contains("cargo test -p my-crate"));
+        let case_toml = std::fs::read_to_string(bundle_dir.join("case.toml")).unwrap();
+        assert!(case_toml.contains("name = \"broken case\""));
+        assert!(bundle_dir.join("repro.ps1").exists());
+    }
+
+    #[test]
+    fn test_bundle_sets_case_env_for_each_shell() {
+        let dir = tempdir().unwrap();
+        let case = TestCase {
+            name: "docs".to_string(),
+            kind: CaseKind::Doc,
+            env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+            ..Default::default()
+        };
+
+        write_repro_bundles(
+            &[failed(case)],
+            &RunMetadata::default(),
+            dir.path(),
+            "root",
+            dir.path(),
+        )
+        .unwrap();
+
+        let bundle_dir = dir.path().join("docs");
+        let commands = std::fs::read_to_string(bundle_dir.join("commands.txt")).unwrap();
+        assert!(commands.contains(
+            "RUSTDOCFLAGS='-D rustdoc::broken_intra_doc_links' RUST_LOG=debug cargo doc --no-deps -p root"
+        ));
+        let script = std::fs::read_to_string(bundle_dir.join("repro.sh")).unwrap();
+        assert!(script.contains("export RUSTDOCFLAGS='-D rustdoc::broken_intra_doc_links'\n"));
+        assert!(script.contains("export RUST_LOG=debug\n"));
+        assert!(script.contains("\ncargo doc --no-deps -p root\n"));
+        let ps1 = std::fs::read_to_string(bundle_dir.join("repro.ps1")).unwrap();
+        assert!(ps1.contains("$env:RUSTDOCFLAGS = '-D rustdoc::broken_intra_doc_links'\n"));
+        assert!(ps1.contains("$env:RUST_LOG = 'debug'\n"));
+        assert!(ps1.contains("\ncargo doc --no-deps -p root\n"));
+        assert!(!ps1.contains("RUSTDOCFLAGS='"));
+    }
+}
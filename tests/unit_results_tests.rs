@@ -0,0 +1,227 @@
+//! # Results Module Unit Tests / 结果模块单元测试
+//!
+//! This module contains unit tests for the `results.rs` module,
+//! testing that merging reports does not depend on their order and how conflicts are resolved.
+//!
+//! 此模块包含 `results.rs` 模块的单元测试，
+//! 测试合并报告与其顺序无关，以及冲突如何被解决。
+
+mod common;
+use crate::common::{failed_with, passed_in};
+use chrono::NaiveDate;
+use matrix_runner::core::config::{TestCase, TestMatrix};
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::models::{FailureReason, RunMetadata, TestResult};
+use matrix_runner::core::owners::OwnerFailures;
+use matrix_runner::core::results::{RunReport, load_report, merge};
+use matrix_runner::reporting::json::generate_json_report;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn case(name: &str) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+fn failed(name: &str, secs: u64) -> TestResult {
+    let output = format!("{name} failed");
+    failed_with(
+        case(name),
+        FailureReason::TestFailed,
+        &output,
+        Duration::from_secs(secs),
+    )
+}
+
+fn passed(name: &str, secs: u64) -> TestResult {
+    passed_in(case(name), Duration::from_secs(secs), 1)
+}
+
+fn report(results: Vec<TestResult>, checksums: &[(&str, &str)]) -> RunReport {
+    RunReport {
+        metadata: RunMetadata {
+            artifact_checksums: checksums
+                .iter()
+                .map(|(case, sum)| (case.to_string(), sum.to_string()))
+                .collect(),
+            ..Default::default()
+        },
+        results,
+    }
+}
+
+/// Merges the reports on this platform / 在本平台上合并报告
+fn merge_reports(reports: Vec<RunReport>) -> RunReport {
+    merge(
+        reports,
+        &RunContext::new("en"),
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+    )
+}
+
+fn summary(report: &RunReport) -> Vec<(String, &'static str, Option<Duration>)> {
+    report
+        .results
+        .iter()
+        .map(|r| (r.case_name().to_string(), r.status_key(), r.get_duration()))
+        .collect()
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_combines_shards_sorted_by_case() {
+        let merged = merge_reports(vec![
+            report(vec![passed("b", 1), TestResult::skipped()], &[]),
+            report(vec![passed("a", 2)], &[]),
+        ]);
+        assert_eq!(
+            summary(&merged),
+            vec![
+                ("a".to_string(), "passed", Some(Duration::from_secs(2))),
+                ("b".to_string(), "passed", Some(Duration::from_secs(1))),
+                ("Skipped".to_string(), "skipped", None),
+            ]
+        );
+        assert!(merged.metadata.notes.is_empty());
+        assert_eq!(merged.metadata.axes.len(), 0);
+    }
+
+    #[test]
+    fn test_merge_reports_cases_left_out_only_if_no_report_ran_them() {
+        let skipped = |name| TestResult::skipped_case(case(name), "Requires network access");
+        let merged = merge_reports(vec![
+            report(vec![skipped("net"), skipped("ran-elsewhere")], &[]),
+            report(vec![skipped("net"), passed("ran-elsewhere", 1)], &[]),
+        ]);
+        assert_eq!(
+            summary(&merged),
+            vec![
+                (
+                    "ran-elsewhere".to_string(),
+                    "passed",
+                    Some(Duration::from_secs(1))
+                ),
+                ("net".to_string(), "skipped", None),
+            ]
+        );
+        assert_eq!(
+            merged.results[1].skip_reason(),
+            Some("Requires network access")
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_the_most_severe_result_in_any_order() {
+        let first = report(vec![passed("a", 5), passed("b", 1)], &[("a", "pass-sum")]);
+        let second = report(vec![failed("a", 1), passed("b", 3)], &[("a", "fail-sum")]);
+
+        let merged = merge_reports(vec![first.clone(), second.clone()]);
+        let reversed = merge_reports(vec![second, first]);
+        assert_eq!(summary(&merged), summary(&reversed));
+        assert_eq!(merged.metadata.notes, reversed.metadata.notes);
+
+        assert_eq!(merged.results[0].status_key(), "failed");
+        // Equally severe results keep the longest one.
+        assert_eq!(
+            merged.results[1].get_duration(),
+            Some(Duration::from_secs(3))
+        );
+        // Per-case metadata follows the kept result.
+        assert_eq!(merged.metadata.artifact_checksums["a"], "fail-sum");
+        assert_eq!(merged.metadata.notes.len(), 2);
+        assert!(
+            merged
+                .metadata
+                .notes
+                .iter()
+                .any(|note| note.contains("'a'"))
+        );
+    }
+
+    #[test]
+    fn test_merge_sums_retries_and_filters_owners() {
+        let mut first = report(vec![failed("net-a", 1)], &[]);
+        first.metadata.transient_build_retries = BTreeMap::from([("net-a".to_string(), 1)]);
+        first.metadata.failure_owners = vec![OwnerFailures {
+            owner: Some("@net".to_string()),
+            cases: vec!["net-a".to_string()],
+        }];
+        let mut second = report(vec![failed("net-b", 1), failed("other", 1)], &[]);
+        second.metadata.transient_build_retries = BTreeMap::from([("net-a".to_string(), 2)]);
+        second.metadata.failure_owners = vec![
+            OwnerFailures {
+                owner: None,
+                cases: vec!["other".to_string()],
+            },
+            OwnerFailures {
+                owner: Some("@net".to_string()),
+                cases: vec!["net-b".to_string(), "gone".to_string()],
+            },
+        ];
+
+        let merged = merge_reports(vec![second, first]);
+        assert_eq!(merged.metadata.transient_build_retries["net-a"], 3);
+        assert_eq!(
+            merged.metadata.failure_owners,
+            vec![
+                OwnerFailures {
+                    owner: Some("@net".to_string()),
+                    cases: vec!["net-a".to_string(), "net-b".to_string()],
+                },
+                OwnerFailures {
+                    owner: None,
+                    cases: vec!["other".to_string()],
+                },
+            ]
+        );
+        assert!(merge_reports(Vec::new()).results.is_empty());
+    }
+
+    #[test]
+    fn test_merge_keeps_the_config_only_when_the_reports_agree() {
+        let matrix = TestMatrix::from_cases([case("a"), case("b")]).unwrap();
+        let with_config = |results| {
+            let mut report = report(results, &[]);
+            report.metadata.config = Some(matrix.clone());
+            report
+        };
+
+        let merged = merge_reports(vec![
+            with_config(vec![passed("a", 1)]),
+            report(vec![passed("c", 1)], &[]),
+            with_config(vec![passed("b", 1)]),
+        ]);
+        let config = merged
+            .metadata
+            .config
+            .expect("the shards recorded the same matrix");
+        assert_eq!(config.cases.len(), 2);
+
+        let mut other = with_config(vec![passed("b", 1)]);
+        other.metadata.config.as_mut().unwrap().cases.pop();
+        let merged = merge_reports(vec![with_config(vec![passed("a", 1)]), other]);
+        assert!(merged.metadata.config.is_none());
+    }
+
+    #[test]
+    fn test_load_report_reads_the_results_json() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        let metadata = RunMetadata {
+            notes: vec!["note".to_string()],
+            ..Default::default()
+        };
+        generate_json_report(&[failed("a", 1), TestResult::skipped()], &metadata, &path).unwrap();
+
+        let loaded = load_report(&path).unwrap();
+        assert_eq!(loaded.metadata.notes, vec!["note"]);
+        assert_eq!(loaded.results.len(), 2);
+        assert!(load_report(&dir.path().join("missing.json")).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! # Resume Module Unit Tests / 恢复模块单元测试
+//!
+//! This module contains unit tests for the `resume.rs` module,
+//! testing that the journal round-trips and which results are kept when resuming.
+//!
+//! 此模块包含 `resume.rs` 模块的单元测试，
+//! 测试日志能够往返读写，以及恢复时保留哪些结果。
+
+mod common;
+use crate::common::{failed, passed};
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::models::TestResult;
+use matrix_runner::core::resume::{
+    RunJournal, clear_journal, journal_path, load_journal, resumable_results,
+};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod resume_tests {
+    use super::*;
+
+    fn case(name: &str) -> TestCase {
+        TestCase {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let dir = tempdir().unwrap();
+        assert!(load_journal(&journal_path(dir.path())).is_none());
+
+        let journal =
+            RunJournal::start(&journal_path(dir.path()), &[passed(case("kept"))]).unwrap();
+        journal.record(&failed(case("broken"))).unwrap();
+        journal.record(&TestResult::skipped()).unwrap();
+
+        let results = load_journal(&journal_path(dir.path())).unwrap();
+        let names: Vec<&str> = results.iter().map(TestResult::case_name).collect();
+        assert_eq!(names, vec!["kept", "broken"]);
+
+        clear_journal(&journal_path(dir.path()));
+        assert!(load_journal(&journal_path(dir.path())).is_none());
+    }
+
+    #[test]
+    fn test_truncated_line_is_ignored() {
+        let dir = tempdir().unwrap();
+        let journal = RunJournal::start(&journal_path(dir.path()), &[]).unwrap();
+        journal.record(&passed(case("complete"))).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal_path(dir.path()))
+            .unwrap();
+        file.write_all(br#"{"Passed":{"case""#).unwrap();
+
+        let results = load_journal(&journal_path(dir.path())).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].case_name(), "complete");
+    }
+
+    #[test]
+    fn test_start_replaces_previous_journal() {
+        let dir = tempdir().unwrap();
+        RunJournal::start(&journal_path(dir.path()), &[passed(case("old"))]).unwrap();
+        RunJournal::start(&journal_path(dir.path()), &[]).unwrap();
+        assert_eq!(fs::read_to_string(journal_path(dir.path())).unwrap(), "");
+    }
+
+    #[test]
+    fn test_journal_at_a_state_file_creates_its_directory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("ci-cache").join("matrix.jsonl");
+        let journal = RunJournal::start(&path, &[passed(case("kept"))]).unwrap();
+        journal.record(&passed(case("later"))).unwrap();
+
+        let results = load_journal(&path).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(load_journal(&journal_path(dir.path())).is_none());
+    }
+
+    #[test]
+    fn test_only_unchanged_passed_cases_are_kept() {
+        let mut changed = case("changed");
+        changed.features = "new".to_string();
+        let journal = vec![
+            passed(case("passed")),
+            failed(case("failed")),
+            passed(case("changed")),
+            passed(case("removed")),
+        ];
+        let planned = vec![case("passed"), case("failed"), changed, case("new")];
+
+        let kept = resumable_results(journal, &planned);
+        let names: Vec<&str> = kept.iter().map(TestResult::case_name).collect();
+        assert_eq!(names, vec!["passed"]);
+    }
+}
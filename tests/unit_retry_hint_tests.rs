@@ -0,0 +1,98 @@
+//! # Retry Hint Module Unit Tests / 重试提示模块单元测试
+//!
+//! This module contains unit tests for the `retry_hint.rs` module,
+//! testing which cases end up in the retry matrix and that the written file loads.
+//!
+//! 此模块包含 `retry_hint.rs` 模块的单元测试，
+//! 测试哪些用例会进入重试矩阵，以及写出的文件能否被加载。
+
+mod common;
+use crate::common::{failed, passed};
+use chrono::NaiveDate;
+use matrix_runner::core::config::{TestCase, TestMatrix, load_test_matrix};
+use matrix_runner::core::context::RunContext;
+use matrix_runner::core::models::TestResult;
+use matrix_runner::core::retry_hint::{RETRY_CONFIG_FILE, retry_matrix, write_retry_config};
+use tempfile::tempdir;
+
+fn case(name: &str, after: &[&str]) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        after: after.iter().map(|name| name.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod retry_hint_tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_matrix_keeps_unexpected_failures() {
+        let settings = TestMatrix {
+            language: "zh-CN".to_string(),
+            ..Default::default()
+        };
+        let allowed = TestCase {
+            allow_failure: vec![std::env::consts::OS.into()],
+            ..case("allowed", &[])
+        };
+        let results = vec![
+            failed(case("broken", &["warmup", "other-broken"])),
+            failed(case("other-broken", &[])),
+            passed(case("warmup", &[])),
+            failed(allowed),
+            TestResult::skipped(),
+        ];
+
+        let context = RunContext::new("en");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let matrix = retry_matrix(&settings, &results, &context, today).unwrap();
+        assert_eq!(matrix.language, "zh-CN");
+        let names: Vec<&str> = matrix.cases.iter().map(|case| case.name.as_str()).collect();
+        assert_eq!(names, vec!["broken", "other-broken"]);
+        // The passed warmup case is not part of the retry, so the hint naming it is dropped.
+        assert_eq!(matrix.cases[0].after, vec!["other-broken".to_string()]);
+
+        assert!(retry_matrix(&settings, &[passed(case("ok", &[]))], &context, today).is_none());
+    }
+
+    #[test]
+    fn test_write_retry_config() {
+        let dir = tempdir().unwrap();
+        let settings = TestMatrix::default();
+        let context = RunContext::new("en");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        let hint = write_retry_config(
+            &settings,
+            &[failed(case("broken", &[]))],
+            dir.path(),
+            1,
+            3,
+            &context,
+            today,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!((hint.runner_index, hint.total_runners), (1, 3));
+        assert_eq!(hint.cases, vec!["broken".to_string()]);
+        assert_eq!(hint.config, dir.path().join(RETRY_CONFIG_FILE));
+        let matrix = load_test_matrix(&hint.config).unwrap();
+        assert_eq!(matrix.cases.len(), 1);
+
+        // A later run without failures removes the stale file.
+        let hint = write_retry_config(
+            &settings,
+            &[passed(case("ok", &[]))],
+            dir.path(),
+            1,
+            3,
+            &context,
+            today,
+        )
+        .unwrap();
+        assert!(hint.is_none());
+        assert!(!dir.path().join(RETRY_CONFIG_FILE).exists());
+    }
+}
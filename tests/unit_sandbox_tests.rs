@@ -0,0 +1,172 @@
+//! # Sandbox Module Unit Tests / 沙箱模块单元测试
+//!
+//! This module contains unit tests for the `sandbox.rs` module and the `sandbox` case option,
+//! testing how it is parsed and validated, the bubblewrap arguments, and a sandboxed process.
+//!
+//! 此模块包含 `sandbox.rs` 模块和 `sandbox` 用例选项的单元测试，
+//! 测试其解析和校验方式、bubblewrap 参数以及沙箱中的进程。
+
+use matrix_runner::core::config::{CaseKind, Sandbox, TestCase, TestMatrix};
+use matrix_runner::infra::sandbox::{bwrap_args, writable_paths};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn args(sandbox: Sandbox, root: &str, writable: &[&str]) -> Vec<String> {
+    let writable: Vec<PathBuf> = writable.iter().map(PathBuf::from).collect();
+    bwrap_args(&sandbox, Path::new(root), &writable)
+        .into_iter()
+        .map(|arg: OsString| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod sandbox_config_tests {
+    use super::*;
+
+    #[test]
+    fn test_network_defaults_to_allowed() {
+        let matrix: TestMatrix = toml::from_str(
+            r#"
+[[cases]]
+name = "offline"
+features = ""
+no_default_features = false
+sandbox = { readonly_project = true }
+"#,
+        )
+        .unwrap();
+        let sandbox = matrix.cases[0].sandbox.unwrap();
+        assert!(sandbox.network);
+        assert!(sandbox.readonly_project);
+        assert!(!sandbox.is_open());
+        assert!(Sandbox::default().is_open());
+    }
+
+    #[test]
+    fn test_sandbox_is_only_for_test_cases() {
+        let result = TestCase::builder()
+            .name("coverage")
+            .kind(CaseKind::Tarpaulin)
+            .sandbox(Sandbox::default())
+            .build();
+        assert!(result.unwrap_err().to_string().contains("sandbox"));
+    }
+
+    #[test]
+    fn test_sandbox_without_network_conflicts_with_requires_network() {
+        let sandbox = Sandbox {
+            network: false,
+            ..Default::default()
+        };
+        let result = TestCase::builder()
+            .name("online")
+            .requires_network(true)
+            .sandbox(sandbox)
+            .build();
+        assert!(result.unwrap_err().to_string().contains("requires_network"));
+    }
+}
+
+#[cfg(test)]
+mod bwrap_tests {
+    use super::*;
+
+    #[test]
+    fn test_no_network_unshares_the_network() {
+        let sandbox = Sandbox {
+            network: false,
+            readonly_project: false,
+        };
+        assert_eq!(
+            args(sandbox, "/work", &["/work/target"]),
+            [
+                "--dev-bind",
+                "/",
+                "/",
+                "--die-with-parent",
+                "--unshare-net",
+                "--"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_readonly_project_binds_the_writable_paths_back() {
+        let sandbox = Sandbox {
+            network: true,
+            readonly_project: true,
+        };
+        assert_eq!(
+            args(sandbox, "/work", &["/work/target"]),
+            [
+                "--dev-bind",
+                "/",
+                "/",
+                "--die-with-parent",
+                "--ro-bind",
+                "/work",
+                "/work",
+                "--bind",
+                "/work/target",
+                "/work/target",
+                "--",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_writable_paths_are_the_existing_target_and_state_directories() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        assert!(writable_paths(root, &root.join("target/matrix-runner")).is_empty());
+
+        fs::create_dir_all(root.join("target/matrix-runner")).unwrap();
+        fs::create_dir_all(root.join(".state")).unwrap();
+        assert_eq!(
+            writable_paths(root, &root.join("target/matrix-runner")),
+            [root.join("target")]
+        );
+        assert_eq!(
+            writable_paths(root, &root.join(".state")),
+            [root.join("target"), root.join(".state")]
+        );
+        assert_eq!(
+            writable_paths(root, Path::new("/elsewhere")),
+            [root.join("target")]
+        );
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod sandboxed_process_tests {
+    use super::*;
+    use matrix_runner::infra::sandbox;
+
+    #[tokio::test]
+    async fn test_readonly_project_rejects_writes_outside_target() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("target")).unwrap();
+        let sandbox_config = Sandbox {
+            network: false,
+            readonly_project: true,
+        };
+        let run = |script: &str| {
+            let mut cmd =
+                sandbox::command("sh", Some(&sandbox_config), root, &root.join("target")).unwrap();
+            cmd.arg("-c").arg(script).current_dir(root);
+            cmd.status()
+        };
+        // Hosts without unprivileged user namespaces (or bubblewrap) cannot sandbox at all.
+        if !run("true").await.is_ok_and(|status| status.success()) {
+            return;
+        }
+
+        assert!(run("touch target/built").await.unwrap().success());
+        assert!(!run("touch source").await.unwrap().success());
+        assert!(root.join("target/built").exists());
+        assert!(!root.join("source").exists());
+    }
+}
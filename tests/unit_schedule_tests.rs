@@ -0,0 +1,154 @@
+//! # Schedule Module Unit Tests / 调度模块单元测试
+//!
+//! This module contains unit tests for the `schedule.rs` module,
+//! testing cron parsing, the next run time, pass rates and drift detection.
+//!
+//! 此模块包含 `schedule.rs` 模块的单元测试，
+//! 测试 cron 解析、下一次运行时间、通过率以及漂移检测。
+
+mod common;
+use chrono::{NaiveDate, TimeZone, Utc};
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::models::TestResult;
+use matrix_runner::core::schedule::{CronSchedule, detect_drift, pass_rate};
+
+fn passed() -> TestResult {
+    common::passed(TestCase::default())
+}
+
+fn failed() -> TestResult {
+    common::failed(TestCase::default())
+}
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod cron_tests {
+    use super::*;
+
+    #[test]
+    fn test_next_after_a_daily_schedule() {
+        let schedule: CronSchedule = "0 3 * * *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 10, 2, 59)),
+            Some(at(2026, 1, 10, 3, 0))
+        );
+        // A run exactly at the scheduled minute is not scheduled again.
+        // 恰好在计划分钟的运行不会再次被调度。
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 10, 3, 0)),
+            Some(at(2026, 1, 11, 3, 0))
+        );
+        assert_eq!(
+            schedule.next_after(&at(2026, 12, 31, 23, 30)),
+            Some(at(2027, 1, 1, 3, 0))
+        );
+    }
+
+    #[test]
+    fn test_lists_ranges_and_steps() {
+        let schedule: CronSchedule = "*/15 9-17 * * 1-5".parse().unwrap();
+        // 2026-01-10 is a Saturday, so the next run is on Monday.
+        // 2026-01-10 是星期六，因此下一次运行在星期一。
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 10, 12, 0)),
+            Some(at(2026, 1, 12, 9, 0))
+        );
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 12, 9, 0)),
+            Some(at(2026, 1, 12, 9, 15))
+        );
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 12, 17, 45)),
+            Some(at(2026, 1, 13, 9, 0))
+        );
+
+        let schedule: CronSchedule = "5,35 0 1 */3 *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 1, 0, 5)),
+            Some(at(2026, 1, 1, 0, 35))
+        );
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 1, 0, 35)),
+            Some(at(2026, 4, 1, 0, 5))
+        );
+    }
+
+    #[test]
+    fn test_day_of_month_or_day_of_week() {
+        // Sunday is both 0 and 7; with both day fields restricted, either one matches.
+        // 星期日既是 0 也是 7；当两个日字段都受限时，任意一个匹配即可。
+        let sunday: CronSchedule = "0 0 * * 7".parse().unwrap();
+        assert_eq!(sunday, "0 0 * * 0".parse().unwrap());
+        let schedule: CronSchedule = "0 0 13 * 5".parse().unwrap();
+        let date = |day| {
+            NaiveDate::from_ymd_opt(2026, 2, day)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        };
+        assert!(schedule.matches(&date(13)));
+        assert!(schedule.matches(&date(6)));
+        assert!(!schedule.matches(&date(7)));
+    }
+
+    #[test]
+    fn test_schedules_that_never_or_rarely_match() {
+        let schedule: CronSchedule = "0 0 31 2 *".parse().unwrap();
+        assert_eq!(schedule.next_after(&at(2026, 1, 1, 0, 0)), None);
+        let schedule: CronSchedule = "0 0 29 2 *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(&at(2026, 1, 1, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions_are_rejected() {
+        for expression in [
+            "",
+            "0 3 * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_err(), "{expression}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod drift_tests {
+    use super::*;
+
+    #[test]
+    fn test_pass_rate_leaves_skipped_cases_out() {
+        assert_eq!(
+            pass_rate(&[passed(), passed(), failed(), TestResult::skipped()]),
+            Some(200.0 / 3.0)
+        );
+        assert_eq!(pass_rate(&[TestResult::skipped()]), None);
+        assert_eq!(pass_rate(&[]), None);
+    }
+
+    #[test]
+    fn test_drift_against_the_trailing_average() {
+        let drift = detect_drift(&[70.0, 90.0, 100.0, 95.0], 2, 10.0).unwrap();
+        assert_eq!(drift.pass_rate, 70.0);
+        assert_eq!(drift.trailing_average, 95.0);
+        assert_eq!(drift.runs, 2);
+
+        // A drop within the threshold, a rise, or no earlier run is not a drift.
+        // 在阈值之内的下降、上升或没有较早的运行都不算漂移。
+        assert_eq!(detect_drift(&[90.0, 95.0, 100.0], 5, 10.0), None);
+        assert_eq!(detect_drift(&[100.0, 50.0], 5, 10.0), None);
+        assert_eq!(detect_drift(&[0.0], 5, 10.0), None);
+        assert_eq!(detect_drift(&[], 5, 10.0), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! # Scheduler Module Unit Tests / Scheduler 模块单元测试
+//!
+//! This module contains unit tests for the `scheduler.rs` module,
+//! testing global job slots, concurrency group limits and the smoke phase.
+//!
+//! 此模块包含 `scheduler.rs` 模块的单元测试，
+//! 测试全局任务槽位、并发组限制和冒烟阶段。
+
+use matrix_runner::core::config::{TestCase, TestMatrix};
+use matrix_runner::core::scheduler::{ResourceCeiling, Scheduler, is_smoke_case, parse_ceiling};
+use matrix_runner::infra::system_load::SystemLoad;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// Helper function to create a test case in an optional group / 创建可选分组测试用例的辅助函数
+fn create_test_case(name: &str, group: Option<&str>) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        concurrency_group: group.map(String::from),
+        ..Default::default()
+    }
+}
+
+/// Helper function to parse a matrix from TOML / 从 TOML 解析矩阵的辅助函数
+fn matrix_from(toml_str: &str) -> TestMatrix {
+    toml::from_str(toml_str).unwrap()
+}
+
+#[cfg(test)]
+mod scheduler_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_group_limits_cases_in_same_group() {
+        let matrix = matrix_from(
+            r#"
+            [concurrency_groups.db]
+            max_concurrent = 1
+
+            [[cases]]
+            name = "db-a"
+            features = ""
+            no_default_features = false
+            concurrency_group = "db"
+            "#,
+        );
+        let scheduler = Scheduler::new(4, &matrix);
+        let db_a = create_test_case("db-a", Some("db"));
+        let db_b = create_test_case("db-b", Some("db"));
+        let free = create_test_case("free", None);
+
+        let held = scheduler.acquire(&db_a).await;
+
+        // Another case of the same group must wait, an ungrouped case must not.
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&db_b))
+                .await
+                .is_err()
+        );
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&free))
+                .await
+                .is_ok()
+        );
+
+        drop(held);
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&db_b))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_allows_configured_parallelism() {
+        let matrix = matrix_from(
+            r#"
+            [concurrency_groups.gpu]
+            max_concurrent = 2
+
+            [[cases]]
+            name = "gpu-a"
+            features = ""
+            no_default_features = false
+            concurrency_group = "gpu"
+            "#,
+        );
+        let scheduler = Scheduler::new(4, &matrix);
+        let case = create_test_case("gpu", Some("gpu"));
+
+        let _first = scheduler.acquire(&case).await;
+        let _second = scheduler.acquire(&case).await;
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&case))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_job_slots() {
+        let scheduler = Scheduler::new(1, &TestMatrix::default());
+        let case = create_test_case("any", None);
+
+        let _held = scheduler.acquire(&case).await;
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&case))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_after_waits_for_named_cases() {
+        let warmup = create_test_case("warmup", None);
+        let after = TestCase {
+            after: vec!["warmup".to_string(), "not-in-run".to_string()],
+            ..create_test_case("after", None)
+        };
+        let scheduler =
+            Scheduler::new(4, &TestMatrix::default()).with_order(&[warmup.clone(), after.clone()]);
+
+        let running = scheduler.completion_guard("warmup");
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&after))
+                .await
+                .is_err()
+        );
+
+        // Finishing the warmup case releases it; the case outside the run is ignored.
+        drop(running);
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&after))
+                .await
+                .is_ok()
+        );
+    }
+
+    fn smoke_case(name: &str) -> TestCase {
+        TestCase {
+            tags: vec!["smoke".to_string()],
+            ..create_test_case(name, None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smoke_first_holds_other_cases_back() {
+        let smoke_a = smoke_case("smoke-a");
+        let smoke_b = smoke_case("smoke-b");
+        let slow = create_test_case("slow", None);
+        assert!(is_smoke_case(&smoke_a) && !is_smoke_case(&slow));
+        let scheduler = Scheduler::new(4, &TestMatrix::default())
+            .with_smoke_first(&[smoke_a.clone(), smoke_b.clone(), slow.clone()], true);
+
+        let guard_a = scheduler.completion_guard("smoke-a");
+        let guard_b = scheduler.completion_guard("smoke-b");
+        let _a = scheduler.acquire(&smoke_a).await;
+        let _b = scheduler.acquire(&smoke_b).await;
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&slow))
+                .await
+                .is_err()
+        );
+
+        drop(guard_a);
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&slow))
+                .await
+                .is_err()
+        );
+        drop(guard_b);
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&slow))
+                .await
+                .is_ok()
+        );
+        assert!(!scheduler.smoke_failed());
+        assert!(!scheduler.skips_after_smoke(&slow));
+    }
+
+    #[tokio::test]
+    async fn test_failed_smoke_case_skips_the_rest() {
+        let smoke = smoke_case("smoke");
+        let slow = create_test_case("slow", None);
+        let cases = [smoke.clone(), slow.clone()];
+
+        let stopping = Scheduler::new(4, &TestMatrix::default()).with_smoke_first(&cases, true);
+        stopping.record_failure("slow");
+        assert!(!stopping.smoke_failed());
+        stopping.record_failure("smoke");
+        assert!(stopping.smoke_failed());
+        assert!(stopping.skips_after_smoke(&slow));
+        assert!(!stopping.skips_after_smoke(&smoke));
+
+        let continuing = Scheduler::new(4, &TestMatrix::default()).with_smoke_first(&cases, false);
+        continuing.record_failure("smoke");
+        assert!(continuing.smoke_failed());
+        assert!(!continuing.skips_after_smoke(&slow));
+    }
+
+    #[tokio::test]
+    async fn test_smoke_case_ignores_after_hints_on_the_long_tail() {
+        let slow = create_test_case("slow", None);
+        let smoke = TestCase {
+            after: vec!["slow".to_string()],
+            ..smoke_case("smoke")
+        };
+        let cases = [smoke.clone(), slow.clone()];
+        let scheduler = Scheduler::new(4, &TestMatrix::default())
+            .with_order(&cases)
+            .with_smoke_first(&cases, true);
+
+        let _running = scheduler.completion_guard("slow");
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&smoke))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cases_wait_while_the_load_is_above_the_ceiling() {
+        // The load average, in hundredths, as the sampler reports it.
+        let load = Arc::new(AtomicU64::new(1200));
+        let sampled = load.clone();
+        let ceiling = ResourceCeiling {
+            max_load: Some(8.0),
+            max_total_memory_gb: Some(64.0),
+        };
+        let scheduler = Scheduler::new(4, &TestMatrix::default()).with_resource_sampler(
+            ceiling,
+            Duration::from_millis(10),
+            move || SystemLoad {
+                load: Some(sampled.load(Ordering::SeqCst) as f64 / 100.0),
+                used_memory_gb: None,
+            },
+            "en",
+        );
+        let case = create_test_case("heavy", None);
+
+        assert!(
+            timeout(Duration::from_millis(50), scheduler.acquire(&case))
+                .await
+                .is_err()
+        );
+        load.store(350, Ordering::SeqCst);
+        assert!(
+            timeout(Duration::from_millis(100), scheduler.acquire(&case))
+                .await
+                .is_ok()
+        );
+        assert!(
+            timeout(Duration::from_millis(100), scheduler.acquire(&case))
+                .await
+                .is_ok()
+        );
+        assert_eq!(scheduler.resource_waits(), 1);
+    }
+
+    #[test]
+    fn test_resource_ceiling_checks_each_measured_value() {
+        let ceiling = ResourceCeiling {
+            max_load: Some(8.0),
+            max_total_memory_gb: Some(16.0),
+        };
+        let busy = SystemLoad {
+            load: Some(2.0),
+            used_memory_gb: Some(20.0),
+        };
+        let exceeded = ceiling.exceeded_by(&busy, "en").unwrap();
+        assert!(exceeded.contains("20.0"));
+        assert_ne!(ceiling.exceeded_by(&busy, "zh-CN"), Some(exceeded));
+        assert!(ceiling.exceeded_by(&SystemLoad::default(), "en").is_none());
+        assert!(ResourceCeiling::default().is_empty());
+
+        assert_eq!(parse_ceiling("2.5"), Ok(2.5));
+        assert!(parse_ceiling("0").is_err());
+        assert!(parse_ceiling("-1").is_err());
+        assert!(parse_ceiling("lots").is_err());
+    }
+}
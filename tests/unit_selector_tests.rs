@@ -0,0 +1,179 @@
+//! # Selector Module Unit Tests / Selector 模块单元测试
+//!
+//! This module contains unit tests for the `selector.rs` module,
+//! testing parsing and evaluation of `--select` expressions.
+//!
+//! 此模块包含 `selector.rs` 模块的单元测试，
+//! 测试 `--select` 表达式的解析和求值。
+
+use matrix_runner::core::config::{CaseKind, TestCase};
+use matrix_runner::core::history::RunHistory;
+use matrix_runner::core::selector::Selector;
+
+/// Helper function to create a tagged test case / 创建带标签测试用例的辅助函数
+fn create_test_case(name: &str, features: &str, tags: &[&str]) -> TestCase {
+    TestCase {
+        name: name.to_string(),
+        features: features.to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// Helper function to create a history with one entry / 创建包含单个条目的历史记录的辅助函数
+fn history_with(name: &str, status: &str) -> RunHistory {
+    let mut history = RunHistory::default();
+    history.cases.insert(name.to_string(), status.to_string());
+    history
+}
+
+#[cfg(test)]
+mod evaluation_tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_and_last_run_status() {
+        let selector = Selector::parse(r#"status(last_run) == failed || tag == "fast""#).unwrap();
+        let history = history_with("was-failing", "failed");
+
+        assert!(selector.matches(&create_test_case("was-failing", "", &[]), &history));
+        assert!(selector.matches(&create_test_case("quick", "", &["fast"]), &history));
+        assert!(!selector.matches(&create_test_case("slow", "", &["slow"]), &history));
+    }
+
+    #[test]
+    fn test_unknown_status_without_history() {
+        let selector = Selector::parse("status(last_run) == unknown").unwrap();
+
+        assert!(selector.matches(&create_test_case("new", "", &[]), &RunHistory::default()));
+    }
+
+    #[test]
+    fn test_features_negation_and_grouping() {
+        let selector = Selector::parse("!(feature == std) && name != 'excluded'").unwrap();
+        let history = RunHistory::default();
+
+        assert!(selector.matches(&create_test_case("no-std", "core, alloc", &[]), &history));
+        assert!(!selector.matches(&create_test_case("std", "std,alloc", &[]), &history));
+        assert!(!selector.matches(&create_test_case("excluded", "core", &[]), &history));
+    }
+
+    #[test]
+    fn test_kind_field() {
+        let selector = Selector::parse("kind == command").unwrap();
+        let mut case = create_test_case("custom", "", &[]);
+
+        assert!(!selector.matches(&case, &RunHistory::default()));
+        case.command = Some("echo hi".to_string());
+        assert!(selector.matches(&case, &RunHistory::default()));
+    }
+
+    #[test]
+    fn test_kind_field_matches_case_kinds() {
+        let history = RunHistory::default();
+        let mut case = create_test_case("coverage", "", &[]);
+
+        assert!(
+            Selector::parse("kind == test")
+                .unwrap()
+                .matches(&case, &history)
+        );
+        for (kind, name) in [
+            (CaseKind::Tarpaulin, "tarpaulin"),
+            (CaseKind::Doc, "doc"),
+            (CaseKind::FuzzSmoke, "fuzz-smoke"),
+        ] {
+            case.kind = kind;
+            let selector = Selector::parse(&format!("kind == {name}")).unwrap();
+            assert!(selector.matches(&case, &history), "{name}");
+            assert!(
+                Selector::parse("kind == cargo")
+                    .unwrap()
+                    .matches(&case, &history)
+            );
+            assert!(
+                !Selector::parse("kind == test")
+                    .unwrap()
+                    .matches(&case, &history)
+            );
+        }
+
+        // A custom command takes precedence over the kind.
+        case.command = Some("echo hi".to_string());
+        assert!(
+            Selector::parse("kind == command")
+                .unwrap()
+                .matches(&case, &history)
+        );
+        assert!(
+            !Selector::parse("kind == fuzz-smoke")
+                .unwrap()
+                .matches(&case, &history)
+        );
+    }
+
+    #[test]
+    fn test_package_case_answers_to_both_names() {
+        let case = create_test_case("core::unit", "", &[]);
+        let history = RunHistory::default();
+
+        assert!(
+            Selector::parse("name == 'core::unit'")
+                .unwrap()
+                .matches(&case, &history)
+        );
+        assert!(
+            Selector::parse("name == unit")
+                .unwrap()
+                .matches(&case, &history)
+        );
+        assert!(
+            !Selector::parse("name != unit")
+                .unwrap()
+                .matches(&case, &history)
+        );
+    }
+}
+
+#[cfg(test)]
+mod parse_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_field_points_at_field() {
+        let err = Selector::parse("tag == fast || colour == red").unwrap_err();
+
+        assert_eq!(err.position, 15);
+        assert!(err.message.contains("unknown field `colour`"));
+        assert!(err.to_string().contains("               ^"));
+    }
+
+    #[test]
+    fn test_single_equals_is_rejected() {
+        let err = Selector::parse("tag = fast").unwrap_err();
+
+        assert_eq!(err.position, 4);
+        assert!(err.message.contains("expected `==`"));
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let err = Selector::parse(r#"name == "open"#).unwrap_err();
+
+        assert!(err.message.contains("unterminated string"));
+    }
+
+    #[test]
+    fn test_missing_closing_paren() {
+        let err = Selector::parse("(tag == fast").unwrap_err();
+
+        assert!(err.message.contains("expected `)`"));
+    }
+
+    #[test]
+    fn test_unsupported_status_argument() {
+        let err = Selector::parse("status(yesterday) == failed").unwrap_err();
+
+        assert!(err.message.contains("expected `last_run`"));
+    }
+}
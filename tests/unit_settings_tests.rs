@@ -0,0 +1,158 @@
+//! # Settings Module Unit Tests / Settings 模块单元测试
+//!
+//! This module contains unit tests for the `settings.rs` module,
+//! testing `.matrixrunner.toml` parsing and value precedence.
+//!
+//! 此模块包含 `settings.rs` 模块的单元测试，
+//! 测试 `.matrixrunner.toml` 的解析和取值优先级。
+
+use matrix_runner::core::history::Retention;
+use matrix_runner::core::settings::{ColorMode, SETTINGS_FILE, Settings, load_settings};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_settings_file_yields_defaults() {
+        let dir = tempdir().unwrap();
+        let settings = load_settings(dir.path()).unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_load_settings_file() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(SETTINGS_FILE),
+            r#"
+            jobs = 3
+            html = "reports/matrix.html"
+            color = "never"
+            language = "zh-CN"
+            cache_dir = ".cache/matrix"
+            "#,
+        )
+        .unwrap();
+
+        let settings = load_settings(dir.path()).unwrap();
+        assert_eq!(settings.jobs, Some(3));
+        assert_eq!(settings.html, Some(PathBuf::from("reports/matrix.html")));
+        assert_eq!(settings.color, Some(ColorMode::Never));
+        assert_eq!(settings.language.as_deref(), Some("zh-CN"));
+        assert_eq!(settings.cache_dir, Some(PathBuf::from(".cache/matrix")));
+    }
+
+    #[test]
+    fn test_history_retention() {
+        assert_eq!(
+            Settings::default().history_retention(),
+            Retention {
+                keep_runs: 100,
+                keep_days: 90
+            }
+        );
+
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(SETTINGS_FILE),
+            "history_keep_runs = 20\nhistory_keep_days = 0\n",
+        )
+        .unwrap();
+        let settings = load_settings(dir.path()).unwrap();
+        assert_eq!(
+            settings.history_retention(),
+            Retention {
+                keep_runs: 20,
+                keep_days: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_settings_file_is_an_error() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(SETTINGS_FILE), "color = \"sometimes\"").unwrap();
+        assert!(load_settings(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_cli_takes_precedence_over_settings() {
+        let settings = Settings {
+            jobs: Some(3),
+            html: Some(PathBuf::from("settings.html")),
+            json: Some(PathBuf::from("settings.json")),
+            junit: Some(PathBuf::from("settings.xml")),
+            language: Some("zh-CN".to_string()),
+            ..Default::default()
+        };
+        let root = Path::new("/project");
+
+        assert_eq!(settings.jobs(Some(8)), 8);
+        assert_eq!(
+            settings.html_report(Some(PathBuf::from("cli.html")), root),
+            Some(PathBuf::from("cli.html"))
+        );
+        assert_eq!(
+            settings.json_report(Some(PathBuf::from("cli.json")), root),
+            Some(PathBuf::from("cli.json"))
+        );
+        assert_eq!(
+            settings.junit_report(Some(PathBuf::from("cli.xml")), root),
+            Some(PathBuf::from("cli.xml"))
+        );
+        assert_eq!(
+            settings.language(Some("en".to_string())).as_deref(),
+            Some("en")
+        );
+    }
+
+    #[test]
+    fn test_settings_take_precedence_over_defaults() {
+        let settings = Settings {
+            jobs: Some(3),
+            html: Some(PathBuf::from("settings.html")),
+            json: Some(PathBuf::from("settings.json")),
+            junit: Some(PathBuf::from("settings.xml")),
+            language: Some("zh-CN".to_string()),
+            cache_dir: Some(PathBuf::from("cache")),
+            ..Default::default()
+        };
+        let root = Path::new("/project");
+
+        assert_eq!(settings.jobs(None), 3);
+        assert_eq!(
+            settings.html_report(None, root),
+            Some(root.join("settings.html"))
+        );
+        assert_eq!(
+            settings.json_report(None, root),
+            Some(root.join("settings.json"))
+        );
+        assert_eq!(
+            settings.junit_report(None, root),
+            Some(root.join("settings.xml"))
+        );
+        assert_eq!(settings.language(None).as_deref(), Some("zh-CN"));
+        assert_eq!(settings.state_dir(root), root.join("cache"));
+    }
+
+    #[test]
+    fn test_defaults_when_nothing_is_set() {
+        let settings = Settings::default();
+        let root = Path::new("/project");
+
+        assert!(settings.jobs(None) >= 1);
+        assert_eq!(settings.html_report(None, root), None);
+        assert_eq!(settings.json_report(None, root), None);
+        assert_eq!(settings.junit_report(None, root), None);
+        assert_eq!(settings.language(None), None);
+        assert_eq!(
+            settings.state_dir(root),
+            matrix_runner::infra::fs::state_dir(root)
+        );
+    }
+}
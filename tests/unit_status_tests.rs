@@ -0,0 +1,93 @@
+//! # Status Module Unit Tests / 状态模块单元测试
+//!
+//! This module contains unit tests for the `status.rs` module,
+//! testing the progress counts, the remaining time estimate and the status file.
+//!
+//! 此模块包含 `status.rs` 模块的单元测试，
+//! 测试进度计数、剩余时间估计以及状态文件。
+
+mod common;
+use chrono::{Duration as ChronoDuration, Utc};
+use matrix_runner::core::config::TestCase;
+use matrix_runner::core::models::TestResult;
+use matrix_runner::core::status::{RunStatus, StatusFile, load_status, process_alive, status_path};
+use tempfile::tempdir;
+
+fn failed(name: &str) -> TestResult {
+    common::failed(TestCase {
+        name: name.to_string(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod run_status_tests {
+    use super::*;
+
+    #[test]
+    fn test_eta_unknown_until_a_case_finishes() {
+        let now = Utc::now();
+        let status = RunStatus::new(10, 4, now);
+        assert_eq!(status.completed(), 4);
+        assert!(status.eta(now + ChronoDuration::seconds(30)).is_none());
+    }
+
+    #[test]
+    fn test_eta_from_finish_rate() {
+        let start = Utc::now();
+        let mut status = RunStatus::new(10, 0, start);
+        status.passed = 3;
+        status.failed = 1;
+        status.skipped = 1;
+        // Five cases in 100 seconds, five remaining.
+        let eta = status.eta(start + ChronoDuration::seconds(100)).unwrap();
+        assert_eq!(eta.as_secs(), 100);
+    }
+
+    #[test]
+    fn test_eta_ignores_resumed_cases() {
+        let start = Utc::now();
+        let mut status = RunStatus::new(10, 6, start);
+        status.passed += 2;
+        // Two cases of this run in 60 seconds, two remaining.
+        let eta = status.eta(start + ChronoDuration::seconds(60)).unwrap();
+        assert_eq!(eta.as_secs(), 60);
+    }
+}
+
+#[cfg(test)]
+mod status_file_tests {
+    use super::*;
+
+    #[test]
+    fn test_status_file_tracks_cases() {
+        let dir = tempdir().unwrap();
+        let file = StatusFile::start(dir.path(), 3, 0).unwrap();
+        let status = load_status(dir.path()).unwrap();
+        assert_eq!(status.pid, std::process::id());
+        assert_eq!(status.total, 3);
+        assert_eq!(status.completed(), 0);
+
+        file.case_started("a");
+        file.case_started("b");
+        let status = load_status(dir.path()).unwrap();
+        assert_eq!(status.running.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+
+        file.case_finished("a", &failed("a"));
+        file.case_finished("c", &TestResult::skipped());
+        let status = load_status(dir.path()).unwrap();
+        assert_eq!(status.running.keys().collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(status.failed, 1);
+        assert_eq!(status.skipped, 1);
+        assert_eq!(status.completed(), 2);
+
+        file.finish();
+        assert!(!status_path(dir.path()).exists());
+        assert!(load_status(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_process_alive() {
+        assert!(process_alive(std::process::id()));
+    }
+}
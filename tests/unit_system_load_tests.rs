@@ -0,0 +1,39 @@
+//! # System Load Module Unit Tests / System Load 模块单元测试
+//!
+//! This module contains unit tests for the `system_load.rs` module,
+//! testing how the memory in use is read from `/proc/meminfo`.
+//!
+//! 此模块包含 `system_load.rs` 模块的单元测试，
+//! 测试如何从 `/proc/meminfo` 读取正在使用的内存。
+
+use matrix_runner::infra::system_load::{parse_meminfo, sample};
+
+#[cfg(test)]
+mod system_load_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_subtracts_the_available_memory() {
+        let meminfo = "\
+MemTotal:       16318480 kB
+MemFree:         1012340 kB
+MemAvailable:    8159240 kB
+Buffers:          402120 kB
+";
+        assert_eq!(parse_meminfo(meminfo), Some((16318480 - 8159240) * 1024));
+    }
+
+    #[test]
+    fn test_parse_meminfo_needs_both_fields() {
+        assert_eq!(parse_meminfo("MemTotal:       16318480 kB\n"), None);
+        assert_eq!(parse_meminfo(""), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_measures_the_host() {
+        let load = sample();
+        assert!(load.load.is_some_and(|load| load >= 0.0));
+        assert!(load.used_memory_gb.is_some_and(|used| used > 0.0));
+    }
+}
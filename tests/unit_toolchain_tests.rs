@@ -0,0 +1,197 @@
+//! # Toolchain Module Unit Tests / Toolchain 模块单元测试
+//!
+//! This module contains unit tests for the `toolchain.rs` module,
+//! testing how the toolchains and targets used by cases are collected and matched
+//! against rustup's listings.
+//!
+//! 此模块包含 `toolchain.rs` 模块的单元测试，
+//! 测试如何收集用例使用的工具链和目标，以及如何将其与 rustup 的列表进行匹配。
+
+use matrix_runner::core::config::TestCase;
+use matrix_runner::infra::toolchain::{
+    MissingComponents, ToolchainRequirement, cases_missing_components, cases_needing_nightly,
+    describe_missing_toolchains, is_component_installed, is_toolchain_installed, parse_rustup_list,
+    toolchain_requirements,
+};
+
+#[cfg(test)]
+mod toolchain_tests {
+    use super::*;
+
+    fn case(toolchain: Option<&str>, target: Option<&str>) -> TestCase {
+        TestCase {
+            toolchain: toolchain.map(str::to_string),
+            target: target.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unstable_flags_without_toolchain_need_a_nightly_active_toolchain() {
+        let mut unstable = case(None, None);
+        unstable.name = "build-std".to_string();
+        unstable.cargo_unstable_flags = vec!["-Zbuild-std=core".to_string()];
+        let mut pinned = unstable.clone();
+        pinned.name = "pinned".to_string();
+        pinned.toolchain = Some("nightly".to_string());
+        let cases = vec![unstable, pinned, case(None, None)];
+
+        assert_eq!(
+            cases_needing_nightly(&cases, "stable-x86_64-unknown-linux-gnu"),
+            vec!["build-std"]
+        );
+        assert!(cases_needing_nightly(&cases, "nightly-x86_64-unknown-linux-gnu").is_empty());
+    }
+
+    #[test]
+    fn test_requirements_grouped_by_toolchain() {
+        let cases = vec![
+            case(None, None),
+            case(Some("nightly"), Some("wasm32-unknown-unknown")),
+            case(Some("nightly"), None),
+            case(None, Some("aarch64-unknown-linux-gnu")),
+            case(Some("1.75.0"), None),
+        ];
+
+        assert_eq!(
+            toolchain_requirements(&cases),
+            vec![
+                ToolchainRequirement {
+                    toolchain: None,
+                    targets: vec!["aarch64-unknown-linux-gnu".to_string()],
+                    components: vec![],
+                },
+                ToolchainRequirement {
+                    toolchain: Some("1.75.0".to_string()),
+                    targets: vec![],
+                    components: vec![],
+                },
+                ToolchainRequirement {
+                    toolchain: Some("nightly".to_string()),
+                    targets: vec!["wasm32-unknown-unknown".to_string()],
+                    components: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_requirements_ignore_custom_commands_and_plain_cases() {
+        let custom = TestCase {
+            command: Some("make test".to_string()),
+            ..case(Some("nightly"), None)
+        };
+        assert!(toolchain_requirements(&[custom, case(None, None)]).is_empty());
+    }
+
+    #[test]
+    fn test_requirements_collect_components() {
+        let miri = TestCase {
+            command: Some("cargo +nightly miri test".to_string()),
+            components: vec!["miri".to_string(), "rust-src".to_string()],
+            ..case(Some("nightly"), None)
+        };
+        let coverage = TestCase {
+            components: vec!["llvm-tools-preview".to_string()],
+            ..case(None, None)
+        };
+
+        assert_eq!(
+            toolchain_requirements(&[miri, coverage]),
+            vec![
+                ToolchainRequirement {
+                    toolchain: None,
+                    targets: vec![],
+                    components: vec!["llvm-tools-preview".to_string()],
+                },
+                ToolchainRequirement {
+                    toolchain: Some("nightly".to_string()),
+                    targets: vec![],
+                    components: vec!["miri".to_string(), "rust-src".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_component_matching() {
+        let installed = parse_rustup_list(
+            "cargo-x86_64-unknown-linux-gnu\nllvm-tools-x86_64-unknown-linux-gnu\nrust-src\n",
+        );
+        assert!(is_component_installed(&installed, "rust-src"));
+        assert!(is_component_installed(&installed, "cargo"));
+        assert!(is_component_installed(&installed, "llvm-tools"));
+        assert!(is_component_installed(&installed, "llvm-tools-preview"));
+        assert!(!is_component_installed(&installed, "miri"));
+    }
+
+    #[test]
+    fn test_cases_missing_components() {
+        let named = |name: &str, toolchain: Option<&str>, components: &[&str]| TestCase {
+            name: name.to_string(),
+            components: components.iter().map(|c| c.to_string()).collect(),
+            ..case(toolchain, None)
+        };
+        let cases = vec![
+            named("miri", Some("nightly"), &["miri", "rust-src"]),
+            named("src-only", Some("nightly"), &["rust-src"]),
+            named("stable-miri", None, &["miri"]),
+            named("plain", Some("nightly"), &[]),
+        ];
+        let missing = vec![MissingComponents {
+            toolchain: Some("nightly".to_string()),
+            components: vec!["miri".to_string()],
+        }];
+
+        let affected = cases_missing_components(&cases, &missing);
+        assert_eq!(affected.len(), 1);
+        assert_eq!(affected["miri"], vec!["miri".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_rustup_list() {
+        let output = "stable-x86_64-unknown-linux-gnu (active, default)\nnightly-x86_64-unknown-linux-gnu\n\n";
+        assert_eq!(
+            parse_rustup_list(output),
+            vec![
+                "stable-x86_64-unknown-linux-gnu".to_string(),
+                "nightly-x86_64-unknown-linux-gnu".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_toolchains_listed_with_their_cases() {
+        let named = |name: &str, toolchain: Option<&str>| TestCase {
+            name: name.to_string(),
+            ..case(toolchain, None)
+        };
+        let cases = vec![
+            named("miri", Some("nightly")),
+            named("plain", None),
+            named("msrv", Some("1.70")),
+            named("simd", Some("nightly")),
+        ];
+        let missing = vec!["nightly".to_string(), "1.70".to_string()];
+        assert_eq!(
+            describe_missing_toolchains(&cases, &missing),
+            "nightly (miri, simd); 1.70 (msrv)"
+        );
+    }
+
+    #[test]
+    fn test_toolchain_matching() {
+        let installed = vec![
+            "stable-x86_64-unknown-linux-gnu".to_string(),
+            "1.75.0-x86_64-unknown-linux-gnu".to_string(),
+        ];
+        assert!(is_toolchain_installed(&installed, "stable"));
+        assert!(is_toolchain_installed(&installed, "1.75.0"));
+        assert!(is_toolchain_installed(
+            &installed,
+            "stable-x86_64-unknown-linux-gnu"
+        ));
+        assert!(!is_toolchain_installed(&installed, "nightly"));
+        assert!(!is_toolchain_installed(&installed, "1.75"));
+    }
+}
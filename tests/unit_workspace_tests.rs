@@ -0,0 +1,102 @@
+//! # Workspace Module Unit Tests / Workspace 模块单元测试
+//!
+//! This module contains unit tests for the `workspace.rs` module,
+//! testing how `cargo metadata` output is turned into workspace members, the
+//! package of a manifest and the features and metadata of the packages.
+//!
+//! 此模块包含 `workspace.rs` 模块的单元测试，
+//! 测试如何将 `cargo metadata` 的输出转换为工作区成员、清单对应的包以及各个包的 features 和元数据。
+
+use matrix_runner::infra::workspace::{
+    parse_package_manifests, parse_package_name, parse_workspace_members,
+};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod parse_workspace_members_tests {
+    use super::*;
+
+    #[test]
+    fn test_members_sorted_with_their_directories() {
+        let json = r#"{
+            "packages": [
+                { "name": "server", "version": "0.1.0", "manifest_path": "/ws/crates/server/Cargo.toml" },
+                { "name": "core", "version": "0.1.0", "manifest_path": "/ws/crates/core/Cargo.toml" }
+            ],
+            "workspace_members": [],
+            "workspace_root": "/ws"
+        }"#;
+
+        let members = parse_workspace_members(json).unwrap();
+        let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["core", "server"]);
+        assert_eq!(members[0].dir, PathBuf::from("/ws/crates/core"));
+    }
+
+    #[test]
+    fn test_invalid_json_yields_none() {
+        assert!(parse_workspace_members("not json").is_none());
+    }
+}
+
+#[cfg(test)]
+mod parse_package_name_tests {
+    use super::*;
+
+    const METADATA: &str = r#"{
+        "packages": [
+            { "name": "server", "version": "0.1.0", "manifest_path": "/ws/crates/server/Cargo.toml" },
+            { "name": "core", "version": "0.1.0", "manifest_path": "/ws/crates/core/Cargo.toml" }
+        ],
+        "workspace_members": [],
+        "workspace_root": "/ws"
+    }"#;
+
+    #[test]
+    fn test_member_manifest_yields_its_package() {
+        let name = parse_package_name(METADATA, Path::new("/ws/crates/core/Cargo.toml"));
+        assert_eq!(name.as_deref(), Some("core"));
+    }
+
+    #[test]
+    fn test_virtual_manifest_yields_empty_name() {
+        let name = parse_package_name(METADATA, Path::new("/ws/Cargo.toml"));
+        assert_eq!(name.as_deref(), Some(""));
+        assert!(parse_package_name("not json", Path::new("/ws/Cargo.toml")).is_none());
+    }
+}
+
+#[cfg(test)]
+mod parse_package_manifests_tests {
+    use super::*;
+
+    #[test]
+    fn test_features_rust_version_and_docs_rs_metadata_are_read() {
+        let json = r#"{
+            "packages": [
+                {
+                    "name": "core", "version": "0.1.0", "manifest_path": "/ws/core/Cargo.toml",
+                    "features": { "default": ["std"], "std": [], "serde": ["dep:serde"] },
+                    "rust_version": "1.70",
+                    "metadata": { "docs": { "rs": { "all-features": true, "targets": ["wasm32-unknown-unknown"], "rustdoc-args": ["--cfg", "docsrs"] } } }
+                },
+                {
+                    "name": "app", "version": "0.1.0", "manifest_path": "/ws/app/Cargo.toml",
+                    "features": {}, "rust_version": null, "metadata": { "docs": "not a table" }
+                }
+            ],
+            "workspace_members": [],
+            "workspace_root": "/ws"
+        }"#;
+
+        let packages = parse_package_manifests(json).unwrap();
+        assert_eq!(packages[0].name, "app");
+        assert_eq!(packages[0].docs_rs, None);
+        let core = &packages[1];
+        assert_eq!(core.features["default"], ["std"]);
+        assert_eq!(core.rust_version.as_deref(), Some("1.70"));
+        let docs_rs = core.docs_rs.as_ref().unwrap();
+        assert!(docs_rs.all_features);
+        assert_eq!(docs_rs.targets, ["wasm32-unknown-unknown"]);
+    }
+}